@@ -0,0 +1,286 @@
+/// Dart bindings for the PolliNet C API (`include/pollinet.h`).
+///
+/// A thin layer: every call maps 1:1 to a `pollinet_*` function, JSON results
+/// are decoded from the `FfiResult` envelope, and failures throw
+/// [PolliNetException] carrying the `ERR_*` code.
+library pollinet;
+
+import 'dart:convert';
+import 'dart:ffi';
+import 'dart:io';
+import 'dart:typed_data';
+
+import 'package:ffi/ffi.dart';
+
+/// Largest frame the engine produces (one BLE frame)
+const int maxFrameLength = 516;
+
+/// Failed PolliNet call; [code] is an `ERR_*` code (see `FfiErrorCode`)
+class PolliNetException implements Exception {
+  PolliNetException(this.code, this.message);
+
+  final String code;
+  final String message;
+
+  @override
+  String toString() => 'PolliNetException[$code]: $message';
+}
+
+typedef _EventCallbackNative = Void Function(Pointer<Utf8>, Pointer<Void>);
+
+DynamicLibrary _open() {
+  if (Platform.isIOS || Platform.isMacOS) return DynamicLibrary.process();
+  if (Platform.isWindows) return DynamicLibrary.open('pollinet.dll');
+  return DynamicLibrary.open('libpollinet.so');
+}
+
+final DynamicLibrary _lib = _open();
+
+final _version = _lib.lookupFunction<Pointer<Utf8> Function(),
+    Pointer<Utf8> Function()>('pollinet_version');
+final _init = _lib.lookupFunction<Int64 Function(Pointer<Utf8>),
+    int Function(Pointer<Utf8>)>('pollinet_init');
+final _shutdown = _lib.lookupFunction<Int32 Function(Int64),
+    int Function(int)>('pollinet_shutdown');
+final _pushInbound = _lib.lookupFunction<
+    Int32 Function(Int64, Pointer<Uint8>, UintPtr),
+    int Function(int, Pointer<Uint8>, int)>('pollinet_push_inbound');
+final _nextOutbound = _lib.lookupFunction<
+    IntPtr Function(Int64, Pointer<Uint8>, UintPtr),
+    int Function(int, Pointer<Uint8>, int)>('pollinet_next_outbound');
+final _tick = _lib.lookupFunction<Pointer<Utf8> Function(Int64, Uint64),
+    Pointer<Utf8> Function(int, int)>('pollinet_tick');
+final _queueTransaction = _lib.lookupFunction<
+    Int32 Function(Int64, Pointer<Uint8>, UintPtr),
+    int Function(int, Pointer<Uint8>, int)>('pollinet_queue_transaction');
+final _setEventCallback = _lib.lookupFunction<
+    Int32 Function(
+        Int64, Pointer<NativeFunction<_EventCallbackNative>>, Pointer<Void>),
+    int Function(int, Pointer<NativeFunction<_EventCallbackNative>>,
+        Pointer<Void>)>('pollinet_set_owned_event_callback');
+final _sendTextMessage = _lib.lookupFunction<
+    Pointer<Utf8> Function(Int64, Pointer<Utf8>, Pointer<Utf8>),
+    Pointer<Utf8> Function(
+        int, Pointer<Utf8>, Pointer<Utf8>)>('pollinet_send_text_message');
+final _ingestTextFrame = _lib.lookupFunction<
+    Int32 Function(Int64, Pointer<Utf8>, Pointer<Uint8>, UintPtr),
+    int Function(
+        int, Pointer<Utf8>, Pointer<Uint8>, int)>('pollinet_ingest_text_frame');
+final _nextTextFrame = _lib.lookupFunction<
+    IntPtr Function(Int64, Pointer<Uint8>, UintPtr),
+    int Function(int, Pointer<Uint8>, int)>('pollinet_next_text_frame');
+final _textMessageStatus = _lib.lookupFunction<
+    Pointer<Utf8> Function(Int64, Pointer<Utf8>),
+    Pointer<Utf8> Function(int, Pointer<Utf8>)>('pollinet_text_message_status');
+final _protocolInfo = _lib.lookupFunction<Pointer<Utf8> Function(),
+    Pointer<Utf8> Function()>('pollinet_protocol_info');
+final _stringFree = _lib.lookupFunction<Void Function(Pointer<Utf8>),
+    void Function(Pointer<Utf8>)>('pollinet_string_free');
+
+typedef _HandleJsonFn = Pointer<Utf8> Function(int);
+final Map<String, _HandleJsonFn> _handleJson = {
+  for (final name in [
+    'pollinet_metrics',
+    'pollinet_queue_metrics',
+    'pollinet_health',
+    'pollinet_mesh_state',
+    'pollinet_next_text_message',
+  ])
+    name: _lib.lookupFunction<Pointer<Utf8> Function(Int64), _HandleJsonFn>(
+        name),
+};
+
+typedef _RequestJsonFn = Pointer<Utf8> Function(Pointer<Utf8>);
+final Map<String, _RequestJsonFn> _requestJson = {
+  for (final name in [
+    'pollinet_create_approve_transaction',
+    'pollinet_create_revoke_transaction',
+    'pollinet_create_custom_transaction',
+    'pollinet_create_intent_bytes',
+  ])
+    name: _lib.lookupFunction<Pointer<Utf8> Function(Pointer<Utf8>),
+        _RequestJsonFn>(name),
+};
+
+/// Take ownership of a returned JSON string and unwrap its `FfiResult`
+dynamic _unwrap(Pointer<Utf8> ptr) {
+  final json = ptr.toDartString();
+  _stringFree(ptr);
+  final result = jsonDecode(json) as Map<String, dynamic>;
+  if (result['ok'] != true) {
+    throw PolliNetException(
+        result['code'] as String? ?? 'ERR_INTERNAL',
+        result['message'] as String? ?? json);
+  }
+  return result['data'];
+}
+
+void _check(int code, String call) {
+  if (code < 0) {
+    throw PolliNetException(_codeName(code), '$call failed ($code)');
+  }
+}
+
+String _codeName(int code) {
+  switch (code) {
+    case -2:
+      return 'ERR_INVALID_HANDLE';
+    case -3:
+      return 'ERR_SHUT_DOWN';
+    case -4:
+      return 'ERR_VERSION_MISMATCH';
+    default:
+      return 'ERR_INTERNAL';
+  }
+}
+
+T _withBytes<T>(Uint8List bytes, T Function(Pointer<Uint8>) body) {
+  final ptr = malloc<Uint8>(bytes.isEmpty ? 1 : bytes.length);
+  try {
+    ptr.asTypedList(bytes.length).setAll(0, bytes);
+    return body(ptr);
+  } finally {
+    malloc.free(ptr);
+  }
+}
+
+T _withString<T>(String value, T Function(Pointer<Utf8>) body) {
+  final ptr = value.toNativeUtf8();
+  try {
+    return body(ptr);
+  } finally {
+    malloc.free(ptr);
+  }
+}
+
+/// Stateless builders (no node required)
+class PolliNetBuilders {
+  static dynamic executorPda() =>
+      _unwrap(_lib.lookupFunction<Pointer<Utf8> Function(),
+          Pointer<Utf8> Function()>('pollinet_get_executor_pda')());
+
+  static dynamic createApproveTransaction(Map<String, dynamic> request) =>
+      _build('pollinet_create_approve_transaction', request);
+
+  static dynamic createRevokeTransaction(Map<String, dynamic> request) =>
+      _build('pollinet_create_revoke_transaction', request);
+
+  static dynamic createCustomTransaction(Map<String, dynamic> request) =>
+      _build('pollinet_create_custom_transaction', request);
+
+  static dynamic createIntentBytes(Map<String, dynamic> request) =>
+      _build('pollinet_create_intent_bytes', request);
+
+  static dynamic _build(String name, Map<String, dynamic> request) =>
+      _withString(jsonEncode(request), (ptr) => _unwrap(_requestJson[name]!(ptr)));
+}
+
+/// One PolliNet engine instance (wraps a C API handle)
+class PolliNetNode {
+  PolliNetNode._(this.handle);
+
+  /// Create a node from an `SdkConfig` map
+  factory PolliNetNode(Map<String, dynamic> config) {
+    final handle = _withString(jsonEncode(config), _init);
+    _check(handle, 'pollinet_init');
+    return PolliNetNode._(handle);
+  }
+
+  final int handle;
+  NativeCallable<_EventCallbackNative>? _listener;
+
+  static String get version => _version().toDartString();
+
+  /// `{sdkVersion, protocolVersion, configVersion, features}` of the loaded library
+  static Map<String, dynamic> get protocolInfo =>
+      _unwrap(_protocolInfo()) as Map<String, dynamic>;
+
+  /// Flush state and release the node; the object is unusable afterwards
+  void shutdown() {
+    // Shutdown detaches the native callback, so the callable can be closed after
+    final result = _shutdown(handle);
+    _listener?.close();
+    _listener = null;
+    _check(result, 'pollinet_shutdown');
+  }
+
+  /// Receive `ProtocolEvent` maps (delivered on the Dart event loop), or clear with null
+  void setEventListener(void Function(Map<String, dynamic> event)? listener) {
+    final previous = _listener;
+    if (listener == null) {
+      _check(_setEventCallback(handle, nullptr, nullptr),
+          'pollinet_set_owned_event_callback');
+      _listener = null;
+    } else {
+      final callable = NativeCallable<_EventCallbackNative>.listener(
+          (Pointer<Utf8> json, Pointer<Void> _) {
+        // Owned string: the listener runs after the native call has returned
+        final event = json.toDartString();
+        _stringFree(json);
+        listener(jsonDecode(event) as Map<String, dynamic>);
+      });
+      _check(_setEventCallback(handle, callable.nativeFunction, nullptr),
+          'pollinet_set_owned_event_callback');
+      _listener = callable;
+    }
+    previous?.close();
+  }
+
+  void pushInbound(Uint8List frame) => _check(
+      _withBytes(frame, (ptr) => _pushInbound(handle, ptr, frame.length)),
+      'pollinet_push_inbound');
+
+  /// Next frame to write, or null when none fits in [maxLen]
+  Uint8List? nextOutbound([int maxLen = maxFrameLength]) =>
+      _readFrame(_nextOutbound, maxLen, 'pollinet_next_outbound');
+
+  /// Periodic tick; returns the frames to send
+  List<Uint8List> tick(int nowMs) => (_unwrap(_tick(handle, nowMs)) as List)
+      .map((frame) => base64Decode(frame as String))
+      .toList();
+
+  void queueTransaction(Uint8List txBytes) => _check(
+      _withBytes(txBytes, (ptr) => _queueTransaction(handle, ptr, txBytes.length)),
+      'pollinet_queue_transaction');
+
+  dynamic metrics() => _handleCall('pollinet_metrics');
+  dynamic queueMetrics() => _handleCall('pollinet_queue_metrics');
+  dynamic health() => _handleCall('pollinet_health');
+  dynamic meshState() => _handleCall('pollinet_mesh_state');
+
+  /// Queue a text message; returns `{messageId, status, ...}`
+  dynamic sendTextMessage(String peerId, String body) => _withString(
+      peerId,
+      (peer) => _withString(
+          body, (text) => _unwrap(_sendTextMessage(handle, peer, text))));
+
+  void ingestTextFrame(String peerId, Uint8List frame) => _check(
+      _withString(
+          peerId,
+          (peer) => _withBytes(frame,
+              (ptr) => _ingestTextFrame(handle, peer, ptr, frame.length))),
+      'pollinet_ingest_text_frame');
+
+  Uint8List? nextTextFrame([int maxLen = maxFrameLength]) =>
+      _readFrame(_nextTextFrame, maxLen, 'pollinet_next_text_frame');
+
+  /// Oldest received text message, or null
+  dynamic nextTextMessage() => _handleCall('pollinet_next_text_message');
+
+  dynamic textMessageStatus(String messageId) => _withString(
+      messageId, (id) => _unwrap(_textMessageStatus(handle, id)));
+
+  dynamic _handleCall(String name) => _unwrap(_handleJson[name]!(handle));
+
+  Uint8List? _readFrame(
+      int Function(int, Pointer<Uint8>, int) next, int maxLen, String call) {
+    final buf = malloc<Uint8>(maxLen);
+    try {
+      final len = next(handle, buf, maxLen);
+      _check(len, call);
+      return len == 0 ? null : Uint8List.fromList(buf.asTypedList(len));
+    } finally {
+      malloc.free(buf);
+    }
+  }
+}
@@ -0,0 +1,210 @@
+#include "NativePolliNetModule.h"
+
+#include <pollinet.h>
+
+#include <cstring>
+#include <functional>
+#include <vector>
+
+namespace facebook::react {
+
+namespace {
+
+constexpr char kBase64[] =
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+std::string base64Encode(const uint8_t* data, size_t len) {
+  std::string out;
+  out.reserve((len + 2) / 3 * 4);
+  for (size_t i = 0; i < len; i += 3) {
+    uint32_t n = data[i] << 16;
+    if (i + 1 < len) n |= data[i + 1] << 8;
+    if (i + 2 < len) n |= data[i + 2];
+    out.push_back(kBase64[(n >> 18) & 63]);
+    out.push_back(kBase64[(n >> 12) & 63]);
+    out.push_back(i + 1 < len ? kBase64[(n >> 6) & 63] : '=');
+    out.push_back(i + 2 < len ? kBase64[n & 63] : '=');
+  }
+  return out;
+}
+
+std::vector<uint8_t> base64Decode(const std::string& in) {
+  std::vector<uint8_t> out;
+  out.reserve(in.size() / 4 * 3);
+  uint32_t buffer = 0;
+  int bits = 0;
+  for (char c : in) {
+    const char* pos = c ? std::strchr(kBase64, c) : nullptr;
+    if (pos == nullptr) continue; // padding / whitespace
+    buffer = (buffer << 6) | static_cast<uint32_t>(pos - kBase64);
+    bits += 6;
+    if (bits >= 8) {
+      bits -= 8;
+      out.push_back(static_cast<uint8_t>((buffer >> bits) & 0xFF));
+    }
+  }
+  return out;
+}
+
+// Take ownership of a C API string
+std::string take(char* json) {
+  std::string out = json ? json : R"({"ok":false,"code":"ERR_INTERNAL","message":"null result"})";
+  pollinet_string_free(json);
+  return out;
+}
+
+int64_t toHandle(double handle) {
+  return static_cast<int64_t>(handle);
+}
+
+std::optional<std::string> readFrame(
+    intptr_t (*next)(int64_t, uint8_t*, uintptr_t),
+    double handle,
+    double maxLen) {
+  std::vector<uint8_t> buf(static_cast<size_t>(maxLen > 0 ? maxLen : 0));
+  intptr_t len = next(toHandle(handle), buf.data(), buf.size());
+  if (len <= 0) return std::nullopt;
+  return base64Encode(buf.data(), static_cast<size_t>(len));
+}
+
+} // namespace
+
+struct NativePolliNetModule::EventSink : std::enable_shared_from_this<EventSink> {
+  std::weak_ptr<CallInvoker> jsInvoker;
+  std::function<void(const std::string&)> emit; // JS thread only
+
+  static void onEvent(char* eventJson, void* userData) {
+    auto* sink = static_cast<EventSink*>(userData);
+    std::string json = take(eventJson);
+    auto invoker = sink->jsInvoker.lock();
+    if (!invoker) return;
+    // The module (and its sink) is destroyed on the JS thread, so a live weak
+    // reference there means `emit` is still safe to call
+    invoker->invokeAsync([weak = sink->weak_from_this(), json = std::move(json)]() {
+      if (auto sink = weak.lock()) sink->emit(json);
+    });
+  }
+};
+
+NativePolliNetModule::NativePolliNetModule(std::shared_ptr<CallInvoker> jsInvoker)
+    : NativePolliNetCxxSpec(jsInvoker), sink_(std::make_shared<EventSink>()) {
+  sink_->jsInvoker = jsInvoker;
+  sink_->emit = [this](const std::string& json) { emitOnEvent(json); };
+}
+
+NativePolliNetModule::~NativePolliNetModule() {
+  for (int64_t handle : eventHandles_) {
+    pollinet_set_owned_event_callback(handle, nullptr, nullptr);
+  }
+}
+
+std::string NativePolliNetModule::version(jsi::Runtime&) {
+  return pollinet_version();
+}
+
+std::string NativePolliNetModule::protocolInfo(jsi::Runtime&) {
+  return take(pollinet_protocol_info());
+}
+
+double NativePolliNetModule::init(jsi::Runtime&, std::string configJson) {
+  return static_cast<double>(pollinet_init(configJson.c_str()));
+}
+
+double NativePolliNetModule::shutdown(jsi::Runtime&, double handle) {
+  eventHandles_.erase(toHandle(handle));
+  return pollinet_shutdown(toHandle(handle));
+}
+
+double NativePolliNetModule::setEventsEnabled(jsi::Runtime&, double handle, bool enabled) {
+  int64_t h = toHandle(handle);
+  int32_t status = enabled
+      ? pollinet_set_owned_event_callback(h, &EventSink::onEvent, sink_.get())
+      : pollinet_set_owned_event_callback(h, nullptr, nullptr);
+  if (status == 0) {
+    if (enabled) {
+      eventHandles_.insert(h);
+    } else {
+      eventHandles_.erase(h);
+    }
+  }
+  return status;
+}
+
+double NativePolliNetModule::pushInbound(jsi::Runtime&, double handle, std::string frameBase64) {
+  auto frame = base64Decode(frameBase64);
+  return pollinet_push_inbound(toHandle(handle), frame.data(), frame.size());
+}
+
+std::optional<std::string> NativePolliNetModule::nextOutbound(jsi::Runtime&, double handle, double maxLen) {
+  return readFrame(&pollinet_next_outbound, handle, maxLen);
+}
+
+std::string NativePolliNetModule::tick(jsi::Runtime&, double handle, double nowMs) {
+  return take(pollinet_tick(toHandle(handle), static_cast<uint64_t>(nowMs)));
+}
+
+double NativePolliNetModule::queueTransaction(jsi::Runtime&, double handle, std::string txBase64) {
+  auto tx = base64Decode(txBase64);
+  return pollinet_queue_transaction(toHandle(handle), tx.data(), tx.size());
+}
+
+std::string NativePolliNetModule::metrics(jsi::Runtime&, double handle) {
+  return take(pollinet_metrics(toHandle(handle)));
+}
+
+std::string NativePolliNetModule::queueMetrics(jsi::Runtime&, double handle) {
+  return take(pollinet_queue_metrics(toHandle(handle)));
+}
+
+std::string NativePolliNetModule::health(jsi::Runtime&, double handle) {
+  return take(pollinet_health(toHandle(handle)));
+}
+
+std::string NativePolliNetModule::meshState(jsi::Runtime&, double handle) {
+  return take(pollinet_mesh_state(toHandle(handle)));
+}
+
+std::string NativePolliNetModule::sendTextMessage(
+    jsi::Runtime&, double handle, std::string peerId, std::string body) {
+  return take(pollinet_send_text_message(toHandle(handle), peerId.c_str(), body.c_str()));
+}
+
+double NativePolliNetModule::ingestTextFrame(
+    jsi::Runtime&, double handle, std::string peerId, std::string frameBase64) {
+  auto frame = base64Decode(frameBase64);
+  return pollinet_ingest_text_frame(toHandle(handle), peerId.c_str(), frame.data(), frame.size());
+}
+
+std::optional<std::string> NativePolliNetModule::nextTextFrame(jsi::Runtime&, double handle, double maxLen) {
+  return readFrame(&pollinet_next_text_frame, handle, maxLen);
+}
+
+std::string NativePolliNetModule::nextTextMessage(jsi::Runtime&, double handle) {
+  return take(pollinet_next_text_message(toHandle(handle)));
+}
+
+std::string NativePolliNetModule::textMessageStatus(jsi::Runtime&, double handle, std::string messageId) {
+  return take(pollinet_text_message_status(toHandle(handle), messageId.c_str()));
+}
+
+std::string NativePolliNetModule::getExecutorPda(jsi::Runtime&) {
+  return take(pollinet_get_executor_pda());
+}
+
+std::string NativePolliNetModule::createApproveTransaction(jsi::Runtime&, std::string requestJson) {
+  return take(pollinet_create_approve_transaction(requestJson.c_str()));
+}
+
+std::string NativePolliNetModule::createRevokeTransaction(jsi::Runtime&, std::string requestJson) {
+  return take(pollinet_create_revoke_transaction(requestJson.c_str()));
+}
+
+std::string NativePolliNetModule::createCustomTransaction(jsi::Runtime&, std::string requestJson) {
+  return take(pollinet_create_custom_transaction(requestJson.c_str()));
+}
+
+std::string NativePolliNetModule::createIntentBytes(jsi::Runtime&, std::string requestJson) {
+  return take(pollinet_create_intent_bytes(requestJson.c_str()));
+}
+
+} // namespace facebook::react
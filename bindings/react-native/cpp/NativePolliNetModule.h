@@ -0,0 +1,59 @@
+// Pure C++ TurboModule over the PolliNet C API (include/pollinet.h).
+//
+// Works on Android and iOS alike: the app links libpollinet (staticlib on iOS,
+// cdylib on Android) and registers this module from its C++ module provider.
+// Handles cross into JS as numbers, exact for any handle below 2^53.
+
+#pragma once
+
+#include <PolliNetSpecJSI.h>
+
+#include <memory>
+#include <optional>
+#include <set>
+#include <string>
+
+namespace facebook::react {
+
+class NativePolliNetModule : public NativePolliNetCxxSpec<NativePolliNetModule> {
+ public:
+  explicit NativePolliNetModule(std::shared_ptr<CallInvoker> jsInvoker);
+  ~NativePolliNetModule();
+
+  std::string version(jsi::Runtime& rt);
+  std::string protocolInfo(jsi::Runtime& rt);
+  double init(jsi::Runtime& rt, std::string configJson);
+  double shutdown(jsi::Runtime& rt, double handle);
+  double setEventsEnabled(jsi::Runtime& rt, double handle, bool enabled);
+
+  double pushInbound(jsi::Runtime& rt, double handle, std::string frameBase64);
+  std::optional<std::string> nextOutbound(jsi::Runtime& rt, double handle, double maxLen);
+  std::string tick(jsi::Runtime& rt, double handle, double nowMs);
+  double queueTransaction(jsi::Runtime& rt, double handle, std::string txBase64);
+
+  std::string metrics(jsi::Runtime& rt, double handle);
+  std::string queueMetrics(jsi::Runtime& rt, double handle);
+  std::string health(jsi::Runtime& rt, double handle);
+  std::string meshState(jsi::Runtime& rt, double handle);
+
+  std::string sendTextMessage(jsi::Runtime& rt, double handle, std::string peerId, std::string body);
+  double ingestTextFrame(jsi::Runtime& rt, double handle, std::string peerId, std::string frameBase64);
+  std::optional<std::string> nextTextFrame(jsi::Runtime& rt, double handle, double maxLen);
+  std::string nextTextMessage(jsi::Runtime& rt, double handle);
+  std::string textMessageStatus(jsi::Runtime& rt, double handle, std::string messageId);
+
+  std::string getExecutorPda(jsi::Runtime& rt);
+  std::string createApproveTransaction(jsi::Runtime& rt, std::string requestJson);
+  std::string createRevokeTransaction(jsi::Runtime& rt, std::string requestJson);
+  std::string createCustomTransaction(jsi::Runtime& rt, std::string requestJson);
+  std::string createIntentBytes(jsi::Runtime& rt, std::string requestJson);
+
+  // Forwards native events to the JS thread (defined in the .cpp)
+  struct EventSink;
+
+ private:
+  std::shared_ptr<EventSink> sink_;
+  std::set<int64_t> eventHandles_;
+};
+
+} // namespace facebook::react
@@ -0,0 +1,41 @@
+// TurboModule spec for the PolliNet C API (see cpp/NativePolliNetModule.h).
+//
+// Codegen generates the C++ base class from this file. JSON-returning methods
+// return the raw `FfiResult` envelope; byte payloads are base64 strings.
+
+import type { TurboModule, CodegenTypes } from 'react-native';
+import { TurboModuleRegistry } from 'react-native';
+
+export interface Spec extends TurboModule {
+  readonly onEvent: CodegenTypes.EventEmitter<string>;
+
+  version(): string;
+  protocolInfo(): string;
+  init(configJson: string): number;
+  shutdown(handle: number): number;
+  setEventsEnabled(handle: number, enabled: boolean): number;
+
+  pushInbound(handle: number, frameBase64: string): number;
+  nextOutbound(handle: number, maxLen: number): string | null;
+  tick(handle: number, nowMs: number): string;
+  queueTransaction(handle: number, txBase64: string): number;
+
+  metrics(handle: number): string;
+  queueMetrics(handle: number): string;
+  health(handle: number): string;
+  meshState(handle: number): string;
+
+  sendTextMessage(handle: number, peerId: string, body: string): string;
+  ingestTextFrame(handle: number, peerId: string, frameBase64: string): number;
+  nextTextFrame(handle: number, maxLen: number): string | null;
+  nextTextMessage(handle: number): string;
+  textMessageStatus(handle: number, messageId: string): string;
+
+  getExecutorPda(): string;
+  createApproveTransaction(requestJson: string): string;
+  createRevokeTransaction(requestJson: string): string;
+  createCustomTransaction(requestJson: string): string;
+  createIntentBytes(requestJson: string): string;
+}
+
+export default TurboModuleRegistry.getEnforcing<Spec>('NativePolliNet');
@@ -0,0 +1,155 @@
+// Typed wrapper over the PolliNet TurboModule: unwraps `FfiResult` envelopes
+// and throws `PolliNetError` (with the `ERR_*` code) on failure.
+
+import NativePolliNet from './NativePolliNet';
+
+/** Largest frame the engine produces (one BLE frame) */
+export const MAX_FRAME_LENGTH = 516;
+
+export class PolliNetError extends Error {
+  constructor(public readonly code: string, message: string) {
+    super(`[${code}] ${message}`);
+    this.name = 'PolliNetError';
+  }
+}
+
+const STATUS_CODES: Record<number, string> = {
+  [-2]: 'ERR_INVALID_HANDLE',
+  [-3]: 'ERR_SHUT_DOWN',
+  [-4]: 'ERR_VERSION_MISMATCH',
+};
+
+function unwrap<T>(json: string): T {
+  const result = JSON.parse(json);
+  if (!result.ok) {
+    throw new PolliNetError(result.code ?? 'ERR_INTERNAL', result.message ?? json);
+  }
+  return result.data as T;
+}
+
+function check(status: number, call: string): void {
+  if (status < 0) {
+    throw new PolliNetError(STATUS_CODES[status] ?? 'ERR_INTERNAL', `${call} failed (${status})`);
+  }
+}
+
+export type ProtocolEvent = {
+  type: 'TransactionComplete' | 'Ack' | 'TextMessage' | 'Error' | 'RequestComplete';
+  tx_id?: string | null;
+  size?: number | null;
+  message?: string | null;
+  requestId?: number;
+};
+
+/** Stateless builders (no node required) */
+export const builders = {
+  executorPda: () => unwrap<{ pda: string; bump: number }>(NativePolliNet.getExecutorPda()),
+  createApproveTransaction: (request: object) =>
+    unwrap(NativePolliNet.createApproveTransaction(JSON.stringify(request))),
+  createRevokeTransaction: (request: object) =>
+    unwrap(NativePolliNet.createRevokeTransaction(JSON.stringify(request))),
+  createCustomTransaction: (request: object) =>
+    unwrap(NativePolliNet.createCustomTransaction(JSON.stringify(request))),
+  createIntentBytes: (request: object) =>
+    unwrap(NativePolliNet.createIntentBytes(JSON.stringify(request))),
+};
+
+/** One PolliNet engine instance (wraps a C API handle) */
+export class PolliNetNode {
+  private subscription?: { remove(): void };
+
+  private constructor(readonly handle: number) {}
+
+  static get version(): string {
+    return NativePolliNet.version();
+  }
+
+  /** SDK version, wire protocol version and compiled features */
+  static get protocolInfo() {
+    return unwrap<{
+      sdkVersion: string;
+      protocolVersion: number;
+      configVersion: number;
+      features: string[];
+    }>(NativePolliNet.protocolInfo());
+  }
+
+  /**
+   * Create a node from an `SdkConfig` object; `minSdkVersion` /
+   * `minProtocolVersion` make it fail with `ERR_VERSION_MISMATCH` on an older library
+   */
+  static create(config: object): PolliNetNode {
+    const handle = NativePolliNet.init(JSON.stringify(config));
+    check(handle, 'pollinet_init');
+    return new PolliNetNode(handle);
+  }
+
+  shutdown(): void {
+    this.subscription?.remove();
+    this.subscription = undefined;
+    check(NativePolliNet.shutdown(this.handle), 'pollinet_shutdown');
+  }
+
+  /** Receive engine events on the JS thread, or stop with `null` */
+  setEventListener(listener: ((event: ProtocolEvent) => void) | null): void {
+    this.subscription?.remove();
+    this.subscription = undefined;
+    if (listener) {
+      // All nodes share the module's emitter; apps normally run a single node
+      this.subscription = NativePolliNet.onEvent((json) => listener(JSON.parse(json)));
+    }
+    check(NativePolliNet.setEventsEnabled(this.handle, listener !== null), 'setEventsEnabled');
+  }
+
+  pushInbound(frameBase64: string): void {
+    check(NativePolliNet.pushInbound(this.handle, frameBase64), 'pollinet_push_inbound');
+  }
+
+  /** Next frame to write (base64), or null when none fits in `maxLen` */
+  nextOutbound(maxLen = MAX_FRAME_LENGTH): string | null {
+    return NativePolliNet.nextOutbound(this.handle, maxLen);
+  }
+
+  /** Periodic tick; returns base64 frames to send */
+  tick(nowMs = Date.now()): string[] {
+    return unwrap<string[]>(NativePolliNet.tick(this.handle, nowMs));
+  }
+
+  queueTransaction(txBase64: string): void {
+    check(NativePolliNet.queueTransaction(this.handle, txBase64), 'pollinet_queue_transaction');
+  }
+
+  metrics = () => unwrap(NativePolliNet.metrics(this.handle));
+  queueMetrics = () => unwrap(NativePolliNet.queueMetrics(this.handle));
+  health = () => unwrap<{ status: string; issues: string[] }>(NativePolliNet.health(this.handle));
+  meshState = () => unwrap(NativePolliNet.meshState(this.handle));
+
+  sendTextMessage(peerId: string, body: string) {
+    return unwrap<{ messageId: string; status: string }>(
+      NativePolliNet.sendTextMessage(this.handle, peerId, body),
+    );
+  }
+
+  ingestTextFrame(peerId: string, frameBase64: string): void {
+    check(
+      NativePolliNet.ingestTextFrame(this.handle, peerId, frameBase64),
+      'pollinet_ingest_text_frame',
+    );
+  }
+
+  nextTextFrame(maxLen = MAX_FRAME_LENGTH): string | null {
+    return NativePolliNet.nextTextFrame(this.handle, maxLen);
+  }
+
+  nextTextMessage() {
+    return unwrap<{ messageId: string; peerId: string; body: string; receivedAt: number } | null>(
+      NativePolliNet.nextTextMessage(this.handle),
+    );
+  }
+
+  textMessageStatus(messageId: string) {
+    return unwrap<{ messageId: string; status: string }>(
+      NativePolliNet.textMessageStatus(this.handle, messageId),
+    );
+  }
+}
@@ -24,4 +24,28 @@ fn main() {
     // Re-run if .env changes
     println!("cargo:rerun-if-changed=.env");
     println!("cargo:rerun-if-env-changed=POLLICORE_URL");
+
+    #[cfg(feature = "c-api")]
+    generate_c_header();
+}
+
+// Regenerate include/pollinet.h from src/ffi/c_api.rs (see cbindgen.toml)
+#[cfg(feature = "c-api")]
+fn generate_c_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+        .expect("Failed to read cbindgen.toml");
+    match cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+    {
+        Ok(bindings) => {
+            bindings.write_to_file(format!("{}/include/pollinet.h", crate_dir));
+        }
+        // Don't fail the build on parse errors; the committed header stays usable
+        Err(e) => println!("cargo:warning=cbindgen failed: {}", e),
+    }
+    println!("cargo:rerun-if-changed=src/ffi/c_api.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
 }
@@ -0,0 +1,250 @@
+#ifndef POLLINET_H
+#define POLLINET_H
+
+/* Generated by cbindgen from src/ffi/c_api.rs — do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Failure (invalid input, engine error)
+#define POLLINET_ERR -1
+
+// The handle was never issued
+#define POLLINET_ERR_INVALID_HANDLE -2
+
+// The handle was shut down
+#define POLLINET_ERR_SHUT_DOWN -3
+
+// `pollinet_init`: the config requires a newer SDK or wire protocol
+#define POLLINET_ERR_VERSION_MISMATCH -4
+
+// Event callback: receives a `ProtocolEvent` JSON document (valid only for the
+// duration of the call) and the `user_data` given at registration
+typedef void (*PollinetEventCallback)(const char *event_json, void *user_data);
+
+// Owned-string event callback: `event_json` belongs to the callee, which must
+// release it with `pollinet_string_free`
+typedef void (*PollinetOwnedEventCallback)(char *event_json, void *user_data);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// SDK version (static string, do not free)
+const char *pollinet_version(void);
+
+// SDK version, wire protocol version and compiled features as JSON `FfiResult`
+//
+// Safe to call before `pollinet_init`.
+char *pollinet_protocol_info(void);
+
+// Create a node from an `SdkConfig` JSON document; returns its handle or -1
+//
+// Returns `POLLINET_ERR_VERSION_MISMATCH` when the config's `minSdkVersion`
+// or `minProtocolVersion` is newer than this build.
+//
+// # Safety
+// `config_json` must be a valid NUL-terminated string.
+int64_t pollinet_init(const char *config_json);
+
+// Create a node from an `SdkConfig` file (`.toml` or `.json`); returns its
+// handle or -1, like `pollinet_init`
+//
+// # Safety
+// `path` must be a valid NUL-terminated string.
+int64_t pollinet_init_from_file(const char *path);
+
+// Flush and release a node; returns 0 or a negative handle error
+//
+// Later calls with the handle fail with `POLLINET_ERR_SHUT_DOWN`.
+int32_t pollinet_shutdown(int64_t handle);
+
+// Feed `len` bytes received from a peer; returns 0 or a negative error
+//
+// # Safety
+// `data` must point to `len` readable bytes.
+int32_t pollinet_push_inbound(int64_t handle, const uint8_t *data, uintptr_t len);
+
+// Like `pollinet_push_inbound`, recording `peer_id` as the source of the
+// transaction in the transaction history
+//
+// # Safety
+// `peer_id` must be a valid NUL-terminated string and `data` must point to
+// `len` readable bytes.
+int32_t pollinet_push_inbound_from(int64_t handle,
+                                   const char *peer_id,
+                                   const uint8_t *data,
+                                   uintptr_t len);
+
+// Copy the next outbound frame that fits in `cap` bytes into `buf`
+//
+// Returns the frame length, 0 when nothing fits, or a negative error.
+//
+// # Safety
+// `buf` must point to `cap` writable bytes.
+intptr_t pollinet_next_outbound(int64_t handle, uint8_t *buf, uintptr_t cap);
+
+// Periodic tick; returns JSON `FfiResult` with the base64 frames to send
+char *pollinet_tick(int64_t handle, uint64_t now_ms);
+
+// Fragment a signed transaction and queue it for relay; returns the fragment count or a negative error
+//
+// # Safety
+// `tx_bytes` must point to `len` readable bytes.
+int32_t pollinet_queue_transaction(int64_t handle, const uint8_t *tx_bytes, uintptr_t len);
+
+// Transport metrics as JSON `FfiResult`
+char *pollinet_metrics(int64_t handle);
+
+// Register (or, with a null callback, clear) the event callback; returns 0 or a negative error
+//
+// The callback runs synchronously on the thread that produced the event
+// (e.g. the caller of `pollinet_push_inbound`) and must not block.
+// `user_data` must remain valid, and usable from any thread, until the
+// callback is replaced or the node is shut down.
+int32_t pollinet_set_event_callback(int64_t handle,
+                                    PollinetEventCallback callback,
+                                    void *user_data);
+
+// Like `pollinet_set_event_callback`, but the event string outlives the call
+//
+// For hosts that hand events to another thread or event loop before reading
+// them (Dart `NativeCallable.listener`, React Native's JS thread).
+int32_t pollinet_set_owned_event_callback(int64_t handle,
+                                          PollinetOwnedEventCallback callback,
+                                          void *user_data);
+
+// Queue metrics as JSON `FfiResult<QueueMetricsFFI>`
+char *pollinet_queue_metrics(int64_t handle);
+
+// Compression totals as JSON `FfiResult<CompressionStats>`
+char *pollinet_compression_stats(int64_t handle);
+
+// Queue health as JSON `FfiResult<QueueHealthFFI>` (`status`: HEALTHY | WARNING | CRITICAL)
+char *pollinet_health(int64_t handle);
+
+// Mesh view (discovered/connected peers, per-peer stats, topology) as JSON `FfiResult`
+char *pollinet_mesh_state(int64_t handle);
+
+// Queue a text message for `peer_id`; returns JSON `FfiResult` with its id and status
+//
+// # Safety
+// `peer_id` and `body` must be valid NUL-terminated strings.
+char *pollinet_send_text_message(int64_t handle, const char *peer_id, const char *body);
+
+// Feed a text frame received from `peer_id`; returns 0 or a negative error
+//
+// # Safety
+// `peer_id` must be a valid NUL-terminated string and `data` must point to
+// `len` readable bytes.
+int32_t pollinet_ingest_text_frame(int64_t handle,
+                                   const char *peer_id,
+                                   const uint8_t *data,
+                                   uintptr_t len);
+
+// Copy the next text or ack frame that fits in `cap` bytes into `buf`
+//
+// Returns the frame length, 0 when nothing fits, or a negative error.
+//
+// # Safety
+// `buf` must point to `cap` writable bytes.
+intptr_t pollinet_next_text_frame(int64_t handle, uint8_t *buf, uintptr_t cap);
+
+// Oldest received text message as JSON `FfiResult` (`data` is null when none is pending)
+char *pollinet_next_text_message(int64_t handle);
+
+// Delivery status of a sent text message as JSON `FfiResult`
+//
+// # Safety
+// `message_id` must be a valid NUL-terminated string.
+char *pollinet_text_message_status(int64_t handle, const char *message_id);
+
+// Watch a transaction by mesh id (hex) or base58 signature as JSON `FfiResult`
+//
+// Status changes are delivered to the event callback as `TxStatus` events.
+//
+// # Safety
+// `id` must be a valid NUL-terminated string.
+char *pollinet_watch_transaction(int64_t handle, const char *id);
+
+// Stop watching; returns 1 if the watch was active, 0 if not, or a negative handle error
+int32_t pollinet_unwatch_transaction(int64_t handle, uint64_t watch_id);
+
+// Add or replace an address book contact as JSON `FfiResult`
+//
+// `contact_json` is `{label, pubkey, mints?}`; labels ignore case.
+//
+// # Safety
+// `contact_json` must be a valid NUL-terminated string.
+char *pollinet_save_contact(int64_t handle, const char *contact_json);
+
+// Remove an address book contact by label; returns `{removed}` as JSON `FfiResult`
+//
+// # Safety
+// `label` must be a valid NUL-terminated string.
+char *pollinet_remove_contact(int64_t handle, const char *label);
+
+// Address book contacts sorted by label as JSON `FfiResult`
+char *pollinet_contacts(int64_t handle);
+
+// Page of the received-transaction history (newest first) as JSON `FfiResult`
+//
+// `query_json` is a `TxHistoryQuery` document; null or empty uses the defaults.
+//
+// # Safety
+// `query_json` must be null or a valid NUL-terminated string.
+char *pollinet_transaction_history(int64_t handle, const char *query_json);
+
+// Executor PDA as JSON `FfiResult`
+char *pollinet_get_executor_pda(void);
+
+// Build an approve transaction from a `CreateApproveTransactionRequest` JSON document
+//
+// # Safety
+// `request_json` must be a valid NUL-terminated string.
+char *pollinet_create_approve_transaction(const char *request_json);
+
+// Build a revoke transaction from a `CreateRevokeTransactionRequest` JSON document
+//
+// # Safety
+// `request_json` must be a valid NUL-terminated string.
+char *pollinet_create_revoke_transaction(const char *request_json);
+
+// Build a transaction from a `CreateCustomTransactionRequest` JSON document
+//
+// # Safety
+// `request_json` must be a valid NUL-terminated string.
+char *pollinet_create_custom_transaction(const char *request_json);
+
+// Parse a Solana Pay transfer request URL into its `TransferRequest` fields
+//
+// # Safety
+// `url` must be a valid NUL-terminated string.
+char *pollinet_parse_solana_pay_url(const char *url);
+
+// Build a durable-nonce payment from a `CreateSolanaPayTransactionRequest`
+// JSON document
+//
+// # Safety
+// `request_json` must be a valid NUL-terminated string.
+char *pollinet_create_solana_pay_transaction(const char *request_json);
+
+// Build canonical intent bytes from a `CreateIntentBytesRequest` JSON document
+//
+// # Safety
+// `request_json` must be a valid NUL-terminated string.
+char *pollinet_create_intent_bytes(const char *request_json);
+
+// Free a string returned by this API
+//
+// # Safety
+// `ptr` must be null or a pointer returned by a `pollinet_*` call, freed once.
+void pollinet_string_free(char *ptr);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* POLLINET_H */
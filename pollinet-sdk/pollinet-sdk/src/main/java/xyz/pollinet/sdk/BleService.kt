@@ -152,6 +152,8 @@ class BleService : Service() {
     private var bluetoothAdapter: BluetoothAdapter? = null
     private var bleScanner: BluetoothLeScanner? = null
     private var bleAdvertiser: BluetoothLeAdvertiser? = null
+    /** Manufacturer data last fetched from the SDK (see [PolliNetSDK.getAdvertisement]) */
+    @Volatile private var currentAdvertisement: Advertisement? = null
     private var gattServer: BluetoothGattServer? = null
     private var connectedDevice: BluetoothDevice? = null
     private var gattCharacteristicTx: BluetoothGattCharacteristic? = null
@@ -245,6 +247,9 @@ class BleService : Service() {
 
     // Relevance system: tx currently loaded into the transport frame buffer for this connection
     private var activeTxId: String? = null
+
+    // Control frames to send before the next transaction fragment (e.g. our TX_DIGEST)
+    private val pendingControlFrames = java.util.concurrent.ConcurrentLinkedDeque<ByteArray>()
     
     // Track if we're ready to send (descriptor write completed)
     private var descriptorWriteComplete = false
@@ -630,6 +635,26 @@ class BleService : Service() {
                 }
             }
             
+            // Keep the advertised id current; in privacy mode it rotates and
+            // advertising restarts so the radio address changes with it
+            serviceScope.launch {
+                while (isActive) {
+                    val ad = sdk?.getAdvertisement()?.getOrNull()
+                    if (ad != null && ad != currentAdvertisement) {
+                        currentAdvertisement = ad
+                        if (_isAdvertising.value) {
+                            withContext(Dispatchers.Main) {
+                                appendLog("🎭 Advertised id now ${ad.advertisedId}")
+                                stopAdvertising()
+                                startAdvertising()
+                            }
+                        }
+                    }
+                    val untilRotation = ad?.rotatesAt?.let { it * 1000 - System.currentTimeMillis() }
+                    delay((untilRotation ?: 60_000L).coerceIn(1_000L, 60_000L))
+                }
+            }
+            
             // Start metrics collection
             serviceScope.launch {
                 while (isActive) {
@@ -2266,6 +2291,12 @@ class BleService : Service() {
             // Check frame type byte (first byte of every frame).
             // 0x08 = CONFIRMATION, 0x09 = TX_ABORT, 0x0A = DRAIN_READY, 0x0B = CLOSE_ACK
             // 0x0C = CONFIRMATION_FRAG (multi-packet JSON confirmation, new)
+            // 0x0D = signed envelope (text messages, acks and read receipts)
+            // 0x0E = TX_DIGEST (transactions the peer already holds)
+            // 0x0F = TX_CANCEL (discard a transaction's fragments)
+            // 0x10 = FRAGMENT_NACK (peer dropped a fragment; resend it)
+            // 0x14 = RELAY_RECEIPT (signed proof a relay carried our transaction)
+            // 0x01 / 0x02 = PING / PONG (mesh diagnostics; fall through if not a probe)
             // Legacy: '{' (0x7B) = JSON confirmation (old path, keep for backward compat)
             // 0x01–0x07 = DATA_FRAGMENT types (fall through to pushInbound)
             if (data.isNotEmpty()) {
@@ -2313,6 +2344,86 @@ class BleService : Service() {
                         handleConfirmationFragment(data)
                         return
                     }
+                    0x0D -> {
+                        val peerAddr = connectedDevice?.address ?: clientGatt?.device?.address ?: ""
+                        sdk?.ingestTextFrame(peerAddr, data)?.onSuccess { result ->
+                            appendLog("💬 Text frame from ${peerAddr.takeLast(5)}: ${result.kind}")
+                        }?.onFailure { e ->
+                            appendLog("Failed to ingest envelope: ${e.message}")
+                        }
+                        return
+                    }
+                    0x0E -> {
+                        val peerAddr = connectedDevice?.address ?: clientGatt?.device?.address ?: ""
+                        sdk?.ingestTxDigest(peerAddr, data)?.onSuccess { summary ->
+                            appendLog("🌸 Peer holds ${summary.alreadyHeld} of our queued tx; ${summary.toSend} left to send")
+                        }?.onFailure { e ->
+                            appendLog("Failed to ingest TX_DIGEST: ${e.message}")
+                        }
+                        return
+                    }
+                    0x01, 0x02 -> {
+                        val probe = sdk?.ingestProbeFrame("", data)
+                        if (probe?.isSuccess == true) {
+                            probe.getOrNull()?.let { result ->
+                                appendLog("📡 Probe ${result.probeId} answered: rtt=${result.rttMs}ms hops=${result.hops.size}")
+                            }
+                            return
+                        }
+                    }
+                    0x0F -> {
+                        // TX_CANCEL: drop every buffered fragment of the tx
+                        sdk?.ingestTxCancel(data)?.onSuccess { dropped ->
+                            appendLog("Received TX_CANCEL frame — dropped=$dropped")
+                        }?.onFailure { e ->
+                            appendLog("Failed to ingest TX_CANCEL: ${e.message}")
+                        }
+                        return
+                    }
+                    0x10 -> {
+                        sdk?.ingestFragmentNack(data)?.onSuccess { resent ->
+                            appendLog("Received FRAGMENT_NACK frame — resent=$resent")
+                        }?.onFailure { e ->
+                            appendLog("Failed to ingest FRAGMENT_NACK: ${e.message}")
+                        }
+                        return
+                    }
+                    0x11 -> {
+                        // EMERGENCY_BROADCAST: shown once, relayed by the engine
+                        sdk?.ingestEmergency(data)?.onSuccess { notice ->
+                            notice?.let { appendLog("🚨 Emergency from ${it.origin.take(8)}: ${it.message}") }
+                        }?.onFailure { e ->
+                            appendLog("Failed to ingest EMERGENCY_BROADCAST: ${e.message}")
+                        }
+                        return
+                    }
+                    0x12 -> {
+                        // PAYMENT_REQUEST: a merchant asks this device to pay
+                        sdk?.ingestPaymentRequest(data)?.onSuccess { request ->
+                            appendLog("🧾 Payment request ${request.requestId}: ${request.amount} to ${request.recipient.take(8)}")
+                        }?.onFailure { e ->
+                            appendLog("Failed to ingest PAYMENT_REQUEST: ${e.message}")
+                        }
+                        return
+                    }
+                    0x13 -> {
+                        // PAYMENT_RECEIPT: a payer's receipt or a merchant's countersignature
+                        sdk?.ingestPaymentReceipt(data)?.onSuccess { receipt ->
+                            val state = if (receipt.merchantSignature != null) "countersigned" else "received"
+                            appendLog("🧾 Payment receipt ${receipt.txSignature.take(8)} $state")
+                        }?.onFailure { e ->
+                            appendLog("Failed to ingest PAYMENT_RECEIPT: ${e.message}")
+                        }
+                        return
+                    }
+                    0x14 -> {
+                        sdk?.ingestRelayReceipt(data)?.onSuccess { accepted ->
+                            if (accepted) appendLog("🧾 Relay receipt received")
+                        }?.onFailure { e ->
+                            appendLog("Failed to ingest RELAY_RECEIPT: ${e.message}")
+                        }
+                        return
+                    }
                     0x7B -> {
                         // Legacy JSON confirmation ('{' = 0x7B)
                         handleReceivedConfirmation(data)
@@ -3124,10 +3235,16 @@ class BleService : Service() {
                 .setTxPowerLevel(AdvertiseSettings.ADVERTISE_TX_POWER_HIGH)
                 .build()
             
-            val data = AdvertiseData.Builder()
+            val builder = AdvertiseData.Builder()
                 .setIncludeDeviceName(false)
                 .addServiceUuid(android.os.ParcelUuid(SERVICE_UUID))
-                .build()
+            currentAdvertisement?.let { ad ->
+                builder.addManufacturerData(
+                    ad.manufacturerId,
+                    android.util.Base64.decode(ad.manufacturerData, android.util.Base64.NO_WRAP)
+                )
+            }
+            val data = builder.build()
             
             advertiser.startAdvertising(settings, data, advertiseCallback)
             _isAdvertising.value = true
@@ -3232,6 +3349,12 @@ class BleService : Service() {
 
         appendLog("🚀 Starting sending loop")
         sendingJob = serviceScope.launch {
+            // Tell the peer what we already hold, and forget its digest from a past session
+            pendingControlFrames.clear()
+            val peerAddr = connectedDevice?.address ?: clientGatt?.device?.address ?: ""
+            sdk?.clearTxDigest(peerAddr)
+            sdk?.getTxDigest()?.getOrNull()?.let { pendingControlFrames.add(it) }
+
             // Load the highest-relevance transaction BEFORE the loop starts so that
             // sendNextOutbound()'s first call always finds frames in the transport buffer.
             // Previously this ran in a parallel coroutine, causing a race where the sending
@@ -3279,7 +3402,13 @@ class BleService : Service() {
             // BLE safe fragment size: dynamically tied to negotiated MTU
             // Use currentMtu - 10 to ensure reliable transmission (10 bytes safety margin)
             val safeMaxLen = (currentMtu - 10).coerceAtLeast(20) // guard against too small values
-            var data = sdkInstance.nextOutbound(maxLen = safeMaxLen)
+            val peerAddr = connectedDevice?.address ?: clientGatt?.device?.address ?: ""
+            // Control frames (our digest, relay receipts) and text frames go ahead of
+            // transaction fragments
+            var data = pendingControlFrames.pollFirst()
+                ?: sdkInstance.nextRelayReceipt().getOrNull()
+                ?: sdkInstance.nextTextFrame(safeMaxLen)
+                ?: sdkInstance.nextOutboundForPeer(peerAddr, maxLen = safeMaxLen)
 
             // The transport's low-level outbound_queue only refills via loadForSending(), which
             // pulls from queue_manager().outbound. loadForSending() is called once at connect;
@@ -3291,7 +3420,7 @@ class BleService : Service() {
                 if (loaded != null) {
                     activeTxId = loaded.txId
                     appendLog("📡 Loaded fresh tx ${loaded.txId.take(8)}… mid-session (relevance=${loaded.relevance}, fragments=${loaded.fragmentCount})")
-                    data = sdkInstance.nextOutbound(maxLen = safeMaxLen)
+                    data = sdkInstance.nextOutboundForPeer(peerAddr, maxLen = safeMaxLen)
                 }
             }
 
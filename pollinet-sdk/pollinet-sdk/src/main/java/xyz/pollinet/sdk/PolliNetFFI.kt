@@ -17,7 +17,8 @@ object PolliNetFFI {
 
     /**
      * Initialize the PolliNet SDK with the given configuration.
-     * @param configBytes JSON-encoded SdkConfig
+     * @param configBytes JSON-encoded SdkConfig; set `minSdkVersion` / `minProtocolVersion`
+     *   to refuse (return -1, logging ERR_VERSION_MISMATCH) a library older than the app expects
      * @return Handle to the initialized SDK instance, or -1 on error
      */
     external fun init(configBytes: ByteArray): Long
@@ -28,7 +29,15 @@ object PolliNetFFI {
     external fun version(): String
 
     /**
-     * Shutdown the SDK and release resources
+     * SDK version, wire protocol version and compiled features; callable before [init]
+     * @return JSON FfiResult<ProtocolInfo> (`sdkVersion`, `protocolVersion`, `configVersion`, `features`)
+     */
+    external fun protocolInfo(): String
+
+    /**
+     * Shutdown the SDK and release resources. Queues are flushed and the engine is
+     * dropped once no other handle shares it; later calls with [handle] return an
+     * error ("Handle … has been shut down").
      */
     external fun shutdown(handle: Long)
 
@@ -71,6 +80,28 @@ object PolliNetFFI {
      */
     external fun pushInbound(handle: Long, data: ByteArray): String
 
+    /**
+     * Push inbound data received from [peerId]; the peer is recorded as the
+     * source of the transaction in the transaction history (BLE handles only)
+     * @return JSON FfiResult
+     */
+    external fun pushInboundFromPeer(handle: Long, peerId: String, data: ByteArray): String
+
+    /**
+     * Queue inbound data without blocking the calling (BLE callback) thread.
+     * When the queue is full the frame is dropped and its sender NACKed.
+     * Queued frames are processed on [tick] or by [processInbound].
+     * @param peerId Sender address, or "" if unknown
+     * @return JSON FfiResult<Boolean>: false if the frame was dropped
+     */
+    external fun enqueueInbound(handle: Long, peerId: String, data: ByteArray): String
+
+    /**
+     * Process up to [max] frames queued by [enqueueInbound]
+     * @return JSON FfiResult<Int> with the number processed
+     */
+    external fun processInbound(handle: Long, max: Int): String
+
     /**
      * Get next outbound frame to send via GATT
      * @param maxLen Maximum frame size (MTU)
@@ -78,6 +109,75 @@ object PolliNetFFI {
      */
     external fun nextOutbound(handle: Long, maxLen: Long): ByteArray?
 
+    /**
+     * Next outbound frame for one peer, skipping transactions its digest
+     * (see [ingestTxDigest]) says it already holds. Same as [nextOutbound]
+     * until the peer's digest arrives.
+     * @return Frame bytes, or null if nothing is left for this peer
+     */
+    external fun nextOutboundForPeer(handle: Long, peerId: String, maxLen: Long): ByteArray?
+
+    /**
+     * Bloom digest of the transactions this device holds. Send `frame` to a
+     * peer right after connecting as a TX_DIGEST (0x0E) control frame.
+     * @return JSON FfiResult with {frame (base64)}
+     */
+    external fun getTxDigest(handle: Long): String
+
+    /**
+     * Hand a TX_DIGEST frame received from a peer to the engine
+     * @return JSON FfiResult with {peerCount, alreadyHeld, toSend}
+     */
+    external fun ingestTxDigest(handle: Long, peerId: String, data: ByteArray): String
+
+    /**
+     * Forget a peer's digest; call when it disconnects
+     * @return JSON FfiResult with true
+     */
+    external fun clearTxDigest(handle: Long, peerId: String): String
+
+    /**
+     * Record the capability byte a peer advertises (see [getCapabilityFlags]).
+     * Peers without bit 4 are sent fragments in the legacy layout, unless
+     * they have sent a compact fragment or a TX_DIGEST themselves.
+     * @return JSON FfiResult with true
+     */
+    external fun setPeerCapabilities(handle: Long, peerId: String, capabilities: Int): String
+
+    /**
+     * Pin a peer's base58 device key (e.g. scanned from its QR code) under its
+     * short id. Envelopes claiming that short id must be signed by this key,
+     * and control messages are only accepted from pinned keys; other senders
+     * are pinned on first use.
+     * @return JSON FfiResult with true
+     */
+    external fun pinPeerKey(handle: Long, deviceKey: String): String
+
+    /**
+     * Save the session with a peer that disconnected, keyed by its device
+     * key, so the next encounter can skip the handshake. Call instead of
+     * [clearTxDigest].
+     * @param mtu Negotiated MTU, or -1 if unknown
+     * @param protocolVersion Peer's protocol version, or -1 if unknown
+     * @return JSON FfiResult with true
+     */
+    external fun savePeerSession(
+        handle: Long,
+        peerId: String,
+        deviceKey: String,
+        mtu: Int,
+        protocolVersion: Int
+    ): String
+
+    /**
+     * Resume the saved session with a reconnected peer: restores its digest
+     * and requests the fragments missing from interrupted transfers
+     * @return JSON FfiResult with {mtu, protocolVersion, digestRestored,
+     *         resumedTransfers, fragmentsRequested}, or null data when there
+     *         is no session and the full handshake is needed
+     */
+    external fun resumePeerSession(handle: Long, peerId: String, deviceKey: String): String
+
     /**
      * Periodic tick for retry/timeout handling
      * @param nowMs Current timestamp in milliseconds
@@ -91,6 +191,14 @@ object PolliNetFFI {
      */
     external fun metrics(handle: Long): String
 
+    /**
+     * Register [listener] for engine events, or clear it with null.
+     * Events are ProtocolEvent JSON (`type`: TransactionComplete | Ack | TextMessage | Error | RequestComplete)
+     * delivered on the thread that produced them (e.g. the [pushInbound] caller).
+     * @return JSON FfiResult<SuccessResponse>
+     */
+    external fun setEventListener(handle: Long, listener: PolliNetEventListener?): String
+
     /**
      * Clear a transaction from reassembly buffers
      */
@@ -108,6 +216,24 @@ object PolliNetFFI {
      */
     external fun fragment(handle: Long, txBytes: ByteArray, maxPayload: Long = 0): String
 
+    /**
+     * Fragment a transaction sealed for one gateway: relays carry only
+     * ciphertext, so they learn neither the sender nor the amount
+     * @param gatewayKey Gateway's base58 device key (see getDeviceIdentity)
+     * @param maxPayload Optional maximum payload size (MTU - 10). Pass 0 to use default
+     * @return JSON FfiResult with FragmentList
+     */
+    external fun fragmentSealed(handle: Long, txBytes: ByteArray, gatewayKey: String, maxPayload: Long = 0): String
+
+    /**
+     * Fragment several transactions for the same peer as one batched stream,
+     * sent back to back in a single session
+     * @param transactionsJson JSON array of base64-encoded transactions
+     * @param maxPayload Optional maximum payload size (MTU - 10). Pass 0 to use default
+     * @return JSON FfiResult with FragmentList
+     */
+    external fun fragmentBatch(handle: Long, transactionsJson: String, maxPayload: Long = 0): String
+
     // =========================================================================
     // BLE Mesh Operations
     // =========================================================================
@@ -159,6 +285,14 @@ object PolliNetFFI {
      * @return JSON FfiResult with QueueSizeResponse (queueSize: int)
      */
     external fun getReceivedQueueSize(handle: Long): String
+
+    /**
+     * Sender, recipient, amount and expiry of the transactions waiting for
+     * auto-submission, read from each transaction's message
+     * @param handle SDK handle
+     * @return JSON FfiResult with an array of ReceivedTxMetadata
+     */
+    external fun getReceivedTransactionMetadata(handle: Long): String
     
     /**
      * Get fragment reassembly info for all incomplete transactions
@@ -218,6 +352,14 @@ object PolliNetFFI {
      */
     external fun popOutboundTransaction(handle: Long): String
     
+    /**
+     * Pop as many outbound transactions as fit in one transmission window
+     * @param handle SDK handle
+     * @param maxBytes Window size in bytes (serialized fragments)
+     * @return JSON FfiResult<List<OutboundTransactionFFI>>
+     */
+    external fun popOutboundBatch(handle: Long, maxBytes: Int): String
+    
     /**
      * Get outbound queue size
      * @param handle SDK handle
@@ -246,6 +388,49 @@ object PolliNetFFI {
      * @return JSON FfiResult<QueueSizeResponse>
      */
     external fun getRetryQueueSize(handle: Long): String
+
+    /**
+     * Metrics for all queues: outbound size by priority, retry, confirmation and
+     * dead-letter sizes, and expired/evicted/throttled totals.
+     * @return JSON FfiResult<QueueMetricsFFI>
+     */
+    external fun getQueueMetrics(handle: Long): String
+
+    /**
+     * Compression totals for transactions accepted for relay.
+     * @return JSON FfiResult<{ payloads, bytesIn, bytesOut, ratio,
+     *   byAlgorithm: [{ algorithm: "none" | "lz4" | "zstd", payloads, bytesIn, bytesOut }] }>
+     */
+    external fun getCompressionStats(handle: Long): String
+
+    /**
+     * Queue health for backlog warnings.
+     * @return JSON FfiResult<{ status: "HEALTHY" | "WARNING" | "CRITICAL", issues: [String] }>
+     */
+    external fun getQueueHealth(handle: Long): String
+    
+    /**
+     * List transactions that exhausted or outlived their retries
+     * @param handle SDK handle
+     * @return JSON FfiResult<List<DeadLetterFFI>>
+     */
+    external fun getDeadLetters(handle: Long): String
+    
+    /**
+     * Move a dead-lettered transaction back into the retry queue (attempts reset)
+     * @param handle SDK handle
+     * @param txId Transaction ID (hex SHA-256)
+     * @return JSON FfiResult<{requeued: Boolean}>
+     */
+    external fun requeueDeadLetter(handle: Long, txId: String): String
+    
+    /**
+     * Export a diagnostic snapshot of all queues (for support / field debugging)
+     * @param handle SDK handle
+     * @param includePayloads Include base64 transaction bytes (redacted when false)
+     * @return JSON FfiResult<QueueSnapshot>
+     */
+    external fun exportQueueSnapshot(handle: Long, includePayloads: Boolean): String
     
     /**
      * Queue confirmation for relay
@@ -296,6 +481,297 @@ object PolliNetFFI {
      */
     external fun purgeStaleOutbound(handle: Long, maxAgeSecs: Long): String
 
+    /**
+     * Persist a text or control message exchanged with a peer (requires storageDirectory).
+     * @param messageJson JSON {peerId, direction: "inbound"|"outbound", kind: "text"|"control", body}
+     * @return JSON FfiResult with success status
+     */
+    external fun recordMeshMessage(handle: Long, messageJson: String): String
+
+    /**
+     * Persisted message history with one peer, oldest first (survives restarts)
+     * @param limit Maximum number of most recent messages to return
+     * @return JSON FfiResult with [{messageId?, peerId, direction, kind, body,
+     *   timestamp, status?}]: status is the delivery status of sent messages, and
+     *   "READ" for received messages marked read
+     */
+    external fun getConversation(handle: Long, peerId: String, limit: Int): String
+
+    /**
+     * Received-transaction history, newest first (survives restarts)
+     * @param queryJson JSON TxHistoryQuery: optional `status` ("received", "submitted",
+     *   "confirmed", "failed"), `peerId`, `offset` and `limit` (default 50); "" for defaults
+     * @return JSON FfiResult<TxHistoryPage> (`records` plus the filtered `total`)
+     */
+    external fun getTransactionHistory(handle: Long, queryJson: String): String
+
+    /**
+     * Mesh activity over a time window (survives restarts)
+     * @param windowSecs Window to sum, e.g. 86400 for the last day
+     * @return JSON FfiResult<MeshStatsSummary> (transactions originated / relayed /
+     *   submitted / delivered, submitFailures, uniquePeers, bytesSent, bytesReceived,
+     *   avgHopsToGateway, submitSuccessRate, deliveryRate)
+     */
+    external fun getMeshStats(handle: Long, windowSecs: Long): String
+
+    /**
+     * Start the auto-submit gateway: transactions received over the mesh are
+     * submitted to [rpcUrl] in the background (retried with backoff on failure)
+     * and success confirmations are relayed back through the mesh. Unless a
+     * connectivity probe is already running, the first URL is probed for connectivity.
+     * @param rpcUrl One RPC URL, or several separated by commas: they are ranked by
+     *   latency and failures, and a failing endpoint fails over to the next
+     * @return JSON FfiResult
+     */
+    external fun startGateway(handle: Long, rpcUrl: String): String
+
+    /**
+     * Like [startGateway], also paying fees for sponsored transactions: those
+     * built with the sponsor's pubkey as fee payer and the fee-payer signature
+     * left empty get it signed before submission. Transactions whose
+     * instructions use the sponsor account are refused.
+     * @param sponsorJson `{"type":"keypair","secretKey":<base58>}` or
+     *   `{"type":"http","url":...,"pubkey":<base58>}` (sponsorship API)
+     * @return JSON FfiResult
+     */
+    external fun startSponsoredGateway(handle: Long, rpcUrl: String, sponsorJson: String): String
+
+    /**
+     * Stop the auto-submit gateway
+     * @return JSON FfiResult<Boolean> (false if no gateway was running)
+     */
+    external fun stopGateway(handle: Long): String
+
+    /**
+     * Adapter, peer and queue status
+     * @return JSON FfiResult<BleStatus>: `adapter` (`state`, `role`, `capabilityFlags`,
+     *   `online`), `nearbyPeers`, `connectedPeers`, `outboundFrames`,
+     *   `pendingReassemblies`, `receivedTransactions`
+     */
+    external fun getBleStatus(handle: Long): String
+
+    /**
+     * Report the Bluetooth adapter state: "on", "off", "unauthorized" or "unavailable"
+     * @return JSON FfiResult
+     */
+    external fun reportAdapterState(handle: Long, state: String): String
+
+    /**
+     * Change the device role: "originator" (own transactions first), "relay"
+     * (never submits, keeps relayed transactions longer) or "gateway" (submits
+     * what it receives); "" clears it. Switching to relay stops the gateway.
+     * @return JSON FfiResult
+     */
+    external fun setDeviceRole(handle: Long, role: String): String
+
+    /**
+     * Capability byte for the BLE advertisement: bit 0 originator, bit 1 relay,
+     * bit 2 gateway, bit 3 online, bit 4 compact fragment frames
+     * @return JSON FfiResult<Int>
+     */
+    external fun getCapabilityFlags(handle: Long): String
+
+    /**
+     * Report OS connectivity changes (e.g. from ConnectivityManager callbacks).
+     * The gateway idles while offline.
+     * @return JSON FfiResult<Boolean> (true if the state changed)
+     */
+    external fun reportConnectivity(handle: Long, online: Boolean): String
+
+    /**
+     * Probe connectivity periodically, replacing any running probe
+     * @param configJson JSON ConnectivityConfig: `probe` is `{"type":"os"}` (host
+     *   reports only), `{"type":"rpcHealth","url":...}` or
+     *   `{"type":"http","url":...,"expectStatus":204}`; optional `intervalSecs` (15)
+     *   and `timeoutSecs` (5)
+     * @return JSON FfiResult
+     */
+    external fun startConnectivityProbe(handle: Long, configJson: String): String
+
+    /**
+     * Current connectivity state
+     * @return JSON FfiResult<Boolean>
+     */
+    external fun isOnline(handle: Long): String
+
+    /**
+     * Limit how many transactions per minute one origin may push through this
+     * relay; transactions over the limit are dropped and counted
+     * @param configJson JSON RelayPolicyConfig: `perFeePayerPerMinute` (20) and
+     *   `perDevicePerMinute` (60), null for unlimited; "" disables the policy
+     * @return JSON FfiResult
+     */
+    external fun setRelayPolicy(handle: Long, configJson: String): String
+
+    /**
+     * Relay policy counters
+     * @return JSON FfiResult with {accepted, droppedFeePayer, droppedDevice,
+     *   trackedOrigins, topOffenders: [{origin: {kind, id}, dropped}]}
+     */
+    external fun getRelayPolicyStats(handle: Long): String
+
+    /**
+     * Key this device signs relay receipts with
+     * @return JSON FfiResult with the base58 Ed25519 public key
+     */
+    external fun getDevicePublicKey(handle: Long): String
+
+    /**
+     * Stable device identity, persisted once secure storage is configured
+     * @return JSON FfiResult with {publicKey, shortId, persisted}; shortId is
+     *         the hex id to put in BLE advertisements
+     */
+    external fun getDeviceIdentity(handle: Long): String
+
+    /**
+     * Manufacturer data to put in the BLE advertisement
+     * @return JSON FfiResult with {manufacturerId, manufacturerData (base64),
+     *         advertisedId, private, rotatesAt}; in privacy mode restart
+     *         advertising at rotatesAt (unix seconds)
+     */
+    external fun getAdvertisement(handle: Long): String
+
+    /**
+     * Privacy mode: advertise a rotating pseudonym instead of the stable short id
+     * @param rotationSecs Seconds between rotations, or 0 for the default (15 min)
+     * @return JSON FfiResult with true
+     */
+    external fun setPrivacyMode(handle: Long, enabled: Boolean, rotationSecs: Long): String
+
+    /**
+     * Pop the next signed relay receipt to send back toward a transaction's
+     * origin. Send `frame` to `peerId` as a RELAY_RECEIPT (0x0C) control frame.
+     * @return JSON FfiResult with {peerId, frame (base64)}, data null when none
+     */
+    external fun nextRelayReceipt(handle: Long): String
+
+    /**
+     * Hand a relay receipt frame received from a peer to the engine. Verified
+     * receipts are stored and passed on toward the origin.
+     * @return JSON FfiResult with {accepted}
+     */
+    external fun ingestRelayReceipt(handle: Long, data: ByteArray): String
+
+    /**
+     * Stored relay receipts (requires a storage directory), newest first
+     * @return JSON FfiResult with [{txIdHash, action, timestamp, relayer,
+     *   signature, issued}]
+     */
+    external fun getRelayReceipts(handle: Long, offset: Long, limit: Long): String
+
+    /**
+     * Watch a transaction until it is confirmed or fails. Status changes arrive
+     * as `TxStatus` events (message holds the watch JSON), whether they come
+     * from this device's gateway submission or from a mesh confirmation.
+     * @param id Mesh transaction id (64 hex chars) or base58 signature
+     * @return JSON FfiResult with {watchId, txId, signature, status, source, error}
+     */
+    external fun watchTransaction(handle: Long, id: String): String
+
+    /**
+     * Stop watching a transaction
+     * @return JSON FfiResult with {removed}
+     */
+    external fun unwatchTransaction(handle: Long, watchId: Long): String
+
+    /**
+     * Active watches, oldest first
+     * @return JSON FfiResult with [{watchId, txId, signature, status, source, error}]
+     */
+    external fun getWatchedTransactions(handle: Long): String
+
+    /**
+     * Add a contact to the address book (requires a storage directory), or
+     * replace the one with the same label (labels ignore case)
+     * @param contactJson {label, pubkey, mints?: [mint]}
+     * @return JSON FfiResult with the saved {label, pubkey, mints, updatedAt}
+     */
+    external fun saveContact(handle: Long, contactJson: String): String
+
+    /**
+     * Remove a contact by label
+     * @return JSON FfiResult with {removed}
+     */
+    external fun removeContact(handle: Long, label: String): String
+
+    /**
+     * Address book sorted by label
+     * @return JSON FfiResult with [{label, pubkey, mints, updatedAt}]
+     */
+    external fun getContacts(handle: Long): String
+
+    /**
+     * Queue a text message for a peer. Send its frame via [nextTextFrame].
+     * @param body Message text (at most ~310 UTF-8 bytes, one BLE frame)
+     * @return JSON FfiResult with {messageId, peerId, body, createdAt, status}
+     */
+    external fun sendTextMessage(handle: Long, peerId: String, body: String): String
+
+    /**
+     * Hand a text frame (0x0D signed envelope) received from [peerId] to the
+     * engine; tampered or replayed envelopes are rejected. New messages raise a
+     * TextMessage event and queue an ack frame; acks and read receipts mark our
+     * messages DELIVERED / READ and raise MessageStatus events.
+     * @return JSON FfiResult with {kind: "message"|"delivered"|"read"|"duplicate",
+     *   messageId?, messageIds? (read)}
+     */
+    external fun ingestTextFrame(handle: Long, peerId: String, data: ByteArray): String
+
+    /**
+     * Next text or ack frame to write, sealed in a signed 0x0D envelope, or
+     * null when none is pending or fits [maxLen]
+     */
+    external fun nextTextFrame(handle: Long, maxLen: Long): ByteArray?
+
+    /**
+     * Pop the oldest received text message not yet retrieved
+     * @return JSON FfiResult with {messageId, peerId, body, receivedAt} or null data
+     */
+    external fun nextTextMessage(handle: Long): String
+
+    /**
+     * Delivery status of a sent text message
+     * @return JSON FfiResult with {messageId, peerId, body, createdAt, attempts,
+     *   status: "QUEUED"|"SENT"|"DELIVERED"|"READ"|"FAILED"}. Unacknowledged
+     *   messages are resent from [tick] with backoff and FAILED after 5 sends.
+     */
+    external fun getTextMessageStatus(handle: Long, messageId: String): String
+
+    /**
+     * Queue read receipts (sent via [nextTextFrame]) for messages received from [peerId]
+     * @param messageIdsJson JSON array of message ids
+     * @return JSON FfiResult
+     */
+    external fun markMessagesRead(handle: Long, peerId: String, messageIdsJson: String): String
+
+    /**
+     * Disk usage of the storage directory per category, against the configured budget
+     * (SdkConfig.storageBudgetBytes). Errors when no budget is configured.
+     * @return JSON FfiResult<StorageUsage>
+     */
+    external fun getStorageUsage(handle: Long): String
+
+    /**
+     * Delete the oldest fragment caches / logs / archives now if over budget
+     * (also runs automatically from [tick] about once a minute).
+     * @return JSON FfiResult<CleanupReport>
+     */
+    external fun enforceStorageQuota(handle: Long): String
+
+    /**
+     * Write an encrypted archive of all SDK state (queues, caches, message history,
+     * secure storage) to [path], for moving to a replacement device.
+     * @return JSON FfiResult<BackupSummary>
+     */
+    external fun backupState(handle: Long, path: String, passphrase: String): String
+
+    /**
+     * Replace all SDK state with the archive at [path] created by [backupState].
+     * Secure values are re-encrypted with this device's key.
+     * @return JSON FfiResult<BackupSummary>
+     */
+    external fun restoreState(handle: Long, path: String, passphrase: String): String
+
     /**
      * Confirm delivery of [txId] to the current peer. Decrements relevance; returns
      * JSON FfiResult with { removed: Boolean } — true means evicted (relevance = 0).
@@ -352,6 +828,27 @@ object PolliNetFFI {
      */
     external fun getHealthSnapshot(handle: Long): String
 
+    /**
+     * Live mesh view for UIs: peers seen in scans (with cooldown state), connected
+     * peers, per-peer link stats, topology and overall health metrics.
+     * @return JSON FfiResult<MeshStateFFI>
+     */
+    external fun getMeshState(handle: Long): String
+
+    /**
+     * Link statistics (latency, RSSI, packet loss, quality score) for one peer.
+     * @return JSON FfiResult<PeerHealth?> (data is null for unknown peers)
+     */
+    external fun getPeerHealth(handle: Long, peerId: String): String
+
+    /**
+     * Report the connection graph so topology and hop counts stay current.
+     * @param requestJson JSON UpdateTopologyRequest
+     *   `{ "directConnections": [..], "connections": { peerId: [..] } }`
+     * @return JSON FfiResult<SuccessResponse>
+     */
+    external fun updateMeshTopology(handle: Long, requestJson: ByteArray): String
+
     /**
      * Record a heartbeat for a peer (marks it as alive / connected).
      * Call this whenever a BLE connection is established or a fragment is received.
@@ -428,6 +925,30 @@ object PolliNetFFI {
      */
     external fun submitIntent(handle: Long, requestJson: ByteArray): String
 
+    // =========================================================================
+    // Background requests (non-blocking variants)
+    // =========================================================================
+    //
+    // Each *Async call returns JSON FfiResult<{ requestId: Long }> at once and runs
+    // the work on a background thread. The outcome is delivered to the handle's
+    // [PolliNetEventListener] as a RequestComplete event (`requestId`, `message` =
+    // the call's FfiResult JSON) and can also be collected with [pollRequest].
+
+    /** Non-blocking [submitIntent] */
+    external fun submitIntentAsync(handle: Long, requestJson: ByteArray): String
+
+    /** Non-blocking [saveQueues] */
+    external fun saveQueuesAsync(handle: Long): String
+
+    /** Non-blocking [backupState] */
+    external fun backupStateAsync(handle: Long, path: String, passphrase: String): String
+
+    /**
+     * Check on a background request. A finished request is forgotten once returned.
+     * @return JSON FfiResult with { status: "PENDING" } or { status: "DONE", result: FfiResult }
+     */
+    external fun pollRequest(requestId: Long): String
+
     /**
      * Builds a single unsigned transaction with one `revoke` instruction per token account,
      * clearing the executor PDA's delegate authority.
@@ -436,6 +957,31 @@ object PolliNetFFI {
      */
     external fun createRevokeTransaction(requestJson: ByteArray): String
 
+    /**
+     * Builds a single unsigned transaction from arbitrary program instructions
+     * (e.g. governance votes) for the same offline sign-and-relay path.
+     * @param requestJson JSON-encoded CreateCustomTransactionRequest:
+     *   `{ fee_payer, recent_blockhash, instructions: [{ program_id, accounts: [{ pubkey, is_signer, is_writable }], data (base64) }] }`
+     * @return JSON FfiResult<CustomTransactionResponse> (`transaction`, `required_signers`)
+     */
+    external fun createCustomTransaction(requestJson: ByteArray): String
+
+    /**
+     * Parses a Solana Pay transfer request URL (`solana:<recipient>?amount=...`).
+     * Transaction request URLs (`solana:https://...`) need the network and are rejected.
+     * @return JSON FfiResult<SolanaPayRequest>
+     */
+    external fun parseSolanaPayUrl(url: String): String
+
+    /**
+     * Builds the unsigned durable-nonce transaction paying a Solana Pay transfer request.
+     * @param requestJson JSON-encoded CreateSolanaPayTransactionRequest:
+     *   `{ url, payer, nonce_account: NonceAccountInfo, decimals?, amount?, staleness? }`
+     * @return JSON FfiResult<SolanaPayTransactionResponse>; ERR_STALE_NONCE when `staleness`
+     *   refuses the nonce account
+     */
+    external fun createSolanaPayTransaction(requestJson: ByteArray): String
+
     /**
      * Returns the pollicore base URL baked in at compile time from POLLICORE_URL env var.
      * Returns an empty string if POLLICORE_URL was not set when the native library was built.
@@ -520,11 +1066,12 @@ object PolliNetFFI {
 
     /**
      * Ingest a received or locally-generated Pollicore confirmation.
-     * Verifies signature, purges matching carrier entry, creates tombstone,
-     * and re-queues the confirmation at HIGH priority for further propagation.
+     * Verifies signature, removes the confirmed transaction from the outbound,
+     * retry and dead-letter queues, creates tombstone, and re-queues the
+     * confirmation at HIGH priority for further propagation (once, within the hop limit).
      * Silently drops tampered confirmations.
      * @param confirmationBytes bincode-serialized MeshConfirmation
-     * @return JSON FfiResult<{ purged: Boolean, added_to_carrier: Boolean }>
+     * @return JSON FfiResult<{ purged: Boolean, retriesRemoved: Int, duplicate: Boolean, added_to_carrier: Boolean }>
      */
     external fun ingestConfirmation(handle: Long, confirmationBytes: ByteArray): String
 
@@ -535,6 +1082,140 @@ object PolliNetFFI {
      */
     external fun isTombstoned(handle: Long, txIdHashHex: String): String
 
+    /**
+     * Cancel a transaction's fragment group (hex tx id): drop its buffered
+     * and queued fragments and queue a TX_CANCEL (0x0F) frame for peers,
+     * signed with the device key. Peers only honor it for transactions this
+     * device originated.
+     * @param reason "confirmed", "superseded" or "expired"
+     * @return JSON FfiResult<{ dropped: Int }>
+     */
+    external fun cancelTransaction(handle: Long, txId: String, reason: String): String
+
+    /**
+     * Hand a received TX_CANCEL (0x0F) frame to the engine. Cancels not
+     * signed by the transaction's origin are rejected with an error. It is
+     * passed on to other peers only if it dropped something here.
+     * @return JSON FfiResult<{ dropped: Boolean }>
+     */
+    external fun ingestTxCancel(handle: Long, data: ByteArray): String
+
+    /**
+     * Hand a received FRAGMENT_NACK (0x10) frame to the engine, which queues
+     * the dropped fragment (or whole transaction) again if it still has it.
+     * @return JSON FfiResult<{ resent: Int }>
+     */
+    external fun ingestFragmentNack(handle: Long, data: ByteArray): String
+
+    /**
+     * Sign and flood an emergency notice (at most 100 bytes of text). It is
+     * sent before anything else queued.
+     * @return JSON FfiResult<{ id: String }>
+     */
+    external fun broadcastEmergency(handle: Long, message: String): String
+
+    /**
+     * Hand a received EMERGENCY_BROADCAST (0x11) frame to the engine, which
+     * relays new notices on its own.
+     * @return JSON FfiResult<EmergencyNotice?> (null for duplicates and stale notices)
+     */
+    external fun ingestEmergency(handle: Long, data: ByteArray): String
+
+    /**
+     * Emergency notices received so far, oldest first.
+     * @return JSON FfiResult<List<EmergencyNotice>>
+     */
+    external fun getEmergencyNotices(handle: Long): String
+
+    /**
+     * Sign an offline payment request with the device key (merchant side)
+     * and queue it for the payer in range.
+     * @param requestJson UTF-8 JSON CreatePaymentRequestRequest
+     * @return JSON FfiResult<PaymentRequestInfo>
+     */
+    external fun createPaymentRequest(handle: Long, requestJson: ByteArray): String
+
+    /**
+     * Hand a received PAYMENT_REQUEST (0x12) frame to the engine (payer side).
+     * @return JSON FfiResult<PaymentRequestInfo>
+     */
+    external fun ingestPaymentRequest(handle: Long, data: ByteArray): String
+
+    /**
+     * Payment requests received and still payable.
+     * @return JSON FfiResult<List<PaymentRequestInfo>>
+     */
+    external fun getPaymentRequests(handle: Long): String
+
+    /**
+     * Build the unsigned durable-nonce transaction paying a received request.
+     * @param nonceAccountJson JSON NonceAccountInfo of the payer's nonce account
+     * @return JSON FfiResult<PaymentTransactionResponse>; ERR_STALE_NONCE when the config's
+     *   `nonceStaleness` refuses the nonce account
+     */
+    external fun buildPaymentTransaction(
+        handle: Long,
+        requestId: String,
+        payer: String,
+        nonceAccountJson: String
+    ): String
+
+    /**
+     * Sign a receipt for a payment the wallet just signed (payer side) and
+     * queue it for the merchant.
+     * @param signedTx Bincode-serialized signed transaction
+     * @param requestId Hex id of the payment request it pays, or "" for none
+     * @return JSON FfiResult<PaymentReceiptRecord>
+     */
+    external fun createPaymentReceipt(handle: Long, signedTx: ByteArray, requestId: String): String
+
+    /**
+     * Hand a received PAYMENT_RECEIPT (0x13) frame to the engine: a payer's
+     * receipt (countersigned automatically if it pays one of our requests) or
+     * a merchant's countersignature.
+     * @return JSON FfiResult<PaymentReceiptRecord>
+     */
+    external fun ingestPaymentReceipt(handle: Long, data: ByteArray): String
+
+    /**
+     * Countersign a received receipt (merchant side) and queue the
+     * countersignature for the payer.
+     * @return JSON FfiResult<PaymentReceiptRecord>
+     */
+    external fun countersignPaymentReceipt(handle: Long, txSignature: String): String
+
+    /**
+     * Stored payment receipts (requires a storage directory), newest first
+     * @return JSON FfiResult<List<PaymentReceiptRecord>>
+     */
+    external fun getPaymentReceipts(handle: Long, offset: Long, limit: Long): String
+
+    /**
+     * Ping a neighbour; the first device hearing the ping answers it.
+     * @param peerId Address of the peer, echoed in the result
+     * @return JSON FfiResult<{ probeId: String }>
+     */
+    external fun pingPeer(handle: Long, peerId: String): String
+
+    /**
+     * Trace the mesh path to a device; every relay records its node id and clock.
+     * @param deviceKey Base58 device key of the target
+     * @return JSON FfiResult<{ probeId: String }>
+     */
+    external fun traceroute(handle: Long, deviceKey: String): String
+
+    /**
+     * Hand a received ping / pong (0x01 / 0x02) frame to the engine.
+     * @return JSON FfiResult<ProbeResult?>, set when it answers one of our probes
+     */
+    external fun ingestProbeFrame(handle: Long, peerId: String, data: ByteArray): String
+
+    /**
+     * Result of an answered ping or traceroute
+     * @return JSON FfiResult<ProbeResult?> (probeId, kind, peerId, rttMs, hops[{ node, latencyMs }])
+     */
+    external fun getProbeResult(handle: Long, probeId: String): String
+
     /**
      * Periodic maintenance: evict expired tombstones and cooldowns.
      * Call from the 10-second adaptive params recomputation loop.
@@ -549,3 +1230,9 @@ object PolliNetFFI {
     external fun getTombstoneCount(handle: Long): String
 }
 
+/**
+ * Receives engine events pushed from the Rust core (see [PolliNetFFI.setEventListener]).
+ */
+fun interface PolliNetEventListener {
+    fun onEvent(eventJson: String)
+}
@@ -153,6 +153,30 @@ class PolliNetSDK private constructor(
         }
     }
 
+    /**
+     * Queue inbound data without blocking; processed on the next tick.
+     * Not a suspend function so BLE callbacks can call it directly.
+     * @return False if the queue was full and the frame dropped (sender NACKed)
+     */
+    fun enqueueInbound(data: ByteArray, peerId: String = ""): Result<Boolean> =
+        try {
+            parseResult<Boolean>(PolliNetFFI.enqueueInbound(handle, peerId, data))
+        } catch (e: Exception) {
+            Result.failure(e)
+        }
+
+    /**
+     * Process up to [max] queued inbound frames
+     * @return Number processed
+     */
+    suspend fun processInbound(max: Int = Int.MAX_VALUE): Result<Int> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<Int>(PolliNetFFI.processInbound(handle, max))
+        } catch (e: Exception) {
+            Result.failure(e)
+        }
+    }
+
     /**
      * Get next outbound frame to send
      */
@@ -160,6 +184,99 @@ class PolliNetSDK private constructor(
         PolliNetFFI.nextOutbound(handle, maxLen.toLong())
     }
 
+    /**
+     * Next outbound frame for one peer, skipping transactions its digest says it already holds
+     */
+    suspend fun nextOutboundForPeer(peerId: String, maxLen: Int = 1024): ByteArray? = withContext(Dispatchers.IO) {
+        PolliNetFFI.nextOutboundForPeer(handle, peerId, maxLen.toLong())
+    }
+
+    /**
+     * TX_DIGEST frame listing the transactions this device holds; send it right after connecting
+     */
+    suspend fun getTxDigest(): Result<ByteArray> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<EncodedFrame>(PolliNetFFI.getTxDigest(handle)).map {
+                android.util.Base64.decode(it.frame, android.util.Base64.NO_WRAP)
+            }
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Ingest a peer's TX_DIGEST frame
+     */
+    suspend fun ingestTxDigest(peerId: String, frame: ByteArray): Result<DigestSummary> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<DigestSummary>(PolliNetFFI.ingestTxDigest(handle, peerId, frame))
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Forget a peer's digest (it may have dropped transactions since)
+     */
+    suspend fun clearTxDigest(peerId: String): Result<Boolean> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<Boolean>(PolliNetFFI.clearTxDigest(handle, peerId))
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Record the capability byte a peer advertises
+     */
+    suspend fun setPeerCapabilities(peerId: String, capabilities: Int): Result<Boolean> =
+        withContext(Dispatchers.IO) {
+            try {
+                parseResult<Boolean>(PolliNetFFI.setPeerCapabilities(handle, peerId, capabilities))
+            } catch (e: Exception) { Result.failure(e) }
+        }
+
+    /**
+     * Pin a peer's base58 device key under its short id
+     */
+    suspend fun pinPeerKey(deviceKey: String): Result<Boolean> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<Boolean>(PolliNetFFI.pinPeerKey(handle, deviceKey))
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Next RELAY_RECEIPT frame to send back toward a transaction's origin, or null
+     */
+    suspend fun nextRelayReceipt(): Result<ByteArray?> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<EncodedFrame?>(PolliNetFFI.nextRelayReceipt(handle)).map { receipt ->
+                receipt?.let { android.util.Base64.decode(it.frame, android.util.Base64.NO_WRAP) }
+            }
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Ingest a received RELAY_RECEIPT frame.
+     * @return false for duplicates and receipts that fail verification
+     */
+    suspend fun ingestRelayReceipt(frame: ByteArray): Result<Boolean> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<ReceiptAccepted>(PolliNetFFI.ingestRelayReceipt(handle, frame)).map { it.accepted }
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Next text or ack frame (a 0x0D envelope) to write, or null
+     */
+    suspend fun nextTextFrame(maxLen: Int): ByteArray? = withContext(Dispatchers.IO) {
+        PolliNetFFI.nextTextFrame(handle, maxLen.toLong())
+    }
+
+    /**
+     * Ingest a received 0x0D envelope carrying a text message, ack or read receipt
+     */
+    suspend fun ingestTextFrame(peerId: String, frame: ByteArray): Result<TextFrameResult> =
+        withContext(Dispatchers.IO) {
+            try {
+                parseResult<TextFrameResult>(PolliNetFFI.ingestTextFrame(handle, peerId, frame))
+            } catch (e: Exception) { Result.failure(e) }
+        }
+
     /**
      * Periodic tick for protocol state machine
      */
@@ -216,6 +333,23 @@ class PolliNetSDK private constructor(
         }
     }
 
+    /**
+     * Fragment a transaction sealed for the gateway with [gatewayKey], hiding
+     * its signers and contents from relays on the way
+     */
+    suspend fun fragmentSealed(
+        txBytes: ByteArray,
+        gatewayKey: String,
+        maxPayload: Int? = null
+    ): Result<FragmentList> = withContext(Dispatchers.IO) {
+        try {
+            val maxPayloadLong = maxPayload?.toLong() ?: 0L
+            parseResult<FragmentList>(PolliNetFFI.fragmentSealed(handle, txBytes, gatewayKey, maxPayloadLong))
+        } catch (e: Exception) {
+            Result.failure(e)
+        }
+    }
+
     // =========================================================================
     // BLE Mesh Operations
     // =========================================================================
@@ -753,6 +887,233 @@ class PolliNetSDK private constructor(
         } catch (e: Exception) { Result.failure(e) }
     }
 
+    /**
+     * Cancel a transaction's fragment group so peers stop relaying it.
+     * @param reason "confirmed", "superseded" or "expired"
+     * @return Number of fragments and frames dropped locally
+     */
+    suspend fun cancelTransaction(txId: String, reason: String): Result<Int> = withContext(Dispatchers.IO) {
+        try {
+            @Serializable data class CancelResponse(val dropped: Int)
+            parseResult<CancelResponse>(PolliNetFFI.cancelTransaction(handle, txId, reason)).map { it.dropped }
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Ingest a received TX_CANCEL frame.
+     * @return True if it dropped buffered or queued fragments
+     */
+    suspend fun ingestTxCancel(frame: ByteArray): Result<Boolean> = withContext(Dispatchers.IO) {
+        try {
+            @Serializable data class IngestResponse(val dropped: Boolean)
+            parseResult<IngestResponse>(PolliNetFFI.ingestTxCancel(handle, frame)).map { it.dropped }
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Ingest a received FRAGMENT_NACK frame.
+     * @return Number of fragments queued again
+     */
+    suspend fun ingestFragmentNack(frame: ByteArray): Result<Int> = withContext(Dispatchers.IO) {
+        try {
+            @Serializable data class IngestResponse(val resent: Int)
+            parseResult<IngestResponse>(PolliNetFFI.ingestFragmentNack(handle, frame)).map { it.resent }
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Sign and flood an emergency notice (at most 100 bytes of text).
+     * @return Notice id
+     */
+    suspend fun broadcastEmergency(message: String): Result<String> = withContext(Dispatchers.IO) {
+        try {
+            @Serializable data class BroadcastResponse(val id: String)
+            parseResult<BroadcastResponse>(PolliNetFFI.broadcastEmergency(handle, message)).map { it.id }
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Ingest a received EMERGENCY_BROADCAST frame.
+     * @return The notice if it is new, null for duplicates and stale notices
+     */
+    suspend fun ingestEmergency(frame: ByteArray): Result<EmergencyNotice?> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<EmergencyNotice?>(PolliNetFFI.ingestEmergency(handle, frame))
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Emergency notices received so far, oldest first.
+     */
+    suspend fun getEmergencyNotices(): Result<List<EmergencyNotice>> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<List<EmergencyNotice>>(PolliNetFFI.getEmergencyNotices(handle))
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Issue a signed payment request (merchant side). It is beamed to the payer in range.
+     * @param amount Lamports, or the token's smallest unit when [mint] is set
+     * @param mint SPL Token mint; null for SOL
+     */
+    suspend fun createPaymentRequest(
+        recipient: String,
+        amount: Long,
+        mint: String? = null,
+        decimals: Int = 0,
+        memo: String? = null,
+        expiresInSecs: Long = 300,
+    ): Result<PaymentRequestInfo> = withContext(Dispatchers.IO) {
+        try {
+            val req = CreatePaymentRequestRequest(recipient, amount, mint, decimals, memo, expiresInSecs)
+            parseResult<PaymentRequestInfo>(
+                PolliNetFFI.createPaymentRequest(handle, json.encodeToString(req).toByteArray())
+            )
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Ingest a received PAYMENT_REQUEST frame (payer side).
+     */
+    suspend fun ingestPaymentRequest(frame: ByteArray): Result<PaymentRequestInfo> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<PaymentRequestInfo>(PolliNetFFI.ingestPaymentRequest(handle, frame))
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Payment requests received and still payable.
+     */
+    suspend fun getPaymentRequests(): Result<List<PaymentRequestInfo>> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<List<PaymentRequestInfo>>(PolliNetFFI.getPaymentRequests(handle))
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Build the unsigned durable-nonce transaction paying a received request. Sign it with
+     * [payer] and send it back to the merchant with [fragment].
+     */
+    suspend fun buildPaymentTransaction(
+        requestId: String,
+        payer: String,
+        nonceAccount: NonceAccountInfo,
+    ): Result<PaymentTransactionResponse> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<PaymentTransactionResponse>(
+                PolliNetFFI.buildPaymentTransaction(handle, requestId, payer, json.encodeToString(nonceAccount))
+            )
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Sign a receipt for a payment the wallet just signed and send it to the merchant, who
+     * countersigns it. Pass the [requestId] when paying a payment request.
+     */
+    suspend fun createPaymentReceipt(
+        signedTx: ByteArray,
+        requestId: String? = null,
+    ): Result<PaymentReceiptRecord> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<PaymentReceiptRecord>(
+                PolliNetFFI.createPaymentReceipt(handle, signedTx, requestId ?: "")
+            )
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Ingest a received PAYMENT_RECEIPT frame (receipt or countersignature).
+     */
+    suspend fun ingestPaymentReceipt(frame: ByteArray): Result<PaymentReceiptRecord> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<PaymentReceiptRecord>(PolliNetFFI.ingestPaymentReceipt(handle, frame))
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Countersign a received receipt (merchant side), e.g. for a payment made without a request.
+     */
+    suspend fun countersignPaymentReceipt(txSignature: String): Result<PaymentReceiptRecord> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<PaymentReceiptRecord>(PolliNetFFI.countersignPaymentReceipt(handle, txSignature))
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Stored payment receipts, newest first.
+     */
+    suspend fun getPaymentReceipts(offset: Long = 0, limit: Long = 50): Result<List<PaymentReceiptRecord>> =
+        withContext(Dispatchers.IO) {
+            try {
+                parseResult<List<PaymentReceiptRecord>>(PolliNetFFI.getPaymentReceipts(handle, offset, limit))
+            } catch (e: Exception) { Result.failure(e) }
+        }
+
+    /**
+     * Ping a neighbour. The result arrives through [ingestProbeFrame] or [getProbeResult].
+     * @return Probe id
+     */
+    suspend fun pingPeer(peerId: String): Result<String> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<ProbeId>(PolliNetFFI.pingPeer(handle, peerId)).map { it.probeId }
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Trace the mesh path to the device with [deviceKey] (base58).
+     * @return Probe id
+     */
+    suspend fun traceroute(deviceKey: String): Result<String> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<ProbeId>(PolliNetFFI.traceroute(handle, deviceKey)).map { it.probeId }
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Ingest a received ping / pong frame.
+     * @return The probe result when the frame answers one of our probes
+     */
+    suspend fun ingestProbeFrame(peerId: String, frame: ByteArray): Result<ProbeResult?> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<ProbeResult?>(PolliNetFFI.ingestProbeFrame(handle, peerId, frame))
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    suspend fun getProbeResult(probeId: String): Result<ProbeResult?> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<ProbeResult?>(PolliNetFFI.getProbeResult(handle, probeId))
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Manufacturer data for the BLE advertisement (rotating id in privacy mode)
+     */
+    suspend fun getAdvertisement(): Result<Advertisement> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<Advertisement>(PolliNetFFI.getAdvertisement(handle))
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Privacy mode: advertise a pseudonym that changes every [rotationSecs]
+     * (0 for the default 15 minutes) instead of the stable device id
+     */
+    suspend fun setPrivacyMode(enabled: Boolean, rotationSecs: Long = 0): Result<Unit> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<Boolean>(PolliNetFFI.setPrivacyMode(handle, enabled, rotationSecs)).map { }
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
+    /**
+     * Mesh activity over the last [windowSecs] (default one day).
+     * Requires a storage directory.
+     */
+    suspend fun getMeshStats(windowSecs: Long = 86_400): Result<MeshStats> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<MeshStats>(PolliNetFFI.getMeshStats(handle, windowSecs))
+        } catch (e: Exception) { Result.failure(e) }
+    }
+
     /**
      * Returns true if [txIdHashHex] has an active tombstone.
      * Call before buffering inbound reassembly fragments.
@@ -1149,6 +1510,49 @@ class PolliNetSDK private constructor(
         }
     }
 
+    /**
+     * Parses a scanned Solana Pay transfer request URL, to show the payee, amount,
+     * label and message before paying.
+     */
+    suspend fun parseSolanaPayUrl(url: String): Result<SolanaPayRequest> = withContext(Dispatchers.IO) {
+        try {
+            parseResult<SolanaPayRequest>(PolliNetFFI.parseSolanaPayUrl(url))
+        } catch (e: Exception) {
+            Result.failure(e)
+        }
+    }
+
+    /**
+     * Builds the unsigned durable-nonce transaction paying a Solana Pay transfer request,
+     * so a QR code scanned offline produces a transaction the mesh can relay.
+     *
+     * Sign the returned transaction with [payer] (and the nonce authority), then [fragment] it.
+     *
+     * @param decimals Decimal places of the `spl-token` mint (required for token payments).
+     * @param amount   Amount in base units, used only when the URL has none.
+     * @param staleness Maximum age of [nonceAccount]; refusing fails with [PolliNetErrorCode.STALE_NONCE].
+     */
+    suspend fun createSolanaPayTransaction(
+        url: String,
+        payer: String,
+        nonceAccount: NonceAccountInfo,
+        decimals: Int? = null,
+        amount: Long? = null,
+        staleness: NonceStalenessPolicy = NonceStalenessPolicy(),
+    ): Result<SolanaPayTransactionResponse> = withContext(Dispatchers.IO) {
+        try {
+            val req = CreateSolanaPayTransactionRequest(
+                url, payer, nonceAccount, decimals, amount, staleness
+            )
+            val resultJson = PolliNetFFI.createSolanaPayTransaction(
+                json.encodeToString(req).toByteArray()
+            )
+            parseResult<SolanaPayTransactionResponse>(resultJson)
+        } catch (e: Exception) {
+            Result.failure(e)
+        }
+    }
+
     /**
      * Fetch the most recent confirmed blockhash from the configured Solana RPC.
      * Used as input to [createApproveTransaction] / [createRevokeTransaction]. Pure RPC call,
@@ -1476,12 +1880,44 @@ class PolliNetSDK private constructor(
 
 /**
  * Exception thrown by PolliNet SDK operations
+ *
+ * [code] is one of the [PolliNetErrorCode] constants; branch on it rather than on the message.
  */
 class PolliNetException(
     val code: String,
     message: String
 ) : Exception("[$code] $message")
 
+/**
+ * Error codes carried by failed FFI results (mirrors `FfiErrorCode` in the Rust core)
+ */
+object PolliNetErrorCode {
+    /** Handle was never issued, or is bound to a transport lacking the call */
+    const val INVALID_HANDLE = "ERR_INVALID_HANDLE"
+    /** Handle was valid but has been shut down */
+    const val SHUT_DOWN = "ERR_SHUT_DOWN"
+    /** Malformed argument: unreadable string/bytes, bad JSON, bad key or hex */
+    const val INVALID_INPUT = "ERR_INVALID_INPUT"
+    /** Transaction is unsigned or its signatures do not verify */
+    const val INVALID_SIGNATURE = "ERR_INVALID_SIGNATURE"
+    /** Requested item does not exist */
+    const val NOT_FOUND = "ERR_NOT_FOUND"
+    /** Feature needs configuration the node was not initialized with */
+    const val NOT_CONFIGURED = "ERR_NOT_CONFIGURED"
+    /** Network request (Solana RPC, Pollicore) failed */
+    const val RPC = "ERR_RPC"
+    /** Queue rejected the operation */
+    const val QUEUE = "ERR_QUEUE"
+    /** Reading or writing persisted state failed */
+    const val STORAGE = "ERR_STORAGE"
+    /** App requires a newer SDK or wire protocol than the bundled library */
+    const val VERSION_MISMATCH = "ERR_VERSION_MISMATCH"
+    /** Cached nonce is older than the staleness policy allows; refresh it */
+    const val STALE_NONCE = "ERR_STALE_NONCE"
+    /** Unexpected failure; see the message */
+    const val INTERNAL = "ERR_INTERNAL"
+}
+
 // =============================================================================
 // Data types
 // =============================================================================
@@ -1495,6 +1931,13 @@ data class SdkConfig(
     val storageDirectory: String? = null,
     /** AES-256-GCM encryption key for nonce bundle storage. Required when [storageDirectory] is set. */
     val encryptionKey: String? = null,
+    /**
+     * Base64-encoded 32-byte storage key protected by Android Keystore
+     * (see [StorageKeyManager.getOrCreateStorageKey]). Takes precedence over [encryptionKey].
+     */
+    val platformKey: String? = null,
+    /** Disk budget in bytes for [storageDirectory]; oldest caches/logs are cleaned up when exceeded. */
+    val storageBudgetBytes: Long? = null,
     /**
      * Base58-encoded Solana wallet address that owns this node session.
      * When provided it is stored on the Rust transport and used to attribute
@@ -1502,6 +1945,17 @@ data class SdkConfig(
      */
     @SerialName("walletAddress")
     val walletAddress: String? = null,
+    /** Oldest native library version this app supports; init fails on an older `libpollinet`. */
+    val minSdkVersion: String? = null,
+    /** Oldest mesh wire protocol version this app supports. */
+    val minProtocolVersion: Int? = null,
+    /**
+     * Part this device plays in the mesh: "originator", "relay" (never uses
+     * [rpcUrl]) or "gateway". Null keeps outbound priorities as requested.
+     */
+    val role: String? = null,
+    /** Maximum age of cached nonces passed to [PolliNetSDK.buildPaymentTransaction] */
+    val nonceStaleness: NonceStalenessPolicy = NonceStalenessPolicy(),
 )
 
 @Serializable
@@ -1538,8 +1992,150 @@ data class MetricsSnapshot(
     val fragmentsBuffered: Int,
     val transactionsComplete: Int,
     val reassemblyFailures: Int,
+    val corruptFragments: Int = 0,
+    val reassemblyEvictions: Int = 0,
+    val reassemblyTimeouts: Int = 0,
+    val inboundDropped: Int = 0,
     val lastError: String,
-    val updatedAt: Long
+    val updatedAt: Long,
+    val compressionBytesIn: Long = 0,
+    val compressionBytesOut: Long = 0,
+    val compressionRatio: Double = 0.0
+)
+
+@Serializable
+data class ProbeId(val probeId: String)
+
+/** Base64 control frame; extra fields (e.g. a relay receipt's peerId) are ignored */
+@Serializable
+internal data class EncodedFrame(val frame: String)
+
+@Serializable
+internal data class ReceiptAccepted(val accepted: Boolean)
+
+@Serializable
+data class TextFrameResult(
+    val kind: String,
+    val messageId: String? = null,
+    val messageIds: List<String> = emptyList()
+)
+
+@Serializable
+data class DigestSummary(
+    val peerCount: Int,
+    val alreadyHeld: Int,
+    val toSend: Int
+)
+
+@Serializable
+data class ProbeHop(
+    val node: String,
+    val latencyMs: Long
+)
+
+@Serializable
+data class EmergencyNotice(
+    val id: String,
+    val origin: String, // base58 device key of the sender
+    val timestamp: Long,
+    val message: String,
+    val hops: Int
+)
+
+@Serializable
+data class PaymentRequestInfo(
+    val requestId: String,
+    val merchant: String, // base58 device key of the merchant
+    val recipient: String,
+    val mint: String? = null, // null for SOL
+    val decimals: Int,
+    val amount: Long,
+    val expiresAt: Long,
+    val memo: String? = null
+)
+
+@Serializable
+data class NonceAccountInfo(
+    val address: String,
+    val authority: String,
+    val nonce: String,
+    val lamportsPerSignature: Long,
+    /** Unix seconds the account was read from the chain; null counts as stale */
+    val fetchedAt: Long? = null
+)
+
+/** Maximum age of a cached [NonceAccountInfo] */
+@Serializable
+data class NonceStalenessPolicy(
+    val maxAgeSecs: Long = 24 * 60 * 60,
+    /** "warn" (log and use it) or "refuse" (fail with [PolliNetErrorCode.STALE_NONCE]) */
+    val action: String = "warn",
+)
+
+@Serializable
+internal data class CreatePaymentRequestRequest(
+    val recipient: String,
+    val amount: Long,
+    val mint: String? = null,
+    val decimals: Int = 0,
+    val memo: String? = null,
+    @SerialName("expires_in_secs") val expiresInSecs: Long = 300,
+)
+
+@Serializable
+data class PaymentTransactionResponse(
+    @SerialName("request_id") val requestId: String,
+    /** Base64-encoded unsigned transaction; sign with the payer and nonce authority. */
+    val transaction: String,
+)
+
+@Serializable
+data class PaymentReceiptRecord(
+    val txSignature: String, // base58 transaction signature
+    val requestId: String? = null,
+    val recipient: String,
+    val amount: Long,
+    val issuedAt: Long,
+    val payer: String, // base58 device key of the payer
+    val payerSignature: String,
+    val merchant: String? = null, // set once countersigned
+    val merchantSignature: String? = null,
+    val paid: Boolean // true on the payer's side
+)
+
+@Serializable
+data class ProbeResult(
+    val probeId: String,
+    val kind: String, // "ping" or "traceroute"
+    val peerId: String,
+    val rttMs: Long,
+    val hops: List<ProbeHop>
+)
+
+@Serializable
+data class Advertisement(
+    val manufacturerId: Int,
+    val manufacturerData: String, // base64
+    val advertisedId: String,
+    val private: Boolean,
+    val rotatesAt: Long? = null
+)
+
+@Serializable
+data class MeshStats(
+    val windowSecs: Long,
+    val since: Long,
+    val transactionsOriginated: Long,
+    val transactionsRelayed: Long,
+    val transactionsSubmitted: Long,
+    val submitFailures: Long,
+    val transactionsDelivered: Long,
+    val uniquePeers: Int,
+    val bytesSent: Long,
+    val bytesReceived: Long,
+    val avgHopsToGateway: Double? = null,
+    val submitSuccessRate: Double? = null,
+    val deliveryRate: Double? = null
 )
 
 @Serializable
@@ -1904,6 +2500,41 @@ data class IntentBytesResponse(
     @SerialName("nonce_hex")    val nonceHex: String,
 )
 
+/** A parsed Solana Pay transfer request ([PolliNetSDK.parseSolanaPayUrl]). */
+@Serializable
+data class SolanaPayRequest(
+    val recipient: String,
+    /** Amount in user units as written in the URL (e.g. "1.5"); null lets the payer choose. */
+    val amount: String? = null,
+    /** SPL Token mint; null for SOL. */
+    val splToken: String? = null,
+    val references: List<String> = emptyList(),
+    val label: String? = null,
+    val message: String? = null,
+    val memo: String? = null,
+)
+
+/** Parameters for [PolliNetSDK.createSolanaPayTransaction]. */
+@Serializable
+internal data class CreateSolanaPayTransactionRequest(
+    val url: String,
+    val payer: String,
+    @SerialName("nonce_account") val nonceAccount: NonceAccountInfo,
+    val decimals: Int? = null,
+    val amount: Long? = null,
+    val staleness: NonceStalenessPolicy = NonceStalenessPolicy(),
+)
+
+/** Response from [PolliNetSDK.createSolanaPayTransaction]. */
+@Serializable
+data class SolanaPayTransactionResponse(
+    /** Base64-encoded unsigned transaction; sign with the payer before relaying. */
+    val transaction: String,
+    /** Amount paid in base units (lamports or token units). */
+    val amount: Long,
+    val request: SolanaPayRequest,
+)
+
 /** Parameters for [PolliNetSDK.createApproveTransaction]. */
 @Serializable
 internal data class CreateApproveTransactionRequest(
@@ -0,0 +1,109 @@
+package xyz.pollinet.sdk
+
+import android.content.Context
+import android.security.keystore.KeyGenParameterSpec
+import android.security.keystore.KeyProperties
+import android.util.Base64
+import java.security.KeyStore
+import java.security.SecureRandom
+import javax.crypto.Cipher
+import javax.crypto.KeyGenerator
+import javax.crypto.SecretKey
+import javax.crypto.spec.GCMParameterSpec
+
+/**
+ * Supplies the secure storage key to the Rust core via [SdkConfig.platformKey].
+ *
+ * A random 32-byte storage key is generated once and stored wrapped (AES-GCM)
+ * by a non-exportable Android Keystore key, so the raw key never touches disk
+ * and Rust never derives key material itself.
+ */
+class StorageKeyManager(context: Context) {
+    companion object {
+        private const val ANDROID_KEYSTORE = "AndroidKeyStore"
+        private const val WRAPPING_KEY_ALIAS = "pollinet_storage_wrap"
+        private const val PREFS_NAME = "pollinet_storage_key"
+        private const val PREF_WRAPPED_KEY = "wrapped_key"
+        private const val PREF_IV = "iv"
+        private const val STORAGE_KEY_BYTES = 32
+        private const val GCM_TAG_BITS = 128
+    }
+
+    private val prefs = context.getSharedPreferences(PREFS_NAME, Context.MODE_PRIVATE)
+
+    private val keyStore: KeyStore = KeyStore.getInstance(ANDROID_KEYSTORE).apply {
+        load(null)
+    }
+
+    /**
+     * Return the storage key as base64 (for [SdkConfig.platformKey]),
+     * generating and wrapping a new one on first use.
+     */
+    fun getOrCreateStorageKey(): Result<String> {
+        return try {
+            val wrapped = prefs.getString(PREF_WRAPPED_KEY, null)
+            val iv = prefs.getString(PREF_IV, null)
+
+            val rawKey = if (wrapped != null && iv != null) {
+                unwrap(Base64.decode(wrapped, Base64.NO_WRAP), Base64.decode(iv, Base64.NO_WRAP))
+            } else {
+                ByteArray(STORAGE_KEY_BYTES).also { key ->
+                    SecureRandom().nextBytes(key)
+                    wrapAndStore(key)
+                }
+            }
+
+            val encoded = Base64.encodeToString(rawKey, Base64.NO_WRAP)
+            rawKey.fill(0)
+            Result.success(encoded)
+        } catch (e: Exception) {
+            Result.failure(e)
+        }
+    }
+
+    /**
+     * Forget the storage key (data encrypted with it becomes unreadable)
+     */
+    fun deleteStorageKey() {
+        prefs.edit().clear().apply()
+        if (keyStore.containsAlias(WRAPPING_KEY_ALIAS)) {
+            keyStore.deleteEntry(WRAPPING_KEY_ALIAS)
+        }
+    }
+
+    private fun wrappingKey(): SecretKey {
+        (keyStore.getEntry(WRAPPING_KEY_ALIAS, null) as? KeyStore.SecretKeyEntry)?.let {
+            return it.secretKey
+        }
+
+        val spec = KeyGenParameterSpec.Builder(
+            WRAPPING_KEY_ALIAS,
+            KeyProperties.PURPOSE_ENCRYPT or KeyProperties.PURPOSE_DECRYPT
+        )
+            .setBlockModes(KeyProperties.BLOCK_MODE_GCM)
+            .setEncryptionPaddings(KeyProperties.ENCRYPTION_PADDING_NONE)
+            .setKeySize(256)
+            .build()
+
+        return KeyGenerator.getInstance(KeyProperties.KEY_ALGORITHM_AES, ANDROID_KEYSTORE)
+            .apply { init(spec) }
+            .generateKey()
+    }
+
+    private fun wrapAndStore(rawKey: ByteArray) {
+        val cipher = Cipher.getInstance("AES/GCM/NoPadding")
+        cipher.init(Cipher.ENCRYPT_MODE, wrappingKey())
+        val wrapped = cipher.doFinal(rawKey)
+
+        prefs.edit()
+            .putString(PREF_WRAPPED_KEY, Base64.encodeToString(wrapped, Base64.NO_WRAP))
+            .putString(PREF_IV, Base64.encodeToString(cipher.iv, Base64.NO_WRAP))
+            .commit()
+    }
+
+    private fun unwrap(wrapped: ByteArray, iv: ByteArray): ByteArray {
+        val cipher = Cipher.getInstance("AES/GCM/NoPadding")
+        cipher.init(Cipher.DECRYPT_MODE, wrappingKey(), GCMParameterSpec(GCM_TAG_BITS, iv))
+        return cipher.doFinal(wrapped)
+    }
+}
@@ -52,7 +52,7 @@ import java.util.concurrent.CopyOnWriteArrayList
  * routing, voting, polling, or Solana semantics — those live in the Rust shared layers.
  *
  * Framing on the socket is **length-prefixed** (4-byte big-endian length + payload),
- * where each payload is exactly the bincode `TransactionFragment` the Rust engine emits.
+ * where each payload is exactly the fragment wire frame the Rust engine emits.
  *
  * NOTE: requires `ACCESS_FINE_LOCATION` (and `NEARBY_WIFI_DEVICES` on API 33+),
  * `ACCESS_WIFI_STATE`, `CHANGE_WIFI_STATE`, `INTERNET` permissions in the host app
@@ -130,7 +130,7 @@ class WifiDirectService : Service() {
         val connectedPeers: StateFlow<Int> = _connectedPeers.asStateFlow()
 
         // ── Wire frame types (1-byte tag prefixed to every socket payload) ──────
-        /** Forward path: a bincode TransactionFragment → engine `pushInbound`. */
+        /** Forward path: a fragment wire frame → engine `pushInbound`. */
         const val FRAME_TYPE_FRAGMENT: Byte = 0x00
         /** Reverse path: a JSON Confirmation → engine confirmation handling. */
         const val FRAME_TYPE_CONFIRMATION: Byte = 0x01
@@ -0,0 +1,79 @@
+#!/usr/bin/env python3
+"""Example gateway script built on the `pollinet` Python bindings.
+
+Watches a directory for collected transactions (one base64 transaction per
+`*.b64` file), queues them, submits them to a Solana RPC endpoint and writes a
+JSON report. Build the bindings first with `maturin develop --release`.
+"""
+
+import argparse
+import base64
+import json
+import pathlib
+import time
+import urllib.request
+
+import pollinet
+
+
+def send_transaction(rpc_url, tx_bytes):
+    body = json.dumps({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "sendTransaction",
+        "params": [base64.b64encode(tx_bytes).decode(), {"encoding": "base64"}],
+    }).encode()
+    request = urllib.request.Request(rpc_url, body, {"Content-Type": "application/json"})
+    with urllib.request.urlopen(request, timeout=30) as response:
+        reply = json.load(response)
+    if "error" in reply:
+        raise RuntimeError(reply["error"].get("message", str(reply["error"])))
+    return reply["result"]
+
+
+def submit(gateway, rpc_url, tx_id, tx_bytes):
+    try:
+        signature = send_transaction(rpc_url, tx_bytes)
+        gateway.record_success(tx_id, signature)
+        print(f"submitted {tx_id[:16]} -> {signature}")
+    except Exception as error:  # network and RPC errors are retried
+        if not gateway.record_failure(tx_bytes, tx_id, str(error)):
+            print(f"dead-lettered {tx_id[:16]}: {error}")
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__)
+    parser.add_argument("inbox", type=pathlib.Path)
+    parser.add_argument("--rpc", default="https://api.devnet.solana.com")
+    parser.add_argument("--storage", default="gateway-state")
+    parser.add_argument("--report", type=pathlib.Path, default=pathlib.Path("gateway-report.json"))
+    parser.add_argument("--interval", type=float, default=5.0)
+    args = parser.parse_args()
+
+    gateway = pollinet.Gateway(args.storage)
+    done = args.inbox / "accepted"
+    done.mkdir(parents=True, exist_ok=True)
+
+    while True:
+        for path in sorted(args.inbox.glob("*.b64")):
+            try:
+                tx_id = gateway.accept_transaction(path.read_text().strip())
+                print(f"accepted {path.name} as {tx_id[:16]}")
+            except ValueError as error:
+                print(f"rejected {path.name}: {error}")
+            path.rename(done / path.name)
+
+        while (entry := gateway.pop_outbound()) is not None:
+            submit(gateway, args.rpc, *entry)
+        while (retry := gateway.pop_ready_retry()) is not None:
+            tx_id, tx_bytes, _attempts = retry
+            submit(gateway, args.rpc, tx_id, tx_bytes)
+
+        gateway.run_maintenance()
+        gateway.save()
+        args.report.write_text(gateway.snapshot())
+        time.sleep(args.interval)
+
+
+if __name__ == "__main__":
+    main()
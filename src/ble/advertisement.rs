@@ -0,0 +1,172 @@
+//! What a device puts in its BLE advertisement
+//!
+//! Besides the service UUID, hosts advertise a short manufacturer-data
+//! block: format version, flags, the capability byte (see
+//! `role::capability`) and a 4-byte id. Normally the id is the device's
+//! short id (first 4 bytes of its key), so peers recognise it across
+//! encounters and can resume sessions.
+//!
+//! The same stable id lets a passive scanner follow a phone from place to
+//! place while it relays. In privacy mode the id is a pseudonym hashed from
+//! the device's secret key and the current rotation epoch. It changes every
+//! `rotation_secs`, and two pseudonyms can't be linked without the key. The
+//! host should restart advertising at [`Advertisement::rotates_at`] so the
+//! radio's private address rotates with it. The device name is never
+//! advertised.
+//!
+//! Layout: version (1), flags (1), capabilities (1), id (4).
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ed25519_dalek::SigningKey;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Manufacturer id the block is advertised under (reserved by the Bluetooth
+/// SIG for testing; replace once an assigned company id exists)
+pub const MANUFACTURER_ID: u16 = 0xFFFF;
+pub const ADVERTISEMENT_VERSION: u8 = 1;
+/// Encoded manufacturer-data length
+pub const ADVERTISEMENT_LEN: usize = 7;
+/// Default time between pseudonym changes in privacy mode
+pub const DEFAULT_ROTATION_SECS: u64 = 15 * 60;
+/// Shortest rotation accepted; faster rotation only costs reconnects
+pub const MIN_ROTATION_SECS: u64 = 60;
+
+/// Flag: the id is a rotating pseudonym, not the device's short id
+const FLAG_PRIVATE: u8 = 1 << 0;
+const PSEUDONYM_DOMAIN: &[u8] = b"pollinet-adv-v1";
+
+/// Privacy mode settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PrivacySettings {
+    pub enabled: bool,
+    /// Seconds between pseudonym changes (at least [`MIN_ROTATION_SECS`])
+    pub rotation_secs: u64,
+}
+
+impl Default for PrivacySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rotation_secs: DEFAULT_ROTATION_SECS,
+        }
+    }
+}
+
+/// Advertisement for the host to broadcast
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Advertisement {
+    pub manufacturer_id: u16,
+    /// Base64 manufacturer data
+    pub manufacturer_data: String,
+    /// Hex id carried in the data
+    pub advertised_id: String,
+    pub private: bool,
+    /// Unix seconds when the pseudonym changes (privacy mode only)
+    pub rotates_at: Option<u64>,
+}
+
+impl Advertisement {
+    /// Build the advertisement for `key` at `now_secs`
+    pub fn build(
+        key: &SigningKey,
+        capabilities: u8,
+        privacy: &PrivacySettings,
+        now_secs: u64,
+    ) -> Self {
+        let (id, flags, rotates_at) = if privacy.enabled {
+            let rotation = privacy.rotation_secs.max(MIN_ROTATION_SECS);
+            let epoch = now_secs / rotation;
+            (
+                pseudonym(key, epoch),
+                FLAG_PRIVATE,
+                Some((epoch + 1) * rotation),
+            )
+        } else {
+            let public = key.verifying_key().to_bytes();
+            ([public[0], public[1], public[2], public[3]], 0, None)
+        };
+
+        let mut data = Vec::with_capacity(ADVERTISEMENT_LEN);
+        data.extend_from_slice(&[ADVERTISEMENT_VERSION, flags, capabilities]);
+        data.extend_from_slice(&id);
+        Self {
+            manufacturer_id: MANUFACTURER_ID,
+            manufacturer_data: BASE64.encode(&data),
+            advertised_id: hex::encode(id),
+            private: privacy.enabled,
+            rotates_at,
+        }
+    }
+}
+
+/// Advertisement fields read back by a scanner
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScannedAdvertisement {
+    pub capabilities: u8,
+    pub id: [u8; 4],
+    /// `id` is a pseudonym; it can't be matched to a known device
+    pub private: bool,
+}
+
+impl ScannedAdvertisement {
+    /// Parse manufacturer data; `None` for other formats or versions
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() != ADVERTISEMENT_LEN || data[0] != ADVERTISEMENT_VERSION {
+            return None;
+        }
+        Some(Self {
+            private: data[1] & FLAG_PRIVATE != 0,
+            capabilities: data[2],
+            id: data[3..7].try_into().ok()?,
+        })
+    }
+}
+
+/// Pseudonym for one rotation epoch
+fn pseudonym(key: &SigningKey, epoch: u64) -> [u8; 4] {
+    let hash = Sha256::new()
+        .chain_update(PSEUDONYM_DOMAIN)
+        .chain_update(key.to_bytes())
+        .chain_update(epoch.to_le_bytes())
+        .finalize();
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(ad: &Advertisement) -> ScannedAdvertisement {
+        let data = BASE64.decode(&ad.manufacturer_data).unwrap();
+        ScannedAdvertisement::parse(&data).unwrap()
+    }
+
+    #[test]
+    fn test_privacy_mode_rotates_pseudonym() {
+        let key = crate::ble::generate_device_key();
+        let stable = Advertisement::build(&key, 0b101, &PrivacySettings::default(), 1_000);
+        let scanned = decode(&stable);
+        assert!(!scanned.private);
+        assert_eq!(scanned.capabilities, 0b101);
+        assert_eq!(scanned.id, key.verifying_key().to_bytes()[..4]);
+        assert_eq!(stable.rotates_at, None);
+
+        let privacy = PrivacySettings {
+            enabled: true,
+            rotation_secs: 600,
+        };
+        let first = Advertisement::build(&key, 0b101, &privacy, 1_000);
+        assert!(decode(&first).private);
+        assert_ne!(first.advertised_id, stable.advertised_id);
+        assert_eq!(first.rotates_at, Some(1_200));
+        // Same epoch, same pseudonym; the next epoch changes it
+        assert_eq!(Advertisement::build(&key, 0b101, &privacy, 1_199), first);
+        let next = Advertisement::build(&key, 0b101, &privacy, 1_200);
+        assert_ne!(next.advertised_id, first.advertised_id);
+
+        assert!(ScannedAdvertisement::parse(&[2, 0, 0, 1, 2, 3, 4]).is_none());
+    }
+}
@@ -0,0 +1,102 @@
+//! Several transactions sent as one fragment stream
+//!
+//! Encounters between phones are short. When a device has several
+//! transactions for the same peer, fragmenting each on its own costs a
+//! header per fragment plus a part-empty last fragment per transaction, and
+//! the peer runs one reassembly each. Packing them into a single payload —
+//! `BATCH_MAGIC`, a varint count, then a varint length and the bytes of
+//! each transaction — sends them as one fragment group, back to back.
+//!
+//! The magic can't start a serialized transaction: its first byte would be
+//! a signature count of 80, far beyond what fits in a packet.
+
+use super::wire::{put_varint, read_varint};
+
+/// Prefix of a batch payload
+pub const BATCH_MAGIC: &[u8; 4] = b"PNB1";
+/// Transactions per batch at most
+pub const MAX_BATCH_TRANSACTIONS: usize = 32;
+
+/// Pack transactions into one batch payload
+pub fn encode_batch<T: AsRef<[u8]>>(transactions: &[T]) -> Result<Vec<u8>, String> {
+    if transactions.is_empty() || transactions.len() > MAX_BATCH_TRANSACTIONS {
+        return Err(format!(
+            "A batch holds 1-{} transactions, got {}",
+            MAX_BATCH_TRANSACTIONS,
+            transactions.len()
+        ));
+    }
+
+    let total: usize = transactions.iter().map(|tx| tx.as_ref().len() + 2).sum();
+    let mut payload = Vec::with_capacity(BATCH_MAGIC.len() + 1 + total);
+    payload.extend_from_slice(BATCH_MAGIC);
+    put_varint(&mut payload, transactions.len() as u64);
+    for tx in transactions {
+        put_varint(&mut payload, tx.as_ref().len() as u64);
+        payload.extend_from_slice(tx.as_ref());
+    }
+    Ok(payload)
+}
+
+/// True if a reassembled payload is a batch
+pub fn is_batch(payload: &[u8]) -> bool {
+    payload.starts_with(BATCH_MAGIC)
+}
+
+/// Split a batch payload back into its transactions
+pub fn decode_batch(payload: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+    if !is_batch(payload) {
+        return Err("Not a batch payload".to_string());
+    }
+
+    let mut pos = BATCH_MAGIC.len();
+    let count = read_varint(payload, &mut pos).ok_or("Truncated batch count")? as usize;
+    if count == 0 || count > MAX_BATCH_TRANSACTIONS {
+        return Err(format!("Invalid batch count {}", count));
+    }
+
+    let mut transactions = Vec::with_capacity(count);
+    for i in 0..count {
+        let len = read_varint(payload, &mut pos).ok_or("Truncated batch length")? as usize;
+        let tx = pos
+            .checked_add(len)
+            .and_then(|end| payload.get(pos..end))
+            .ok_or_else(|| format!("Batch transaction {} is truncated", i))?;
+        transactions.push(tx.to_vec());
+        pos += len;
+    }
+    if pos != payload.len() {
+        return Err(format!(
+            "{} trailing bytes after batch",
+            payload.len() - pos
+        ));
+    }
+    Ok(transactions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ble::fragmenter::{fragment_transaction_with_max_payload, reconstruct_transaction};
+
+    #[test]
+    fn test_batch_round_trip_uses_fewer_fragments() {
+        let transactions: Vec<Vec<u8>> = (0..4u8).map(|n| vec![n; 300]).collect();
+        let payload = encode_batch(&transactions).unwrap();
+        assert!(is_batch(&payload));
+
+        let batched = fragment_transaction_with_max_payload(&payload, 512);
+        let separate: usize = transactions
+            .iter()
+            .map(|tx| fragment_transaction_with_max_payload(tx, 512).len())
+            .sum();
+        assert!(batched.len() < separate);
+
+        let reassembled = reconstruct_transaction(&batched).unwrap();
+        assert_eq!(decode_batch(&reassembled).unwrap(), transactions);
+
+        assert!(decode_batch(&payload[..payload.len() - 1]).is_err());
+        assert!(encode_batch::<Vec<u8>>(&[]).is_err());
+        assert!(!is_batch(&transactions[1]));
+    }
+}
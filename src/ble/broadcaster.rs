@@ -292,9 +292,7 @@ impl TransactionBroadcaster {
         &self,
         fragment: &TransactionFragment,
     ) -> Result<Vec<u8>, String> {
-        // Serialize fragment
-        let payload = bincode1::serialize(fragment)
-            .map_err(|e| format!("Failed to serialize fragment: {}", e))?;
+        let payload = fragment.serialize();
 
         // Create mesh packet
         let packet = MeshPacket::new(PacketType::TransactionFragment, self.device_id, payload);
@@ -3,6 +3,7 @@
 //! Extends the base PacketType with four new types starting at 0x08.
 //! All new frame types are single-BLE-fragment (no sub-fragmentation).
 
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use serde::{Deserialize, Serialize};
 
 /// Extended packet type byte values.
@@ -18,6 +19,22 @@ pub enum ControlFrameType {
     DrainReady = 0x0A,
     /// Handshake close acknowledgment.
     CloseAck = 0x0B,
+    /// Bloom filter of the transaction ids a peer holds, sent on connect
+    /// (see `digest.rs`). 0x0D is the `PolliNetMessage` envelope.
+    TxDigest = 0x0E,
+    /// Discard every buffered fragment of a transaction and stop relaying it.
+    TxCancel = 0x0F,
+    /// "Your fragment was dropped, send it again" (see [`FragmentNackFrame`]).
+    FragmentNack = 0x10,
+    /// Signed emergency notice flooded through the mesh (see `emergency.rs`).
+    EmergencyBroadcast = 0x11,
+    /// Signed offline invoice from a merchant (see `payment_request.rs`).
+    PaymentRequest = 0x12,
+    /// Signed payment receipt or its countersignature (see `payment_receipt.rs`).
+    PaymentReceipt = 0x13,
+    /// Device-signed relay receipt travelling back toward the origin
+    /// (see `relay_receipt.rs`).
+    RelayReceipt = 0x14,
 }
 
 impl ControlFrameType {
@@ -27,6 +44,13 @@ impl ControlFrameType {
             0x09 => Some(Self::TxAbort),
             0x0A => Some(Self::DrainReady),
             0x0B => Some(Self::CloseAck),
+            0x0E => Some(Self::TxDigest),
+            0x0F => Some(Self::TxCancel),
+            0x10 => Some(Self::FragmentNack),
+            0x11 => Some(Self::EmergencyBroadcast),
+            0x12 => Some(Self::PaymentRequest),
+            0x13 => Some(Self::PaymentReceipt),
+            0x14 => Some(Self::RelayReceipt),
             _ => None,
         }
     }
@@ -55,6 +79,18 @@ impl ConfirmationStatus {
 /// TTL for confirmation carrier entries (10 minutes > max tx TTL of 5 minutes).
 pub const CONFIRMATION_TTL_SECS: u64 = 600;
 
+/// Maximum number of relay hops for a transaction or confirmation carrier entry.
+pub const MAX_TX_RELAY_HOPS: u8 = 5;
+
+/// Confirmation key for a transaction: first 16 bytes of SHA-256(tx_id_hex).
+pub fn tx_id_hash(tx_id_hex: &str) -> [u8; 16] {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(tx_id_hex.as_bytes());
+    let mut hash = [0u8; 16];
+    hash.copy_from_slice(&digest[..16]);
+    hash
+}
+
 /// Signed Pollicore confirmation. Propagates through the mesh like a transaction.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MeshConfirmation {
@@ -91,21 +127,23 @@ impl MeshConfirmation {
             slot_or_error,
             relevance: 10,
             delivered_to: Vec::new(),
-            added_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs(),
+            added_at: crate::util::clock::now_secs(),
         }
     }
 
+    /// Count one more relay hop. Returns false (and leaves the count unchanged)
+    /// once MAX_TX_RELAY_HOPS is reached — the confirmation should not be relayed.
+    pub fn increment_hop(&mut self) -> bool {
+        if self.hop_count >= MAX_TX_RELAY_HOPS {
+            return false;
+        }
+        self.hop_count += 1;
+        true
+    }
+
     /// True if this confirmation has not expired.
     pub fn is_alive(&self) -> bool {
-        let age = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs()
-            .saturating_sub(self.added_at);
-        age < CONFIRMATION_TTL_SECS
+        crate::util::clock::secs_since(self.added_at) < CONFIRMATION_TTL_SECS
     }
 
     /// Serialize the signable payload: tx_id_hash || status_byte || slot_or_error
@@ -154,20 +192,12 @@ pub struct Tombstone {
 impl Tombstone {
     /// Create a tombstone valid for 2 × original_tx_ttl_secs.
     pub fn new(tx_id_hash: [u8; 16], original_tx_ttl_secs: u64) -> Self {
-        let until = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs()
-            + 2 * original_tx_ttl_secs;
+        let until = crate::util::clock::now_secs() + 2 * original_tx_ttl_secs;
         Self { tx_id_hash, until }
     }
 
     pub fn is_valid(&self) -> bool {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-        now < self.until
+        crate::util::clock::now_secs() < self.until
     }
 }
 
@@ -177,6 +207,194 @@ pub struct TxAbortFrame {
     pub tx_id_hash: [u8; 16],
 }
 
+/// Why a fragment group was cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum CancelReason {
+    /// The transaction landed on chain.
+    Confirmed = 1,
+    /// The origin replaced it (e.g. re-signed with a fresh nonce).
+    Superseded = 2,
+    /// Its intent or blockhash expired.
+    Expired = 3,
+}
+
+impl CancelReason {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            1 => Some(Self::Confirmed),
+            2 => Some(Self::Superseded),
+            3 => Some(Self::Expired),
+            _ => None,
+        }
+    }
+}
+
+/// TX_CANCEL frame: `0x0F || tx_id (32) || reason || hop_count || signature (64)`.
+/// The origin signs `0x0F || tx_id || reason` with its device key; receivers
+/// check it against the key the transaction's first fragment carried, then
+/// drop the transaction's reassembly buffer and queued fragments, refuse
+/// further fragments for a while, and pass the frame on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxCancelFrame {
+    /// Full transaction id (SHA-256 of the transaction bytes)
+    pub tx_id: [u8; 32],
+    pub reason: CancelReason,
+    /// Relay hop count, capped at MAX_TX_RELAY_HOPS (not signed).
+    pub hop_count: u8,
+    /// Ed25519 signature of the origin's device key
+    pub signature: [u8; 64],
+}
+
+impl TxCancelFrame {
+    pub const LEN: usize = 99;
+
+    /// Sign a cancel for `tx_id` with the origin's device key
+    pub fn sign(key: &SigningKey, tx_id: [u8; 32], reason: CancelReason) -> Self {
+        let mut frame = Self {
+            tx_id,
+            reason,
+            hop_count: 0,
+            signature: [0; 64],
+        };
+        frame.signature = key.sign(&frame.signable_payload()).to_bytes();
+        frame
+    }
+
+    /// Whether the cancel was signed by `origin`
+    pub fn verify(&self, origin: &[u8; 32]) -> bool {
+        VerifyingKey::from_bytes(origin).is_ok_and(|key| {
+            key.verify(
+                &self.signable_payload(),
+                &Signature::from_bytes(&self.signature),
+            )
+            .is_ok()
+        })
+    }
+
+    fn signable_payload(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(34);
+        payload.push(ControlFrameType::TxCancel as u8);
+        payload.extend_from_slice(&self.tx_id);
+        payload.push(self.reason as u8);
+        payload
+    }
+
+    /// Count one more relay hop. Returns false (and leaves the count unchanged)
+    /// once MAX_TX_RELAY_HOPS is reached — the frame should not be relayed.
+    pub fn increment_hop(&mut self) -> bool {
+        if self.hop_count >= MAX_TX_RELAY_HOPS {
+            return false;
+        }
+        self.hop_count += 1;
+        true
+    }
+
+    pub fn to_frame_bytes(&self) -> Vec<u8> {
+        let mut frame = Vec::with_capacity(Self::LEN);
+        frame.push(ControlFrameType::TxCancel as u8);
+        frame.extend_from_slice(&self.tx_id);
+        frame.push(self.reason as u8);
+        frame.push(self.hop_count);
+        frame.extend_from_slice(&self.signature);
+        frame
+    }
+
+    pub fn from_frame_bytes(data: &[u8]) -> Result<Self, String> {
+        if data.len() != Self::LEN || data[0] != ControlFrameType::TxCancel as u8 {
+            return Err(format!("Invalid TX_CANCEL frame ({} bytes)", data.len()));
+        }
+        let reason = CancelReason::from_u8(data[33])
+            .ok_or_else(|| format!("Unknown cancel reason {}", data[33]))?;
+        let mut tx_id = [0u8; 32];
+        tx_id.copy_from_slice(&data[1..33]);
+        let mut signature = [0u8; 64];
+        signature.copy_from_slice(&data[35..]);
+        Ok(Self {
+            tx_id,
+            reason,
+            hop_count: data[34],
+            signature,
+        })
+    }
+}
+
+/// Why a receiver dropped fragments it was sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum NackReason {
+    /// Its inbound queue was full.
+    QueueFull = 1,
+    /// The partial transaction sat idle too long and was discarded.
+    Timeout = 2,
+    /// The link dropped mid-transfer; the fragment is missing on reconnect.
+    Resume = 3,
+    /// The fragment arrived but failed its checksum.
+    Corrupt = 4,
+}
+
+impl NackReason {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            1 => Some(Self::QueueFull),
+            2 => Some(Self::Timeout),
+            3 => Some(Self::Resume),
+            4 => Some(Self::Corrupt),
+            _ => None,
+        }
+    }
+}
+
+/// FRAGMENT_NACK frame: `0x10 || tx_id (32) || fragment index (u16 LE) || reason`.
+/// Sent back to the peer a fragment came from; index 0xFFFF asks for the
+/// whole transaction again. Never relayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FragmentNackFrame {
+    /// Full transaction id (SHA-256 of the transaction bytes)
+    pub tx_id: [u8; 32],
+    /// Fragment to resend, or `None` for all of them
+    pub fragment_index: Option<u16>,
+    pub reason: NackReason,
+}
+
+impl FragmentNackFrame {
+    pub const LEN: usize = 36;
+    const ALL_FRAGMENTS: u16 = u16::MAX;
+
+    pub fn to_frame_bytes(&self) -> Vec<u8> {
+        let mut frame = Vec::with_capacity(Self::LEN);
+        frame.push(ControlFrameType::FragmentNack as u8);
+        frame.extend_from_slice(&self.tx_id);
+        frame.extend_from_slice(
+            &self
+                .fragment_index
+                .unwrap_or(Self::ALL_FRAGMENTS)
+                .to_le_bytes(),
+        );
+        frame.push(self.reason as u8);
+        frame
+    }
+
+    pub fn from_frame_bytes(data: &[u8]) -> Result<Self, String> {
+        if data.len() != Self::LEN || data[0] != ControlFrameType::FragmentNack as u8 {
+            return Err(format!(
+                "Invalid FRAGMENT_NACK frame ({} bytes)",
+                data.len()
+            ));
+        }
+        let reason = NackReason::from_u8(data[35])
+            .ok_or_else(|| format!("Unknown NACK reason {}", data[35]))?;
+        let mut tx_id = [0u8; 32];
+        tx_id.copy_from_slice(&data[1..33]);
+        let index = u16::from_le_bytes([data[33], data[34]]);
+        Ok(Self {
+            tx_id,
+            fragment_index: (index != Self::ALL_FRAGMENTS).then_some(index),
+            reason,
+        })
+    }
+}
+
 /// DRAIN_READY / CLOSE_ACK frames carry no payload — the type byte is sufficient.
 /// This zero-sized struct is kept for symmetry.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -204,9 +422,93 @@ mod tests {
             ControlFrameType::from_u8(0x0B),
             Some(ControlFrameType::CloseAck)
         );
+        assert_eq!(
+            ControlFrameType::from_u8(0x14),
+            Some(ControlFrameType::RelayReceipt)
+        );
         assert_eq!(ControlFrameType::from_u8(0x01), None);
     }
 
+    #[test]
+    fn test_frame_types_avoid_android_reserved_bytes() {
+        // BleService.kt claims these first bytes for its own frames:
+        // 0x01–0x07 data fragments, 0x0C CONFIRMATION_FRAG, '{' legacy JSON
+        let reserved = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x0C, b'{'];
+        for byte in 0..=u8::MAX {
+            if let Some(frame_type) = ControlFrameType::from_u8(byte) {
+                assert_eq!(frame_type as u8, byte);
+                assert!(
+                    !reserved.contains(&byte),
+                    "{:?} uses the reserved byte 0x{:02X}",
+                    frame_type,
+                    byte
+                );
+            }
+        }
+        assert!(!reserved.contains(&crate::ble::envelope::ENVELOPE_FRAME_TYPE));
+    }
+
+    #[test]
+    fn test_confirmation_hop_limit() {
+        let mut conf = MeshConfirmation::new(
+            tx_id_hash("abcd"),
+            ConfirmationStatus::Success,
+            [0u8; 64],
+            Vec::new(),
+        );
+        for _ in 0..MAX_TX_RELAY_HOPS {
+            assert!(conf.increment_hop());
+        }
+        assert!(!conf.increment_hop());
+        assert_eq!(conf.hop_count, MAX_TX_RELAY_HOPS);
+    }
+
+    #[test]
+    fn test_tx_cancel_frame_roundtrip() {
+        let origin = crate::ble::generate_device_key();
+        let origin_key = origin.verifying_key().to_bytes();
+        let mut frame = TxCancelFrame::sign(&origin, [3u8; 32], CancelReason::Superseded);
+        assert!(frame.increment_hop());
+        let bytes = frame.to_frame_bytes();
+        assert_eq!(bytes.len(), TxCancelFrame::LEN);
+        let decoded = TxCancelFrame::from_frame_bytes(&bytes).unwrap();
+        assert_eq!(decoded, frame);
+        // Relays may bump the hop count; the signature still holds
+        assert!(decoded.verify(&origin_key));
+
+        let other = crate::ble::generate_device_key().verifying_key().to_bytes();
+        assert!(!decoded.verify(&other));
+        let mut retargeted = decoded;
+        retargeted.reason = CancelReason::Confirmed;
+        assert!(!retargeted.verify(&origin_key));
+
+        let mut unknown = bytes.clone();
+        unknown[33] = 9;
+        assert!(TxCancelFrame::from_frame_bytes(&unknown).is_err());
+        assert!(TxCancelFrame::from_frame_bytes(&bytes[..35]).is_err());
+    }
+
+    #[test]
+    fn test_fragment_nack_frame_roundtrip() {
+        let nack = FragmentNackFrame {
+            tx_id: [7u8; 32],
+            fragment_index: Some(3),
+            reason: NackReason::QueueFull,
+        };
+        let bytes = nack.to_frame_bytes();
+        assert_eq!(bytes.len(), FragmentNackFrame::LEN);
+        assert_eq!(FragmentNackFrame::from_frame_bytes(&bytes).unwrap(), nack);
+
+        let restart = FragmentNackFrame {
+            fragment_index: None,
+            reason: NackReason::Timeout,
+            ..nack
+        };
+        let decoded = FragmentNackFrame::from_frame_bytes(&restart.to_frame_bytes()).unwrap();
+        assert_eq!(decoded.fragment_index, None);
+        assert!(FragmentNackFrame::from_frame_bytes(&bytes[..20]).is_err());
+    }
+
     #[test]
     fn test_tombstone_validity() {
         let hash = [0u8; 16];
@@ -218,6 +520,17 @@ mod tests {
             until: 0,
         };
         assert!(!expired.is_valid());
+
+        // Expiry follows the SDK clock
+        use crate::util::clock::{with_clock, ManualClock};
+        let clock = std::sync::Arc::new(ManualClock::new(1_000_000));
+        with_clock(clock.clone(), || {
+            let tomb = Tombstone::new(hash, 300);
+            clock.advance_secs(599);
+            assert!(tomb.is_valid());
+            clock.advance_secs(1);
+            assert!(!tomb.is_valid());
+        });
     }
 
     #[test]
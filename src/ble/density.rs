@@ -5,7 +5,6 @@
 //! Also owns the per-device cooldown list used for peer-rotation scheduling.
 
 use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Base session target in milliseconds (tunable). 60 seconds.
 const BASE_SESSION_MS: f64 = 60_000.0;
@@ -45,9 +44,13 @@ impl DensityEstimator {
     }
 
     /// Record a scan observation for `peer_id`. Call on every `onScanResult`.
-    pub fn record(&mut self, peer_id: &str) {
+    /// Returns true if the peer had not been seen within the window.
+    pub fn record(&mut self, peer_id: &str) -> bool {
         let now = Self::now_secs();
-        self.seen.insert(peer_id.to_string(), now);
+        let cutoff = now.saturating_sub(DENSITY_WINDOW_SECS);
+        self.seen
+            .insert(peer_id.to_string(), now)
+            .is_none_or(|last_seen| last_seen <= cutoff)
     }
 
     /// Evict entries older than `DENSITY_WINDOW_SECS` and return the current N.
@@ -57,6 +60,15 @@ impl DensityEstimator {
         self.seen.len() as u32
     }
 
+    /// Peers observed within the window as (peer_id, last_seen_unix_secs), newest first.
+    pub fn recent_peers(&mut self) -> Vec<(String, u64)> {
+        self.evict_and_count();
+        let mut peers: Vec<(String, u64)> =
+            self.seen.iter().map(|(id, &ts)| (id.clone(), ts)).collect();
+        peers.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        peers
+    }
+
     /// Compute adaptive parameters from current N.
     pub fn compute_params(&mut self) -> AdaptiveParams {
         let n = self.evict_and_count().max(1) as f64;
@@ -81,10 +93,7 @@ impl DensityEstimator {
     }
 
     fn now_secs() -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs()
+        crate::util::clock::now_secs()
     }
 }
 
@@ -172,10 +181,7 @@ impl CooldownList {
     }
 
     fn now_ms() -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64
+        crate::util::clock::now_ms()
     }
 }
 
@@ -236,6 +242,16 @@ mod tests {
         assert_eq!(params.cooldown_ms, 216_000);
     }
 
+    #[test]
+    fn test_recent_peers_evicts_stale() {
+        let mut est = DensityEstimator::new();
+        est.record("peerA");
+        est.seen.insert("peerOld".to_string(), 0);
+        let peers = est.recent_peers();
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0].0, "peerA");
+    }
+
     #[test]
     fn test_cooldown_list_basic() {
         let mut list = CooldownList::new();
@@ -0,0 +1,128 @@
+//! Transaction digests exchanged on connect ("what do you have?")
+//!
+//! Right after connecting, each side sends a Bloom filter of the
+//! transaction ids it already holds (queued to relay, received or
+//! submitted). The other side then skips pushing fragments of those
+//! transactions instead of re-sending everything on every encounter.
+//!
+//! Transaction ids are SHA-256 hashes, so the filter's bit positions are
+//! taken straight from id bytes. False positives mean a peer misses a
+//! transaction on this encounter, never that it receives a duplicate; at
+//! [`DIGEST_CAPACITY`] ids the rate is about 2%.
+//!
+//! Frame: `DIGEST_FRAME_TYPE`, id count (u16 LE), then the filter bits.
+
+use super::control_frames::ControlFrameType;
+
+/// First byte of a digest frame
+pub const DIGEST_FRAME_TYPE: u8 = ControlFrameType::TxDigest as u8;
+/// Filter size in bits
+pub const DIGEST_BITS: usize = 1024;
+/// Bit positions set per id
+pub const DIGEST_HASHES: usize = 4;
+/// Ids a digest is sized for
+pub const DIGEST_CAPACITY: usize = 128;
+/// Encoded frame length
+pub const DIGEST_FRAME_LEN: usize = 3 + DIGEST_BITS / 8;
+
+/// Bloom filter of transaction ids
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxDigest {
+    bits: [u8; DIGEST_BITS / 8],
+    count: u16,
+}
+
+impl Default for TxDigest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TxDigest {
+    pub fn new() -> Self {
+        Self {
+            bits: [0u8; DIGEST_BITS / 8],
+            count: 0,
+        }
+    }
+
+    pub fn insert(&mut self, tx_id: &[u8; 32]) {
+        for bit in positions(tx_id) {
+            self.bits[bit / 8] |= 1 << (bit % 8);
+        }
+        self.count = self.count.saturating_add(1);
+    }
+
+    /// True if the peer probably holds `tx_id`
+    pub fn contains(&self, tx_id: &[u8; 32]) -> bool {
+        positions(tx_id).all(|bit| self.bits[bit / 8] & (1 << (bit % 8)) != 0)
+    }
+
+    /// Ids inserted by the sender
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn to_frame(&self) -> Vec<u8> {
+        let mut frame = Vec::with_capacity(DIGEST_FRAME_LEN);
+        frame.push(DIGEST_FRAME_TYPE);
+        frame.extend_from_slice(&self.count.to_le_bytes());
+        frame.extend_from_slice(&self.bits);
+        frame
+    }
+
+    pub fn from_frame(frame: &[u8]) -> Result<Self, String> {
+        if frame.len() != DIGEST_FRAME_LEN || frame[0] != DIGEST_FRAME_TYPE {
+            return Err(format!(
+                "Invalid digest frame ({} bytes, expected {})",
+                frame.len(),
+                DIGEST_FRAME_LEN
+            ));
+        }
+        let mut bits = [0u8; DIGEST_BITS / 8];
+        bits.copy_from_slice(&frame[3..]);
+        Ok(Self {
+            bits,
+            count: u16::from_le_bytes([frame[1], frame[2]]),
+        })
+    }
+}
+
+fn positions(tx_id: &[u8; 32]) -> impl Iterator<Item = usize> + '_ {
+    (0..DIGEST_HASHES)
+        .map(move |i| u16::from_le_bytes([tx_id[2 * i], tx_id[2 * i + 1]]) as usize % DIGEST_BITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    fn tx_id(n: u32) -> [u8; 32] {
+        Sha256::digest(n.to_le_bytes()).into()
+    }
+
+    #[test]
+    fn test_digest_round_trip_and_false_positive_rate() {
+        let mut digest = TxDigest::new();
+        for n in 0..DIGEST_CAPACITY as u32 {
+            digest.insert(&tx_id(n));
+        }
+
+        let decoded = TxDigest::from_frame(&digest.to_frame()).unwrap();
+        assert_eq!(decoded, digest);
+        assert_eq!(decoded.len(), DIGEST_CAPACITY);
+        assert!((0..DIGEST_CAPACITY as u32).all(|n| decoded.contains(&tx_id(n))));
+
+        let false_positives = (10_000..20_000u32)
+            .filter(|&n| decoded.contains(&tx_id(n)))
+            .count();
+        assert!(false_positives < 500, "{} false positives", false_positives);
+
+        assert!(TxDigest::from_frame(&[DIGEST_FRAME_TYPE, 0, 0]).is_err());
+    }
+}
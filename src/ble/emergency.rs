@@ -0,0 +1,196 @@
+//! Emergency broadcasts
+//!
+//! PolliNet is often deployed where the usual networks are down. Emergency
+//! notices ("water at the school", "road north is closed") are short,
+//! signed text messages that skip the transaction queues and flood the
+//! mesh: every device shows a notice once and passes it on to everyone it
+//! meets until its TTL runs out. The TTL is well above the transaction hop
+//! limit, since a notice is small and useful far from where it started.
+//!
+//! Anyone can broadcast; the signature only ties a notice to its origin's
+//! device key, so apps can show (or mute) who sent it. Notices older than
+//! [`EMERGENCY_MAX_AGE_SECS`] are dropped.
+//!
+//! Frame: `0x11 || origin (32) || timestamp (u64 LE) || ttl || len ||
+//! message || signature (64)`. The TTL is left out of the signature so
+//! relays can decrement it.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::control_frames::ControlFrameType;
+
+/// Longest message, in bytes
+pub const MAX_EMERGENCY_MESSAGE: usize = 100;
+/// Hops a notice travels from its origin
+pub const EMERGENCY_TTL: u8 = 16;
+/// Notices older than this are not shown or relayed (24 hours)
+pub const EMERGENCY_MAX_AGE_SECS: u64 = 24 * 60 * 60;
+/// Received notices kept for the app
+pub const MAX_EMERGENCY_INBOX: usize = 32;
+/// Notice ids remembered so each is shown and relayed once
+pub const MAX_SEEN_EMERGENCIES: usize = 256;
+
+const HEADER_LEN: usize = 1 + 32 + 8 + 1 + 1;
+const SIGNATURE_LEN: usize = 64;
+
+/// Signed emergency notice
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmergencyBroadcast {
+    pub origin: [u8; 32],
+    /// Unix seconds on the origin's clock
+    pub timestamp: u64,
+    /// Hops left
+    pub ttl: u8,
+    pub message: String,
+    pub signature: [u8; 64],
+}
+
+impl EmergencyBroadcast {
+    /// Sign a new notice with this device's key
+    pub fn sign(key: &SigningKey, message: &str, timestamp: u64) -> Result<Self, String> {
+        if message.is_empty() || message.len() > MAX_EMERGENCY_MESSAGE {
+            return Err(format!(
+                "Emergency message must be 1-{} bytes (got {})",
+                MAX_EMERGENCY_MESSAGE,
+                message.len()
+            ));
+        }
+        let mut broadcast = Self {
+            origin: key.verifying_key().to_bytes(),
+            timestamp,
+            ttl: EMERGENCY_TTL,
+            message: message.to_string(),
+            signature: [0; 64],
+        };
+        broadcast.signature = key.sign(&broadcast.signable_payload()).to_bytes();
+        Ok(broadcast)
+    }
+
+    /// Whether the signature matches the origin key
+    pub fn verify(&self) -> bool {
+        VerifyingKey::from_bytes(&self.origin).is_ok_and(|key| {
+            key.verify(
+                &self.signable_payload(),
+                &Signature::from_bytes(&self.signature),
+            )
+            .is_ok()
+        })
+    }
+
+    /// Stable id for deduplication (the same notice relayed with a
+    /// different TTL has the same id)
+    pub fn id(&self) -> [u8; 16] {
+        let hash = Sha256::digest(self.signable_payload());
+        hash[..16].try_into().unwrap()
+    }
+
+    /// Spend one hop; false once the notice should not travel further
+    pub fn decrement_ttl(&mut self) -> bool {
+        if self.ttl <= 1 {
+            return false;
+        }
+        self.ttl -= 1;
+        true
+    }
+
+    pub fn to_frame_bytes(&self) -> Vec<u8> {
+        let mut frame = Vec::with_capacity(HEADER_LEN + self.message.len() + SIGNATURE_LEN);
+        frame.push(ControlFrameType::EmergencyBroadcast as u8);
+        frame.extend_from_slice(&self.origin);
+        frame.extend_from_slice(&self.timestamp.to_le_bytes());
+        frame.push(self.ttl);
+        frame.push(self.message.len() as u8);
+        frame.extend_from_slice(self.message.as_bytes());
+        frame.extend_from_slice(&self.signature);
+        frame
+    }
+
+    pub fn from_frame_bytes(data: &[u8]) -> Result<Self, String> {
+        let invalid = || format!("Invalid EMERGENCY_BROADCAST frame ({} bytes)", data.len());
+        if data.len() < HEADER_LEN + SIGNATURE_LEN
+            || data[0] != ControlFrameType::EmergencyBroadcast as u8
+        {
+            return Err(invalid());
+        }
+        let len = data[HEADER_LEN - 1] as usize;
+        if len > MAX_EMERGENCY_MESSAGE || data.len() != HEADER_LEN + len + SIGNATURE_LEN {
+            return Err(invalid());
+        }
+        let message = std::str::from_utf8(&data[HEADER_LEN..HEADER_LEN + len])
+            .map_err(|_| "Emergency message is not UTF-8".to_string())?;
+        Ok(Self {
+            origin: data[1..33].try_into().unwrap(),
+            timestamp: u64::from_le_bytes(data[33..41].try_into().unwrap()),
+            ttl: data[41],
+            message: message.to_string(),
+            signature: data[HEADER_LEN + len..].try_into().unwrap(),
+        })
+    }
+
+    fn signable_payload(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(32 + 8 + self.message.len());
+        payload.extend_from_slice(&self.origin);
+        payload.extend_from_slice(&self.timestamp.to_le_bytes());
+        payload.extend_from_slice(self.message.as_bytes());
+        payload
+    }
+}
+
+/// Received notice, as shown to the app
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmergencyNotice {
+    /// Hex dedup id
+    pub id: String,
+    /// Base58 key of the device that sent it
+    pub origin: String,
+    pub timestamp: u64,
+    pub message: String,
+    /// Hops travelled to reach this device
+    pub hops: u8,
+}
+
+impl From<&EmergencyBroadcast> for EmergencyNotice {
+    fn from(broadcast: &EmergencyBroadcast) -> Self {
+        Self {
+            id: hex::encode(broadcast.id()),
+            origin: bs58::encode(broadcast.origin).into_string(),
+            timestamp: broadcast.timestamp,
+            message: broadcast.message.clone(),
+            hops: EMERGENCY_TTL.saturating_sub(broadcast.ttl),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signed_notice_roundtrip_and_relay() {
+        let key = crate::ble::generate_device_key();
+        assert!(EmergencyBroadcast::sign(&key, &"x".repeat(101), 0).is_err());
+
+        let notice = EmergencyBroadcast::sign(&key, "Flood: go to high ground", 1_000).unwrap();
+        let mut decoded = EmergencyBroadcast::from_frame_bytes(&notice.to_frame_bytes()).unwrap();
+        assert_eq!(decoded, notice);
+        assert!(decoded.verify());
+
+        // Relaying changes the TTL, not the id or the signature
+        assert!(decoded.decrement_ttl());
+        assert!(decoded.verify());
+        assert_eq!(decoded.id(), notice.id());
+        assert_eq!(EmergencyNotice::from(&decoded).hops, 1);
+
+        let mut forged = decoded.clone();
+        forged.message = "All clear".to_string();
+        assert!(!forged.verify());
+
+        decoded.ttl = 1;
+        assert!(!decoded.decrement_ttl());
+        let frame = notice.to_frame_bytes();
+        assert!(EmergencyBroadcast::from_frame_bytes(&frame[..frame.len() - 1]).is_err());
+    }
+}
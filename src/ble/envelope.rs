@@ -0,0 +1,327 @@
+//! Self-describing mesh envelope (`PolliNetMessage`)
+//!
+//! One container for everything a node sends: a message kind (the
+//! `PacketType` / `ControlFrameType` byte of the payload), priority, the
+//! hops it travelled and the payload itself. Frames are:
+//!
+//! | bytes | field                                   |
+//! |-------|-----------------------------------------|
+//! | 1     | `ENVELOPE_FRAME_TYPE`                   |
+//! | 1     | envelope version                        |
+//! | …     | fields: tag (1), varint length, value   |
+//!
+//! Like protobuf, every field carries its length, so a decoder skips tags
+//! it doesn't know; new fields don't need a version bump. Text messages and
+//! their acks travel in signed envelopes (see `HostBleTransport::next_text_frame`);
+//! other kinds keep their own frames for now and can move in one at a time,
+//! since the type byte keeps envelopes apart from the frames hosts route.
+//!
+//! The origin signs kind, priority, sequence number and payload with its
+//! device key (the sequence feeds [`super::replay::ReplayWindow`]). Relays
+//! append hop records without touching the signature, and check it at every
+//! hop: a tampered signed message, or a control message that isn't signed
+//! at all, is dropped before it can (say) convince an origin that its
+//! transaction was confirmed. A valid signature only proves the sender holds
+//! the key it carries, so the receiving transport also checks that key
+//! against the one pinned for its short id (`HostBleTransport::pin_peer_key`).
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+use super::control_frames::ControlFrameType;
+use super::mesh::MeshError;
+use super::wire::{put_varint, read_varint};
+use crate::queue::Priority;
+
+/// First byte of every envelope frame (between the 0x08–0x0B and 0x0E+
+/// control frames)
+pub const ENVELOPE_FRAME_TYPE: u8 = 0x0D;
+/// Envelope layout version
+pub const ENVELOPE_VERSION: u8 = 1;
+/// Hop records kept per message, oldest dropped first
+pub const MAX_HOP_RECORDS: usize = 16;
+/// Most bytes sealing adds to a payload under 2 MiB: type and version, kind,
+/// priority and sequence fields, the payload's tag and length, origin key
+/// and signature (a fresh envelope has no hop records)
+pub const SIGNED_ENVELOPE_OVERHEAD: usize = 2 + 3 + 3 + (2 + 10) + (1 + 3) + (2 + 32) + (2 + 64);
+
+const TAG_KIND: u8 = 1;
+const TAG_PRIORITY: u8 = 2;
+const TAG_HOP: u8 = 3;
+const TAG_PAYLOAD: u8 = 4;
+const TAG_ORIGIN: u8 = 5;
+const TAG_SIGNATURE: u8 = 6;
+const TAG_SEQUENCE: u8 = 7;
+
+/// One relay a message passed through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HopRecord {
+    /// Compact peer id (first 4 bytes of the relay's device id)
+    pub peer: [u8; 4],
+    /// Unix timestamp (seconds) the relay forwarded the message
+    pub at: u64,
+}
+
+/// Canonical mesh message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolliNetMessage {
+    /// Envelope version the message was decoded from (or is written as)
+    pub version: u8,
+    /// Frame type byte of the payload (`PacketType` or `ControlFrameType`)
+    pub kind: u8,
+    pub priority: Priority,
+    /// Per-origin sequence number, for replay detection
+    pub sequence: u64,
+    /// Oldest first; not covered by the signature
+    pub hops: Vec<HopRecord>,
+    pub payload: Vec<u8>,
+    /// Ed25519 verifying key of the origin device
+    pub origin: Option<[u8; 32]>,
+    /// Origin's signature over `signable_payload()`
+    pub signature: Option<[u8; 64]>,
+}
+
+impl PolliNetMessage {
+    pub fn new(kind: u8, payload: Vec<u8>) -> Self {
+        Self {
+            version: ENVELOPE_VERSION,
+            kind,
+            priority: Priority::Normal,
+            sequence: 0,
+            hops: Vec::new(),
+            payload,
+            origin: None,
+            signature: None,
+        }
+    }
+
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Record a relay, keeping at most [`MAX_HOP_RECORDS`]
+    pub fn add_hop(&mut self, peer: [u8; 4], at: u64) {
+        if self.hops.len() >= MAX_HOP_RECORDS {
+            self.hops.remove(0);
+        }
+        self.hops.push(HopRecord { peer, at });
+    }
+
+    pub fn hop_count(&self) -> usize {
+        self.hops.len()
+    }
+
+    /// Control frame kinds (confirmations, aborts, receipts, …)
+    pub fn is_control(&self) -> bool {
+        ControlFrameType::from_u8(self.kind).is_some()
+    }
+
+    /// Serialize the signable payload:
+    /// kind || priority || sequence (LE) || origin || payload
+    pub fn signable_payload(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(2 + 8 + 32 + self.payload.len());
+        buf.push(self.kind);
+        buf.push(self.priority as u8);
+        buf.extend_from_slice(&self.sequence.to_le_bytes());
+        buf.extend_from_slice(&self.origin.unwrap_or_default());
+        buf.extend_from_slice(&self.payload);
+        buf
+    }
+
+    /// Sign as the origin with this device's key
+    pub fn sign(&mut self, key: &SigningKey) {
+        self.origin = Some(key.verifying_key().to_bytes());
+        self.signature = Some(key.sign(&self.signable_payload()).to_bytes());
+    }
+
+    /// Verify the signature against the embedded origin key (which says
+    /// nothing about whether that key is trusted).
+    /// Silently returns false on any error (including unsigned messages).
+    pub fn verify(&self) -> bool {
+        let (Some(origin), Some(signature)) = (&self.origin, &self.signature) else {
+            return false;
+        };
+        let Ok(vk) = VerifyingKey::from_bytes(origin) else {
+            return false;
+        };
+        vk.verify(&self.signable_payload(), &Signature::from_bytes(signature))
+            .is_ok()
+    }
+
+    /// Check run at every hop before a message is acted on or relayed:
+    /// signed messages must verify, and control messages must be signed
+    pub fn check_relay(&self) -> Result<(), MeshError> {
+        let signed = self.signature.is_some();
+        if (signed || self.is_control()) && !self.verify() {
+            return Err(MeshError::BadSignature(format!(
+                "{} message of kind 0x{:02x}",
+                if signed { "tampered" } else { "unsigned" },
+                self.kind
+            )));
+        }
+        Ok(())
+    }
+
+    /// Encode as an envelope frame
+    pub fn encode(&self) -> Vec<u8> {
+        let mut frame = Vec::with_capacity(16 + self.hops.len() * 10 + self.payload.len());
+        frame.push(ENVELOPE_FRAME_TYPE);
+        frame.push(ENVELOPE_VERSION);
+
+        put_field(&mut frame, TAG_KIND, &[self.kind]);
+        put_field(&mut frame, TAG_PRIORITY, &[self.priority as u8]);
+        if self.sequence != 0 {
+            let mut value = Vec::new();
+            put_varint(&mut value, self.sequence);
+            put_field(&mut frame, TAG_SEQUENCE, &value);
+        }
+        for hop in &self.hops {
+            let mut value = hop.peer.to_vec();
+            put_varint(&mut value, hop.at);
+            put_field(&mut frame, TAG_HOP, &value);
+        }
+        put_field(&mut frame, TAG_PAYLOAD, &self.payload);
+        if let (Some(origin), Some(signature)) = (&self.origin, &self.signature) {
+            put_field(&mut frame, TAG_ORIGIN, origin);
+            put_field(&mut frame, TAG_SIGNATURE, signature);
+        }
+        frame
+    }
+
+    /// Decode an envelope frame, skipping unknown fields
+    pub fn decode(frame: &[u8]) -> Result<Self, MeshError> {
+        let invalid = |msg: &str| MeshError::InvalidPacket(format!("Envelope: {}", msg));
+
+        if frame.first() != Some(&ENVELOPE_FRAME_TYPE) {
+            return Err(invalid("not an envelope frame"));
+        }
+        let version = *frame.get(1).ok_or_else(|| invalid("missing version"))?;
+        if version == 0 {
+            return Err(invalid("version 0"));
+        }
+
+        let mut kind = None;
+        let mut priority = Priority::Normal;
+        let mut sequence = 0;
+        let mut hops = Vec::new();
+        let mut payload = None;
+        let mut origin = None;
+        let mut signature = None;
+
+        let mut pos = 2;
+        while pos < frame.len() {
+            let tag = frame[pos];
+            pos += 1;
+            let len = read_varint(frame, &mut pos).ok_or_else(|| invalid("truncated length"))?;
+            let value = usize::try_from(len)
+                .ok()
+                .and_then(|len| frame.get(pos..pos.checked_add(len)?))
+                .ok_or_else(|| invalid("truncated field"))?;
+            pos += value.len();
+
+            match tag {
+                TAG_KIND => kind = value.first().copied(),
+                TAG_PRIORITY => {
+                    priority = match value.first() {
+                        Some(2) => Priority::High,
+                        Some(0) => Priority::Low,
+                        _ => Priority::Normal,
+                    }
+                }
+                TAG_SEQUENCE => {
+                    sequence = read_varint(value, &mut 0).ok_or_else(|| invalid("bad sequence"))?
+                }
+                TAG_HOP => {
+                    let peer: [u8; 4] = value
+                        .get(..4)
+                        .and_then(|b| b.try_into().ok())
+                        .ok_or_else(|| invalid("short hop record"))?;
+                    let mut at_pos = 4;
+                    let at = read_varint(value, &mut at_pos)
+                        .ok_or_else(|| invalid("short hop record"))?;
+                    if hops.len() < MAX_HOP_RECORDS {
+                        hops.push(HopRecord { peer, at });
+                    }
+                }
+                TAG_PAYLOAD => payload = Some(value.to_vec()),
+                TAG_ORIGIN => {
+                    origin = Some(value.try_into().map_err(|_| invalid("bad origin key"))?)
+                }
+                TAG_SIGNATURE => {
+                    signature = Some(value.try_into().map_err(|_| invalid("bad signature"))?)
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            version,
+            kind: kind.ok_or_else(|| invalid("missing kind"))?,
+            priority,
+            sequence,
+            hops,
+            payload: payload.ok_or_else(|| invalid("missing payload"))?,
+            origin,
+            signature,
+        })
+    }
+}
+
+fn put_field(buf: &mut Vec<u8>, tag: u8, value: &[u8]) {
+    buf.push(tag);
+    put_varint(buf, value.len() as u64);
+    buf.extend_from_slice(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ble::control_frames::ControlFrameType;
+
+    #[test]
+    fn test_envelope_round_trip_skips_unknown_fields() {
+        let mut message = PolliNetMessage::new(ControlFrameType::Confirmation as u8, vec![9; 120])
+            .with_priority(Priority::High);
+        message.add_hop([1, 2, 3, 4], 1_700_000_000);
+        message.add_hop([5, 6, 7, 8], 1_700_000_042);
+
+        let frame = message.encode();
+        assert_eq!(frame[0], ENVELOPE_FRAME_TYPE);
+        assert_eq!(PolliNetMessage::decode(&frame).unwrap(), message);
+
+        // A field from a newer sender is ignored
+        let mut newer = frame.clone();
+        put_field(&mut newer, 42, b"future");
+        assert_eq!(PolliNetMessage::decode(&newer).unwrap(), message);
+
+        assert!(PolliNetMessage::decode(&frame[..frame.len() - 1]).is_err());
+        assert!(PolliNetMessage::decode(&[ENVELOPE_FRAME_TYPE, 1]).is_err());
+    }
+
+    #[test]
+    fn test_relays_drop_tampered_and_unsigned_control_messages() {
+        use crate::ble::generate_device_key;
+
+        let mut message = PolliNetMessage::new(ControlFrameType::Confirmation as u8, vec![1; 40]);
+        assert!(message.check_relay().is_err());
+
+        message.sign(&generate_device_key());
+        // Hop records are added in transit without breaking the signature
+        message.add_hop([1, 2, 3, 4], 1_700_000_000);
+        let decoded = PolliNetMessage::decode(&message.encode()).unwrap();
+        assert!(decoded.verify());
+        assert!(decoded.check_relay().is_ok());
+
+        let mut tampered = decoded.clone();
+        tampered.payload[0] ^= 0xFF;
+        assert!(tampered.check_relay().is_err());
+
+        let mut forged = decoded;
+        forged.origin = Some(generate_device_key().verifying_key().to_bytes());
+        assert!(forged.check_relay().is_err());
+
+        // Unsigned data messages still pass
+        let text = PolliNetMessage::new(crate::ble::PacketType::TextMessage as u8, b"hi".to_vec());
+        assert!(text.check_relay().is_ok());
+    }
+}
@@ -4,9 +4,16 @@
 //! and reconstructing them on the receiving side.
 
 use crate::ble::mesh::{TransactionFragment, MAX_FRAGMENT_DATA};
+use crate::storage::{BatchOp, StorageBackend, StorageError};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+/// Backend key prefix for persisted reassembly buffers
+const TX_CACHE_PREFIX: &str = "txcache/";
+/// Format version byte prefixed to each persisted reassembly buffer
+const TX_CACHE_FORMAT_VERSION: u8 = 1;
 
 /// Upper bound on per-fragment data size when an MTU-aware payload is supplied.
 ///
@@ -83,22 +90,7 @@ pub fn fragment_transaction_with_max_payload(
 
     tracing::debug!("Transaction ID: {}", hex::encode(transaction_id));
 
-    // Calculate max data size per fragment based on actual BLE constraints
-    // The max_payload comes from Android's (MTU - 10)
-    // We need to account for bincode serialization overhead:
-    // - transaction_id: 32 bytes (fixed array)
-    // - fragment_index: 2-3 bytes (u16 + varint overhead)
-    // - total_fragments: 2-3 bytes (u16 + varint overhead)
-    // - data length prefix: 1-4 bytes (Vec<u8> length)
-    // - bincode container overhead: ~2-4 bytes
-    // Total overhead: ~45-50 bytes (measured: 44 bytes actual, using 50 for safety margin)
-    let bincode_overhead = 50; // Increased from 40 to account for actual measured overhead
-    let max_data = max_payload.saturating_sub(bincode_overhead);
-
-    // Ensure minimum fragment size (but allow much larger with good MTU).
-    // Ceiling is shared across transports; BLE never reaches it (see constant docs),
-    // larger-MTU transports like Wi-Fi Direct use it to send fewer, bigger fragments.
-    let max_data = max_data.clamp(20, MAX_FRAGMENT_PAYLOAD_CEILING);
+    let max_data = max_fragment_data(max_payload);
 
     // Calculate number of fragments needed using the same max_data that we'll use for chunking
     // CRITICAL FIX: Use max_data instead of MAX_FRAGMENT_DATA to match actual chunking
@@ -139,6 +131,104 @@ pub fn fragment_transaction_with_max_payload(
     fragments
 }
 
+/// Fragment with the first fragment `first_room` bytes shorter than the
+/// rest, so its frame can also carry the origin's device key (see
+/// [`crate::ble::wire::encode_first_fragment`]) in the same payload size.
+/// Without `max_payload` the data size is `MAX_FRAGMENT_DATA`.
+pub fn fragment_transaction_with_first_room(
+    transaction_bytes: &[u8],
+    max_payload: Option<usize>,
+    first_room: usize,
+) -> Vec<TransactionFragment> {
+    if transaction_bytes.is_empty() {
+        return Vec::new();
+    }
+    let transaction_id: [u8; 32] = Sha256::digest(transaction_bytes).into();
+    let max_data = max_payload.map_or(MAX_FRAGMENT_DATA, max_fragment_data);
+    let first_len = max_data
+        .saturating_sub(first_room)
+        .clamp(1, transaction_bytes.len());
+    let (first, rest) = transaction_bytes.split_at(first_len);
+    let total_fragments = (1 + rest.len().div_ceil(max_data)) as u16;
+
+    std::iter::once(first)
+        .chain(rest.chunks(max_data))
+        .enumerate()
+        .map(|(index, chunk)| TransactionFragment {
+            transaction_id,
+            fragment_index: index as u16,
+            total_fragments,
+            data: chunk.to_vec(),
+        })
+        .collect()
+}
+
+/// Data bytes per fragment for a transport's max payload
+fn max_fragment_data(max_payload: usize) -> usize {
+    // Calculate max data size per fragment based on actual BLE constraints
+    // The max_payload comes from Android's (MTU - 10) and must hold the whole
+    // wire frame, header included (see `ble::wire`)
+    let max_data = max_payload.saturating_sub(crate::ble::wire::MAX_FRAGMENT_OVERHEAD);
+
+    // Ensure minimum fragment size (but allow much larger with good MTU).
+    // Ceiling is shared across transports; BLE never reaches it (see constant docs),
+    // larger-MTU transports like Wi-Fi Direct use it to send fewer, bigger fragments.
+    max_data.clamp(20, MAX_FRAGMENT_PAYLOAD_CEILING)
+}
+
+/// Fragment lazily, one fragment per `next()`
+///
+/// Yields the same fragments as [`fragment_transaction_with_max_payload`]
+/// without building the whole `Vec` first, so large payloads can be sent as
+/// the link drains. Only the id hash reads the payload up front.
+pub fn fragment_transaction_iter(transaction_bytes: &[u8], max_payload: usize) -> FragmentIter<'_> {
+    let transaction_id: [u8; 32] = Sha256::digest(transaction_bytes).into();
+    let max_data = max_fragment_data(max_payload);
+    FragmentIter {
+        transaction_id,
+        total_fragments: transaction_bytes.len().div_ceil(max_data) as u16,
+        chunks: transaction_bytes.chunks(max_data).enumerate(),
+    }
+}
+
+/// Iterator returned by [`fragment_transaction_iter`]
+#[derive(Debug, Clone)]
+pub struct FragmentIter<'a> {
+    transaction_id: [u8; 32],
+    total_fragments: u16,
+    chunks: std::iter::Enumerate<std::slice::Chunks<'a, u8>>,
+}
+
+impl FragmentIter<'_> {
+    pub fn transaction_id(&self) -> &[u8; 32] {
+        &self.transaction_id
+    }
+
+    pub fn total_fragments(&self) -> u16 {
+        self.total_fragments
+    }
+}
+
+impl Iterator for FragmentIter<'_> {
+    type Item = TransactionFragment;
+
+    fn next(&mut self) -> Option<TransactionFragment> {
+        let (index, chunk) = self.chunks.next()?;
+        Some(TransactionFragment {
+            transaction_id: self.transaction_id,
+            fragment_index: index as u16,
+            total_fragments: self.total_fragments,
+            data: chunk.to_vec(),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl ExactSizeIterator for FragmentIter<'_> {}
+
 /// Reconstruct a complete transaction from fragments
 ///
 /// Takes a collection of fragments and reconstructs the original transaction.
@@ -294,6 +384,185 @@ impl FragmentationStats {
     }
 }
 
+// ── Reassembly budget ─────────────────────────────────────────────────────────
+
+/// Fragments a transaction may claim; larger totals are rejected outright
+pub const MAX_REASSEMBLY_FRAGMENTS: u16 = 256;
+/// Bytes buffered for one partial transaction (a full batch fits)
+pub const MAX_REASSEMBLY_TX_BYTES: usize = 64 * 1024;
+/// Bytes buffered across all partial transactions
+pub const MAX_REASSEMBLY_TOTAL_BYTES: usize = 1024 * 1024;
+/// Partial transactions buffered at once
+pub const MAX_REASSEMBLY_TRANSACTIONS: usize = 64;
+/// Idle time after which a partial transaction is discarded
+pub const DEFAULT_REASSEMBLY_TIMEOUT_SECS: u64 = 120;
+
+/// Reject a fragment that can't belong to a real transaction, before
+/// anything is buffered (or sized) from its claimed total
+pub fn validate_fragment(fragment: &TransactionFragment) -> Result<(), String> {
+    if fragment.transaction_id == [0u8; 32] {
+        return Err("Fragment has an all-zero transaction id".to_string());
+    }
+    if fragment.total_fragments == 0 || fragment.total_fragments > MAX_REASSEMBLY_FRAGMENTS {
+        return Err(format!(
+            "Fragment claims {} fragments (max {})",
+            fragment.total_fragments, MAX_REASSEMBLY_FRAGMENTS
+        ));
+    }
+    if fragment.fragment_index >= fragment.total_fragments {
+        return Err(format!(
+            "Invalid fragment index {} (total: {})",
+            fragment.fragment_index, fragment.total_fragments
+        ));
+    }
+    if fragment.data.is_empty() || fragment.data.len() > MAX_FRAGMENT_PAYLOAD_CEILING {
+        return Err(format!(
+            "Fragment carries {} bytes (1-{} allowed)",
+            fragment.data.len(),
+            MAX_FRAGMENT_PAYLOAD_CEILING
+        ));
+    }
+    Ok(())
+}
+
+/// Memory accounting for partial reassemblies
+///
+/// A peer can send fragments claiming any `total` and never finish, so
+/// every buffered fragment is admitted here first. Absurd totals and
+/// transactions over their byte budget are refused; when the global
+/// budget or transaction count is reached, the partial transactions
+/// updated least recently are evicted to make room.
+#[derive(Debug)]
+pub struct ReassemblyBudget {
+    /// tx id → (total fragments, bytes buffered, last update)
+    entries: HashMap<String, (u16, usize, Instant)>,
+    total_bytes: usize,
+    max_tx_bytes: usize,
+    max_total_bytes: usize,
+}
+
+impl Default for ReassemblyBudget {
+    fn default() -> Self {
+        Self::new(MAX_REASSEMBLY_TX_BYTES, MAX_REASSEMBLY_TOTAL_BYTES)
+    }
+}
+
+impl ReassemblyBudget {
+    pub fn new(max_tx_bytes: usize, max_total_bytes: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            total_bytes: 0,
+            max_tx_bytes,
+            max_total_bytes,
+        }
+    }
+
+    /// Account for `len` more bytes of `tx_id`. Returns the transactions
+    /// evicted to make room, which the caller must drop.
+    pub fn admit(
+        &mut self,
+        tx_id: &str,
+        total_fragments: u16,
+        len: usize,
+    ) -> Result<Vec<String>, String> {
+        if total_fragments == 0 || total_fragments > MAX_REASSEMBLY_FRAGMENTS {
+            return Err(format!(
+                "Transaction {} claims {} fragments (max {})",
+                tx_id, total_fragments, MAX_REASSEMBLY_FRAGMENTS
+            ));
+        }
+        let buffered = match self.entries.get(tx_id) {
+            Some((total, _, _)) if *total != total_fragments => {
+                return Err(format!(
+                    "Total fragments mismatch for {} ({} vs {})",
+                    tx_id, total, total_fragments
+                ));
+            }
+            Some((_, bytes, _)) => *bytes,
+            None => 0,
+        };
+        if buffered + len > self.max_tx_bytes {
+            return Err(format!(
+                "Transaction {} exceeds its reassembly budget ({} bytes)",
+                tx_id, self.max_tx_bytes
+            ));
+        }
+
+        let mut evicted = Vec::new();
+        while self.total_bytes + len > self.max_total_bytes
+            || (buffered == 0 && self.entries.len() >= MAX_REASSEMBLY_TRANSACTIONS)
+        {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .filter(|(id, _)| id.as_str() != tx_id)
+                .min_by_key(|(_, (_, _, updated))| *updated)
+                .map(|(id, _)| id.clone())
+            else {
+                return Err(format!("Reassembly budget exhausted by {}", tx_id));
+            };
+            self.release(&oldest);
+            evicted.push(oldest);
+        }
+
+        let entry =
+            self.entries
+                .entry(tx_id.to_string())
+                .or_insert((total_fragments, 0, Instant::now()));
+        entry.1 += len;
+        entry.2 = Instant::now();
+        self.total_bytes += len;
+        Ok(evicted)
+    }
+
+    /// Forget a transaction that completed or was dropped
+    pub fn release(&mut self, tx_id: &str) {
+        if let Some((_, bytes, _)) = self.entries.remove(tx_id) {
+            self.total_bytes -= bytes;
+        }
+    }
+
+    /// Keep only the transactions for which `keep` returns true
+    pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        let dropped: Vec<String> = self
+            .entries
+            .keys()
+            .filter(|id| !keep(id))
+            .cloned()
+            .collect();
+        for tx_id in dropped {
+            self.release(&tx_id);
+        }
+    }
+
+    /// Forget transactions with no new fragment for `timeout`; returns
+    /// their ids so the caller can drop their buffers
+    pub fn take_idle(&mut self, timeout: Duration) -> Vec<String> {
+        let idle: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|(_, (_, _, updated))| updated.elapsed() >= timeout)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for tx_id in &idle {
+            self.release(tx_id);
+        }
+        idle
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        self.total_bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
 // ── Reassembly cache ──────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone)]
@@ -335,6 +604,8 @@ impl FragmentSet {
 
 pub struct TransactionCache {
     reassembly_buffers: HashMap<String, FragmentSet>,
+    /// Cancelled transactions (TX_CANCEL) whose fragments are refused
+    cancelled: HashMap<String, Instant>,
 }
 
 impl Default for TransactionCache {
@@ -347,28 +618,32 @@ impl TransactionCache {
     pub fn new() -> Self {
         Self {
             reassembly_buffers: HashMap::new(),
+            cancelled: HashMap::new(),
         }
     }
 
     pub fn add_ble_fragment(&mut self, fragment: TransactionFragment) -> Result<(), String> {
         let tx_id_hex = hex::encode(fragment.transaction_id);
+        if self.cancelled.contains_key(&tx_id_hex) {
+            return Err(format!("Transaction {} was cancelled", &tx_id_hex[..8]));
+        }
+        validate_fragment(&fragment)?;
+        if self
+            .reassembly_buffers
+            .get(&tx_id_hex)
+            .and_then(|set| set.received_fragments.get(fragment.fragment_index as usize))
+            .is_some_and(Option::is_some)
+        {
+            return Ok(()); // Duplicate
+        }
+
         let set = self
             .reassembly_buffers
             .entry(tx_id_hex.clone())
             .or_insert_with(|| FragmentSet::new(fragment.transaction_id, fragment.total_fragments));
-
-        if set.transaction_id != fragment.transaction_id {
-            return Err(format!("Transaction ID mismatch for {}", tx_id_hex));
-        }
         if set.total_fragments != fragment.total_fragments {
             return Err(format!("Total fragments mismatch for {}", tx_id_hex));
         }
-        if fragment.fragment_index >= fragment.total_fragments {
-            return Err(format!(
-                "Invalid fragment index {} (total: {})",
-                fragment.fragment_index, fragment.total_fragments
-            ));
-        }
 
         set.received_fragments[fragment.fragment_index as usize] = Some(fragment.data);
         set.last_updated = Instant::now();
@@ -383,7 +658,22 @@ impl TransactionCache {
         Ok(())
     }
 
+    /// Drop the buffer for `tx_id_hex` and refuse its fragments until a
+    /// [`Self::cleanup_stale_fragments`] call finds the cancel older than
+    /// its timeout. Returns true if a buffer was dropped.
+    pub fn cancel(&mut self, tx_id_hex: &str) -> bool {
+        self.cancelled.insert(tx_id_hex.to_string(), Instant::now());
+        self.reassembly_buffers.remove(tx_id_hex).is_some()
+    }
+
+    pub fn is_cancelled(&self, tx_id_hex: &str) -> bool {
+        self.cancelled.contains_key(tx_id_hex)
+    }
+
     pub fn cleanup_stale_fragments(&mut self, timeout_secs: u64) -> usize {
+        self.cancelled
+            .retain(|_, at| at.elapsed().as_secs() <= timeout_secs);
+
         let stale: Vec<String> = self
             .reassembly_buffers
             .iter()
@@ -404,12 +694,209 @@ impl TransactionCache {
         }
         count
     }
+
+    /// Persist partial reassembly buffers so a restart doesn't drop them
+    ///
+    /// Buffers no longer in the cache are deleted in the same atomic batch.
+    pub fn save_to(&self, backend: &dyn StorageBackend) -> Result<(), StorageError> {
+        let mut ops = Vec::with_capacity(self.reassembly_buffers.len());
+        for (tx_id_hex, set) in &self.reassembly_buffers {
+            let persist = FragmentSetPersist {
+                transaction_id: set.transaction_id,
+                total_fragments: set.total_fragments,
+                received_fragments: set.received_fragments.clone(),
+                age_secs: set.age_seconds(),
+            };
+            let mut value = vec![TX_CACHE_FORMAT_VERSION];
+            bincode1::serialize_into(&mut value, &persist).map_err(|e| {
+                StorageError::Serialization(format!("Failed to encode fragment set: {}", e))
+            })?;
+            ops.push(BatchOp::Put {
+                key: format!("{}{}", TX_CACHE_PREFIX, tx_id_hex),
+                value,
+            });
+        }
+
+        for key in backend.list(TX_CACHE_PREFIX)? {
+            if !self
+                .reassembly_buffers
+                .contains_key(&key[TX_CACHE_PREFIX.len()..])
+            {
+                ops.push(BatchOp::Delete { key });
+            }
+        }
+
+        backend.batch(&ops)
+    }
+
+    /// Restore reassembly buffers saved with [`save_to`](Self::save_to)
+    ///
+    /// Entries are checked like incoming fragments; bad ones are skipped.
+    pub fn load_from(backend: &dyn StorageBackend) -> Result<Self, StorageError> {
+        let mut cache = Self::new();
+        for key in backend.list(TX_CACHE_PREFIX)? {
+            let Some(bytes) = backend.get(&key)? else {
+                continue;
+            };
+            // Partial buffers are disposable: skip other format versions instead of migrating
+            let Some((&TX_CACHE_FORMAT_VERSION, body)) = bytes.split_first() else {
+                tracing::warn!("Skipping fragment set {} with unknown format version", key);
+                continue;
+            };
+            let persist: FragmentSetPersist = match bincode1::deserialize(body) {
+                Ok(persist) => persist,
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable fragment set {}: {}", key, e);
+                    continue;
+                }
+            };
+            let tx_id_hex = &key[TX_CACHE_PREFIX.len()..];
+            let valid = tx_id_hex == hex::encode(persist.transaction_id)
+                && persist.received_fragments.len() == persist.total_fragments as usize
+                && persist
+                    .received_fragments
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, data)| Some((index, data.clone()?)))
+                    .all(|(index, data)| {
+                        validate_fragment(&TransactionFragment {
+                            transaction_id: persist.transaction_id,
+                            fragment_index: index as u16,
+                            total_fragments: persist.total_fragments,
+                            data,
+                        })
+                        .is_ok()
+                    });
+            if !valid {
+                tracing::warn!("Skipping inconsistent fragment set {}", key);
+                continue;
+            }
+
+            let now = Instant::now();
+            let first_received = now
+                .checked_sub(Duration::from_secs(persist.age_secs))
+                .unwrap_or(now);
+            cache.reassembly_buffers.insert(
+                tx_id_hex.to_string(),
+                FragmentSet {
+                    transaction_id: persist.transaction_id,
+                    total_fragments: persist.total_fragments,
+                    received_fragments: persist.received_fragments,
+                    first_received,
+                    last_updated: now,
+                },
+            );
+        }
+        Ok(cache)
+    }
+}
+
+/// On-disk form of a [`FragmentSet`] (`Instant` is process-local, so store the age)
+#[derive(Serialize, Deserialize)]
+struct FragmentSetPersist {
+    transaction_id: [u8; 32],
+    total_fragments: u16,
+    received_fragments: Vec<Option<Vec<u8>>>,
+    age_secs: u64,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_transaction_cache_persists_partial_sets() {
+        let backend = crate::storage::MemoryBackend::new();
+        let fragments = fragment_transaction(&vec![7u8; 1200]);
+        assert!(fragments.len() > 1);
+
+        let mut cache = TransactionCache::new();
+        cache.add_ble_fragment(fragments[0].clone()).unwrap();
+        cache.save_to(&backend).unwrap();
+
+        let mut restored = TransactionCache::load_from(&backend).unwrap();
+        assert_eq!(restored.reassembly_buffers.len(), 1);
+        let set = restored.reassembly_buffers.values().next().unwrap();
+        assert_eq!(set.received_count(), 1);
+
+        // Saving an empty cache removes the stale entry
+        restored.reassembly_buffers.clear();
+        restored.save_to(&backend).unwrap();
+        assert!(backend.list(TX_CACHE_PREFIX).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reassembly_budget_rejects_absurd_totals_and_evicts_oldest() {
+        let mut cache = TransactionCache::new();
+        let mut bogus = fragment_transaction(&[1u8; 100]).remove(0);
+        bogus.total_fragments = u16::MAX;
+        assert!(cache.add_ble_fragment(bogus).is_err());
+        assert!(cache.reassembly_buffers.is_empty());
+        let fragments = fragment_transaction(&[2u8; 1200]);
+        cache.add_ble_fragment(fragments[0].clone()).unwrap();
+        let mut changed = fragments[1].clone();
+        changed.total_fragments += 1;
+        assert!(cache.add_ble_fragment(changed).is_err());
+
+        // Three partial transactions of 400 bytes under a 1000 byte budget
+        let mut budget = ReassemblyBudget::new(600, 1000);
+        assert!(budget.admit("a", 2, 400).unwrap().is_empty());
+        assert!(budget.admit("b", 2, 400).unwrap().is_empty());
+        assert_eq!(budget.admit("c", 2, 400).unwrap(), vec!["a".to_string()]);
+        assert_eq!(budget.total_bytes(), 800);
+
+        // Per-transaction budget and a changed total
+        assert!(budget.admit("b", 2, 300).is_err());
+        assert!(budget.admit("c", 3, 10).is_err());
+        budget.release("b");
+        assert_eq!((budget.len(), budget.total_bytes()), (1, 400));
+    }
+
+    #[test]
+    fn test_validate_fragment_rejects_malformed_fragments() {
+        let valid = fragment_transaction(&[7u8; 100]).remove(0);
+        assert!(validate_fragment(&valid).is_ok());
+
+        let reject = |change: fn(&mut TransactionFragment)| {
+            let mut fragment = valid.clone();
+            change(&mut fragment);
+            validate_fragment(&fragment).is_err()
+        };
+        assert!(reject(|f| f.transaction_id = [0; 32]));
+        assert!(reject(|f| f.total_fragments = 0));
+        assert!(reject(|f| f.total_fragments = MAX_REASSEMBLY_FRAGMENTS + 1));
+        assert!(reject(|f| f.fragment_index = f.total_fragments));
+        assert!(reject(|f| f.data.clear()));
+        assert!(reject(
+            |f| f.data = vec![0; MAX_FRAGMENT_PAYLOAD_CEILING + 1]
+        ));
+    }
+
+    #[test]
+    fn test_fragment_iter_matches_eager_fragmentation() {
+        let tx: Vec<u8> = (0..2000u32).map(|n| n as u8).collect();
+        let mut iter = fragment_transaction_iter(&tx, 200);
+        let eager = fragment_transaction_with_max_payload(&tx, 200);
+        assert_eq!(iter.len(), eager.len());
+        assert_eq!(iter.total_fragments() as usize, eager.len());
+
+        let key = |f: &TransactionFragment| {
+            (
+                f.transaction_id,
+                f.fragment_index,
+                f.total_fragments,
+                f.data.clone(),
+            )
+        };
+        let first = iter.next().unwrap();
+        assert_eq!(key(&first), key(&eager[0]));
+        assert_eq!(iter.len(), eager.len() - 1);
+        assert!(iter
+            .zip(&eager[1..])
+            .all(|(lazy, eager)| key(&lazy) == key(eager)));
+        assert_eq!(fragment_transaction_iter(&[], 200).count(), 0);
+    }
+
     #[test]
     fn test_fragment_small_transaction() {
         // Small transaction that fits in one fragment
@@ -9,6 +9,9 @@ use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+/// Mesh wire protocol version carried in every packet header
+pub const PROTOCOL_VERSION: u8 = 1;
+
 /// Maximum number of hops a message can traverse
 pub const MAX_HOPS: u8 = 10;
 
@@ -92,7 +95,7 @@ impl MeshHeader {
     pub fn new(packet_type: PacketType, sender_id: Uuid) -> Self {
         Self {
             packet_type,
-            version: 1,
+            version: PROTOCOL_VERSION,
             ttl: DEFAULT_TTL,
             hop_count: 0,
             message_id: Uuid::new_v4(),
@@ -191,42 +194,14 @@ pub struct TransactionFragment {
 }
 
 impl TransactionFragment {
+    /// Encode in the compact wire format (see [`super::wire`])
     pub fn serialize(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-        bytes.extend_from_slice(&self.transaction_id);
-        bytes.extend_from_slice(&self.fragment_index.to_be_bytes());
-        bytes.extend_from_slice(&self.total_fragments.to_be_bytes());
-        bytes.extend_from_slice(&(self.data.len() as u16).to_be_bytes());
-        bytes.extend_from_slice(&self.data);
-        bytes
+        super::wire::encode_fragment(self)
     }
 
+    /// Decode a wire frame (compact, or either layout from older nodes)
     pub fn deserialize(bytes: &[u8]) -> Result<Self, MeshError> {
-        if bytes.len() < 38 {
-            return Err(MeshError::InvalidPacket(
-                "Fragment payload too short".into(),
-            ));
-        }
-
-        let mut transaction_id = [0u8; 32];
-        transaction_id.copy_from_slice(&bytes[0..32]);
-
-        let fragment_index = u16::from_be_bytes([bytes[32], bytes[33]]);
-        let total_fragments = u16::from_be_bytes([bytes[34], bytes[35]]);
-        let data_len = u16::from_be_bytes([bytes[36], bytes[37]]) as usize;
-
-        if bytes.len() < 38 + data_len {
-            return Err(MeshError::InvalidPacket("Fragment data truncated".into()));
-        }
-
-        let data = bytes[38..38 + data_len].to_vec();
-
-        Ok(Self {
-            transaction_id,
-            fragment_index,
-            total_fragments,
-            data,
-        })
+        super::wire::decode_fragment(bytes)
     }
 }
 
@@ -503,6 +478,15 @@ pub enum MeshError {
 
     #[error("Reassembly failed: {0}")]
     ReassemblyFailed(String),
+
+    #[error("Signature check failed: {0}")]
+    BadSignature(String),
+
+    #[error("Fragment {fragment_index} failed its checksum")]
+    ChecksumMismatch {
+        transaction_id: [u8; 32],
+        fragment_index: u16,
+    },
 }
 
 #[cfg(test)]
@@ -4,20 +4,35 @@
 //! This module contains the protocol structs and algorithms for
 //! fragment reassembly, broadcast preparation, and network health tracking.
 
+pub mod advertisement;
+pub mod batch;
 pub mod broadcaster;
 pub mod control_frames;
 pub mod density;
+pub mod digest;
+pub mod emergency;
+pub mod envelope;
 pub mod fragmenter;
 pub mod health_monitor;
 pub mod mesh;
+pub mod payment_receipt;
+pub mod payment_request;
+pub mod probe;
+pub mod relay_receipt;
+pub mod replay;
+pub mod sealed;
+pub mod wire;
 
 // Fragmenter functions
-pub use fragmenter::{fragment_transaction, reconstruct_transaction, FragmentationStats};
+pub use fragmenter::{
+    fragment_transaction, fragment_transaction_iter, reconstruct_transaction, FragmentIter,
+    FragmentationStats,
+};
 
 // Mesh protocol types
 pub use mesh::{
     MeshError, MeshHeader, MeshPacket, MeshRouter, MeshStats, PacketType, TransactionFragment,
-    DEFAULT_TTL, MAX_FRAGMENTS, MAX_FRAGMENT_DATA, MAX_HOPS, MAX_PAYLOAD_SIZE,
+    DEFAULT_TTL, MAX_FRAGMENTS, MAX_FRAGMENT_DATA, MAX_HOPS, MAX_PAYLOAD_SIZE, PROTOCOL_VERSION,
 };
 
 // Broadcaster types
@@ -36,6 +51,30 @@ pub use density::{AdaptiveParams, CloseReason, CooldownList, DensityEstimator, S
 
 // Control frames (Subsystem 3)
 pub use control_frames::{
-    ConfirmationStatus, ControlFrameType, MeshConfirmation, Tombstone, TxAbortFrame,
-    CONFIRMATION_TTL_SECS,
+    tx_id_hash, CancelReason, ConfirmationStatus, ControlFrameType, FragmentNackFrame,
+    MeshConfirmation, NackReason, Tombstone, TxAbortFrame, TxCancelFrame, CONFIRMATION_TTL_SECS,
+    MAX_TX_RELAY_HOPS,
 };
+
+// Emergency broadcasts
+pub use emergency::{EmergencyBroadcast, EmergencyNotice};
+
+// Offline payment requests
+pub use payment_receipt::{Countersignature, PaymentReceipt, ReceiptFrame};
+pub use payment_request::{PaymentRequest, PaymentRequestInfo};
+
+// Gossip digests
+pub use digest::TxDigest;
+
+// Mesh envelope
+pub use envelope::{HopRecord, PolliNetMessage};
+pub use replay::ReplayWindow;
+
+// Advertisement contents and privacy mode
+pub use advertisement::{Advertisement, PrivacySettings, ScannedAdvertisement};
+
+// Ping / traceroute diagnostics
+pub use probe::{Probe, ProbeHop, ProbeKind, ProbeResult, ProbeTracker};
+
+// Relay receipts (incentive groundwork)
+pub use relay_receipt::{generate_device_key, ReceiptAction, RelayReceipt};
@@ -0,0 +1,268 @@
+//! Signed offline payment receipts
+//!
+//! An offline payment only settles once a gateway submits it, which can be
+//! hours away. Meanwhile the merchant needs something better than "the
+//! phone said it paid". After the payer signs the transaction, its device
+//! signs a compact receipt: the transaction's signature (its on-chain id),
+//! the payment request it answers, recipient and amount. The merchant
+//! device countersigns it and sends the countersignature back, so both
+//! sides hold the same doubly-signed proof. It can later be checked
+//! against the chain by looking up the transaction signature.
+//!
+//! Signatures are made with the devices' keys. The payer's wallet
+//! signature is the transaction signature inside the receipt.
+//!
+//! Frames (type `0x13`, then a kind byte):
+//! - receipt: `0 || request id (16) || tx signature (64) || recipient (32)
+//!   || amount (u64 LE) || issued_at (u64 LE) || payer key (32) ||
+//!   payer signature (64)`
+//! - countersignature: `1 || tx signature (64) || merchant key (32) ||
+//!   merchant signature (64)`
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+use super::control_frames::ControlFrameType;
+
+const KIND_RECEIPT: u8 = 0;
+const KIND_COUNTERSIGN: u8 = 1;
+const RECEIPT_FRAME_LEN: usize = 2 + 16 + 64 + 32 + 8 + 8 + 32 + 64;
+const COUNTERSIGN_FRAME_LEN: usize = 2 + 64 + 32 + 64;
+const PAYER_DOMAIN: &[u8] = b"pollinet-receipt-payer-v1";
+const MERCHANT_DOMAIN: &[u8] = b"pollinet-receipt-merchant-v1";
+
+/// Merchant's countersignature over a payer-signed receipt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Countersignature {
+    pub merchant: [u8; 32],
+    pub signature: [u8; 64],
+}
+
+/// Proof of an offline payment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentReceipt {
+    /// Payment request answered (all zeros when there was none, e.g. a
+    /// Solana Pay QR code)
+    pub request_id: [u8; 16],
+    /// First signature of the signed transaction
+    pub tx_signature: [u8; 64],
+    /// Wallet (SOL) or token account (SPL) paid
+    pub recipient: [u8; 32],
+    /// Lamports or token base units
+    pub amount: u64,
+    /// Unix seconds the payer signed the receipt
+    pub issued_at: u64,
+    /// Payer's device key
+    pub payer: [u8; 32],
+    pub payer_signature: [u8; 64],
+    pub countersignature: Option<Countersignature>,
+}
+
+/// A decoded receipt frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReceiptFrame {
+    /// Payer-signed receipt, for the merchant to countersign
+    Receipt(PaymentReceipt),
+    /// Merchant's countersignature for the receipt of `tx_signature`
+    Countersign {
+        tx_signature: [u8; 64],
+        countersignature: Countersignature,
+    },
+}
+
+impl PaymentReceipt {
+    /// Sign a receipt for a transaction the payer just signed
+    pub fn sign(
+        key: &SigningKey,
+        request_id: [u8; 16],
+        tx_signature: [u8; 64],
+        recipient: [u8; 32],
+        amount: u64,
+        issued_at: u64,
+    ) -> Self {
+        let mut receipt = Self {
+            request_id,
+            tx_signature,
+            recipient,
+            amount,
+            issued_at,
+            payer: key.verifying_key().to_bytes(),
+            payer_signature: [0; 64],
+            countersignature: None,
+        };
+        receipt.payer_signature = key.sign(&receipt.payer_payload()).to_bytes();
+        receipt
+    }
+
+    /// Countersign as the merchant
+    pub fn countersign(&mut self, key: &SigningKey) -> Countersignature {
+        let countersignature = Countersignature {
+            merchant: key.verifying_key().to_bytes(),
+            signature: key.sign(&self.merchant_payload()).to_bytes(),
+        };
+        self.countersignature = Some(countersignature);
+        countersignature
+    }
+
+    /// Attach a countersignature received from the merchant; false if it
+    /// does not verify
+    pub fn apply_countersignature(&mut self, countersignature: Countersignature) -> bool {
+        if !verify(
+            &countersignature.merchant,
+            &self.merchant_payload(),
+            &countersignature.signature,
+        ) {
+            return false;
+        }
+        self.countersignature = Some(countersignature);
+        true
+    }
+
+    /// Whether the payer's signature (and the countersignature, if any) is valid
+    pub fn verify(&self) -> bool {
+        verify(&self.payer, &self.payer_payload(), &self.payer_signature)
+            && self
+                .countersignature
+                .is_none_or(|c| verify(&c.merchant, &self.merchant_payload(), &c.signature))
+    }
+
+    pub fn has_request(&self) -> bool {
+        self.request_id != [0; 16]
+    }
+
+    /// Receipt frame (without the countersignature)
+    pub fn to_frame_bytes(&self) -> Vec<u8> {
+        let mut frame = Vec::with_capacity(RECEIPT_FRAME_LEN);
+        frame.extend_from_slice(&[ControlFrameType::PaymentReceipt as u8, KIND_RECEIPT]);
+        frame.extend_from_slice(&self.signed_fields());
+        frame.extend_from_slice(&self.payer);
+        frame.extend_from_slice(&self.payer_signature);
+        frame
+    }
+
+    /// Countersignature frame, for the merchant to send back
+    pub fn countersign_frame(&self) -> Option<Vec<u8>> {
+        let countersignature = self.countersignature?;
+        let mut frame = Vec::with_capacity(COUNTERSIGN_FRAME_LEN);
+        frame.extend_from_slice(&[ControlFrameType::PaymentReceipt as u8, KIND_COUNTERSIGN]);
+        frame.extend_from_slice(&self.tx_signature);
+        frame.extend_from_slice(&countersignature.merchant);
+        frame.extend_from_slice(&countersignature.signature);
+        Some(frame)
+    }
+
+    /// Fields the payer signs, in frame order
+    fn signed_fields(&self) -> Vec<u8> {
+        let mut fields = Vec::with_capacity(16 + 64 + 32 + 8 + 8);
+        fields.extend_from_slice(&self.request_id);
+        fields.extend_from_slice(&self.tx_signature);
+        fields.extend_from_slice(&self.recipient);
+        fields.extend_from_slice(&self.amount.to_le_bytes());
+        fields.extend_from_slice(&self.issued_at.to_le_bytes());
+        fields
+    }
+
+    fn payer_payload(&self) -> Vec<u8> {
+        [
+            PAYER_DOMAIN,
+            self.signed_fields().as_slice(),
+            &self.payer[..],
+        ]
+        .concat()
+    }
+
+    /// The merchant signs the whole payer-signed receipt
+    fn merchant_payload(&self) -> Vec<u8> {
+        [
+            MERCHANT_DOMAIN,
+            self.signed_fields().as_slice(),
+            &self.payer[..],
+            &self.payer_signature[..],
+        ]
+        .concat()
+    }
+}
+
+impl ReceiptFrame {
+    pub fn from_frame_bytes(data: &[u8]) -> Result<Self, String> {
+        let invalid = || format!("Invalid PAYMENT_RECEIPT frame ({} bytes)", data.len());
+        if data.len() < 2 || data[0] != ControlFrameType::PaymentReceipt as u8 {
+            return Err(invalid());
+        }
+        match data[1] {
+            KIND_RECEIPT if data.len() == RECEIPT_FRAME_LEN => Ok(Self::Receipt(PaymentReceipt {
+                request_id: array(data, 2),
+                tx_signature: array(data, 18),
+                recipient: array(data, 82),
+                amount: u64::from_le_bytes(array(data, 114)),
+                issued_at: u64::from_le_bytes(array(data, 122)),
+                payer: array(data, 130),
+                payer_signature: array(data, 162),
+                countersignature: None,
+            })),
+            KIND_COUNTERSIGN if data.len() == COUNTERSIGN_FRAME_LEN => Ok(Self::Countersign {
+                tx_signature: array(data, 2),
+                countersignature: Countersignature {
+                    merchant: array(data, 66),
+                    signature: array(data, 98),
+                },
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// `N` bytes of `data` from `start` (lengths are checked by the caller)
+fn array<const N: usize>(data: &[u8], start: usize) -> [u8; N] {
+    data[start..start + N].try_into().unwrap()
+}
+
+fn verify(key: &[u8; 32], payload: &[u8], signature: &[u8; 64]) -> bool {
+    VerifyingKey::from_bytes(key).is_ok_and(|key| {
+        key.verify(payload, &Signature::from_bytes(signature))
+            .is_ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_receipt_signed_and_countersigned() {
+        let payer = crate::ble::generate_device_key();
+        let merchant = crate::ble::generate_device_key();
+        let receipt = PaymentReceipt::sign(&payer, [1; 16], [2; 64], [3; 32], 5_000, 1_000);
+        assert!(receipt.verify());
+        assert!(receipt.countersign_frame().is_none());
+
+        let frame = receipt.to_frame_bytes();
+        assert_eq!(frame.len(), RECEIPT_FRAME_LEN);
+        let ReceiptFrame::Receipt(mut received) = ReceiptFrame::from_frame_bytes(&frame).unwrap()
+        else {
+            panic!("expected a receipt");
+        };
+        assert_eq!(received, receipt);
+        received.countersign(&merchant);
+        assert!(received.verify());
+
+        let ReceiptFrame::Countersign {
+            tx_signature,
+            countersignature,
+        } = ReceiptFrame::from_frame_bytes(&received.countersign_frame().unwrap()).unwrap()
+        else {
+            panic!("expected a countersignature");
+        };
+        assert_eq!(tx_signature, receipt.tx_signature);
+        let mut held = receipt.clone();
+        assert!(held.apply_countersignature(countersignature));
+        assert_eq!(held, received);
+
+        // A countersignature only fits the receipt it was made for
+        let mut other = PaymentReceipt::sign(&payer, [1; 16], [2; 64], [3; 32], 1, 1_000);
+        assert!(!other.apply_countersignature(countersignature));
+        let mut tampered = held;
+        tampered.amount = 50_000;
+        assert!(!tampered.verify());
+        assert!(ReceiptFrame::from_frame_bytes(&frame[..frame.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,290 @@
+//! Offline payment requests (invoices)
+//!
+//! A merchant device beams a signed request to the payer at the point of
+//! sale: who to pay, how much, in which token, and until when. The payer's
+//! SDK checks the signature and expiry, then builds the matching
+//! durable-nonce transaction (see
+//! `intent::build_nonce_transfer_transaction`), which the payer signs and
+//! sends back through the mesh. Neither side needs to be online.
+//!
+//! The request is signed with the merchant's device key; the recipient
+//! wallet is a separate field, so one wallet can take payments through many
+//! devices.
+//!
+//! Frame: `0x12 || id (16) || merchant key (32) || recipient (32) ||
+//! has_mint (1) || mint (32, if set) || decimals || amount (u64 LE) ||
+//! expires_at (u64 LE) || memo len || memo || signature (64)`. The
+//! signature covers everything between the type byte and itself.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use super::control_frames::ControlFrameType;
+
+/// Longest memo, in bytes (keeps the frame within one 247-byte MTU)
+pub const MAX_PAYMENT_MEMO: usize = 32;
+/// Requests kept on the payer's side at most
+pub const MAX_PENDING_PAYMENT_REQUESTS: usize = 16;
+
+const SIGNATURE_LEN: usize = 64;
+
+/// Signed request for a SOL or SPL Token payment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentRequest {
+    pub id: [u8; 16],
+    /// Device key of the merchant that issued the request
+    pub merchant: [u8; 32],
+    /// Wallet to pay
+    pub recipient: [u8; 32],
+    /// SPL Token mint; `None` for SOL
+    pub mint: Option<[u8; 32]>,
+    pub decimals: u8,
+    /// Lamports, or the token's smallest unit
+    pub amount: u64,
+    /// Unix seconds after which the request must not be paid
+    pub expires_at: u64,
+    pub memo: Option<String>,
+    pub signature: [u8; 64],
+}
+
+impl PaymentRequest {
+    /// Sign a new request with the merchant's device key
+    pub fn sign(
+        key: &SigningKey,
+        recipient: [u8; 32],
+        amount: u64,
+        mint: Option<([u8; 32], u8)>,
+        memo: Option<String>,
+        expires_at: u64,
+    ) -> Result<Self, String> {
+        if amount == 0 {
+            return Err("Payment amount must be greater than zero".to_string());
+        }
+        if memo.as_ref().is_some_and(|m| m.len() > MAX_PAYMENT_MEMO) {
+            return Err(format!(
+                "Payment memo must be at most {} bytes",
+                MAX_PAYMENT_MEMO
+            ));
+        }
+        let mut request = Self {
+            id: *uuid::Uuid::new_v4().as_bytes(),
+            merchant: key.verifying_key().to_bytes(),
+            recipient,
+            mint: mint.map(|(mint, _)| mint),
+            decimals: mint.map_or(0, |(_, decimals)| decimals),
+            amount,
+            expires_at,
+            memo: memo.filter(|m| !m.is_empty()),
+            signature: [0; 64],
+        };
+        request.signature = key.sign(&request.signable_payload()).to_bytes();
+        Ok(request)
+    }
+
+    /// Whether the merchant's signature is valid
+    pub fn verify(&self) -> bool {
+        VerifyingKey::from_bytes(&self.merchant).is_ok_and(|key| {
+            key.verify(
+                &self.signable_payload(),
+                &Signature::from_bytes(&self.signature),
+            )
+            .is_ok()
+        })
+    }
+
+    pub fn is_expired(&self, now_secs: u64) -> bool {
+        now_secs >= self.expires_at
+    }
+
+    /// Whether a transfer of `amount` to `destination` pays this request.
+    /// For SPL the destination is the recipient's associated token account.
+    pub fn is_paid_by(&self, destination: [u8; 32], amount: u64) -> bool {
+        let expected = match self.mint {
+            Some(mint) => spl_associated_token_account::get_associated_token_address(
+                &Pubkey::new_from_array(self.recipient),
+                &Pubkey::new_from_array(mint),
+            )
+            .to_bytes(),
+            None => self.recipient,
+        };
+        destination == expected && amount == self.amount
+    }
+
+    pub fn to_frame_bytes(&self) -> Vec<u8> {
+        let mut frame = vec![ControlFrameType::PaymentRequest as u8];
+        frame.extend_from_slice(&self.signable_payload());
+        frame.extend_from_slice(&self.signature);
+        frame
+    }
+
+    pub fn from_frame_bytes(data: &[u8]) -> Result<Self, String> {
+        let invalid = || format!("Invalid PAYMENT_REQUEST frame ({} bytes)", data.len());
+        if data.first() != Some(&(ControlFrameType::PaymentRequest as u8)) {
+            return Err(invalid());
+        }
+        let mut reader = Reader {
+            data: &data[1..],
+            pos: 0,
+        };
+        let id = reader.array::<16>().ok_or_else(invalid)?;
+        let merchant = reader.array::<32>().ok_or_else(invalid)?;
+        let recipient = reader.array::<32>().ok_or_else(invalid)?;
+        let mint = match reader.array::<1>().ok_or_else(invalid)? {
+            [0] => None,
+            [1] => Some(reader.array::<32>().ok_or_else(invalid)?),
+            _ => return Err(invalid()),
+        };
+        let [decimals] = reader.array::<1>().ok_or_else(invalid)?;
+        let amount = u64::from_le_bytes(reader.array::<8>().ok_or_else(invalid)?);
+        let expires_at = u64::from_le_bytes(reader.array::<8>().ok_or_else(invalid)?);
+        let [memo_len] = reader.array::<1>().ok_or_else(invalid)?;
+        if memo_len as usize > MAX_PAYMENT_MEMO {
+            return Err(invalid());
+        }
+        let memo = reader.bytes(memo_len as usize).ok_or_else(invalid)?;
+        let memo = std::str::from_utf8(memo)
+            .map_err(|_| "Payment memo is not UTF-8".to_string())?
+            .to_string();
+        let signature = reader.array::<SIGNATURE_LEN>().ok_or_else(invalid)?;
+        if reader.pos != reader.data.len() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            id,
+            merchant,
+            recipient,
+            mint,
+            decimals,
+            amount,
+            expires_at,
+            memo: (!memo.is_empty()).then_some(memo),
+            signature,
+        })
+    }
+
+    fn signable_payload(&self) -> Vec<u8> {
+        let memo = self.memo.as_deref().unwrap_or_default();
+        let mut payload = Vec::with_capacity(16 + 32 + 32 + 33 + 1 + 8 + 8 + 1 + memo.len());
+        payload.extend_from_slice(&self.id);
+        payload.extend_from_slice(&self.merchant);
+        payload.extend_from_slice(&self.recipient);
+        match &self.mint {
+            Some(mint) => {
+                payload.push(1);
+                payload.extend_from_slice(mint);
+            }
+            None => payload.push(0),
+        }
+        payload.push(self.decimals);
+        payload.extend_from_slice(&self.amount.to_le_bytes());
+        payload.extend_from_slice(&self.expires_at.to_le_bytes());
+        payload.push(memo.len() as u8);
+        payload.extend_from_slice(memo.as_bytes());
+        payload
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos + len)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.bytes(N)?.try_into().ok()
+    }
+}
+
+/// Payment request as shown to the app
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentRequestInfo {
+    /// Hex request id
+    pub request_id: String,
+    /// Base58 device key of the merchant
+    pub merchant: String,
+    /// Base58 wallet to pay
+    pub recipient: String,
+    /// Base58 mint; `None` for SOL
+    pub mint: Option<String>,
+    pub decimals: u8,
+    pub amount: u64,
+    pub expires_at: u64,
+    pub memo: Option<String>,
+}
+
+impl From<&PaymentRequest> for PaymentRequestInfo {
+    fn from(request: &PaymentRequest) -> Self {
+        let b58 = |bytes: &[u8; 32]| bs58::encode(bytes).into_string();
+        Self {
+            request_id: hex::encode(request.id),
+            merchant: b58(&request.merchant),
+            recipient: b58(&request.recipient),
+            mint: request.mint.as_ref().map(b58),
+            decimals: request.decimals,
+            amount: request.amount,
+            expires_at: request.expires_at,
+            memo: request.memo.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payment_request_roundtrip_and_tamper() {
+        let merchant = crate::ble::generate_device_key();
+        let recipient = [7u8; 32];
+        assert!(PaymentRequest::sign(&merchant, recipient, 0, None, None, 100).is_err());
+        assert!(
+            PaymentRequest::sign(&merchant, recipient, 1, None, Some("x".repeat(33)), 100).is_err()
+        );
+
+        let sol = PaymentRequest::sign(&merchant, recipient, 5_000, None, None, 100).unwrap();
+        let token = PaymentRequest::sign(
+            &merchant,
+            recipient,
+            2_500_000,
+            Some(([9u8; 32], 6)),
+            Some("Coffee x2".to_string()),
+            100,
+        )
+        .unwrap();
+        assert_ne!(sol.id, token.id);
+        for request in [&sol, &token] {
+            let frame = request.to_frame_bytes();
+            assert!(frame.len() <= 244);
+            let decoded = PaymentRequest::from_frame_bytes(&frame).unwrap();
+            assert_eq!(&decoded, request);
+            assert!(decoded.verify());
+            assert!(PaymentRequest::from_frame_bytes(&frame[..frame.len() - 1]).is_err());
+        }
+        assert!(!sol.is_expired(99));
+        assert!(sol.is_expired(100));
+        assert!(sol.is_paid_by(recipient, 5_000));
+        assert!(!sol.is_paid_by(recipient, 4_999));
+        // Token payments go to the recipient's associated token account
+        assert!(!token.is_paid_by(recipient, 2_500_000));
+        let ata = spl_associated_token_account::get_associated_token_address(
+            &Pubkey::new_from_array(recipient),
+            &Pubkey::new_from_array([9; 32]),
+        );
+        assert!(token.is_paid_by(ata.to_bytes(), 2_500_000));
+
+        let mut tampered = token.clone();
+        tampered.amount = 1;
+        assert!(!tampered.verify());
+        let info = PaymentRequestInfo::from(&token);
+        assert_eq!(info.mint, Some(bs58::encode([9u8; 32]).into_string()));
+        assert_eq!(info.memo.as_deref(), Some("Coffee x2"));
+    }
+}
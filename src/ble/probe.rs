@@ -0,0 +1,286 @@
+//! Mesh ping and traceroute diagnostics
+//!
+//! Probes travel as `Ping` / `Pong` mesh packets. A ping is answered by the
+//! first device that hears it, which checks a direct link. A traceroute
+//! names a target node: every relay on the way appends a hop record with
+//! its node id and clock, and the target answers with the full list, so a
+//! technician can see which path a transaction would take toward a gateway
+//! and where the time goes.
+//!
+//! The round trip is measured on the origin's clock alone. Per-hop latency
+//! compares clocks of neighbouring devices, so it is only as good as their
+//! time sync (phones on network time are usually within tens of ms).
+//!
+//! Payload: kind (1), target node (4), sent-at ms (u64 LE), hop count (1),
+//! then per hop: node (4), at ms (u64 LE).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use super::mesh::{MeshError, MeshPacket, PacketType, MAX_HOPS};
+
+/// Short node id carried in probes (first 4 bytes of the device key)
+pub type NodeId = [u8; 4];
+
+/// Target of a ping: whichever neighbour hears it
+pub const ANY_NODE: NodeId = [0; 4];
+/// Probes waiting for a pong before they are reported lost (seconds)
+pub const PROBE_TIMEOUT_SECS: u64 = 30;
+
+/// Probes in flight at most
+const MAX_PENDING_PROBES: usize = 32;
+/// Answered probes kept for retrieval
+const MAX_PROBE_RESULTS: usize = 32;
+/// Relayed probe ids remembered, so each is forwarded once
+const MAX_SEEN_PROBES: usize = 256;
+
+const HOP_LEN: usize = 4 + 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProbeKind {
+    Ping,
+    Traceroute,
+}
+
+/// Ping or pong packet body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Probe {
+    pub kind: ProbeKind,
+    pub target: NodeId,
+    /// Origin's clock when sent (unix ms)
+    pub sent_at_ms: u64,
+    /// Relays passed and, last in a pong, the node that answered
+    pub hops: Vec<(NodeId, u64)>,
+}
+
+impl Probe {
+    pub fn ping(sent_at_ms: u64) -> Self {
+        Self {
+            kind: ProbeKind::Ping,
+            target: ANY_NODE,
+            sent_at_ms,
+            hops: Vec::new(),
+        }
+    }
+
+    pub fn traceroute(target: NodeId, sent_at_ms: u64) -> Self {
+        Self {
+            kind: ProbeKind::Traceroute,
+            target,
+            sent_at_ms,
+            hops: Vec::new(),
+        }
+    }
+
+    /// Whether `node` should answer this probe
+    pub fn is_for(&self, node: &NodeId) -> bool {
+        self.target == ANY_NODE || &self.target == node
+    }
+
+    /// Record a relay (or the answering node); false once the hop list is full
+    pub fn add_hop(&mut self, node: NodeId, at_ms: u64) -> bool {
+        if self.hops.len() >= MAX_HOPS as usize {
+            return false;
+        }
+        self.hops.push((node, at_ms));
+        true
+    }
+
+    /// Build the mesh packet; traceroutes may be relayed, pings can't
+    pub fn to_packet(&self, packet_type: PacketType, probe_id: Uuid, sender_id: Uuid) -> Vec<u8> {
+        let mut packet = MeshPacket::new(packet_type, sender_id, self.encode());
+        packet.header.message_id = probe_id;
+        if self.kind == ProbeKind::Ping {
+            packet.header.ttl = 1;
+        }
+        packet.serialize()
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(14 + self.hops.len() * HOP_LEN);
+        payload.push(match self.kind {
+            ProbeKind::Ping => 0,
+            ProbeKind::Traceroute => 1,
+        });
+        payload.extend_from_slice(&self.target);
+        payload.extend_from_slice(&self.sent_at_ms.to_le_bytes());
+        payload.push(self.hops.len() as u8);
+        for (node, at_ms) in &self.hops {
+            payload.extend_from_slice(node);
+            payload.extend_from_slice(&at_ms.to_le_bytes());
+        }
+        payload
+    }
+
+    pub fn decode(payload: &[u8]) -> Result<Self, MeshError> {
+        let invalid = |msg: &str| MeshError::InvalidPacket(format!("Probe: {}", msg));
+
+        if payload.len() < 14 {
+            return Err(invalid("too short"));
+        }
+        let kind = match payload[0] {
+            0 => ProbeKind::Ping,
+            1 => ProbeKind::Traceroute,
+            other => return Err(invalid(&format!("unknown kind {}", other))),
+        };
+        let hop_count = payload[13] as usize;
+        if hop_count > MAX_HOPS as usize || payload.len() != 14 + hop_count * HOP_LEN {
+            return Err(invalid("bad hop list"));
+        }
+        let hops = payload[14..]
+            .chunks_exact(HOP_LEN)
+            .map(|hop| {
+                let node: NodeId = hop[..4].try_into().unwrap();
+                (node, u64::from_le_bytes(hop[4..].try_into().unwrap()))
+            })
+            .collect();
+
+        Ok(Self {
+            kind,
+            target: payload[1..5].try_into().unwrap(),
+            sent_at_ms: u64::from_le_bytes(payload[5..13].try_into().unwrap()),
+            hops,
+        })
+    }
+
+    /// Result of a pong received at `now_ms` on the origin's clock
+    pub fn result(&self, probe_id: &str, peer_id: &str, now_ms: u64) -> ProbeResult {
+        let mut previous = self.sent_at_ms;
+        let hops = self
+            .hops
+            .iter()
+            .map(|(node, at_ms)| {
+                let latency_ms = at_ms.saturating_sub(previous);
+                previous = *at_ms;
+                ProbeHop {
+                    node: hex::encode(node),
+                    latency_ms,
+                }
+            })
+            .collect();
+        ProbeResult {
+            probe_id: probe_id.to_string(),
+            kind: self.kind,
+            peer_id: peer_id.to_string(),
+            rtt_ms: now_ms.saturating_sub(self.sent_at_ms),
+            hops,
+        }
+    }
+}
+
+/// One device on a probed path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeHop {
+    /// Hex short node id
+    pub node: String,
+    /// Time since the previous hop (clock skew between devices included)
+    pub latency_ms: u64,
+}
+
+/// Answered ping or traceroute
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeResult {
+    pub probe_id: String,
+    pub kind: ProbeKind,
+    /// Peer address pinged, or target device key traced
+    pub peer_id: String,
+    /// Round trip on the origin's clock
+    pub rtt_ms: u64,
+    /// Relays then the answering node, nearest first
+    pub hops: Vec<ProbeHop>,
+}
+
+/// Probes this device sent and the packets it already relayed
+#[derive(Debug, Default)]
+pub struct ProbeTracker {
+    /// Probe id → (peer or target, sent)
+    pending: HashMap<Uuid, (String, Instant)>,
+    results: VecDeque<ProbeResult>,
+    seen: VecDeque<(Uuid, PacketType)>,
+}
+
+impl ProbeTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a probe sent to `peer_id`, forgetting timed-out ones
+    pub fn start(&mut self, probe_id: Uuid, peer_id: &str) -> Result<(), String> {
+        let timeout = Duration::from_secs(PROBE_TIMEOUT_SECS);
+        self.pending.retain(|_, (_, sent)| sent.elapsed() < timeout);
+        if self.pending.len() >= MAX_PENDING_PROBES {
+            return Err(format!("{} probes already in flight", MAX_PENDING_PROBES));
+        }
+        self.pending
+            .insert(probe_id, (peer_id.to_string(), Instant::now()));
+        self.seen(probe_id, PacketType::Ping);
+        Ok(())
+    }
+
+    /// Match a pong to a probe we sent; the result is kept for retrieval
+    pub fn finish(&mut self, probe_id: Uuid, pong: &Probe, now_ms: u64) -> Option<ProbeResult> {
+        let (peer_id, _) = self.pending.remove(&probe_id)?;
+        let result = pong.result(&probe_id.to_string(), &peer_id, now_ms);
+        if self.results.len() >= MAX_PROBE_RESULTS {
+            self.results.pop_front();
+        }
+        self.results.push_back(result.clone());
+        Some(result)
+    }
+
+    pub fn result(&self, probe_id: &str) -> Option<ProbeResult> {
+        self.results
+            .iter()
+            .find(|result| result.probe_id == probe_id)
+            .cloned()
+    }
+
+    pub fn is_pending(&self, probe_id: &Uuid) -> bool {
+        self.pending.contains_key(probe_id)
+    }
+
+    /// Record a packet; false if it was seen before
+    pub fn seen(&mut self, probe_id: Uuid, packet_type: PacketType) -> bool {
+        if self.seen.contains(&(probe_id, packet_type)) {
+            return false;
+        }
+        if self.seen.len() >= MAX_SEEN_PROBES {
+            self.seen.pop_front();
+        }
+        self.seen.push_back((probe_id, packet_type));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traceroute_records_hops_and_latency() {
+        let mut probe = Probe::traceroute([9, 9, 9, 9], 1_000);
+        assert!(!probe.is_for(&[1, 1, 1, 1]));
+        assert!(probe.add_hop([1, 1, 1, 1], 1_040));
+        assert!(probe.add_hop([9, 9, 9, 9], 1_100));
+
+        let frame = probe.to_packet(PacketType::Pong, Uuid::new_v4(), Uuid::new_v4());
+        let packet = MeshPacket::deserialize(&frame).unwrap();
+        assert_eq!(packet.header.packet_type, PacketType::Pong);
+        let decoded = Probe::decode(&packet.payload).unwrap();
+        assert_eq!(decoded, probe);
+
+        let result = decoded.result("id", "target", 1_250);
+        assert_eq!(result.rtt_ms, 250);
+        let latencies: Vec<u64> = result.hops.iter().map(|h| h.latency_ms).collect();
+        assert_eq!(latencies, vec![40, 60]);
+        assert_eq!(result.hops[0].node, "01010101");
+
+        assert!(Probe::ping(0).is_for(&[1, 2, 3, 4]));
+        assert!(Probe::decode(&packet.payload[..20]).is_err());
+    }
+}
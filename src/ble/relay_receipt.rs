@@ -0,0 +1,160 @@
+//! Signed relay receipts
+//!
+//! When a device accepts a transaction to carry it, or submits it as a
+//! gateway, it signs a [`RelayReceipt`] with its device key over the
+//! transaction hash, what it did and when. The receipt travels back toward the
+//! origin the same way the transaction came, so the origin (and every relay on
+//! the way) ends up with verifiable proof of who helped. Nothing rewards
+//! relayers yet; receipts are groundwork for an incentive layer.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use super::control_frames::{ControlFrameType, MAX_TX_RELAY_HOPS};
+
+/// What the signing device did with the transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[repr(u8)]
+pub enum ReceiptAction {
+    /// Accepted from a peer to carry through the mesh
+    Relayed = 1,
+    /// Submitted to the network as a gateway
+    Submitted = 2,
+}
+
+impl ReceiptAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReceiptAction::Relayed => "relayed",
+            ReceiptAction::Submitted => "submitted",
+        }
+    }
+}
+
+/// Device-signed proof that a relay carried or submitted a transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayReceipt {
+    /// First 16 bytes of SHA-256(tx_id_hex), as in confirmations
+    pub tx_id_hash: [u8; 16],
+    pub action: ReceiptAction,
+    /// Unix timestamp (seconds) when the action happened
+    pub timestamp: u64,
+    /// Ed25519 verifying key of the signing device
+    pub relayer: [u8; 32],
+    /// Ed25519 signature over `signable_payload()` (64 bytes)
+    pub signature: Vec<u8>,
+    /// Hops travelled back toward the origin, capped at MAX_TX_RELAY_HOPS
+    pub hop_count: u8,
+}
+
+impl RelayReceipt {
+    /// Sign a receipt for `tx_id_hash` with this device's key
+    pub fn sign(
+        key: &SigningKey,
+        tx_id_hash: [u8; 16],
+        action: ReceiptAction,
+        timestamp: u64,
+    ) -> Self {
+        let mut receipt = Self {
+            tx_id_hash,
+            action,
+            timestamp,
+            relayer: key.verifying_key().to_bytes(),
+            signature: Vec::new(),
+            hop_count: 0,
+        };
+        receipt.signature = key.sign(&receipt.signable_payload()).to_bytes().to_vec();
+        receipt
+    }
+
+    /// Serialize the signable payload: tx_id_hash || action_byte || timestamp (LE)
+    pub fn signable_payload(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(16 + 1 + 8);
+        buf.extend_from_slice(&self.tx_id_hash);
+        buf.push(self.action as u8);
+        buf.extend_from_slice(&self.timestamp.to_le_bytes());
+        buf
+    }
+
+    /// Verify the signature against the embedded relayer key.
+    /// Silently returns false on any error.
+    pub fn verify(&self) -> bool {
+        let Ok(vk) = VerifyingKey::from_bytes(&self.relayer) else {
+            return false;
+        };
+        let Ok(sig_bytes) = <[u8; 64]>::try_from(self.signature.as_slice()) else {
+            return false;
+        };
+        vk.verify(&self.signable_payload(), &Signature::from_bytes(&sig_bytes))
+            .is_ok()
+    }
+
+    /// Count one more hop toward the origin. Returns false (and leaves the
+    /// count unchanged) once MAX_TX_RELAY_HOPS is reached.
+    pub fn increment_hop(&mut self) -> bool {
+        if self.hop_count >= MAX_TX_RELAY_HOPS {
+            return false;
+        }
+        self.hop_count += 1;
+        true
+    }
+
+    /// Frame for BLE: `0x14` followed by the bincode v1 encoding
+    pub fn to_frame_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut frame = vec![ControlFrameType::RelayReceipt as u8];
+        bincode1::serialize_into(&mut frame, self)
+            .map_err(|e| format!("Receipt serialize: {}", e))?;
+        Ok(frame)
+    }
+
+    /// Decode a frame made by [`Self::to_frame_bytes`]
+    pub fn from_frame_bytes(data: &[u8]) -> Result<Self, String> {
+        match data.split_first() {
+            Some((&kind, payload)) if kind == ControlFrameType::RelayReceipt as u8 => {
+                bincode1::deserialize(payload).map_err(|e| format!("Receipt deserialize: {}", e))
+            }
+            _ => Err(format!(
+                "Invalid RELAY_RECEIPT frame ({} bytes)",
+                data.len()
+            )),
+        }
+    }
+}
+
+/// Generate a fresh device signing key
+pub fn generate_device_key() -> SigningKey {
+    use rand::RngCore;
+    let mut secret = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut secret);
+    SigningKey::from_bytes(&secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_receipt_roundtrip_and_tamper_detection() {
+        let key = generate_device_key();
+        let receipt = RelayReceipt::sign(&key, [7u8; 16], ReceiptAction::Relayed, 1_700_000_000);
+        assert!(receipt.verify());
+
+        let frame = receipt.to_frame_bytes().unwrap();
+        assert_eq!(frame[0], ControlFrameType::RelayReceipt as u8);
+        assert!(RelayReceipt::from_frame_bytes(&frame[1..]).is_err());
+        let mut decoded = RelayReceipt::from_frame_bytes(&frame).unwrap();
+        assert_eq!(decoded, receipt);
+        // Hop count is not signed, so carriers can bump it
+        assert!(decoded.increment_hop());
+        assert!(decoded.verify());
+
+        let mut tampered = receipt.clone();
+        tampered.action = ReceiptAction::Submitted;
+        assert!(!tampered.verify());
+
+        let mut forged = receipt;
+        forged.relayer = generate_device_key().verifying_key().to_bytes();
+        assert!(!forged.verify());
+    }
+}
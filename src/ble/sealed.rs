@@ -0,0 +1,139 @@
+//! Transactions sealed for one gateway
+//!
+//! A relay that reassembles a plain transaction learns who sent it (the fee
+//! payer and signers) and what it does (amounts, recipients). In sealed mode
+//! the origin encrypts the transaction to a chosen gateway's device key;
+//! relays carry an opaque blob whose only readable field is a routing hint
+//! (the gateway's 4-byte short id). The gateway opens it and submits the
+//! transaction as usual, so it still sees everything.
+//!
+//! Encryption: an ephemeral Ed25519 key is generated per transaction, both
+//! keys are mapped to X25519, and the shared secret is hashed with both
+//! public keys into an AES-256-GCM key.
+//!
+//! Layout: magic (4), gateway short id (4), ephemeral key (32), nonce (12),
+//! ciphertext (transaction + 16-byte tag).
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+use super::probe::NodeId;
+
+/// Marks a sealed transaction; no bincode transaction or zstd frame starts
+/// with it
+pub const SEALED_MAGIC: [u8; 4] = *b"PNS\x01";
+/// Bytes added to a transaction by sealing
+pub const SEALED_OVERHEAD: usize = HEADER_LEN + 16;
+
+const HEADER_LEN: usize = 4 + 4 + 32 + 12;
+const KDF_DOMAIN: &[u8] = b"pollinet-sealed-v1";
+
+/// Whether `bytes` is a sealed transaction
+pub fn is_sealed(bytes: &[u8]) -> bool {
+    bytes.len() > HEADER_LEN && bytes.starts_with(&SEALED_MAGIC)
+}
+
+/// Gateway a sealed transaction is addressed to
+pub fn routing_hint(bytes: &[u8]) -> Option<NodeId> {
+    is_sealed(bytes).then(|| bytes[4..8].try_into().unwrap())
+}
+
+/// Encrypt `tx_bytes` so only the holder of `gateway`'s key can read it
+pub fn seal(tx_bytes: &[u8], gateway: &VerifyingKey) -> Result<Vec<u8>, String> {
+    let ephemeral = super::generate_device_key();
+    let ephemeral_public = ephemeral.verifying_key().to_bytes();
+    let shared = gateway
+        .to_montgomery()
+        .mul_clamped(ephemeral.to_scalar_bytes());
+    let cipher = cipher(&shared.to_bytes(), &ephemeral_public, &gateway.to_bytes());
+
+    let gateway_bytes = gateway.to_bytes();
+    let mut sealed = Vec::with_capacity(tx_bytes.len() + SEALED_OVERHEAD);
+    sealed.extend_from_slice(&SEALED_MAGIC);
+    sealed.extend_from_slice(&gateway_bytes[..4]);
+    sealed.extend_from_slice(&ephemeral_public);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    sealed.extend_from_slice(&nonce);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: tx_bytes,
+                aad: &sealed[..8],
+            },
+        )
+        .map_err(|e| format!("Failed to seal transaction: {}", e))?;
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypt a transaction sealed for `key`
+pub fn open(sealed: &[u8], key: &SigningKey) -> Result<Vec<u8>, String> {
+    if !is_sealed(sealed) {
+        return Err("Not a sealed transaction".to_string());
+    }
+    let public = key.verifying_key().to_bytes();
+    if sealed[4..8] != public[..4] {
+        return Err("Sealed for another gateway".to_string());
+    }
+    let ephemeral: [u8; 32] = sealed[8..40].try_into().unwrap();
+    let ephemeral_key =
+        VerifyingKey::from_bytes(&ephemeral).map_err(|_| "Invalid ephemeral key".to_string())?;
+    let shared = ephemeral_key
+        .to_montgomery()
+        .mul_clamped(key.to_scalar_bytes());
+    cipher(&shared.to_bytes(), &ephemeral, &public)
+        .decrypt(
+            Nonce::from_slice(&sealed[40..HEADER_LEN]),
+            Payload {
+                msg: &sealed[HEADER_LEN..],
+                aad: &sealed[..8],
+            },
+        )
+        .map_err(|_| "Failed to open sealed transaction".to_string())
+}
+
+fn cipher(shared: &[u8; 32], ephemeral: &[u8; 32], gateway: &[u8; 32]) -> Aes256Gcm {
+    let key = Sha256::new()
+        .chain_update(KDF_DOMAIN)
+        .chain_update(shared)
+        .chain_update(ephemeral)
+        .chain_update(gateway)
+        .finalize();
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_addressed_gateway_opens() {
+        let gateway = crate::ble::generate_device_key();
+        let relay = crate::ble::generate_device_key();
+        let tx = b"transfer 5 SOL to someone".to_vec();
+
+        let sealed = seal(&tx, &gateway.verifying_key()).unwrap();
+        assert!(is_sealed(&sealed));
+        assert_eq!(sealed.len(), tx.len() + SEALED_OVERHEAD);
+        assert!(!sealed.windows(tx.len()).any(|w| w == tx.as_slice()));
+        assert_eq!(
+            routing_hint(&sealed).unwrap(),
+            gateway.verifying_key().to_bytes()[..4]
+        );
+
+        assert_eq!(open(&sealed, &gateway).unwrap(), tx);
+        assert!(open(&sealed, &relay).is_err());
+
+        // The routing hint is authenticated
+        let mut rerouted = sealed.clone();
+        rerouted[4..8].copy_from_slice(&relay.verifying_key().to_bytes()[..4]);
+        assert!(open(&rerouted, &relay).is_err());
+        let mut tampered = sealed;
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(open(&tampered, &gateway).is_err());
+        assert!(!is_sealed(&tx));
+    }
+}
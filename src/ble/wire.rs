@@ -0,0 +1,371 @@
+//! Compact binary wire format for transaction fragments
+//!
+//! Every fragment frame sent over a link is:
+//!
+//! | bytes  | field                                              |
+//! |--------|----------------------------------------------------|
+//! | 1      | frame type (`PacketType::TransactionFragment`)     |
+//! | 32     | transaction id                                     |
+//! | varint | fragment index                                     |
+//! | varint | total fragments                                    |
+//! | varint | payload length                                     |
+//! | 4      | CRC32C of the payload (LE)                         |
+//! | n      | payload                                            |
+//! | 0 / 32 | origin device key (first fragment only)            |
+//!
+//! The first fragment of a transaction may end with the Ed25519 key of the
+//! device that originated it; the CRC then covers payload and key. Relays
+//! keep that key to check the signature of a later TX_CANCEL.
+//!
+//! Varints are unsigned LEB128. The CRC lets a receiver reject a corrupted
+//! fragment as soon as it arrives, rather than after the whole transaction
+//! fails its SHA-256 check at reassembly. The leading type byte puts fragments in the
+//! same first-byte space as control frames (0x08 and up), so hosts can route a
+//! frame without trying to decode it.
+//!
+//! Nodes from before this format can't read it. They sent fragments in one
+//! of two layouts, both still decoded here: the bincode encoding of
+//! `TransactionFragment` (what their transport put on the wire) and the
+//! big-endian `id || index || total || length || payload` layout of their
+//! `TransactionFragment::serialize`. Frames for a peer that hasn't
+//! advertised [`crate::role::capability::COMPACT_FRAMES`] are sent with
+//! [`encode_legacy_fragment`].
+
+use super::mesh::{MeshError, PacketType, TransactionFragment};
+
+/// First byte of every fragment frame
+pub const FRAGMENT_FRAME_TYPE: u8 = PacketType::TransactionFragment as u8;
+
+/// Largest header a fragment of up to `MAX_FRAGMENT_PAYLOAD_CEILING` bytes
+/// can have: type + id + three varints of at most 3 bytes + CRC
+pub const MAX_FRAGMENT_OVERHEAD: usize = 1 + 32 + 3 + 3 + 3 + 4;
+
+/// Bytes the origin key adds to a first fragment
+pub const ORIGIN_KEY_LEN: usize = 32;
+
+const ID_END: usize = 33;
+const LEGACY_BINCODE_OVERHEAD: usize = 32 + 2 + 2 + 8;
+const LEGACY_BE_OVERHEAD: usize = 32 + 2 + 2 + 2;
+
+/// Encode a fragment as a wire frame
+pub fn encode_fragment(fragment: &TransactionFragment) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(MAX_FRAGMENT_OVERHEAD + fragment.data.len());
+    frame.push(FRAGMENT_FRAME_TYPE);
+    frame.extend_from_slice(&fragment.transaction_id);
+    put_varint(&mut frame, fragment.fragment_index as u64);
+    put_varint(&mut frame, fragment.total_fragments as u64);
+    put_varint(&mut frame, fragment.data.len() as u64);
+    frame.extend_from_slice(&crc32c(&fragment.data).to_le_bytes());
+    frame.extend_from_slice(&fragment.data);
+    frame
+}
+
+/// Encode the first fragment of a transaction with the key of the device
+/// that originated it
+pub fn encode_first_fragment(fragment: &TransactionFragment, origin: &[u8; 32]) -> Vec<u8> {
+    let mut frame = encode_fragment(fragment);
+    let crc_start = frame.len() - fragment.data.len() - 4;
+    frame.extend_from_slice(origin);
+    let crc = crc32c(&frame[crc_start + 4..]);
+    frame[crc_start..crc_start + 4].copy_from_slice(&crc.to_le_bytes());
+    frame
+}
+
+/// Decode a wire frame, falling back to the two legacy layouts.
+///
+/// A well-formed frame whose payload fails the CRC yields
+/// [`MeshError::ChecksumMismatch`].
+pub fn decode_fragment(frame: &[u8]) -> Result<TransactionFragment, MeshError> {
+    match decode_compact(frame) {
+        Ok(fragment) => Ok(fragment),
+        Err(e @ MeshError::ChecksumMismatch { .. }) => Err(e),
+        Err(e) => decode_legacy_bincode(frame)
+            .or_else(|| decode_legacy_be(frame))
+            .ok_or(e),
+    }
+}
+
+/// Encode a fragment for a node that predates the compact format (the
+/// bincode encoding their transport sent and decoded)
+pub fn encode_legacy_fragment(fragment: &TransactionFragment) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(LEGACY_BINCODE_OVERHEAD + fragment.data.len());
+    frame.extend_from_slice(&fragment.transaction_id);
+    frame.extend_from_slice(&fragment.fragment_index.to_le_bytes());
+    frame.extend_from_slice(&fragment.total_fragments.to_le_bytes());
+    frame.extend_from_slice(&(fragment.data.len() as u64).to_le_bytes());
+    frame.extend_from_slice(&fragment.data);
+    frame
+}
+
+/// Whether `frame` is a fragment in the compact format (so its sender
+/// understands it)
+pub fn is_compact_fragment(frame: &[u8]) -> bool {
+    decode_compact(frame).is_ok()
+}
+
+/// Origin device key carried by a compact first fragment, if any
+pub fn fragment_origin(frame: &[u8]) -> Option<[u8; 32]> {
+    decode_compact_with_origin(frame).ok()?.1
+}
+
+/// Re-encode a compact fragment frame for a legacy peer; other frames are
+/// returned unchanged
+pub fn to_legacy_frame(frame: Vec<u8>) -> Vec<u8> {
+    match decode_compact(&frame) {
+        Ok(fragment) => encode_legacy_fragment(&fragment),
+        Err(_) => frame,
+    }
+}
+
+/// bincode v1: id (32) || index (u16 LE) || total (u16 LE) || length (u64 LE) || payload
+fn decode_legacy_bincode(frame: &[u8]) -> Option<TransactionFragment> {
+    let data_len = u64::from_le_bytes(frame.get(36..44)?.try_into().ok()?);
+    if (frame.len() as u64).checked_sub(LEGACY_BINCODE_OVERHEAD as u64) != Some(data_len) {
+        return None;
+    }
+    Some(TransactionFragment {
+        transaction_id: frame[..32].try_into().ok()?,
+        fragment_index: u16::from_le_bytes([frame[32], frame[33]]),
+        total_fragments: u16::from_le_bytes([frame[34], frame[35]]),
+        data: frame[44..].to_vec(),
+    })
+}
+
+/// id (32) || index (u16 BE) || total (u16 BE) || length (u16 BE) || payload
+fn decode_legacy_be(frame: &[u8]) -> Option<TransactionFragment> {
+    let data_len = u16::from_be_bytes(frame.get(36..38)?.try_into().ok()?) as usize;
+    if frame.len() != LEGACY_BE_OVERHEAD + data_len {
+        return None;
+    }
+    Some(TransactionFragment {
+        transaction_id: frame[..32].try_into().ok()?,
+        fragment_index: u16::from_be_bytes([frame[32], frame[33]]),
+        total_fragments: u16::from_be_bytes([frame[34], frame[35]]),
+        data: frame[38..].to_vec(),
+    })
+}
+
+/// Transaction id of a frame in the compact format, without decoding the rest
+pub fn frame_transaction_id(frame: &[u8]) -> Option<[u8; 32]> {
+    if frame.len() < ID_END || frame[0] != FRAGMENT_FRAME_TYPE {
+        return None;
+    }
+    frame[1..ID_END].try_into().ok()
+}
+
+fn decode_compact(frame: &[u8]) -> Result<TransactionFragment, MeshError> {
+    decode_compact_with_origin(frame).map(|(fragment, _)| fragment)
+}
+
+fn decode_compact_with_origin(
+    frame: &[u8],
+) -> Result<(TransactionFragment, Option<[u8; 32]>), MeshError> {
+    let transaction_id = frame_transaction_id(frame)
+        .ok_or_else(|| MeshError::InvalidPacket("Not a fragment frame".into()))?;
+
+    let mut pos = ID_END;
+    let mut field = |name: &str| -> Result<u64, MeshError> {
+        read_varint(frame, &mut pos)
+            .ok_or_else(|| MeshError::InvalidPacket(format!("Truncated fragment {}", name)))
+    };
+    let fragment_index = field("index")?;
+    let total_fragments = field("total")?;
+    let data_len = field("length")?;
+
+    let to_u16 = |value: u64, name: &str| {
+        u16::try_from(value)
+            .map_err(|_| MeshError::InvalidFragment(format!("Fragment {} out of range", name)))
+    };
+    let fragment_index = to_u16(fragment_index, "index")?;
+    let total_fragments = to_u16(total_fragments, "total")?;
+
+    let crc_end = pos + 4;
+    let crc: [u8; 4] = frame
+        .get(pos..crc_end)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| MeshError::InvalidPacket("Truncated fragment checksum".into()))?;
+
+    // The length must account for the rest of the frame exactly, but for
+    // the origin key a first fragment may carry
+    let rest = &frame[crc_end..];
+    let length_error = || {
+        MeshError::InvalidPacket(format!(
+            "Fragment length {} does not match {} payload bytes",
+            data_len,
+            rest.len()
+        ))
+    };
+    // Untrusted: compared in u64 so a huge length can't overflow or wrap
+    if data_len > rest.len() as u64 {
+        return Err(length_error());
+    }
+    let data_len = data_len as usize;
+    let has_origin = fragment_index == 0 && rest.len() - data_len == ORIGIN_KEY_LEN;
+    if rest.len() != data_len && !has_origin {
+        return Err(length_error());
+    }
+    if crc32c(rest) != u32::from_le_bytes(crc) {
+        return Err(MeshError::ChecksumMismatch {
+            transaction_id,
+            fragment_index,
+        });
+    }
+
+    let (data, origin) = rest.split_at(data_len);
+    let fragment = TransactionFragment {
+        transaction_id,
+        fragment_index,
+        total_fragments,
+        data: data.to_vec(),
+    };
+    Ok((fragment, origin.try_into().ok()))
+}
+
+/// CRC32C (Castagnoli), as used by iSCSI and ext4
+pub fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc = CRC32C_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+const CRC32C_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0x82F6_3B78
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+pub(crate) fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+pub(crate) fn read_varint(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_frames_round_trip_and_accept_legacy() {
+        let fragment = TransactionFragment {
+            transaction_id: [7u8; 32],
+            fragment_index: 200,
+            total_fragments: 300,
+            data: vec![0xAB; 468],
+        };
+
+        let frame = encode_fragment(&fragment);
+        assert_eq!(frame[0], FRAGMENT_FRAME_TYPE);
+        assert_eq!(frame_transaction_id(&frame), Some([7u8; 32]));
+        // 1 + 32 + 2 + 2 + 2 + 4 bytes of header; bincode spends 44
+        assert_eq!(frame.len(), 43 + 468);
+        let legacy = bincode1::serialize(&fragment).unwrap();
+        assert_eq!(encode_legacy_fragment(&fragment), legacy);
+        assert_eq!(to_legacy_frame(frame.clone()), legacy);
+        assert!(frame.len() < legacy.len());
+
+        // TransactionFragment::serialize from before the compact format
+        let mut big_endian = fragment.transaction_id.to_vec();
+        big_endian.extend_from_slice(&200u16.to_be_bytes());
+        big_endian.extend_from_slice(&300u16.to_be_bytes());
+        big_endian.extend_from_slice(&468u16.to_be_bytes());
+        big_endian.extend_from_slice(&fragment.data);
+
+        for bytes in [&frame, &legacy, &big_endian] {
+            let decoded = decode_fragment(bytes).unwrap();
+            assert_eq!(decoded.transaction_id, fragment.transaction_id);
+            assert_eq!(decoded.fragment_index, 200);
+            assert_eq!(decoded.total_fragments, 300);
+            assert_eq!(decoded.data, fragment.data);
+        }
+
+        assert!(decode_fragment(&frame[..frame.len() - 1]).is_err());
+        assert!(decode_fragment(&[FRAGMENT_FRAME_TYPE; 10]).is_err());
+
+        // A flipped payload bit is caught by the CRC
+        assert_eq!(crc32c(b"123456789"), 0xE306_9283);
+        let mut corrupt = frame.clone();
+        *corrupt.last_mut().unwrap() ^= 0x01;
+        assert!(matches!(
+            decode_fragment(&corrupt),
+            Err(MeshError::ChecksumMismatch {
+                fragment_index: 200,
+                ..
+            })
+        ));
+    }
+    #[test]
+    fn test_first_fragment_carries_origin_key() {
+        let fragment = TransactionFragment {
+            transaction_id: [7u8; 32],
+            fragment_index: 0,
+            total_fragments: 2,
+            data: vec![0xAB; 100],
+        };
+        let frame = encode_first_fragment(&fragment, &[9u8; 32]);
+        assert_eq!(
+            frame.len(),
+            encode_fragment(&fragment).len() + ORIGIN_KEY_LEN
+        );
+        assert_eq!(decode_fragment(&frame).unwrap().data, fragment.data);
+        assert_eq!(fragment_origin(&frame), Some([9u8; 32]));
+        assert_eq!(fragment_origin(&encode_fragment(&fragment)), None);
+
+        // The key is covered by the CRC
+        let mut corrupt = frame.clone();
+        *corrupt.last_mut().unwrap() ^= 0x01;
+        assert!(fragment_origin(&corrupt).is_none());
+
+        // Only the first fragment may carry one
+        let mut later = fragment.clone();
+        later.fragment_index = 1;
+        assert!(decode_fragment(&encode_first_fragment(&later, &[9u8; 32])).is_err());
+    }
+
+    #[test]
+    fn test_huge_length_is_rejected_without_panicking() {
+        for fragment_index in [0u64, 1] {
+            for data_len in [u64::MAX, u64::MAX - (ORIGIN_KEY_LEN as u64 - 1)] {
+                let mut frame = vec![FRAGMENT_FRAME_TYPE];
+                frame.extend_from_slice(&[7u8; 32]);
+                put_varint(&mut frame, fragment_index);
+                put_varint(&mut frame, 2);
+                put_varint(&mut frame, data_len);
+                frame.extend_from_slice(&crc32c(&[]).to_le_bytes());
+
+                assert!(decode_fragment(&frame).is_err());
+                assert!(fragment_origin(&frame).is_none());
+            }
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! LAN link for `gateway run --listen`
+//!
+//! Speaks the same socket framing as the Android Wi-Fi Direct driver
+//! (`WifiDirectService`): every frame is a 4-byte big-endian length followed
+//! by a 1-byte type and the payload, so phones in range of the desktop can
+//! hand it fragments over an ordinary TCP connection. Fragments are fed into
+//! the shared transport engine, which reassembles them into the received
+//! queue the gateway submits from; outbound fragments are fanned out to every
+//! connected peer.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, watch};
+
+use pollinet::ffi::transport::HostBleTransport;
+use pollinet::ffi::wifi_direct_transport::{WIFI_DIRECT_MAX_FRAME, WIFI_DIRECT_MAX_PAYLOAD};
+
+/// Mesh fragment (a `ble::wire` frame)
+pub const FRAME_TYPE_FRAGMENT: u8 = 0x00;
+
+/// Frames buffered per peer before new ones are dropped
+const PEER_QUEUE_DEPTH: usize = 256;
+const BROADCAST_INTERVAL: Duration = Duration::from_millis(50);
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+type Peers = Arc<Mutex<HashMap<u64, mpsc::Sender<Vec<u8>>>>>;
+
+/// Accept LAN peers until `stop` flips to true
+pub async fn serve(
+    transport: Arc<HostBleTransport>,
+    listener: TcpListener,
+    mut stop: watch::Receiver<bool>,
+) {
+    let peers: Peers = Arc::new(Mutex::new(HashMap::new()));
+    let next_id = AtomicU64::new(0);
+    let broadcast = tokio::spawn(broadcast_loop(
+        transport.clone(),
+        peers.clone(),
+        stop.clone(),
+    ));
+
+    loop {
+        tokio::select! {
+            _ = stop.changed() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, addr)) => {
+                    let id = next_id.fetch_add(1, Ordering::Relaxed);
+                    connect_peer(&transport, &peers, id, stream, addr);
+                }
+                Err(e) => tracing::warn!("⚠️ LAN accept failed: {}", e),
+            },
+        }
+    }
+
+    // Dropping the senders ends every writer task
+    peers.lock().clear();
+    let _ = broadcast.await;
+}
+
+fn connect_peer(
+    transport: &Arc<HostBleTransport>,
+    peers: &Peers,
+    id: u64,
+    stream: TcpStream,
+    addr: SocketAddr,
+) {
+    tracing::info!("🔗 LAN peer connected: {}", addr);
+    let _ = stream.set_nodelay(true);
+    let (reader, writer) = stream.into_split();
+    let (tx, rx) = mpsc::channel(PEER_QUEUE_DEPTH);
+    peers.lock().insert(id, tx);
+
+    tokio::spawn(write_frames(writer, rx));
+
+    let transport = transport.clone();
+    let peers = peers.clone();
+    tokio::spawn(async move {
+        if let Err(e) = read_frames(&transport, reader, &addr.to_string()).await {
+            tracing::debug!("LAN peer {} closed: {}", addr, e);
+        }
+        peers.lock().remove(&id);
+        tracing::info!("🔌 LAN peer disconnected: {}", addr);
+    });
+}
+
+async fn read_frames(
+    transport: &HostBleTransport,
+    mut reader: OwnedReadHalf,
+    peer: &str,
+) -> Result<(), String> {
+    loop {
+        let len = reader.read_u32().await.map_err(|e| e.to_string())? as usize;
+        if len == 0 || len > WIFI_DIRECT_MAX_FRAME {
+            return Err(format!("invalid frame length {}", len));
+        }
+        let mut frame = vec![0u8; len];
+        reader
+            .read_exact(&mut frame)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // Other frame types (e.g. the platform's JSON confirmations) are not
+        // used by the desktop node
+        if frame[0] == FRAME_TYPE_FRAGMENT {
+            frame.remove(0);
+            if let Err(e) = transport.push_inbound_from(peer, frame) {
+                tracing::warn!("⚠️ Dropped fragment from {}: {}", peer, e);
+            }
+        }
+    }
+}
+
+async fn write_frames(mut writer: OwnedWriteHalf, mut rx: mpsc::Receiver<Vec<u8>>) {
+    while let Some(frame) = rx.recv().await {
+        let sent = async {
+            writer.write_u32(frame.len() as u32).await?;
+            writer.write_all(&frame).await
+        };
+        if sent.await.is_err() {
+            break;
+        }
+    }
+    let _ = writer.shutdown().await;
+}
+
+/// Move queued transactions into the frame queue and fan frames out to every
+/// peer. Nothing is drained while no peer is connected, so fragments wait in
+/// the queue instead of being lost.
+async fn broadcast_loop(
+    transport: Arc<HostBleTransport>,
+    peers: Peers,
+    mut stop: watch::Receiver<bool>,
+) {
+    let mut interval = tokio::time::interval(BROADCAST_INTERVAL);
+    let mut last_tick = tokio::time::Instant::now();
+
+    loop {
+        tokio::select! {
+            _ = stop.changed() => break,
+            _ = interval.tick() => {}
+        }
+
+        if last_tick.elapsed() >= TICK_INTERVAL {
+            last_tick = tokio::time::Instant::now();
+            let now_ms = pollinet::util::clock::now_ms();
+            // Control frames from tick() travel like any other frame
+            for frame in transport.tick(now_ms) {
+                fan_out(&peers, frame);
+            }
+        }
+
+        if peers.lock().is_empty() {
+            continue;
+        }
+
+        // Transactions queued by `pollinet send` live in the persisted queue
+        while let Some(tx) = transport.sdk.queue_manager().pop_outbound().await {
+            if let Err(e) =
+                transport.queue_transaction(tx.original_bytes, Some(WIFI_DIRECT_MAX_PAYLOAD))
+            {
+                tracing::warn!("⚠️ Failed to fragment {}: {}", tx.tx_id, e);
+            }
+        }
+
+        while let Some(frame) = transport.next_outbound(WIFI_DIRECT_MAX_FRAME - 1) {
+            fan_out(&peers, frame);
+        }
+    }
+}
+
+fn fan_out(peers: &Peers, payload: Vec<u8>) {
+    let mut frame = Vec::with_capacity(payload.len() + 1);
+    frame.push(FRAME_TYPE_FRAGMENT);
+    frame.extend_from_slice(&payload);
+    for peer in peers.lock().values() {
+        // A slow peer drops this frame rather than stalling the others
+        let _ = peer.try_send(frame.clone());
+    }
+}
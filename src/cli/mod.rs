@@ -0,0 +1,491 @@
+//! Command parsing and implementations for the `pollinet` binary
+
+mod lan;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use pollinet::ffi::transport::HostBleTransport;
+use pollinet::ffi::types::{ProtocolInfo, SdkConfig};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Signer};
+use solana_sdk::transaction::Transaction;
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+pub const USAGE: &str = "\
+Usage: pollinet [--config <FILE>] <COMMAND>
+
+Commands:
+  advertise                  Print the advertisement data a host radio should
+                             broadcast for this node (capabilities, role, key)
+  send --to <PUBKEY> --amount <SOL> --keypair <FILE> [--blockhash <HASH>]
+                             Sign a SOL transfer and queue it for the mesh. The
+                             blockhash is fetched from rpcUrl unless given, and
+                             must still be recent when a gateway submits it
+  queue inspect [--payloads] Print the outbound, retry, confirmation and
+                             dead-letter queues as JSON
+  gateway run [--rpc <URLS>] [--listen <ADDR>]
+                             Submit received transactions until Ctrl-C. With
+                             --listen, also exchange mesh fragments with LAN
+                             peers (Wi-Fi Direct socket framing, port 8988)
+  help                       Show this message
+
+Options:
+  --config <FILE>            SdkConfig as .toml or .json (rpcUrl, rpcEndpoints,
+                             storageDirectory, role, ble, queue, logLevel, ...).
+                             Queues only persist between runs with a
+                             storageDirectory.";
+
+/// Options that take no value
+const FLAGS: &[&str] = &["payloads", "help"];
+
+/// Lamports per SOL
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// System program id (all zeroes)
+const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::new_from_array([0; 32]);
+
+/// System program `Transfer` instruction index
+const SYSTEM_TRANSFER: u32 = 2;
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Help,
+    Advertise,
+    Send {
+        to: Pubkey,
+        lamports: u64,
+        keypair: PathBuf,
+        blockhash: Option<Hash>,
+    },
+    QueueInspect {
+        payloads: bool,
+    },
+    GatewayRun {
+        rpc: Option<String>,
+        listen: Option<SocketAddr>,
+    },
+}
+
+/// A parsed command line
+#[derive(Debug, PartialEq)]
+pub struct Invocation {
+    pub config: Option<PathBuf>,
+    pub command: Command,
+}
+
+impl Invocation {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut args = RawArgs::split(args)?;
+        let config = args.take("config").map(PathBuf::from);
+        if args.flag("help") {
+            return Ok(Self {
+                config,
+                command: Command::Help,
+            });
+        }
+
+        let positional = std::mem::take(&mut args.positional);
+        let words: Vec<&str> = positional.iter().map(String::as_str).collect();
+        let command = match words.as_slice() {
+            [] | ["help"] => Command::Help,
+            ["advertise"] => Command::Advertise,
+            ["send"] => Command::Send {
+                to: parse_pubkey(&args.require("to")?)?,
+                lamports: parse_sol(&args.require("amount")?)?,
+                keypair: PathBuf::from(args.require("keypair")?),
+                blockhash: args
+                    .take("blockhash")
+                    .map(|b| Hash::from_str(&b).map_err(|e| format!("Invalid blockhash: {}", e)))
+                    .transpose()?,
+            },
+            ["queue", "inspect"] => Command::QueueInspect {
+                payloads: args.flag("payloads"),
+            },
+            ["gateway", "run"] => Command::GatewayRun {
+                rpc: args.take("rpc"),
+                listen: args
+                    .take("listen")
+                    .map(|a| {
+                        SocketAddr::from_str(&a)
+                            .map_err(|e| format!("Invalid listen address: {}", e))
+                    })
+                    .transpose()?,
+            },
+            _ => return Err(format!("Unknown command: {}", words.join(" "))),
+        };
+        args.finish()?;
+        Ok(Self { config, command })
+    }
+}
+
+/// Command line split into positional words, `--name value` options and flags
+struct RawArgs {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+    flags: HashSet<String>,
+}
+
+impl RawArgs {
+    fn split(args: &[String]) -> Result<Self, String> {
+        let mut raw = Self {
+            positional: Vec::new(),
+            options: HashMap::new(),
+            flags: HashSet::new(),
+        };
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                raw.positional.push(arg.clone());
+                continue;
+            };
+            if let Some((name, value)) = name.split_once('=') {
+                raw.options.insert(name.to_string(), value.to_string());
+            } else if FLAGS.contains(&name) {
+                raw.flags.insert(name.to_string());
+            } else {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("Missing value for --{}", name))?;
+                raw.options.insert(name.to_string(), value.clone());
+            }
+        }
+        Ok(raw)
+    }
+
+    fn take(&mut self, name: &str) -> Option<String> {
+        self.options.remove(name)
+    }
+
+    fn require(&mut self, name: &str) -> Result<String, String> {
+        self.take(name)
+            .ok_or_else(|| format!("Missing required option --{}", name))
+    }
+
+    fn flag(&mut self, name: &str) -> bool {
+        self.flags.remove(name)
+    }
+
+    /// Reject options the command did not use
+    fn finish(self) -> Result<(), String> {
+        let mut unused: Vec<String> = self
+            .options
+            .into_keys()
+            .chain(self.flags)
+            .map(|name| format!("--{}", name))
+            .collect();
+        if unused.is_empty() {
+            return Ok(());
+        }
+        unused.sort();
+        Err(format!("Unexpected option(s): {}", unused.join(", ")))
+    }
+}
+
+fn parse_pubkey(value: &str) -> Result<Pubkey, String> {
+    Pubkey::from_str(value).map_err(|e| format!("Invalid pubkey {}: {}", value, e))
+}
+
+/// Parse a SOL amount ("1", "0.25") into lamports without going through floats
+fn parse_sol(value: &str) -> Result<u64, String> {
+    let invalid = || format!("Invalid amount: {}", value);
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || fraction.len() > 9
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| invalid())?
+    };
+    let fraction: u64 = format!("{:0<9}", fraction).parse().map_err(|_| invalid())?;
+    let lamports = whole
+        .checked_mul(LAMPORTS_PER_SOL)
+        .and_then(|l| l.checked_add(fraction))
+        .ok_or_else(invalid)?;
+    if lamports == 0 {
+        return Err("Amount must be greater than zero".to_string());
+    }
+    Ok(lamports)
+}
+
+pub async fn run(invocation: Invocation) -> Result<(), String> {
+    if invocation.command == Command::Help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let config = load_config(invocation.config.as_deref())?;
+    init_logging(&config);
+    let transport = open_node(&config).await?;
+
+    let result = match invocation.command {
+        Command::Help => Ok(()),
+        Command::Advertise => advertise(&transport),
+        Command::Send {
+            to,
+            lamports,
+            keypair,
+            blockhash,
+        } => send(&transport, &config, to, lamports, &keypair, blockhash).await,
+        Command::QueueInspect { payloads } => {
+            let snapshot = transport.sdk.queue_manager().snapshot(payloads).await;
+            print_json(&snapshot)
+        }
+        Command::GatewayRun { rpc, listen } => {
+            let rpc = rpc.or_else(|| config.rpc_url_list()).ok_or(
+                "gateway run needs an RPC URL (--rpc or rpcUrl in the config)".to_string(),
+            )?;
+            gateway_run(&transport, &rpc, listen).await
+        }
+    };
+
+    transport.shutdown().await;
+    result
+}
+
+fn load_config(path: Option<&Path>) -> Result<SdkConfig, String> {
+    match path {
+        Some(path) => SdkConfig::from_file(path).map_err(|e| e.message),
+        None => serde_json::from_str("{}").map_err(|e| format!("Invalid config: {}", e)),
+    }
+}
+
+/// Logs go to stderr so command output on stdout stays parseable
+fn init_logging(config: &SdkConfig) {
+    if !config.enable_logging {
+        return;
+    }
+    let level = config
+        .log_level
+        .as_deref()
+        .and_then(|level| level.parse::<tracing::Level>().ok())
+        .unwrap_or(tracing::Level::WARN);
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
+
+/// Engine with persistent queues when the config has a storage directory
+async fn open_node(config: &SdkConfig) -> Result<Arc<HostBleTransport>, String> {
+    HostBleTransport::from_config(config)
+        .await
+        .map(Arc::new)
+        .map_err(|e| e.message)
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> Result<(), String> {
+    let json =
+        serde_json::to_string_pretty(value).map_err(|e| format!("Serialization error: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn advertise(transport: &HostBleTransport) -> Result<(), String> {
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Advertisement {
+        /// Capability byte (see `role::capability`)
+        capabilities: u8,
+        role: Option<pollinet::DeviceRole>,
+        /// Key relay receipts from this node are signed with
+        device_public_key: String,
+        /// Hex id derived from the device key, stable across restarts
+        short_id: String,
+        /// Manufacturer data block (rotating id in privacy mode)
+        advertisement: pollinet::ble::Advertisement,
+        protocol: ProtocolInfo,
+    }
+
+    let identity = transport.device_identity();
+    print_json(&Advertisement {
+        capabilities: transport.capability_flags(),
+        role: transport.device_role(),
+        device_public_key: identity.public_key,
+        short_id: identity.short_id,
+        advertisement: transport.advertisement(),
+        protocol: ProtocolInfo::current(),
+    })
+}
+
+async fn send(
+    transport: &HostBleTransport,
+    config: &SdkConfig,
+    to: Pubkey,
+    lamports: u64,
+    keypair: &Path,
+    blockhash: Option<Hash>,
+) -> Result<(), String> {
+    let keypair = read_keypair_file(keypair)
+        .map_err(|e| format!("Failed to read keypair {}: {}", keypair.display(), e))?;
+    let blockhash = match blockhash {
+        Some(blockhash) => blockhash,
+        None => latest_blockhash(config).await?,
+    };
+
+    let mut data = SYSTEM_TRANSFER.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    let transfer = Instruction::new_with_bytes(
+        SYSTEM_PROGRAM_ID,
+        &data,
+        vec![
+            AccountMeta::new(keypair.pubkey(), true),
+            AccountMeta::new(to, false),
+        ],
+    );
+    let message = Message::new_with_blockhash(&[transfer], Some(&keypair.pubkey()), &blockhash);
+    let tx = Transaction::new(&[&keypair], message, blockhash);
+    let tx_bytes = bincode1::serialize(&tx).map_err(|e| format!("Serialization error: {}", e))?;
+
+    let tx_id = transport
+        .sdk
+        .accept_and_queue_external_transaction(&STANDARD.encode(tx_bytes), None)
+        .await
+        .map_err(|e| e.to_string())?;
+    if config.storage_directory.is_none() {
+        eprintln!("warning: no storageDirectory configured, the queued transfer is lost on exit");
+    }
+
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Queued {
+        tx_id: String,
+        from: String,
+        to: String,
+        lamports: u64,
+    }
+    print_json(&Queued {
+        tx_id,
+        from: keypair.pubkey().to_string(),
+        to: to.to_string(),
+        lamports,
+    })
+}
+
+async fn latest_blockhash(config: &SdkConfig) -> Result<Hash, String> {
+    let rpc_urls = config
+        .rpc_url_list()
+        .ok_or("No --blockhash given and no rpcUrl configured to fetch one")?;
+    let pool = pollinet::ffi::rpc_pool::RpcPool::from_list(&rpc_urls)?;
+    pool.call(|client| async move { client.get_latest_blockhash().await })
+        .await
+        .map_err(|e| format!("Failed to fetch a recent blockhash: {}", e))
+}
+
+async fn gateway_run(
+    transport: &Arc<HostBleTransport>,
+    rpc: &str,
+    listen: Option<SocketAddr>,
+) -> Result<(), String> {
+    use pollinet::ffi::connectivity::ConnectivityConfig;
+    use pollinet::ffi::gateway::RpcSubmitter;
+    use pollinet::ffi::rpc_pool::RpcPool;
+
+    let pool = Arc::new(RpcPool::from_list(rpc)?);
+    transport
+        .connectivity()
+        .start(ConnectivityConfig::rpc_health(pool.urls()[0]))?;
+    transport.start_gateway(Arc::new(RpcSubmitter::new(pool)));
+    eprintln!(
+        "Gateway running ({} received transaction(s) queued), Ctrl-C to stop",
+        transport.received_queue_size()
+    );
+
+    let (stop_tx, stop_rx) = tokio::sync::watch::channel(false);
+    let link = match listen {
+        Some(addr) => {
+            let listener = tokio::net::TcpListener::bind(addr)
+                .await
+                .map_err(|e| format!("Failed to listen on {}: {}", addr, e))?;
+            eprintln!("Exchanging mesh fragments with LAN peers on {}", addr);
+            Some(tokio::spawn(lan::serve(
+                transport.clone(),
+                listener,
+                stop_rx,
+            )))
+        }
+        None => None,
+    };
+
+    tokio::signal::ctrl_c()
+        .await
+        .map_err(|e| format!("Failed to wait for Ctrl-C: {}", e))?;
+    eprintln!("Stopping gateway");
+    let _ = stop_tx.send(true);
+    if let Some(link) = link {
+        let _ = link.await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Invocation, String> {
+        let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+        Invocation::parse(&args)
+    }
+
+    #[test]
+    fn test_parse_commands_and_options() {
+        let to = Pubkey::new_unique();
+        let invocation = parse(&format!(
+            "--config node.json send --to {} --amount 0.5 --keypair id.json",
+            to
+        ))
+        .unwrap();
+        assert_eq!(invocation.config, Some(PathBuf::from("node.json")));
+        assert_eq!(
+            invocation.command,
+            Command::Send {
+                to,
+                lamports: 500_000_000,
+                keypair: PathBuf::from("id.json"),
+                blockhash: None,
+            }
+        );
+
+        assert_eq!(
+            parse("gateway run --listen=0.0.0.0:8988").unwrap().command,
+            Command::GatewayRun {
+                rpc: None,
+                listen: Some("0.0.0.0:8988".parse().unwrap()),
+            }
+        );
+        assert_eq!(
+            parse("queue inspect --payloads").unwrap().command,
+            Command::QueueInspect { payloads: true }
+        );
+        assert_eq!(parse("").unwrap().command, Command::Help);
+
+        assert!(parse("send --to x").is_err());
+        assert!(parse("advertise --payloads").is_err());
+        assert!(parse("gateway stop").is_err());
+        assert!(parse("queue inspect --config").is_err());
+    }
+
+    #[test]
+    fn test_parse_sol_amounts() {
+        assert_eq!(parse_sol("1"), Ok(LAMPORTS_PER_SOL));
+        assert_eq!(parse_sol("0.000000001"), Ok(1));
+        assert_eq!(parse_sol(".25"), Ok(250_000_000));
+        assert!(parse_sol("0").is_err());
+        assert!(parse_sol("1.0000000001").is_err());
+        assert!(parse_sol("-1").is_err());
+        assert!(parse_sol("1e3").is_err());
+        assert!(parse_sol("99999999999").is_err());
+    }
+}
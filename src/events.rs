@@ -0,0 +1,85 @@
+//! SDK-wide event notifications
+//!
+//! `PolliNetSDK` publishes an [`SdkEvent`] on a broadcast channel as mesh
+//! activity happens (peers seen, fragments and transactions received,
+//! confirmations, adapter and connectivity changes), so native Rust consumers can react with
+//! `subscribe()` instead of polling. Queue changes have their own channel, see
+//! [`crate::queue::QueueEvent`].
+
+use serde::{Deserialize, Serialize};
+
+/// Capacity of the event channel. Slow subscribers lag (and skip events)
+/// instead of blocking the transport.
+pub const SDK_EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Radio adapter state as reported by the host
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AdapterState {
+    On,
+    Off,
+    /// Permission to use the radio was denied
+    Unauthorized,
+    /// No usable adapter on this device
+    Unavailable,
+}
+
+/// Mesh activity event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SdkEvent {
+    /// Peer observed in a scan for the first time within the density window
+    #[serde(rename_all = "camelCase")]
+    PeerDiscovered { peer_id: String },
+    /// New (non-duplicate) fragment buffered for reassembly
+    #[serde(rename_all = "camelCase")]
+    FragmentReceived {
+        tx_id: String,
+        index: u16,
+        total: u16,
+    },
+    /// Fragment dropped for a bad CRC; the sender should resend it
+    #[serde(rename_all = "camelCase")]
+    FragmentCorrupt { tx_id: String, index: u16 },
+    /// All fragments arrived and the transaction was reassembled
+    #[serde(rename_all = "camelCase")]
+    TransactionComplete { tx_id: String, size: usize },
+    /// Verified mesh confirmation; `tx_id_hash` is the hex confirmation hash
+    #[serde(rename_all = "camelCase")]
+    ConfirmationReceived { tx_id_hash: String, success: bool },
+    /// Host reported a change of radio adapter state
+    #[serde(rename_all = "camelCase")]
+    AdapterStateChanged { state: AdapterState },
+    /// Internet connectivity changed (see [`crate::ffi::connectivity`])
+    #[serde(rename_all = "camelCase")]
+    ConnectivityChanged { online: bool },
+    /// A ping or traceroute sent by this device was answered
+    #[serde(rename_all = "camelCase")]
+    ProbeCompleted {
+        probe_id: String,
+        rtt_ms: u64,
+        hops: usize,
+    },
+    /// A new verified emergency notice arrived (see [`crate::ble::emergency`])
+    #[serde(rename_all = "camelCase")]
+    EmergencyReceived {
+        id: String,
+        origin: String,
+        message: String,
+        hops: u8,
+    },
+    /// A verified payment request arrived from a merchant
+    #[serde(rename_all = "camelCase")]
+    PaymentRequested {
+        request_id: String,
+        recipient: String,
+        amount: u64,
+        mint: Option<String>,
+    },
+    /// A payment receipt was received, or countersigned by the merchant
+    #[serde(rename_all = "camelCase")]
+    PaymentReceipt {
+        tx_signature: String,
+        countersigned: bool,
+    },
+}
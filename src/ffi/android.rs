@@ -7,9 +7,9 @@
 #![allow(deprecated)]
 
 #[cfg(feature = "android")]
-use jni::objects::{JByteArray, JClass, JString};
+use jni::objects::{JByteArray, JClass, JObject, JString};
 #[cfg(feature = "android")]
-use jni::sys::{jbyteArray, jint, jlong, jstring};
+use jni::sys::{jboolean, jbyteArray, jint, jlong, jstring};
 #[cfg(feature = "android")]
 use jni::JNIEnv;
 #[cfg(feature = "android")]
@@ -19,6 +19,8 @@ use std::str::FromStr;
 #[cfg(feature = "android")]
 use std::sync::Arc;
 
+#[cfg(feature = "android")]
+use super::handles::ShardedHandleMap;
 #[cfg(feature = "android")]
 use super::host_transport::HostTransport;
 use super::runtime;
@@ -52,20 +54,26 @@ struct TransportEntry {
     kind: TransportKind,
     core: Arc<dyn HostTransport>,
     ble: Option<Arc<HostBleTransport>>,
+    /// Engine behind `core`, shut down once no live handle uses it
+    engine: Arc<HostBleTransport>,
 }
 
-// Global state for transport instances (single tagged registry; handle == index).
+// Global state for transport instances (single tagged registry; generational handles).
+// Sharded so concurrent lookups from BLE callbacks only share read locks.
 #[cfg(feature = "android")]
-lazy_static::lazy_static! {
-    static ref TRANSPORTS: Arc<Mutex<Vec<Option<TransportEntry>>>> = Arc::new(Mutex::new(Vec::new()));
-}
+static TRANSPORTS: ShardedHandleMap<TransportEntry> = ShardedHandleMap::new();
+
+/// Serializes shutdowns, so two handles sharing an engine can't both see
+/// the other gone and shut it down twice
+#[cfg(feature = "android")]
+static SHUTDOWN_LOCK: Mutex<()> = Mutex::new(());
 
 // =============================================================================
 // Initialization and lifecycle
 // =============================================================================
 
 /// Initialize the PolliNet SDK
-/// Returns a handle (index) to the initialized transport instance
+/// Returns a generational handle to the initialized transport instance
 #[cfg(feature = "android")]
 #[no_mangle]
 pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_init(
@@ -85,14 +93,14 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_init(
         }
     });
 
-    let result: Result<jlong, String> = (|| {
+    let result: Result<jlong, FfiError> = (|| {
         // Parse config before touching any logging so the enable_logging flag controls everything.
         let config_data: Vec<u8> = env
             .convert_byte_array(&config_bytes)
-            .map_err(|e| format!("Failed to read config bytes: {}", e))?;
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read config bytes: {}", e)))?;
 
         let config: SdkConfig = serde_json::from_slice(&config_data)
-            .map_err(|e| format!("Failed to parse config: {}", e))?;
+            .map_err(|e| FfiError::invalid_input(format!("Failed to parse config: {}", e)))?;
 
         // Apply log level — Off when enableLogging is false, desired level otherwise.
         // log::set_max_level is the global filter gate; setting it to Off prevents all
@@ -119,80 +127,38 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_init(
         }
 
         info!("📱 FFI init — RPC: {:?}", config.rpc_url);
+        config.check_compatibility()?;
 
         // Initialize runtime if needed
         match runtime::init_runtime() {
             Ok(_) => info!("✅ Runtime initialized"),
             Err(e) if e.contains("already initialized") => {}
-            Err(e) => return Err(format!("Failed to initialize runtime: {}", e)),
+            Err(e) => {
+                return Err(FfiError::internal(format!(
+                    "Failed to initialize runtime: {}",
+                    e
+                )))
+            }
         }
 
         info!("Step 5: Creating transport...");
 
-        // Create transport instance
-        let mut transport = runtime::block_on(async {
-            if let Some(rpc_url) = &config.rpc_url {
-                info!("Creating transport with RPC: {}", rpc_url);
-                HostBleTransport::new_with_rpc(rpc_url).await
-            } else {
-                info!("Creating transport without RPC");
-                HostBleTransport::new().await
-            }
-        })
-        .map_err(|e| {
+        // Create transport instance (shared with the other bindings and the CLI)
+        let transport = runtime::block_on(HostBleTransport::from_config(&config)).map_err(|e| {
             error!("❌ Transport creation failed: {}", e);
             e
         })?;
 
-        // Set secure storage if directory provided
-        if let Some(storage_dir) = &config.storage_directory {
-            info!("Step 5b: Setting up secure storage at: {}", storage_dir);
-            transport
-                .set_secure_storage(storage_dir, config.encryption_key.clone())
-                .map_err(|e| {
-                    error!("❌ Failed to set secure storage: {}", e);
-                    e
-                })?;
-            info!("✅ Secure storage configured");
-
-            // Phase 5: Set queue storage directory (stored on transport, no env var mutation)
-            let queue_storage_dir = format!("{}/queues", storage_dir);
-            transport.set_queue_storage_dir(queue_storage_dir.clone());
-            info!("✅ Queue persistence enabled at: {}", queue_storage_dir);
-        } else {
-            info!("ℹ️  No storage directory provided - bundle persistence disabled");
-        }
-
-        // Resolve pollicore URL: baked-in at compile time from .env / POLLICORE_URL env var
-        let pollicore_url: Option<&str> = option_env!("POLLICORE_URL");
-        if let Some(url) = pollicore_url {
-            transport.set_pollicore_url(Some(url.to_string()));
-            info!("✅ Pollicore URL (compile-time): {}", url);
-        } else {
-            info!("⚠️  POLLICORE_URL not set at compile time — submitIntent will fail");
-        }
-
-        // Store wallet address if provided in config
-        if let Some(ref addr) = config.wallet_address {
-            transport.set_wallet_address(Some(addr.clone()));
-            info!("✅ Wallet address set: {}", addr);
-        } else {
-            info!(
-                "ℹ️  No wallet address provided — rewards will not be attributed until one is set"
-            );
-        }
-
         info!("Step 6: Storing transport...");
 
         let transport_arc = Arc::new(transport);
         let core: Arc<dyn HostTransport> = transport_arc.clone();
-        let mut transports = TRANSPORTS.lock();
-        transports.push(Some(TransportEntry {
+        let handle = TRANSPORTS.insert(TransportEntry {
             kind: TransportKind::Ble,
             core,
-            ble: Some(transport_arc),
-        }));
-        let handle = (transports.len() - 1) as jlong;
+            ble: Some(transport_arc.clone()),
+            engine: transport_arc,
+        });
 
         info!(
             "✅ PolliNet SDK initialized successfully with handle {}",
@@ -238,12 +204,12 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_initWifiDirect(
         }
     });
 
-    let result: Result<jlong, String> = (|| {
+    let result: Result<jlong, FfiError> = (|| {
         let config_data: Vec<u8> = env
             .convert_byte_array(&config_bytes)
-            .map_err(|e| format!("Failed to read config bytes: {}", e))?;
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read config bytes: {}", e)))?;
         let config: SdkConfig = serde_json::from_slice(&config_data)
-            .map_err(|e| format!("Failed to parse config: {}", e))?;
+            .map_err(|e| FfiError::invalid_input(format!("Failed to parse config: {}", e)))?;
 
         if config.enable_logging {
             let tracing_level = parse_log_level(config.log_level.as_deref());
@@ -263,17 +229,27 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_initWifiDirect(
         }
 
         info!("📶 FFI initWifiDirect — RPC: {:?}", config.rpc_url);
+        config.check_compatibility()?;
 
         match runtime::init_runtime() {
             Ok(_) => {}
             Err(e) if e.contains("already initialized") => {}
-            Err(e) => return Err(format!("Failed to initialize runtime: {}", e)),
+            Err(e) => {
+                return Err(FfiError::internal(format!(
+                    "Failed to initialize runtime: {}",
+                    e
+                )))
+            }
         }
 
         // Create and configure the engine identically to the BLE path (shared state),
         // then wrap it in the Wi-Fi Direct adapter.
+        let rpc_url = config
+            .rpc_url
+            .as_ref()
+            .filter(|_| config.role != Some(crate::DeviceRole::Relay));
         let mut engine = runtime::block_on(async {
-            if let Some(rpc_url) = &config.rpc_url {
+            if let Some(rpc_url) = rpc_url {
                 HostBleTransport::new_with_rpc(rpc_url).await
             } else {
                 HostBleTransport::new().await
@@ -286,13 +262,19 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_initWifiDirect(
 
         if let Some(storage_dir) = &config.storage_directory {
             engine
-                .set_secure_storage(storage_dir, config.encryption_key.clone())
+                .set_secure_storage(storage_dir, config.storage_key_provider()?)
                 .map_err(|e| {
                     error!("❌ Failed to set secure storage: {}", e);
                     e
                 })?;
             let queue_storage_dir = format!("{}/queues", storage_dir);
             engine.set_queue_storage_dir(queue_storage_dir);
+            if let Some(budget) = config.storage_budget_bytes {
+                engine.set_storage_quota(Some(crate::storage::StorageQuota::new(
+                    storage_dir,
+                    budget,
+                )));
+            }
         }
         if let Some(url) = option_env!("POLLICORE_URL") {
             engine.set_pollicore_url(Some(url.to_string()));
@@ -300,17 +282,17 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_initWifiDirect(
         if let Some(ref addr) = config.wallet_address {
             engine.set_wallet_address(Some(addr.clone()));
         }
+        engine.set_device_role(config.role);
 
-        let transport = HostWifiDirectTransport::from_engine(Arc::new(engine));
-        let transport_arc = Arc::new(transport);
-        let core: Arc<dyn HostTransport> = transport_arc.clone();
-        let mut transports = TRANSPORTS.lock();
-        transports.push(Some(TransportEntry {
+        let engine = Arc::new(engine);
+        let transport = HostWifiDirectTransport::from_engine(engine.clone());
+        let core: Arc<dyn HostTransport> = Arc::new(transport);
+        let handle = TRANSPORTS.insert(TransportEntry {
             kind: TransportKind::WifiDirect,
             core,
             ble: None,
-        }));
-        let handle = (transports.len() - 1) as jlong;
+            engine,
+        });
         info!(
             "✅ Wi-Fi Direct transport initialized with handle {}",
             handle
@@ -340,20 +322,19 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_initWifiDirectSharing(
     _class: JClass,
     ble_handle: jlong,
 ) -> jlong {
-    let result: Result<jlong, String> = (|| {
+    let result: Result<jlong, FfiError> = (|| {
         let engine = get_transport(ble_handle)?; // Arc<HostBleTransport>, shared
         let transport = Arc::new(HostWifiDirectTransport::from_engine(engine.clone()));
         let core: Arc<dyn HostTransport> = transport;
-        let mut transports = TRANSPORTS.lock();
-        transports.push(Some(TransportEntry {
+        let handle = TRANSPORTS.insert(TransportEntry {
             kind: TransportKind::WifiDirect,
             core,
             // Expose the SHARED engine via the BLE surface too, so BLE-gated FFI
             // (confirmations: popConfirmation / relayConfirmation / confirmDelivered)
             // work on this Wi-Fi handle — enabling the Wi-Fi confirmation reverse-channel.
-            ble: Some(engine),
-        }));
-        let handle = (transports.len() - 1) as jlong;
+            ble: Some(engine.clone()),
+            engine,
+        });
         info!(
             "✅ Wi-Fi Direct handle {} sharing engine of BLE handle {}",
             handle, ble_handle
@@ -377,14 +358,7 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_transportKind(
     _class: JClass,
     handle: jlong,
 ) -> jstring {
-    let kind = {
-        let transports = TRANSPORTS.lock();
-        transports
-            .get(handle as usize)
-            .and_then(|t| t.as_ref())
-            .map(|e| e.kind.as_str())
-            .unwrap_or("")
-    };
+    let kind = TRANSPORTS.with(handle, |e| e.kind.as_str()).unwrap_or("");
     env.new_string(kind)
         .expect("Failed to create Java string")
         .into_raw()
@@ -403,6 +377,18 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_version(
         .into_raw()
 }
 
+/// SDK version, wire protocol version and compiled features (safe before `init`)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_protocolInfo(
+    mut env: JNIEnv,
+    _class: JClass,
+) -> jstring {
+    let response: FfiResult<ProtocolInfo> = FfiResult::success(ProtocolInfo::current());
+    let result = serde_json::to_string(&response).map_err(FfiError::serialization);
+    create_result_string(&mut env, result)
+}
+
 /// Return the pollicore base URL baked in at compile time from POLLICORE_URL env var.
 #[cfg(feature = "android")]
 #[no_mangle]
@@ -427,11 +413,13 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_deriveAssociatedTokenAccount
     owner_j: JString,
     mint_j: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let owner_str: String = env.get_string(&owner_j).map_err(|e| e.to_string())?.into();
         let mint_str: String = env.get_string(&mint_j).map_err(|e| e.to_string())?.into();
-        let owner = Pubkey::from_str(&owner_str).map_err(|e| format!("Invalid owner: {}", e))?;
-        let mint = Pubkey::from_str(&mint_str).map_err(|e| format!("Invalid mint: {}", e))?;
+        let owner = Pubkey::from_str(&owner_str)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid owner: {}", e)))?;
+        let mint = Pubkey::from_str(&mint_str)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid mint: {}", e)))?;
         let ata = spl_associated_token_account::get_associated_token_address(&owner, &mint);
         Ok(ata.to_string())
     })();
@@ -448,6 +436,10 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_deriveAssociatedTokenAccount
 }
 
 /// Shutdown the SDK and release resources
+///
+/// The handle is invalidated (later calls fail with "has been shut down") and its
+/// slot may be reused. The engine is flushed and dropped once no other handle
+/// (e.g. a Wi-Fi Direct handle sharing it) still uses it.
 #[cfg(feature = "android")]
 #[no_mangle]
 pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_shutdown(
@@ -455,11 +447,25 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_shutdown(
     _class: JClass,
     handle: jlong,
 ) {
-    let mut transports = TRANSPORTS.lock();
-    if handle >= 0 && (handle as usize) < transports.len() {
-        transports[handle as usize] = None;
-        tracing::info!("🛑 SDK handle {} shut down and invalidated", handle);
+    let (entry, engine_in_use) = {
+        let _guard = SHUTDOWN_LOCK.lock();
+        match TRANSPORTS.remove(handle) {
+            Ok(entry) => {
+                let in_use = TRANSPORTS.any(|other| Arc::ptr_eq(&other.engine, &entry.engine));
+                (entry, in_use)
+            }
+            Err(e) => {
+                log::warn!("⚠️ shutdown: {}", e);
+                return;
+            }
+        }
+    };
+
+    if !engine_in_use {
+        runtime::block_on(entry.engine.shutdown());
     }
+    drop(entry);
+    info!("🛑 SDK handle {} shut down and invalidated", handle);
 }
 
 // =============================================================================
@@ -479,14 +485,102 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_pushInbound(
         let transport = get_core(handle)?;
         let data_vec: Vec<u8> = env
             .convert_byte_array(&data)
-            .map_err(|e| format!("Failed to read data: {}", e))?;
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read data: {}", e)))?;
 
         log::debug!("📡 pushInbound handle={} bytes={}", handle, data_vec.len());
         transport.push_inbound(data_vec)?;
         log::debug!("✅ pushInbound queued successfully");
 
         let response: FfiResult<()> = FfiResult::success(());
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Push inbound data received from a known peer, recording the peer as the
+/// transaction's provenance in the transaction history
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_pushInboundFromPeer(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    peer_id: JString,
+    data: JByteArray,
+) -> jstring {
+    let result = (|| {
+        let transport = get_transport(handle)?;
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer ID: {}", e)))?
+            .into();
+        let data_vec: Vec<u8> = env
+            .convert_byte_array(&data)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read data: {}", e)))?;
+
+        log::debug!(
+            "📡 pushInboundFromPeer handle={} peer={} bytes={}",
+            handle,
+            peer_id,
+            data_vec.len()
+        );
+        transport.push_inbound_from(&peer_id, data_vec)?;
+
+        let response: FfiResult<()> = FfiResult::success(());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Queue inbound data without blocking the BLE callback thread. A full
+/// queue drops the frame and NACKs the sender; data is false then.
+/// `peerId` may be empty when the sender is unknown.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_enqueueInbound(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    peer_id: JString,
+    data: JByteArray,
+) -> jstring {
+    let result = (|| {
+        let transport = get_transport(handle)?;
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer ID: {}", e)))?
+            .into();
+        let data_vec: Vec<u8> = env
+            .convert_byte_array(&data)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read data: {}", e)))?;
+
+        let peer_id = Some(peer_id.as_str()).filter(|p| !p.is_empty());
+        let queued = transport.enqueue_inbound(peer_id, data_vec);
+
+        let response: FfiResult<bool> = FfiResult::success(queued);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Process up to `max` queued inbound frames (also done on every tick);
+/// data is the number processed
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_processInbound(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    max: jint,
+) -> jstring {
+    let result = (|| {
+        let transport = get_transport(handle)?;
+        let max = usize::try_from(max).map_err(|_| FfiError::invalid_input("max must be >= 0"))?;
+        let response: FfiResult<usize> = FfiResult::success(transport.process_inbound(max));
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
@@ -501,7 +595,7 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_nextOutbound(
     handle: jlong,
     max_len: jlong,
 ) -> jbyteArray {
-    let result: Result<Option<Vec<u8>>, String> = (|| {
+    let result: Result<Option<Vec<u8>>, FfiError> = (|| {
         let transport = get_core(handle)?;
         Ok(transport.next_outbound(max_len as usize))
     })();
@@ -519,2496 +613,4916 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_nextOutbound(
     }
 }
 
-/// Periodic tick for retry/timeout handling
+/// Get next outbound frame for a peer, skipping transactions its digest
+/// says it already holds
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_tick(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_nextOutboundForPeer(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    peer_id: JString,
+    max_len: jlong,
+) -> jbyteArray {
+    let result: Result<Option<Vec<u8>>, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| format!("Failed to read peer id: {}", e))?
+            .into();
+        Ok(transport.next_outbound_for_peer(&peer_id, max_len as usize))
+    })();
+
+    match result {
+        Ok(Some(data)) => env
+            .byte_array_from_slice(&data)
+            .expect("Failed to create byte array")
+            .into_raw(),
+        Ok(None) => std::ptr::null_mut(),
+        Err(e) => {
+            tracing::error!("nextOutboundForPeer error: {}", e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Digest of the transactions this device holds, to send on connect
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getTxDigest(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    now_ms: jlong,
 ) -> jstring {
-    let result = (|| {
-        let transport = get_core(handle)?;
-        let frames = transport.tick(now_ms as u64);
+    let result: Result<String, FfiError> = (|| {
+        use base64::{engine::general_purpose::STANDARD, Engine};
 
-        // Encode frames as JSON array of base64 strings
-        use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
-        let encoded: Vec<String> = frames.iter().map(|f| BASE64.encode(f)).collect();
+        #[derive(serde::Serialize)]
+        struct DigestFrame {
+            frame: String,
+        }
 
-        let response: FfiResult<Vec<String>> = FfiResult::success(encoded);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let transport = get_transport(handle)?;
+        let response: FfiResult<DigestFrame> = FfiResult::success(DigestFrame {
+            frame: STANDARD.encode(transport.tx_digest()),
+        });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Get current metrics
+/// Hand a peer's digest frame to the engine. Returns a `DigestSummary`.
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_metrics(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_ingestTxDigest(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
+    peer_id: JString,
+    data: JByteArray,
 ) -> jstring {
-    let result = (|| {
-        let transport = get_core(handle)?;
-        let metrics = transport.metrics();
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer id: {}", e)))?
+            .into();
+        let data: Vec<u8> = env
+            .convert_byte_array(&data)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read data: {}", e)))?;
+        let summary = transport
+            .ingest_tx_digest(&peer_id, &data)
+            .map_err(FfiError::invalid_input)?;
 
-        let response: FfiResult<MetricsSnapshot> = FfiResult::success(metrics);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<crate::ffi::types::DigestSummary> = FfiResult::success(summary);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Clear transaction from buffers
+/// Forget a peer's digest (on disconnect)
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_clearTransaction(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_clearTxDigest(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    tx_id: JString,
+    peer_id: JString,
 ) -> jstring {
-    let result = (|| {
-        let transport = get_core(handle)?;
-        let tx_id_str: String = env
-            .get_string(&tx_id)
-            .map_err(|e| format!("Failed to read tx_id: {}", e))?
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer id: {}", e)))?
             .into();
+        transport.clear_tx_digest(&peer_id);
 
-        transport.clear_transaction(&tx_id_str);
-
-        let response: FfiResult<()> = FfiResult::success(());
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<bool> = FfiResult::success(true);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Remove all outbound queue fragments that belong to `tx_id`.
-/// Must be called when a BLE confirmation arrives (success or failure) so the
-/// originating device stops re-broadcasting a transaction already handled by a
-/// relay peer.
+/// Record the capability byte a peer advertises (`COMPACT_FRAMES` decides
+/// the fragment format it is sent)
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_clearOutboundTransaction(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_setPeerCapabilities(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    tx_id: JString,
+    peer_id: JString,
+    capabilities: jint,
 ) -> jstring {
-    let result = (|| {
-        let transport = get_core(handle)?;
-        let tx_id_str: String = env
-            .get_string(&tx_id)
-            .map_err(|e| format!("Failed to read tx_id: {}", e))?
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer id: {}", e)))?
             .into();
+        transport.set_peer_capabilities(&peer_id, capabilities as u8);
 
-        let removed = transport.clear_outbound_for_tx(&tx_id_str);
-
-        #[derive(serde::Serialize)]
-        struct Out {
-            removed: usize,
-        }
-        let response: FfiResult<Out> = FfiResult::success(Out { removed });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<bool> = FfiResult::success(true);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-// =============================================================================
-// Fragmentation API (M6)
-// =============================================================================
-
-/// Fragment a transaction for BLE transmission
-///
-/// Optionally accepts max_payload (MTU - 10) for MTU-aware fragmentation
+/// Pin a peer's base58 device key under its short id (required before
+/// control messages from it are accepted in envelopes)
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_fragment(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_pinPeerKey(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    tx_bytes: JByteArray,
-    max_payload: jlong,
+    device_key: JString,
 ) -> jstring {
-    let result = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-        let tx_data: Vec<u8> = env
-            .convert_byte_array(&tx_bytes)
-            .map_err(|e| format!("Failed to read tx bytes: {}", e))?;
-
-        let max_payload_opt = if max_payload > 0 {
-            Some(max_payload as usize)
-        } else {
-            None
-        };
-        log::info!(
-            "✂️  fragment handle={} input_bytes={} max_payload={:?}",
-            handle,
-            tx_data.len(),
-            max_payload_opt
-        );
-
-        let fragments = transport.queue_transaction(tx_data, max_payload_opt)?;
-
-        let total_fragment_bytes: usize = fragments.iter().map(|f| f.data.len()).sum();
-        log::info!(
-            "✅ fragment → {} fragments, {} total payload bytes",
-            fragments.len(),
-            total_fragment_bytes
-        );
+        let device_key: String = env
+            .get_string(&device_key)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read device key: {}", e)))?
+            .into();
+        transport
+            .pin_peer_key(&device_key)
+            .map_err(FfiError::invalid_input)?;
 
-        let fragment_list = FragmentList { fragments };
-        let response: FfiResult<FragmentList> = FfiResult::success(fragment_list);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<bool> = FfiResult::success(true);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-// =============================================================================
-// Helper functions
-// =============================================================================
-
-/// Resolve a handle to the concrete BLE engine. Used by BLE-specific FFI functions
-/// (queue manager, health, intent building). Returns an error for non-BLE handles.
+/// Save the session with a peer that disconnected (instead of
+/// `clearTxDigest`); `mtu` / `protocol_version` below 0 are unknown
 #[cfg(feature = "android")]
-fn get_transport(handle: jlong) -> Result<Arc<HostBleTransport>, String> {
-    let transports = TRANSPORTS.lock();
-    if handle < 0 || handle as usize >= transports.len() {
-        return Err(format!("Invalid handle: {}", handle));
-    }
-    let entry = transports[handle as usize]
-        .as_ref()
-        .ok_or_else(|| format!("Handle {} has been shut down", handle))?;
-    entry.ble.clone().ok_or_else(|| {
-        format!(
-            "Handle {} is a {} transport (no BLE-specific surface)",
-            handle,
-            entry.kind.as_str()
-        )
-    })
-}
-
-/// Resolve a handle to the radio-agnostic transport contract. Works for BLE and Wi-Fi
-/// Direct alike — used by the byte-level FFI functions (pushInbound/nextOutbound/…).
-#[cfg(feature = "android")]
-fn get_core(handle: jlong) -> Result<Arc<dyn HostTransport>, String> {
-    let transports = TRANSPORTS.lock();
-    if handle < 0 || handle as usize >= transports.len() {
-        return Err(format!("Invalid handle: {}", handle));
-    }
-    transports[handle as usize]
-        .as_ref()
-        .map(|e| e.core.clone())
-        .ok_or_else(|| format!("Handle {} has been shut down", handle))
-}
-
-#[cfg(feature = "android")]
-fn create_result_string(env: &mut JNIEnv, result: Result<String, String>) -> jstring {
-    match result {
-        Ok(json) => env
-            .new_string(json)
-            .expect("Failed to create Java string")
-            .into_raw(),
-        Err(e) => {
-            log::error!("❌ FFI error: {}", e);
-            let error_response: FfiResult<()> = FfiResult::error("ERR_INTERNAL", e);
-            let error_json = serde_json::to_string(&error_response).unwrap_or_else(|_| {
-                r#"{"ok":false,"code":"ERR_FATAL","message":"Serialization failed"}"#.to_string()
-            });
-            env.new_string(error_json)
-                .expect("Failed to create error string")
-                .into_raw()
-        }
-    }
-}
-
-fn parse_log_level(level: Option<&str>) -> tracing::Level {
-    match level {
-        Some("trace") => tracing::Level::TRACE,
-        Some("debug") => tracing::Level::DEBUG,
-        Some("info") => tracing::Level::INFO,
-        Some("warn") => tracing::Level::WARN,
-        Some("error") => tracing::Level::ERROR,
-        _ => tracing::Level::INFO,
-    }
-}
-
-/// Reconstruct a transaction from fragments
-/// Takes JSON array of fragment objects with base64 data
 #[no_mangle]
-#[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_reconstructTransaction(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_savePeerSession(
     mut env: JNIEnv,
     _class: JClass,
-    fragments_json: JByteArray,
+    handle: jlong,
+    peer_id: JString,
+    device_key: JString,
+    mtu: jint,
+    protocol_version: jint,
 ) -> jstring {
-    let result = (|| -> Result<String, String> {
-        tracing::info!("🔗 FFI reconstructTransaction called");
-
-        let json_data: Vec<u8> = env
-            .convert_byte_array(&fragments_json)
-            .map_err(|e| format!("Failed to read fragments JSON: {}", e))?;
-
-        // Parse fragment data from JSON
-        #[derive(serde::Deserialize)]
-        struct FragmentData {
-            #[serde(rename = "transactionId")]
-            transaction_id: String,
-            #[serde(rename = "fragmentIndex")]
-            fragment_index: u16,
-            #[serde(rename = "totalFragments")]
-            total_fragments: u16,
-            #[serde(rename = "dataBase64")]
-            data_base64: String,
-        }
-
-        let fragment_data: Vec<FragmentData> = serde_json::from_slice(&json_data)
-            .map_err(|e| format!("Failed to parse fragments JSON: {}", e))?;
-
-        tracing::info!("Reconstructing from {} fragments", fragment_data.len());
-
-        // Convert to internal fragment format
-        let fragments: Vec<crate::ble::mesh::TransactionFragment> = fragment_data
-            .iter()
-            .map(|f| {
-                let mut tx_id = [0u8; 32];
-                let tx_id_bytes = hex::decode(&f.transaction_id)
-                    .map_err(|e| format!("Invalid transaction ID: {}", e))?;
-                tx_id.copy_from_slice(&tx_id_bytes);
-
-                let data = base64::decode(&f.data_base64)
-                    .map_err(|e| format!("Invalid fragment data: {}", e))?;
-
-                Ok(crate::ble::mesh::TransactionFragment {
-                    transaction_id: tx_id,
-                    fragment_index: f.fragment_index,
-                    total_fragments: f.total_fragments,
-                    data,
-                })
-            })
-            .collect::<Result<Vec<_>, String>>()?;
-
-        // Reconstruct the transaction
-        let reconstructed = crate::ble::reconstruct_transaction(&fragments)
-            .map_err(|e| format!("Reconstruction failed: {}", e))?;
-
-        tracing::info!(
-            "✅ Reconstructed transaction: {} bytes",
-            reconstructed.len()
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer id: {}", e)))?
+            .into();
+        let device_key: String = env
+            .get_string(&device_key)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read device key: {}", e)))?
+            .into();
+        transport.save_peer_session(
+            &peer_id,
+            &device_key,
+            usize::try_from(mtu).ok(),
+            u8::try_from(protocol_version).ok(),
         );
 
-        // Return base64-encoded transaction
-        let tx_base64 = base64::encode(&reconstructed);
-
-        let response: FfiResult<String> = FfiResult::success(tx_base64);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<bool> = FfiResult::success(true);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Get fragmentation statistics for a transaction
-#[no_mangle]
+/// Resume the saved session with a reconnected peer: `{mtu, protocolVersion,
+/// digestRestored, resumedTransfers, fragmentsRequested}`, or null data when
+/// there is none and the full handshake is needed
 #[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getFragmentationStats(
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_resumePeerSession(
     mut env: JNIEnv,
     _class: JClass,
-    transaction_bytes: JByteArray,
+    handle: jlong,
+    peer_id: JString,
+    device_key: JString,
 ) -> jstring {
-    let result = (|| -> Result<String, String> {
-        tracing::info!("📊 FFI getFragmentationStats called");
-
-        let tx_bytes: Vec<u8> = env
-            .convert_byte_array(&transaction_bytes)
-            .map_err(|e| format!("Failed to read transaction: {}", e))?;
-
-        let stats = crate::ble::FragmentationStats::calculate(&tx_bytes);
-
-        #[derive(serde::Serialize)]
-        struct StatsResponse {
-            #[serde(rename = "originalSize")]
-            original_size: usize,
-            #[serde(rename = "fragmentCount")]
-            fragment_count: usize,
-            #[serde(rename = "maxFragmentSize")]
-            max_fragment_size: usize,
-            #[serde(rename = "avgFragmentSize")]
-            avg_fragment_size: usize,
-            #[serde(rename = "totalOverhead")]
-            total_overhead: usize,
-            #[serde(rename = "efficiency")]
-            efficiency: f32,
-        }
-
-        let stats_response = StatsResponse {
-            original_size: stats.original_size,
-            fragment_count: stats.fragment_count,
-            max_fragment_size: stats.max_fragment_size,
-            avg_fragment_size: stats.avg_fragment_size,
-            total_overhead: stats.total_overhead,
-            efficiency: stats.efficiency,
-        };
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer id: {}", e)))?
+            .into();
+        let device_key: String = env
+            .get_string(&device_key)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read device key: {}", e)))?
+            .into();
 
-        let response: FfiResult<StatsResponse> = FfiResult::success(stats_response);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<Option<super::peer_sessions::ResumedSession>> =
+            FfiResult::success(transport.resume_peer_session(&peer_id, &device_key));
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-// =============================================================================
-// Transaction Broadcasting
-// =============================================================================
-
-/// Prepare a transaction broadcast (fragments it and returns fragments with packets)
-/// Takes transaction bytes and returns fragments ready for BLE transmission
-#[no_mangle]
+/// Periodic tick for retry/timeout handling
 #[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_prepareBroadcast(
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_tick(
     mut env: JNIEnv,
     _class: JClass,
-    _handle: jlong,
-    transaction_bytes: JByteArray,
+    handle: jlong,
+    now_ms: jlong,
 ) -> jstring {
-    let result = (|| -> Result<String, String> {
-        tracing::info!("📡 FFI prepareBroadcast called");
-
-        let tx_bytes: Vec<u8> = env
-            .convert_byte_array(&transaction_bytes)
-            .map_err(|e| format!("Failed to read transaction: {}", e))?;
-
-        tracing::info!(
-            "Preparing broadcast for {} byte transaction",
-            tx_bytes.len()
-        );
-
-        // Fragment the transaction
-        let fragments = crate::ble::fragment_transaction(&tx_bytes);
-        let transaction_id = fragments[0].transaction_id;
-
-        // Create broadcaster to prepare packets
-        let broadcaster = crate::ble::TransactionBroadcaster::new(uuid::Uuid::new_v4());
-
-        // Prepare packet for each fragment
-        #[derive(serde::Serialize)]
-        struct FragmentPacket {
-            #[serde(rename = "transactionId")]
-            transaction_id: String,
-            #[serde(rename = "fragmentIndex")]
-            fragment_index: u16,
-            #[serde(rename = "totalFragments")]
-            total_fragments: u16,
-            #[serde(rename = "packetBytes")]
-            packet_bytes: String, // Base64-encoded mesh packet
-        }
+    let result = (|| {
+        let transport = get_core(handle)?;
+        let frames = transport.tick(now_ms as u64);
 
-        let mut fragment_packets = Vec::new();
-        for fragment in &fragments {
-            let packet_bytes = broadcaster.prepare_fragment_packet(fragment)?;
-            fragment_packets.push(FragmentPacket {
-                transaction_id: hex::encode(fragment.transaction_id),
-                fragment_index: fragment.fragment_index,
-                total_fragments: fragment.total_fragments,
-                packet_bytes: base64::encode(&packet_bytes),
-            });
-        }
+        // Encode frames as JSON array of base64 strings
+        use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+        let encoded: Vec<String> = frames.iter().map(|f| BASE64.encode(f)).collect();
 
-        tracing::info!(
-            "✅ Prepared {} fragment packets for broadcast",
-            fragment_packets.len()
-        );
+        let response: FfiResult<Vec<String>> = FfiResult::success(encoded);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
 
-        #[derive(serde::Serialize)]
-        struct BroadcastPreparation {
-            #[serde(rename = "transactionId")]
-            transaction_id: String,
-            #[serde(rename = "fragmentPackets")]
-            fragment_packets: Vec<FragmentPacket>,
-        }
+    create_result_string(&mut env, result)
+}
 
-        let preparation = BroadcastPreparation {
-            transaction_id: hex::encode(transaction_id),
-            fragment_packets,
-        };
+/// Get current metrics
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_metrics(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result = (|| {
+        let transport = get_core(handle)?;
+        let metrics = transport.metrics();
 
-        let response: FfiResult<BroadcastPreparation> = FfiResult::success(preparation);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<MetricsSnapshot> = FfiResult::success(metrics);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Get mesh health snapshot
-/// Returns current health metrics, peer status, and network topology
-#[no_mangle]
+/// Register (or, with a null listener, clear) the event listener for a handle
+///
+/// The listener's `onEvent(String)` receives each `ProtocolEvent` as JSON on
+/// the thread that produced it (the caller of `pushInbound`, etc.).
 #[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getHealthSnapshot(
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_setEventListener(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
+    listener: JObject,
 ) -> jstring {
-    let result = (|| -> Result<String, String> {
-        tracing::info!("💚 FFI getHealthSnapshot called");
-
+    let result = (|| {
         let transport = get_transport(handle)?;
-        let monitor = transport.health_monitor();
-        let snapshot = monitor.get_snapshot();
-
-        tracing::info!(
-            "✅ Health snapshot: {} peers, health score: {}",
-            snapshot.metrics.total_peers,
-            snapshot.metrics.health_score
-        );
 
-        #[derive(serde::Serialize)]
-        struct HealthSnapshotResponse {
-            #[serde(rename = "snapshot")]
-            snapshot: crate::ble::HealthSnapshot,
+        if listener.is_null() {
+            transport.set_event_callback(None);
+            info!("🔕 Event listener cleared for handle {}", handle);
+        } else {
+            let vm = env
+                .get_java_vm()
+                .map_err(|e| FfiError::invalid_input(format!("Failed to get JavaVM: {}", e)))?;
+            let listener = env
+                .new_global_ref(&listener)
+                .map_err(|e| format!("Failed to retain listener: {}", e))?;
+
+            let callback: super::transport::EventCallback =
+                Arc::new(move |event: &ProtocolEvent| {
+                    let Ok(json) = serde_json::to_string(event) else {
+                        return;
+                    };
+                    let mut env = match vm.attach_current_thread() {
+                        Ok(env) => env,
+                        Err(e) => {
+                            log::warn!("⚠️ Event listener: failed to attach thread: {}", e);
+                            return;
+                        }
+                    };
+                    let delivered = env.new_string(json).and_then(|json| {
+                        env.call_method(
+                            &listener,
+                            "onEvent",
+                            "(Ljava/lang/String;)V",
+                            &[(&json).into()],
+                        )
+                    });
+                    if let Err(e) = delivered {
+                        log::warn!("⚠️ Event listener threw: {}", e);
+                        let _ = env.exception_clear();
+                    }
+                });
+            transport.set_event_callback(Some(callback));
+            info!("🔔 Event listener registered for handle {}", handle);
         }
 
-        let response: FfiResult<HealthSnapshotResponse> =
-            FfiResult::success(HealthSnapshotResponse { snapshot });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<SuccessResponse> =
+            FfiResult::success(SuccessResponse { success: true });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Record peer heartbeat
-#[no_mangle]
+/// Clear transaction from buffers
 #[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_recordPeerHeartbeat(
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_clearTransaction(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    peer_id: JString,
+    tx_id: JString,
 ) -> jstring {
-    let result = (|| -> Result<String, String> {
-        tracing::info!("💓 FFI recordPeerHeartbeat called");
-
-        let peer_id: String = env
-            .get_string(&peer_id)
-            .map_err(|e| format!("Failed to read peer_id: {}", e))?
+    let result = (|| {
+        let transport = get_core(handle)?;
+        let tx_id_str: String = env
+            .get_string(&tx_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read tx_id: {}", e)))?
             .into();
 
-        let transport = get_transport(handle)?;
-        let monitor = transport.health_monitor();
-        monitor.record_heartbeat(&peer_id);
-
-        tracing::info!("✅ Recorded heartbeat for peer: {}", peer_id);
-
-        #[derive(serde::Serialize)]
-        struct SuccessResponse {
-            success: bool,
-        }
+        transport.clear_transaction(&tx_id_str);
 
-        let response: FfiResult<SuccessResponse> =
-            FfiResult::success(SuccessResponse { success: true });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<()> = FfiResult::success(());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Record peer latency measurement
-#[no_mangle]
+/// Remove all outbound queue fragments that belong to `tx_id`.
+/// Must be called when a BLE confirmation arrives (success or failure) so the
+/// originating device stops re-broadcasting a transaction already handled by a
+/// relay peer.
 #[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_recordPeerLatency(
-    mut env: JNIEnv,
-    _class: JClass,
-    handle: jlong,
-    peer_id: JString,
-    latency_ms: jint,
-) -> jstring {
-    let result = (|| -> Result<String, String> {
-        tracing::info!("⏱️ FFI recordPeerLatency called");
-
-        let peer_id: String = env
-            .get_string(&peer_id)
-            .map_err(|e| format!("Failed to read peer_id: {}", e))?
-            .into();
-
-        let transport = get_transport(handle)?;
-        let monitor = transport.health_monitor();
-        monitor.record_latency(&peer_id, latency_ms as u32);
-
-        tracing::info!("✅ Recorded {}ms latency for peer: {}", latency_ms, peer_id);
-
-        #[derive(serde::Serialize)]
-        struct SuccessResponse {
-            success: bool,
-        }
-
-        let response: FfiResult<SuccessResponse> =
-            FfiResult::success(SuccessResponse { success: true });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
-    })();
-
-    create_result_string(&mut env, result)
-}
-
-/// Record peer RSSI (signal strength)
 #[no_mangle]
-#[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_recordPeerRssi(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_clearOutboundTransaction(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    peer_id: JString,
-    rssi: jint,
+    tx_id: JString,
 ) -> jstring {
-    let result = (|| -> Result<String, String> {
-        tracing::info!("📶 FFI recordPeerRssi called");
-
-        let peer_id: String = env
-            .get_string(&peer_id)
-            .map_err(|e| format!("Failed to read peer_id: {}", e))?
+    let result = (|| {
+        let transport = get_core(handle)?;
+        let tx_id_str: String = env
+            .get_string(&tx_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read tx_id: {}", e)))?
             .into();
 
-        let transport = get_transport(handle)?;
-        let monitor = transport.health_monitor();
-        monitor.record_rssi(&peer_id, rssi as i8);
-
-        tracing::info!("✅ Recorded {}dBm RSSI for peer: {}", rssi, peer_id);
+        let removed = transport.clear_outbound_for_tx(&tx_id_str);
 
         #[derive(serde::Serialize)]
-        struct SuccessResponse {
-            success: bool,
+        struct Out {
+            removed: usize,
         }
-
-        let response: FfiResult<SuccessResponse> =
-            FfiResult::success(SuccessResponse { success: true });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<Out> = FfiResult::success(Out { removed });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Push a received transaction into the auto-submission queue
+// =============================================================================
+// Fragmentation API (M6)
+// =============================================================================
+
+/// Fragment a transaction for BLE transmission
+///
+/// Optionally accepts max_payload (MTU - 10) for MTU-aware fragmentation
+#[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_pushReceivedTransaction(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_fragment(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    transaction_bytes: JByteArray,
+    tx_bytes: JByteArray,
+    max_payload: jlong,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        let tx_bytes: Vec<u8> = env
-            .convert_byte_array(&transaction_bytes)
-            .map_err(|e| format!("Failed to read transaction bytes: {}", e))?;
+    let result = (|| {
+        let transport = get_transport(handle)?;
+        let tx_data: Vec<u8> = env
+            .convert_byte_array(&tx_bytes)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read tx bytes: {}", e)))?;
 
-        let transport = get_core(handle)?;
+        let max_payload_opt = if max_payload > 0 {
+            Some(max_payload as usize)
+        } else {
+            None
+        };
         log::info!(
-            "📥 pushReceivedTransaction handle={} bytes={}",
+            "✂️  fragment handle={} input_bytes={} max_payload={:?}",
             handle,
-            tx_bytes.len()
+            tx_data.len(),
+            max_payload_opt
         );
 
-        let added = transport.push_received_transaction(tx_bytes);
-
-        #[derive(serde::Serialize)]
-        struct PushResponse {
-            added: bool,
-            queue_size: usize,
-        }
-
-        let queue_size = transport.received_queue_size();
-        if added {
-            log::info!(
-                "✅ pushReceivedTransaction accepted — queue_size={}",
-                queue_size
-            );
-        } else {
-            log::info!(
-                "⚠️  pushReceivedTransaction duplicate/full — queue_size={}",
-                queue_size
-            );
-        }
-
-        let response: FfiResult<PushResponse> =
-            FfiResult::success(PushResponse { added, queue_size });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
-    })();
-
-    create_result_string(&mut env, result)
-}
+        let fragments = transport.queue_transaction(tx_data, max_payload_opt)?;
 
-/// Get next received transaction for auto-submission
-#[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_nextReceivedTransaction(
-    mut env: JNIEnv,
-    _class: JClass,
-    handle: jlong,
-) -> jstring {
-    let result: Result<String, String> = (|| {
-        log::debug!(
-            "🔍 FFI nextReceivedTransaction called with handle: {}",
-            handle
+        let total_fragment_bytes: usize = fragments.iter().map(|f| f.data.len()).sum();
+        log::info!(
+            "✅ fragment → {} fragments, {} total payload bytes",
+            fragments.len(),
+            total_fragment_bytes
         );
-        let transport = get_core(handle)?;
-        match transport.next_received_transaction() {
-            Some((tx_id, tx_bytes, received_at)) => {
-                log::debug!(
-                    "✅ Popped transaction {} ({} bytes) from queue",
-                    tx_id,
-                    tx_bytes.len()
-                );
-                use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
-
-                #[derive(serde::Serialize)]
-                struct ReceivedTransaction {
-                    #[serde(rename = "txId")]
-                    tx_id: String,
-                    #[serde(rename = "transactionBase64")]
-                    transaction_base64: String,
-                    #[serde(rename = "receivedAt")]
-                    received_at: u64,
-                }
-
-                let response: FfiResult<ReceivedTransaction> =
-                    FfiResult::success(ReceivedTransaction {
-                        tx_id,
-                        transaction_base64: BASE64.encode(&tx_bytes),
-                        received_at,
-                    });
 
-                serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
-            }
-            None => {
-                log::debug!("📭 No transaction in queue, returning None");
-                let response: FfiResult<Option<String>> = FfiResult::success(None);
-                let json_response = serde_json::to_string(&response)
-                    .map_err(|e| format!("Serialization error: {}", e))?;
-                log::debug!(
-                    "📤 FFI nextReceivedTransaction returning None (JSON: {})",
-                    json_response
-                );
-                Ok(json_response)
-            }
-        }
+        let fragment_list = FragmentList { fragments };
+        let response: FfiResult<FragmentList> = FfiResult::success(fragment_list);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Get count of transactions waiting for auto-submission
+/// Fragment a transaction sealed for one gateway (base58 device key), so
+/// relays carry only ciphertext and a routing hint
+#[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getReceivedQueueSize(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_fragmentSealed(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
+    tx_bytes: JByteArray,
+    gateway_key: JString,
+    max_payload: jlong,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        log::debug!("🔍 FFI getReceivedQueueSize called with handle: {}", handle);
-        let transport = get_core(handle)?;
-        log::debug!("✅ Got transport instance for handle {}", handle);
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let tx_data: Vec<u8> = env
+            .convert_byte_array(&tx_bytes)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read tx bytes: {}", e)))?;
+        let gateway_key: String = env
+            .get_string(&gateway_key)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read gateway key: {}", e)))?
+            .into();
+        let max_payload_opt = (max_payload > 0).then_some(max_payload as usize);
 
-        let queue_size = transport.received_queue_size();
-        #[derive(serde::Serialize)]
-        struct QueueSizeResponse {
-            #[serde(rename = "queueSize")]
-            queue_size: usize,
-        }
+        let fragments = transport
+            .queue_sealed_transaction(tx_data, &gateway_key, max_payload_opt)
+            .map_err(FfiError::invalid_input)?;
 
-        let response: FfiResult<QueueSizeResponse> =
-            FfiResult::success(QueueSizeResponse { queue_size });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<FragmentList> = FfiResult::success(FragmentList { fragments });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Get fragment reassembly info for all incomplete transactions
+/// Fragment several transactions for the same peer as one batched stream
+///
+/// Takes a JSON array of base64 transactions; returns the FragmentList of
+/// the batch (a single transaction is fragmented as with `fragment`)
+#[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getFragmentReassemblyInfo(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_fragmentBatch(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
+    transactions_json: JString,
+    max_payload: jlong,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        log::debug!(
-            "🔍 FFI getFragmentReassemblyInfo called with handle: {}",
-            handle
-        );
-        let transport = get_transport(handle)?;
-        log::debug!("✅ Got transport instance for handle {}", handle);
-
-        let info_list = transport.get_fragment_reassembly_info();
+    let result = (|| {
+        use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 
-        use crate::ffi::types::FragmentReassemblyInfoList;
+        let transport = get_transport(handle)?;
+        let json: String = env
+            .get_string(&transactions_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read transactions: {}", e)))?
+            .into();
+        let encoded: Vec<String> = serde_json::from_str(&json)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid transactions JSON: {}", e)))?;
+        let transactions = encoded
+            .iter()
+            .map(|tx| BASE64.decode(tx))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| FfiError::invalid_input(format!("Invalid base64 transaction: {}", e)))?;
 
-        let response_data = FragmentReassemblyInfoList {
-            transactions: info_list,
+        let max_payload_opt = if max_payload > 0 {
+            Some(max_payload as usize)
+        } else {
+            None
         };
+        let fragments = transport.queue_transaction_batch(transactions, max_payload_opt)?;
+        log::info!(
+            "✅ fragmentBatch → {} transactions in {} fragments",
+            encoded.len(),
+            fragments.len()
+        );
 
-        let response: FfiResult<FragmentReassemblyInfoList> = FfiResult::success(response_data);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let fragment_list = FragmentList { fragments };
+        let response: FfiResult<FragmentList> = FfiResult::success(fragment_list);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Mark a transaction as successfully submitted
-#[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_markTransactionSubmitted(
-    mut env: JNIEnv,
-    _class: JClass,
-    handle: jlong,
-    transaction_bytes: JByteArray,
-) -> jstring {
-    let result: Result<String, String> = (|| {
-        let tx_bytes: Vec<u8> = env
-            .convert_byte_array(&transaction_bytes)
-            .map_err(|e| format!("Failed to read transaction bytes: {}", e))?;
+// =============================================================================
+// Helper functions
+// =============================================================================
 
-        let transport = get_transport(handle)?;
-        // Log SHA-256 prefix for dedup tracing without logging the full tx
-        let hash_prefix = {
-            use sha2::{Digest, Sha256};
-            let h = Sha256::digest(&tx_bytes);
-            hex::encode(&h[..4])
-        };
-        log::info!(
-            "🔖 markTransactionSubmitted handle={} sha256_prefix={} bytes={}",
-            handle,
-            hash_prefix,
-            tx_bytes.len()
-        );
-        transport.mark_transaction_submitted(&tx_bytes);
+/// Resolve a handle to the concrete BLE engine. Used by BLE-specific FFI functions
+/// (queue manager, health, intent building). Returns an error for non-BLE handles.
+#[cfg(feature = "android")]
+fn get_transport(handle: jlong) -> Result<Arc<HostBleTransport>, FfiError> {
+    TRANSPORTS
+        .with(handle, |entry| {
+            entry.ble.clone().ok_or_else(|| {
+                FfiError::new(
+                    FfiErrorCode::InvalidHandle,
+                    format!(
+                        "Handle {} is a {} transport (no BLE-specific surface)",
+                        handle,
+                        entry.kind.as_str()
+                    ),
+                )
+            })
+        })
+        .map_err(FfiError::from)?
+}
 
-        #[derive(serde::Serialize)]
-        struct SuccessResponse {
-            success: bool,
-        }
+/// Resolve a handle to the radio-agnostic transport contract. Works for BLE and Wi-Fi
+/// Direct alike — used by the byte-level FFI functions (pushInbound/nextOutbound/…).
+#[cfg(feature = "android")]
+fn get_core(handle: jlong) -> Result<Arc<dyn HostTransport>, FfiError> {
+    TRANSPORTS
+        .with(handle, |e| e.core.clone())
+        .map_err(FfiError::from)
+}
 
-        let response: FfiResult<SuccessResponse> =
-            FfiResult::success(SuccessResponse { success: true });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
-    })();
+#[cfg(feature = "android")]
+fn create_result_string(env: &mut JNIEnv, result: Result<String, FfiError>) -> jstring {
+    if let Err(e) = &result {
+        log::error!("❌ FFI error: {}", e);
+    }
+    env.new_string(result_envelope(result))
+        .expect("Failed to create Java string")
+        .into_raw()
+}
 
-    create_result_string(&mut env, result)
+fn parse_log_level(level: Option<&str>) -> tracing::Level {
+    match level {
+        Some("trace") => tracing::Level::TRACE,
+        Some("debug") => tracing::Level::DEBUG,
+        Some("info") => tracing::Level::INFO,
+        Some("warn") => tracing::Level::WARN,
+        Some("error") => tracing::Level::ERROR,
+        _ => tracing::Level::INFO,
+    }
 }
 
-/// Clean up old submitted transaction hashes
+/// Reconstruct a transaction from fragments
+/// Takes JSON array of fragment objects with base64 data
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_cleanupOldSubmissions(
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_reconstructTransaction(
     mut env: JNIEnv,
     _class: JClass,
-    handle: jlong,
+    fragments_json: JByteArray,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        let transport = get_transport(handle)?;
-        transport.cleanup_old_submissions();
+    let result = (|| -> Result<String, FfiError> {
+        tracing::info!("🔗 FFI reconstructTransaction called");
 
-        #[derive(serde::Serialize)]
-        struct SuccessResponse {
-            success: bool,
+        let json_data: Vec<u8> = env.convert_byte_array(&fragments_json).map_err(|e| {
+            FfiError::invalid_input(format!("Failed to read fragments JSON: {}", e))
+        })?;
+
+        // Parse fragment data from JSON
+        #[derive(serde::Deserialize)]
+        struct FragmentData {
+            #[serde(rename = "transactionId")]
+            transaction_id: String,
+            #[serde(rename = "fragmentIndex")]
+            fragment_index: u16,
+            #[serde(rename = "totalFragments")]
+            total_fragments: u16,
+            #[serde(rename = "dataBase64")]
+            data_base64: String,
         }
 
-        let response: FfiResult<SuccessResponse> =
-            FfiResult::success(SuccessResponse { success: true });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
-    })();
+        let fragment_data: Vec<FragmentData> = serde_json::from_slice(&json_data).map_err(|e| {
+            FfiError::invalid_input(format!("Failed to parse fragments JSON: {}", e))
+        })?;
 
-    create_result_string(&mut env, result)
-}
+        tracing::info!("Reconstructing from {} fragments", fragment_data.len());
 
-/// Get outbound queue size (non-destructive peek for debugging)
-#[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getOutboundQueueSize(
-    mut env: JNIEnv,
-    _class: JClass,
-    handle: jlong,
-) -> jstring {
-    let result: Result<String, String> = (|| {
-        let transport = get_transport(handle)?;
-        let queue_size = transport.outbound_queue_size();
+        // Convert to internal fragment format
+        let fragments: Vec<crate::ble::mesh::TransactionFragment> = fragment_data
+            .iter()
+            .map(|f| {
+                let mut tx_id = [0u8; 32];
+                let tx_id_bytes = hex::decode(&f.transaction_id).map_err(|e| {
+                    FfiError::invalid_input(format!("Invalid transaction ID: {}", e))
+                })?;
+                tx_id.copy_from_slice(&tx_id_bytes);
 
-        #[derive(serde::Serialize)]
-        struct QueueSizeResponse {
-            #[serde(rename = "queueSize")]
-            queue_size: usize,
-        }
+                let data = base64::decode(&f.data_base64).map_err(|e| {
+                    FfiError::invalid_input(format!("Invalid fragment data: {}", e))
+                })?;
 
-        let response: FfiResult<QueueSizeResponse> =
-            FfiResult::success(QueueSizeResponse { queue_size });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+                Ok(crate::ble::mesh::TransactionFragment {
+                    transaction_id: tx_id,
+                    fragment_index: f.fragment_index,
+                    total_fragments: f.total_fragments,
+                    data,
+                })
+            })
+            .collect::<Result<Vec<_>, FfiError>>()?;
+
+        // Reconstruct the transaction
+        let reconstructed = crate::ble::reconstruct_transaction(&fragments)
+            .map_err(|e| format!("Reconstruction failed: {}", e))?;
+
+        tracing::info!(
+            "✅ Reconstructed transaction: {} bytes",
+            reconstructed.len()
+        );
+
+        // Return base64-encoded transaction
+        let tx_base64 = base64::encode(&reconstructed);
+
+        let response: FfiResult<String> = FfiResult::success(tx_base64);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Get outbound queue debug info (non-destructive peek)
+/// Get fragmentation statistics for a transaction
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_debugOutboundQueue(
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getFragmentationStats(
     mut env: JNIEnv,
     _class: JClass,
-    handle: jlong,
+    transaction_bytes: JByteArray,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        let transport = get_transport(handle)?;
-        let queue_info = transport.outbound_queue_debug();
+    let result = (|| -> Result<String, FfiError> {
+        tracing::info!("📊 FFI getFragmentationStats called");
 
-        #[derive(serde::Serialize)]
-        struct FragmentInfo {
-            index: usize,
-            size: usize,
-        }
+        let tx_bytes: Vec<u8> = env
+            .convert_byte_array(&transaction_bytes)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read transaction: {}", e)))?;
+
+        let stats = crate::ble::FragmentationStats::calculate(&tx_bytes);
 
         #[derive(serde::Serialize)]
-        struct QueueDebugResponse {
-            total_fragments: usize,
-            fragments: Vec<FragmentInfo>,
+        struct StatsResponse {
+            #[serde(rename = "originalSize")]
+            original_size: usize,
+            #[serde(rename = "fragmentCount")]
+            fragment_count: usize,
+            #[serde(rename = "maxFragmentSize")]
+            max_fragment_size: usize,
+            #[serde(rename = "avgFragmentSize")]
+            avg_fragment_size: usize,
+            #[serde(rename = "totalOverhead")]
+            total_overhead: usize,
+            #[serde(rename = "efficiency")]
+            efficiency: f32,
         }
 
-        let fragments: Vec<FragmentInfo> = queue_info
-            .iter()
-            .map(|(idx, size)| FragmentInfo {
-                index: *idx,
-                size: *size,
-            })
-            .collect();
-
-        let total_bytes: usize = fragments.iter().map(|f| f.size).sum();
-
-        tracing::info!(
-            "🔍 Queue debug: {} fragments, {} total bytes",
-            fragments.len(),
-            total_bytes
-        );
-
-        let response = QueueDebugResponse {
-            total_fragments: fragments.len(),
-            fragments,
+        let stats_response = StatsResponse {
+            original_size: stats.original_size,
+            fragment_count: stats.fragment_count,
+            max_fragment_size: stats.max_fragment_size,
+            avg_fragment_size: stats.avg_fragment_size,
+            total_overhead: stats.total_overhead,
+            efficiency: stats.efficiency,
         };
 
-        let ffi_response: FfiResult<QueueDebugResponse> = FfiResult::success(response);
-        serde_json::to_string(&ffi_response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<StatsResponse> = FfiResult::success(stats_response);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
 // =============================================================================
-// Queue Persistence FFI Functions (Phase 5)
+// Transaction Broadcasting
 // =============================================================================
 
-/// Save all queues to disk
-#[cfg(feature = "android")]
+/// Prepare a transaction broadcast (fragments it and returns fragments with packets)
+/// Takes transaction bytes and returns fragments ready for BLE transmission
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_saveQueues(
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_prepareBroadcast(
     mut env: JNIEnv,
     _class: JClass,
-    handle: jlong,
+    _handle: jlong,
+    transaction_bytes: JByteArray,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        let transport = get_transport(handle)?;
+    let result = (|| -> Result<String, FfiError> {
+        tracing::info!("📡 FFI prepareBroadcast called");
 
-        runtime::block_on(async {
-            // Save queue manager queues (outbound, retry, confirmation)
-            transport
-                .sdk
-                .queue_manager()
-                .force_save()
-                .await
-                .map_err(|e| format!("Failed to save queues: {}", e))?;
+        let tx_bytes: Vec<u8> = env
+            .convert_byte_array(&transaction_bytes)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read transaction: {}", e)))?;
 
-            // Save received queue if storage directory is available
-            if let Some(queue_storage_dir) = transport.get_queue_storage_dir() {
-                if let Err(e) = transport.save_received_queue(&queue_storage_dir) {
-                    log::warn!("⚠️ Failed to save received queue: {}", e);
-                    // Don't fail the entire operation if received queue save fails
-                }
-            }
+        tracing::info!(
+            "Preparing broadcast for {} byte transaction",
+            tx_bytes.len()
+        );
 
-            Ok::<(), String>(())
-        })?;
+        // Fragment the transaction
+        let fragments = crate::ble::fragment_transaction(&tx_bytes);
+        let transaction_id = fragments[0].transaction_id;
 
-        let response: FfiResult<SuccessResponse> =
-            FfiResult::success(SuccessResponse { success: true });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
-    })();
+        // Create broadcaster to prepare packets
+        let broadcaster = crate::ble::TransactionBroadcaster::new(uuid::Uuid::new_v4());
 
-    create_result_string(&mut env, result)
-}
+        // Prepare packet for each fragment
+        #[derive(serde::Serialize)]
+        struct FragmentPacket {
+            #[serde(rename = "transactionId")]
+            transaction_id: String,
+            #[serde(rename = "fragmentIndex")]
+            fragment_index: u16,
+            #[serde(rename = "totalFragments")]
+            total_fragments: u16,
+            #[serde(rename = "packetBytes")]
+            packet_bytes: String, // Base64-encoded mesh packet
+        }
 
-/// Trigger auto-save if needed (debounced)
-#[cfg(feature = "android")]
-#[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_autoSaveQueues(
-    mut env: JNIEnv,
-    _class: JClass,
-    handle: jlong,
-) -> jstring {
-    let result: Result<String, String> = (|| {
-        let transport = get_transport(handle)?;
+        let mut fragment_packets = Vec::new();
+        for fragment in &fragments {
+            let packet_bytes = broadcaster.prepare_fragment_packet(fragment)?;
+            fragment_packets.push(FragmentPacket {
+                transaction_id: hex::encode(fragment.transaction_id),
+                fragment_index: fragment.fragment_index,
+                total_fragments: fragment.total_fragments,
+                packet_bytes: base64::encode(&packet_bytes),
+            });
+        }
 
-        runtime::block_on(async {
-            // Auto-save queue manager queues (outbound, retry, confirmation)
-            transport
-                .sdk
-                .queue_manager()
-                .save_if_needed()
-                .await
-                .map_err(|e| format!("Failed to auto-save queues: {}", e))?;
+        tracing::info!(
+            "✅ Prepared {} fragment packets for broadcast",
+            fragment_packets.len()
+        );
 
-            // Auto-save received queue if storage directory is available
-            // Note: Received queue uses the same debouncing as queue manager
-            if let Some(queue_storage_dir) = transport.get_queue_storage_dir() {
-                if let Err(e) = transport.save_received_queue(&queue_storage_dir) {
-                    log::warn!("⚠️ Failed to auto-save received queue: {}", e);
-                    // Don't fail the entire operation if received queue save fails
-                }
-            }
+        #[derive(serde::Serialize)]
+        struct BroadcastPreparation {
+            #[serde(rename = "transactionId")]
+            transaction_id: String,
+            #[serde(rename = "fragmentPackets")]
+            fragment_packets: Vec<FragmentPacket>,
+        }
 
-            Ok::<(), String>(())
-        })?;
+        let preparation = BroadcastPreparation {
+            transaction_id: hex::encode(transaction_id),
+            fragment_packets,
+        };
 
-        let response: FfiResult<SuccessResponse> =
-            FfiResult::success(SuccessResponse { success: true });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<BroadcastPreparation> = FfiResult::success(preparation);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-// =============================================================================
-// Queue Management FFI Functions (Phase 2)
-// =============================================================================
-
-/// Push transaction to outbound queue
-#[cfg(feature = "android")]
+/// Get mesh health snapshot
+/// Returns current health metrics, peer status, and network topology
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_pushOutboundTransaction(
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getHealthSnapshot(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    request_json: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        let transport = get_transport(handle)?;
-        let request_str: String = env
-            .get_string(&request_json)
-            .map_err(|e| format!("Failed to get request string: {}", e))?
-            .into();
+    let result = (|| -> Result<String, FfiError> {
+        tracing::info!("💚 FFI getHealthSnapshot called");
 
-        let request: PushOutboundRequest = serde_json::from_str(&request_str)
-            .map_err(|e| format!("Failed to parse request: {}", e))?;
+        let transport = get_transport(handle)?;
+        let monitor = transport.health_monitor();
+        let snapshot = monitor.get_snapshot();
 
-        log::info!(
-            "📤 pushOutboundTransaction handle={} tx_id={} fragments={} priority={:?}",
-            handle,
-            &request.tx_id[..8.min(request.tx_id.len())],
-            request.fragments.len(),
-            request.priority
+        tracing::info!(
+            "✅ Health snapshot: {} peers, health score: {}",
+            snapshot.metrics.total_peers,
+            snapshot.metrics.health_score
         );
 
-        // Convert FFI fragments to mesh fragments
-        let fragments: Result<Vec<crate::ble::mesh::TransactionFragment>, String> = request
-            .fragments
-            .iter()
-            .map(|f| {
-                let tx_id = hex::decode(&f.transaction_id)
-                    .map_err(|e| format!("Invalid transaction ID: {}", e))?;
-                if tx_id.len() != 32 {
-                    return Err("Transaction ID must be 32 bytes".to_string());
-                }
-                let mut tx_id_array = [0u8; 32];
-                tx_id_array.copy_from_slice(&tx_id);
-
-                let data = base64::decode(&f.data_base64)
-                    .map_err(|e| format!("Invalid fragment data: {}", e))?;
-
-                Ok(crate::ble::mesh::TransactionFragment {
-                    transaction_id: tx_id_array,
-                    fragment_index: f.fragment_index,
-                    total_fragments: f.total_fragments,
-                    data,
-                })
-            })
-            .collect();
-
-        let fragments = fragments?;
-        let tx_bytes = base64::decode(&request.tx_bytes)
-            .map_err(|e| format!("Invalid transaction bytes: {}", e))?;
-
-        // Convert priority
-        let priority = match request.priority {
-            PriorityFFI::High => crate::queue::Priority::High,
-            PriorityFFI::Normal => crate::queue::Priority::Normal,
-            PriorityFFI::Low => crate::queue::Priority::Low,
-        };
-
-        // Create outbound transaction
-        let outbound_tx =
-            crate::queue::OutboundTransaction::new(request.tx_id, tx_bytes, fragments, priority);
-
-        // Push to queue
-        runtime::block_on(async {
-            let mut queue = transport.sdk.queue_manager().outbound.write().await;
-            queue
-                .push(outbound_tx)
-                .map_err(|e| format!("Failed to push to queue: {}", e))?;
-            Ok::<(), String>(())
-        })?;
+        #[derive(serde::Serialize)]
+        struct HealthSnapshotResponse {
+            #[serde(rename = "snapshot")]
+            snapshot: crate::ble::HealthSnapshot,
+        }
 
-        log::info!("✅ pushOutboundTransaction enqueued");
-        let response: FfiResult<SuccessResponse> =
-            FfiResult::success(SuccessResponse { success: true });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<HealthSnapshotResponse> =
+            FfiResult::success(HealthSnapshotResponse { snapshot });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Accept and queue a pre-signed transaction from external partners
-/// Verifies the transaction, compresses it if needed, fragments it, and adds to queue
-#[cfg(feature = "android")]
+/// Get the live mesh view: discovered peers, connected peers, per-peer stats and topology
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_acceptAndQueueExternalTransaction(
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getMeshState(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    request_json: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result = (|| -> Result<String, FfiError> {
         let transport = get_transport(handle)?;
-        let request_str: String = env
-            .get_string(&request_json)
-            .map_err(|e| format!("Failed to get request string: {}", e))?
-            .into();
-
-        let request: AcceptExternalTransactionRequest = serde_json::from_str(&request_str)
-            .map_err(|e| format!("Failed to parse request: {}", e))?;
-
-        let tx_id = runtime::block_on(async {
-            // First, verify and queue in priority queue (for tracking/management)
-            transport
-                .sdk
-                .accept_and_queue_external_transaction(
-                    &request.base64_signed_tx,
-                    request.max_payload,
-                )
-                .await
-        })
-        .map_err(|e| format!("Failed to accept and queue external transaction: {}", e))?;
-
-        // CRITICAL FIX: Also populate transport.outbound_queue so next_outbound() can read fragments
-        // The transaction was already verified and fragmented by accept_and_queue_external_transaction
-        // Now we need to get those fragments and add them to the fragment queue
-        runtime::block_on(async {
-            // Get mutable access to the queue to pop transactions
-            let mut queue = transport.sdk.queue_manager().outbound.write().await;
-
-            // Pop transactions until we find the one we just added
-            let mut found_tx = None;
-            let mut popped_txs = Vec::new();
-
-            // Search through all priorities by popping
-            while let Some(tx) = queue.pop() {
-                if tx.tx_id == tx_id {
-                    found_tx = Some(tx);
-                    break;
-                } else {
-                    popped_txs.push(tx);
-                }
-            }
-
-            // Put back all the transactions we popped (maintain original order)
-            // Note: push() will add to the correct priority queue based on tx.priority
-            for tx in popped_txs {
-                // Re-add to queue (this will maintain priority)
-                if let Err(e) = queue.push(tx) {
-                    tracing::warn!("⚠️ Failed to re-queue transaction: {}", e);
-                }
-            }
-
-            if let Some(tx) = found_tx {
-                // Store fragment count before moving tx
-                let fragment_count = tx.fragments.len();
-
-                // Queue fragments directly using the public method
-                transport.queue_fragments(&tx.fragments)
-                    .map_err(|e| format!("Failed to queue fragments: {}", e))?;
-
-                // Put the transaction back in the priority queue (for management/tracking)
-                queue.push(tx).map_err(|e| format!("Failed to re-queue transaction: {}", e))?;
-
-                tracing::info!("✅ External transaction {} fragments added to transport outbound queue ({} fragments)", tx_id, fragment_count);
-            } else {
-                tracing::warn!("⚠️ Could not find queued transaction {} to populate fragment queue", tx_id);
-            }
-
-            Ok::<(), String>(())
-        }).map_err(|e| format!("Failed to populate fragment queue: {}", e))?;
+        let state = transport.mesh_state();
+        tracing::debug!(
+            "🕸️ Mesh state: {} discovered, {} connected",
+            state.discovered_peers.len(),
+            state.connected_peers.len()
+        );
 
-        let response: FfiResult<String> = FfiResult::success(tx_id);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<MeshStateFFI> = FfiResult::success(state);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Pop next transaction from outbound queue
-#[cfg(feature = "android")]
+/// Get link statistics for one peer (`data` is null for unknown peers)
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_popOutboundTransaction(
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getPeerHealth(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
+    peer_id: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        let transport = get_transport(handle)?;
-
-        let tx_opt = runtime::block_on(async {
-            let mut queue = transport.sdk.queue_manager().outbound.write().await;
-            queue.pop()
-        });
+    let result = (|| -> Result<String, FfiError> {
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer_id: {}", e)))?
+            .into();
 
-        if let Some(tx) = tx_opt {
-            log::info!(
-                "📦 popOutboundTransaction → tx_id={} fragments={} priority={:?}",
-                &tx.tx_id[..8.min(tx.tx_id.len())],
-                tx.fragments.len(),
-                tx.priority
-            );
-            let tx_ffi = OutboundTransactionFFI {
-                tx_id: tx.tx_id,
-                original_bytes: base64::encode(&tx.original_bytes),
-                fragment_count: tx.fragments.len(),
-                priority: match tx.priority {
-                    crate::queue::Priority::High => PriorityFFI::High,
-                    crate::queue::Priority::Normal => PriorityFFI::Normal,
-                    crate::queue::Priority::Low => PriorityFFI::Low,
-                },
-                created_at: tx.created_at,
-                retry_count: tx.retry_count,
-            };
+        let transport = get_transport(handle)?;
+        let peer = transport.health_monitor().get_peer_health(&peer_id);
 
-            let response: FfiResult<Option<OutboundTransactionFFI>> =
-                FfiResult::success(Some(tx_ffi));
-            serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
-        } else {
-            log::debug!("📭 popOutboundTransaction — queue empty");
-            let response: FfiResult<Option<OutboundTransactionFFI>> = FfiResult::success(None);
-            serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
-        }
+        let response: FfiResult<Option<crate::ble::PeerHealth>> = FfiResult::success(peer);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Add transaction to retry queue
-#[cfg(feature = "android")]
+/// Report the current connection graph (direct connections and what peers report)
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_addToRetryQueue(
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_updateMeshTopology(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    request_json: JString,
+    request_json: JByteArray,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result = (|| -> Result<String, FfiError> {
+        let request_data: Vec<u8> = env
+            .convert_byte_array(&request_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read request data: {}", e)))?;
+        let request: UpdateTopologyRequest = serde_json::from_slice(&request_data)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to parse request: {}", e)))?;
+
         let transport = get_transport(handle)?;
-        let request_str: String = env
-            .get_string(&request_json)
-            .map_err(|e| format!("Failed to get request string: {}", e))?
-            .into();
+        let monitor = transport.health_monitor();
+        monitor.update_direct_connections(request.direct_connections);
+        if !request.connections.is_empty() {
+            monitor.update_topology(request.connections);
+        }
 
-        let request: AddToRetryRequest = serde_json::from_str(&request_str)
-            .map_err(|e| format!("Failed to parse request: {}", e))?;
+        let response: FfiResult<SuccessResponse> =
+            FfiResult::success(SuccessResponse { success: true });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
 
-        let tx_bytes = base64::decode(&request.tx_bytes)
-            .map_err(|e| format!("Invalid transaction bytes: {}", e))?;
+    create_result_string(&mut env, result)
+}
 
-        log::info!(
-            "🔁 addToRetryQueue handle={} tx_id={} error={:?}",
-            handle,
-            &request.tx_id[..8.min(request.tx_id.len())],
-            request.error
-        );
+/// Record peer heartbeat
+#[no_mangle]
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_recordPeerHeartbeat(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    peer_id: JString,
+) -> jstring {
+    let result = (|| -> Result<String, FfiError> {
+        tracing::info!("💓 FFI recordPeerHeartbeat called");
 
-        let retry_item = crate::queue::RetryItem::new(tx_bytes, request.tx_id, request.error);
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer_id: {}", e)))?
+            .into();
 
-        runtime::block_on(async {
-            let mut queue = transport.sdk.queue_manager().retries.write().await;
-            queue
-                .push(retry_item)
-                .map_err(|e| format!("Failed to push to retry queue: {}", e))?;
-            Ok::<(), String>(())
-        })?;
+        let transport = get_transport(handle)?;
+        let monitor = transport.health_monitor();
+        monitor.record_heartbeat(&peer_id);
+
+        tracing::info!("✅ Recorded heartbeat for peer: {}", peer_id);
+
+        #[derive(serde::Serialize)]
+        struct SuccessResponse {
+            success: bool,
+        }
 
-        log::info!("✅ addToRetryQueue enqueued");
         let response: FfiResult<SuccessResponse> =
             FfiResult::success(SuccessResponse { success: true });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Pop next ready retry item
-#[cfg(feature = "android")]
+/// Record peer latency measurement
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_popReadyRetry(
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_recordPeerLatency(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
+    peer_id: JString,
+    latency_ms: jint,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        let transport = get_transport(handle)?;
+    let result = (|| -> Result<String, FfiError> {
+        tracing::info!("⏱️ FFI recordPeerLatency called");
 
-        let retry_opt = runtime::block_on(async {
-            let mut queue = transport.sdk.queue_manager().retries.write().await;
-            queue.pop_ready()
-        });
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer_id: {}", e)))?
+            .into();
 
-        if let Some(retry) = retry_opt {
-            let retry_ffi = RetryItemFFI {
-                tx_bytes: base64::encode(&retry.tx_bytes),
-                tx_id: retry.tx_id.clone(),
-                attempt_count: retry.attempt_count,
-                last_error: retry.last_error.clone(),
-                next_retry_in_secs: retry.time_until_retry().as_secs(),
-                age_seconds: retry.age().as_secs(),
-            };
+        let transport = get_transport(handle)?;
+        let monitor = transport.health_monitor();
+        monitor.record_latency(&peer_id, latency_ms as u32);
 
-            let response: FfiResult<Option<RetryItemFFI>> = FfiResult::success(Some(retry_ffi));
-            serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
-        } else {
-            let response: FfiResult<Option<RetryItemFFI>> = FfiResult::success(None);
-            serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        tracing::info!("✅ Recorded {}ms latency for peer: {}", latency_ms, peer_id);
+
+        #[derive(serde::Serialize)]
+        struct SuccessResponse {
+            success: bool,
         }
+
+        let response: FfiResult<SuccessResponse> =
+            FfiResult::success(SuccessResponse { success: true });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Get retry queue size
-#[cfg(feature = "android")]
+/// Record peer RSSI (signal strength)
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getRetryQueueSize(
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_recordPeerRssi(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
+    peer_id: JString,
+    rssi: jint,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result = (|| -> Result<String, FfiError> {
+        tracing::info!("📶 FFI recordPeerRssi called");
+
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer_id: {}", e)))?
+            .into();
+
         let transport = get_transport(handle)?;
+        let monitor = transport.health_monitor();
+        monitor.record_rssi(&peer_id, rssi as i8);
 
-        let size = runtime::block_on(async {
-            let queue = transport.sdk.queue_manager().retries.read().await;
-            queue.len()
-        });
+        tracing::info!("✅ Recorded {}dBm RSSI for peer: {}", rssi, peer_id);
 
         #[derive(serde::Serialize)]
-        struct QueueSizeResponse {
-            #[serde(rename = "queueSize")]
-            queue_size: usize,
+        struct SuccessResponse {
+            success: bool,
         }
 
-        let response: FfiResult<QueueSizeResponse> =
-            FfiResult::success(QueueSizeResponse { queue_size: size });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<SuccessResponse> =
+            FfiResult::success(SuccessResponse { success: true });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Cleanup expired confirmations and retry items
-#[cfg(feature = "android")]
+/// Push a received transaction into the auto-submission queue
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_cleanupExpired(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_pushReceivedTransaction(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
+    transaction_bytes: JByteArray,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        let transport = get_transport(handle)?;
-
-        let (confirmations_cleaned, retries_cleaned) = runtime::block_on(async {
-            let mut conf_queue = transport.sdk.queue_manager().confirmations.write().await;
-            let conf_cleaned = conf_queue.cleanup_expired();
+    let result: Result<String, FfiError> = (|| {
+        let tx_bytes: Vec<u8> = env.convert_byte_array(&transaction_bytes).map_err(|e| {
+            FfiError::invalid_input(format!("Failed to read transaction bytes: {}", e))
+        })?;
 
-            let mut retry_queue = transport.sdk.queue_manager().retries.write().await;
-            let retry_cleaned = retry_queue.cleanup_expired();
+        let transport = get_core(handle)?;
+        log::info!(
+            "📥 pushReceivedTransaction handle={} bytes={}",
+            handle,
+            tx_bytes.len()
+        );
 
-            (conf_cleaned, retry_cleaned)
-        });
+        let added = transport.push_received_transaction(tx_bytes);
 
         #[derive(serde::Serialize)]
-        struct CleanupExpiredResponse {
-            confirmations_cleaned: usize,
-            retries_cleaned: usize,
+        struct PushResponse {
+            added: bool,
+            queue_size: usize,
         }
 
-        let response: FfiResult<CleanupExpiredResponse> =
-            FfiResult::success(CleanupExpiredResponse {
-                confirmations_cleaned,
-                retries_cleaned,
-            });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let queue_size = transport.received_queue_size();
+        if added {
+            log::info!(
+                "✅ pushReceivedTransaction accepted — queue_size={}",
+                queue_size
+            );
+        } else {
+            log::info!(
+                "⚠️  pushReceivedTransaction duplicate/full — queue_size={}",
+                queue_size
+            );
+        }
+
+        let response: FfiResult<PushResponse> =
+            FfiResult::success(PushResponse { added, queue_size });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Confirm that all fragments for `tx_id` were delivered to the current peer.
-/// Decrements the transaction's relevance counter by 1. Evicts the transaction and
-/// returns { removed: true } when relevance hits 0 (fan-out exhausted).
-/// Returns { removed: false } when the transaction is retained for future peers.
-#[cfg(feature = "android")]
+/// Get next received transaction for auto-submission
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_confirmDelivered(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_nextReceivedTransaction(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    tx_id_j: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        let transport = get_transport(handle)?;
-        let tx_id: String = env.get_string(&tx_id_j).map_err(|e| e.to_string())?.into();
+    let result: Result<String, FfiError> = (|| {
+        log::debug!(
+            "🔍 FFI nextReceivedTransaction called with handle: {}",
+            handle
+        );
+        let transport = get_core(handle)?;
+        match transport.next_received_transaction() {
+            Some((tx_id, tx_bytes, received_at)) => {
+                log::debug!(
+                    "✅ Popped transaction {} ({} bytes) from queue",
+                    tx_id,
+                    tx_bytes.len()
+                );
+                use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 
-        let removed = runtime::block_on(async {
-            let mut queue = transport.sdk.queue_manager().outbound.write().await;
-            queue.confirm_delivered(&tx_id)
-        });
+                #[derive(serde::Serialize)]
+                struct ReceivedTransaction {
+                    #[serde(rename = "txId")]
+                    tx_id: String,
+                    #[serde(rename = "transactionBase64")]
+                    transaction_base64: String,
+                    #[serde(rename = "receivedAt")]
+                    received_at: u64,
+                }
 
-        #[derive(serde::Serialize)]
-        struct ConfirmDeliveredResponse {
-            removed: bool,
+                let response: FfiResult<ReceivedTransaction> =
+                    FfiResult::success(ReceivedTransaction {
+                        tx_id,
+                        transaction_base64: BASE64.encode(&tx_bytes),
+                        received_at,
+                    });
+
+                serde_json::to_string(&response).map_err(FfiError::serialization)
+            }
+            None => {
+                log::debug!("📭 No transaction in queue, returning None");
+                let response: FfiResult<Option<String>> = FfiResult::success(None);
+                let json_response =
+                    serde_json::to_string(&response).map_err(FfiError::serialization)?;
+                log::debug!(
+                    "📤 FFI nextReceivedTransaction returning None (JSON: {})",
+                    json_response
+                );
+                Ok(json_response)
+            }
         }
-        let response: FfiResult<ConfirmDeliveredResponse> =
-            FfiResult::success(ConfirmDeliveredResponse { removed });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
     })();
+
     create_result_string(&mut env, result)
 }
 
-/// Peek at the highest-relevance transaction in the outbound queue and load its
-/// fragments into the transport's BLE frame buffer so the sending loop can deliver
-/// them to the current peer. Returns the tx_id, current relevance, and fragment count,
-/// or null data if the queue is empty.
+/// Sender, recipient, amount and expiry of the transactions waiting for
+/// auto-submission, without removing them from the queue
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_loadForSending(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getReceivedTransactionMetadata(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-
-        // Peek under a read lock — clone the data we need so we don't hold the lock
-        // while calling queue_fragments (which takes an unrelated mutex).
-        let tx_info = runtime::block_on(async {
-            let queue = transport.sdk.queue_manager().outbound.read().await;
-            queue
-                .peek_highest_relevance()
-                .map(|tx| (tx.tx_id.clone(), tx.fragments.clone(), tx.relevance))
-        });
-
-        #[derive(serde::Serialize)]
-        struct LoadResponse {
-            tx_id: String,
-            relevance: u8,
-            fragment_count: usize,
-        }
-
-        if let Some((tx_id, fragments, relevance)) = tx_info {
-            transport
-                .queue_fragments(&fragments)
-                .map_err(|e| format!("Failed to load fragments into transport: {}", e))?;
-
-            let response: FfiResult<Option<LoadResponse>> =
-                FfiResult::success(Some(LoadResponse {
-                    tx_id,
-                    relevance,
-                    fragment_count: fragments.len(),
-                }));
-            serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
-        } else {
-            let response: FfiResult<Option<LoadResponse>> = FfiResult::success(None);
-            serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
-        }
+        let response: FfiResult<Vec<super::tx_metadata::ReceivedTxMetadata>> =
+            FfiResult::success(transport.received_transaction_metadata());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
+
     create_result_string(&mut env, result)
 }
 
-/// Purge outbound transactions older than max_age_secs from all priority queues.
-/// Call this at connection-start so stale relayed data is not forwarded.
-#[cfg(feature = "android")]
+/// Get count of transactions waiting for auto-submission
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_purgeStaleOutbound(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getReceivedQueueSize(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    max_age_secs: jlong,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        let transport = get_transport(handle)?;
-        let max_age = max_age_secs.max(0) as u64;
-
-        let removed = runtime::block_on(async {
-            let mut outbound = transport.sdk.queue_manager().outbound.write().await;
-            outbound.cleanup_stale(max_age)
-        });
+    let result: Result<String, FfiError> = (|| {
+        log::debug!("🔍 FFI getReceivedQueueSize called with handle: {}", handle);
+        let transport = get_core(handle)?;
+        log::debug!("✅ Got transport instance for handle {}", handle);
 
+        let queue_size = transport.received_queue_size();
         #[derive(serde::Serialize)]
-        struct PurgeResponse {
-            removed: usize,
+        struct QueueSizeResponse {
+            #[serde(rename = "queueSize")]
+            queue_size: usize,
         }
-        let response: FfiResult<PurgeResponse> = FfiResult::success(PurgeResponse { removed });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+
+        let response: FfiResult<QueueSizeResponse> =
+            FfiResult::success(QueueSizeResponse { queue_size });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
+
     create_result_string(&mut env, result)
 }
 
-/// Queue a confirmation for relay back to origin device
-#[cfg(feature = "android")]
+/// Get fragment reassembly info for all incomplete transactions
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_queueConfirmation(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getFragmentReassemblyInfo(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    request_json: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
+        log::debug!(
+            "🔍 FFI getFragmentReassemblyInfo called with handle: {}",
+            handle
+        );
         let transport = get_transport(handle)?;
+        log::debug!("✅ Got transport instance for handle {}", handle);
 
-        // Parse request JSON from Kotlin
-        let request_str: String = env
-            .get_string(&request_json)
-            .map_err(|e| format!("Failed to read request: {}", e))?
-            .into();
-
-        let request: QueueConfirmationRequest = serde_json::from_str(&request_str)
-            .map_err(|e| format!("Failed to parse request: {}", e))?;
+        let info_list = transport.get_fragment_reassembly_info();
 
-        tracing::info!(
-            "📨 Queueing confirmation for tx {} with signature {}...",
-            request.tx_id,
-            &request.signature[..std::cmp::min(16, request.signature.len())]
-        );
+        use crate::ffi::types::FragmentReassemblyInfoList;
 
-        // Push into confirmation queue (auto-relay subsystem)
-        runtime::block_on(async {
-            let mut conf_queue = transport.sdk.queue_manager().confirmations.write().await;
-            // Confirmation queue expects tx_id as [u8; 32]
-            let tx_id_bytes =
-                hex::decode(&request.tx_id).map_err(|e| format!("Invalid txId hex: {}", e))?;
-            if tx_id_bytes.len() != 32 {
-                return Err(format!(
-                    "Invalid txId length: expected 32 bytes, got {}",
-                    tx_id_bytes.len()
-                ));
-            }
-            let mut tx_id_array = [0u8; 32];
-            tx_id_array.copy_from_slice(&tx_id_bytes);
+        let response_data = FragmentReassemblyInfoList {
+            transactions: info_list,
+        };
 
-            let confirmation = crate::queue::confirmation::Confirmation::success(
-                tx_id_array,
-                request.signature.clone(),
-            );
+        let response: FfiResult<FragmentReassemblyInfoList> = FfiResult::success(response_data);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
 
-            conf_queue
-                .push(confirmation)
-                .map_err(|e| format!("Failed to queue confirmation: {:?}", e))?;
+    create_result_string(&mut env, result)
+}
 
-            Ok::<(), String>(())
+/// Mark a transaction as successfully submitted
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_markTransactionSubmitted(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    transaction_bytes: JByteArray,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let tx_bytes: Vec<u8> = env.convert_byte_array(&transaction_bytes).map_err(|e| {
+            FfiError::invalid_input(format!("Failed to read transaction bytes: {}", e))
         })?;
 
-        let response: FfiResult<crate::ffi::types::SuccessResponse> =
-            FfiResult::success(crate::ffi::types::SuccessResponse { success: true });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let transport = get_transport(handle)?;
+        // Log SHA-256 prefix for dedup tracing without logging the full tx
+        let hash_prefix = {
+            use sha2::{Digest, Sha256};
+            let h = Sha256::digest(&tx_bytes);
+            hex::encode(&h[..4])
+        };
+        log::info!(
+            "🔖 markTransactionSubmitted handle={} sha256_prefix={} bytes={}",
+            handle,
+            hash_prefix,
+            tx_bytes.len()
+        );
+        transport.mark_transaction_submitted(&tx_bytes);
+
+        #[derive(serde::Serialize)]
+        struct SuccessResponse {
+            success: bool,
+        }
+
+        let response: FfiResult<SuccessResponse> =
+            FfiResult::success(SuccessResponse { success: true });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Pop next confirmation from queue
-#[cfg(feature = "android")]
+/// Clean up old submitted transaction hashes
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_popConfirmation(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_cleanupOldSubmissions(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
+        transport.cleanup_old_submissions();
 
-        let confirmation = runtime::block_on(async {
-            let mut conf_queue = transport.sdk.queue_manager().confirmations.write().await;
-            conf_queue.pop()
-        });
-
-        if let Some(conf) = confirmation {
-            // Convert Rust Confirmation to FFI format
-            let tx_id_hex = hex::encode(conf.original_tx_id);
-            let status_ffi = match &conf.status {
-                crate::queue::confirmation::ConfirmationStatus::Success { signature } => {
-                    crate::ffi::types::ConfirmationStatusFFI::Success {
-                        signature: signature.clone(),
-                    }
-                }
-                crate::queue::confirmation::ConfirmationStatus::Failed { error } => {
-                    crate::ffi::types::ConfirmationStatusFFI::Failed {
-                        error: error.clone(),
-                    }
-                }
-            };
-
-            let conf_ffi = crate::ffi::types::ConfirmationFFI {
-                tx_id: tx_id_hex,
-                status: status_ffi,
-                timestamp: conf.timestamp,
-                relay_count: conf.relay_count,
-            };
-
-            let response: FfiResult<Option<crate::ffi::types::ConfirmationFFI>> =
-                FfiResult::success(Some(conf_ffi));
-            serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
-        } else {
-            let response: FfiResult<Option<crate::ffi::types::ConfirmationFFI>> =
-                FfiResult::success(None);
-            serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        #[derive(serde::Serialize)]
+        struct SuccessResponse {
+            success: bool,
         }
+
+        let response: FfiResult<SuccessResponse> =
+            FfiResult::success(SuccessResponse { success: true });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Cleanup stale fragments from the transaction cache
-#[cfg(feature = "android")]
+/// Get outbound queue size (non-destructive peek for debugging)
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_cleanupStaleFragments(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getOutboundQueueSize(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-
-        // Cleanup stale fragments (older than 5 minutes = 300 seconds)
-        // cleanup_stale_fragments is on TransactionCache, accessed via SDK's local_cache
-        let cleaned = runtime::block_on(async {
-            let mut cache = transport.sdk.local_cache.write().await;
-            cache.cleanup_stale_fragments(300)
-        });
+        let queue_size = transport.outbound_queue_size();
 
         #[derive(serde::Serialize)]
-        struct CleanupResponse {
-            fragments_cleaned: usize,
+        struct QueueSizeResponse {
+            #[serde(rename = "queueSize")]
+            queue_size: usize,
         }
 
-        let response: FfiResult<CleanupResponse> = FfiResult::success(CleanupResponse {
-            fragments_cleaned: cleaned,
-        });
-
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<QueueSizeResponse> =
+            FfiResult::success(QueueSizeResponse { queue_size });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
 
     create_result_string(&mut env, result)
 }
 
-/// Relay a received confirmation (increment hop count and re-queue for relay)
-/// This is called when a confirmation is received that needs to be relayed further
+/// Get outbound queue debug info (non-destructive peek)
 #[no_mangle]
-#[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_relayConfirmation(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_debugOutboundQueue(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    confirmation_json: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
+        let queue_info = transport.outbound_queue_debug();
 
-        // Parse confirmation JSON from Kotlin
-        let conf_str: String = env
-            .get_string(&confirmation_json)
-            .map_err(|e| format!("Failed to read confirmation JSON: {}", e))?
-            .into();
+        #[derive(serde::Serialize)]
+        struct FragmentInfo {
+            index: usize,
+            size: usize,
+        }
 
-        let conf_ffi: ConfirmationFFI = serde_json::from_str(&conf_str)
-            .map_err(|e| format!("Failed to parse confirmation: {}", e))?;
+        #[derive(serde::Serialize)]
+        struct QueueDebugResponse {
+            total_fragments: usize,
+            fragments: Vec<FragmentInfo>,
+        }
 
-        tracing::info!(
-            "🔄 Relaying confirmation for tx {} (current hops: {})",
-            &conf_ffi.tx_id[..std::cmp::min(16, conf_ffi.tx_id.len())],
-            conf_ffi.relay_count
-        );
+        let fragments: Vec<FragmentInfo> = queue_info
+            .iter()
+            .map(|(idx, size)| FragmentInfo {
+                index: *idx,
+                size: *size,
+            })
+            .collect();
 
-        // Convert FFI confirmation to Rust confirmation
-        let tx_id_bytes =
-            hex::decode(&conf_ffi.tx_id).map_err(|e| format!("Invalid txId hex: {}", e))?;
-        if tx_id_bytes.len() != 32 {
-            return Err(format!(
-                "Invalid txId length: expected 32 bytes, got {}",
-                tx_id_bytes.len()
-            ));
-        }
-        let mut tx_id_array = [0u8; 32];
-        tx_id_array.copy_from_slice(&tx_id_bytes);
+        let total_bytes: usize = fragments.iter().map(|f| f.size).sum();
 
-        let status = match &conf_ffi.status {
-            ConfirmationStatusFFI::Success { signature } => {
-                crate::queue::confirmation::ConfirmationStatus::Success {
-                    signature: signature.clone(),
-                }
-            }
-            ConfirmationStatusFFI::Failed { error } => {
-                crate::queue::confirmation::ConfirmationStatus::Failed {
-                    error: error.clone(),
-                }
-            }
-        };
+        tracing::info!(
+            "🔍 Queue debug: {} fragments, {} total bytes",
+            fragments.len(),
+            total_bytes
+        );
 
-        // Create confirmation with incremented relay count
-        let mut confirmation = crate::queue::confirmation::Confirmation {
-            original_tx_id: tx_id_array,
-            status,
-            timestamp: conf_ffi.timestamp,
-            relay_count: conf_ffi.relay_count,
-            max_hops: 5, // Default max hops
+        let response = QueueDebugResponse {
+            total_fragments: fragments.len(),
+            fragments,
         };
 
-        // Increment relay count
-        let relay_count_before = confirmation.relay_count;
-        let max_hops = confirmation.max_hops;
-        if !confirmation.increment_relay() {
-            tracing::warn!(
-                "⚠️ Confirmation for tx {} exceeded max hops ({}/{}) - dropping",
-                &conf_ffi.tx_id[..std::cmp::min(16, conf_ffi.tx_id.len())],
-                relay_count_before,
-                max_hops
-            );
-            // Return success but don't queue (TTL exceeded)
-            let response: FfiResult<SuccessResponse> =
-                FfiResult::success(SuccessResponse { success: true });
-            return serde_json::to_string(&response)
-                .map_err(|e| format!("Serialization error: {}", e));
-        }
+        let ffi_response: FfiResult<QueueDebugResponse> = FfiResult::success(response);
+        serde_json::to_string(&ffi_response).map_err(FfiError::serialization)
+    })();
 
-        // Store relay count after increment for logging
-        let relay_count_after = confirmation.relay_count;
+    create_result_string(&mut env, result)
+}
+
+// =============================================================================
+// Queue Persistence FFI Functions (Phase 5)
+// =============================================================================
+
+/// Flush the queue manager and received queue to disk (shared by the sync and async calls)
+#[cfg(feature = "android")]
+fn save_queues(transport: &HostBleTransport) -> Result<String, FfiError> {
+    runtime::block_on(async {
+        // Save queue manager queues (outbound, retry, confirmation)
+        transport
+            .sdk
+            .queue_manager()
+            .force_save()
+            .await
+            .map_err(|e| {
+                FfiError::new(
+                    FfiErrorCode::Storage,
+                    format!("Failed to save queues: {}", e),
+                )
+            })?;
+
+        // Save received queue if storage directory is available
+        if let Some(queue_storage_dir) = transport.get_queue_storage_dir() {
+            if let Err(e) = transport.save_received_queue(&queue_storage_dir) {
+                log::warn!("⚠️ Failed to save received queue: {}", e);
+                // Don't fail the entire operation if received queue save fails
+            }
+        }
+
+        Ok::<(), FfiError>(())
+    })?;
+
+    let response: FfiResult<SuccessResponse> =
+        FfiResult::success(SuccessResponse { success: true });
+    serde_json::to_string(&response).map_err(FfiError::serialization)
+}
+
+/// Save all queues to disk
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_saveQueues(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result = get_transport(handle).and_then(|transport| save_queues(&transport));
+
+    create_result_string(&mut env, result)
+}
+
+/// Trigger auto-save if needed (debounced)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_autoSaveQueues(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
 
-        // Re-queue for relay
         runtime::block_on(async {
-            let mut conf_queue = transport.sdk.queue_manager().confirmations.write().await;
-            conf_queue
-                .push(confirmation)
-                .map_err(|e| format!("Failed to re-queue confirmation: {:?}", e))?;
+            // Auto-save queue manager queues (outbound, retry, confirmation)
+            transport
+                .sdk
+                .queue_manager()
+                .save_if_needed()
+                .await
+                .map_err(|e| {
+                    FfiError::new(
+                        FfiErrorCode::Storage,
+                        format!("Failed to auto-save queues: {}", e),
+                    )
+                })?;
 
-            tracing::info!(
-                "✅ Re-queued confirmation for tx {} (hops: {}/{})",
-                &conf_ffi.tx_id[..std::cmp::min(16, conf_ffi.tx_id.len())],
-                relay_count_after,
-                max_hops
-            );
+            // Auto-save received queue if storage directory is available
+            // Note: Received queue uses the same debouncing as queue manager
+            if let Some(queue_storage_dir) = transport.get_queue_storage_dir() {
+                if let Err(e) = transport.save_received_queue(&queue_storage_dir) {
+                    log::warn!("⚠️ Failed to auto-save received queue: {}", e);
+                    // Don't fail the entire operation if received queue save fails
+                }
+            }
 
-            Ok::<(), String>(())
+            Ok::<(), FfiError>(())
+        })?;
+
+        let response: FfiResult<SuccessResponse> =
+            FfiResult::success(SuccessResponse { success: true });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+// =============================================================================
+// Queue Management FFI Functions (Phase 2)
+// =============================================================================
+
+/// Push transaction to outbound queue
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_pushOutboundTransaction(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    request_json: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let request_str: String = env
+            .get_string(&request_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to get request string: {}", e)))?
+            .into();
+
+        let request: PushOutboundRequest = serde_json::from_str(&request_str)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to parse request: {}", e)))?;
+
+        log::info!(
+            "📤 pushOutboundTransaction handle={} tx_id={} fragments={} priority={:?}",
+            handle,
+            &request.tx_id[..8.min(request.tx_id.len())],
+            request.fragments.len(),
+            request.priority
+        );
+
+        // Convert FFI fragments to mesh fragments
+        let fragments: Result<Vec<crate::ble::mesh::TransactionFragment>, FfiError> = request
+            .fragments
+            .iter()
+            .map(|f| {
+                let tx_id = hex::decode(&f.transaction_id).map_err(|e| {
+                    FfiError::invalid_input(format!("Invalid transaction ID: {}", e))
+                })?;
+                if tx_id.len() != 32 {
+                    return Err(FfiError::invalid_input("Transaction ID must be 32 bytes"));
+                }
+                let mut tx_id_array = [0u8; 32];
+                tx_id_array.copy_from_slice(&tx_id);
+
+                let data = base64::decode(&f.data_base64).map_err(|e| {
+                    FfiError::invalid_input(format!("Invalid fragment data: {}", e))
+                })?;
+
+                Ok(crate::ble::mesh::TransactionFragment {
+                    transaction_id: tx_id_array,
+                    fragment_index: f.fragment_index,
+                    total_fragments: f.total_fragments,
+                    data,
+                })
+            })
+            .collect();
+
+        let fragments = fragments?;
+        let tx_bytes = base64::decode(&request.tx_bytes)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid transaction bytes: {}", e)))?;
+
+        // Convert priority
+        let priority = match request.priority {
+            PriorityFFI::High => crate::queue::Priority::High,
+            PriorityFFI::Normal => crate::queue::Priority::Normal,
+            PriorityFFI::Low => crate::queue::Priority::Low,
+        };
+
+        // Create outbound transaction
+        let outbound_tx =
+            crate::queue::OutboundTransaction::new(request.tx_id, tx_bytes, fragments, priority);
+
+        // Push to queue
+        runtime::block_on(transport.sdk.queue_manager().push_outbound(outbound_tx))
+            .map_err(|e| format!("Failed to push to queue: {}", e))?;
+
+        log::info!("✅ pushOutboundTransaction enqueued");
+        let response: FfiResult<SuccessResponse> =
+            FfiResult::success(SuccessResponse { success: true });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Accept and queue a pre-signed transaction from external partners
+/// Verifies the transaction, compresses it if needed, fragments it, and adds to queue
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_acceptAndQueueExternalTransaction(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    request_json: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let request_str: String = env
+            .get_string(&request_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to get request string: {}", e)))?
+            .into();
+
+        let request: AcceptExternalTransactionRequest = serde_json::from_str(&request_str)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to parse request: {}", e)))?;
+
+        let tx_id = runtime::block_on(async {
+            // First, verify and queue in priority queue (for tracking/management)
+            transport
+                .sdk
+                .accept_and_queue_external_transaction(
+                    &request.base64_signed_tx,
+                    request.max_payload,
+                )
+                .await
+        })
+        .map_err(|e| {
+            let e = FfiError::from(e);
+            FfiError::new(
+                e.code,
+                format!(
+                    "Failed to accept and queue external transaction: {}",
+                    e.message
+                ),
+            )
         })?;
 
-        let response: FfiResult<SuccessResponse> =
-            FfiResult::success(SuccessResponse { success: true });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        // CRITICAL FIX: Also populate transport.outbound_queue so next_outbound() can read fragments
+        // The transaction was already verified and fragmented by accept_and_queue_external_transaction
+        // Now we need to get those fragments and add them to the fragment queue
+        runtime::block_on(async {
+            // Get mutable access to the queue to pop transactions
+            let mut queue = transport.sdk.queue_manager().outbound.write().await;
+
+            // Pop transactions until we find the one we just added
+            let mut found_tx = None;
+            let mut popped_txs = Vec::new();
+
+            // Search through all priorities by popping
+            while let Some(tx) = queue.pop() {
+                if tx.tx_id == tx_id {
+                    found_tx = Some(tx);
+                    break;
+                } else {
+                    popped_txs.push(tx);
+                }
+            }
+
+            // Put back all the transactions we popped (maintain original order)
+            // Note: push() will add to the correct priority queue based on tx.priority
+            for tx in popped_txs {
+                // Re-add to queue (this will maintain priority)
+                if let Err(e) = queue.push(tx) {
+                    tracing::warn!("⚠️ Failed to re-queue transaction: {}", e);
+                }
+            }
+
+            if let Some(tx) = found_tx {
+                // Store fragment count before moving tx
+                let fragment_count = tx.fragments.len();
+
+                // Queue fragments directly using the public method
+                transport.queue_fragments(&tx.fragments)
+                    .map_err(|e| format!("Failed to queue fragments: {}", e))?;
+
+                // Put the transaction back in the priority queue (for management/tracking)
+                queue.push(tx).map_err(|e| format!("Failed to re-queue transaction: {}", e))?;
+
+                tracing::info!("✅ External transaction {} fragments added to transport outbound queue ({} fragments)", tx_id, fragment_count);
+            } else {
+                tracing::warn!("⚠️ Could not find queued transaction {} to populate fragment queue", tx_id);
+            }
+
+            Ok::<(), String>(())
+        }).map_err(|e| format!("Failed to populate fragment queue: {}", e))?;
+
+        let response: FfiResult<String> = FfiResult::success(tx_id);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Pop next transaction from outbound queue
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_popOutboundTransaction(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        let tx_opt = runtime::block_on(transport.sdk.queue_manager().pop_outbound());
+
+        if let Some(tx) = tx_opt {
+            log::info!(
+                "📦 popOutboundTransaction → tx_id={} fragments={} priority={:?}",
+                &tx.tx_id[..8.min(tx.tx_id.len())],
+                tx.fragments.len(),
+                tx.priority
+            );
+            let tx_ffi = outbound_to_ffi(tx);
+
+            let response: FfiResult<Option<OutboundTransactionFFI>> =
+                FfiResult::success(Some(tx_ffi));
+            serde_json::to_string(&response).map_err(FfiError::serialization)
+        } else {
+            log::debug!("📭 popOutboundTransaction — queue empty");
+            let response: FfiResult<Option<OutboundTransactionFFI>> = FfiResult::success(None);
+            serde_json::to_string(&response).map_err(FfiError::serialization)
+        }
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Pop as many outbound transactions as fit in `max_bytes` (priority order)
+///
+/// Lets the bridge fill a whole BLE connection event in one call.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_popOutboundBatch(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    max_bytes: jint,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        if max_bytes <= 0 {
+            return Err(FfiError::invalid_input(format!(
+                "max_bytes must be positive, got {}",
+                max_bytes
+            )));
+        }
+
+        let batch = runtime::block_on(
+            transport
+                .sdk
+                .queue_manager()
+                .pop_outbound_batch(max_bytes as usize),
+        );
+
+        log::info!(
+            "📦 popOutboundBatch max_bytes={} → {} transactions",
+            max_bytes,
+            batch.len()
+        );
+
+        let batch_ffi: Vec<OutboundTransactionFFI> =
+            batch.into_iter().map(outbound_to_ffi).collect();
+        let response: FfiResult<Vec<OutboundTransactionFFI>> = FfiResult::success(batch_ffi);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+#[cfg(feature = "android")]
+fn outbound_to_ffi(tx: crate::queue::OutboundTransaction) -> OutboundTransactionFFI {
+    OutboundTransactionFFI {
+        tx_id: tx.tx_id,
+        original_bytes: base64::encode(&tx.original_bytes),
+        fragment_count: tx.fragments.len(),
+        priority: match tx.priority {
+            crate::queue::Priority::High => PriorityFFI::High,
+            crate::queue::Priority::Normal => PriorityFFI::Normal,
+            crate::queue::Priority::Low => PriorityFFI::Low,
+        },
+        created_at: tx.created_at,
+        retry_count: tx.retry_count,
+    }
+}
+
+/// Add transaction to retry queue
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_addToRetryQueue(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    request_json: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let request_str: String = env
+            .get_string(&request_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to get request string: {}", e)))?
+            .into();
+
+        let request: AddToRetryRequest = serde_json::from_str(&request_str)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to parse request: {}", e)))?;
+
+        let tx_bytes = base64::decode(&request.tx_bytes)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid transaction bytes: {}", e)))?;
+
+        log::info!(
+            "🔁 addToRetryQueue handle={} tx_id={} error={:?}",
+            handle,
+            &request.tx_id[..8.min(request.tx_id.len())],
+            request.error
+        );
+
+        runtime::block_on(transport.sdk.queue_manager().record_submission_failure(
+            tx_bytes,
+            request.tx_id,
+            request.error,
+        ))
+        .map_err(|e| format!("Failed to push to retry queue: {}", e))?;
+
+        log::info!("✅ addToRetryQueue enqueued");
+        let response: FfiResult<SuccessResponse> =
+            FfiResult::success(SuccessResponse { success: true });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Pop next ready retry item
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_popReadyRetry(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        let retry_opt = runtime::block_on(transport.sdk.queue_manager().pop_ready_retry());
+
+        if let Some(retry) = retry_opt {
+            let retry_ffi = RetryItemFFI {
+                tx_bytes: base64::encode(&retry.tx_bytes),
+                tx_id: retry.tx_id.clone(),
+                attempt_count: retry.attempt_count,
+                last_error: retry.last_error.clone(),
+                next_retry_in_secs: retry.time_until_retry().as_secs(),
+                age_seconds: retry.age().as_secs(),
+            };
+
+            let response: FfiResult<Option<RetryItemFFI>> = FfiResult::success(Some(retry_ffi));
+            serde_json::to_string(&response).map_err(FfiError::serialization)
+        } else {
+            let response: FfiResult<Option<RetryItemFFI>> = FfiResult::success(None);
+            serde_json::to_string(&response).map_err(FfiError::serialization)
+        }
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Get retry queue size
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getRetryQueueSize(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        let size = runtime::block_on(async {
+            let queue = transport.sdk.queue_manager().retries.read().await;
+            queue.len()
+        });
+
+        #[derive(serde::Serialize)]
+        struct QueueSizeResponse {
+            #[serde(rename = "queueSize")]
+            queue_size: usize,
+        }
+
+        let response: FfiResult<QueueSizeResponse> =
+            FfiResult::success(QueueSizeResponse { queue_size: size });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Get metrics for all queues (outbound by priority, retry, confirmation, dead-letter)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getQueueMetrics(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let metrics = runtime::block_on(transport.sdk.get_queue_metrics());
+
+        let response: FfiResult<QueueMetricsFFI> = FfiResult::success(metrics.into());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Compression totals: bytes in/out, ratio and per-algorithm usage
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getCompressionStats(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let stats = transport.sdk.get_compression_stats();
+
+        let response: FfiResult<crate::util::compression::CompressionStats> =
+            FfiResult::success(stats);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Get queue health: HEALTHY, or WARNING/CRITICAL with the issues found
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getQueueHealth(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let health: QueueHealthFFI = runtime::block_on(transport.sdk.get_queue_health()).into();
+        if health.status != "HEALTHY" {
+            log::warn!("⚠️ Queue health {}: {:?}", health.status, health.issues);
+        }
+
+        let response: FfiResult<QueueHealthFFI> = FfiResult::success(health);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// List dead-lettered transactions (oldest first)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getDeadLetters(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        let letters: Vec<DeadLetterFFI> = runtime::block_on(async {
+            let queue = transport.sdk.queue_manager().dead_letters.read().await;
+            queue
+                .iter()
+                .map(|letter| DeadLetterFFI {
+                    tx_bytes: base64::encode(&letter.tx_bytes),
+                    tx_id: letter.tx_id.clone(),
+                    attempts: letter.attempts,
+                    last_error: letter.last_error.clone(),
+                    reason: format!("{:?}", letter.reason),
+                    first_failed_at: letter.first_failed_at,
+                    dead_lettered_at: letter.dead_lettered_at,
+                })
+                .collect()
+        });
+
+        let response: FfiResult<Vec<DeadLetterFFI>> = FfiResult::success(letters);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Persist a text or control message exchanged with a peer
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_recordMeshMessage(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    message_json: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let json: String = env
+            .get_string(&message_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read message JSON: {}", e)))?
+            .into();
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RecordRequest {
+            peer_id: String,
+            direction: crate::storage::MessageDirection,
+            kind: crate::storage::MessageKind,
+            body: String,
+        }
+
+        let request: RecordRequest = serde_json::from_str(&json)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid message JSON: {}", e)))?;
+        let log = transport
+            .message_log()
+            .ok_or_else(|| FfiError::not_configured("Message log requires a storage directory"))?;
+        let event = (request.direction == crate::storage::MessageDirection::Inbound
+            && request.kind == crate::storage::MessageKind::Text)
+            .then(|| ProtocolEvent::text_message(&request.peer_id, &request.body));
+        log.append(&crate::storage::LoggedMessage::new(
+            request.peer_id,
+            request.direction,
+            request.kind,
+            request.body,
+        ))
+        .map_err(|e| e.to_string())?;
+        if let Some(event) = event {
+            transport.emit_event(event);
+        }
+
+        #[derive(serde::Serialize)]
+        struct SuccessResponse {
+            success: bool,
+        }
+
+        let response: FfiResult<SuccessResponse> =
+            FfiResult::success(SuccessResponse { success: true });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Fetch persisted message history with one peer (oldest first), with
+/// delivery/read statuses
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getConversation(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    peer_id: JString,
+    limit: jint,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer ID: {}", e)))?
+            .into();
+        let messages = transport
+            .conversation(&peer_id, limit.max(0) as usize)
+            .ok_or_else(|| FfiError::not_configured("Message log requires a storage directory"))?;
+        let response: FfiResult<Vec<super::messaging::ConversationMessage>> =
+            FfiResult::success(messages);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Page through the received-transaction history (newest first), optionally
+/// filtered by status and/or source peer
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getTransactionHistory(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    query_json: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let json: String = env
+            .get_string(&query_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read query JSON: {}", e)))?
+            .into();
+
+        let query: crate::storage::TxHistoryQuery = if json.trim().is_empty() {
+            Default::default()
+        } else {
+            serde_json::from_str(&json)
+                .map_err(|e| FfiError::invalid_input(format!("Invalid history query: {}", e)))?
+        };
+        let history = transport.tx_history().ok_or_else(|| {
+            FfiError::not_configured("Transaction history requires a storage directory")
+        })?;
+
+        let response: FfiResult<crate::storage::TxHistoryPage> =
+            FfiResult::success(history.query(&query));
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Mesh activity over the last `window_secs` (counts, unique peers, bytes,
+/// average hops to a gateway, success rates); persisted across restarts
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getMeshStats(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    window_secs: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let window_secs = u64::try_from(window_secs)
+            .map_err(|_| FfiError::invalid_input("Window must not be negative"))?;
+        let summary = transport.mesh_stats_summary(window_secs).ok_or_else(|| {
+            FfiError::not_configured("Mesh statistics require a storage directory")
+        })?;
+
+        let response: FfiResult<crate::storage::MeshStatsSummary> = FfiResult::success(summary);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Start the auto-submit gateway: received transactions are submitted with
+/// retry while online, and confirmations relayed to the mesh. `rpc_url` is one
+/// URL or a comma-separated list tried best-first with failover. Probes the
+/// first URL for connectivity unless a probe is already running.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_startGateway(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    rpc_url: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        if transport.device_role() == Some(crate::DeviceRole::Relay) {
+            return Err(FfiError::not_configured(
+                "Relay-only devices do not submit transactions",
+            ));
+        }
+        let rpc_url: String = env
+            .get_string(&rpc_url)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read RPC URL: {}", e)))?
+            .into();
+
+        info!("🌐 startGateway handle={} rpc={}", handle, rpc_url);
+        start_rpc_gateway(&transport, &rpc_url, None)?;
+
+        let response: FfiResult<()> = FfiResult::success(());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Like `startGateway`, also signing the fee-payer slot of transactions
+/// sponsored by the JSON `SponsorConfig`
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_startSponsoredGateway(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    rpc_url: JString,
+    sponsor_json: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        if transport.device_role() == Some(crate::DeviceRole::Relay) {
+            return Err(FfiError::not_configured(
+                "Relay-only devices do not submit transactions",
+            ));
+        }
+        let rpc_url: String = env
+            .get_string(&rpc_url)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read RPC URL: {}", e)))?
+            .into();
+        let sponsor_json: String = env
+            .get_string(&sponsor_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read sponsor: {}", e)))?
+            .into();
+        let sponsor: super::sponsor::SponsorConfig = serde_json::from_str(&sponsor_json)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid sponsor: {}", e)))?;
+        let fee_payer = sponsor.into_provider().map_err(FfiError::invalid_input)?;
+
+        info!(
+            "🌐 startSponsoredGateway handle={} rpc={} sponsor={}",
+            handle,
+            rpc_url,
+            fee_payer.pubkey()
+        );
+        start_rpc_gateway(&transport, &rpc_url, Some(fee_payer))?;
+
+        let response: FfiResult<()> = FfiResult::success(());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Start the gateway on the comma-separated `rpc_url` list
+#[cfg(feature = "android")]
+fn start_rpc_gateway(
+    transport: &Arc<HostBleTransport>,
+    rpc_url: &str,
+    fee_payer: Option<Arc<dyn super::sponsor::FeePayerProvider>>,
+) -> Result<(), FfiError> {
+    let pool = super::rpc_pool::RpcPool::from_list(rpc_url).map_err(FfiError::invalid_input)?;
+    let primary_url = pool.urls()[0].to_string();
+    let mut submitter = super::gateway::RpcSubmitter::new(Arc::new(pool));
+    if let Some(fee_payer) = fee_payer {
+        submitter = submitter.with_fee_payer(fee_payer);
+    }
+    let submitter = Arc::new(submitter);
+    runtime::block_on(async {
+        // Without a host-chosen probe, the gateway's own RPC decides connectivity
+        if !transport.connectivity().is_probing() {
+            transport
+                .connectivity()
+                .start(super::connectivity::ConnectivityConfig::rpc_health(
+                    primary_url,
+                ))?;
+        }
+        transport.start_gateway(submitter);
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// Stop the auto-submit gateway; data is false if none was running
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_stopGateway(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let response: FfiResult<bool> = FfiResult::success(transport.stop_gateway());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Typed BLE status: adapter, peers and queue sizes
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getBleStatus(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let response: FfiResult<BleStatus> = FfiResult::success(transport.ble_status());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Report the radio adapter state ("on", "off", "unauthorized", "unavailable")
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_reportAdapterState(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    state: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let state: String = env
+            .get_string(&state)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read adapter state: {}", e)))?
+            .into();
+        let state: crate::AdapterState =
+            serde_json::from_value(serde_json::Value::String(state))
+                .map_err(|e| FfiError::invalid_input(format!("Invalid adapter state: {}", e)))?;
+        transport.sdk.report_adapter_state(state);
+
+        let response: FfiResult<()> = FfiResult::success(());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Change the device role at runtime (`role` is "originator", "relay",
+/// "gateway", or "" to clear it)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_setDeviceRole(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    role: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let role: String = env
+            .get_string(&role)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read role: {}", e)))?
+            .into();
+        let role: Option<crate::DeviceRole> = if role.is_empty() {
+            None
+        } else {
+            Some(
+                serde_json::from_value(serde_json::Value::String(role))
+                    .map_err(|e| FfiError::invalid_input(format!("Invalid role: {}", e)))?,
+            )
+        };
+        transport.set_device_role(role);
+        if role == Some(crate::DeviceRole::Relay) && transport.stop_gateway() {
+            info!("🌐 Gateway stopped: device is now a relay");
+        }
+
+        let response: FfiResult<()> = FfiResult::success(());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Capability byte to put in the BLE advertisement (role and connectivity bits)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getCapabilityFlags(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let response: FfiResult<u8> = FfiResult::success(transport.capability_flags());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Report OS connectivity (e.g. from ConnectivityManager callbacks); data is
+/// true if the state changed
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_reportConnectivity(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    online: jboolean,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let response: FfiResult<bool> =
+            FfiResult::success(transport.connectivity().report(online != 0));
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Start probing connectivity with a JSON `ConnectivityConfig`, replacing any
+/// running probe (`{"probe":{"type":"os"}}` stops probing)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_startConnectivityProbe(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    config_json: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let json: String = env
+            .get_string(&config_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read probe config: {}", e)))?
+            .into();
+        let config: super::connectivity::ConnectivityConfig = serde_json::from_str(&json)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid probe config: {}", e)))?;
+
+        runtime::block_on(async { transport.connectivity().start(config) })
+            .map_err(FfiError::not_configured)?;
+
+        let response: FfiResult<()> = FfiResult::success(());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Current connectivity state
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_isOnline(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let response: FfiResult<bool> = FfiResult::success(transport.connectivity().is_online());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Set per-origin relay limits from a JSON `RelayPolicyConfig`; an empty
+/// string disables them
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_setRelayPolicy(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    config_json: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let json: String = env
+            .get_string(&config_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read relay policy: {}", e)))?
+            .into();
+        let config: Option<super::relay_policy::RelayPolicyConfig> = if json.is_empty() {
+            None
+        } else {
+            Some(
+                serde_json::from_str(&json)
+                    .map_err(|e| FfiError::invalid_input(format!("Invalid relay policy: {}", e)))?,
+            )
+        };
+        transport.set_relay_policy(config);
+
+        let response: FfiResult<()> = FfiResult::success(());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Relay policy counters (accepted, drops by fee payer and device, top offenders)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getRelayPolicyStats(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let stats = transport
+            .relay_policy_stats()
+            .ok_or_else(|| FfiError::not_configured("No relay policy set"))?;
+        let response = FfiResult::success(stats);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Key this device signs relay receipts with (base58)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getDevicePublicKey(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let response: FfiResult<String> = FfiResult::success(transport.device_public_key());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Device identity: `{publicKey, shortId, persisted}`
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getDeviceIdentity(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let response: FfiResult<DeviceIdentity> = FfiResult::success(transport.device_identity());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Manufacturer data to advertise: `{manufacturerId, manufacturerData
+/// (base64), advertisedId, private, rotatesAt}`
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getAdvertisement(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let response: FfiResult<crate::ble::Advertisement> =
+            FfiResult::success(transport.advertisement());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Turn advertisement privacy mode on or off; `rotation_secs` <= 0 keeps the
+/// default rotation
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_setPrivacyMode(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    enabled: jboolean,
+    rotation_secs: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let mut privacy = crate::ble::PrivacySettings {
+            enabled: enabled != 0,
+            ..Default::default()
+        };
+        if rotation_secs > 0 {
+            privacy.rotation_secs = rotation_secs as u64;
+        }
+        transport.set_privacy(privacy);
+
+        let response: FfiResult<bool> = FfiResult::success(true);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Pop the next relay receipt to send back toward an origin: `{peerId, frame}`
+/// with a base64 frame (data is null when none is pending)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_nextRelayReceipt(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+
+        #[derive(serde::Serialize)]
+        struct OutboundReceipt {
+            #[serde(rename = "peerId")]
+            peer_id: String,
+            frame: String,
+        }
+
+        let transport = get_transport(handle)?;
+        let receipt = transport
+            .next_relay_receipt()
+            .map(|(peer_id, frame)| OutboundReceipt {
+                peer_id,
+                frame: STANDARD.encode(frame),
+            });
+        let response: FfiResult<Option<OutboundReceipt>> = FfiResult::success(receipt);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Hand a received relay receipt frame to the engine. Returns `{accepted}`;
+/// false for duplicates and receipts that fail verification.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_ingestRelayReceipt(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    data: JByteArray,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let data: Vec<u8> = env
+            .convert_byte_array(&data)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read data: {}", e)))?;
+        let accepted = transport
+            .ingest_relay_receipt(&data)
+            .map_err(FfiError::invalid_input)?;
+
+        #[derive(serde::Serialize)]
+        struct IngestResponse {
+            accepted: bool,
+        }
+
+        let response: FfiResult<IngestResponse> = FfiResult::success(IngestResponse { accepted });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Stored relay receipts (issued and received), newest first
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getRelayReceipts(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    offset: jlong,
+    limit: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let store = transport.relay_receipts().ok_or_else(|| {
+            FfiError::not_configured("Relay receipts require a storage directory")
+        })?;
+        let receipts = store.list(offset.max(0) as usize, limit.max(0) as usize);
+
+        let response: FfiResult<Vec<crate::storage::ReceiptRecord>> = FfiResult::success(receipts);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Watch a transaction by mesh id or signature; updates arrive as `TxStatus` events
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_watchTransaction(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let id: String = env
+            .get_string(&id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read transaction id: {}", e)))?
+            .into();
+
+        let watched = transport.watch_transaction(&id, None)?;
+        let response: FfiResult<super::tx_watch::WatchedTransaction> = FfiResult::success(watched);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Stop watching a transaction
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_unwatchTransaction(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    watch_id: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        #[derive(serde::Serialize)]
+        struct UnwatchResponse {
+            removed: bool,
+        }
+
+        let removed = transport.unwatch_transaction(watch_id.max(0) as u64);
+        let response: FfiResult<UnwatchResponse> = FfiResult::success(UnwatchResponse { removed });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Active transaction watches, oldest first
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getWatchedTransactions(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let response: FfiResult<Vec<super::tx_watch::WatchedTransaction>> =
+            FfiResult::success(transport.watched_transactions());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Add or replace an address book contact
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_saveContact(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    contact_json: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let contact_json: String = env
+            .get_string(&contact_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read contact: {}", e)))?
+            .into();
+        let contact: crate::storage::Contact = serde_json::from_str(&contact_json)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid contact: {}", e)))?;
+
+        let book = transport.address_book().ok_or_else(|| {
+            FfiError::not_configured("The address book requires a storage directory")
+        })?;
+        let saved = book.save(contact).map_err(|e| match e {
+            crate::storage::StorageError::InvalidData(_) => FfiError::invalid_input(e.to_string()),
+            _ => FfiError::new(FfiErrorCode::Storage, e.to_string()),
+        })?;
+        let response: FfiResult<crate::storage::Contact> = FfiResult::success(saved);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Remove an address book contact by label
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_removeContact(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    label: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let label: String = env
+            .get_string(&label)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read label: {}", e)))?
+            .into();
+        let book = transport.address_book().ok_or_else(|| {
+            FfiError::not_configured("The address book requires a storage directory")
+        })?;
+
+        #[derive(serde::Serialize)]
+        struct RemoveResponse {
+            removed: bool,
+        }
+
+        let removed = book
+            .remove(&label)
+            .map_err(|e| FfiError::new(FfiErrorCode::Storage, e.to_string()))?;
+        let response: FfiResult<RemoveResponse> = FfiResult::success(RemoveResponse { removed });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Address book contacts sorted by label
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getContacts(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let book = transport.address_book().ok_or_else(|| {
+            FfiError::not_configured("The address book requires a storage directory")
+        })?;
+        let response: FfiResult<Vec<crate::storage::Contact>> = FfiResult::success(book.list());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Queue a text message for a peer; returns its message id and status
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_sendTextMessage(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    peer_id: JString,
+    body: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer ID: {}", e)))?
+            .into();
+        let body: String = env
+            .get_string(&body)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read message body: {}", e)))?
+            .into();
+
+        let message = transport.send_text_message(&peer_id, &body)?;
+        let response: FfiResult<super::messaging::OutgoingTextMessage> =
+            FfiResult::success(message);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Hand a received text frame to the engine (acks are queued automatically)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_ingestTextFrame(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    peer_id: JString,
+    data: JByteArray,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer ID: {}", e)))?
+            .into();
+        let data: Vec<u8> = env
+            .convert_byte_array(&data)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read data: {}", e)))?;
+
+        #[derive(serde::Serialize)]
+        struct IngestResponse {
+            kind: &'static str,
+            #[serde(rename = "messageId", skip_serializing_if = "Option::is_none")]
+            message_id: Option<String>,
+            #[serde(rename = "messageIds", skip_serializing_if = "Vec::is_empty")]
+            message_ids: Vec<String>,
+        }
+
+        let response = match transport.ingest_text_frame(&peer_id, &data)? {
+            super::messaging::IngestedText::Message(message) => IngestResponse {
+                kind: "message",
+                message_id: Some(message.message_id),
+                message_ids: Vec::new(),
+            },
+            super::messaging::IngestedText::Delivered(message) => IngestResponse {
+                kind: "delivered",
+                message_id: message.map(|m| m.message_id),
+                message_ids: Vec::new(),
+            },
+            super::messaging::IngestedText::Read(messages) => IngestResponse {
+                kind: "read",
+                message_id: None,
+                message_ids: messages.into_iter().map(|m| m.message_id).collect(),
+            },
+            super::messaging::IngestedText::Duplicate => IngestResponse {
+                kind: "duplicate",
+                message_id: None,
+                message_ids: Vec::new(),
+            },
+        };
+        let response: FfiResult<IngestResponse> = FfiResult::success(response);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Get the next text or ack frame to send (null when none fits)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_nextTextFrame(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    max_len: jlong,
+) -> jbyteArray {
+    let result: Result<Option<Vec<u8>>, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        Ok(transport.next_text_frame(max_len.max(0) as usize))
+    })();
+
+    match result {
+        Ok(Some(data)) => env
+            .byte_array_from_slice(&data)
+            .map(|array| array.into_raw())
+            .unwrap_or(std::ptr::null_mut()),
+        Ok(None) => std::ptr::null_mut(),
+        Err(e) => {
+            error!("nextTextFrame failed: {}", e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Pop the oldest received text message (data is null when none is pending)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_nextTextMessage(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let response: FfiResult<Option<super::messaging::IncomingTextMessage>> =
+            FfiResult::success(transport.next_text_message());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Delivery status of a sent text message
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getTextMessageStatus(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    message_id: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let message_id: String = env
+            .get_string(&message_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read message ID: {}", e)))?
+            .into();
+        let message = transport
+            .text_message_status(&message_id)
+            .ok_or_else(|| FfiError::not_found(format!("Unknown text message: {}", message_id)))?;
+
+        let response: FfiResult<super::messaging::OutgoingTextMessage> =
+            FfiResult::success(message);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Send read receipts to a peer for messages received from it
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_markMessagesRead(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    peer_id: JString,
+    message_ids_json: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer ID: {}", e)))?
+            .into();
+        let json: String = env
+            .get_string(&message_ids_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read message IDs: {}", e)))?
+            .into();
+        let message_ids: Vec<String> = serde_json::from_str(&json)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid message IDs: {}", e)))?;
+        transport.mark_text_messages_read(&peer_id, &message_ids)?;
+
+        let response: FfiResult<()> = FfiResult::success(());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Report disk usage of the storage directory against the configured budget
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getStorageUsage(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let usage = transport
+            .storage_usage()
+            .ok_or_else(|| FfiError::not_configured("No storage budget configured"))?;
+
+        let response: FfiResult<crate::storage::StorageUsage> = FfiResult::success(usage);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Delete the oldest disposable files now if over the storage budget
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_enforceStorageQuota(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let report = transport
+            .enforce_storage_quota()
+            .ok_or_else(|| FfiError::not_configured("No storage budget configured"))?;
+
+        let response: FfiResult<crate::storage::CleanupReport> = FfiResult::success(report);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Write an encrypted backup of all SDK state to `path`
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_backupState(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    path: JString,
+    passphrase: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let path: String = env
+            .get_string(&path)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to get path string: {}", e)))?
+            .into();
+        let passphrase: String = env
+            .get_string(&passphrase)
+            .map_err(|e| {
+                FfiError::invalid_input(format!("Failed to get passphrase string: {}", e))
+            })?
+            .into();
+
+        let summary = transport.backup_state(&path, &passphrase)?;
+        let response: FfiResult<crate::storage::BackupSummary> = FfiResult::success(summary);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Replace all SDK state with the backup at `path`
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_restoreState(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    path: JString,
+    passphrase: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let path: String = env
+            .get_string(&path)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to get path string: {}", e)))?
+            .into();
+        let passphrase: String = env
+            .get_string(&passphrase)
+            .map_err(|e| {
+                FfiError::invalid_input(format!("Failed to get passphrase string: {}", e))
+            })?
+            .into();
+
+        let summary = transport.restore_state(&path, &passphrase)?;
+        let response: FfiResult<crate::storage::BackupSummary> = FfiResult::success(summary);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Move a dead-lettered transaction back into the retry queue
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_requeueDeadLetter(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    tx_id: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let tx_id: String = env
+            .get_string(&tx_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to get tx_id string: {}", e)))?
+            .into();
+
+        let requeued = runtime::block_on(transport.sdk.queue_manager().requeue_dead_letter(&tx_id))
+            .map_err(|e| format!("Failed to requeue dead letter: {}", e))?;
+
+        #[derive(serde::Serialize)]
+        struct RequeueResponse {
+            requeued: bool,
+        }
+
+        let response: FfiResult<RequeueResponse> = FfiResult::success(RequeueResponse { requeued });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Export a diagnostic snapshot of all queues
+///
+/// Payload bytes are redacted unless `include_payloads` is true.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_exportQueueSnapshot(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    include_payloads: jboolean,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        let snapshot = runtime::block_on(
+            transport
+                .sdk
+                .queue_manager()
+                .snapshot(include_payloads != 0),
+        );
+
+        log::info!(
+            "📸 exportQueueSnapshot handle={} outbound={} retries={} payloads={}",
+            handle,
+            snapshot.outbound.len(),
+            snapshot.retries.len(),
+            snapshot.payloads_included
+        );
+
+        let response: FfiResult<crate::queue::QueueSnapshot> = FfiResult::success(snapshot);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Cleanup expired confirmations and retry items
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_cleanupExpired(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        let (confirmations_cleaned, retries_cleaned) = runtime::block_on(async {
+            let mut conf_queue = transport.sdk.queue_manager().confirmations.write().await;
+            let conf_cleaned = conf_queue.cleanup_expired();
+
+            let mut retry_queue = transport.sdk.queue_manager().retries.write().await;
+            let retry_cleaned = retry_queue.cleanup_expired();
+
+            (conf_cleaned, retry_cleaned)
+        });
+
+        #[derive(serde::Serialize)]
+        struct CleanupExpiredResponse {
+            confirmations_cleaned: usize,
+            retries_cleaned: usize,
+        }
+
+        let response: FfiResult<CleanupExpiredResponse> =
+            FfiResult::success(CleanupExpiredResponse {
+                confirmations_cleaned,
+                retries_cleaned,
+            });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Confirm that all fragments for `tx_id` were delivered to the current peer.
+/// Decrements the transaction's relevance counter by 1. Evicts the transaction and
+/// returns { removed: true } when relevance hits 0 (fan-out exhausted).
+/// Returns { removed: false } when the transaction is retained for future peers.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_confirmDelivered(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    tx_id_j: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let tx_id: String = env.get_string(&tx_id_j).map_err(|e| e.to_string())?.into();
+
+        let removed = runtime::block_on(async {
+            let mut queue = transport.sdk.queue_manager().outbound.write().await;
+            queue.confirm_delivered(&tx_id)
+        });
+
+        #[derive(serde::Serialize)]
+        struct ConfirmDeliveredResponse {
+            removed: bool,
+        }
+        let response: FfiResult<ConfirmDeliveredResponse> =
+            FfiResult::success(ConfirmDeliveredResponse { removed });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Peek at the highest-relevance transaction in the outbound queue and load its
+/// fragments into the transport's BLE frame buffer so the sending loop can deliver
+/// them to the current peer. Returns the tx_id, current relevance, and fragment count,
+/// or null data if the queue is empty.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_loadForSending(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        // Peek under a read lock — clone the data we need so we don't hold the lock
+        // while calling queue_fragments (which takes an unrelated mutex).
+        let tx_info = runtime::block_on(async {
+            let queue = transport.sdk.queue_manager().outbound.read().await;
+            queue
+                .peek_highest_relevance()
+                .map(|tx| (tx.tx_id.clone(), tx.fragments.clone(), tx.relevance))
+        });
+
+        #[derive(serde::Serialize)]
+        struct LoadResponse {
+            tx_id: String,
+            relevance: u8,
+            fragment_count: usize,
+        }
+
+        if let Some((tx_id, fragments, relevance)) = tx_info {
+            transport
+                .queue_fragments(&fragments)
+                .map_err(|e| format!("Failed to load fragments into transport: {}", e))?;
+
+            let response: FfiResult<Option<LoadResponse>> =
+                FfiResult::success(Some(LoadResponse {
+                    tx_id,
+                    relevance,
+                    fragment_count: fragments.len(),
+                }));
+            serde_json::to_string(&response).map_err(FfiError::serialization)
+        } else {
+            let response: FfiResult<Option<LoadResponse>> = FfiResult::success(None);
+            serde_json::to_string(&response).map_err(FfiError::serialization)
+        }
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Purge outbound transactions older than max_age_secs from all priority queues.
+/// Call this at connection-start so stale relayed data is not forwarded.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_purgeStaleOutbound(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    max_age_secs: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let max_age = max_age_secs.max(0) as u64;
+
+        let removed = runtime::block_on(async {
+            let mut outbound = transport.sdk.queue_manager().outbound.write().await;
+            outbound.cleanup_stale(max_age)
+        });
+
+        #[derive(serde::Serialize)]
+        struct PurgeResponse {
+            removed: usize,
+        }
+        let response: FfiResult<PurgeResponse> = FfiResult::success(PurgeResponse { removed });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Queue a confirmation for relay back to origin device
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_queueConfirmation(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    request_json: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        // Parse request JSON from Kotlin
+        let request_str: String = env
+            .get_string(&request_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read request: {}", e)))?
+            .into();
+
+        let request: QueueConfirmationRequest = serde_json::from_str(&request_str)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to parse request: {}", e)))?;
+
+        tracing::info!(
+            "📨 Queueing confirmation for tx {} with signature {}...",
+            request.tx_id,
+            &request.signature[..std::cmp::min(16, request.signature.len())]
+        );
+
+        // Push into confirmation queue (auto-relay subsystem)
+        runtime::block_on(async {
+            let mut conf_queue = transport.sdk.queue_manager().confirmations.write().await;
+            // Confirmation queue expects tx_id as [u8; 32]
+            let tx_id_bytes = hex::decode(&request.tx_id)
+                .map_err(|e| FfiError::invalid_input(format!("Invalid txId hex: {}", e)))?;
+            if tx_id_bytes.len() != 32 {
+                return Err(FfiError::invalid_input(format!(
+                    "Invalid txId length: expected 32 bytes, got {}",
+                    tx_id_bytes.len()
+                )));
+            }
+            let mut tx_id_array = [0u8; 32];
+            tx_id_array.copy_from_slice(&tx_id_bytes);
+
+            let confirmation = crate::queue::confirmation::Confirmation::success(
+                tx_id_array,
+                request.signature.clone(),
+            );
+
+            conf_queue
+                .push(confirmation)
+                .map_err(|e| format!("Failed to queue confirmation: {:?}", e))?;
+
+            Ok::<(), FfiError>(())
+        })?;
+
+        let response: FfiResult<crate::ffi::types::SuccessResponse> =
+            FfiResult::success(crate::ffi::types::SuccessResponse { success: true });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Pop next confirmation from queue
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_popConfirmation(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        let confirmation = runtime::block_on(async {
+            let mut conf_queue = transport.sdk.queue_manager().confirmations.write().await;
+            conf_queue.pop()
+        });
+
+        if let Some(conf) = confirmation {
+            // Convert Rust Confirmation to FFI format
+            let tx_id_hex = hex::encode(conf.original_tx_id);
+            let status_ffi = match &conf.status {
+                crate::queue::confirmation::ConfirmationStatus::Success { signature } => {
+                    crate::ffi::types::ConfirmationStatusFFI::Success {
+                        signature: signature.clone(),
+                    }
+                }
+                crate::queue::confirmation::ConfirmationStatus::Failed { error } => {
+                    crate::ffi::types::ConfirmationStatusFFI::Failed {
+                        error: error.clone(),
+                    }
+                }
+            };
+
+            let conf_ffi = crate::ffi::types::ConfirmationFFI {
+                tx_id: tx_id_hex,
+                status: status_ffi,
+                timestamp: conf.timestamp,
+                relay_count: conf.relay_count,
+            };
+
+            let response: FfiResult<Option<crate::ffi::types::ConfirmationFFI>> =
+                FfiResult::success(Some(conf_ffi));
+            serde_json::to_string(&response).map_err(FfiError::serialization)
+        } else {
+            let response: FfiResult<Option<crate::ffi::types::ConfirmationFFI>> =
+                FfiResult::success(None);
+            serde_json::to_string(&response).map_err(FfiError::serialization)
+        }
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Cleanup stale fragments from the transaction cache
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_cleanupStaleFragments(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        // Cleanup stale fragments (older than 5 minutes = 300 seconds)
+        // cleanup_stale_fragments is on TransactionCache, accessed via SDK's local_cache
+        let cleaned = runtime::block_on(async {
+            let mut cache = transport.sdk.local_cache.write().await;
+            cache.cleanup_stale_fragments(300)
+        });
+
+        #[derive(serde::Serialize)]
+        struct CleanupResponse {
+            fragments_cleaned: usize,
+        }
+
+        let response: FfiResult<CleanupResponse> = FfiResult::success(CleanupResponse {
+            fragments_cleaned: cleaned,
+        });
+
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Relay a received confirmation (increment hop count and re-queue for relay)
+/// This is called when a confirmation is received that needs to be relayed further
+#[no_mangle]
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_relayConfirmation(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    confirmation_json: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        // Parse confirmation JSON from Kotlin
+        let conf_str: String = env
+            .get_string(&confirmation_json)
+            .map_err(|e| {
+                FfiError::invalid_input(format!("Failed to read confirmation JSON: {}", e))
+            })?
+            .into();
+
+        let conf_ffi: ConfirmationFFI = serde_json::from_str(&conf_str)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to parse confirmation: {}", e)))?;
+
+        tracing::info!(
+            "🔄 Relaying confirmation for tx {} (current hops: {})",
+            &conf_ffi.tx_id[..std::cmp::min(16, conf_ffi.tx_id.len())],
+            conf_ffi.relay_count
+        );
+
+        // Convert FFI confirmation to Rust confirmation
+        let tx_id_bytes = hex::decode(&conf_ffi.tx_id)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid txId hex: {}", e)))?;
+        if tx_id_bytes.len() != 32 {
+            return Err(FfiError::invalid_input(format!(
+                "Invalid txId length: expected 32 bytes, got {}",
+                tx_id_bytes.len()
+            )));
+        }
+        let mut tx_id_array = [0u8; 32];
+        tx_id_array.copy_from_slice(&tx_id_bytes);
+
+        let status = match &conf_ffi.status {
+            ConfirmationStatusFFI::Success { signature } => {
+                crate::queue::confirmation::ConfirmationStatus::Success {
+                    signature: signature.clone(),
+                }
+            }
+            ConfirmationStatusFFI::Failed { error } => {
+                crate::queue::confirmation::ConfirmationStatus::Failed {
+                    error: error.clone(),
+                }
+            }
+        };
+
+        // Create confirmation with incremented relay count
+        let mut confirmation = crate::queue::confirmation::Confirmation {
+            original_tx_id: tx_id_array,
+            status,
+            timestamp: conf_ffi.timestamp,
+            relay_count: conf_ffi.relay_count,
+            max_hops: 5, // Default max hops
+        };
+
+        // Increment relay count
+        let relay_count_before = confirmation.relay_count;
+        let max_hops = confirmation.max_hops;
+        if !confirmation.increment_relay() {
+            tracing::warn!(
+                "⚠️ Confirmation for tx {} exceeded max hops ({}/{}) - dropping",
+                &conf_ffi.tx_id[..std::cmp::min(16, conf_ffi.tx_id.len())],
+                relay_count_before,
+                max_hops
+            );
+            // Return success but don't queue (TTL exceeded)
+            let response: FfiResult<SuccessResponse> =
+                FfiResult::success(SuccessResponse { success: true });
+            return serde_json::to_string(&response).map_err(FfiError::serialization);
+        }
+
+        // Store relay count after increment for logging
+        let relay_count_after = confirmation.relay_count;
+
+        // Re-queue for relay
+        runtime::block_on(async {
+            let mut conf_queue = transport.sdk.queue_manager().confirmations.write().await;
+            conf_queue
+                .push(confirmation)
+                .map_err(|e| format!("Failed to re-queue confirmation: {:?}", e))?;
+
+            tracing::info!(
+                "✅ Re-queued confirmation for tx {} (hops: {}/{})",
+                &conf_ffi.tx_id[..std::cmp::min(16, conf_ffi.tx_id.len())],
+                relay_count_after,
+                max_hops
+            );
+
+            Ok::<(), FfiError>(())
+        })?;
+
+        let response: FfiResult<SuccessResponse> =
+            FfiResult::success(SuccessResponse { success: true });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Clear all queues (outbound, retry, confirmation, received) and reassembly buffers
+/// Note: This does NOT clear nonce data
+#[no_mangle]
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_clearAllQueues(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        runtime::block_on(async {
+            // Clear queue manager queues (outbound, retry, confirmation)
+            transport.sdk.clear_all_queues().await.map_err(|e| {
+                FfiError::new(
+                    FfiErrorCode::Storage,
+                    format!("Failed to clear queues: {}", e),
+                )
+            })?;
+
+            // Clear reassembly buffers and completed transactions in transport
+            transport.clear_all_reassembly_buffers();
+
+            // Clear received queue
+            transport.clear_received_queue();
+
+            tracing::info!("✅ Cleared all queues (outbound, retry, confirmation, received) and reassembly buffers");
+
+            Ok::<(), FfiError>(())
+        })?;
+
+        let response: FfiResult<SuccessResponse> =
+            FfiResult::success(SuccessResponse { success: true });
+
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+// =============================================================================
+// Wallet address — reward attribution
+// =============================================================================
+
+/// Set the wallet address for this node session.
+/// Pass an empty string to clear a previously-set address.
+#[no_mangle]
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_setWalletAddress(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    address: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        let addr: String = env
+            .get_string(&address)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read address string: {}", e)))?
+            .into();
+
+        let addr_opt = if addr.is_empty() {
+            None
+        } else {
+            Some(addr.clone())
+        };
+        transport.set_wallet_address(addr_opt);
+
+        info!(
+            "✅ Wallet address updated: {}",
+            if addr.is_empty() { "<cleared>" } else { &addr }
+        );
+
+        let response: FfiResult<SuccessResponse> =
+            FfiResult::success(SuccessResponse { success: true });
+
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+/// Get the wallet address currently set for this node session.
+/// Returns an empty address field if none has been set.
+#[no_mangle]
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getWalletAddress(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        let addr = transport.get_wallet_address().unwrap_or_default();
+
+        #[derive(serde::Serialize)]
+        struct WalletAddressResponse {
+            address: String,
+        }
+
+        let response: FfiResult<WalletAddressResponse> =
+            FfiResult::success(WalletAddressResponse { address: addr });
+
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+
+    create_result_string(&mut env, result)
+}
+
+// =============================================================================
+// Intent protocol — stateless helpers (no SDK transport handle needed)
+// =============================================================================
+
+/// Returns the executor PDA address for the pollinet-executor Anchor program.
+#[no_mangle]
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getExecutorPda(
+    mut env: JNIEnv,
+    _class: JClass,
+) -> jstring {
+    let pda = super::builders::executor_pda();
+    log::info!("🏦 getExecutorPda → pda={} bump={}", pda.pda, pda.bump);
+    let response: FfiResult<ExecutorPdaResponse> = FfiResult::success(pda);
+    let result: Result<String, FfiError> =
+        serde_json::to_string(&response).map_err(FfiError::serialization);
+    create_result_string(&mut env, result)
+}
+
+/// Builds a single unsigned transaction containing one `approve_checked` instruction
+/// per entry in the request. The `owner_wallet` must sign the returned transaction
+/// before it can be submitted to Solana.
+#[no_mangle]
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_createApproveTransaction(
+    mut env: JNIEnv,
+    _class: JClass,
+    request_json: JByteArray,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let bytes: Vec<u8> = env
+            .convert_byte_array(&request_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read request bytes: {}", e)))?;
+
+        let req: CreateApproveTransactionRequest = serde_json::from_slice(&bytes)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to parse request: {}", e)))?;
+
+        log::info!(
+            "🔐 createApproveTransaction owner={} fee_payer={} blockhash={} tokens={}",
+            req.owner_wallet,
+            req.fee_payer,
+            &req.recent_blockhash[..8],
+            req.tokens.len()
+        );
+        for t in &req.tokens {
+            log::info!(
+                "   token: mint={} account={} amount={} decimals={}",
+                t.mint_address,
+                t.token_account,
+                t.amount,
+                t.decimals
+            );
+        }
+
+        let approve = super::builders::approve_transaction(req)?;
+        log::info!(
+            "✅ createApproveTransaction → executor_pda={} tx_base64_len={}",
+            approve.executor_pda,
+            approve.transaction.len()
+        );
+        let response: FfiResult<ApproveTransactionResponse> = FfiResult::success(approve);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Builds a single unsigned transaction with one `revoke` instruction per token account,
+/// clearing the executor PDA's delegate authority.
+#[no_mangle]
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_createRevokeTransaction(
+    mut env: JNIEnv,
+    _class: JClass,
+    request_json: JByteArray,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let bytes: Vec<u8> = env
+            .convert_byte_array(&request_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read request bytes: {}", e)))?;
+
+        let req: CreateRevokeTransactionRequest = serde_json::from_slice(&bytes)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to parse request: {}", e)))?;
+
+        log::info!(
+            "🔓 createRevokeTransaction owner={} fee_payer={} accounts={} program={}",
+            req.owner_wallet,
+            req.fee_payer,
+            req.token_accounts.len(),
+            req.token_program
+        );
+
+        let revoke = super::builders::revoke_transaction(&req)?;
+        log::info!(
+            "✅ createRevokeTransaction → tx_base64_len={}",
+            revoke.transaction.len()
+        );
+        let response: FfiResult<RevokeTransactionResponse> = FfiResult::success(revoke);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Builds a single unsigned transaction from caller-supplied program instructions
+/// (governance votes, program-specific apps), for the same offline signing path.
+#[no_mangle]
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_createCustomTransaction(
+    mut env: JNIEnv,
+    _class: JClass,
+    request_json: JByteArray,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let bytes: Vec<u8> = env
+            .convert_byte_array(&request_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read request bytes: {}", e)))?;
+
+        let req: CreateCustomTransactionRequest = serde_json::from_slice(&bytes)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to parse request: {}", e)))?;
+
+        log::info!(
+            "🧩 createCustomTransaction fee_payer={} instructions={}",
+            req.fee_payer,
+            req.instructions.len()
+        );
+
+        let custom = super::builders::custom_transaction(&req)?;
+        log::info!(
+            "✅ createCustomTransaction → tx_base64_len={} signers={}",
+            custom.transaction.len(),
+            custom.required_signers.len()
+        );
+        let response: FfiResult<CustomTransactionResponse> = FfiResult::success(custom);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Parses a Solana Pay transfer request URL (`solana:<recipient>?amount=...`)
+/// into its `TransferRequest` fields, to show before paying.
+#[no_mangle]
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_parseSolanaPayUrl(
+    mut env: JNIEnv,
+    _class: JClass,
+    url: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let url: String = env
+            .get_string(&url)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read URL: {}", e)))?
+            .into();
+        let request = crate::intent::solana_pay::TransferRequest::parse(&url)
+            .map_err(FfiError::invalid_input)?;
+        let response: FfiResult<crate::intent::solana_pay::TransferRequest> =
+            FfiResult::success(request);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Builds the unsigned durable-nonce transaction paying a Solana Pay transfer
+/// request, so a QR code scanned offline can be paid over the mesh.
+#[no_mangle]
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_createSolanaPayTransaction(
+    mut env: JNIEnv,
+    _class: JClass,
+    request_json: JByteArray,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let bytes: Vec<u8> = env
+            .convert_byte_array(&request_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read request bytes: {}", e)))?;
+        let req: super::types::CreateSolanaPayTransactionRequest =
+            serde_json::from_slice(&bytes)
+                .map_err(|e| FfiError::invalid_input(format!("Failed to parse request: {}", e)))?;
+
+        let payment = super::builders::solana_pay_transaction(&req)?;
+        log::info!(
+            "✅ createSolanaPayTransaction → amount={} recipient={}",
+            payment.amount,
+            payment.request.recipient
+        );
+        let response: FfiResult<super::types::SolanaPayTransactionResponse> =
+            FfiResult::success(payment);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Serializes an Intent into the canonical 169-byte borsh layout and returns it as
+/// base64. Generates a random 16-byte nonce unless `nonce_hex` is supplied.
+/// Sign the returned `intent_bytes` with Ed25519 before submitting via pollicore.
+#[no_mangle]
+#[cfg(feature = "android")]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_createIntentBytes(
+    mut env: JNIEnv,
+    _class: JClass,
+    request_json: JByteArray,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let bytes: Vec<u8> = env
+            .convert_byte_array(&request_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read request bytes: {}", e)))?;
+
+        let req: CreateIntentBytesRequest = serde_json::from_slice(&bytes)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to parse request: {}", e)))?;
+
+        log::info!("🎯 createIntentBytes");
+        log::info!("   from={}", req.from);
+        log::info!("   to={}", req.to);
+        log::info!("   token_mint={}", req.token_mint);
+        log::info!("   amount={}", req.amount);
+        log::info!("   expires_at={}", req.expires_at);
+        log::info!("   gas_fee_amount={}", req.gas_fee_amount);
+        log::info!("   gas_fee_payee={}", req.gas_fee_payee);
+
+        let intent = super::builders::intent_bytes(&req)?;
+        log::info!(
+            "✅ createIntentBytes → 169 bytes (base64_len={}) nonce={}",
+            intent.intent_bytes.len(),
+            intent.nonce_hex
+        );
+        let response: FfiResult<IntentBytesResponse> = FfiResult::success(intent);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+// =============================================================================
+// Intent submission — delegates to crate::submission
+// =============================================================================
+
+/// Submit a signed intent to pollicore.
+///
+/// The pollicore URL is baked in at compile time from `POLLICORE_URL` in `.env`.
+/// All submission logic (HTTP client, logging, error handling) lives in
+/// `crate::submission` so that censorship-hardening transports can be added there
+/// without touching the FFI glue.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_submitIntent(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    request_json: JByteArray,
+) -> jstring {
+    use crate::submission::SubmitIntentRequest;
+
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+
+        let pollicore_url = transport.get_pollicore_url().ok_or_else(|| {
+            FfiError::not_configured(
+                "POLLICORE_URL not configured — set it in .env before building",
+            )
+        })?;
+
+        let req_bytes: Vec<u8> = env
+            .convert_byte_array(&request_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read request bytes: {}", e)))?;
+        let req: SubmitIntentRequest = serde_json::from_slice(&req_bytes).map_err(|e| {
+            FfiError::invalid_input(format!("Failed to parse SubmitIntentRequest: {}", e))
+        })?;
+
+        submit_intent(&pollicore_url, &req)
+    })();
+    create_result_string(&mut env, result)
+}
+
+#[cfg(feature = "android")]
+fn submit_intent(
+    pollicore_url: &str,
+    req: &crate::submission::SubmitIntentRequest,
+) -> Result<String, FfiError> {
+    let resp = crate::submission::submit_intent(pollicore_url, req)
+        .map_err(|e| FfiError::new(FfiErrorCode::Rpc, e.to_string()))?;
+
+    let response: FfiResult<crate::submission::SubmitIntentResponse> = FfiResult::success(resp);
+    serde_json::to_string(&response).map_err(FfiError::serialization)
+}
+
+// =============================================================================
+// Background requests — non-blocking variants of slow calls
+// =============================================================================
+
+/// Run `job` on a background thread; its `FfiResult` is delivered to the
+/// handle's event listener as a `RequestComplete` event and via `pollRequest`
+#[cfg(feature = "android")]
+fn start_request<J>(transport: Arc<HostBleTransport>, job: J) -> Result<String, FfiError>
+where
+    J: FnOnce(&HostBleTransport) -> Result<String, FfiError> + Send + 'static,
+{
+    let engine = transport.clone();
+    let request_id = super::requests::start(
+        move || job(&engine),
+        move |id, envelope| transport.emit_event(ProtocolEvent::request_complete(id, envelope)),
+    )?;
+    info!("⏳ Started background request {}", request_id);
+
+    let response: FfiResult<RequestIdResponse> =
+        FfiResult::success(RequestIdResponse { request_id });
+    serde_json::to_string(&response).map_err(FfiError::serialization)
+}
+
+/// Non-blocking [`submitIntent`](Java_xyz_pollinet_sdk_PolliNetFFI_submitIntent);
+/// returns `{requestId}` immediately
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_submitIntentAsync(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    request_json: JByteArray,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let pollicore_url = transport.get_pollicore_url().ok_or_else(|| {
+            FfiError::not_configured(
+                "POLLICORE_URL not configured — set it in .env before building",
+            )
+        })?;
+
+        let req_bytes: Vec<u8> = env
+            .convert_byte_array(&request_json)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read request bytes: {}", e)))?;
+        let req: crate::submission::SubmitIntentRequest = serde_json::from_slice(&req_bytes)
+            .map_err(|e| {
+                FfiError::invalid_input(format!("Failed to parse SubmitIntentRequest: {}", e))
+            })?;
+
+        start_request(transport, move |_| submit_intent(&pollicore_url, &req))
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Non-blocking [`saveQueues`](Java_xyz_pollinet_sdk_PolliNetFFI_saveQueues)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_saveQueuesAsync(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result = get_transport(handle).and_then(|transport| start_request(transport, save_queues));
+    create_result_string(&mut env, result)
+}
+
+/// Non-blocking [`backupState`](Java_xyz_pollinet_sdk_PolliNetFFI_backupState)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_backupStateAsync(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    path: JString,
+    passphrase: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let path: String = env
+            .get_string(&path)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to get path string: {}", e)))?
+            .into();
+        let passphrase: String = env
+            .get_string(&passphrase)
+            .map_err(|e| {
+                FfiError::invalid_input(format!("Failed to get passphrase string: {}", e))
+            })?
+            .into();
+
+        start_request(transport, move |transport| {
+            let summary = transport.backup_state(&path, &passphrase)?;
+            let response: FfiResult<crate::storage::BackupSummary> = FfiResult::success(summary);
+            serde_json::to_string(&response).map_err(FfiError::serialization)
+        })
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Check on a background request: `{status: "PENDING"}` or
+/// `{status: "DONE", result: <FfiResult>}` (forgotten once returned)
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_pollRequest(
+    mut env: JNIEnv,
+    _class: JClass,
+    request_id: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let state = super::requests::poll(request_id as u64)
+            .ok_or_else(|| FfiError::not_found(format!("Unknown request: {}", request_id)))?;
+        let response: FfiResult<super::requests::RequestPoll> = FfiResult::success(state);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+// =============================================================================
+// Subsystem 1 — Density-adaptive rotation
+// =============================================================================
+
+/// Record a scan observation for density estimation.
+/// Call on every `onScanResult` with the remote device address.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_recordScanResult(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    peer_id: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let peer_str: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("peer_id: {}", e)))?
+            .into();
+        transport.record_scan_result(&peer_str);
+        let response: FfiResult<bool> = FfiResult::success(true);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Recompute and return adaptive BLE session/cooldown parameters.
+/// Call every 10 seconds from Kotlin.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getAdaptiveParams(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let params = transport.density_estimator.lock().compute_params();
+        let response: FfiResult<crate::ble::AdaptiveParams> = FfiResult::success(params);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Add `peer_id` to the cooldown list for `cooldown_ms` milliseconds.
+/// Call after every session ends (both mutual-drain and force-close paths).
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_addPeerToCooldown(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    peer_id: JString,
+    cooldown_ms: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let peer_str: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("peer_id: {}", e)))?
+            .into();
+        transport
+            .cooldown_list
+            .lock()
+            .add(&peer_str, cooldown_ms as u64);
+        let response: FfiResult<bool> = FfiResult::success(true);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Returns true if `peer_id` is currently in cooldown (should not be connected to).
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_isPeerInCooldown(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    peer_id: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let peer_str: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("peer_id: {}", e)))?
+            .into();
+        let cooling = transport.cooldown_list.lock().is_cooling(&peer_str);
+        let response: FfiResult<bool> = FfiResult::success(cooling);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Sparse-network safety net: expire the oldest cooldown entry early.
+/// Call when idle_ms > 2 * session_target_ms AND all eligible peers are in cooldown.
+/// Returns the peer_id that was released, or null if the cooldown list was empty.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_expireOldestCooldown(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let expired = transport.cooldown_list.lock().expire_oldest();
+        let response: FfiResult<Option<String>> = FfiResult::success(expired);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Log a session telemetry record. Stored locally; future versions will relay to Pollicore.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_logSessionTelemetry(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    telemetry_json: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let _transport = get_transport(handle)?;
+        let json_str: String = env
+            .get_string(&telemetry_json)
+            .map_err(|e| FfiError::invalid_input(format!("telemetry_json: {}", e)))?
+            .into();
+        // Parse to validate the structure before logging.
+        let _record: crate::ble::SessionTelemetry = serde_json::from_str(&json_str)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid telemetry JSON: {}", e)))?;
+        log::info!("[SESSION_TELEMETRY] {}", json_str);
+        let response: FfiResult<bool> = FfiResult::success(true);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+// =============================================================================
+// Subsystem 2 — Per-peer materialized queue
+// =============================================================================
+
+/// Returns the list of tx_ids that should be sent to `peer_id` (4-byte hex compact ID).
+/// Filters by deliveredTo exclusion, TTL, and relevance > 0.
+/// Sorted by: is_confirmation desc, priority desc, relevance desc, age asc.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_outboundForPeer(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    peer_id_hex: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let peer_hex: String = env
+            .get_string(&peer_id_hex)
+            .map_err(|e| FfiError::invalid_input(format!("peer_id_hex: {}", e)))?
+            .into();
+        let peer_bytes = hex::decode(&peer_hex)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid peer_id_hex: {}", e)))?;
+        let peer_id: [u8; 4] = peer_bytes
+            .try_into()
+            .map_err(|_| "peer_id must be 4 bytes (8 hex chars)".to_string())?;
+
+        let tx_ids = runtime::block_on(async {
+            let queue = transport.sdk.queue_manager().outbound.read().await;
+            queue
+                .outbound_for_peer(&peer_id)
+                .iter()
+                .map(|tx| tx.tx_id.clone())
+                .collect::<Vec<_>>()
+        });
+
+        let response: FfiResult<Vec<String>> = FfiResult::success(tx_ids);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+/// Drain-conditional delivery confirmation (Subsystem 2).
+/// Call ONLY on mutual drain. Adds `peer_id` to deliveredTo, decrements relevance.
+/// Returns `{ removed: bool }` — true if the entry was evicted (relevance reached 0).
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_confirmDeliveredByPeer(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    tx_id: JString,
+    peer_id_hex: JString,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let tx_id_str: String = env
+            .get_string(&tx_id)
+            .map_err(|e| FfiError::invalid_input(format!("tx_id: {}", e)))?
+            .into();
+        let peer_hex: String = env
+            .get_string(&peer_id_hex)
+            .map_err(|e| FfiError::invalid_input(format!("peer_id_hex: {}", e)))?
+            .into();
+        let peer_bytes = hex::decode(&peer_hex)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid peer_id_hex: {}", e)))?;
+        let peer_id: [u8; 4] = peer_bytes
+            .try_into()
+            .map_err(|_| "peer_id must be 4 bytes".to_string())?;
+
+        let removed = runtime::block_on(async {
+            let mut queue = transport.sdk.queue_manager().outbound.write().await;
+            queue.confirm_delivered_by_peer(&tx_id_str, &peer_id)
+        });
+
+        #[derive(serde::Serialize)]
+        struct RemovedResponse {
+            removed: bool,
+        }
+        let response: FfiResult<RemovedResponse> = FfiResult::success(RemovedResponse { removed });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
+
+// =============================================================================
+// Subsystem 3 — Confirmation-driven purge
+// =============================================================================
+
+/// Ingest a received (or locally generated) confirmation.
+///
+/// Verifies the Ed25519 signature against the bundled Pollicore public key.
+/// On success:
+///   - removes the confirmed transaction from the outbound, retry and dead-letter queues
+///   - discards the inbound reassembly buffer for this txId (if present)
+///   - creates a tombstone
+///   - queues the confirmation for re-propagation at HIGH priority, unless it was
+///     already seen (active tombstone) or has reached MAX_TX_RELAY_HOPS
+///
+/// Returns `{ purged: bool, retriesRemoved: usize, duplicate: bool, added_to_carrier: bool }`.
+/// Silently drops tampered/unverifiable confirmations (returns success with both false).
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_ingestConfirmation(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    confirmation_bytes: JByteArray,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let raw: Vec<u8> = env
+            .convert_byte_array(&confirmation_bytes)
+            .map_err(|e| FfiError::invalid_input(format!("confirmation_bytes: {}", e)))?;
+
+        let mut conf = crate::ble::MeshConfirmation::from_frame_bytes(&raw)
+            .map_err(|e| FfiError::invalid_input(format!("Deserialize confirmation: {}", e)))?;
+
+        // Verify signature — POLLICORE_PUBKEY_BYTES is the 32-byte Ed25519 verifying key
+        // bundled at compile time. If not set, skip verification (dev mode only).
+        let valid = if let Some(pk) = get_pollicore_pubkey() {
+            conf.verify(&pk)
+        } else {
+            log::warn!(
+                "POLLICORE_PUBKEY not configured — skipping signature verification (dev mode)"
+            );
+            true
+        };
+
+        #[derive(serde::Serialize)]
+        struct IngestResult {
+            purged: bool,
+            #[serde(rename = "retriesRemoved")]
+            retries_removed: usize,
+            duplicate: bool,
+            added_to_carrier: bool,
+        }
+
+        if !valid {
+            log::warn!(
+                "Dropped tampered confirmation for tx_id_hash={}",
+                hex::encode(conf.tx_id_hash)
+            );
+            let response: FfiResult<IngestResult> = FfiResult::success(IngestResult {
+                purged: false,
+                retries_removed: 0,
+                duplicate: false,
+                added_to_carrier: false,
+            });
+            return serde_json::to_string(&response).map_err(FfiError::serialization);
+        }
+
+        let tx_id_hash_hex = hex::encode(conf.tx_id_hash);
+
+        // Already seen this confirmation? (tombstone still active)
+        let duplicate = transport
+            .tombstones
+            .lock()
+            .get(&tx_id_hash_hex)
+            .is_some_and(|tomb| tomb.is_valid());
+
+        // Remove the confirmed transaction from outbound, retry and dead-letter queues
+        let matched = runtime::block_on(
+            transport
+                .sdk
+                .queue_manager()
+                .apply_mesh_confirmation(&conf.tx_id_hash),
+        );
+        let purged = matched.outbound_purged > 0;
+
+        // Discard inbound reassembly buffer for this txId
+        {
+            let mut bufs = transport.inbound_buffers.lock();
+            bufs.remove(&tx_id_hash_hex);
+        }
+
+        // Create tombstone (valid for 2 × confirmation TTL)
+        {
+            let tomb =
+                crate::ble::Tombstone::new(conf.tx_id_hash, crate::ble::CONFIRMATION_TTL_SECS / 2);
+            transport
+                .tombstones
+                .lock()
+                .insert(tx_id_hash_hex.clone(), tomb);
+        }
+
+        if !duplicate {
+            transport.record_confirmation(&conf);
+        }
+
+        // Expire cooldown overrides so peers learn about this purge quickly
+        {
+            transport
+                .cooldown_list
+                .lock()
+                .expire_not_delivered(&conf.delivered_to);
+        }
+
+        // Wrap confirmation as an outbound entry and push to HIGH priority
+        // (once per confirmation, and only while it has hops left)
+        let added_to_carrier = if !duplicate && conf.is_alive() && conf.increment_hop() {
+            let conf_bytes = conf.to_frame_bytes()?;
+            let fragments = crate::ble::fragment_transaction(&conf_bytes);
+            let tx = crate::queue::OutboundTransaction {
+                tx_id: tx_id_hash_hex.clone(),
+                original_bytes: conf_bytes,
+                fragments,
+                priority: crate::queue::Priority::High,
+                created_at: conf.added_at,
+                retry_count: 0,
+                max_retries: 3,
+                relevance: conf.relevance,
+                delivered_to: conf.delivered_to,
+                ttl_secs: crate::ble::CONFIRMATION_TTL_SECS,
+                hop_count: conf.hop_count,
+                is_confirmation: true,
+            };
+            let pushed = runtime::block_on(async {
+                let mut queue = transport.sdk.queue_manager().outbound.write().await;
+                queue.push(tx).is_ok()
+            });
+            pushed
+        } else {
+            false
+        };
+
+        log::info!(
+            "ingestConfirmation txId={} purged={} retries_removed={} duplicate={} added_to_carrier={}",
+            tx_id_hash_hex,
+            purged,
+            matched.retries_removed,
+            duplicate,
+            added_to_carrier
+        );
+        if !duplicate {
+            transport.emit_event(ProtocolEvent::ack(&tx_id_hash_hex, "confirmed"));
+        }
+
+        let response: FfiResult<IngestResult> = FfiResult::success(IngestResult {
+            purged,
+            retries_removed: matched.retries_removed,
+            duplicate,
+            added_to_carrier,
+        });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
-
     create_result_string(&mut env, result)
 }
 
-/// Clear all queues (outbound, retry, confirmation, received) and reassembly buffers
-/// Note: This does NOT clear nonce data
-#[no_mangle]
+/// Check if a tx_id_hash (hex) has an active tombstone.
+/// Returns `{ tombstoned: bool }`. Call before buffering inbound fragments.
 #[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_clearAllQueues(
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_isTombstoned(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
+    tx_id_hash_hex: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-
-        runtime::block_on(async {
-            // Clear queue manager queues (outbound, retry, confirmation)
-            transport
-                .sdk
-                .clear_all_queues()
-                .await
-                .map_err(|e| format!("Failed to clear queues: {}", e))?;
-
-            // Clear reassembly buffers and completed transactions in transport
-            transport.clear_all_reassembly_buffers();
-
-            // Clear received queue
-            transport.clear_received_queue();
-
-            tracing::info!("✅ Cleared all queues (outbound, retry, confirmation, received) and reassembly buffers");
-
-            Ok::<(), String>(())
-        })?;
-
-        let response: FfiResult<SuccessResponse> =
-            FfiResult::success(SuccessResponse { success: true });
-
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let hash_hex: String = env
+            .get_string(&tx_id_hash_hex)
+            .map_err(|e| FfiError::invalid_input(format!("tx_id_hash_hex: {}", e)))?
+            .into();
+        let tombstoned = transport
+            .tombstones
+            .lock()
+            .get(&hash_hex)
+            .map(|t| t.is_valid())
+            .unwrap_or(false);
+        #[derive(serde::Serialize)]
+        struct TombResponse {
+            tombstoned: bool,
+        }
+        let response: FfiResult<TombResponse> = FfiResult::success(TombResponse { tombstoned });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
-
     create_result_string(&mut env, result)
 }
 
-// =============================================================================
-// Wallet address — reward attribution
-// =============================================================================
-
-/// Set the wallet address for this node session.
-/// Pass an empty string to clear a previously-set address.
-#[no_mangle]
+/// Cancel a transaction's fragment group: drop it locally and queue a
+/// TX_CANCEL frame. `reason` is "confirmed", "superseded" or "expired".
+/// Returns `{ dropped: usize }`.
 #[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_setWalletAddress(
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_cancelTransaction(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    address: JString,
+    tx_id: JString,
+    reason: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-
-        let addr: String = env
-            .get_string(&address)
-            .map_err(|e| format!("Failed to read address string: {}", e))?
+        let tx_id: String = env
+            .get_string(&tx_id)
+            .map_err(|e| FfiError::invalid_input(format!("tx_id: {}", e)))?
             .into();
-
-        let addr_opt = if addr.is_empty() {
-            None
-        } else {
-            Some(addr.clone())
+        let reason: String = env
+            .get_string(&reason)
+            .map_err(|e| FfiError::invalid_input(format!("reason: {}", e)))?
+            .into();
+        let reason = match reason.as_str() {
+            "confirmed" => crate::ble::CancelReason::Confirmed,
+            "superseded" => crate::ble::CancelReason::Superseded,
+            "expired" => crate::ble::CancelReason::Expired,
+            other => {
+                return Err(FfiError::invalid_input(format!(
+                    "Unknown cancel reason '{}'",
+                    other
+                )))
+            }
         };
-        transport.set_wallet_address(addr_opt);
-
-        info!(
-            "✅ Wallet address updated: {}",
-            if addr.is_empty() { "<cleared>" } else { &addr }
-        );
-
-        let response: FfiResult<SuccessResponse> =
-            FfiResult::success(SuccessResponse { success: true });
+        let dropped = transport
+            .cancel_transaction(&tx_id, reason)
+            .map_err(FfiError::invalid_input)?;
 
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        #[derive(serde::Serialize)]
+        struct CancelResponse {
+            dropped: usize,
+        }
+        let response: FfiResult<CancelResponse> = FfiResult::success(CancelResponse { dropped });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
-
     create_result_string(&mut env, result)
 }
 
-/// Get the wallet address currently set for this node session.
-/// Returns an empty address field if none has been set.
-#[no_mangle]
+/// Hand a received TX_CANCEL (0x0F) frame to the engine.
+/// Returns `{ dropped: bool }`.
 #[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getWalletAddress(
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_ingestTxCancel(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
+    data: JByteArray,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-
-        let addr = transport.get_wallet_address().unwrap_or_default();
+        let data: Vec<u8> = env
+            .convert_byte_array(&data)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read data: {}", e)))?;
+        let dropped = transport
+            .ingest_tx_cancel(&data)
+            .map_err(FfiError::invalid_input)?;
 
         #[derive(serde::Serialize)]
-        struct WalletAddressResponse {
-            address: String,
+        struct IngestResponse {
+            dropped: bool,
         }
-
-        let response: FfiResult<WalletAddressResponse> =
-            FfiResult::success(WalletAddressResponse { address: addr });
-
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<IngestResponse> = FfiResult::success(IngestResponse { dropped });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
-
     create_result_string(&mut env, result)
 }
 
-// =============================================================================
-// Intent protocol — stateless helpers (no SDK transport handle needed)
-// =============================================================================
-
-/// Returns the executor PDA address for the pollinet-executor Anchor program.
-#[no_mangle]
+/// Resend fragments a peer NACKed (FRAGMENT_NACK, 0x10) if the
+/// transaction is still queued. Returns `{ resent: number }`.
 #[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getExecutorPda(
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_ingestFragmentNack(
     mut env: JNIEnv,
     _class: JClass,
+    handle: jlong,
+    data: JByteArray,
 ) -> jstring {
-    let (pda, bump) = crate::intent::executor_pda();
-    log::info!("🏦 getExecutorPda → pda={} bump={}", pda, bump);
-    let response: FfiResult<ExecutorPdaResponse> = FfiResult::success(ExecutorPdaResponse {
-        pda: pda.to_string(),
-        bump,
-    });
-    let result: Result<String, String> =
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e));
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let data: Vec<u8> = env
+            .convert_byte_array(&data)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read data: {}", e)))?;
+        let resent = transport
+            .ingest_fragment_nack(&data)
+            .map_err(FfiError::invalid_input)?;
+
+        #[derive(serde::Serialize)]
+        struct IngestResponse {
+            resent: usize,
+        }
+        let response: FfiResult<IngestResponse> = FfiResult::success(IngestResponse { resent });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
     create_result_string(&mut env, result)
 }
 
-/// Builds a single unsigned transaction containing one `approve_checked` instruction
-/// per entry in the request. The `owner_wallet` must sign the returned transaction
-/// before it can be submitted to Solana.
-#[no_mangle]
+/// Sign and flood an emergency notice (at most 100 bytes of text); it is
+/// sent before anything else queued. Returns `{ id: string }`.
 #[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_createApproveTransaction(
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_broadcastEmergency(
     mut env: JNIEnv,
     _class: JClass,
-    request_json: JByteArray,
+    handle: jlong,
+    message: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        let bytes: Vec<u8> = env
-            .convert_byte_array(&request_json)
-            .map_err(|e| format!("Failed to read request bytes: {}", e))?;
-
-        let req: CreateApproveTransactionRequest = serde_json::from_slice(&bytes)
-            .map_err(|e| format!("Failed to parse request: {}", e))?;
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let message: String = env
+            .get_string(&message)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read message: {}", e)))?
+            .into();
+        let id = transport
+            .broadcast_emergency(&message)
+            .map_err(FfiError::invalid_input)?;
 
-        log::info!(
-            "🔐 createApproveTransaction owner={} fee_payer={} blockhash={} tokens={}",
-            req.owner_wallet,
-            req.fee_payer,
-            &req.recent_blockhash[..8],
-            req.tokens.len()
-        );
-        for t in &req.tokens {
-            log::info!(
-                "   token: mint={} account={} amount={} decimals={}",
-                t.mint_address,
-                t.token_account,
-                t.amount,
-                t.decimals
-            );
+        #[derive(serde::Serialize)]
+        struct BroadcastResponse {
+            id: String,
         }
-
-        let owner: solana_sdk::pubkey::Pubkey = std::str::FromStr::from_str(&req.owner_wallet)
-            .map_err(|e| format!("Invalid owner_wallet: {}", e))?;
-        let fee_payer: solana_sdk::pubkey::Pubkey = std::str::FromStr::from_str(&req.fee_payer)
-            .map_err(|e| format!("Invalid fee_payer: {}", e))?;
-
-        let blockhash_bytes = bs58::decode(&req.recent_blockhash)
-            .into_vec()
-            .map_err(|e| format!("Invalid recent_blockhash: {}", e))?;
-        let blockhash_arr: [u8; 32] = blockhash_bytes
-            .try_into()
-            .map_err(|_| "recent_blockhash must decode to 32 bytes".to_string())?;
-        let recent_blockhash = solana_sdk::hash::Hash::new_from_array(blockhash_arr);
-
-        let approvals: Vec<crate::intent::TokenApprovalInput> = req
-            .tokens
-            .into_iter()
-            .map(|t| crate::intent::TokenApprovalInput {
-                mint_address: t.mint_address,
-                amount: t.amount,
-                decimals: t.decimals,
-                token_account: t.token_account,
-                token_program: t.token_program,
-            })
-            .collect();
-
-        let (executor_pda_key, _) = crate::intent::executor_pda();
-
-        let tx_base64 = crate::intent::build_approve_transaction(
-            &owner,
-            &fee_payer,
-            recent_blockhash,
-            &approvals,
-        )?;
-
-        log::info!(
-            "✅ createApproveTransaction → executor_pda={} tx_base64_len={}",
-            executor_pda_key,
-            tx_base64.len()
-        );
-        let response: FfiResult<ApproveTransactionResponse> =
-            FfiResult::success(ApproveTransactionResponse {
-                transaction: tx_base64,
-                executor_pda: executor_pda_key.to_string(),
-            });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<BroadcastResponse> = FfiResult::success(BroadcastResponse { id });
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
     create_result_string(&mut env, result)
 }
 
-/// Builds a single unsigned transaction with one `revoke` instruction per token account,
-/// clearing the executor PDA's delegate authority.
-#[no_mangle]
+/// Hand a received EMERGENCY_BROADCAST (0x11) frame to the engine. New
+/// notices are relayed automatically. Returns the `EmergencyNotice`, or
+/// null for duplicates and stale notices.
 #[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_createRevokeTransaction(
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_ingestEmergency(
     mut env: JNIEnv,
     _class: JClass,
-    request_json: JByteArray,
+    handle: jlong,
+    data: JByteArray,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        let bytes: Vec<u8> = env
-            .convert_byte_array(&request_json)
-            .map_err(|e| format!("Failed to read request bytes: {}", e))?;
-
-        let req: CreateRevokeTransactionRequest = serde_json::from_slice(&bytes)
-            .map_err(|e| format!("Failed to parse request: {}", e))?;
-
-        log::info!(
-            "🔓 createRevokeTransaction owner={} fee_payer={} accounts={} program={}",
-            req.owner_wallet,
-            req.fee_payer,
-            req.token_accounts.len(),
-            req.token_program
-        );
-
-        let owner: solana_sdk::pubkey::Pubkey = std::str::FromStr::from_str(&req.owner_wallet)
-            .map_err(|e| format!("Invalid owner_wallet: {}", e))?;
-        let fee_payer: solana_sdk::pubkey::Pubkey = std::str::FromStr::from_str(&req.fee_payer)
-            .map_err(|e| format!("Invalid fee_payer: {}", e))?;
-
-        let blockhash_bytes = bs58::decode(&req.recent_blockhash)
-            .into_vec()
-            .map_err(|e| format!("Invalid recent_blockhash: {}", e))?;
-        let blockhash_arr: [u8; 32] = blockhash_bytes
-            .try_into()
-            .map_err(|_| "recent_blockhash must decode to 32 bytes".to_string())?;
-        let recent_blockhash = solana_sdk::hash::Hash::new_from_array(blockhash_arr);
-
-        let tx_base64 = crate::intent::build_revoke_transaction(
-            &owner,
-            &fee_payer,
-            recent_blockhash,
-            &req.token_accounts,
-            &req.token_program,
-        )?;
-
-        log::info!(
-            "✅ createRevokeTransaction → tx_base64_len={}",
-            tx_base64.len()
-        );
-        let response: FfiResult<RevokeTransactionResponse> =
-            FfiResult::success(RevokeTransactionResponse {
-                transaction: tx_base64,
-            });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let data: Vec<u8> = env
+            .convert_byte_array(&data)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read data: {}", e)))?;
+        let notice = transport
+            .ingest_emergency(&data)
+            .map_err(FfiError::invalid_input)?;
+        let response: FfiResult<Option<crate::ble::EmergencyNotice>> = FfiResult::success(notice);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
     create_result_string(&mut env, result)
 }
 
-/// Serializes an Intent into the canonical 169-byte borsh layout and returns it as
-/// base64. Generates a random 16-byte nonce unless `nonce_hex` is supplied.
-/// Sign the returned `intent_bytes` with Ed25519 before submitting via pollicore.
-#[no_mangle]
+/// Emergency notices received so far, oldest first.
 #[cfg(feature = "android")]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_createIntentBytes(
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getEmergencyNotices(
     mut env: JNIEnv,
     _class: JClass,
-    request_json: JByteArray,
+    handle: jlong,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        let bytes: Vec<u8> = env
-            .convert_byte_array(&request_json)
-            .map_err(|e| format!("Failed to read request bytes: {}", e))?;
-
-        let req: CreateIntentBytesRequest = serde_json::from_slice(&bytes)
-            .map_err(|e| format!("Failed to parse request: {}", e))?;
-
-        log::info!("🎯 createIntentBytes");
-        log::info!("   from={}", req.from);
-        log::info!("   to={}", req.to);
-        log::info!("   token_mint={}", req.token_mint);
-        log::info!("   amount={}", req.amount);
-        log::info!("   expires_at={}", req.expires_at);
-        log::info!("   gas_fee_amount={}", req.gas_fee_amount);
-        log::info!("   gas_fee_payee={}", req.gas_fee_payee);
-
-        let pubkey_bytes = |s: &str, field: &str| -> Result<[u8; 32], String> {
-            let pk: solana_sdk::pubkey::Pubkey =
-                std::str::FromStr::from_str(s).map_err(|e| format!("Invalid {}: {}", field, e))?;
-            Ok(pk.to_bytes())
-        };
-
-        let from = pubkey_bytes(&req.from, "from")?;
-        let to = pubkey_bytes(&req.to, "to")?;
-        let token_mint = pubkey_bytes(&req.token_mint, "token_mint")?;
-        let gas_fee_payee = pubkey_bytes(&req.gas_fee_payee, "gas_fee_payee")?;
-
-        let nonce: [u8; 16] = if let Some(hex_str) = &req.nonce_hex {
-            let decoded = hex::decode(hex_str).map_err(|e| format!("Invalid nonce_hex: {}", e))?;
-            decoded.try_into().map_err(|_| {
-                "nonce_hex must decode to exactly 16 bytes (32 hex chars)".to_string()
-            })?
-        } else {
-            crate::intent::random_nonce()
-        };
-
-        let intent_bytes = crate::intent::serialize_intent(
-            1,
-            &from,
-            &to,
-            &token_mint,
-            req.amount,
-            &nonce,
-            req.expires_at,
-            req.gas_fee_amount,
-            &gas_fee_payee,
-        );
-
-        use base64::{engine::general_purpose::STANDARD, Engine};
-        let encoded = STANDARD.encode(intent_bytes);
-        log::info!(
-            "✅ createIntentBytes → {} bytes (base64_len={}) nonce={}",
-            169,
-            encoded.len(),
-            hex::encode(nonce)
-        );
-        let response: FfiResult<IntentBytesResponse> = FfiResult::success(IntentBytesResponse {
-            intent_bytes: encoded,
-            nonce_hex: hex::encode(nonce),
-        });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let response: FfiResult<Vec<crate::ble::EmergencyNotice>> =
+            FfiResult::success(transport.emergency_notices());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
     create_result_string(&mut env, result)
 }
 
-// =============================================================================
-// Intent submission — delegates to crate::submission
-// =============================================================================
-
-/// Submit a signed intent to pollicore.
-///
-/// The pollicore URL is baked in at compile time from `POLLICORE_URL` in `.env`.
-/// All submission logic (HTTP client, logging, error handling) lives in
-/// `crate::submission` so that censorship-hardening transports can be added there
-/// without touching the FFI glue.
+/// Sign an offline payment request with the device key (merchant side) and
+/// queue it for the payer in range. Takes a `CreatePaymentRequestRequest`
+/// JSON; returns the `PaymentRequestInfo`.
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_submitIntent(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_createPaymentRequest(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
     request_json: JByteArray,
 ) -> jstring {
-    use crate::submission::{SubmitIntentRequest, SubmitIntentResponse};
-
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-
-        let pollicore_url = transport.get_pollicore_url().ok_or_else(|| {
-            "POLLICORE_URL not configured — set it in .env before building".to_string()
-        })?;
-
-        let req_bytes: Vec<u8> = env
+        let bytes: Vec<u8> = env
             .convert_byte_array(&request_json)
-            .map_err(|e| format!("Failed to read request bytes: {}", e))?;
-        let req: SubmitIntentRequest = serde_json::from_slice(&req_bytes)
-            .map_err(|e| format!("Failed to parse SubmitIntentRequest: {}", e))?;
-
-        let resp: SubmitIntentResponse =
-            crate::submission::submit_intent(&pollicore_url, &req).map_err(|e| e.to_string())?;
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read request bytes: {}", e)))?;
+        let req: super::types::CreatePaymentRequestRequest = serde_json::from_slice(&bytes)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to parse request: {}", e)))?;
+        let info = transport
+            .create_payment_request(&req)
+            .map_err(FfiError::invalid_input)?;
+        let response: FfiResult<crate::ble::PaymentRequestInfo> = FfiResult::success(info);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
+    })();
+    create_result_string(&mut env, result)
+}
 
-        let response: FfiResult<SubmitIntentResponse> = FfiResult::success(resp);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+/// Hand a received PAYMENT_REQUEST (0x12) frame to the engine (payer side).
+/// Returns the verified `PaymentRequestInfo`.
+#[cfg(feature = "android")]
+#[no_mangle]
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_ingestPaymentRequest(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    data: JByteArray,
+) -> jstring {
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let data: Vec<u8> = env
+            .convert_byte_array(&data)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read data: {}", e)))?;
+        let info = transport
+            .ingest_payment_request(&data)
+            .map_err(FfiError::invalid_input)?;
+        let response: FfiResult<crate::ble::PaymentRequestInfo> = FfiResult::success(info);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
     create_result_string(&mut env, result)
 }
 
-// =============================================================================
-// Subsystem 1 — Density-adaptive rotation
-// =============================================================================
-
-/// Record a scan observation for density estimation.
-/// Call on every `onScanResult` with the remote device address.
+/// Payment requests received and still payable.
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_recordScanResult(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getPaymentRequests(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    peer_id: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-        let peer_str: String = env
-            .get_string(&peer_id)
-            .map_err(|e| format!("peer_id: {}", e))?
-            .into();
-        transport.density_estimator.lock().record(&peer_str);
-        let response: FfiResult<bool> = FfiResult::success(true);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<Vec<crate::ble::PaymentRequestInfo>> =
+            FfiResult::success(transport.payment_requests());
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
     create_result_string(&mut env, result)
 }
 
-/// Recompute and return adaptive BLE session/cooldown parameters.
-/// Call every 10 seconds from Kotlin.
+/// Build the unsigned durable-nonce transaction paying a received request.
+/// `nonce_account_json` is a `NonceAccountInfo`. Returns a
+/// `PaymentTransactionResponse`.
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getAdaptiveParams(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_buildPaymentTransaction(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
+    request_id: JString,
+    payer: JString,
+    nonce_account_json: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-        let params = transport.density_estimator.lock().compute_params();
-        let response: FfiResult<crate::ble::AdaptiveParams> = FfiResult::success(params);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let mut read = |value: &JString, field: &str| -> Result<String, FfiError> {
+            Ok(env
+                .get_string(value)
+                .map_err(|e| FfiError::invalid_input(format!("Failed to read {}: {}", field, e)))?
+                .into())
+        };
+        let request_id = read(&request_id, "request ID")?;
+        let payer = read(&payer, "payer")?;
+        let nonce_json = read(&nonce_account_json, "nonce account")?;
+        let nonce: super::nonce_accounts::NonceAccountInfo = serde_json::from_str(&nonce_json)
+            .map_err(|e| {
+                FfiError::invalid_input(format!("Failed to parse nonce account: {}", e))
+            })?;
+        let built = transport.build_payment_transaction(&request_id, &payer, &nonce)?;
+        let response: FfiResult<super::types::PaymentTransactionResponse> =
+            FfiResult::success(built);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
     create_result_string(&mut env, result)
 }
 
-/// Add `peer_id` to the cooldown list for `cooldown_ms` milliseconds.
-/// Call after every session ends (both mutual-drain and force-close paths).
+/// Sign a receipt for a payment the wallet just signed (payer side) and
+/// queue it for the merchant. `request_id` is the hex id of the payment
+/// request it pays, or empty for none. Returns the `PaymentReceiptRecord`.
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_addPeerToCooldown(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_createPaymentReceipt(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    peer_id: JString,
-    cooldown_ms: jlong,
+    signed_tx: JByteArray,
+    request_id: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-        let peer_str: String = env
-            .get_string(&peer_id)
-            .map_err(|e| format!("peer_id: {}", e))?
+        let signed_tx: Vec<u8> = env.convert_byte_array(&signed_tx).map_err(|e| {
+            FfiError::invalid_input(format!("Failed to read transaction bytes: {}", e))
+        })?;
+        let request_id: String = env
+            .get_string(&request_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read request ID: {}", e)))?
             .into();
-        transport
-            .cooldown_list
-            .lock()
-            .add(&peer_str, cooldown_ms as u64);
-        let response: FfiResult<bool> = FfiResult::success(true);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let request_id = (!request_id.is_empty()).then_some(request_id.as_str());
+        let record = transport.create_payment_receipt(&signed_tx, request_id)?;
+        let response: FfiResult<crate::storage::PaymentReceiptRecord> = FfiResult::success(record);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
     create_result_string(&mut env, result)
 }
 
-/// Returns true if `peer_id` is currently in cooldown (should not be connected to).
+/// Hand a received PAYMENT_RECEIPT (0x13) frame to the engine: a payer's
+/// receipt, or a merchant's countersignature. Returns the stored
+/// `PaymentReceiptRecord`.
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_isPeerInCooldown(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_ingestPaymentReceipt(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    peer_id: JString,
+    data: JByteArray,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-        let peer_str: String = env
-            .get_string(&peer_id)
-            .map_err(|e| format!("peer_id: {}", e))?
-            .into();
-        let cooling = transport.cooldown_list.lock().is_cooling(&peer_str);
-        let response: FfiResult<bool> = FfiResult::success(cooling);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let data: Vec<u8> = env
+            .convert_byte_array(&data)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read data: {}", e)))?;
+        let record = transport.ingest_payment_receipt(&data)?;
+        let response: FfiResult<crate::storage::PaymentReceiptRecord> = FfiResult::success(record);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
     create_result_string(&mut env, result)
 }
 
-/// Sparse-network safety net: expire the oldest cooldown entry early.
-/// Call when idle_ms > 2 * session_target_ms AND all eligible peers are in cooldown.
-/// Returns the peer_id that was released, or null if the cooldown list was empty.
+/// Countersign a received receipt (merchant side), e.g. for a payment made
+/// without a request, and queue the countersignature for the payer.
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_expireOldestCooldown(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_countersignPaymentReceipt(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
+    tx_signature: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-        let expired = transport.cooldown_list.lock().expire_oldest();
-        let response: FfiResult<Option<String>> = FfiResult::success(expired);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let tx_signature: String = env
+            .get_string(&tx_signature)
+            .map_err(|e| {
+                FfiError::invalid_input(format!("Failed to read transaction signature: {}", e))
+            })?
+            .into();
+        let record = transport.countersign_payment_receipt(&tx_signature)?;
+        let response: FfiResult<crate::storage::PaymentReceiptRecord> = FfiResult::success(record);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
     create_result_string(&mut env, result)
 }
 
-/// Log a session telemetry record. Stored locally; future versions will relay to Pollicore.
+/// Stored payment receipts (paid and received), newest first
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_logSessionTelemetry(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getPaymentReceipts(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    telemetry_json: JString,
+    offset: jlong,
+    limit: jlong,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
-        let _transport = get_transport(handle)?;
-        let json_str: String = env
-            .get_string(&telemetry_json)
-            .map_err(|e| format!("telemetry_json: {}", e))?
-            .into();
-        // Parse to validate the structure before logging.
-        let _record: crate::ble::SessionTelemetry = serde_json::from_str(&json_str)
-            .map_err(|e| format!("Invalid telemetry JSON: {}", e))?;
-        log::info!("[SESSION_TELEMETRY] {}", json_str);
-        let response: FfiResult<bool> = FfiResult::success(true);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+    let result: Result<String, FfiError> = (|| {
+        let transport = get_transport(handle)?;
+        let receipts = transport.payment_receipts(offset.max(0) as usize, limit.max(0) as usize)?;
+        let response: FfiResult<Vec<crate::storage::PaymentReceiptRecord>> =
+            FfiResult::success(receipts);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
     create_result_string(&mut env, result)
 }
 
-// =============================================================================
-// Subsystem 2 — Per-peer materialized queue
-// =============================================================================
-
-/// Returns the list of tx_ids that should be sent to `peer_id` (4-byte hex compact ID).
-/// Filters by deliveredTo exclusion, TTL, and relevance > 0.
-/// Sorted by: is_confirmation desc, priority desc, relevance desc, age asc.
+/// Ping a neighbour (`peerId` is its address, for the result).
+/// Returns `{ probeId }`; the answer arrives via `ingestProbeFrame`.
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_outboundForPeer(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_pingPeer(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    peer_id_hex: JString,
+    peer_id: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-        let peer_hex: String = env
-            .get_string(&peer_id_hex)
-            .map_err(|e| format!("peer_id_hex: {}", e))?
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer ID: {}", e)))?
             .into();
-        let peer_bytes =
-            hex::decode(&peer_hex).map_err(|e| format!("Invalid peer_id_hex: {}", e))?;
-        let peer_id: [u8; 4] = peer_bytes
-            .try_into()
-            .map_err(|_| "peer_id must be 4 bytes (8 hex chars)".to_string())?;
-
-        let tx_ids = runtime::block_on(async {
-            let queue = transport.sdk.queue_manager().outbound.read().await;
-            queue
-                .outbound_for_peer(&peer_id)
-                .iter()
-                .map(|tx| tx.tx_id.clone())
-                .collect::<Vec<_>>()
-        });
-
-        let response: FfiResult<Vec<String>> = FfiResult::success(tx_ids);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let probe_id = transport.ping_peer(&peer_id)?;
+        probe_id_response(probe_id)
     })();
     create_result_string(&mut env, result)
 }
 
-/// Drain-conditional delivery confirmation (Subsystem 2).
-/// Call ONLY on mutual drain. Adds `peer_id` to deliveredTo, decrements relevance.
-/// Returns `{ removed: bool }` — true if the entry was evicted (relevance reached 0).
+/// Trace the path to the device with this base58 device key.
+/// Returns `{ probeId }`.
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_confirmDeliveredByPeer(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_traceroute(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    tx_id: JString,
-    peer_id_hex: JString,
+    device_key: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-        let tx_id_str: String = env
-            .get_string(&tx_id)
-            .map_err(|e| format!("tx_id: {}", e))?
+        let device_key: String = env
+            .get_string(&device_key)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read device key: {}", e)))?
             .into();
-        let peer_hex: String = env
-            .get_string(&peer_id_hex)
-            .map_err(|e| format!("peer_id_hex: {}", e))?
-            .into();
-        let peer_bytes =
-            hex::decode(&peer_hex).map_err(|e| format!("Invalid peer_id_hex: {}", e))?;
-        let peer_id: [u8; 4] = peer_bytes
-            .try_into()
-            .map_err(|_| "peer_id must be 4 bytes".to_string())?;
-
-        let removed = runtime::block_on(async {
-            let mut queue = transport.sdk.queue_manager().outbound.write().await;
-            queue.confirm_delivered_by_peer(&tx_id_str, &peer_id)
-        });
-
-        #[derive(serde::Serialize)]
-        struct RemovedResponse {
-            removed: bool,
-        }
-        let response: FfiResult<RemovedResponse> = FfiResult::success(RemovedResponse { removed });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let probe_id = transport
+            .traceroute(&device_key)
+            .map_err(FfiError::invalid_input)?;
+        probe_id_response(probe_id)
     })();
     create_result_string(&mut env, result)
 }
 
-// =============================================================================
-// Subsystem 3 — Confirmation-driven purge
-// =============================================================================
+#[cfg(feature = "android")]
+fn probe_id_response(probe_id: String) -> Result<String, FfiError> {
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ProbeResponse {
+        probe_id: String,
+    }
+    let response: FfiResult<ProbeResponse> = FfiResult::success(ProbeResponse { probe_id });
+    serde_json::to_string(&response).map_err(FfiError::serialization)
+}
 
-/// Ingest a received (or locally generated) confirmation.
-///
-/// Verifies the Ed25519 signature against the bundled Pollicore public key.
-/// On success:
-///   - purges any matching entry from the outbound carrier set
-///   - discards the inbound reassembly buffer for this txId (if present)
-///   - creates a tombstone
-///   - queues the confirmation for re-propagation at HIGH priority
-///
-/// Returns `{ purged: bool, added_to_carrier: bool }`.
-/// Silently drops tampered/unverifiable confirmations (returns success with both false).
+/// Hand a received ping / pong (0x01 / 0x02) frame to the engine.
+/// Returns the `ProbeResult` when it answers one of our probes, else null.
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_ingestConfirmation(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_ingestProbeFrame(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    confirmation_bytes: JByteArray,
+    peer_id: JString,
+    data: JByteArray,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-        let raw: Vec<u8> = env
-            .convert_byte_array(&confirmation_bytes)
-            .map_err(|e| format!("confirmation_bytes: {}", e))?;
-
-        let conf = crate::ble::MeshConfirmation::from_frame_bytes(&raw)
-            .map_err(|e| format!("Deserialize confirmation: {}", e))?;
-
-        // Verify signature — POLLICORE_PUBKEY_BYTES is the 32-byte Ed25519 verifying key
-        // bundled at compile time. If not set, skip verification (dev mode only).
-        let valid = if let Some(pk) = get_pollicore_pubkey() {
-            conf.verify(&pk)
-        } else {
-            log::warn!(
-                "POLLICORE_PUBKEY not configured — skipping signature verification (dev mode)"
-            );
-            true
-        };
-
-        #[derive(serde::Serialize)]
-        struct IngestResult {
-            purged: bool,
-            added_to_carrier: bool,
-        }
-
-        if !valid {
-            log::warn!(
-                "Dropped tampered confirmation for tx_id_hash={}",
-                hex::encode(conf.tx_id_hash)
-            );
-            let response: FfiResult<IngestResult> = FfiResult::success(IngestResult {
-                purged: false,
-                added_to_carrier: false,
-            });
-            return serde_json::to_string(&response)
-                .map_err(|e| format!("Serialization error: {}", e));
-        }
-
-        let tx_id_hash_hex = hex::encode(conf.tx_id_hash);
-
-        // Purge matching entry from outbound carrier set
-        let purged = runtime::block_on(async {
-            let mut queue = transport.sdk.queue_manager().outbound.write().await;
-            queue.purge_by_tx_id(&tx_id_hash_hex)
-        });
-
-        // Discard inbound reassembly buffer for this txId
-        {
-            let mut bufs = transport.inbound_buffers.lock();
-            bufs.remove(&tx_id_hash_hex);
-        }
-
-        // Create tombstone (valid for 2 × confirmation TTL)
-        {
-            let tomb =
-                crate::ble::Tombstone::new(conf.tx_id_hash, crate::ble::CONFIRMATION_TTL_SECS / 2);
-            transport
-                .tombstones
-                .lock()
-                .insert(tx_id_hash_hex.clone(), tomb);
-        }
-
-        // Expire cooldown overrides so peers learn about this purge quickly
-        {
-            transport
-                .cooldown_list
-                .lock()
-                .expire_not_delivered(&conf.delivered_to);
-        }
-
-        // Wrap confirmation as an outbound entry and push to HIGH priority
-        let added_to_carrier = if conf.is_alive() {
-            let conf_bytes = conf.to_frame_bytes()?;
-            let fragments = crate::ble::fragment_transaction(&conf_bytes);
-            let tx = crate::queue::OutboundTransaction {
-                tx_id: tx_id_hash_hex.clone(),
-                original_bytes: conf_bytes,
-                fragments,
-                priority: crate::queue::Priority::High,
-                created_at: conf.added_at,
-                retry_count: 0,
-                max_retries: 3,
-                relevance: conf.relevance,
-                delivered_to: conf.delivered_to,
-                ttl_secs: crate::ble::CONFIRMATION_TTL_SECS,
-                hop_count: conf.hop_count,
-                is_confirmation: true,
-            };
-            let pushed = runtime::block_on(async {
-                let mut queue = transport.sdk.queue_manager().outbound.write().await;
-                queue.push(tx).is_ok()
-            });
-            pushed
-        } else {
-            false
-        };
-
-        log::info!(
-            "ingestConfirmation txId={} purged={} added_to_carrier={}",
-            tx_id_hash_hex,
-            purged,
-            added_to_carrier
-        );
+        let peer_id: String = env
+            .get_string(&peer_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read peer ID: {}", e)))?
+            .into();
+        let data: Vec<u8> = env
+            .convert_byte_array(&data)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read data: {}", e)))?;
+        let probe_result = transport
+            .ingest_probe_frame(&peer_id, &data)
+            .map_err(FfiError::invalid_input)?;
 
-        let response: FfiResult<IngestResult> = FfiResult::success(IngestResult {
-            purged,
-            added_to_carrier,
-        });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<Option<crate::ble::ProbeResult>> = FfiResult::success(probe_result);
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
     create_result_string(&mut env, result)
 }
 
-/// Check if a tx_id_hash (hex) has an active tombstone.
-/// Returns `{ tombstoned: bool }`. Call before buffering inbound fragments.
+/// Result of an answered ping or traceroute, or null if none came back yet
 #[cfg(feature = "android")]
 #[no_mangle]
-pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_isTombstoned(
+pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getProbeResult(
     mut env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    tx_id_hash_hex: JString,
+    probe_id: JString,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
-        let hash_hex: String = env
-            .get_string(&tx_id_hash_hex)
-            .map_err(|e| format!("tx_id_hash_hex: {}", e))?
+        let probe_id: String = env
+            .get_string(&probe_id)
+            .map_err(|e| FfiError::invalid_input(format!("Failed to read probe ID: {}", e)))?
             .into();
-        let tombstoned = transport
-            .tombstones
-            .lock()
-            .get(&hash_hex)
-            .map(|t| t.is_valid())
-            .unwrap_or(false);
-        #[derive(serde::Serialize)]
-        struct TombResponse {
-            tombstoned: bool,
-        }
-        let response: FfiResult<TombResponse> = FfiResult::success(TombResponse { tombstoned });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        let response: FfiResult<Option<crate::ble::ProbeResult>> =
+            FfiResult::success(transport.probe_result(&probe_id));
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
     create_result_string(&mut env, result)
 }
 
-/// Evict expired tombstones and expired cooldowns. Call in the periodic 10s tick.
+/// Evict expired tombstones and cooldowns, and run queue maintenance.
+/// Call in the periodic 10s tick.
 #[cfg(feature = "android")]
 #[no_mangle]
 pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_periodicMaintenance(
@@ -3016,14 +5530,16 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_periodicMaintenance(
     _class: JClass,
     handle: jlong,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
         // Evict expired tombstones
         transport.tombstones.lock().retain(|_, t| t.is_valid());
         // Evict expired cooldowns
         transport.cooldown_list.lock().evict_expired();
+        // Expire stale queue entries and auto-save
+        runtime::block_on(transport.sdk.queue_manager().run_maintenance());
         let response: FfiResult<bool> = FfiResult::success(true);
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
     create_result_string(&mut env, result)
 }
@@ -3036,7 +5552,7 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getTombstoneCount(
     _class: JClass,
     handle: jlong,
 ) -> jstring {
-    let result: Result<String, String> = (|| {
+    let result: Result<String, FfiError> = (|| {
         let transport = get_transport(handle)?;
         let count = transport.tombstones.lock().len();
         #[derive(serde::Serialize)]
@@ -3044,7 +5560,7 @@ pub extern "C" fn Java_xyz_pollinet_sdk_PolliNetFFI_getTombstoneCount(
             count: usize,
         }
         let response: FfiResult<CountResponse> = FfiResult::success(CountResponse { count });
-        serde_json::to_string(&response).map_err(|e| format!("Serialization error: {}", e))
+        serde_json::to_string(&response).map_err(FfiError::serialization)
     })();
     create_result_string(&mut env, result)
 }
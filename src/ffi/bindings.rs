@@ -0,0 +1,192 @@
+//! UniFFI interface for the host-driven API
+//!
+//! One Rust definition of the host-driven transport API from which Kotlin and
+//! Swift bindings are generated (`cargo run --features uniffi --bin
+//! uniffi-bindgen`), instead of hand-writing JNI and C wrappers for each new
+//! call. Complex results stay JSON, matching the existing `FfiResult` payloads.
+
+use std::sync::Arc;
+
+use super::runtime;
+use super::transport::HostBleTransport;
+use super::types::{MetricsSnapshot, SdkConfig};
+
+/// Error returned across the generated bindings
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi(flat_error)]
+pub enum PolliNetFfiError {
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
+
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl From<String> for PolliNetFfiError {
+    fn from(message: String) -> Self {
+        PolliNetFfiError::Failed(message)
+    }
+}
+
+/// A transaction reassembled from the mesh, awaiting submission
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ReceivedTransaction {
+    pub tx_id: String,
+    pub tx_bytes: Vec<u8>,
+    /// Unix timestamp (seconds) it was received
+    pub received_at: u64,
+}
+
+/// Receives engine events (`ProtocolEvent` JSON) as they happen
+#[uniffi::export(with_foreign)]
+pub trait PolliNetEventListener: Send + Sync {
+    fn on_event(&self, event_json: String);
+}
+
+/// Host-driven PolliNet node (BLE transport engine)
+#[derive(uniffi::Object)]
+pub struct PolliNetNode {
+    transport: Arc<HostBleTransport>,
+}
+
+#[uniffi::export]
+impl PolliNetNode {
+    /// Create a node from an `SdkConfig` JSON document
+    #[uniffi::constructor]
+    pub fn new(config_json: String) -> Result<Arc<Self>, PolliNetFfiError> {
+        let config: SdkConfig = serde_json::from_str(&config_json)
+            .map_err(|e| PolliNetFfiError::InvalidConfig(e.to_string()))?;
+        config
+            .check_compatibility()
+            .map_err(|e| PolliNetFfiError::InvalidConfig(e.to_string()))?;
+
+        match runtime::init_runtime() {
+            Ok(()) => {}
+            Err(e) if e.contains("already initialized") => {}
+            Err(e) => return Err(PolliNetFfiError::Failed(e)),
+        }
+
+        let transport = runtime::block_on(HostBleTransport::from_config(&config))
+            .map_err(|e| PolliNetFfiError::Failed(e.message))?;
+
+        tracing::info!("✅ PolliNet node created via UniFFI bindings");
+        Ok(Arc::new(Self {
+            transport: Arc::new(transport),
+        }))
+    }
+
+    /// Register (or clear) the event listener; it runs on the thread that produced the event
+    pub fn set_event_listener(&self, listener: Option<Arc<dyn PolliNetEventListener>>) {
+        self.transport.set_event_callback(listener.map(|listener| {
+            Arc::new(move |event: &super::types::ProtocolEvent| {
+                if let Ok(json) = serde_json::to_string(event) {
+                    listener.on_event(json);
+                }
+            }) as super::transport::EventCallback
+        }));
+    }
+
+    /// Feed bytes received from a peer
+    pub fn push_inbound(&self, data: Vec<u8>) -> Result<(), PolliNetFfiError> {
+        Ok(self.transport.push_inbound(data)?)
+    }
+
+    /// Next frame to send, at most `max_len` bytes
+    pub fn next_outbound(&self, max_len: u32) -> Option<Vec<u8>> {
+        self.transport.next_outbound(max_len as usize)
+    }
+
+    /// Periodic tick for retries, timeouts and storage cleanup
+    pub fn tick(&self, now_ms: u64) -> Vec<Vec<u8>> {
+        self.transport.tick(now_ms)
+    }
+
+    /// Fragment a signed transaction and queue it for relay; returns the fragment count
+    pub fn queue_transaction(
+        &self,
+        tx_bytes: Vec<u8>,
+        max_payload: Option<u32>,
+    ) -> Result<u32, PolliNetFfiError> {
+        let fragments = self
+            .transport
+            .queue_transaction(tx_bytes, max_payload.map(|m| m as usize))?;
+        Ok(fragments.len() as u32)
+    }
+
+    /// Pop the next reassembled transaction awaiting submission
+    pub fn next_received_transaction(&self) -> Option<ReceivedTransaction> {
+        self.transport
+            .next_received_transaction()
+            .map(|(tx_id, tx_bytes, received_at)| ReceivedTransaction {
+                tx_id,
+                tx_bytes,
+                received_at,
+            })
+    }
+
+    /// Record that a transaction was submitted so it is not relayed again
+    pub fn mark_transaction_submitted(&self, tx_bytes: Vec<u8>) {
+        self.transport.mark_transaction_submitted(&tx_bytes);
+    }
+
+    /// Number of frames waiting to be sent
+    pub fn outbound_queue_size(&self) -> u32 {
+        self.transport.outbound_queue_size() as u32
+    }
+
+    /// Transport metrics
+    pub fn metrics(&self) -> MetricsSnapshot {
+        self.transport.metrics()
+    }
+
+    /// Queue metrics as JSON (`QueueMetricsFFI`)
+    pub fn queue_metrics_json(&self) -> Result<String, PolliNetFfiError> {
+        let metrics = runtime::block_on(self.transport.sdk.get_queue_metrics());
+        serde_json::to_string(&super::types::QueueMetricsFFI::from(metrics))
+            .map_err(|e| PolliNetFfiError::Failed(format!("Serialization error: {}", e)))
+    }
+
+    /// Queue health as JSON (`QueueHealthFFI`)
+    pub fn queue_health_json(&self) -> Result<String, PolliNetFfiError> {
+        let health = runtime::block_on(self.transport.sdk.get_queue_health());
+        serde_json::to_string(&super::types::QueueHealthFFI::from(health))
+            .map_err(|e| PolliNetFfiError::Failed(format!("Serialization error: {}", e)))
+    }
+
+    /// Mesh view as JSON (`MeshStateFFI`)
+    pub fn mesh_state_json(&self) -> Result<String, PolliNetFfiError> {
+        serde_json::to_string(&self.transport.mesh_state())
+            .map_err(|e| PolliNetFfiError::Failed(format!("Serialization error: {}", e)))
+    }
+
+    /// Storage usage as JSON (`StorageUsage`), if a budget is configured
+    pub fn storage_usage_json(&self) -> Result<Option<String>, PolliNetFfiError> {
+        self.transport
+            .storage_usage()
+            .map(|usage| serde_json::to_string(&usage))
+            .transpose()
+            .map_err(|e| PolliNetFfiError::Failed(format!("Serialization error: {}", e)))
+    }
+
+    /// Write an encrypted backup of all persisted state; returns `BackupSummary` JSON
+    pub fn backup_state(
+        &self,
+        path: String,
+        passphrase: String,
+    ) -> Result<String, PolliNetFfiError> {
+        let summary = self.transport.backup_state(&path, &passphrase)?;
+        serde_json::to_string(&summary)
+            .map_err(|e| PolliNetFfiError::Failed(format!("Serialization error: {}", e)))
+    }
+
+    /// Replace persisted state with a backup; returns `BackupSummary` JSON
+    pub fn restore_state(
+        &self,
+        path: String,
+        passphrase: String,
+    ) -> Result<String, PolliNetFfiError> {
+        let summary = self.transport.restore_state(&path, &passphrase)?;
+        serde_json::to_string(&summary)
+            .map_err(|e| PolliNetFfiError::Failed(format!("Serialization error: {}", e)))
+    }
+}
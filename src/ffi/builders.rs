@@ -0,0 +1,230 @@
+//! Stateless transaction and intent builders shared by every binding layer
+//!
+//! The JNI and C APIs only marshal requests and responses; the building itself
+//! lives here so both produce identical transactions.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+use super::types::{
+    ApproveTransactionResponse, CreateApproveTransactionRequest, CreateCustomTransactionRequest,
+    CreateIntentBytesRequest, CreateRevokeTransactionRequest, CreateSolanaPayTransactionRequest,
+    CustomTransactionResponse, ExecutorPdaResponse, FfiError, IntentBytesResponse,
+    PaymentTransactionResponse, RevokeTransactionResponse, SolanaPayTransactionResponse,
+};
+
+fn parse_pubkey(value: &str, field: &str) -> Result<Pubkey, String> {
+    Pubkey::from_str(value).map_err(|e| format!("Invalid {}: {}", field, e))
+}
+
+fn parse_blockhash(value: &str) -> Result<Hash, String> {
+    let bytes = bs58::decode(value)
+        .into_vec()
+        .map_err(|e| format!("Invalid recent_blockhash: {}", e))?;
+    let array: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| "recent_blockhash must decode to 32 bytes".to_string())?;
+    Ok(Hash::new_from_array(array))
+}
+
+/// Executor PDA of the pollinet-executor program
+pub fn executor_pda() -> ExecutorPdaResponse {
+    let (pda, bump) = crate::intent::executor_pda();
+    ExecutorPdaResponse {
+        pda: pda.to_string(),
+        bump,
+    }
+}
+
+/// Unsigned batch `approve_checked` transaction delegating to the executor PDA
+pub fn approve_transaction(
+    req: CreateApproveTransactionRequest,
+) -> Result<ApproveTransactionResponse, String> {
+    let owner = parse_pubkey(&req.owner_wallet, "owner_wallet")?;
+    let fee_payer = parse_pubkey(&req.fee_payer, "fee_payer")?;
+    let recent_blockhash = parse_blockhash(&req.recent_blockhash)?;
+
+    let approvals: Vec<crate::intent::TokenApprovalInput> = req
+        .tokens
+        .into_iter()
+        .map(|t| crate::intent::TokenApprovalInput {
+            mint_address: t.mint_address,
+            amount: t.amount,
+            decimals: t.decimals,
+            token_account: t.token_account,
+            token_program: t.token_program,
+        })
+        .collect();
+
+    let (executor_pda, _) = crate::intent::executor_pda();
+    let transaction =
+        crate::intent::build_approve_transaction(&owner, &fee_payer, recent_blockhash, &approvals)?;
+
+    Ok(ApproveTransactionResponse {
+        transaction,
+        executor_pda: executor_pda.to_string(),
+    })
+}
+
+/// Unsigned batch `revoke` transaction clearing the executor PDA's delegation
+pub fn revoke_transaction(
+    req: &CreateRevokeTransactionRequest,
+) -> Result<RevokeTransactionResponse, String> {
+    let owner = parse_pubkey(&req.owner_wallet, "owner_wallet")?;
+    let fee_payer = parse_pubkey(&req.fee_payer, "fee_payer")?;
+    let recent_blockhash = parse_blockhash(&req.recent_blockhash)?;
+
+    let transaction = crate::intent::build_revoke_transaction(
+        &owner,
+        &fee_payer,
+        recent_blockhash,
+        &req.token_accounts,
+        &req.token_program,
+    )?;
+    Ok(RevokeTransactionResponse { transaction })
+}
+
+/// Unsigned transaction from caller-supplied program instructions
+pub fn custom_transaction(
+    req: &CreateCustomTransactionRequest,
+) -> Result<CustomTransactionResponse, String> {
+    let fee_payer = parse_pubkey(&req.fee_payer, "fee_payer")?;
+    let recent_blockhash = parse_blockhash(&req.recent_blockhash)?;
+
+    let (transaction, signers) =
+        crate::intent::build_custom_transaction(&fee_payer, recent_blockhash, &req.instructions)?;
+    Ok(CustomTransactionResponse {
+        transaction,
+        required_signers: signers.iter().map(|s| s.to_string()).collect(),
+    })
+}
+
+/// Unsigned durable-nonce transaction paying `request` from `payer`; a
+/// `nonce` older than `staleness` allows fails with `ERR_STALE_NONCE`
+pub fn payment_transaction(
+    request: &crate::ble::PaymentRequest,
+    payer: &str,
+    nonce: &super::nonce_accounts::NonceAccountInfo,
+    staleness: &super::nonce_accounts::NonceStalenessPolicy,
+) -> Result<PaymentTransactionResponse, FfiError> {
+    staleness.check(nonce)?;
+    build_payment_transaction(request, payer, nonce).map_err(FfiError::invalid_input)
+}
+
+fn build_payment_transaction(
+    request: &crate::ble::PaymentRequest,
+    payer: &str,
+    nonce: &super::nonce_accounts::NonceAccountInfo,
+) -> Result<PaymentTransactionResponse, String> {
+    let input = crate::intent::NonceTransferInput {
+        payer: parse_pubkey(payer, "payer")?,
+        recipient: Pubkey::new_from_array(request.recipient),
+        amount: request.amount,
+        mint: request.mint.map(Pubkey::new_from_array),
+        decimals: request.decimals,
+        memo: request.memo.clone(),
+        references: Vec::new(),
+        nonce_account: parse_pubkey(&nonce.address, "nonce account")?,
+        nonce_authority: parse_pubkey(&nonce.authority, "nonce authority")?,
+        nonce: parse_blockhash(&nonce.nonce)?,
+    };
+    Ok(PaymentTransactionResponse {
+        request_id: hex::encode(request.id),
+        transaction: crate::intent::build_nonce_transfer_transaction(&input)?,
+    })
+}
+
+/// Unsigned durable-nonce transaction paying a Solana Pay transfer request
+/// URL, so a QR code scanned offline produces a mesh-relayable payment; a
+/// nonce older than `req.staleness` allows fails with `ERR_STALE_NONCE`
+pub fn solana_pay_transaction(
+    req: &CreateSolanaPayTransactionRequest,
+) -> Result<SolanaPayTransactionResponse, FfiError> {
+    req.staleness.check(&req.nonce_account)?;
+    build_solana_pay_transaction(req).map_err(FfiError::invalid_input)
+}
+
+fn build_solana_pay_transaction(
+    req: &CreateSolanaPayTransactionRequest,
+) -> Result<SolanaPayTransactionResponse, String> {
+    use crate::intent::solana_pay::{TransferRequest, SOL_DECIMALS};
+
+    let request = TransferRequest::parse(&req.url)?;
+    let decimals = match (&request.spl_token, req.decimals) {
+        (None, _) => SOL_DECIMALS,
+        (Some(_), Some(decimals)) => decimals,
+        (Some(mint), None) => {
+            return Err(format!("decimals are required to pay in token {}", mint));
+        }
+    };
+    let amount = match (request.amount_in_base_units(decimals)?, req.amount) {
+        (Some(amount), _) => amount,
+        (None, Some(amount)) => amount,
+        (None, None) => return Err("The URL has no amount; pass one".to_string()),
+    };
+    if amount == 0 {
+        return Err("Payment amount must be greater than zero".to_string());
+    }
+    let input = crate::intent::NonceTransferInput {
+        payer: parse_pubkey(&req.payer, "payer")?,
+        recipient: parse_pubkey(&request.recipient, "recipient")?,
+        amount,
+        mint: request
+            .spl_token
+            .as_deref()
+            .map(|mint| parse_pubkey(mint, "spl-token"))
+            .transpose()?,
+        decimals,
+        memo: request.memo.clone(),
+        references: request
+            .references
+            .iter()
+            .map(|reference| parse_pubkey(reference, "reference"))
+            .collect::<Result<_, _>>()?,
+        nonce_account: parse_pubkey(&req.nonce_account.address, "nonce account")?,
+        nonce_authority: parse_pubkey(&req.nonce_account.authority, "nonce authority")?,
+        nonce: parse_blockhash(&req.nonce_account.nonce)?,
+    };
+    Ok(SolanaPayTransactionResponse {
+        transaction: crate::intent::build_nonce_transfer_transaction(&input)?,
+        amount,
+        request,
+    })
+}
+
+/// Canonical 169-byte intent (random nonce unless `nonce_hex` is given)
+pub fn intent_bytes(req: &CreateIntentBytesRequest) -> Result<IntentBytesResponse, String> {
+    let from = parse_pubkey(&req.from, "from")?.to_bytes();
+    let to = parse_pubkey(&req.to, "to")?.to_bytes();
+    let token_mint = parse_pubkey(&req.token_mint, "token_mint")?.to_bytes();
+    let gas_fee_payee = parse_pubkey(&req.gas_fee_payee, "gas_fee_payee")?.to_bytes();
+
+    let nonce: [u8; 16] = match &req.nonce_hex {
+        Some(hex_str) => {
+            let decoded = hex::decode(hex_str).map_err(|e| format!("Invalid nonce_hex: {}", e))?;
+            decoded.try_into().map_err(|_| {
+                "nonce_hex must decode to exactly 16 bytes (32 hex chars)".to_string()
+            })?
+        }
+        None => crate::intent::random_nonce(),
+    };
+
+    let intent = crate::intent::serialize_intent(
+        1,
+        &from,
+        &to,
+        &token_mint,
+        req.amount,
+        &nonce,
+        req.expires_at,
+        req.gas_fee_amount,
+        &gas_fee_payee,
+    );
+
+    Ok(IntentBytesResponse {
+        intent_bytes: STANDARD.encode(intent),
+        nonce_hex: hex::encode(nonce),
+    })
+}
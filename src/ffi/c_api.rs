@@ -0,0 +1,791 @@
+//! Platform-neutral C API (`pollinet_core`)
+//!
+//! Built with the `c-api` feature for desktop embedders (Electron, Qt, …) on
+//! Linux/macOS/Windows. `build.rs` regenerates `include/pollinet.h` with
+//! cbindgen. Conventions:
+//! - Nodes are referred to by an opaque `int64_t` handle; negative means error.
+//!   Handles are generational: once shut down, a handle keeps failing with
+//!   `POLLINET_ERR_SHUT_DOWN` even after its slot is reused.
+//! - JSON results use the same `FfiResult` envelope as the mobile FFI and must
+//!   be released with `pollinet_string_free`. Failures carry an `ERR_*` code
+//!   (see `FfiErrorCode`).
+//! - Byte-returning calls copy into a caller buffer and return the length.
+
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::sync::Arc;
+
+use super::handles::{HandleError, ShardedHandleMap};
+use super::runtime;
+use super::transport::HostBleTransport;
+use super::types::{
+    fallback_envelope, result_envelope, CreateApproveTransactionRequest,
+    CreateCustomTransactionRequest, CreateIntentBytesRequest, CreateRevokeTransactionRequest,
+    CreateSolanaPayTransactionRequest, FfiError, FfiErrorCode, FfiResult, ProtocolInfo,
+    QueueHealthFFI, QueueMetricsFFI, SdkConfig,
+};
+
+/// Failure (invalid input, engine error)
+pub const POLLINET_ERR: i32 = -1;
+/// The handle was never issued
+pub const POLLINET_ERR_INVALID_HANDLE: i32 = -2;
+/// The handle was shut down
+pub const POLLINET_ERR_SHUT_DOWN: i32 = -3;
+/// `pollinet_init`: the config requires a newer SDK or wire protocol
+pub const POLLINET_ERR_VERSION_MISMATCH: i32 = -4;
+
+static NODES: ShardedHandleMap<Arc<HostBleTransport>> = ShardedHandleMap::new();
+
+fn get_node(handle: i64) -> Result<Arc<HostBleTransport>, HandleError> {
+    NODES.with(handle, Arc::clone)
+}
+
+fn handle_error_code(error: HandleError) -> i32 {
+    tracing::warn!("⚠️ C API: {}", error);
+    match error {
+        HandleError::Invalid(_) => POLLINET_ERR_INVALID_HANDLE,
+        HandleError::ShutDown(_) => POLLINET_ERR_SHUT_DOWN,
+    }
+}
+
+/// Borrow a C string as UTF-8
+///
+/// # Safety
+/// `ptr` must be null or a valid NUL-terminated string.
+unsafe fn read_str<'a>(ptr: *const c_char, field: &str) -> Result<&'a str, FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::invalid_input(format!("{} is null", field)));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|e| FfiError::invalid_input(format!("{} is not valid UTF-8: {}", field, e)))
+}
+
+fn into_c_string(json: String) -> *mut c_char {
+    // JSON never contains interior NULs; fall back to a fixed error just in case
+    CString::new(json)
+        .unwrap_or_else(|_| CString::new(fallback_envelope("Invalid string")).unwrap())
+        .into_raw()
+}
+
+fn result_string(result: Result<String, FfiError>) -> *mut c_char {
+    match result {
+        Ok(json) => into_c_string(json),
+        Err(e) => {
+            tracing::error!("❌ C API error: {}", e);
+            into_c_string(result_envelope(Err(e)))
+        }
+    }
+}
+
+fn success_string<T: serde::Serialize>(data: T) -> Result<String, FfiError> {
+    serde_json::to_string(&FfiResult::success(data)).map_err(FfiError::serialization)
+}
+
+/// SDK version (static string, do not free)
+#[no_mangle]
+pub extern "C" fn pollinet_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+/// SDK version, wire protocol version and compiled features as JSON `FfiResult`
+///
+/// Safe to call before `pollinet_init`.
+#[no_mangle]
+pub extern "C" fn pollinet_protocol_info() -> *mut c_char {
+    result_string(success_string(ProtocolInfo::current()))
+}
+
+/// Create a node from an `SdkConfig` JSON document; returns its handle or -1
+///
+/// Returns `POLLINET_ERR_VERSION_MISMATCH` when the config's `minSdkVersion`
+/// or `minProtocolVersion` is newer than this build.
+///
+/// # Safety
+/// `config_json` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_init(config_json: *const c_char) -> i64 {
+    init_node(read_str(config_json, "config_json").and_then(|json| {
+        serde_json::from_str(json)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid config: {}", e)))
+    }))
+}
+
+/// Create a node from an `SdkConfig` file (`.toml` or `.json`); returns its
+/// handle or -1, like `pollinet_init`
+///
+/// # Safety
+/// `path` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_init_from_file(path: *const c_char) -> i64 {
+    init_node(read_str(path, "path").and_then(SdkConfig::from_file))
+}
+
+fn init_node(config: Result<SdkConfig, FfiError>) -> i64 {
+    let result = (|| -> Result<HostBleTransport, FfiError> {
+        let config = config?;
+        config.check_compatibility()?;
+
+        match runtime::init_runtime() {
+            Ok(()) => {}
+            Err(e) if e.contains("already initialized") => {}
+            Err(e) => return Err(FfiError::internal(e)),
+        }
+
+        runtime::block_on(HostBleTransport::from_config(&config))
+    })();
+
+    match result {
+        Ok(transport) => {
+            let handle = NODES.insert(Arc::new(transport));
+            tracing::info!("✅ PolliNet node {} created via C API", handle);
+            handle
+        }
+        Err(e) => {
+            tracing::error!("❌ pollinet_init failed: {}", e);
+            match e.code {
+                FfiErrorCode::VersionMismatch => POLLINET_ERR_VERSION_MISMATCH as i64,
+                _ => POLLINET_ERR as i64,
+            }
+        }
+    }
+}
+
+/// Flush and release a node; returns 0 or a negative handle error
+///
+/// Later calls with the handle fail with `POLLINET_ERR_SHUT_DOWN`.
+#[no_mangle]
+pub extern "C" fn pollinet_shutdown(handle: i64) -> i32 {
+    let node = match NODES.remove(handle) {
+        Ok(node) => node,
+        Err(e) => return handle_error_code(e),
+    };
+    runtime::block_on(node.shutdown());
+    tracing::info!("🛑 PolliNet node {} shut down", handle);
+    0
+}
+
+/// Feed `len` bytes received from a peer; returns 0 or a negative error
+///
+/// # Safety
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_push_inbound(handle: i64, data: *const u8, len: usize) -> i32 {
+    let node = match get_node(handle) {
+        Ok(node) => node,
+        Err(e) => return handle_error_code(e),
+    };
+    if data.is_null() {
+        return POLLINET_ERR;
+    }
+    let bytes = std::slice::from_raw_parts(data, len).to_vec();
+    match node.push_inbound(bytes) {
+        Ok(()) => 0,
+        Err(e) => {
+            tracing::warn!("⚠️ pollinet_push_inbound: {}", e);
+            POLLINET_ERR
+        }
+    }
+}
+
+/// Like `pollinet_push_inbound`, recording `peer_id` as the source of the
+/// transaction in the transaction history
+///
+/// # Safety
+/// `peer_id` must be a valid NUL-terminated string and `data` must point to
+/// `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_push_inbound_from(
+    handle: i64,
+    peer_id: *const c_char,
+    data: *const u8,
+    len: usize,
+) -> i32 {
+    let node = match get_node(handle) {
+        Ok(node) => node,
+        Err(e) => return handle_error_code(e),
+    };
+    let Ok(peer_id) = read_str(peer_id, "peer_id") else {
+        return POLLINET_ERR;
+    };
+    if data.is_null() {
+        return POLLINET_ERR;
+    }
+    let bytes = std::slice::from_raw_parts(data, len).to_vec();
+    match node.push_inbound_from(peer_id, bytes) {
+        Ok(()) => 0,
+        Err(e) => {
+            tracing::warn!("⚠️ pollinet_push_inbound_from: {}", e);
+            POLLINET_ERR
+        }
+    }
+}
+
+/// Copy the next outbound frame that fits in `cap` bytes into `buf`
+///
+/// Returns the frame length, 0 when nothing fits, or a negative error.
+///
+/// # Safety
+/// `buf` must point to `cap` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_next_outbound(handle: i64, buf: *mut u8, cap: usize) -> isize {
+    let node = match get_node(handle) {
+        Ok(node) => node,
+        Err(e) => return handle_error_code(e) as isize,
+    };
+    if buf.is_null() {
+        return POLLINET_ERR as isize;
+    }
+    match node.next_outbound(cap) {
+        Some(frame) => {
+            std::ptr::copy_nonoverlapping(frame.as_ptr(), buf, frame.len());
+            frame.len() as isize
+        }
+        None => 0,
+    }
+}
+
+/// Periodic tick; returns JSON `FfiResult` with the base64 frames to send
+#[no_mangle]
+pub extern "C" fn pollinet_tick(handle: i64, now_ms: u64) -> *mut c_char {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    result_string((|| {
+        let node = get_node(handle).map_err(FfiError::from)?;
+        let frames: Vec<String> = node
+            .tick(now_ms)
+            .iter()
+            .map(|frame| STANDARD.encode(frame))
+            .collect();
+        success_string(frames)
+    })())
+}
+
+/// Fragment a signed transaction and queue it for relay; returns the fragment count or a negative error
+///
+/// # Safety
+/// `tx_bytes` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_queue_transaction(
+    handle: i64,
+    tx_bytes: *const u8,
+    len: usize,
+) -> i32 {
+    let node = match get_node(handle) {
+        Ok(node) => node,
+        Err(e) => return handle_error_code(e),
+    };
+    if tx_bytes.is_null() {
+        return POLLINET_ERR;
+    }
+    let bytes = std::slice::from_raw_parts(tx_bytes, len).to_vec();
+    match node.queue_transaction(bytes, None) {
+        Ok(fragments) => fragments.len() as i32,
+        Err(e) => {
+            tracing::warn!("⚠️ pollinet_queue_transaction: {}", e);
+            POLLINET_ERR
+        }
+    }
+}
+
+/// Transport metrics as JSON `FfiResult`
+#[no_mangle]
+pub extern "C" fn pollinet_metrics(handle: i64) -> *mut c_char {
+    result_string((|| {
+        let node = get_node(handle).map_err(FfiError::from)?;
+        success_string(node.metrics())
+    })())
+}
+
+/// Event callback: receives a `ProtocolEvent` JSON document (valid only for the
+/// duration of the call) and the `user_data` given at registration
+pub type PollinetEventCallback =
+    Option<unsafe extern "C" fn(event_json: *const c_char, user_data: *mut c_void)>;
+
+/// Host-owned `user_data` pointer handed back to the callback
+struct UserData(*mut c_void);
+// SAFETY: the embedder guarantees `user_data` may be used from any thread
+// (documented on `pollinet_set_event_callback`).
+unsafe impl Send for UserData {}
+unsafe impl Sync for UserData {}
+
+impl UserData {
+    /// Going through a method makes closures capture the whole wrapper rather
+    /// than the bare pointer field
+    fn get(&self) -> *mut c_void {
+        self.0
+    }
+}
+
+/// Register (or, with a null callback, clear) the event callback; returns 0 or a negative error
+///
+/// The callback runs synchronously on the thread that produced the event
+/// (e.g. the caller of `pollinet_push_inbound`) and must not block.
+/// `user_data` must remain valid, and usable from any thread, until the
+/// callback is replaced or the node is shut down.
+#[no_mangle]
+pub extern "C" fn pollinet_set_event_callback(
+    handle: i64,
+    callback: PollinetEventCallback,
+    user_data: *mut c_void,
+) -> i32 {
+    let node = match get_node(handle) {
+        Ok(node) => node,
+        Err(e) => return handle_error_code(e),
+    };
+    let callback = callback.map(|callback| {
+        let user_data = UserData(user_data);
+        Arc::new(move |event: &super::types::ProtocolEvent| {
+            let Ok(json) = serde_json::to_string(event) else {
+                return;
+            };
+            let Ok(json) = CString::new(json) else {
+                return;
+            };
+            // SAFETY: contract of `pollinet_set_event_callback`
+            unsafe { callback(json.as_ptr(), user_data.get()) };
+        }) as super::transport::EventCallback
+    });
+    node.set_event_callback(callback);
+    0
+}
+
+/// Owned-string event callback: `event_json` belongs to the callee, which must
+/// release it with `pollinet_string_free`
+pub type PollinetOwnedEventCallback =
+    Option<unsafe extern "C" fn(event_json: *mut c_char, user_data: *mut c_void)>;
+
+/// Like `pollinet_set_event_callback`, but the event string outlives the call
+///
+/// For hosts that hand events to another thread or event loop before reading
+/// them (Dart `NativeCallable.listener`, React Native's JS thread).
+#[no_mangle]
+pub extern "C" fn pollinet_set_owned_event_callback(
+    handle: i64,
+    callback: PollinetOwnedEventCallback,
+    user_data: *mut c_void,
+) -> i32 {
+    let node = match get_node(handle) {
+        Ok(node) => node,
+        Err(e) => return handle_error_code(e),
+    };
+    let callback = callback.map(|callback| {
+        let user_data = UserData(user_data);
+        Arc::new(move |event: &super::types::ProtocolEvent| {
+            let Ok(json) = serde_json::to_string(event) else {
+                return;
+            };
+            // SAFETY: contract of `pollinet_set_owned_event_callback`
+            unsafe { callback(into_c_string(json), user_data.get()) };
+        }) as super::transport::EventCallback
+    });
+    node.set_event_callback(callback);
+    0
+}
+
+/// Queue metrics as JSON `FfiResult<QueueMetricsFFI>`
+#[no_mangle]
+pub extern "C" fn pollinet_queue_metrics(handle: i64) -> *mut c_char {
+    result_string((|| {
+        let node = get_node(handle).map_err(FfiError::from)?;
+        let metrics = runtime::block_on(node.sdk.get_queue_metrics());
+        success_string(QueueMetricsFFI::from(metrics))
+    })())
+}
+
+/// Compression totals as JSON `FfiResult<CompressionStats>`
+#[no_mangle]
+pub extern "C" fn pollinet_compression_stats(handle: i64) -> *mut c_char {
+    result_string((|| {
+        let node = get_node(handle).map_err(FfiError::from)?;
+        success_string(node.sdk.get_compression_stats())
+    })())
+}
+
+/// Queue health as JSON `FfiResult<QueueHealthFFI>` (`status`: HEALTHY | WARNING | CRITICAL)
+#[no_mangle]
+pub extern "C" fn pollinet_health(handle: i64) -> *mut c_char {
+    result_string((|| {
+        let node = get_node(handle).map_err(FfiError::from)?;
+        let health = runtime::block_on(node.sdk.get_queue_health());
+        success_string(QueueHealthFFI::from(health))
+    })())
+}
+
+/// Mesh view (discovered/connected peers, per-peer stats, topology) as JSON `FfiResult`
+#[no_mangle]
+pub extern "C" fn pollinet_mesh_state(handle: i64) -> *mut c_char {
+    result_string((|| {
+        let node = get_node(handle).map_err(FfiError::from)?;
+        success_string(node.mesh_state())
+    })())
+}
+
+/// Queue a text message for `peer_id`; returns JSON `FfiResult` with its id and status
+///
+/// # Safety
+/// `peer_id` and `body` must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_send_text_message(
+    handle: i64,
+    peer_id: *const c_char,
+    body: *const c_char,
+) -> *mut c_char {
+    result_string((|| {
+        let node = get_node(handle).map_err(FfiError::from)?;
+        let peer_id = read_str(peer_id, "peer_id")?;
+        let body = read_str(body, "body")?;
+        success_string(node.send_text_message(peer_id, body)?)
+    })())
+}
+
+/// Feed a text frame received from `peer_id`; returns 0 or a negative error
+///
+/// # Safety
+/// `peer_id` must be a valid NUL-terminated string and `data` must point to
+/// `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_ingest_text_frame(
+    handle: i64,
+    peer_id: *const c_char,
+    data: *const u8,
+    len: usize,
+) -> i32 {
+    let node = match get_node(handle) {
+        Ok(node) => node,
+        Err(e) => return handle_error_code(e),
+    };
+    if data.is_null() {
+        return POLLINET_ERR;
+    }
+    let peer_id = match read_str(peer_id, "peer_id") {
+        Ok(peer_id) => peer_id,
+        Err(_) => return POLLINET_ERR,
+    };
+    let bytes = std::slice::from_raw_parts(data, len);
+    match node.ingest_text_frame(peer_id, bytes) {
+        Ok(_) => 0,
+        Err(e) => {
+            tracing::warn!("⚠️ pollinet_ingest_text_frame: {}", e);
+            POLLINET_ERR
+        }
+    }
+}
+
+/// Copy the next text or ack frame that fits in `cap` bytes into `buf`
+///
+/// Returns the frame length, 0 when nothing fits, or a negative error.
+///
+/// # Safety
+/// `buf` must point to `cap` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_next_text_frame(handle: i64, buf: *mut u8, cap: usize) -> isize {
+    let node = match get_node(handle) {
+        Ok(node) => node,
+        Err(e) => return handle_error_code(e) as isize,
+    };
+    if buf.is_null() {
+        return POLLINET_ERR as isize;
+    }
+    match node.next_text_frame(cap) {
+        Some(frame) => {
+            std::ptr::copy_nonoverlapping(frame.as_ptr(), buf, frame.len());
+            frame.len() as isize
+        }
+        None => 0,
+    }
+}
+
+/// Oldest received text message as JSON `FfiResult` (`data` is null when none is pending)
+#[no_mangle]
+pub extern "C" fn pollinet_next_text_message(handle: i64) -> *mut c_char {
+    result_string((|| {
+        let node = get_node(handle).map_err(FfiError::from)?;
+        success_string(node.next_text_message())
+    })())
+}
+
+/// Delivery status of a sent text message as JSON `FfiResult`
+///
+/// # Safety
+/// `message_id` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_text_message_status(
+    handle: i64,
+    message_id: *const c_char,
+) -> *mut c_char {
+    result_string((|| {
+        let node = get_node(handle).map_err(FfiError::from)?;
+        let message_id = read_str(message_id, "message_id")?;
+        let message = node
+            .text_message_status(message_id)
+            .ok_or_else(|| FfiError::not_found(format!("Unknown text message: {}", message_id)))?;
+        success_string(message)
+    })())
+}
+
+/// Watch a transaction by mesh id (hex) or base58 signature as JSON `FfiResult`
+///
+/// Status changes are delivered to the event callback as `TxStatus` events.
+///
+/// # Safety
+/// `id` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_watch_transaction(handle: i64, id: *const c_char) -> *mut c_char {
+    result_string((|| {
+        let node = get_node(handle).map_err(FfiError::from)?;
+        success_string(node.watch_transaction(read_str(id, "id")?, None)?)
+    })())
+}
+
+/// Stop watching; returns 1 if the watch was active, 0 if not, or a negative handle error
+#[no_mangle]
+pub extern "C" fn pollinet_unwatch_transaction(handle: i64, watch_id: u64) -> i32 {
+    match get_node(handle) {
+        Ok(node) => node.unwatch_transaction(watch_id) as i32,
+        Err(e) => handle_error_code(e),
+    }
+}
+
+/// Add or replace an address book contact as JSON `FfiResult`
+///
+/// `contact_json` is `{label, pubkey, mints?}`; labels ignore case.
+///
+/// # Safety
+/// `contact_json` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_save_contact(
+    handle: i64,
+    contact_json: *const c_char,
+) -> *mut c_char {
+    result_string((|| {
+        let node = get_node(handle).map_err(FfiError::from)?;
+        let contact: crate::storage::Contact =
+            serde_json::from_str(read_str(contact_json, "contact_json")?)
+                .map_err(|e| FfiError::invalid_input(format!("Invalid contact: {}", e)))?;
+        let book = address_book(&node)?;
+        let saved = book.save(contact).map_err(|e| match e {
+            crate::storage::StorageError::InvalidData(_) => FfiError::invalid_input(e.to_string()),
+            _ => FfiError::new(FfiErrorCode::Storage, e.to_string()),
+        })?;
+        success_string(saved)
+    })())
+}
+
+/// Remove an address book contact by label; returns `{removed}` as JSON `FfiResult`
+///
+/// # Safety
+/// `label` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_remove_contact(handle: i64, label: *const c_char) -> *mut c_char {
+    #[derive(serde::Serialize)]
+    struct Removed {
+        removed: bool,
+    }
+
+    result_string((|| {
+        let node = get_node(handle).map_err(FfiError::from)?;
+        let removed = address_book(&node)?
+            .remove(read_str(label, "label")?)
+            .map_err(|e| FfiError::new(FfiErrorCode::Storage, e.to_string()))?;
+        success_string(Removed { removed })
+    })())
+}
+
+/// Address book contacts sorted by label as JSON `FfiResult`
+#[no_mangle]
+pub extern "C" fn pollinet_contacts(handle: i64) -> *mut c_char {
+    result_string((|| {
+        let node = get_node(handle).map_err(FfiError::from)?;
+        success_string(address_book(&node)?.list())
+    })())
+}
+
+fn address_book(node: &HostBleTransport) -> Result<Arc<crate::storage::AddressBook>, FfiError> {
+    node.address_book()
+        .ok_or_else(|| FfiError::not_configured("The address book requires a storage directory"))
+}
+
+/// Page of the received-transaction history (newest first) as JSON `FfiResult`
+///
+/// `query_json` is a `TxHistoryQuery` document; null or empty uses the defaults.
+///
+/// # Safety
+/// `query_json` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_transaction_history(
+    handle: i64,
+    query_json: *const c_char,
+) -> *mut c_char {
+    result_string((|| {
+        let node = get_node(handle).map_err(FfiError::from)?;
+        let query: crate::storage::TxHistoryQuery = if query_json.is_null() {
+            Default::default()
+        } else {
+            match read_str(query_json, "query_json")?.trim() {
+                "" => Default::default(),
+                json => serde_json::from_str(json).map_err(|e| {
+                    FfiError::invalid_input(format!("Invalid history query: {}", e))
+                })?,
+            }
+        };
+        let history = node.tx_history().ok_or_else(|| {
+            FfiError::not_configured("Transaction history requires a storage directory")
+        })?;
+        success_string(history.query(&query))
+    })())
+}
+
+/// Executor PDA as JSON `FfiResult`
+#[no_mangle]
+pub extern "C" fn pollinet_get_executor_pda() -> *mut c_char {
+    result_string(success_string(super::builders::executor_pda()))
+}
+
+/// Build an approve transaction from a `CreateApproveTransactionRequest` JSON document
+///
+/// # Safety
+/// `request_json` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_create_approve_transaction(
+    request_json: *const c_char,
+) -> *mut c_char {
+    result_string((|| {
+        let req: CreateApproveTransactionRequest =
+            serde_json::from_str(read_str(request_json, "request_json")?)
+                .map_err(|e| FfiError::invalid_input(format!("Invalid request: {}", e)))?;
+        success_string(super::builders::approve_transaction(req)?)
+    })())
+}
+
+/// Build a revoke transaction from a `CreateRevokeTransactionRequest` JSON document
+///
+/// # Safety
+/// `request_json` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_create_revoke_transaction(
+    request_json: *const c_char,
+) -> *mut c_char {
+    result_string((|| {
+        let req: CreateRevokeTransactionRequest =
+            serde_json::from_str(read_str(request_json, "request_json")?)
+                .map_err(|e| FfiError::invalid_input(format!("Invalid request: {}", e)))?;
+        success_string(super::builders::revoke_transaction(&req)?)
+    })())
+}
+
+/// Build a transaction from a `CreateCustomTransactionRequest` JSON document
+///
+/// # Safety
+/// `request_json` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_create_custom_transaction(
+    request_json: *const c_char,
+) -> *mut c_char {
+    result_string((|| {
+        let req: CreateCustomTransactionRequest =
+            serde_json::from_str(read_str(request_json, "request_json")?)
+                .map_err(|e| FfiError::invalid_input(format!("Invalid request: {}", e)))?;
+        success_string(super::builders::custom_transaction(&req)?)
+    })())
+}
+
+/// Parse a Solana Pay transfer request URL into its `TransferRequest` fields
+///
+/// # Safety
+/// `url` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_parse_solana_pay_url(url: *const c_char) -> *mut c_char {
+    result_string((|| {
+        let request = crate::intent::solana_pay::TransferRequest::parse(read_str(url, "url")?)
+            .map_err(FfiError::invalid_input)?;
+        success_string(request)
+    })())
+}
+
+/// Build a durable-nonce payment from a `CreateSolanaPayTransactionRequest`
+/// JSON document
+///
+/// # Safety
+/// `request_json` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_create_solana_pay_transaction(
+    request_json: *const c_char,
+) -> *mut c_char {
+    result_string((|| {
+        let req: CreateSolanaPayTransactionRequest =
+            serde_json::from_str(read_str(request_json, "request_json")?)
+                .map_err(|e| FfiError::invalid_input(format!("Invalid request: {}", e)))?;
+        success_string(super::builders::solana_pay_transaction(&req)?)
+    })())
+}
+
+/// Build canonical intent bytes from a `CreateIntentBytesRequest` JSON document
+///
+/// # Safety
+/// `request_json` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_create_intent_bytes(request_json: *const c_char) -> *mut c_char {
+    result_string((|| {
+        let req: CreateIntentBytesRequest =
+            serde_json::from_str(read_str(request_json, "request_json")?)
+                .map_err(|e| FfiError::invalid_input(format!("Invalid request: {}", e)))?;
+        success_string(super::builders::intent_bytes(&req)?)
+    })())
+}
+
+/// Free a string returned by this API
+///
+/// # Safety
+/// `ptr` must be null or a pointer returned by a `pollinet_*` call, freed once.
+#[no_mangle]
+pub unsafe extern "C" fn pollinet_string_free(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        drop(CString::from_raw(ptr));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_executor_pda_roundtrip() {
+        let ptr = pollinet_get_executor_pda();
+        let json = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string();
+        unsafe { pollinet_string_free(ptr) };
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["ok"], true);
+        assert!(value["data"]["pda"].is_string());
+    }
+
+    #[test]
+    fn test_invalid_handle() {
+        assert_eq!(
+            unsafe { pollinet_push_inbound(999, [1u8].as_ptr(), 1) },
+            POLLINET_ERR_INVALID_HANDLE
+        );
+        let ptr = pollinet_metrics(-5);
+        let json = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string();
+        unsafe { pollinet_string_free(ptr) };
+        assert!(json.contains("\"ok\":false"));
+        assert!(json.contains("\"code\":\"ERR_INVALID_HANDLE\""));
+    }
+
+    #[test]
+    fn test_protocol_info_and_min_version() {
+        let ptr = pollinet_protocol_info();
+        let json = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string();
+        unsafe { pollinet_string_free(ptr) };
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["data"]["sdkVersion"], env!("CARGO_PKG_VERSION"));
+        assert!(value["data"]["features"]
+            .as_array()
+            .unwrap()
+            .contains(&"c-api".into()));
+
+        let config = CString::new(r#"{"minSdkVersion":"999.0.0"}"#).unwrap();
+        assert_eq!(
+            unsafe { pollinet_init(config.as_ptr()) },
+            POLLINET_ERR_VERSION_MISMATCH as i64
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! Internet connectivity detection
+//!
+//! [`ConnectivityMonitor`] tracks whether this device can reach the network.
+//! The state comes either from the host (OS connectivity callbacks reported
+//! through `report`) or from a periodic probe: Solana RPC `getHealth`, or an
+//! HTTP request expecting an exact status (e.g. 204 from a `generate_204`
+//! endpoint) so a captive portal answering with its login page does not count
+//! as online. Changes are published as [`crate::SdkEvent::ConnectivityChanged`]
+//! and on a watch channel that the gateway service follows.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Default pause between probes
+pub const DEFAULT_PROBE_INTERVAL_SECS: u64 = 15;
+/// Default probe timeout
+pub const DEFAULT_PROBE_TIMEOUT_SECS: u64 = 5;
+
+/// How connectivity is determined
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ConnectivityProbe {
+    /// The host reports OS connectivity changes; nothing is probed
+    Os,
+    /// Solana RPC `getHealth` succeeds
+    #[serde(rename_all = "camelCase")]
+    RpcHealth { url: String },
+    /// HTTP GET (redirects not followed) answers with exactly `expect_status`
+    #[serde(rename_all = "camelCase")]
+    Http {
+        url: String,
+        #[serde(default = "default_expect_status")]
+        expect_status: u16,
+    },
+}
+
+fn default_expect_status() -> u16 {
+    204
+}
+
+impl ConnectivityProbe {
+    /// Run the probe once; `None` for [`ConnectivityProbe::Os`]
+    pub async fn check(&self, timeout: Duration) -> Option<bool> {
+        match self {
+            ConnectivityProbe::Os => None,
+            ConnectivityProbe::RpcHealth { url } => {
+                let client = solana_client::nonblocking::rpc_client::RpcClient::new_with_timeout(
+                    url.clone(),
+                    timeout,
+                );
+                Some(client.get_health().await.is_ok())
+            }
+            ConnectivityProbe::Http { url, expect_status } => {
+                Some(http_status(url, timeout).await == Some(*expect_status))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+async fn http_status(url: &str, timeout: Duration) -> Option<u16> {
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .ok()?;
+    let response = client.get(url).send().await.ok()?;
+    Some(response.status().as_u16())
+}
+
+#[cfg(not(feature = "reqwest"))]
+async fn http_status(_url: &str, _timeout: Duration) -> Option<u16> {
+    None
+}
+
+/// Probe settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityConfig {
+    pub probe: ConnectivityProbe,
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_interval_secs() -> u64 {
+    DEFAULT_PROBE_INTERVAL_SECS
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_PROBE_TIMEOUT_SECS
+}
+
+impl ConnectivityConfig {
+    /// Probe `getHealth` on `rpc_url` with default timings
+    pub fn rpc_health(rpc_url: impl Into<String>) -> Self {
+        Self {
+            probe: ConnectivityProbe::RpcHealth {
+                url: rpc_url.into(),
+            },
+            interval_secs: DEFAULT_PROBE_INTERVAL_SECS,
+            timeout_secs: DEFAULT_PROBE_TIMEOUT_SECS,
+        }
+    }
+}
+
+/// Current connectivity plus the probe feeding it
+pub struct ConnectivityMonitor {
+    sdk: Arc<crate::PolliNetSDK>,
+    online: watch::Sender<bool>,
+    probe_task: Mutex<Option<tokio::task::AbortHandle>>,
+}
+
+impl ConnectivityMonitor {
+    /// New monitor; the device counts as offline until told otherwise
+    pub fn new(sdk: Arc<crate::PolliNetSDK>) -> Self {
+        Self {
+            sdk,
+            online: watch::channel(false).0,
+            probe_task: Mutex::new(None),
+        }
+    }
+
+    pub fn is_online(&self) -> bool {
+        *self.online.borrow()
+    }
+
+    /// Follow connectivity changes
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.online.subscribe()
+    }
+
+    /// Set the connectivity state (OS signal or probe result); returns true if
+    /// it changed, in which case `ConnectivityChanged` is published
+    pub fn report(&self, online: bool) -> bool {
+        let changed = self.online.send_if_modified(|state| {
+            let changed = *state != online;
+            *state = online;
+            changed
+        });
+        if changed {
+            tracing::info!(
+                "🌐 Connectivity: {}",
+                if online { "online" } else { "offline" }
+            );
+            self.sdk
+                .notify(crate::SdkEvent::ConnectivityChanged { online });
+        }
+        changed
+    }
+
+    /// Start probing with `config`, replacing a running probe. An `Os` probe
+    /// only stops the previous one. Must be called within a tokio runtime.
+    pub fn start(self: &Arc<Self>, config: ConnectivityConfig) -> Result<(), String> {
+        if matches!(config.probe, ConnectivityProbe::Http { .. }) && !cfg!(feature = "reqwest") {
+            return Err("HTTP probes need the reqwest feature".to_string());
+        }
+        self.stop();
+        if config.probe == ConnectivityProbe::Os {
+            return Ok(());
+        }
+
+        let monitor = Arc::downgrade(self);
+        let interval = Duration::from_secs(config.interval_secs.max(1));
+        let timeout = Duration::from_secs(config.timeout_secs.max(1));
+        let task = tokio::spawn(async move {
+            loop {
+                let Some(online) = config.probe.check(timeout).await else {
+                    break;
+                };
+                let Some(monitor) = monitor.upgrade() else {
+                    break;
+                };
+                monitor.report(online);
+                drop(monitor);
+                tokio::time::sleep(interval).await;
+            }
+        });
+        self.sdk.track_task(&task);
+        *self.probe_task.lock() = Some(task.abort_handle());
+        Ok(())
+    }
+
+    /// Stop probing (the last state is kept); returns false if no probe was running
+    pub fn stop(&self) -> bool {
+        match self.probe_task.lock().take() {
+            Some(task) => {
+                task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_probing(&self) -> bool {
+        self.probe_task.lock().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_report_publishes_changes_only() {
+        let sdk = Arc::new(crate::PolliNetSDK::new().await.unwrap());
+        let mut events = sdk.subscribe();
+        let monitor = ConnectivityMonitor::new(sdk);
+        let mut online = monitor.subscribe();
+
+        assert!(!monitor.is_online());
+        assert!(monitor.report(true));
+        assert!(!monitor.report(true));
+        assert!(online.has_changed().unwrap());
+        assert!(*online.borrow_and_update());
+
+        assert_eq!(
+            events.try_recv().unwrap(),
+            crate::SdkEvent::ConnectivityChanged { online: true }
+        );
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_rpc_probe_goes_offline() {
+        let sdk = Arc::new(crate::PolliNetSDK::new().await.unwrap());
+        let monitor = Arc::new(ConnectivityMonitor::new(sdk));
+        monitor.report(true);
+        let mut online = monitor.subscribe();
+        online.borrow_and_update();
+
+        monitor
+            .start(ConnectivityConfig {
+                timeout_secs: 1,
+                ..ConnectivityConfig::rpc_health("http://127.0.0.1:1")
+            })
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(5), online.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!monitor.is_online());
+
+        assert!(monitor.stop());
+        assert!(!monitor.is_probing());
+    }
+}
@@ -0,0 +1,471 @@
+//! Auto-submit gateway for online devices
+//!
+//! While the device has internet connectivity (see [`super::connectivity`]),
+//! [`GatewayService`] drains transactions reassembled from the mesh (and
+//! retries whose backoff has elapsed) and submits them with a
+//! [`TransactionSubmitter`]. Each cycle then waits for its submissions to
+//! confirm (see [`super::signature_watch`]) and queues the on-chain outcome
+//! for relay back through the mesh. Failed or unconfirmed submissions go to
+//! the retry queue and are dead-lettered once their retry budget is used up,
+//! so an online phone relays for everyone without any app code beyond
+//! `start_gateway`.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use solana_sdk::transaction::VersionedTransaction;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::rpc_pool::RpcPool;
+use super::signature_watch::{SignatureOutcome, SignatureWatcher};
+use super::sponsor::{self, FeePayerProvider};
+use super::transport::HostBleTransport;
+use super::tx_watch::WatchedStatus;
+use super::types::ProtocolEvent;
+use crate::queue::RetryItem;
+use crate::storage::TxHistoryStatus;
+
+/// Default pause between drain cycles while online
+pub const DEFAULT_GATEWAY_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Default cap on submissions per drain cycle
+pub const DEFAULT_GATEWAY_BATCH: usize = 20;
+
+/// Submits signed transactions to the network
+#[async_trait]
+pub trait TransactionSubmitter: Send + Sync {
+    /// Submit serialized transaction bytes; returns the signature
+    async fn submit(&self, tx_bytes: &[u8]) -> Result<String, String>;
+
+    /// Wait for a submitted transaction to confirm. Submitters that cannot
+    /// track confirmations treat submission as confirmation.
+    async fn confirm(&self, _signature: &str) -> SignatureOutcome {
+        SignatureOutcome::Confirmed
+    }
+}
+
+/// [`TransactionSubmitter`] backed by Solana JSON-RPC endpoints, failing over
+/// between them (see [`RpcPool`]); confirmations are tracked with a
+/// [`SignatureWatcher`]
+pub struct RpcSubmitter {
+    pool: Arc<RpcPool>,
+    watcher: SignatureWatcher,
+    fee_payer: Option<Arc<dyn FeePayerProvider>>,
+}
+
+impl RpcSubmitter {
+    pub fn new(pool: Arc<RpcPool>) -> Self {
+        Self {
+            watcher: SignatureWatcher::new(pool.clone()),
+            pool,
+            fee_payer: None,
+        }
+    }
+
+    /// Sign the fee-payer slot of transactions sponsored by `fee_payer`
+    /// before submitting them (see [`super::sponsor`])
+    pub fn with_fee_payer(mut self, fee_payer: Arc<dyn FeePayerProvider>) -> Self {
+        self.fee_payer = Some(fee_payer);
+        self
+    }
+
+    /// Replace the confirmation watcher (e.g. to set its websocket URL)
+    pub fn with_watcher(mut self, watcher: SignatureWatcher) -> Self {
+        self.watcher = watcher;
+        self
+    }
+
+    /// Endpoints and their health
+    pub fn pool(&self) -> &Arc<RpcPool> {
+        &self.pool
+    }
+}
+
+#[async_trait]
+impl TransactionSubmitter for RpcSubmitter {
+    async fn submit(&self, tx_bytes: &[u8]) -> Result<String, String> {
+        let mut tx = decode_transaction(tx_bytes)?;
+        if let Some(fee_payer) = &self.fee_payer {
+            sponsor::sponsor(fee_payer.as_ref(), &mut tx).await?;
+        }
+        self.pool
+            .call(|client| {
+                let tx = tx.clone();
+                async move { client.send_transaction(&tx).await }
+            })
+            .await
+            .map(|signature| signature.to_string())
+            .map_err(|e| format!("RPC submission failed: {}", e))
+    }
+
+    async fn confirm(&self, signature: &str) -> SignatureOutcome {
+        self.watcher.wait(signature).await
+    }
+}
+
+/// Decode transaction bytes as relayed over the mesh (LZ4- or zstd-compressed when large)
+pub fn decode_transaction(tx_bytes: &[u8]) -> Result<VersionedTransaction, String> {
+    if let Ok(tx) = bincode1::deserialize::<VersionedTransaction>(tx_bytes) {
+        return Ok(tx);
+    }
+    let decompressed = crate::util::compression::decompress(tx_bytes)
+        .map_err(|e| format!("Invalid transaction bytes: {}", e))?;
+    bincode1::deserialize(&decompressed).map_err(|e| format!("Invalid transaction: {}", e))
+}
+
+/// Gateway tuning
+#[derive(Debug, Clone)]
+pub struct GatewayConfig {
+    /// Pause between drain cycles while online
+    pub poll_interval: Duration,
+    /// Most transactions submitted per cycle
+    pub max_per_cycle: usize,
+}
+
+impl Default for GatewayConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: DEFAULT_GATEWAY_POLL_INTERVAL,
+            max_per_cycle: DEFAULT_GATEWAY_BATCH,
+        }
+    }
+}
+
+/// Outcome of one drain cycle
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayReport {
+    pub submitted: usize,
+    /// Submitted and confirmed on chain
+    pub confirmed: usize,
+    /// Submitted but failed on chain (terminal)
+    pub failed: usize,
+    /// Failures and unconfirmed submissions rescheduled for retry
+    pub retried: usize,
+    /// Failures dead-lettered (retry budget used up)
+    pub dropped: usize,
+}
+
+/// Background submitter for transactions relayed to this device
+pub struct GatewayService {
+    transport: Arc<HostBleTransport>,
+    submitter: Arc<dyn TransactionSubmitter>,
+    config: GatewayConfig,
+}
+
+/// Running gateway
+pub struct GatewayHandle {
+    task: tokio::task::AbortHandle,
+}
+
+impl GatewayHandle {
+    /// Stop the background task
+    pub fn stop(&self) {
+        self.task.abort();
+    }
+}
+
+impl GatewayService {
+    pub fn new(transport: Arc<HostBleTransport>, submitter: Arc<dyn TransactionSubmitter>) -> Self {
+        Self {
+            transport,
+            submitter,
+            config: GatewayConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: GatewayConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Spawn the drain loop (must be called within a tokio runtime). It idles
+    /// while the transport's [`super::connectivity::ConnectivityMonitor`]
+    /// reports offline; the task is also aborted by
+    /// [`crate::PolliNetSDK::shutdown`].
+    pub fn spawn(self) -> GatewayHandle {
+        let mut rx = self.transport.connectivity().subscribe();
+        let sdk = self.transport.sdk.clone();
+        let task = tokio::spawn(async move {
+            tracing::info!("🌐 Gateway started");
+            loop {
+                let is_online = *rx.borrow_and_update();
+                if is_online {
+                    self.run_once().await;
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep(self.config.poll_interval), if is_online => {}
+                    changed = rx.changed() => {
+                        if changed.is_err() {
+                            // Connectivity monitor dropped
+                            break;
+                        }
+                    }
+                }
+            }
+            tracing::info!("🌐 Gateway stopped");
+        });
+        sdk.track_task(&task);
+        GatewayHandle {
+            task: task.abort_handle(),
+        }
+    }
+
+    /// Submit everything currently ready (due retries first, then newly
+    /// received transactions, up to `max_per_cycle`), then wait for the
+    /// submissions to confirm
+    pub async fn run_once(&self) -> GatewayReport {
+        let queue_manager = self.transport.sdk.queue_manager();
+        let mut report = GatewayReport::default();
+        // Take the whole batch first, so a submission failing in this cycle
+        // is not popped again as a ready retry until the next one
+        let mut batch = Vec::new();
+        for _ in 0..self.config.max_per_cycle {
+            let pending = match queue_manager.pop_ready_retry().await {
+                Some(item) => Pending::Retry(item),
+                None => match self.transport.next_received_transaction() {
+                    // Sealed for another gateway: carry it on, never submit
+                    Some((_, tx_bytes, _)) if crate::ble::sealed::is_sealed(&tx_bytes) => {
+                        if let Err(e) = self.transport.queue_transaction(tx_bytes, None) {
+                            tracing::warn!("⚠️ Failed to relay sealed transaction: {}", e);
+                        }
+                        continue;
+                    }
+                    Some((_, tx_bytes, _)) => Pending::Received(tx_bytes),
+                    None => break,
+                },
+            };
+            batch.push(pending);
+        }
+
+        let mut submitted = Vec::new();
+        for pending in batch {
+            if let Some(done) = self.submit(pending, &mut report).await {
+                submitted.push(done);
+            }
+        }
+
+        let outcomes = futures::future::join_all(
+            submitted
+                .iter()
+                .map(|done| self.submitter.confirm(&done.signature)),
+        )
+        .await;
+        for (done, outcome) in submitted.into_iter().zip(outcomes) {
+            self.finish(done, outcome, &mut report).await;
+        }
+
+        if report != GatewayReport::default() {
+            tracing::info!(
+                "🌐 Gateway cycle: {} submitted ({} confirmed, {} failed), {} retried, {} dropped",
+                report.submitted,
+                report.confirmed,
+                report.failed,
+                report.retried,
+                report.dropped
+            );
+        }
+        report
+    }
+
+    async fn submit(&self, pending: Pending, report: &mut GatewayReport) -> Option<Submitted> {
+        let tx_id = pending.tx_id();
+        match self.submitter.submit(pending.tx_bytes()).await {
+            Ok(signature) => {
+                tracing::info!("✅ Gateway submitted tx {}: {}", short(&tx_id), signature);
+                self.transport
+                    .mark_transaction_submitted(pending.tx_bytes());
+                self.transport
+                    .emit_event(ProtocolEvent::ack(tx_id.clone(), "submitted"));
+                self.transport
+                    .update_tx_watch(&tx_id, &signature, WatchedStatus::Submitted, None);
+                report.submitted += 1;
+                Some(Submitted {
+                    pending,
+                    tx_id,
+                    signature,
+                })
+            }
+            Err(error) => {
+                tracing::warn!(
+                    "⚠️ Gateway submission of {} failed: {}",
+                    short(&tx_id),
+                    error
+                );
+                self.transport
+                    .record_mesh_stat(crate::storage::MeshStatsEvent::SubmitFailed);
+                self.reschedule(pending, tx_id, error, report).await;
+                None
+            }
+        }
+    }
+
+    /// Relay the on-chain outcome of a submission back into the mesh
+    async fn finish(&self, done: Submitted, outcome: SignatureOutcome, report: &mut GatewayReport) {
+        let queue_manager = self.transport.sdk.queue_manager();
+        let tx_id = done.tx_id;
+
+        let recorded = match outcome {
+            SignatureOutcome::Confirmed => {
+                report.confirmed += 1;
+                self.transport
+                    .update_tx_history(&tx_id, TxHistoryStatus::Confirmed);
+                self.transport
+                    .emit_event(ProtocolEvent::ack(tx_id.clone(), "confirmed"));
+                self.transport.update_tx_watch(
+                    &tx_id,
+                    &done.signature,
+                    WatchedStatus::Confirmed,
+                    None,
+                );
+                queue_manager
+                    .record_submission_success(&tx_id, done.signature)
+                    .await
+            }
+            SignatureOutcome::Failed(error) => {
+                tracing::warn!("❌ Tx {} failed on chain: {}", short(&tx_id), error);
+                report.failed += 1;
+                self.transport
+                    .update_tx_history(&tx_id, TxHistoryStatus::Failed);
+                self.transport
+                    .emit_event(ProtocolEvent::ack(tx_id.clone(), "failed"));
+                self.transport.update_tx_watch(
+                    &tx_id,
+                    &done.signature,
+                    WatchedStatus::Failed,
+                    Some(error.clone()),
+                );
+                queue_manager
+                    .record_submission_rejected(&tx_id, error)
+                    .await
+            }
+            SignatureOutcome::TimedOut => {
+                // Resubmitting the same transaction is harmless if it did land
+                let error = format!("Not confirmed: {}", done.signature);
+                self.reschedule(done.pending, tx_id, error, report).await;
+                return;
+            }
+        };
+        if let Err(e) = recorded {
+            tracing::warn!(
+                "⚠️ Failed to queue confirmation for {}: {}",
+                short(&tx_id),
+                e
+            );
+        }
+    }
+
+    async fn reschedule(
+        &self,
+        pending: Pending,
+        tx_id: String,
+        error: String,
+        report: &mut GatewayReport,
+    ) {
+        let queue_manager = self.transport.sdk.queue_manager();
+        let scheduled = match pending {
+            Pending::Received(tx_bytes) => {
+                queue_manager
+                    .record_submission_failure(tx_bytes, tx_id, error)
+                    .await
+            }
+            Pending::Retry(item) => queue_manager.record_retry_failure(item, error).await,
+        };
+        match scheduled {
+            Ok(()) => report.retried += 1,
+            Err(_) => report.dropped += 1,
+        }
+    }
+}
+
+/// Transaction taken for submission
+enum Pending {
+    /// Reassembled from the mesh, not yet attempted
+    Received(Vec<u8>),
+    /// Failed before; its backoff has elapsed
+    Retry(RetryItem),
+}
+
+impl Pending {
+    fn tx_bytes(&self) -> &[u8] {
+        match self {
+            Pending::Received(tx_bytes) => tx_bytes,
+            Pending::Retry(item) => &item.tx_bytes,
+        }
+    }
+
+    fn tx_id(&self) -> String {
+        match self {
+            Pending::Received(tx_bytes) => tx_id_of(tx_bytes),
+            Pending::Retry(item) => item.tx_id.clone(),
+        }
+    }
+}
+
+/// Submission awaiting its on-chain outcome
+struct Submitted {
+    pending: Pending,
+    tx_id: String,
+    signature: String,
+}
+
+fn short(tx_id: &str) -> String {
+    tx_id.chars().take(8).collect()
+}
+
+/// Mesh transaction id: hex SHA-256 of the bytes
+fn tx_id_of(tx_bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(tx_bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Fails the first `failures` submissions, then succeeds
+    struct FlakySubmitter {
+        failures: usize,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl TransactionSubmitter for FlakySubmitter {
+        async fn submit(&self, _tx_bytes: &[u8]) -> Result<String, String> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                Err("blockhash not found".to_string())
+            } else {
+                Ok("sig".to_string())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gateway_retries_then_confirms() {
+        let transport = Arc::new(HostBleTransport::new().await.unwrap());
+        transport.push_received_transaction(vec![7u8; 64]);
+
+        let gateway = GatewayService::new(
+            transport.clone(),
+            Arc::new(FlakySubmitter {
+                failures: 1,
+                calls: AtomicUsize::new(0),
+            }),
+        );
+
+        let first = gateway.run_once().await;
+        assert_eq!(first.retried, 1);
+        let queue_manager = transport.sdk.queue_manager();
+        assert_eq!(queue_manager.get_metrics().await.retry_size, 1);
+
+        // A first-failure retry is due immediately
+        let second = gateway.run_once().await;
+        assert_eq!(second.submitted, 1);
+        assert_eq!(second.confirmed, 1);
+        let metrics = queue_manager.get_metrics().await;
+        assert_eq!(metrics.retry_size, 0);
+        assert_eq!(metrics.confirmation_size, 1);
+
+        assert_eq!(gateway.run_once().await, GatewayReport::default());
+    }
+}
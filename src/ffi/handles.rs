@@ -0,0 +1,266 @@
+//! Generational handle map for FFI instances
+//!
+//! Handles given to hosts encode a slot index (low 32 bits) and the slot's
+//! generation (high bits). Shutting a handle down frees its slot for reuse and
+//! bumps the generation, so a stale handle is rejected instead of silently
+//! reaching whichever instance took over the slot.
+//!
+//! [`ShardedHandleMap`] spreads handles over independently locked shards for
+//! the global registries the bindings keep. Lookups only take a shard's read
+//! lock, so BLE callbacks calling push_inbound / next_outbound from several
+//! threads don't serialize on one registry mutex; only creating or shutting
+//! down a handle takes a (single shard's) write lock.
+
+use parking_lot::RwLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Handle lookup failure
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HandleError {
+    #[error("Invalid handle: {0}")]
+    Invalid(i64),
+
+    #[error("Handle {0} has been shut down")]
+    ShutDown(i64),
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// Slab of instances addressed by generational `i64` handles (never negative)
+pub struct HandleMap<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+}
+
+impl<T> HandleMap<T> {
+    pub const fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Store `value`, reusing a freed slot when possible; returns its handle
+    pub fn insert(&mut self, value: T) -> i64 {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.slots.push(Slot {
+                    generation: 1,
+                    value: None,
+                });
+                (self.slots.len() - 1) as u32
+            }
+        };
+        let slot = &mut self.slots[index as usize];
+        slot.value = Some(value);
+        encode(index, slot.generation)
+    }
+
+    pub fn get(&self, handle: i64) -> Result<&T, HandleError> {
+        let (index, generation) = decode(handle).ok_or(HandleError::Invalid(handle))?;
+        let slot = self
+            .slots
+            .get(index as usize)
+            .ok_or(HandleError::Invalid(handle))?;
+        if slot.generation != generation {
+            return Err(if generation < slot.generation {
+                HandleError::ShutDown(handle)
+            } else {
+                HandleError::Invalid(handle)
+            });
+        }
+        slot.value.as_ref().ok_or(HandleError::Invalid(handle))
+    }
+
+    /// Remove and return the instance; the handle is invalid afterwards
+    pub fn remove(&mut self, handle: i64) -> Result<T, HandleError> {
+        self.get(handle)?;
+        let (index, _) = decode(handle).ok_or(HandleError::Invalid(handle))?;
+        let slot = &mut self.slots[index as usize];
+        let value = slot.value.take().ok_or(HandleError::Invalid(handle))?;
+        // Generations stay below 2^31 so handles never go negative
+        slot.generation = match slot.generation.wrapping_add(1) & 0x7FFF_FFFF {
+            0 => 1,
+            next => next,
+        };
+        self.free.push(index);
+        Ok(value)
+    }
+
+    /// Live instances
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().filter_map(|slot| slot.value.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Default for HandleMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Shards of a [`ShardedHandleMap`]
+pub const HANDLE_SHARDS: u32 = 16;
+
+/// [`HandleMap`] split into [`HANDLE_SHARDS`] read-write locked shards. A
+/// handle's slot index is `local index * HANDLE_SHARDS + shard`.
+pub struct ShardedHandleMap<T> {
+    shards: [RwLock<HandleMap<T>>; HANDLE_SHARDS as usize],
+    /// Round-robin shard for the next insert
+    next_shard: AtomicUsize,
+}
+
+impl<T> ShardedHandleMap<T> {
+    pub const fn new() -> Self {
+        Self {
+            shards: [const { RwLock::new(HandleMap::new()) }; HANDLE_SHARDS as usize],
+            next_shard: AtomicUsize::new(0),
+        }
+    }
+
+    /// Store `value` in the next shard; returns its handle
+    pub fn insert(&self, value: T) -> i64 {
+        let shard =
+            (self.next_shard.fetch_add(1, Ordering::Relaxed) % HANDLE_SHARDS as usize) as u32;
+        let local = self.shards[shard as usize].write().insert(value);
+        let (index, generation) = decode(local).expect("HandleMap handles are never negative");
+        encode(index * HANDLE_SHARDS + shard, generation)
+    }
+
+    /// Run `f` on the instance under its shard's read lock
+    pub fn with<R>(&self, handle: i64, f: impl FnOnce(&T) -> R) -> Result<R, HandleError> {
+        let (shard, local) = split(handle)?;
+        let map = self.shards[shard].read();
+        map.get(local).map(f).map_err(|e| e.with_handle(handle))
+    }
+
+    /// Remove and return the instance; the handle is invalid afterwards
+    pub fn remove(&self, handle: i64) -> Result<T, HandleError> {
+        let (shard, local) = split(handle)?;
+        self.shards[shard]
+            .write()
+            .remove(local)
+            .map_err(|e| e.with_handle(handle))
+    }
+
+    /// Whether any live instance matches `f`
+    pub fn any(&self, mut f: impl FnMut(&T) -> bool) -> bool {
+        self.shards
+            .iter()
+            .any(|shard| shard.read().iter().any(&mut f))
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.read().len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Default for ShardedHandleMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HandleError {
+    fn with_handle(self, handle: i64) -> Self {
+        match self {
+            HandleError::Invalid(_) => HandleError::Invalid(handle),
+            HandleError::ShutDown(_) => HandleError::ShutDown(handle),
+        }
+    }
+}
+
+/// Shard of a sharded handle and the handle within that shard
+fn split(handle: i64) -> Result<(usize, i64), HandleError> {
+    let (index, generation) = decode(handle).ok_or(HandleError::Invalid(handle))?;
+    Ok((
+        (index % HANDLE_SHARDS) as usize,
+        encode(index / HANDLE_SHARDS, generation),
+    ))
+}
+
+fn encode(index: u32, generation: u32) -> i64 {
+    ((generation as i64) << 32) | index as i64
+}
+
+fn decode(handle: i64) -> Option<(u32, u32)> {
+    if handle < 0 {
+        return None;
+    }
+    Some((handle as u32, (handle >> 32) as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reused_slot_rejects_stale_handle() {
+        let mut map = HandleMap::new();
+        let first = map.insert("a");
+        assert_eq!(map.get(first), Ok(&"a"));
+
+        assert_eq!(map.remove(first), Ok("a"));
+        assert_eq!(map.get(first), Err(HandleError::ShutDown(first)));
+        assert_eq!(map.remove(first), Err(HandleError::ShutDown(first)));
+
+        let second = map.insert("b");
+        assert_ne!(first, second);
+        assert_eq!(second as u32, first as u32, "slot should be reused");
+        assert_eq!(map.get(first), Err(HandleError::ShutDown(first)));
+        assert_eq!(map.get(second), Ok(&"b"));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_sharded_handles_spread_and_reject_stale() {
+        let map = ShardedHandleMap::new();
+        let handles: Vec<i64> = (0..HANDLE_SHARDS * 2).map(|n| map.insert(n)).collect();
+        assert_eq!(map.len(), handles.len());
+        for (n, handle) in handles.iter().enumerate() {
+            assert_eq!(map.with(*handle, |v| *v), Ok(n as u32));
+        }
+        // Consecutive handles land in different shards
+        assert_ne!(
+            handles[0] as u32 % HANDLE_SHARDS,
+            handles[1] as u32 % HANDLE_SHARDS
+        );
+
+        assert_eq!(map.remove(handles[3]), Ok(3));
+        assert_eq!(
+            map.with(handles[3], |v| *v),
+            Err(HandleError::ShutDown(handles[3]))
+        );
+        assert!(!map.any(|v| *v == 3));
+        assert!(map.any(|v| *v == 4));
+        assert_eq!(map.with(-5, |v| *v), Err(HandleError::Invalid(-5)));
+    }
+
+    #[test]
+    fn test_invalid_handles() {
+        let mut map: HandleMap<u8> = HandleMap::new();
+        assert_eq!(map.get(-1), Err(HandleError::Invalid(-1)));
+        assert_eq!(map.get(0), Err(HandleError::Invalid(0)));
+        let handle = map.insert(7);
+        assert_eq!(
+            map.get(handle + (1 << 32)),
+            Err(HandleError::Invalid(handle + (1 << 32)))
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! Bounded hand-off from radio callbacks to reassembly
+//!
+//! `push_inbound` locks the reassembly buffers, the budget and the metrics.
+//! Calling it from the BLE notification callback means a burst of inbound
+//! data (or a slow lock holder) stalls the radio event thread. Instead the
+//! callback offers frames to an [`InboundQueue`], which never blocks: when
+//! it is full the frame is dropped and the sender is NACKed so it resends
+//! later. The engine drains the queue from `tick` or a host worker.
+
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+
+/// Frames waiting to be processed at most
+pub const INBOUND_QUEUE_CAPACITY: usize = 256;
+
+/// Frame received from a peer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InboundFrame {
+    /// Peer it arrived from, if the host knows
+    pub peer_id: Option<String>,
+    pub data: Vec<u8>,
+}
+
+/// Bounded multi-producer queue of inbound frames
+pub struct InboundQueue {
+    sender: SyncSender<InboundFrame>,
+    receiver: Mutex<Receiver<InboundFrame>>,
+    len: AtomicUsize,
+    dropped: AtomicU64,
+}
+
+impl Default for InboundQueue {
+    fn default() -> Self {
+        Self::new(INBOUND_QUEUE_CAPACITY)
+    }
+}
+
+impl InboundQueue {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        Self {
+            sender,
+            receiver: Mutex::new(receiver),
+            len: AtomicUsize::new(0),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Queue a frame without blocking; gives it back when the queue is full
+    pub fn offer(&self, frame: InboundFrame) -> Result<(), InboundFrame> {
+        // Counted before sending so a concurrent drain never takes it below zero
+        self.len.fetch_add(1, Ordering::Relaxed);
+        match self.sender.try_send(frame) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(frame)) | Err(TrySendError::Disconnected(frame)) => {
+                self.len.fetch_sub(1, Ordering::Relaxed);
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                Err(frame)
+            }
+        }
+    }
+
+    /// Take up to `max` queued frames, oldest first
+    pub fn drain(&self, max: usize) -> Vec<InboundFrame> {
+        let receiver = self.receiver.lock();
+        let frames: Vec<InboundFrame> = receiver.try_iter().take(max).collect();
+        self.len.fetch_sub(frames.len(), Ordering::Relaxed);
+        frames
+    }
+
+    /// Frames waiting
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Frames refused because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(n: u8) -> InboundFrame {
+        InboundFrame {
+            peer_id: None,
+            data: vec![n],
+        }
+    }
+
+    #[test]
+    fn test_full_queue_refuses_without_blocking() {
+        let queue = InboundQueue::new(2);
+        assert!(queue.offer(frame(1)).is_ok());
+        assert!(queue.offer(frame(2)).is_ok());
+        assert_eq!(queue.offer(frame(3)), Err(frame(3)));
+        assert_eq!((queue.len(), queue.dropped()), (2, 1));
+
+        assert_eq!(queue.drain(1), vec![frame(1)]);
+        assert!(queue.offer(frame(4)).is_ok());
+        assert_eq!(queue.drain(usize::MAX), vec![frame(2), frame(4)]);
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,648 @@
+//! Offline text messaging for the host-driven transport
+//!
+//! Text messages travel as single-frame `TextMessage` mesh packets, separate
+//! from the transaction fragment stream: the host sends frames from
+//! `next_text_frame` and hands frames it receives to `ingest_text_frame`,
+//! which seal and open them as signed envelopes (see `ble::envelope`). Each
+//! message is acknowledged by its recipient so the sender can show delivery,
+//! and the recipient's app sends read receipts with `mark_read`. Messages not
+//! acknowledged in time are resent with the retry queue's backoff, and marked
+//! failed after [`MAX_SEND_ATTEMPTS`]. Conversations are rebuilt from the
+//! message log (see [`fold_conversation`]), where receipts are logged next to
+//! the messages they refer to.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+use uuid::Uuid;
+
+use crate::ble::envelope::SIGNED_ENVELOPE_OVERHEAD;
+use crate::ble::mesh::{MeshPacket, PacketType, HEADER_SIZE, MAX_PAYLOAD_SIZE};
+use crate::queue::retry::BackoffStrategy;
+use crate::storage::{LoggedMessage, MessageDirection, MessageKind};
+
+/// Largest text packet, leaving room to seal it in an envelope
+pub const MAX_TEXT_FRAME_BYTES: usize = MAX_PAYLOAD_SIZE - SIGNED_ENVELOPE_OVERHEAD;
+/// Largest message body (UTF-8 bytes); bodies needing JSON escaping may fit less
+pub const MAX_TEXT_BODY_BYTES: usize = MAX_TEXT_FRAME_BYTES - HEADER_SIZE - 32;
+/// Received messages kept until the host retrieves them
+const MAX_INBOX_MESSAGES: usize = 500;
+/// Sent messages tracked for delivery status (oldest dropped first)
+const MAX_TRACKED_MESSAGES: usize = 1000;
+/// Text and ack frames waiting to be sent
+const MAX_PENDING_FRAMES: usize = 1000;
+/// Sends of one message (first send included) before it is marked failed
+pub const MAX_SEND_ATTEMPTS: u32 = 5;
+/// Base wait for an ack before resending (doubled per attempt)
+const RESEND_BASE_SECS: u64 = 10;
+/// Message ids per read-receipt frame
+const READ_IDS_PER_FRAME: usize = 8;
+
+/// Delivery status of a sent message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextMessageStatus {
+    /// Frame is waiting for the host to send it
+    #[serde(rename = "QUEUED")]
+    Queued,
+    /// Frame was handed to the host
+    #[serde(rename = "SENT")]
+    Sent,
+    /// Recipient acknowledged it
+    #[serde(rename = "DELIVERED")]
+    Delivered,
+    /// Recipient's app showed it
+    #[serde(rename = "READ")]
+    Read,
+    /// Not acknowledged after [`MAX_SEND_ATTEMPTS`] sends
+    #[serde(rename = "FAILED")]
+    Failed,
+}
+
+impl TextMessageStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TextMessageStatus::Queued => "QUEUED",
+            TextMessageStatus::Sent => "SENT",
+            TextMessageStatus::Delivered => "DELIVERED",
+            TextMessageStatus::Read => "READ",
+            TextMessageStatus::Failed => "FAILED",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [
+            TextMessageStatus::Queued,
+            TextMessageStatus::Sent,
+            TextMessageStatus::Delivered,
+            TextMessageStatus::Read,
+            TextMessageStatus::Failed,
+        ]
+        .into_iter()
+        .find(|status| status.as_str() == value)
+    }
+
+    /// Statuses only move forward; a late ack still beats `Failed`
+    fn rank(self) -> u8 {
+        match self {
+            TextMessageStatus::Queued => 0,
+            TextMessageStatus::Sent => 1,
+            TextMessageStatus::Failed => 2,
+            TextMessageStatus::Delivered => 3,
+            TextMessageStatus::Read => 4,
+        }
+    }
+
+    /// Whether the message still needs (re)sending
+    pub fn is_pending(self) -> bool {
+        self.rank() < TextMessageStatus::Failed.rank()
+    }
+}
+
+/// Message sent from this device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutgoingTextMessage {
+    pub message_id: String,
+    pub peer_id: String,
+    pub body: String,
+    pub created_at: u64,
+    pub status: TextMessageStatus,
+    /// Times the frame was handed to the host
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+/// Message received from a peer, awaiting retrieval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomingTextMessage {
+    pub message_id: String,
+    pub peer_id: String,
+    pub body: String,
+    pub received_at: u64,
+}
+
+/// Frame payload
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum TextPayload {
+    Message { body: String },
+    Ack { message_id: String },
+    Read { message_ids: Vec<String> },
+}
+
+/// What an ingested frame turned out to be
+#[derive(Debug, Clone)]
+pub enum IngestedText {
+    Message(IncomingTextMessage),
+    /// Ack for one of our messages (`None` if it is no longer tracked)
+    Delivered(Option<OutgoingTextMessage>),
+    /// Read receipt for our tracked messages
+    Read(Vec<OutgoingTextMessage>),
+    /// Message already received (the ack was re-sent)
+    Duplicate,
+}
+
+struct PendingFrame {
+    /// Sent message this frame carries (acks carry none)
+    message_id: Option<String>,
+    bytes: Vec<u8>,
+}
+
+/// Sent message with what is needed to resend it
+struct TrackedMessage {
+    message: OutgoingTextMessage,
+    frame: Vec<u8>,
+    /// When to resend if still unacknowledged
+    resend_at: Option<Instant>,
+}
+
+/// Outbox, inbox and pending frames for one transport
+pub struct TextMessaging {
+    /// Random per-session sender id carried in packet headers
+    sender_id: Uuid,
+    frames: Mutex<VecDeque<PendingFrame>>,
+    sent: Mutex<(HashMap<String, TrackedMessage>, VecDeque<String>)>,
+    inbox: Mutex<VecDeque<IncomingTextMessage>>,
+    /// Ids of recently received messages, for duplicate suppression
+    seen: Mutex<VecDeque<String>>,
+    /// Wait for an ack before resending
+    backoff: BackoffStrategy,
+}
+
+impl TextMessaging {
+    pub fn new() -> Self {
+        Self::with_backoff(BackoffStrategy::exponential(RESEND_BASE_SECS))
+    }
+
+    /// Messaging waiting `backoff` for an ack before each resend
+    pub fn with_backoff(backoff: BackoffStrategy) -> Self {
+        Self {
+            sender_id: Uuid::new_v4(),
+            frames: Mutex::new(VecDeque::new()),
+            sent: Mutex::new((HashMap::new(), VecDeque::new())),
+            inbox: Mutex::new(VecDeque::new()),
+            seen: Mutex::new(VecDeque::new()),
+            backoff,
+        }
+    }
+
+    /// Queue `body` for `peer_id`; returns the message id
+    pub fn send(&self, peer_id: &str, body: &str) -> Result<OutgoingTextMessage, String> {
+        if body.is_empty() {
+            return Err("Message body is empty".to_string());
+        }
+        let packet = self.packet(&TextPayload::Message {
+            body: body.to_string(),
+        })?;
+        // Escaping can grow the body, so check the encoded frame
+        if body.len() > MAX_TEXT_BODY_BYTES
+            || HEADER_SIZE + packet.payload.len() > MAX_TEXT_FRAME_BYTES
+        {
+            return Err(format!(
+                "Message body is too long ({} bytes, max {})",
+                body.len(),
+                MAX_TEXT_BODY_BYTES
+            ));
+        }
+        let message = OutgoingTextMessage {
+            message_id: packet.header.message_id.to_string(),
+            peer_id: peer_id.to_string(),
+            body: body.to_string(),
+            created_at: now_secs(),
+            status: TextMessageStatus::Queued,
+            attempts: 0,
+        };
+        self.track(message.clone(), packet.serialize())?;
+        Ok(message)
+    }
+
+    /// Queue a message again under its original id (e.g. an unacknowledged
+    /// message from the log after a restart); returns false if it is already
+    /// tracked or no longer pending
+    pub fn restore(&self, mut message: OutgoingTextMessage) -> Result<bool, String> {
+        if !message.status.is_pending() || self.sent.lock().0.contains_key(&message.message_id) {
+            return Ok(false);
+        }
+        let message_id = Uuid::parse_str(&message.message_id)
+            .map_err(|e| format!("Invalid message id {}: {}", message.message_id, e))?;
+        let mut packet = self.packet(&TextPayload::Message {
+            body: message.body.clone(),
+        })?;
+        packet.header.message_id = message_id;
+        message.status = TextMessageStatus::Queued;
+        self.track(message, packet.serialize())?;
+        Ok(true)
+    }
+
+    fn track(&self, message: OutgoingTextMessage, frame: Vec<u8>) -> Result<(), String> {
+        self.push_frame(PendingFrame {
+            message_id: Some(message.message_id.clone()),
+            bytes: frame.clone(),
+        })?;
+
+        let mut sent = self.sent.lock();
+        let (messages, order) = &mut *sent;
+        if order.len() >= MAX_TRACKED_MESSAGES {
+            if let Some(oldest) = order.pop_front() {
+                messages.remove(&oldest);
+            }
+        }
+        order.push_back(message.message_id.clone());
+        messages.insert(
+            message.message_id.clone(),
+            TrackedMessage {
+                message,
+                frame,
+                resend_at: None,
+            },
+        );
+        Ok(())
+    }
+
+    /// Next text/ack frame for the host to send, if it fits in `max_len`
+    pub fn next_frame(&self, max_len: usize) -> Option<Vec<u8>> {
+        let frame = {
+            let mut frames = self.frames.lock();
+            if frames.front()?.bytes.len() > max_len {
+                return None;
+            }
+            frames.pop_front()?
+        };
+        if let Some(id) = &frame.message_id {
+            if let Some(tracked) = self.sent.lock().0.get_mut(id) {
+                if tracked.message.status == TextMessageStatus::Queued {
+                    tracked.message.status = TextMessageStatus::Sent;
+                    let delay = self
+                        .backoff
+                        .calculate_delay(tracked.message.attempts as usize);
+                    tracked.message.attempts += 1;
+                    tracked.resend_at = Some(Instant::now() + delay);
+                }
+            }
+        }
+        Some(frame.bytes)
+    }
+
+    /// Requeue sent messages whose ack is overdue; returns the messages that
+    /// used up their attempts and are now `Failed`
+    pub fn resend_overdue(&self) -> Vec<OutgoingTextMessage> {
+        let now = Instant::now();
+        let mut failed = Vec::new();
+        let mut sent = self.sent.lock();
+        for tracked in sent.0.values_mut() {
+            if tracked.message.status != TextMessageStatus::Sent
+                || tracked.resend_at.is_some_and(|at| at > now)
+            {
+                continue;
+            }
+            if tracked.message.attempts >= MAX_SEND_ATTEMPTS {
+                tracked.message.status = TextMessageStatus::Failed;
+                tracked.resend_at = None;
+                failed.push(tracked.message.clone());
+                continue;
+            }
+            let requeued = self.push_frame(PendingFrame {
+                message_id: Some(tracked.message.message_id.clone()),
+                bytes: tracked.frame.clone(),
+            });
+            // A full frame queue is retried on the next call
+            if requeued.is_ok() {
+                tracked.message.status = TextMessageStatus::Queued;
+                tracked.resend_at = None;
+            }
+        }
+        failed
+    }
+
+    /// Queue read receipts for received messages
+    pub fn mark_read(&self, message_ids: &[String]) -> Result<(), String> {
+        for chunk in message_ids.chunks(READ_IDS_PER_FRAME) {
+            let packet = self.packet(&TextPayload::Read {
+                message_ids: chunk.to_vec(),
+            })?;
+            self.push_frame(PendingFrame {
+                message_id: None,
+                bytes: packet.serialize(),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Handle a text frame received from `peer_id` (acks are queued automatically)
+    pub fn ingest_frame(&self, peer_id: &str, data: &[u8]) -> Result<IngestedText, String> {
+        let packet = MeshPacket::deserialize(data).map_err(|e| e.to_string())?;
+        if packet.header.packet_type != PacketType::TextMessage {
+            return Err(format!(
+                "Not a text frame (packet type {:?})",
+                packet.header.packet_type
+            ));
+        }
+        let payload: TextPayload = serde_json::from_slice(&packet.payload)
+            .map_err(|e| format!("Invalid text payload: {}", e))?;
+
+        match payload {
+            TextPayload::Ack { message_id } => Ok(IngestedText::Delivered(
+                self.advance(&message_id, TextMessageStatus::Delivered),
+            )),
+            TextPayload::Read { message_ids } => Ok(IngestedText::Read(
+                message_ids
+                    .iter()
+                    .filter_map(|id| self.advance(id, TextMessageStatus::Read))
+                    .collect(),
+            )),
+            TextPayload::Message { body } => {
+                let message_id = packet.header.message_id.to_string();
+                self.queue_ack(&message_id)?;
+
+                let mut seen = self.seen.lock();
+                if seen.contains(&message_id) {
+                    return Ok(IngestedText::Duplicate);
+                }
+                if seen.len() >= MAX_TRACKED_MESSAGES {
+                    seen.pop_front();
+                }
+                seen.push_back(message_id.clone());
+                drop(seen);
+
+                let message = IncomingTextMessage {
+                    message_id,
+                    peer_id: peer_id.to_string(),
+                    body,
+                    received_at: now_secs(),
+                };
+                let mut inbox = self.inbox.lock();
+                if inbox.len() >= MAX_INBOX_MESSAGES {
+                    inbox.pop_front();
+                }
+                inbox.push_back(message.clone());
+                Ok(IngestedText::Message(message))
+            }
+        }
+    }
+
+    /// Pop the oldest received message not yet retrieved
+    pub fn next_received(&self) -> Option<IncomingTextMessage> {
+        self.inbox.lock().pop_front()
+    }
+
+    /// Number of received messages waiting for retrieval
+    pub fn received_count(&self) -> usize {
+        self.inbox.lock().len()
+    }
+
+    /// Delivery status of a sent message (`None` if unknown or no longer tracked)
+    pub fn status(&self, message_id: &str) -> Option<OutgoingTextMessage> {
+        self.sent
+            .lock()
+            .0
+            .get(message_id)
+            .map(|tracked| tracked.message.clone())
+    }
+
+    /// Move a tracked message forward to `status`; `None` if it is not tracked
+    /// or already past it
+    fn advance(&self, message_id: &str, status: TextMessageStatus) -> Option<OutgoingTextMessage> {
+        let mut sent = self.sent.lock();
+        let tracked = sent.0.get_mut(message_id)?;
+        if tracked.message.status.rank() >= status.rank() {
+            return None;
+        }
+        tracked.message.status = status;
+        tracked.resend_at = None;
+        Some(tracked.message.clone())
+    }
+
+    fn queue_ack(&self, message_id: &str) -> Result<(), String> {
+        let packet = self.packet(&TextPayload::Ack {
+            message_id: message_id.to_string(),
+        })?;
+        self.push_frame(PendingFrame {
+            message_id: None,
+            bytes: packet.serialize(),
+        })
+    }
+
+    fn packet(&self, payload: &TextPayload) -> Result<MeshPacket, String> {
+        let payload = serde_json::to_vec(payload)
+            .map_err(|e| format!("Failed to encode text payload: {}", e))?;
+        Ok(MeshPacket::new(
+            PacketType::TextMessage,
+            self.sender_id,
+            payload,
+        ))
+    }
+
+    fn push_frame(&self, frame: PendingFrame) -> Result<(), String> {
+        let mut frames = self.frames.lock();
+        if frames.len() >= MAX_PENDING_FRAMES {
+            return Err(format!(
+                "Text frame queue full ({} frames)",
+                MAX_PENDING_FRAMES
+            ));
+        }
+        frames.push_back(frame);
+        Ok(())
+    }
+}
+
+impl Default for TextMessaging {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Message as shown in a conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationMessage {
+    /// Unset for messages recorded by the host without an id
+    pub message_id: Option<String>,
+    pub peer_id: String,
+    pub direction: MessageDirection,
+    pub kind: MessageKind,
+    pub body: String,
+    pub timestamp: u64,
+    /// Outbound text: delivery status. Inbound text: `READ` once marked read.
+    pub status: Option<TextMessageStatus>,
+}
+
+/// Fold logged messages and their receipts into conversation entries, oldest first
+pub fn fold_conversation(entries: Vec<LoggedMessage>) -> Vec<ConversationMessage> {
+    let mut messages: Vec<ConversationMessage> = Vec::new();
+    let mut by_id: HashMap<String, usize> = HashMap::new();
+    for entry in entries {
+        if entry.kind == MessageKind::Receipt {
+            let (Some(id), Some(status)) =
+                (&entry.message_id, TextMessageStatus::parse(&entry.body))
+            else {
+                continue;
+            };
+            if let Some(message) = by_id.get(id).map(|&i| &mut messages[i]) {
+                if message
+                    .status
+                    .is_none_or(|current| current.rank() < status.rank())
+                {
+                    message.status = Some(status);
+                }
+            }
+            continue;
+        }
+
+        // Only messages sent through `send` have an id and a delivery status
+        let status = (entry.kind == MessageKind::Text
+            && entry.direction == MessageDirection::Outbound
+            && entry.message_id.is_some())
+        .then_some(TextMessageStatus::Queued);
+        if let Some(id) = &entry.message_id {
+            by_id.insert(id.clone(), messages.len());
+        }
+        messages.push(ConversationMessage {
+            message_id: entry.message_id,
+            peer_id: entry.peer_id,
+            direction: entry.direction,
+            kind: entry.kind,
+            body: entry.body,
+            timestamp: entry.timestamp,
+            status,
+        });
+    }
+    messages
+}
+
+fn now_secs() -> u64 {
+    crate::util::clock::now_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_receive_and_ack() {
+        let alice = TextMessaging::new();
+        let bob = TextMessaging::new();
+
+        let sent = alice.send("bob", "hello").unwrap();
+        let frame = alice.next_frame(MAX_PAYLOAD_SIZE).unwrap();
+        assert!(frame.len() <= MAX_PAYLOAD_SIZE);
+        assert_eq!(
+            alice.status(&sent.message_id).unwrap().status,
+            TextMessageStatus::Sent
+        );
+
+        let received = match bob.ingest_frame("alice", &frame).unwrap() {
+            IngestedText::Message(message) => message,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(received.body, "hello");
+        assert_eq!(received.message_id, sent.message_id);
+        assert!(matches!(
+            bob.ingest_frame("alice", &frame).unwrap(),
+            IngestedText::Duplicate
+        ));
+        assert_eq!(bob.next_received().unwrap().body, "hello");
+        assert!(bob.next_received().is_none());
+
+        let ack = bob.next_frame(MAX_PAYLOAD_SIZE).unwrap();
+        assert!(matches!(
+            alice.ingest_frame("bob", &ack).unwrap(),
+            IngestedText::Delivered(Some(_))
+        ));
+        assert_eq!(
+            alice.status(&sent.message_id).unwrap().status,
+            TextMessageStatus::Delivered
+        );
+    }
+
+    #[test]
+    fn test_resend_then_late_ack_and_read_receipt() {
+        let alice = TextMessaging::with_backoff(BackoffStrategy::Fixed {
+            interval_seconds: 0,
+        });
+        let bob = TextMessaging::new();
+        let sent = alice.send("bob", "hello").unwrap();
+
+        // Every frame is lost until the attempts run out
+        let mut frame = None;
+        for attempt in 1..=MAX_SEND_ATTEMPTS {
+            frame = alice.next_frame(MAX_PAYLOAD_SIZE);
+            let failed = alice.resend_overdue();
+            assert_eq!(failed.len(), usize::from(attempt == MAX_SEND_ATTEMPTS));
+        }
+        assert!(alice.next_frame(MAX_PAYLOAD_SIZE).is_none());
+        let status = alice.status(&sent.message_id).unwrap();
+        assert_eq!(status.status, TextMessageStatus::Failed);
+        assert_eq!(status.attempts, MAX_SEND_ATTEMPTS);
+
+        // A late ack still counts, followed by the read receipt
+        bob.ingest_frame("alice", &frame.unwrap()).unwrap();
+        let ack = bob.next_frame(MAX_PAYLOAD_SIZE).unwrap();
+        assert!(matches!(
+            alice.ingest_frame("bob", &ack).unwrap(),
+            IngestedText::Delivered(Some(_))
+        ));
+        bob.mark_read(std::slice::from_ref(&sent.message_id))
+            .unwrap();
+        let receipt = bob.next_frame(MAX_PAYLOAD_SIZE).unwrap();
+        match alice.ingest_frame("bob", &receipt).unwrap() {
+            IngestedText::Read(messages) => {
+                assert_eq!(messages.len(), 1);
+                assert_eq!(messages[0].status, TextMessageStatus::Read);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        // Statuses never move backwards
+        assert!(matches!(
+            alice.ingest_frame("bob", &ack).unwrap(),
+            IngestedText::Delivered(None)
+        ));
+    }
+
+    #[test]
+    fn test_fold_conversation_applies_receipts() {
+        let receipt = |status: &str| {
+            LoggedMessage::new(
+                "bob",
+                MessageDirection::Inbound,
+                MessageKind::Receipt,
+                status,
+            )
+            .with_message_id("m1")
+        };
+        let entries = vec![
+            LoggedMessage::new("bob", MessageDirection::Outbound, MessageKind::Text, "hi")
+                .with_message_id("m1"),
+            receipt("READ"),
+            receipt("DELIVERED"),
+            LoggedMessage::new("bob", MessageDirection::Inbound, MessageKind::Text, "yo")
+                .with_message_id("m2"),
+        ];
+
+        let conversation = fold_conversation(entries);
+        assert_eq!(conversation.len(), 2);
+        assert_eq!(conversation[0].status, Some(TextMessageStatus::Read));
+        assert_eq!(conversation[1].status, None);
+        assert_eq!(conversation[1].body, "yo");
+    }
+
+    #[test]
+    fn test_body_limit() {
+        let messaging = TextMessaging::new();
+        assert!(messaging.send("peer", "").is_err());
+        assert!(messaging
+            .send("peer", &"\"".repeat(MAX_TEXT_BODY_BYTES))
+            .is_err());
+        assert!(messaging
+            .send("peer", &"x".repeat(MAX_TEXT_BODY_BYTES + 1))
+            .is_err());
+        let frame = {
+            messaging
+                .send("peer", &"x".repeat(MAX_TEXT_BODY_BYTES))
+                .unwrap();
+            messaging.next_frame(MAX_PAYLOAD_SIZE).unwrap()
+        };
+        assert!(frame.len() <= MAX_TEXT_FRAME_BYTES);
+    }
+}
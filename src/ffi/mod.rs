@@ -1,19 +1,45 @@
-//! FFI module for Android integration
+//! FFI module: the host-driven engine and its platform bindings
 //!
-//! This module provides a C-compatible interface for Kotlin/Java to interact with
-//! the PolliNet Rust core. It handles:
-//! - Host-driven BLE transport (push_inbound, next_outbound, tick)
+//! The core is platform-neutral and built on every native target, so Android,
+//! iOS and desktop embedders share one implementation (and its tests). It handles:
+//! - Host-driven BLE / Wi-Fi Direct transport (push_inbound, next_outbound, tick)
 //! - Transaction building and fragmentation
 //! - Signature operations
 //! - Metrics and diagnostics
+//!
+//! Only the binding layers are feature-gated: `android` adds the JNI entry
+//! points in [`android`], `uniffi` exposes the engine through generated
+//! Kotlin/Swift bindings in [`bindings`], and `c-api` exposes it as a plain C
+//! library ([`c_api`]) for iOS and desktop embedders.
 
+#[cfg(feature = "android")]
 pub mod android;
+#[cfg(feature = "uniffi")]
+pub mod bindings;
+pub mod builders;
+#[cfg(feature = "c-api")]
+pub mod c_api;
+pub mod connectivity;
+pub mod gateway;
+pub mod handles;
 pub mod host_transport;
+pub mod inbound_queue;
+pub mod messaging;
+pub mod nonce_accounts;
+pub mod peer_sessions;
+pub mod relay_policy;
+pub mod requests;
+pub mod rpc_pool;
 pub mod runtime;
+pub mod signature_watch;
+pub mod sponsor;
 pub mod transport;
+pub mod tx_metadata;
+pub mod tx_watch;
 pub mod types;
 pub mod wifi_direct_transport;
 
+#[cfg(feature = "android")]
 pub use android::*;
 pub use host_transport::HostTransport;
 pub use types::*;
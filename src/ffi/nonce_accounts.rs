@@ -0,0 +1,160 @@
+//! Durable nonce account values supplied by the host
+//!
+//! Nonce handling lives outside the SDK: the host creates its nonce accounts
+//! and reads their current values while online, then hands each one in as a
+//! [`NonceAccountInfo`] when building a transaction to send offline. The SDK
+//! only builds with those values and checks how old they are.
+//!
+//! A cached nonce goes stale once the account is advanced elsewhere, and a
+//! transaction built on it fails only after crossing the mesh. A
+//! [`NonceStalenessPolicy`] bounds how old a cached value may be: past the
+//! limit it either warns or refuses with a [`StaleNonce`] error, so the host
+//! can refresh its values first. A value without a fetch time has an unknown
+//! age and is treated as stale.
+
+use serde::{Deserialize, Serialize};
+
+/// Current state of one nonce account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NonceAccountInfo {
+    /// Base58 nonce account address
+    pub address: String,
+    /// Base58 key allowed to advance the nonce
+    pub authority: String,
+    /// Base58 nonce value, used as the transaction's recent blockhash
+    pub nonce: String,
+    pub lamports_per_signature: u64,
+    /// Unix seconds the host read the account from the chain (`None` when
+    /// it did not record one)
+    #[serde(default)]
+    pub fetched_at: Option<u64>,
+}
+
+impl NonceAccountInfo {
+    /// Seconds since the account was fetched, if known
+    pub fn age_secs(&self) -> Option<u64> {
+        self.fetched_at.map(crate::util::clock::secs_since)
+    }
+}
+
+/// Default age after which a cached nonce is considered stale
+pub const DEFAULT_NONCE_MAX_AGE_SECS: u64 = 24 * 60 * 60;
+
+/// What to do with a cached nonce older than the limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StaleNonceAction {
+    /// Log a warning and use it anyway
+    #[default]
+    Warn,
+    /// Fail with [`StaleNonce`] so the host refreshes first
+    Refuse,
+}
+
+/// Maximum age of cached nonce values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NonceStalenessPolicy {
+    pub max_age_secs: u64,
+    pub action: StaleNonceAction,
+}
+
+impl Default for NonceStalenessPolicy {
+    fn default() -> Self {
+        Self {
+            max_age_secs: DEFAULT_NONCE_MAX_AGE_SECS,
+            action: StaleNonceAction::Warn,
+        }
+    }
+}
+
+/// A cached nonce older than the policy allows
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[serde(rename_all = "camelCase")]
+#[error(
+    "Nonce account {address} was fetched {} (max {max_age_secs}s); refresh it",
+    describe_age(.age_secs)
+)]
+pub struct StaleNonce {
+    pub address: String,
+    /// `None` when the cached value has no fetch time
+    pub age_secs: Option<u64>,
+    pub max_age_secs: u64,
+}
+
+fn describe_age(age_secs: &Option<u64>) -> String {
+    match age_secs {
+        Some(age_secs) => format!("{}s ago", age_secs),
+        None => "at an unknown time".to_string(),
+    }
+}
+
+impl NonceStalenessPolicy {
+    /// Check a cached nonce. Returns whether it is stale (only possible with
+    /// `Warn`); with `Refuse` a stale nonce is an error. A nonce of unknown
+    /// age counts as stale.
+    pub fn check(&self, info: &NonceAccountInfo) -> Result<bool, StaleNonce> {
+        let age_secs = info.age_secs();
+        if age_secs.is_some_and(|age| age <= self.max_age_secs) {
+            return Ok(false);
+        }
+        let stale = StaleNonce {
+            address: info.address.clone(),
+            age_secs,
+            max_age_secs: self.max_age_secs,
+        };
+        match self.action {
+            StaleNonceAction::Warn => {
+                tracing::warn!("⚠️ {}", stale);
+                Ok(true)
+            }
+            StaleNonceAction::Refuse => Err(stale),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staleness_policy_warns_or_refuses() {
+        use solana_sdk::hash::Hash;
+        use solana_sdk::pubkey::Pubkey;
+
+        let info = NonceAccountInfo {
+            address: Pubkey::new_unique().to_string(),
+            authority: Pubkey::new_unique().to_string(),
+            nonce: Hash::new_unique().to_string(),
+            lamports_per_signature: 5_000,
+            fetched_at: Some(crate::util::clock::now_secs()),
+        };
+
+        let mut refuse = NonceStalenessPolicy {
+            max_age_secs: 60,
+            action: StaleNonceAction::Refuse,
+        };
+        assert_eq!(refuse.check(&info), Ok(false));
+        let old = NonceAccountInfo {
+            fetched_at: info.fetched_at.map(|t| t - 120),
+            ..info.clone()
+        };
+        assert_eq!(refuse.check(&old).unwrap_err().max_age_secs, 60);
+
+        // A copy without a fetch time has an unknown age
+        let mut unknown: NonceAccountInfo = serde_json::from_str(&format!(
+            r#"{{"address":"{}","authority":"{}","nonce":"{}","lamportsPerSignature":5000}}"#,
+            info.address, info.authority, info.nonce
+        ))
+        .unwrap();
+        assert_eq!(unknown.fetched_at, None);
+        assert_eq!(refuse.check(&unknown).unwrap_err().age_secs, None);
+
+        refuse.action = StaleNonceAction::Warn;
+        assert_eq!(refuse.check(&old), Ok(true));
+        assert_eq!(refuse.check(&unknown), Ok(true));
+        unknown.fetched_at = info.fetched_at;
+        assert_eq!(refuse.check(&unknown), Ok(false));
+    }
+}
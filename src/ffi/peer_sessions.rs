@@ -0,0 +1,150 @@
+//! Per-peer session state kept across disconnects
+//!
+//! Phones in a mesh drift in and out of range. Without a cache every
+//! re-encounter repeats the MTU request, the protocol check and the digest
+//! exchange, and a transaction cut off halfway is sent again from fragment
+//! 0. When a link drops, the host saves what was negotiated with the peer
+//! under its device key (stable across restarts and address rotation, see
+//! `DeviceIdentity`). On the next encounter the session is resumed: the
+//! peer's digest is restored, and only the fragments still missing from
+//! interrupted transfers are requested.
+//!
+//! Sessions expire after [`PEER_SESSION_TTL_SECS`]; by then the peer's
+//! digest is out of date and its partial transfers have timed out anyway.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::ble::TxDigest;
+use crate::util::clock;
+
+/// Sessions kept at most; the oldest is dropped first
+pub const MAX_PEER_SESSIONS: usize = 64;
+/// Age after which a saved session is not resumed
+pub const PEER_SESSION_TTL_SECS: u64 = 30 * 60;
+
+/// What was negotiated with a peer before the link dropped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerSession {
+    /// Negotiated ATT MTU
+    pub mtu: Option<usize>,
+    /// Mesh protocol version the peer speaks
+    pub protocol_version: Option<u8>,
+    /// Transactions the peer said it holds
+    pub digest: Option<TxDigest>,
+    /// Inbound transactions the peer was sending (hex tx ids)
+    pub partial_transfers: Vec<String>,
+    /// Unix seconds the session was saved
+    pub saved_at: u64,
+}
+
+/// Result of resuming a session, for the host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumedSession {
+    /// MTU to reuse instead of requesting one
+    pub mtu: Option<usize>,
+    pub protocol_version: Option<u8>,
+    /// Whether the peer's digest was restored (no need to ask for it again)
+    pub digest_restored: bool,
+    /// Interrupted transfers picked up again
+    pub resumed_transfers: usize,
+    /// Fragments requested from the peer to complete them
+    pub fragments_requested: usize,
+}
+
+/// Saved sessions keyed by the peer's base58 device key
+#[derive(Debug)]
+pub struct PeerSessionCache {
+    sessions: HashMap<String, PeerSession>,
+    ttl_secs: u64,
+}
+
+impl Default for PeerSessionCache {
+    fn default() -> Self {
+        Self::new(PEER_SESSION_TTL_SECS)
+    }
+}
+
+impl PeerSessionCache {
+    pub fn new(ttl_secs: u64) -> Self {
+        Self {
+            sessions: HashMap::new(),
+            ttl_secs,
+        }
+    }
+
+    /// Save (or replace) the session with `device_key`
+    pub fn save(&mut self, device_key: &str, session: PeerSession) {
+        self.sessions
+            .retain(|_, s| clock::secs_since(s.saved_at) <= self.ttl_secs);
+        if self.sessions.len() >= MAX_PEER_SESSIONS && !self.sessions.contains_key(device_key) {
+            let oldest = self
+                .sessions
+                .iter()
+                .min_by_key(|(_, s)| s.saved_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.sessions.remove(&oldest);
+            }
+        }
+        self.sessions.insert(device_key.to_string(), session);
+    }
+
+    /// Take the saved session with `device_key`, unless it expired
+    pub fn take(&mut self, device_key: &str) -> Option<PeerSession> {
+        let session = self.sessions.remove(device_key)?;
+        (clock::secs_since(session.saved_at) <= self.ttl_secs).then_some(session)
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::clock::{with_clock, ManualClock};
+    use std::sync::Arc;
+
+    fn session() -> PeerSession {
+        PeerSession {
+            mtu: Some(247),
+            protocol_version: Some(1),
+            digest: None,
+            partial_transfers: vec!["ab".into()],
+            saved_at: clock::now_secs(),
+        }
+    }
+
+    #[test]
+    fn test_sessions_resume_once_and_expire() {
+        let clock = Arc::new(ManualClock::new(1_000_000_000));
+        with_clock(clock.clone(), || {
+            let mut cache = PeerSessionCache::new(60);
+            cache.save("peer-a", session());
+            cache.save("peer-b", session());
+
+            assert_eq!(cache.take("peer-a"), Some(session()));
+            assert_eq!(cache.take("peer-a"), None);
+
+            clock.advance_secs(61);
+            assert_eq!(cache.take("peer-b"), None);
+            assert!(cache.is_empty());
+
+            // Long enough that none of the sessions below expires
+            let mut cache = PeerSessionCache::default();
+            for i in 0..=MAX_PEER_SESSIONS {
+                cache.save(&format!("peer-{}", i), session());
+                clock.advance_secs(1);
+            }
+            assert_eq!(cache.len(), MAX_PEER_SESSIONS);
+            assert!(cache.take("peer-0").is_none());
+        });
+    }
+}
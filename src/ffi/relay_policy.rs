@@ -0,0 +1,268 @@
+//! Per-origin relay limits
+//!
+//! A shared relay (e.g. a kiosk) accepts transactions from anyone nearby.
+//! [`RelayPolicy`] caps how many transactions per minute one origin may push
+//! through it, where an origin is a transaction's fee payer or the device it
+//! arrived from. Each origin gets a token bucket holding its per-minute limit;
+//! transactions beyond it are dropped and counted per origin so operators can
+//! see who is flooding the relay.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::queue::rate_limit::{BucketConfig, TokenBucket};
+
+/// Default transactions per minute per fee payer
+pub const DEFAULT_PER_FEE_PAYER_PER_MINUTE: u32 = 20;
+/// Default transactions per minute per sending device
+pub const DEFAULT_PER_DEVICE_PER_MINUTE: u32 = 60;
+/// Origins tracked before idle ones are forgotten
+const MAX_TRACKED_ORIGINS: usize = 4096;
+/// An origin idle this long has a full bucket again and can be forgotten
+const ORIGIN_IDLE: Duration = Duration::from_secs(60);
+/// Offenders listed in [`RelayPolicyStats`]
+const TOP_OFFENDERS: usize = 5;
+
+/// Relay limits; `None` leaves that kind of origin unlimited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayPolicyConfig {
+    #[serde(default = "default_per_fee_payer")]
+    pub per_fee_payer_per_minute: Option<u32>,
+    #[serde(default = "default_per_device")]
+    pub per_device_per_minute: Option<u32>,
+}
+
+fn default_per_fee_payer() -> Option<u32> {
+    Some(DEFAULT_PER_FEE_PAYER_PER_MINUTE)
+}
+
+fn default_per_device() -> Option<u32> {
+    Some(DEFAULT_PER_DEVICE_PER_MINUTE)
+}
+
+impl Default for RelayPolicyConfig {
+    fn default() -> Self {
+        Self {
+            per_fee_payer_per_minute: default_per_fee_payer(),
+            per_device_per_minute: default_per_device(),
+        }
+    }
+}
+
+/// Who a relayed transaction is attributed to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "id", rename_all = "camelCase")]
+pub enum Origin {
+    /// Base58 fee payer pubkey
+    FeePayer(String),
+    /// Peer id of the device the transaction arrived from
+    Device(String),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::FeePayer(id) => write!(f, "fee payer {}", id),
+            Origin::Device(id) => write!(f, "device {}", id),
+        }
+    }
+}
+
+/// Drop count for one origin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OriginDrops {
+    pub origin: Origin,
+    pub dropped: u64,
+}
+
+/// Relay policy counters
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayPolicyStats {
+    pub accepted: u64,
+    /// Dropped because the fee payer was over its limit
+    pub dropped_fee_payer: u64,
+    /// Dropped because the sending device was over its limit
+    pub dropped_device: u64,
+    pub tracked_origins: usize,
+    /// Tracked origins with the most drops, most first
+    pub top_offenders: Vec<OriginDrops>,
+}
+
+struct OriginState {
+    bucket: TokenBucket,
+    last_seen: Instant,
+    dropped: u64,
+}
+
+#[derive(Default)]
+struct PolicyState {
+    origins: HashMap<Origin, OriginState>,
+    accepted: u64,
+    dropped_fee_payer: u64,
+    dropped_device: u64,
+}
+
+/// Per-origin rate limits for transactions relayed through this device
+pub struct RelayPolicy {
+    config: RelayPolicyConfig,
+    state: Mutex<PolicyState>,
+}
+
+impl RelayPolicy {
+    pub fn new(config: RelayPolicyConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(PolicyState::default()),
+        }
+    }
+
+    pub fn config(&self) -> RelayPolicyConfig {
+        self.config
+    }
+
+    fn limit(&self, origin: &Origin) -> Option<u32> {
+        match origin {
+            Origin::FeePayer(_) => self.config.per_fee_payer_per_minute,
+            Origin::Device(_) => self.config.per_device_per_minute,
+        }
+    }
+
+    /// Admit a transaction from `fee_payer` arriving from `device` (either may
+    /// be unknown). A token is only taken from each origin once both have one,
+    /// so a transaction dropped for its fee payer does not use up the device's
+    /// budget. Returns the origin over its limit on a drop.
+    pub fn admit(&self, fee_payer: Option<&str>, device: Option<&str>) -> Result<(), Origin> {
+        let now = Instant::now();
+        let origins: Vec<(Origin, u32)> = [
+            fee_payer.map(|id| Origin::FeePayer(id.to_string())),
+            device.map(|id| Origin::Device(id.to_string())),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|origin| self.limit(&origin).map(|limit| (origin, limit)))
+        .collect();
+
+        let mut guard = self.state.lock();
+        let state = &mut *guard;
+        if state.origins.len() >= MAX_TRACKED_ORIGINS {
+            state
+                .origins
+                .retain(|_, o| now.saturating_duration_since(o.last_seen) < ORIGIN_IDLE);
+        }
+
+        for (origin, limit) in &origins {
+            let entry = state
+                .origins
+                .entry(origin.clone())
+                .or_insert_with(|| OriginState {
+                    bucket: TokenBucket::new(BucketConfig {
+                        capacity: *limit,
+                        refill_per_sec: *limit as f64 / 60.0,
+                    }),
+                    last_seen: now,
+                    dropped: 0,
+                });
+            entry.last_seen = now;
+            if entry.bucket.available() == 0 {
+                entry.dropped += 1;
+                match origin {
+                    Origin::FeePayer(_) => state.dropped_fee_payer += 1,
+                    Origin::Device(_) => state.dropped_device += 1,
+                }
+                tracing::warn!(
+                    "🚫 Relay limit reached for {}, dropping transaction",
+                    origin
+                );
+                return Err(origin.clone());
+            }
+        }
+
+        for (origin, _) in &origins {
+            if let Some(entry) = state.origins.get_mut(origin) {
+                entry.bucket.try_acquire();
+            }
+        }
+        state.accepted += 1;
+        Ok(())
+    }
+
+    pub fn stats(&self) -> RelayPolicyStats {
+        let state = self.state.lock();
+        let mut top_offenders: Vec<OriginDrops> = state
+            .origins
+            .iter()
+            .filter(|(_, o)| o.dropped > 0)
+            .map(|(origin, o)| OriginDrops {
+                origin: origin.clone(),
+                dropped: o.dropped,
+            })
+            .collect();
+        top_offenders.sort_by_key(|offender| std::cmp::Reverse(offender.dropped));
+        top_offenders.truncate(TOP_OFFENDERS);
+
+        RelayPolicyStats {
+            accepted: state.accepted,
+            dropped_fee_payer: state.dropped_fee_payer,
+            dropped_device: state.dropped_device,
+            tracked_origins: state.origins.len(),
+            top_offenders,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_each_origin_separately() {
+        let policy = RelayPolicy::new(RelayPolicyConfig {
+            per_fee_payer_per_minute: Some(2),
+            per_device_per_minute: Some(3),
+        });
+
+        assert!(policy.admit(Some("payer-a"), Some("kiosk-1")).is_ok());
+        assert!(policy.admit(Some("payer-a"), Some("kiosk-1")).is_ok());
+        assert_eq!(
+            policy.admit(Some("payer-a"), Some("kiosk-1")),
+            Err(Origin::FeePayer("payer-a".into()))
+        );
+
+        // The dropped transaction did not use up the device's budget
+        assert!(policy.admit(Some("payer-b"), Some("kiosk-1")).is_ok());
+        assert_eq!(
+            policy.admit(Some("payer-c"), Some("kiosk-1")),
+            Err(Origin::Device("kiosk-1".into()))
+        );
+        assert!(policy.admit(None, Some("kiosk-2")).is_ok());
+
+        let stats = policy.stats();
+        assert_eq!(stats.accepted, 4);
+        assert_eq!(stats.dropped_fee_payer, 1);
+        assert_eq!(stats.dropped_device, 1);
+        assert_eq!(stats.top_offenders.len(), 2);
+    }
+
+    #[test]
+    fn test_config_defaults_and_unlimited() {
+        let config: RelayPolicyConfig =
+            serde_json::from_str(r#"{"perDevicePerMinute":null}"#).unwrap();
+        assert_eq!(
+            config.per_fee_payer_per_minute,
+            Some(DEFAULT_PER_FEE_PAYER_PER_MINUTE)
+        );
+        assert_eq!(config.per_device_per_minute, None);
+
+        let policy = RelayPolicy::new(config);
+        for _ in 0..100 {
+            assert!(policy.admit(None, Some("kiosk-1")).is_ok());
+        }
+        assert_eq!(policy.stats().tracked_origins, 0);
+    }
+}
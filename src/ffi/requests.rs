@@ -0,0 +1,148 @@
+//! Background FFI requests
+//!
+//! Slow calls (network submission, backups, queue persistence) have `*Async`
+//! variants that return a request id immediately and run the work on a
+//! background thread, so hosts never block a UI thread. The result envelope is
+//! delivered through a completion callback (the engine event listener) and can
+//! also be collected with `poll`.
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::types::{result_envelope, FfiError};
+
+/// Completed results kept for polling; the oldest are dropped beyond this
+const MAX_COMPLETED_REQUESTS: usize = 256;
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+static REQUESTS: Lazy<Mutex<BTreeMap<u64, Option<String>>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+/// State of a background request
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status")]
+pub enum RequestPoll {
+    #[serde(rename = "PENDING")]
+    Pending,
+    /// `result` is the call's `FfiResult` envelope
+    #[serde(rename = "DONE")]
+    Done { result: serde_json::Value },
+}
+
+/// Run `job` on a background thread; returns the request id
+///
+/// `on_complete` receives the id and the `FfiResult` envelope JSON once the job
+/// finishes, on the background thread.
+pub fn start<J, C>(job: J, on_complete: C) -> Result<u64, FfiError>
+where
+    J: FnOnce() -> Result<String, FfiError> + Send + 'static,
+    C: FnOnce(u64, &str) + Send + 'static,
+{
+    let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+    REQUESTS.lock().insert(id, None);
+
+    let spawned = std::thread::Builder::new()
+        .name(format!("pollinet-request-{}", id))
+        .spawn(move || {
+            let envelope = result_envelope(job());
+            complete(id, envelope.clone());
+            on_complete(id, &envelope);
+        });
+    if let Err(e) = spawned {
+        REQUESTS.lock().remove(&id);
+        return Err(FfiError::internal(format!(
+            "Failed to start background request: {}",
+            e
+        )));
+    }
+    Ok(id)
+}
+
+/// Check on a request; a finished request is forgotten once polled
+pub fn poll(id: u64) -> Option<RequestPoll> {
+    let mut requests = REQUESTS.lock();
+    match requests.get(&id)? {
+        None => Some(RequestPoll::Pending),
+        Some(_) => {
+            let envelope = requests.remove(&id).flatten()?;
+            let result = serde_json::from_str(&envelope).unwrap_or(serde_json::Value::Null);
+            Some(RequestPoll::Done { result })
+        }
+    }
+}
+
+fn complete(id: u64, envelope: String) {
+    let mut requests = REQUESTS.lock();
+    requests.insert(id, Some(envelope));
+
+    let completed = requests.values().filter(|state| state.is_some()).count();
+    if completed > MAX_COMPLETED_REQUESTS {
+        let stale: Vec<u64> = requests
+            .iter()
+            .filter(|(_, state)| state.is_some())
+            .map(|(id, _)| *id)
+            .take(completed - MAX_COMPLETED_REQUESTS)
+            .collect();
+        for id in stale {
+            requests.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_request_completes_and_is_polled_once() {
+        let (tx, rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let id = start(
+            move || {
+                release_rx.recv().ok();
+                Ok(r#"{"ok":true,"data":42}"#.to_string())
+            },
+            move |id, envelope| {
+                tx.send((id, envelope.to_string())).unwrap();
+            },
+        )
+        .unwrap();
+
+        assert_eq!(poll(id), Some(RequestPoll::Pending));
+        release_tx.send(()).unwrap();
+
+        let (done_id, envelope) = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(done_id, id);
+        assert_eq!(envelope, r#"{"ok":true,"data":42}"#);
+        assert_eq!(
+            poll(id),
+            Some(RequestPoll::Done {
+                result: serde_json::json!({ "ok": true, "data": 42 })
+            })
+        );
+        assert_eq!(poll(id), None);
+    }
+
+    #[test]
+    fn test_failed_request_carries_error_code() {
+        let (tx, rx) = mpsc::channel();
+        let id = start(
+            || Err(FfiError::not_found("gone")),
+            move |_, envelope| tx.send(envelope.to_string()).unwrap(),
+        )
+        .unwrap();
+        rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        match poll(id) {
+            Some(RequestPoll::Done { result }) => {
+                assert_eq!(result["ok"], false);
+                assert_eq!(result["code"], "ERR_NOT_FOUND");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! Multiple RPC endpoints with health scoring and failover
+//!
+//! [`RpcPool`] keeps a latency average and failure count per endpoint and
+//! tries them best-first. Transport failures (connection errors, timeouts,
+//! unhealthy nodes) move on to the next endpoint; an endpoint failing
+//! repeatedly cools down and is only tried after the others. Responses about
+//! the transaction itself (preflight failures, bad signatures) are returned
+//! as-is, since another endpoint would give the same answer.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY;
+use solana_client::rpc_request::RpcError;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Consecutive failures before an endpoint cools down
+pub const FAILURES_BEFORE_COOLDOWN: u32 = 3;
+/// How long a failing endpoint is tried only as a last resort
+pub const ENDPOINT_COOLDOWN: Duration = Duration::from_secs(30);
+/// Latency assumed for endpoints without a successful call yet
+const UNKNOWN_LATENCY_MS: f64 = 1000.0;
+/// Weight of the newest sample in the latency average
+const LATENCY_EWMA_ALPHA: f64 = 0.3;
+
+#[derive(Default)]
+struct EndpointStats {
+    avg_latency_ms: Option<f64>,
+    successes: u64,
+    failures: u64,
+    consecutive_failures: u32,
+    cooldown_until: Option<Instant>,
+}
+
+impl EndpointStats {
+    fn cooling_down(&self, now: Instant) -> bool {
+        self.cooldown_until.is_some_and(|until| until > now)
+    }
+
+    /// Lower is better
+    fn score(&self) -> f64 {
+        self.avg_latency_ms.unwrap_or(UNKNOWN_LATENCY_MS) * (1 + self.consecutive_failures) as f64
+    }
+}
+
+struct Endpoint {
+    url: String,
+    client: Arc<RpcClient>,
+    stats: Mutex<EndpointStats>,
+}
+
+/// Health of one endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointHealth {
+    pub url: String,
+    /// Average latency of successful calls, once there is one
+    pub avg_latency_ms: Option<u64>,
+    pub successes: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    pub cooling_down: bool,
+}
+
+/// RPC endpoints tried best-first
+pub struct RpcPool {
+    endpoints: Vec<Endpoint>,
+}
+
+impl RpcPool {
+    /// Pool over `urls` (blank and duplicate entries are skipped); fails if none remain
+    pub fn new<I, S>(urls: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut endpoints: Vec<Endpoint> = Vec::new();
+        for url in urls {
+            let url = url.into().trim().to_string();
+            if url.is_empty() || endpoints.iter().any(|e| e.url == url) {
+                continue;
+            }
+            endpoints.push(Endpoint {
+                client: Arc::new(RpcClient::new(url.clone())),
+                url,
+                stats: Mutex::new(EndpointStats::default()),
+            });
+        }
+        if endpoints.is_empty() {
+            return Err("No RPC endpoint configured".to_string());
+        }
+        Ok(Self { endpoints })
+    }
+
+    /// Pool from a comma-separated URL list
+    pub fn from_list(urls: &str) -> Result<Self, String> {
+        Self::new(urls.split(','))
+    }
+
+    /// Endpoint URLs in configured order
+    pub fn urls(&self) -> Vec<&str> {
+        self.endpoints.iter().map(|e| e.url.as_str()).collect()
+    }
+
+    /// Per-endpoint health, in configured order
+    pub fn health(&self) -> Vec<EndpointHealth> {
+        let now = Instant::now();
+        self.endpoints
+            .iter()
+            .map(|e| {
+                let stats = e.stats.lock();
+                EndpointHealth {
+                    url: e.url.clone(),
+                    avg_latency_ms: stats.avg_latency_ms.map(|ms| ms.round() as u64),
+                    successes: stats.successes,
+                    failures: stats.failures,
+                    consecutive_failures: stats.consecutive_failures,
+                    cooling_down: stats.cooling_down(now),
+                }
+            })
+            .collect()
+    }
+
+    /// Endpoint indices in the order they should be tried
+    fn ranked(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let mut order: Vec<(usize, bool, f64)> = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let stats = e.stats.lock();
+                (i, stats.cooling_down(now), stats.score())
+            })
+            .collect();
+        // Stable: ties keep the configured order
+        order.sort_by(|a, b| a.1.cmp(&b.1).then(a.2.total_cmp(&b.2)));
+        order.into_iter().map(|(i, _, _)| i).collect()
+    }
+
+    /// Run `op` against endpoints best-first until one answers. Returns the
+    /// last error when every endpoint fails.
+    pub async fn call<T, F, Fut>(&self, op: F) -> Result<T, ClientError>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let mut last_error = None;
+        for i in self.ranked() {
+            let endpoint = &self.endpoints[i];
+            let started = Instant::now();
+            match op(endpoint.client.clone()).await {
+                Ok(value) => {
+                    self.record_success(endpoint, started.elapsed());
+                    return Ok(value);
+                }
+                Err(e) if is_endpoint_failure(&e) => {
+                    tracing::warn!("⚠️ RPC endpoint {} failed: {}", endpoint.url, e);
+                    self.record_failure(endpoint);
+                    last_error = Some(e);
+                }
+                Err(e) => {
+                    // The endpoint answered; the request itself was rejected
+                    self.record_success(endpoint, started.elapsed());
+                    return Err(e);
+                }
+            }
+        }
+        Err(last_error.expect("pool has at least one endpoint"))
+    }
+
+    fn record_success(&self, endpoint: &Endpoint, latency: Duration) {
+        let sample = latency.as_secs_f64() * 1000.0;
+        let mut stats = endpoint.stats.lock();
+        stats.avg_latency_ms = Some(match stats.avg_latency_ms {
+            Some(avg) => avg + LATENCY_EWMA_ALPHA * (sample - avg),
+            None => sample,
+        });
+        stats.successes += 1;
+        stats.consecutive_failures = 0;
+        stats.cooldown_until = None;
+    }
+
+    fn record_failure(&self, endpoint: &Endpoint) {
+        let mut stats = endpoint.stats.lock();
+        stats.failures += 1;
+        stats.consecutive_failures += 1;
+        if stats.consecutive_failures >= FAILURES_BEFORE_COOLDOWN {
+            stats.cooldown_until = Some(Instant::now() + ENDPOINT_COOLDOWN);
+        }
+    }
+}
+
+/// Whether another endpoint might succeed where this one failed
+fn is_endpoint_failure(error: &ClientError) -> bool {
+    match error.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            *code == JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+        }
+        ClientErrorKind::TransactionError(_) | ClientErrorKind::SigningError(_) => false,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transport_error() -> ClientError {
+        ClientErrorKind::Io(std::io::Error::other("connection refused")).into()
+    }
+
+    #[tokio::test]
+    async fn test_failover_and_cooldown_reorder_endpoints() {
+        let pool = RpcPool::from_list("http://a, http://b,http://a,").unwrap();
+        assert_eq!(pool.urls(), vec!["http://a", "http://b"]);
+
+        // "a" is down, "b" answers and moves to the front
+        let url = pool
+            .call(|client| async move {
+                if client.url().contains("//a") {
+                    Err(transport_error())
+                } else {
+                    Ok(client.url())
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(url, "http://b");
+        assert_eq!(pool.ranked(), vec![1, 0]);
+
+        // Both down: "a" reaches the cooldown threshold first
+        for _ in 1..FAILURES_BEFORE_COOLDOWN {
+            let result: Result<(), _> = pool.call(|_| async { Err(transport_error()) }).await;
+            assert!(result.is_err());
+        }
+        let health = pool.health();
+        assert!(health[0].cooling_down);
+        assert!(!health[1].cooling_down);
+        assert_eq!(health[0].failures, FAILURES_BEFORE_COOLDOWN as u64);
+
+        // Rejections of the request itself are not failed over
+        let rejected: Result<(), _> = pool
+            .call(|_| async {
+                Err(ClientErrorKind::TransactionError(
+                    solana_sdk::transaction::TransactionError::AccountNotFound,
+                )
+                .into())
+            })
+            .await;
+        assert!(rejected.is_err());
+        let health = pool.health();
+        assert_eq!(health[0].failures, FAILURES_BEFORE_COOLDOWN as u64);
+        assert_eq!(health[1].consecutive_failures, 0);
+    }
+}
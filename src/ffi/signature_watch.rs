@@ -0,0 +1,178 @@
+//! Confirmation tracking for submitted transactions
+//!
+//! [`SignatureWatcher`] waits for a signature to reach `confirmed` commitment
+//! using a PubSub `signatureSubscribe`, so a gateway submitting many mesh
+//! transactions does not poll RPC for each. When the websocket is unavailable
+//! (no URL, connection refused, subscription dropped) it falls back to
+//! polling `getSignatureStatuses` through the [`RpcPool`].
+
+use futures::StreamExt;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::RpcSignatureSubscribeConfig;
+use solana_client::rpc_response::RpcSignatureResult;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::rpc_pool::RpcPool;
+
+/// Default time to wait for a confirmation
+pub const DEFAULT_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+/// Default pause between status polls in fallback mode
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How a submitted transaction ended up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureOutcome {
+    Confirmed,
+    /// Landed but failed on chain
+    Failed(String),
+    /// Not confirmed in time (it may still land)
+    TimedOut,
+}
+
+/// Waits for signatures to confirm
+pub struct SignatureWatcher {
+    pool: Arc<RpcPool>,
+    ws_url: Option<String>,
+    timeout: Duration,
+    poll_interval: Duration,
+}
+
+impl SignatureWatcher {
+    /// Watcher subscribing on the websocket of the pool's first endpoint
+    pub fn new(pool: Arc<RpcPool>) -> Self {
+        let ws_url = pool.urls().first().and_then(|url| websocket_url(url));
+        Self {
+            pool,
+            ws_url,
+            timeout: DEFAULT_CONFIRMATION_TIMEOUT,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Use `ws_url` for subscriptions, or only poll with `None`
+    pub fn with_ws_url(mut self, ws_url: Option<String>) -> Self {
+        self.ws_url = ws_url;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Wait for `signature` (base58) to confirm
+    pub async fn wait(&self, signature: &str) -> SignatureOutcome {
+        let Ok(signature) = Signature::from_str(signature) else {
+            return SignatureOutcome::Failed(format!("Invalid signature: {}", signature));
+        };
+        let deadline = Instant::now() + self.timeout;
+
+        if let Some(ws_url) = &self.ws_url {
+            match self.subscribe(ws_url, &signature, deadline).await {
+                Ok(outcome) => return outcome,
+                Err(e) => {
+                    tracing::debug!("Signature subscription unavailable ({}), polling", e);
+                }
+            }
+        }
+        self.poll(&signature, deadline).await
+    }
+
+    async fn subscribe(
+        &self,
+        ws_url: &str,
+        signature: &Signature,
+        deadline: Instant,
+    ) -> Result<SignatureOutcome, String> {
+        let client = PubsubClient::new(ws_url)
+            .await
+            .map_err(|e| format!("PubSub connect failed: {}", e))?;
+        let config = RpcSignatureSubscribeConfig {
+            commitment: Some(CommitmentConfig::confirmed()),
+            enable_received_notification: Some(false),
+        };
+        let (mut notifications, unsubscribe) = client
+            .signature_subscribe(signature, Some(config))
+            .await
+            .map_err(|e| format!("signatureSubscribe failed: {}", e))?;
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let notification = tokio::time::timeout(remaining, notifications.next()).await;
+        drop(notifications);
+        unsubscribe().await;
+
+        match notification {
+            Ok(Some(response)) => match response.value {
+                RpcSignatureResult::ProcessedSignature(result) => Ok(match result.err {
+                    None => SignatureOutcome::Confirmed,
+                    Some(err) => SignatureOutcome::Failed(err.to_string()),
+                }),
+                RpcSignatureResult::ReceivedSignature(_) => {
+                    Err("unexpected received notification".to_string())
+                }
+            },
+            Ok(None) => Err("subscription closed".to_string()),
+            Err(_) => Ok(SignatureOutcome::TimedOut),
+        }
+    }
+
+    async fn poll(&self, signature: &Signature, deadline: Instant) -> SignatureOutcome {
+        loop {
+            let statuses = self
+                .pool
+                .call(|client| async move { client.get_signature_statuses(&[*signature]).await })
+                .await;
+            match statuses {
+                Ok(response) => {
+                    if let Some(Some(status)) = response.value.first() {
+                        if let Some(err) = &status.err {
+                            return SignatureOutcome::Failed(err.to_string());
+                        }
+                        if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+                            return SignatureOutcome::Confirmed;
+                        }
+                    }
+                }
+                Err(e) => tracing::debug!("Signature status poll failed: {}", e),
+            }
+
+            if Instant::now() + self.poll_interval >= deadline {
+                return SignatureOutcome::TimedOut;
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
+
+/// PubSub URL served alongside an RPC URL (`http` → `ws`, `https` → `wss`)
+pub fn websocket_url(rpc_url: &str) -> Option<String> {
+    if let Some(rest) = rpc_url.strip_prefix("https://") {
+        Some(format!("wss://{}", rest))
+    } else {
+        rpc_url
+            .strip_prefix("http://")
+            .map(|rest| format!("ws://{}", rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_websocket_url_follows_scheme() {
+        assert_eq!(
+            websocket_url("https://api.devnet.solana.com").as_deref(),
+            Some("wss://api.devnet.solana.com")
+        );
+        assert_eq!(
+            websocket_url("http://10.0.0.2:8899").as_deref(),
+            Some("ws://10.0.0.2:8899")
+        );
+        assert_eq!(websocket_url("ftp://example.com"), None);
+    }
+}
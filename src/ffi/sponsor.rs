@@ -0,0 +1,239 @@
+//! Fee-payer sponsorship for gasless offline payments
+//!
+//! A sender without SOL builds its transaction with a sponsor's pubkey as fee
+//! payer and signs only its own slots, leaving the fee-payer signature (slot 0)
+//! empty. A gateway holding a [`FeePayerProvider`] fills that slot before
+//! submission, either with a local sponsor keypair or by calling a sponsorship
+//! API. The sponsor pubkey has to be known when building: every signature
+//! covers the whole message, fee payer included, so the fee payer cannot be
+//! swapped in after the sender has signed.
+//!
+//! The sponsor only pays fees: transactions whose instructions reference the
+//! sponsor account (e.g. a transfer out of it) are refused.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::VersionedTransaction;
+use std::sync::Arc;
+
+/// Signs the fee-payer slot of sponsored transactions
+#[async_trait]
+pub trait FeePayerProvider: Send + Sync {
+    /// Fee payer senders build sponsored transactions with
+    fn pubkey(&self) -> Pubkey;
+
+    /// Sign a serialized transaction message as fee payer
+    async fn sign(&self, message: &[u8]) -> Result<Signature, String>;
+}
+
+/// Sponsor keypair held by the gateway
+pub struct KeypairFeePayer {
+    keypair: Keypair,
+}
+
+impl KeypairFeePayer {
+    pub fn new(keypair: Keypair) -> Self {
+        Self { keypair }
+    }
+
+    /// From a base58-encoded 64-byte secret key
+    pub fn from_base58(secret_key: &str) -> Result<Self, String> {
+        let bytes = bs58::decode(secret_key.trim())
+            .into_vec()
+            .map_err(|e| format!("Invalid sponsor secret key: {}", e))?;
+        let keypair = Keypair::try_from(bytes.as_slice())
+            .map_err(|e| format!("Invalid sponsor secret key: {}", e))?;
+        Ok(Self::new(keypair))
+    }
+}
+
+#[async_trait]
+impl FeePayerProvider for KeypairFeePayer {
+    fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature, String> {
+        Ok(self.keypair.sign_message(message))
+    }
+}
+
+/// Sponsorship API: `POST url` with `{"feePayer", "message"}` (base64 message)
+/// answering `{"signature"}` (base58)
+#[cfg(feature = "reqwest")]
+pub struct HttpFeePayer {
+    url: String,
+    pubkey: Pubkey,
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl HttpFeePayer {
+    pub fn new(url: impl Into<String>, pubkey: Pubkey) -> Self {
+        Self {
+            url: url.into(),
+            pubkey,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+#[async_trait]
+impl FeePayerProvider for HttpFeePayer {
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature, String> {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+
+        #[derive(Deserialize)]
+        struct SignResponse {
+            signature: String,
+        }
+
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&serde_json::json!({
+                "feePayer": self.pubkey.to_string(),
+                "message": STANDARD.encode(message),
+            }))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Sponsorship request failed: {}", e))?;
+        let body: SignResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid sponsorship response: {}", e))?;
+        body.signature
+            .parse::<Signature>()
+            .map_err(|e| format!("Invalid sponsor signature: {}", e))
+    }
+}
+
+/// Sponsor settings as passed by hosts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SponsorConfig {
+    /// Local keypair (base58 secret key)
+    #[serde(rename_all = "camelCase")]
+    Keypair { secret_key: String },
+    /// Sponsorship API signing for `pubkey`
+    #[serde(rename_all = "camelCase")]
+    Http { url: String, pubkey: String },
+}
+
+impl SponsorConfig {
+    pub fn into_provider(self) -> Result<Arc<dyn FeePayerProvider>, String> {
+        match self {
+            SponsorConfig::Keypair { secret_key } => {
+                Ok(Arc::new(KeypairFeePayer::from_base58(&secret_key)?))
+            }
+            #[cfg(feature = "reqwest")]
+            SponsorConfig::Http { url, pubkey } => {
+                let pubkey = pubkey
+                    .parse::<Pubkey>()
+                    .map_err(|e| format!("Invalid sponsor pubkey: {}", e))?;
+                Ok(Arc::new(HttpFeePayer::new(url, pubkey)))
+            }
+            #[cfg(not(feature = "reqwest"))]
+            SponsorConfig::Http { .. } => {
+                Err("Sponsorship APIs need the reqwest feature".to_string())
+            }
+        }
+    }
+}
+
+/// Whether `tx` names `sponsor` as fee payer with the fee-payer slot unsigned
+pub fn needs_sponsor(tx: &VersionedTransaction, sponsor: &Pubkey) -> bool {
+    tx.message.static_account_keys().first() == Some(sponsor)
+        && tx.signatures.first() == Some(&Signature::default())
+}
+
+/// Fill the fee-payer slot of `tx` if it asks `provider` to sponsor it;
+/// returns false when the transaction is not sponsored by `provider`
+pub async fn sponsor(
+    provider: &dyn FeePayerProvider,
+    tx: &mut VersionedTransaction,
+) -> Result<bool, String> {
+    let sponsor = provider.pubkey();
+    if !needs_sponsor(tx, &sponsor) {
+        return Ok(false);
+    }
+    // Account index 0 is the fee payer; it may only pay fees
+    let uses_sponsor = tx
+        .message
+        .instructions()
+        .iter()
+        .any(|ix| ix.program_id_index == 0 || ix.accounts.contains(&0));
+    if uses_sponsor {
+        return Err("Sponsored transaction uses the sponsor account".to_string());
+    }
+
+    let message = tx.message.serialize();
+    let signature = provider.sign(&message).await?;
+    if !signature.verify(sponsor.as_ref(), &message) {
+        return Err("Sponsor signature does not verify".to_string());
+    }
+    tx.signatures[0] = signature;
+    tracing::info!("💸 Sponsored fee payer for tx {}", signature);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::message::{Message, VersionedMessage};
+
+    fn sender_signed(sponsor: &Pubkey, sender: &Keypair, from: &Pubkey) -> VersionedTransaction {
+        let ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1],
+            vec![
+                AccountMeta::new(*from, true),
+                AccountMeta::new(Pubkey::new_unique(), false),
+            ],
+        );
+        let message = Message::new_with_blockhash(&[ix], Some(sponsor), &Hash::new_unique());
+        let message = VersionedMessage::Legacy(message);
+        let mut tx = VersionedTransaction {
+            signatures: vec![Signature::default(); 2],
+            message,
+        };
+        tx.signatures[1] = sender.sign_message(&tx.message.serialize());
+        tx
+    }
+
+    #[tokio::test]
+    async fn test_keypair_sponsor_fills_fee_payer_slot() {
+        let provider = KeypairFeePayer::new(Keypair::new());
+        let sender = Keypair::new();
+
+        let mut tx = sender_signed(&provider.pubkey(), &sender, &sender.pubkey());
+        assert!(sponsor(&provider, &mut tx).await.unwrap());
+        assert!(tx.verify_with_results().iter().all(|ok| *ok));
+        // Already signed
+        assert!(!sponsor(&provider, &mut tx).await.unwrap());
+
+        // Not sponsored by this provider
+        let mut other = sender_signed(&Pubkey::new_unique(), &sender, &sender.pubkey());
+        assert!(!sponsor(&provider, &mut other).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_sponsor_refuses_spending_its_account() {
+        let provider = KeypairFeePayer::new(Keypair::new());
+        let sender = Keypair::new();
+
+        let mut tx = sender_signed(&provider.pubkey(), &sender, &provider.pubkey());
+        assert!(sponsor(&provider, &mut tx).await.is_err());
+        assert_eq!(tx.signatures[0], Signature::default());
+    }
+}
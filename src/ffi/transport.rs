@@ -1,22 +1,29 @@
 //! Host-driven BLE transport layer
 //!
-//! This module provides a transport mechanism where the host platform (Android)
-//! drives BLE operations, and Rust only handles packetization, reassembly, and
-//! protocol state.
-
-use super::types::{Fragment, FragmentReassemblyInfo, MetricsSnapshot};
+//! This module provides a transport mechanism where the host platform (Android,
+//! iOS or a desktop embedder) drives BLE operations, and Rust only handles
+//! packetization, reassembly, and protocol state. Nothing here is
+//! platform-specific; with the `android` feature, log lines are mirrored to logcat.
+
+use super::types::{
+    DeviceIdentity, FfiError, FfiErrorCode, Fragment, FragmentReassemblyInfo, MetricsSnapshot,
+    ProtocolEvent, SdkConfig,
+};
 use crate::ble::mesh::TransactionFragment;
+use crate::ble::wire::frame_transaction_id;
 use crate::ble::MeshHealthMonitor;
-use crate::storage::SecureStorage;
+use crate::storage::{KeyProvider, SecureStorage};
 use parking_lot::Mutex;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::Duration;
 
 /// Type alias for the completed transactions queue
 type CompletedTxQueue = Arc<Mutex<VecDeque<(String, Vec<u8>)>>>;
 /// Type alias for the received transaction queue (tx_id, tx_bytes, timestamp)
 type ReceivedTxQueue = Arc<Mutex<VecDeque<(String, Vec<u8>, u64)>>>;
+/// Host callback invoked for every [`ProtocolEvent`]
+pub type EventCallback = Arc<dyn Fn(&ProtocolEvent) + Send + Sync>;
 
 // Unified logging macros for transport layer:
 // - On Android: mirror all messages to log::debug! (for android_logger / logcat),
@@ -83,10 +90,7 @@ macro_rules! t_error {
 const MAX_MTU: usize = 512;
 
 /// Maximum number of distinct transactions buffered for reassembly at once
-const MAX_PENDING_TRANSACTIONS: usize = 64;
-
-/// Maximum number of fragments buffered per transaction
-const MAX_FRAGMENTS_PER_TRANSACTION: usize = 256;
+const MAX_PENDING_TRANSACTIONS: usize = crate::ble::fragmenter::MAX_REASSEMBLY_TRANSACTIONS;
 
 /// Maximum number of transactions in the received-TX queue (awaiting RPC submission)
 const MAX_RECEIVED_QUEUE_SIZE: usize = 1000;
@@ -94,6 +98,21 @@ const MAX_RECEIVED_QUEUE_SIZE: usize = 1000;
 /// Maximum number of outbound BLE frames queued for sending
 const MAX_OUTBOUND_FRAMES: usize = 5000;
 
+/// Maximum number of relay receipts waiting to be sent back
+const MAX_OUTBOUND_RECEIPTS: usize = 256;
+
+/// Maximum number of peer transaction digests kept
+const MAX_PEER_DIGESTS: usize = 64;
+
+/// Maximum number of transaction origin keys kept
+const MAX_TX_ORIGINS: usize = 1024;
+
+/// Maximum number of device keys pinned by short id
+const MAX_PINNED_KEYS: usize = 1024;
+
+/// Secure-storage key of the device signing key
+const DEVICE_KEY_STORAGE_KEY: &str = "secrets/device_key";
+
 /// Host-driven BLE transport bridge
 pub struct HostBleTransport {
     /// Queue of outbound frames ready to send
@@ -102,6 +121,16 @@ pub struct HostBleTransport {
     /// Inbound reassembly buffers keyed by transaction ID
     pub inbound_buffers: Arc<Mutex<HashMap<String, Vec<TransactionFragment>>>>,
 
+    /// Bytes held by `inbound_buffers`, per transaction and in total
+    inbound_budget: Mutex<crate::ble::fragmenter::ReassemblyBudget>,
+
+    /// Largest inbound frame accepted (the negotiated MTU), if set
+    inbound_mtu: Mutex<Option<usize>>,
+
+    /// Idle time before a partial transaction is discarded, and whether
+    /// its sender is then asked to restart it
+    reassembly_timeout: Mutex<(Duration, bool)>,
+
     /// Completed transactions ready for processing
     completed_transactions: CompletedTxQueue,
 
@@ -151,6 +180,108 @@ pub struct HostBleTransport {
     /// Pending confirmations waiting to be queued as outbound carrier entries.
     /// Keyed by tx_id_hash hex. Written by `ingest_confirmation`, read by FFI.
     pub pending_confirmations: Mutex<VecDeque<crate::ble::MeshConfirmation>>,
+
+    /// Disk budget for the storage directory (enforced from `tick`)
+    storage_quota: Mutex<Option<crate::storage::StorageQuota>>,
+
+    /// Persistent text/control message history (enabled with a storage directory)
+    message_log: Mutex<Option<Arc<crate::storage::MessageLog>>>,
+
+    /// Received-transaction history (enabled with a storage directory)
+    tx_history: Mutex<Option<Arc<crate::storage::TxHistory>>>,
+
+    /// Mesh activity statistics (enabled with a storage directory)
+    mesh_stats: Mutex<Option<Arc<crate::storage::MeshStats>>>,
+
+    /// Peer each in-flight inbound transaction arrived from, keyed by tx_id
+    inbound_peers: Mutex<HashMap<String, String>>,
+
+    /// Frames handed over by radio callbacks, processed from `tick`
+    inbound_queue: super::inbound_queue::InboundQueue,
+
+    /// Internet connectivity (host-reported or probed)
+    connectivity: Arc<super::connectivity::ConnectivityMonitor>,
+
+    /// Auto-submit gateway, while running
+    gateway: Mutex<Option<super::gateway::GatewayHandle>>,
+
+    /// Per-origin limits on received transactions (shared relays)
+    relay_policy: Mutex<Option<Arc<super::relay_policy::RelayPolicy>>>,
+
+    /// Device signing key for relay receipts (persisted with secure storage,
+    /// ephemeral otherwise)
+    device_key: ed25519_dalek::SigningKey,
+    /// Whether `device_key` was loaded from or saved to secure storage
+    device_key_persisted: bool,
+
+    /// Advertisement privacy mode
+    privacy: Mutex<crate::ble::PrivacySettings>,
+
+    /// Issued and received relay receipts (enabled with a storage directory)
+    relay_receipts: Mutex<Option<Arc<crate::storage::ReceiptStore>>>,
+
+    /// Receipts waiting to be sent back toward an origin: (peer_id, receipt)
+    outbound_receipts: Mutex<VecDeque<(String, crate::ble::RelayReceipt)>>,
+
+    /// Sequence number of the last envelope this device sealed (seeded from
+    /// the clock so it keeps increasing across restarts)
+    message_sequence: std::sync::atomic::AtomicU64,
+
+    /// Sequences seen per verified envelope origin, to reject replays
+    replay_window: Mutex<crate::ble::ReplayWindow>,
+
+    /// Envelope origin keys by short id: (key, pinned by the host rather
+    /// than on first use). Only host-pinned origins may send control messages.
+    pinned_keys: Mutex<HashMap<crate::ble::probe::NodeId, ([u8; 32], bool)>>,
+
+    /// Transaction digests received from connected peers, by peer id
+    peer_digests: Mutex<HashMap<String, crate::ble::TxDigest>>,
+
+    /// Capability bytes of peers, by peer id: advertised, or learned from
+    /// the frames they send. Peers without `COMPACT_FRAMES` get legacy
+    /// fragment frames.
+    peer_capabilities: Mutex<HashMap<String, u8>>,
+
+    /// Device key of each transaction's origin, by tx id, as carried in its
+    /// first fragment. TX_CANCEL frames must be signed by it.
+    tx_origins: Mutex<HashMap<String, [u8; 32]>>,
+
+    /// Sessions with peers that went out of range, by device key
+    peer_sessions: Mutex<super::peer_sessions::PeerSessionCache>,
+
+    /// Pings and traceroutes in flight, their results and relayed probe ids
+    probes: Mutex<crate::ble::ProbeTracker>,
+
+    /// Emergency notice ids already seen, and the notices received
+    emergency_seen: Mutex<VecDeque<[u8; 16]>>,
+    emergency_inbox: Mutex<VecDeque<crate::ble::EmergencyNotice>>,
+
+    /// Payment requests received from merchants, not yet paid
+    payment_requests: Mutex<VecDeque<crate::ble::PaymentRequest>>,
+
+    /// Payment requests this device issued, to match incoming receipts
+    issued_payment_requests: Mutex<VecDeque<crate::ble::PaymentRequest>>,
+
+    /// Signed payment receipts, as payer and as merchant (enabled with a
+    /// storage directory)
+    payment_receipts: Mutex<Option<Arc<crate::storage::PaymentReceiptStore>>>,
+
+    /// Host event callback, so hosts can react without polling
+    event_callback: Mutex<Option<EventCallback>>,
+
+    /// Text message outbox/inbox and pending text frames
+    text_messaging: super::messaging::TextMessaging,
+
+    /// Fragment payload for `queue_transaction` calls that pass none
+    default_fragment_payload: Mutex<Option<usize>>,
+    /// Maximum age of nonces passed to `build_payment_transaction`
+    nonce_staleness: Mutex<super::nonce_accounts::NonceStalenessPolicy>,
+
+    /// Transactions the host asked to hear about
+    tx_watch: super::tx_watch::TxWatchList,
+
+    /// Saved recipients (enabled with a storage directory)
+    address_book: Mutex<Option<Arc<crate::storage::AddressBook>>>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -158,6 +289,9 @@ struct TransportMetrics {
     fragments_buffered: u32,
     transactions_complete: u32,
     reassembly_failures: u32,
+    corrupt_fragments: u32,
+    reassembly_evictions: u32,
+    reassembly_timeouts: u32,
     last_error: String,
     updated_at: u64,
 }
@@ -173,25 +307,7 @@ impl HostBleTransport {
 
         t_info!("✅ PolliNetSDK created (no RPC)");
 
-        let transport = Self {
-            outbound_queue: Arc::new(Mutex::new(VecDeque::new())),
-            inbound_buffers: Arc::new(Mutex::new(HashMap::new())),
-            completed_transactions: Arc::new(Mutex::new(VecDeque::new())),
-            received_tx_queue: Arc::new(Mutex::new(VecDeque::new())),
-            received_tx_hash_set: Arc::new(Mutex::new(HashSet::new())),
-            submitted_tx_hashes: Arc::new(Mutex::new(HashMap::new())),
-            metrics: Arc::new(Mutex::new(TransportMetrics::default())),
-            secure_storage: None,
-            health_monitor: Arc::new(MeshHealthMonitor::default()),
-            sdk: Arc::new(sdk),
-            queue_storage_dir: Mutex::new(None),
-            wallet_address: Mutex::new(None),
-            pollicore_url: Mutex::new(None),
-            density_estimator: Mutex::new(crate::ble::DensityEstimator::new()),
-            cooldown_list: Mutex::new(crate::ble::CooldownList::new()),
-            tombstones: Mutex::new(HashMap::new()),
-            pending_confirmations: Mutex::new(VecDeque::new()),
-        };
+        let transport = Self::with_sdk(Arc::new(sdk));
 
         t_info!("✅ HostBleTransport::new() initialized");
         Ok(transport)
@@ -210,9 +326,37 @@ impl HostBleTransport {
 
         t_info!("✅ PolliNetSDK created with RPC");
 
-        let transport = Self {
+        let transport = Self::with_sdk(Arc::new(sdk));
+
+        t_info!("✅ HostBleTransport::new_with_rpc() initialized");
+        Ok(transport)
+    }
+
+    /// Create from an `SdkConfig`, the init path shared by every binding layer
+    /// and the CLI: checks compatibility, builds the SDK with the config's
+    /// queue storage and limits, then applies the rest of the config
+    pub async fn from_config(config: &SdkConfig) -> Result<Self, FfiError> {
+        config.check_compatibility()?;
+        let sdk = crate::PolliNetSDK::from_config(config)
+            .await
+            .map_err(FfiError::from)?;
+        let mut transport = Self::with_sdk(Arc::new(sdk));
+        transport.apply_config(config)?;
+        Ok(transport)
+    }
+
+    /// Create around an existing SDK instance, e.g. one whose queues persist
+    /// (see `PolliNetSDK::new_with_storage`)
+    pub fn with_sdk(sdk: Arc<crate::PolliNetSDK>) -> Self {
+        Self {
             outbound_queue: Arc::new(Mutex::new(VecDeque::new())),
             inbound_buffers: Arc::new(Mutex::new(HashMap::new())),
+            inbound_budget: Mutex::new(Default::default()),
+            inbound_mtu: Mutex::new(None),
+            reassembly_timeout: Mutex::new((
+                Duration::from_secs(crate::ble::fragmenter::DEFAULT_REASSEMBLY_TIMEOUT_SECS),
+                false,
+            )),
             completed_transactions: Arc::new(Mutex::new(VecDeque::new())),
             received_tx_queue: Arc::new(Mutex::new(VecDeque::new())),
             received_tx_hash_set: Arc::new(Mutex::new(HashSet::new())),
@@ -220,7 +364,8 @@ impl HostBleTransport {
             metrics: Arc::new(Mutex::new(TransportMetrics::default())),
             secure_storage: None,
             health_monitor: Arc::new(MeshHealthMonitor::default()),
-            sdk: Arc::new(sdk),
+            connectivity: Arc::new(super::connectivity::ConnectivityMonitor::new(sdk.clone())),
+            sdk,
             queue_storage_dir: Mutex::new(None),
             wallet_address: Mutex::new(None),
             pollicore_url: Mutex::new(None),
@@ -228,23 +373,60 @@ impl HostBleTransport {
             cooldown_list: Mutex::new(crate::ble::CooldownList::new()),
             tombstones: Mutex::new(HashMap::new()),
             pending_confirmations: Mutex::new(VecDeque::new()),
-        };
-
-        t_info!("✅ HostBleTransport::new_with_rpc() initialized");
-        Ok(transport)
+            storage_quota: Mutex::new(None),
+            message_log: Mutex::new(None),
+            tx_history: Mutex::new(None),
+            mesh_stats: Mutex::new(None),
+            inbound_peers: Mutex::new(HashMap::new()),
+            inbound_queue: Default::default(),
+            gateway: Mutex::new(None),
+            relay_policy: Mutex::new(None),
+            device_key: crate::ble::generate_device_key(),
+            device_key_persisted: false,
+            privacy: Mutex::new(Default::default()),
+            relay_receipts: Mutex::new(None),
+            outbound_receipts: Mutex::new(VecDeque::new()),
+            message_sequence: std::sync::atomic::AtomicU64::new(crate::util::clock::now_ms()),
+            replay_window: Mutex::new(crate::ble::ReplayWindow::new()),
+            pinned_keys: Mutex::new(HashMap::new()),
+            peer_digests: Mutex::new(HashMap::new()),
+            peer_capabilities: Mutex::new(HashMap::new()),
+            tx_origins: Mutex::new(HashMap::new()),
+            peer_sessions: Mutex::new(Default::default()),
+            probes: Mutex::new(crate::ble::ProbeTracker::new()),
+            emergency_seen: Mutex::new(VecDeque::new()),
+            emergency_inbox: Mutex::new(VecDeque::new()),
+            payment_requests: Mutex::new(VecDeque::new()),
+            issued_payment_requests: Mutex::new(VecDeque::new()),
+            payment_receipts: Mutex::new(None),
+            event_callback: Mutex::new(None),
+            text_messaging: super::messaging::TextMessaging::new(),
+            default_fragment_payload: Mutex::new(None),
+            nonce_staleness: Mutex::new(Default::default()),
+            tx_watch: super::tx_watch::TxWatchList::new(),
+            address_book: Mutex::new(None),
+        }
     }
 
     /// Set secure storage directory for nonce bundle persistence.
-    /// `encryption_key` is forwarded to `SecureStorage`; falls back to the
-    /// `POLLINET_ENCRYPTION_KEY` env var when `None`.
+    /// `key_provider` supplies the encryption key (see `SdkConfig::storage_key_provider`).
     /// Also loads the received queue from disk if storage is available
     pub fn set_secure_storage(
         &mut self,
         storage_dir: &str,
-        encryption_key: Option<String>,
+        key_provider: Arc<dyn KeyProvider>,
     ) -> Result<(), String> {
-        let storage = SecureStorage::new(storage_dir, encryption_key)
+        let storage = SecureStorage::with_key_provider(storage_dir, key_provider)
             .map_err(|e| format!("Failed to create secure storage: {}", e))?;
+        match load_or_create_device_key(&storage) {
+            Ok(key) => {
+                self.device_key = key;
+                self.device_key_persisted = true;
+            }
+            Err(e) => {
+                t_warn!("⚠️ Device key not persisted: {} (using a session key)", e);
+            }
+        }
         self.secure_storage = Some(Arc::new(storage));
         t_info!("🔒 Secure storage enabled for nonce bundles");
 
@@ -257,6 +439,135 @@ impl HostBleTransport {
         Ok(())
     }
 
+    /// Apply the storage, pollicore and wallet settings from an SDK config.
+    /// Shared by every binding layer so init behaves the same on each platform.
+    pub fn apply_config(&mut self, config: &SdkConfig) -> Result<(), String> {
+        if let Some(storage_dir) = &config.storage_directory {
+            t_info!("Setting up secure storage at: {}", storage_dir);
+            self.set_secure_storage(storage_dir, config.storage_key_provider()?)?;
+            t_info!("✅ Secure storage configured");
+
+            // Queue storage directory lives on the transport (no env var mutation)
+            let queue_storage_dir = format!("{}/queues", storage_dir);
+            self.set_queue_storage_dir(queue_storage_dir.clone());
+            t_info!("✅ Queue persistence enabled at: {}", queue_storage_dir);
+
+            if let Some(budget) = config.storage_budget_bytes {
+                self.set_storage_quota(Some(crate::storage::StorageQuota::new(
+                    storage_dir,
+                    budget,
+                )));
+                t_info!("✅ Storage budget: {} bytes", budget);
+            }
+
+            match crate::storage::MessageLog::open(format!("{}/messages", storage_dir)) {
+                Ok(log) => {
+                    self.set_message_log(Some(Arc::new(log)));
+                    self.resume_text_messages();
+                }
+                Err(e) => {
+                    t_warn!("⚠️ Message log disabled: {}", e);
+                }
+            }
+
+            match crate::storage::TxHistory::open(format!("{}/tx_history", storage_dir)) {
+                Ok(history) => self.set_tx_history(Some(Arc::new(history))),
+                Err(e) => {
+                    t_warn!("⚠️ Transaction history disabled: {}", e);
+                }
+            }
+
+            match crate::storage::MeshStats::open(format!("{}/mesh_stats", storage_dir)) {
+                Ok(stats) => self.set_mesh_stats(Some(Arc::new(stats))),
+                Err(e) => {
+                    t_warn!("⚠️ Mesh statistics disabled: {}", e);
+                }
+            }
+
+            match crate::storage::ReceiptStore::open(format!("{}/receipts", storage_dir)) {
+                Ok(store) => self.set_relay_receipts(Some(Arc::new(store))),
+                Err(e) => {
+                    t_warn!("⚠️ Relay receipts disabled: {}", e);
+                }
+            }
+
+            match crate::storage::PaymentReceiptStore::open(format!(
+                "{}/payment_receipts",
+                storage_dir
+            )) {
+                Ok(store) => self.set_payment_receipts(Some(Arc::new(store))),
+                Err(e) => {
+                    t_warn!("⚠️ Payment receipts disabled: {}", e);
+                }
+            }
+
+            match crate::storage::AddressBook::open(format!("{}/address_book", storage_dir)) {
+                Ok(book) => self.set_address_book(Some(Arc::new(book))),
+                Err(e) => {
+                    t_warn!("⚠️ Address book disabled: {}", e);
+                }
+            }
+        } else {
+            t_info!("ℹ️  No storage directory provided - bundle persistence disabled");
+        }
+
+        // Pollicore URL is baked in at compile time from .env / POLLICORE_URL env var
+        if let Some(url) = option_env!("POLLICORE_URL") {
+            self.set_pollicore_url(Some(url.to_string()));
+            t_info!("✅ Pollicore URL (compile-time): {}", url);
+        } else {
+            t_info!("⚠️  POLLICORE_URL not set at compile time — submitIntent will fail");
+        }
+
+        if let Some(role) = config.role {
+            self.set_device_role(Some(role));
+            t_info!("✅ Device role: {:?}", role);
+        }
+
+        self.set_nonce_staleness(config.nonce_staleness);
+        if let Some(payload) = config.ble.fragment_payload {
+            self.set_default_fragment_payload(Some(payload));
+            t_info!("✅ Default fragment payload: {} bytes", payload);
+        }
+        if config.ble.reassembly_timeout_secs.is_some() || config.ble.nack_on_reassembly_timeout {
+            let secs = config
+                .ble
+                .reassembly_timeout_secs
+                .unwrap_or(crate::ble::fragmenter::DEFAULT_REASSEMBLY_TIMEOUT_SECS);
+            self.set_reassembly_timeout(
+                Duration::from_secs(secs),
+                config.ble.nack_on_reassembly_timeout,
+            );
+            t_info!(
+                "✅ Reassembly timeout: {}s (NACK: {})",
+                secs,
+                config.ble.nack_on_reassembly_timeout
+            );
+        }
+        if let Some(policy) = config.ble.relay_policy {
+            self.set_relay_policy(Some(policy));
+            t_info!("✅ Relay policy: {:?}", policy);
+        }
+        if config.ble.privacy.enabled {
+            self.set_privacy(config.ble.privacy);
+            t_info!(
+                "✅ Privacy mode: advertised id rotates every {}s",
+                config.ble.privacy.rotation_secs
+            );
+        }
+
+        if let Some(addr) = &config.wallet_address {
+            self.set_wallet_address(Some(addr.clone()));
+            t_info!("✅ Wallet address set: {}", addr);
+        } else {
+            t_info!(
+                "ℹ️  No wallet address provided — rewards will not be attributed until one is set"
+            );
+        }
+
+        Ok(())
+    }
+
     /// Save received queue to disk
     pub fn save_received_queue(&self, storage_dir: &str) -> Result<(), String> {
         use crate::queue::storage::QueueStorage;
@@ -359,25 +670,195 @@ impl HostBleTransport {
         self.health_monitor.clone()
     }
 
+    /// Queue inbound data for processing without blocking the caller (the
+    /// radio's callback thread). When the queue is full the frame is dropped
+    /// and, for a fragment, the sender is NACKed to resend it. Returns
+    /// whether the frame was queued.
+    pub fn enqueue_inbound(&self, peer_id: Option<&str>, data: Vec<u8>) -> bool {
+        let frame = super::inbound_queue::InboundFrame {
+            peer_id: peer_id.map(str::to_string),
+            data,
+        };
+        let Err(frame) = self.inbound_queue.offer(frame) else {
+            return true;
+        };
+
+        t_warn!(
+            "⚠️ Inbound queue full ({} frames): dropped {} bytes",
+            self.inbound_queue.len(),
+            frame.data.len()
+        );
+        if let Ok(fragment) = TransactionFragment::deserialize(&frame.data) {
+            self.send_fragment_nack(crate::ble::FragmentNackFrame {
+                tx_id: fragment.transaction_id,
+                fragment_index: Some(fragment.fragment_index),
+                reason: crate::ble::NackReason::QueueFull,
+            });
+        }
+        false
+    }
+
+    /// Process up to `max` queued inbound frames; returns how many were taken
+    pub fn process_inbound(&self, max: usize) -> usize {
+        let frames = self.inbound_queue.drain(max);
+        for frame in &frames {
+            let result = match &frame.peer_id {
+                Some(peer_id) => self.push_inbound_from(peer_id, frame.data.clone()),
+                None => self.push_inbound(frame.data.clone()),
+            };
+            if let Err(e) = result {
+                t_debug!("📥 Queued inbound frame rejected: {}", e);
+            }
+        }
+        frames.len()
+    }
+
+    /// Inbound frames waiting to be processed
+    pub fn inbound_queue_len(&self) -> usize {
+        self.inbound_queue.len()
+    }
+
+    /// Ask the peer to resend dropped fragments (sent ahead of queued data)
+    fn send_fragment_nack(&self, nack: crate::ble::FragmentNackFrame) {
+        t_info!(
+            "🔁 NACK tx {} fragment {:?} ({:?})",
+            &hex::encode(nack.tx_id)[..16],
+            nack.fragment_index,
+            nack.reason
+        );
+        self.outbound_queue.lock().push_front(nack.to_frame_bytes());
+    }
+
+    /// Honor a FRAGMENT_NACK from a peer by queuing the fragment (or the
+    /// whole transaction) again, if it is still in the outbound queue.
+    /// Returns the number of fragments queued.
+    pub fn ingest_fragment_nack(&self, frame: &[u8]) -> Result<usize, String> {
+        let nack = crate::ble::FragmentNackFrame::from_frame_bytes(frame)?;
+        let tx_id = hex::encode(nack.tx_id);
+        let found = match self.sdk.queue_manager().outbound.try_read() {
+            Ok(queue) => queue
+                .transactions()
+                .find(|tx| tx.tx_id == tx_id)
+                .map(|tx| (tx.original_bytes.clone(), tx.fragments.clone())),
+            Err(_) => return Err("Outbound queue busy; NACK ignored".to_string()),
+        };
+        let Some((tx_bytes, fragments)) = found else {
+            t_debug!("🔁 NACK for tx {} not in outbound queue", &tx_id[..16]);
+            return Ok(0);
+        };
+
+        match nack.fragment_index {
+            Some(index) => {
+                let resend: Vec<TransactionFragment> = fragments
+                    .into_iter()
+                    .filter(|f| f.fragment_index == index)
+                    .collect();
+                self.queue_fragments(&resend)?;
+                Ok(resend.len())
+            }
+            None => Ok(self.queue_transaction(tx_bytes, None)?.len()),
+        }
+    }
+
+    /// Push inbound data received from `peer_id`, recording the peer as the
+    /// provenance of the transaction the fragment belongs to
+    pub fn push_inbound_from(&self, peer_id: &str, data: Vec<u8>) -> Result<(), String> {
+        self.record_mesh_stat(crate::storage::MeshStatsEvent::PeerSeen(peer_id));
+        if crate::ble::wire::is_compact_fragment(&data) {
+            self.add_peer_capabilities(peer_id, crate::role::capability::COMPACT_FRAMES);
+        }
+        if let Ok(fragment) = TransactionFragment::deserialize(&data) {
+            self.inbound_peers
+                .lock()
+                .entry(hex::encode(fragment.transaction_id))
+                .or_insert_with(|| peer_id.to_string());
+        }
+        let result = self.push_inbound(data);
+        let mut peers = self.inbound_peers.lock();
+        if result.is_err() || peers.len() > MAX_PENDING_TRANSACTIONS {
+            // Drop entries whose reassembly buffer is gone (failed or expired)
+            let buffers = self.inbound_buffers.lock();
+            peers.retain(|tx_id, _| buffers.contains_key(tx_id));
+        }
+        result
+    }
+
     /// Push inbound data from GATT characteristic
     pub fn push_inbound(&self, data: Vec<u8>) -> Result<(), String> {
+        self.record_mesh_stat(crate::storage::MeshStatsEvent::BytesReceived(data.len()));
         t_info!("📥 push_inbound() called with {} bytes", data.len());
 
-        // Deserialize the mesh fragment using bincode1 (matching outbound serialization)
+        if let Some(mtu) = *self.inbound_mtu.lock() {
+            if data.len() > mtu {
+                let error_msg = format!(
+                    "Inbound frame of {} bytes exceeds the negotiated MTU ({})",
+                    data.len(),
+                    mtu
+                );
+                t_warn!("⚠️ {}", error_msg);
+                return Err(error_msg);
+            }
+        }
+
+        // Decode the wire frame (compact format, or a legacy layout from older nodes)
         use crate::ble::fragmenter::reconstruct_transaction;
 
         t_debug!("🔓 Deserializing fragment from binary data...");
-        let fragment: TransactionFragment = bincode1::deserialize(&data).map_err(|e| {
+        let fragment = match TransactionFragment::deserialize(&data) {
+            Ok(fragment) => fragment,
+            Err(crate::ble::MeshError::ChecksumMismatch {
+                transaction_id,
+                fragment_index,
+            }) => {
+                // Reject now and ask the sender for just this fragment; the
+                // slot stays open in the reassembly buffer
+                let tx_id = hex::encode(transaction_id);
+                let error_msg = format!(
+                    "Corrupt fragment {} for tx {} (checksum mismatch)",
+                    fragment_index + 1,
+                    tx_id
+                );
+                t_warn!("⚠️ {}", error_msg);
+                let mut metrics = self.metrics.lock();
+                metrics.corrupt_fragments += 1;
+                metrics.last_error = error_msg.clone();
+                metrics.updated_at = Self::current_timestamp();
+                drop(metrics);
+                self.sdk.notify(crate::SdkEvent::FragmentCorrupt {
+                    tx_id,
+                    index: fragment_index,
+                });
+                self.send_fragment_nack(crate::ble::FragmentNackFrame {
+                    tx_id: transaction_id,
+                    fragment_index: Some(fragment_index),
+                    reason: crate::ble::NackReason::Corrupt,
+                });
+                return Err(error_msg);
+            }
+            Err(e) => {
+                let error_msg = format!(
+                    "Failed to deserialize fragment ({} bytes): {}",
+                    data.len(),
+                    e
+                );
+                t_error!("❌ {}", error_msg);
+                return Err(error_msg);
+            }
+        };
+
+        t_debug!("✅ Fragment deserialized successfully");
+
+        // Ids, index, claimed total and payload size are checked before
+        // anything is buffered for this fragment
+        if let Err(e) = crate::ble::fragmenter::validate_fragment(&fragment) {
             let error_msg = format!(
-                "Failed to deserialize fragment ({} bytes): {}",
-                data.len(),
+                "Rejected fragment for tx {}: {}",
+                hex::encode(fragment.transaction_id),
                 e
             );
             t_error!("❌ {}", error_msg);
-            error_msg
-        })?;
-
-        t_debug!("✅ Fragment deserialized successfully");
+            return Err(error_msg);
+        }
 
         // Use transaction_id as tx_id (convert to 64-character hex string to match sender format)
         let tx_id = hex::encode(fragment.transaction_id);
@@ -390,50 +871,32 @@ impl HostBleTransport {
             data.len()
         );
 
-        let mut buffers = self.inbound_buffers.lock();
-
-        // Enforce per-transaction and total buffer limits (DoS prevention)
-        if buffers.len() >= MAX_PENDING_TRANSACTIONS && !buffers.contains_key(&tx_id) {
-            let error_msg = format!(
-                "Inbound buffer full ({} pending txs), dropping fragment for {}",
-                buffers.len(),
-                tx_id
-            );
-            t_warn!("⚠️ {}", error_msg);
-            drop(buffers);
-            return Err(error_msg);
+        // Confirmed or cancelled transactions are tombstoned; drop their fragments
+        let tombstone_key = hex::encode(crate::ble::tx_id_hash(&tx_id));
+        if self
+            .tombstones
+            .lock()
+            .get(&tombstone_key)
+            .is_some_and(|t| t.is_valid())
+        {
+            t_debug!("🪦 Ignoring fragment for tombstoned tx {}", tx_id);
+            return Ok(());
         }
 
-        // Store TransactionFragment directly (no conversion needed)
-        let buffer = buffers.entry(tx_id.clone()).or_default();
-
-        if buffer.len() >= MAX_FRAGMENTS_PER_TRANSACTION {
-            let error_msg = format!(
-                "Too many fragments for tx {} (max {})",
-                tx_id, MAX_FRAGMENTS_PER_TRANSACTION
-            );
-            t_warn!("⚠️ {}", error_msg);
-            drop(buffers);
-            return Err(error_msg);
+        if fragment.fragment_index == 0 {
+            if let Some(origin) = crate::ble::wire::fragment_origin(&data) {
+                self.record_tx_origin(&tx_id, origin);
+            }
         }
-        let buffer_size_before = buffer.len();
 
-        // Validate fragment index is within expected range
-        if fragment.fragment_index >= fragment.total_fragments {
-            let error_msg = format!(
-                "Invalid fragment index {} (total: {}) for tx {}",
-                fragment.fragment_index, fragment.total_fragments, tx_id
-            );
-            t_error!("❌ {}", error_msg);
-            drop(buffers);
-            return Err(error_msg);
-        }
+        let mut buffers = self.inbound_buffers.lock();
 
         // Check if fragment already exists (avoid duplicates)
-        if buffer
-            .iter()
-            .any(|f| f.fragment_index == fragment.fragment_index)
-        {
+        if buffers.get(&tx_id).is_some_and(|buffer| {
+            buffer
+                .iter()
+                .any(|f| f.fragment_index == fragment.fragment_index)
+        }) {
             t_debug!(
                 "⚠️ Duplicate fragment {}/{} for tx {} - ignoring",
                 fragment.fragment_index + 1,
@@ -444,8 +907,44 @@ impl HostBleTransport {
             return Ok(()); // Ignore duplicate, but don't error
         }
 
+        // Enforce per-transaction and total byte budgets (DoS prevention):
+        // absurd totals are refused, stale partial transactions make room
+        let admitted = {
+            let mut budget = self.inbound_budget.lock();
+            budget.retain(|id| buffers.contains_key(id));
+            budget.admit(&tx_id, fragment.total_fragments, fragment.data.len())
+        };
+        let evicted = match admitted {
+            Ok(evicted) => evicted,
+            Err(error_msg) => {
+                t_warn!("⚠️ Dropping fragment: {}", error_msg);
+                drop(buffers);
+                return Err(error_msg);
+            }
+        };
+        for evicted_id in &evicted {
+            t_warn!(
+                "⚠️ Evicted partial tx {} to make room for {}",
+                evicted_id,
+                tx_id
+            );
+            buffers.remove(evicted_id);
+        }
+        if !evicted.is_empty() {
+            self.metrics.lock().reassembly_evictions += evicted.len() as u32;
+        }
+
+        // Store TransactionFragment directly (no conversion needed)
+        let buffer = buffers.entry(tx_id.clone()).or_default();
+        let buffer_size_before = buffer.len();
+
         buffer.push(fragment.clone());
         let buffer_size_after = buffer.len();
+        self.sdk.notify(crate::SdkEvent::FragmentReceived {
+            tx_id: tx_id.clone(),
+            index: fragment.fragment_index,
+            total: fragment.total_fragments,
+        });
 
         t_debug!(
             "📦 Added fragment to buffer for tx {} (buffer size: {} → {})",
@@ -515,13 +1014,15 @@ impl HostBleTransport {
                 mesh_fragments.len()
             );
 
-            // Try to reassemble using mesh fragmenter
-            match reconstruct_transaction(&mesh_fragments) {
-                Ok(tx_bytes) => {
+            // Try to reassemble using mesh fragmenter; a batch yields several transactions
+            match reconstruct_transaction(&mesh_fragments)
+                .and_then(|bytes| Self::unpack_reassembled(&tx_id, bytes))
+            {
+                Ok(transactions) => {
                     t_info!(
-                        "✅ Transaction {} reassembled successfully ({} bytes)",
+                        "✅ Transaction {} reassembled successfully ({} transaction(s))",
                         tx_id,
-                        tx_bytes.len()
+                        transactions.len()
                     );
 
                     // Remove from inbound buffers FIRST (before updating metrics)
@@ -538,41 +1039,14 @@ impl HostBleTransport {
                         .sum();
                     t_debug!("📊 Remaining fragments in buffers: {}", remaining_fragments);
 
-                    // Move to completed queue
-                    t_debug!("📋 Adding to completed transactions queue...");
-                    let mut completed = self.completed_transactions.lock();
-                    let completed_size_before = completed.len();
-                    completed.push_back((tx_id.clone(), tx_bytes.clone()));
-                    let completed_size_after = completed.len();
-                    drop(completed);
-                    t_debug!(
-                        "✅ Added to completed queue (size: {} → {})",
-                        completed_size_before,
-                        completed_size_after
-                    );
-
-                    // Also add to received transaction queue for auto-submission
-                    t_info!("📥 Calling push_received_transaction() for tx {}...", tx_id);
-                    let was_added = self.push_received_transaction(tx_bytes.clone());
-                    let queue_size = self.received_queue_size();
-
-                    if was_added {
-                        t_info!(
-                            "📥 Transaction {} added to received queue (queue size: {})",
-                            tx_id,
-                            queue_size
-                        );
-                    } else {
-                        t_warn!("⚠️ Transaction {} was NOT added to received queue (likely duplicate, queue size: {})", tx_id, queue_size);
+                    for (tx_id, tx_bytes) in transactions {
+                        self.complete_transaction(tx_id, tx_bytes);
                     }
 
-                    // Update metrics AFTER removing from buffers
                     let mut metrics = self.metrics.lock();
                     metrics.fragments_buffered = remaining_fragments; // Update to actual count
-                    metrics.transactions_complete += 1;
                     metrics.updated_at = Self::current_timestamp();
                     drop(metrics);
-
                     Ok(())
                 }
                 Err(e) => {
@@ -602,6 +1076,7 @@ impl HostBleTransport {
                     // Remove failed fragments
                     self.inbound_buffers.lock().remove(&tx_id);
 
+                    self.emit_event(ProtocolEvent::error(Some(tx_id), error_msg.clone()));
                     Err(error_msg)
                 }
             }
@@ -629,7 +1104,9 @@ impl HostBleTransport {
             let data = queue.pop_front().unwrap();
 
             if data.len() <= max_len {
-                let queue_size_after = queue.len();
+                drop(queue);
+                self.record_mesh_stat(crate::storage::MeshStatsEvent::BytesSent(data.len()));
+                let queue_size_after = queue_size_before - 1;
                 t_info!(
                     "✅ Returning fragment of {} bytes (max: {})",
                     data.len(),
@@ -662,39 +1139,307 @@ impl HostBleTransport {
         None
     }
 
-    /// Convert a BLE mesh TransactionFragment to FFI Fragment
-    fn convert_mesh_fragment_to_ffi(
-        &self,
-        mesh_fragment: &crate::ble::mesh::TransactionFragment,
-    ) -> Fragment {
-        use base64::{engine::general_purpose::STANDARD, Engine as _};
+    /// Like [`Self::next_outbound`], but skips frames of transactions the
+    /// digest from `peer_id` says it already holds; those stay queued for
+    /// other peers. Fragments for a peer not known to read the compact
+    /// format are re-encoded in the legacy layout. Without a digest from a
+    /// compact-capable peer this is `next_outbound`.
+    pub fn next_outbound_for_peer(&self, peer_id: &str, max_len: usize) -> Option<Vec<u8>> {
+        if !self.peer_supports_compact_frames(peer_id) {
+            let mut queue = self.outbound_queue.lock();
+            let position = queue.iter().position(|frame| {
+                crate::ble::wire::to_legacy_frame(frame.clone()).len() <= max_len
+            })?;
+            let frame = crate::ble::wire::to_legacy_frame(queue.remove(position)?);
+            drop(queue);
+            self.record_mesh_stat(crate::storage::MeshStatsEvent::BytesSent(frame.len()));
+            return Some(frame);
+        }
 
-        Fragment {
-            id: format!(
-                "{:x}",
-                &mesh_fragment.transaction_id[0..8]
-                    .iter()
-                    .fold(0u64, |acc, &b| (acc << 8) | b as u64)
-            ),
-            index: mesh_fragment.fragment_index as u32,
-            total: mesh_fragment.total_fragments as u32,
-            data: STANDARD.encode(&mesh_fragment.data),
-            fragment_type: if mesh_fragment.fragment_index == 0 {
-                "FragmentStart".to_string()
-            } else if mesh_fragment.fragment_index == mesh_fragment.total_fragments - 1 {
-                "FragmentEnd".to_string()
-            } else {
-                "FragmentContinue".to_string()
-            },
-            checksum: STANDARD.encode(mesh_fragment.transaction_id),
+        let digests = self.peer_digests.lock();
+        let Some(digest) = digests.get(peer_id) else {
+            drop(digests);
+            return self.next_outbound(max_len);
+        };
+
+        let mut queue = self.outbound_queue.lock();
+        let position = queue.iter().position(|frame| {
+            frame.len() <= max_len
+                && !frame_transaction_id(frame).is_some_and(|id| digest.contains(&id))
+        })?;
+        let frame = queue.remove(position)?;
+        drop(queue);
+        drop(digests);
+        self.record_mesh_stat(crate::storage::MeshStatsEvent::BytesSent(frame.len()));
+        Some(frame)
+    }
+
+    /// Bloom digest of the transactions this device holds (queued, received
+    /// or submitted), to send to a peer right after connecting
+    pub fn tx_digest(&self) -> Vec<u8> {
+        let to_id = |hash: &Vec<u8>| <[u8; 32]>::try_from(hash.as_slice()).ok();
+
+        let mut ids = self.outbound_tx_ids();
+        ids.extend(self.received_tx_hash_set.lock().iter().filter_map(to_id));
+        ids.extend(self.submitted_tx_hashes.lock().keys().filter_map(to_id));
+
+        let mut digest = crate::ble::TxDigest::new();
+        for id in &ids {
+            digest.insert(id);
         }
+        t_debug!("🌸 Built transaction digest of {} ids", ids.len());
+        digest.to_frame()
+    }
+
+    /// Store the digest a peer sent on connect, so
+    /// [`Self::next_outbound_for_peer`] skips what it already has
+    pub fn ingest_tx_digest(
+        &self,
+        peer_id: &str,
+        frame: &[u8],
+    ) -> Result<super::types::DigestSummary, String> {
+        let digest = crate::ble::TxDigest::from_frame(frame)?;
+        let outbound = self.outbound_tx_ids();
+        let already_held = outbound.iter().filter(|id| digest.contains(id)).count();
+        let summary = super::types::DigestSummary {
+            peer_count: digest.len(),
+            already_held,
+            to_send: outbound.len() - already_held,
+        };
+
+        // Only nodes with the compact format send digests
+        self.add_peer_capabilities(peer_id, crate::role::capability::COMPACT_FRAMES);
+        let mut digests = self.peer_digests.lock();
+        if digests.len() >= MAX_PEER_DIGESTS && !digests.contains_key(peer_id) {
+            if let Some(evicted) = digests.keys().next().cloned() {
+                digests.remove(&evicted);
+            }
+        }
+        digests.insert(peer_id.to_string(), digest);
+
+        t_info!(
+            "🌸 Digest from {}: holds {} txs, {} of our {} queued already there",
+            peer_id,
+            summary.peer_count,
+            summary.already_held,
+            outbound.len()
+        );
+        Ok(summary)
+    }
+
+    /// Forget a peer's digest (call on disconnect)
+    pub fn clear_tx_digest(&self, peer_id: &str) {
+        self.peer_digests.lock().remove(peer_id);
+    }
+
+    /// Save the session with a peer that disconnected, keyed by its device
+    /// key: what was negotiated, its digest and the transactions it was
+    /// sending. Replaces `clear_tx_digest` on disconnect.
+    pub fn save_peer_session(
+        &self,
+        peer_id: &str,
+        device_key: &str,
+        mtu: Option<usize>,
+        protocol_version: Option<u8>,
+    ) {
+        let digest = self.peer_digests.lock().remove(peer_id);
+        let partial_transfers: Vec<String> = self
+            .inbound_peers
+            .lock()
+            .iter()
+            .filter(|(_, from)| from.as_str() == peer_id)
+            .map(|(tx_id, _)| tx_id.clone())
+            .collect();
+        t_debug!(
+            "💾 Saved session with {} ({} partial transfers)",
+            device_key,
+            partial_transfers.len()
+        );
+        self.peer_sessions.lock().save(
+            device_key,
+            super::peer_sessions::PeerSession {
+                mtu,
+                protocol_version,
+                digest,
+                partial_transfers,
+                saved_at: crate::util::clock::now_secs(),
+            },
+        );
+    }
+
+    /// Resume the saved session with `device_key`, now reachable as
+    /// `peer_id`: restore its digest and NACK the fragments still missing
+    /// from transfers it did not finish. `None` means no (live) session, so
+    /// the host runs the full handshake.
+    pub fn resume_peer_session(
+        &self,
+        peer_id: &str,
+        device_key: &str,
+    ) -> Option<super::peer_sessions::ResumedSession> {
+        let session = self.peer_sessions.lock().take(device_key)?;
+
+        let digest_restored = match session.digest {
+            Some(digest) => {
+                self.peer_digests.lock().insert(peer_id.to_string(), digest);
+                true
+            }
+            None => false,
+        };
+
+        let mut missing = Vec::new();
+        {
+            let buffers = self.inbound_buffers.lock();
+            let mut peers = self.inbound_peers.lock();
+            for tx_id in &session.partial_transfers {
+                let Some(fragments) = buffers.get(tx_id).filter(|f| !f.is_empty()) else {
+                    continue;
+                };
+                peers.insert(tx_id.clone(), peer_id.to_string());
+                let tx_id_bytes = fragments[0].transaction_id;
+                let total = fragments[0].total_fragments;
+                let held: HashSet<u16> = fragments.iter().map(|f| f.fragment_index).collect();
+                missing.push((
+                    tx_id_bytes,
+                    (0..total).filter(|i| !held.contains(i)).collect::<Vec<_>>(),
+                ));
+            }
+        }
+        let resumed_transfers = missing.len();
+        let mut fragments_requested = 0;
+        for (tx_id, indices) in missing {
+            for index in indices {
+                self.send_fragment_nack(crate::ble::FragmentNackFrame {
+                    tx_id,
+                    fragment_index: Some(index),
+                    reason: crate::ble::NackReason::Resume,
+                });
+                fragments_requested += 1;
+            }
+        }
+
+        t_info!(
+            "🔄 Resumed session with {} ({} transfers, {} fragments requested)",
+            device_key,
+            resumed_transfers,
+            fragments_requested
+        );
+        Some(super::peer_sessions::ResumedSession {
+            mtu: session.mtu,
+            protocol_version: session.protocol_version,
+            digest_restored,
+            resumed_transfers,
+            fragments_requested,
+        })
+    }
+
+    /// Distinct transaction ids in the outbound queue
+    fn outbound_tx_ids(&self) -> HashSet<[u8; 32]> {
+        self.outbound_queue
+            .lock()
+            .iter()
+            .filter_map(|frame| frame_transaction_id(frame))
+            .collect()
+    }
+
+    /// Convert a BLE mesh TransactionFragment to FFI Fragment
+    fn convert_mesh_fragment_to_ffi(
+        &self,
+        mesh_fragment: &crate::ble::mesh::TransactionFragment,
+    ) -> Fragment {
+        use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+        Fragment {
+            id: format!(
+                "{:x}",
+                &mesh_fragment.transaction_id[0..8]
+                    .iter()
+                    .fold(0u64, |acc, &b| (acc << 8) | b as u64)
+            ),
+            index: mesh_fragment.fragment_index as u32,
+            total: mesh_fragment.total_fragments as u32,
+            data: STANDARD.encode(&mesh_fragment.data),
+            fragment_type: if mesh_fragment.fragment_index == 0 {
+                "FragmentStart".to_string()
+            } else if mesh_fragment.fragment_index == mesh_fragment.total_fragments - 1 {
+                "FragmentEnd".to_string()
+            } else {
+                "FragmentContinue".to_string()
+            },
+            checksum: STANDARD.encode(mesh_fragment.transaction_id),
+        }
+    }
+
+    /// Fragment payload used by `queue_transaction` when the caller passes none
+    /// (`None` restores `MAX_FRAGMENT_DATA`)
+    pub fn set_default_fragment_payload(&self, max_payload: Option<usize>) {
+        *self.default_fragment_payload.lock() = max_payload;
+    }
+
+    /// Maximum age of cached nonces `build_payment_transaction` accepts
+    pub fn set_nonce_staleness(&self, policy: super::nonce_accounts::NonceStalenessPolicy) {
+        *self.nonce_staleness.lock() = policy;
+    }
+
+    /// Refuse inbound frames longer than `mtu` (`None` accepts up to the
+    /// fragment ceiling). Leave unset when the engine is shared with a
+    /// larger-MTU transport such as Wi-Fi Direct.
+    pub fn set_inbound_mtu(&self, mtu: Option<usize>) {
+        *self.inbound_mtu.lock() = mtu;
+    }
+
+    /// Discard partial transactions idle for `timeout`; with `nack`, ask
+    /// their senders to send them again from the start
+    pub fn set_reassembly_timeout(&self, timeout: Duration, nack: bool) {
+        *self.reassembly_timeout.lock() = (timeout, nack);
+    }
+
+    /// Drop partial transactions with no new fragment within the reassembly
+    /// timeout (run from `tick`). Returns how many were dropped.
+    pub fn collect_idle_reassemblies(&self) -> usize {
+        let (timeout, nack) = *self.reassembly_timeout.lock();
+        let mut buffers = self.inbound_buffers.lock();
+        let idle = self.inbound_budget.lock().take_idle(timeout);
+        for tx_id in &idle {
+            let received = buffers.remove(tx_id).map_or(0, |f| f.len());
+            t_warn!(
+                "⏳ Discarding partial tx {} after {}s idle ({} fragments received)",
+                tx_id,
+                timeout.as_secs(),
+                received
+            );
+        }
+        drop(buffers);
+        if idle.is_empty() {
+            return 0;
+        }
+
+        let mut peers = self.inbound_peers.lock();
+        for tx_id in &idle {
+            peers.remove(tx_id);
+        }
+        drop(peers);
+        self.metrics.lock().reassembly_timeouts += idle.len() as u32;
+
+        if nack {
+            for tx_id in &idle {
+                if let Ok(Ok(id)) = hex::decode(tx_id).map(<[u8; 32]>::try_from) {
+                    self.send_fragment_nack(crate::ble::FragmentNackFrame {
+                        tx_id: id,
+                        fragment_index: None,
+                        reason: crate::ble::NackReason::Timeout,
+                    });
+                }
+            }
+        }
+        idle.len()
     }
 
     /// Queue transaction fragments for sending
     ///
     /// # Arguments
     /// * `tx_bytes` - Complete signed transaction bytes
-    /// * `max_payload` - Optional maximum payload size (typically MTU - 10). If None, uses default MAX_FRAGMENT_DATA
+    /// * `max_payload` - Optional maximum payload size (typically MTU - 10). If None, uses the
+    ///   configured default fragment payload, else MAX_FRAGMENT_DATA
     pub fn queue_transaction(
         &self,
         tx_bytes: Vec<u8>,
@@ -708,15 +1453,17 @@ impl HostBleTransport {
 
         // Use BLE mesh fragmenter with MTU-aware payload size
         use crate::ble::fragmenter;
+        let max_payload = max_payload.or(*self.default_fragment_payload.lock());
         t_debug!(
             "🔧 Fragmenting transaction with max_payload: {:?}...",
             max_payload
         );
-        let mesh_fragments = if let Some(max_payload) = max_payload {
-            fragmenter::fragment_transaction_with_max_payload(&tx_bytes, max_payload)
-        } else {
-            fragmenter::fragment_transaction(&tx_bytes)
-        };
+        // The first fragment leaves room for the origin's device key
+        let mesh_fragments = fragmenter::fragment_transaction_with_first_room(
+            &tx_bytes,
+            max_payload,
+            crate::ble::wire::ORIGIN_KEY_LEN,
+        );
 
         t_debug!("✅ Fragmenter created {} fragments", mesh_fragments.len());
 
@@ -726,8 +1473,20 @@ impl HostBleTransport {
             tx_bytes.len()
         );
 
-        // Queue each fragment as compact binary bytes (bincode)
-        // We serialize the mesh TransactionFragment which is much more compact
+        // Transactions in the received history came from a peer
+        if let Some(first) = mesh_fragments.first() {
+            let tx_id = hex::encode(first.transaction_id);
+            let received = self
+                .tx_history()
+                .is_some_and(|h| h.get(&tx_id).is_some_and(|r| r.peer_id.is_some()));
+            self.record_mesh_stat(if received {
+                crate::storage::MeshStatsEvent::Relayed(&tx_id)
+            } else {
+                crate::storage::MeshStatsEvent::Originated(&tx_id)
+            });
+        }
+
+        // Queue each fragment as a compact wire frame (see `ble::wire`)
         let mut queue = self.outbound_queue.lock();
 
         // Remove any existing fragments for this transaction before enqueuing new ones.
@@ -735,13 +1494,12 @@ impl HostBleTransport {
         // Kotlin layer calls queue_transaction() again with a larger max_payload. Without
         // this drain, the old (small) fragments remain in the queue alongside the new
         // (larger) ones, causing the peer to receive two complete copies of the same
-        // transaction. The first 32 bytes of every bincode-serialized TransactionFragment
-        // are always the fixed-size transaction_id array, so we can compare without a
-        // full deserialization pass.
+        // transaction. Every fragment frame carries the transaction_id at a fixed
+        // offset, so we can compare without a full deserialization pass.
         if let Some(first) = mesh_fragments.first() {
             let tx_id = first.transaction_id;
             let before = queue.len();
-            queue.retain(|entry| entry.len() < 32 || entry[..32] != tx_id);
+            queue.retain(|entry| frame_transaction_id(entry) != Some(tx_id));
             let dropped = before - queue.len();
             if dropped > 0 {
                 t_info!(
@@ -754,11 +1512,24 @@ impl HostBleTransport {
 
         let queue_size_before = queue.len();
 
+        // Relayed transactions keep the key of the device that originated them
+        let origin = mesh_fragments.first().map(|first| {
+            let tx_id = hex::encode(first.transaction_id);
+            self.tx_origins
+                .lock()
+                .get(&tx_id)
+                .copied()
+                .unwrap_or_else(|| self.device_key.verifying_key().to_bytes())
+        });
+
         for fragment in &mesh_fragments {
-            // Use bincode1 for compact binary serialization
-            // TransactionFragment is: transaction_id[32] + fragment_index(u16) + total_fragments(u16) + data(Vec<u8>)
-            let binary_bytes = bincode1::serialize(fragment)
-                .map_err(|e| format!("Failed to serialize fragment: {}", e))?;
+            // Compact wire frame: type + transaction_id[32] + varint index/total/length + data
+            let binary_bytes = match origin {
+                Some(ref origin) if fragment.fragment_index == 0 => {
+                    crate::ble::wire::encode_first_fragment(fragment, origin)
+                }
+                _ => fragment.serialize(),
+            };
 
             t_info!(
                 "📦 Fragment serialized: {} bytes (data: {}B, index: {}/{})",
@@ -803,57 +1574,1693 @@ impl HostBleTransport {
             total_bytes
         );
 
-        // Log each fragment in queue for debugging
-        for (idx, data) in queue.iter().enumerate() {
-            t_debug!("  Fragment [{}]: {} bytes", idx, data.len());
+        // Log each fragment in queue for debugging
+        for (idx, data) in queue.iter().enumerate() {
+            t_debug!("  Fragment [{}]: {} bytes", idx, data.len());
+        }
+
+        Ok(ffi_fragments)
+    }
+
+    /// Queue a transaction sealed for `gateway_key` (base58 device key), so
+    /// relays see neither its signers nor its contents (see `ble::sealed`)
+    pub fn queue_sealed_transaction(
+        &self,
+        tx_bytes: Vec<u8>,
+        gateway_key: &str,
+        max_payload: Option<usize>,
+    ) -> Result<Vec<Fragment>, String> {
+        let gateway = bs58::decode(gateway_key)
+            .into_vec()
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .and_then(|bytes| ed25519_dalek::VerifyingKey::from_bytes(&bytes).ok())
+            .ok_or_else(|| format!("Invalid gateway key '{}'", gateway_key))?;
+        let sealed = crate::ble::sealed::seal(&tx_bytes, &gateway)?;
+        t_info!(
+            "🔏 Sealed {} byte transaction for gateway {}",
+            tx_bytes.len(),
+            hex::encode(&gateway.to_bytes()[..4])
+        );
+        self.queue_transaction(sealed, max_payload)
+    }
+
+    /// Plain transaction from one sealed for this device, if it is one
+    fn open_sealed(&self, bytes: &[u8]) -> Option<Vec<u8>> {
+        if crate::ble::sealed::routing_hint(bytes)? != self.node_id() {
+            return None;
+        }
+        match crate::ble::sealed::open(bytes, &self.device_key) {
+            Ok(opened) => {
+                t_info!("🔓 Opened transaction sealed for this gateway");
+                Some(opened)
+            }
+            Err(e) => {
+                t_warn!("⚠️ {}", e);
+                None
+            }
+        }
+    }
+
+    /// Queue several transactions for the same peer as one fragment stream
+    /// (see `ble::batch`), so a short encounter carries them back to back
+    /// with less fragment overhead. A single transaction is queued as is.
+    pub fn queue_transaction_batch(
+        &self,
+        transactions: Vec<Vec<u8>>,
+        max_payload: Option<usize>,
+    ) -> Result<Vec<Fragment>, String> {
+        if transactions.len() == 1 {
+            return self.queue_transaction(transactions.into_iter().next().unwrap(), max_payload);
+        }
+        let payload = crate::ble::batch::encode_batch(&transactions)?;
+        t_info!(
+            "📦 Batching {} transactions into one {} byte stream",
+            transactions.len(),
+            payload.len()
+        );
+        self.queue_transaction(payload, max_payload)
+    }
+
+    /// Split a reassembled payload into (tx_id, bytes) pairs: the payload
+    /// itself, or each transaction of a batch under its own SHA-256 id
+    fn unpack_reassembled(tx_id: &str, bytes: Vec<u8>) -> Result<Vec<(String, Vec<u8>)>, String> {
+        use sha2::{Digest, Sha256};
+
+        if !crate::ble::batch::is_batch(&bytes) {
+            return Ok(vec![(tx_id.to_string(), bytes)]);
+        }
+        let transactions = crate::ble::batch::decode_batch(&bytes)?;
+        Ok(transactions
+            .into_iter()
+            .map(|tx| (hex::encode(Sha256::digest(&tx)), tx))
+            .collect())
+    }
+
+    /// Hand a reassembled transaction to the completed and received queues
+    fn complete_transaction(&self, tx_id: String, tx_bytes: Vec<u8>) {
+        // Move to completed queue
+        t_debug!("📋 Adding to completed transactions queue...");
+        let mut completed = self.completed_transactions.lock();
+        let completed_size_before = completed.len();
+        completed.push_back((tx_id.clone(), tx_bytes.clone()));
+        let completed_size_after = completed.len();
+        drop(completed);
+        t_debug!(
+            "✅ Added to completed queue (size: {} → {})",
+            completed_size_before,
+            completed_size_after
+        );
+
+        // Also add to received transaction queue for auto-submission
+        t_info!("📥 Calling push_received_transaction() for tx {}...", tx_id);
+        let was_added = self.push_received_transaction(tx_bytes.clone());
+        let queue_size = self.received_queue_size();
+
+        if was_added {
+            t_info!(
+                "📥 Transaction {} added to received queue (queue size: {})",
+                tx_id,
+                queue_size
+            );
+        } else {
+            t_warn!("⚠️ Transaction {} was NOT added to received queue (likely duplicate, queue size: {})", tx_id, queue_size);
+        }
+
+        let mut metrics = self.metrics.lock();
+        metrics.transactions_complete += 1;
+        metrics.updated_at = Self::current_timestamp();
+        drop(metrics);
+
+        self.sdk.notify(crate::SdkEvent::TransactionComplete {
+            tx_id: tx_id.clone(),
+            size: tx_bytes.len(),
+        });
+        self.emit_event(ProtocolEvent::transaction_complete(&tx_id, tx_bytes.len()));
+    }
+
+    /// Queue pre-fragmented transaction fragments directly to outbound queue
+    /// This is used when fragments are already created (e.g., from accept_and_queue_external_transaction)
+    pub fn queue_fragments(
+        &self,
+        fragments: &[crate::ble::mesh::TransactionFragment],
+    ) -> Result<(), String> {
+        let mut queue = self.outbound_queue.lock();
+
+        for fragment in fragments {
+            let binary_bytes = fragment.serialize();
+            if queue.len() >= MAX_OUTBOUND_FRAMES {
+                queue.pop_front();
+                t_warn!(
+                    "⚠️ Outbound queue overflow: dropped oldest frame to make room (max {})",
+                    MAX_OUTBOUND_FRAMES
+                );
+            }
+            queue.push_back(binary_bytes);
+        }
+
+        t_info!(
+            "✅ Queued {} fragments directly to outbound queue",
+            fragments.len()
+        );
+        Ok(())
+    }
+
+    /// Periodic tick for retries and timeouts
+    pub fn tick(&self, _now_ms: u64) -> Vec<Vec<u8>> {
+        t_debug!("⏱️ HostBleTransport::tick() called (retry/timeout logic not yet implemented)");
+        self.process_inbound(usize::MAX);
+        self.collect_idle_reassemblies();
+        if let Some(quota) = self.storage_quota.lock().as_mut() {
+            quota.enforce_if_due();
+        }
+        for message in self.text_messaging.resend_overdue() {
+            t_warn!("⚠️ Text message {} not delivered", message.message_id);
+            self.record_text_status(&message);
+        }
+        Vec::new()
+    }
+
+    /// Set (or clear) the disk budget for the storage directory
+    pub fn set_storage_quota(&self, quota: Option<crate::storage::StorageQuota>) {
+        *self.storage_quota.lock() = quota;
+    }
+
+    /// Current storage usage (`None` when no budget is configured)
+    pub fn storage_usage(&self) -> Option<crate::storage::StorageUsage> {
+        self.storage_quota
+            .lock()
+            .as_ref()
+            .map(|quota| quota.usage())
+    }
+
+    /// Enable (or disable) the persistent message log
+    pub fn set_message_log(&self, log: Option<Arc<crate::storage::MessageLog>>) {
+        *self.message_log.lock() = log;
+    }
+
+    /// Persistent message log, if enabled
+    pub fn message_log(&self) -> Option<Arc<crate::storage::MessageLog>> {
+        self.message_log.lock().clone()
+    }
+
+    /// Enable (or disable) mesh statistics
+    pub fn set_mesh_stats(&self, stats: Option<Arc<crate::storage::MeshStats>>) {
+        *self.mesh_stats.lock() = stats;
+    }
+
+    /// Mesh statistics store, if enabled
+    pub fn mesh_stats(&self) -> Option<Arc<crate::storage::MeshStats>> {
+        self.mesh_stats.lock().clone()
+    }
+
+    /// Mesh activity over the last `window_secs`, if statistics are enabled
+    pub fn mesh_stats_summary(&self, window_secs: u64) -> Option<crate::storage::MeshStatsSummary> {
+        self.mesh_stats().map(|stats| stats.summary(window_secs))
+    }
+
+    /// Count an event in the mesh statistics, if enabled
+    pub(crate) fn record_mesh_stat(&self, event: crate::storage::MeshStatsEvent<'_>) {
+        if let Some(stats) = self.mesh_stats() {
+            if let Err(e) = stats.record(event) {
+                t_warn!("⚠️ Failed to update mesh statistics: {}", e);
+            }
+        }
+    }
+
+    /// Enable (or disable) the received-transaction history
+    pub fn set_tx_history(&self, history: Option<Arc<crate::storage::TxHistory>>) {
+        *self.tx_history.lock() = history;
+    }
+
+    /// Received-transaction history, if enabled
+    pub fn tx_history(&self) -> Option<Arc<crate::storage::TxHistory>> {
+        self.tx_history.lock().clone()
+    }
+
+    /// Enable (or disable) relay receipt storage
+    pub fn set_relay_receipts(&self, store: Option<Arc<crate::storage::ReceiptStore>>) {
+        *self.relay_receipts.lock() = store;
+    }
+
+    /// Relay receipt store, if enabled
+    pub fn relay_receipts(&self) -> Option<Arc<crate::storage::ReceiptStore>> {
+        self.relay_receipts.lock().clone()
+    }
+
+    /// Enable (or disable) payment receipt storage
+    pub fn set_payment_receipts(&self, store: Option<Arc<crate::storage::PaymentReceiptStore>>) {
+        *self.payment_receipts.lock() = store;
+    }
+
+    /// Payment receipt store, if enabled
+    pub fn payment_receipt_store(&self) -> Option<Arc<crate::storage::PaymentReceiptStore>> {
+        self.payment_receipts.lock().clone()
+    }
+
+    /// Enable (or disable) the address book
+    pub fn set_address_book(&self, book: Option<Arc<crate::storage::AddressBook>>) {
+        *self.address_book.lock() = book;
+    }
+
+    /// Saved recipients, if a storage directory is configured
+    pub fn address_book(&self) -> Option<Arc<crate::storage::AddressBook>> {
+        self.address_book.lock().clone()
+    }
+
+    /// Base58 key this device signs relay receipts with
+    pub fn device_public_key(&self) -> String {
+        bs58::encode(self.device_key.verifying_key().to_bytes()).into_string()
+    }
+
+    /// Identity this device signs and advertises with
+    pub fn device_identity(&self) -> DeviceIdentity {
+        DeviceIdentity {
+            public_key: self.device_public_key(),
+            short_id: hex::encode(self.node_id()),
+            persisted: self.device_key_persisted,
+        }
+    }
+
+    /// Short node id this device records in traceroute hops
+    pub fn node_id(&self) -> crate::ble::probe::NodeId {
+        let key = self.device_key.verifying_key().to_bytes();
+        [key[0], key[1], key[2], key[3]]
+    }
+
+    /// Ping a neighbour: queue a ping that the first device hearing it
+    /// answers. Returns the probe id to look the result up with.
+    pub fn ping_peer(&self, peer_id: &str) -> Result<String, String> {
+        let probe = crate::ble::Probe::ping(Self::current_timestamp_ms());
+        self.send_probe(probe, peer_id)
+    }
+
+    /// Trace the path to the device with this base58 device key; every
+    /// relay on the way records itself and its clock
+    pub fn traceroute(&self, device_key: &str) -> Result<String, String> {
+        let key = bs58::decode(device_key)
+            .into_vec()
+            .ok()
+            .filter(|key| key.len() == 32)
+            .ok_or_else(|| format!("Invalid device key '{}'", device_key))?;
+        let target = [key[0], key[1], key[2], key[3]];
+        if target == self.node_id() {
+            return Err("Cannot traceroute to this device".to_string());
+        }
+        let probe = crate::ble::Probe::traceroute(target, Self::current_timestamp_ms());
+        self.send_probe(probe, device_key)
+    }
+
+    fn send_probe(&self, probe: crate::ble::Probe, peer_id: &str) -> Result<String, String> {
+        let probe_id = uuid::Uuid::new_v4();
+        self.probes.lock().start(probe_id, peer_id)?;
+        let frame = probe.to_packet(
+            crate::ble::PacketType::Ping,
+            probe_id,
+            self.probe_sender_id(),
+        );
+        self.outbound_queue.lock().push_front(frame);
+        t_info!(
+            "📡 Sent {:?} probe {} for {}",
+            probe.kind,
+            probe_id,
+            peer_id
+        );
+        Ok(probe_id.to_string())
+    }
+
+    /// Handle a ping or pong frame from `peer_id`: answer pings addressed to
+    /// this device, relay traceroutes once each, and return the result when
+    /// a pong answers one of our probes
+    pub fn ingest_probe_frame(
+        &self,
+        peer_id: &str,
+        frame: &[u8],
+    ) -> Result<Option<crate::ble::ProbeResult>, String> {
+        use crate::ble::PacketType;
+
+        let mut packet = crate::ble::MeshPacket::deserialize(frame).map_err(|e| e.to_string())?;
+        let packet_type = packet.header.packet_type;
+        if !matches!(packet_type, PacketType::Ping | PacketType::Pong) {
+            return Err(format!("Not a probe frame (packet type {:?})", packet_type));
+        }
+        let mut probe = crate::ble::Probe::decode(&packet.payload).map_err(|e| e.to_string())?;
+        let probe_id = packet.header.message_id;
+        let now_ms = Self::current_timestamp_ms();
+
+        let mut probes = self.probes.lock();
+        if packet_type == PacketType::Pong && probes.is_pending(&probe_id) {
+            let result = probes.finish(probe_id, &probe, now_ms);
+            drop(probes);
+            if let Some(result) = &result {
+                t_info!(
+                    "📡 Probe {} answered in {} ms over {} hop(s)",
+                    probe_id,
+                    result.rtt_ms,
+                    result.hops.len()
+                );
+                self.sdk.notify(crate::SdkEvent::ProbeCompleted {
+                    probe_id: result.probe_id.clone(),
+                    rtt_ms: result.rtt_ms,
+                    hops: result.hops.len(),
+                });
+            }
+            return Ok(result);
+        }
+        if !probes.seen(probe_id, packet_type) {
+            return Ok(None);
+        }
+        drop(probes);
+
+        let reply = if packet_type == PacketType::Ping && probe.is_for(&self.node_id()) {
+            probe.add_hop(self.node_id(), now_ms);
+            t_debug!("📡 Answering probe {} from {}", probe_id, peer_id);
+            Some(probe.to_packet(PacketType::Pong, probe_id, self.probe_sender_id()))
+        } else if packet.header.ttl > 1 {
+            // Relay toward the target (pings) or back toward the origin (pongs)
+            if packet_type == PacketType::Ping && !probe.add_hop(self.node_id(), now_ms) {
+                return Ok(None);
+            }
+            packet.header.prepare_for_forward();
+            packet.payload = probe.encode();
+            Some(packet.serialize())
+        } else {
+            None
+        };
+        if let Some(frame) = reply {
+            self.outbound_queue.lock().push_front(frame);
+        }
+        Ok(None)
+    }
+
+    /// Result of an answered ping or traceroute, if it came back
+    pub fn probe_result(&self, probe_id: &str) -> Option<crate::ble::ProbeResult> {
+        self.probes.lock().result(probe_id)
+    }
+
+    fn probe_sender_id(&self) -> uuid::Uuid {
+        let key = self.device_key.verifying_key().to_bytes();
+        uuid::Uuid::from_bytes(key[..16].try_into().unwrap())
+    }
+
+    /// Sign and flood an emergency notice (at most 100 bytes). It goes
+    /// ahead of everything else in the outbound queue. Returns its id.
+    pub fn broadcast_emergency(&self, message: &str) -> Result<String, String> {
+        let broadcast = crate::ble::EmergencyBroadcast::sign(
+            &self.device_key,
+            message,
+            crate::util::clock::now_secs(),
+        )?;
+        self.mark_emergency_seen(broadcast.id());
+        self.outbound_queue
+            .lock()
+            .push_front(broadcast.to_frame_bytes());
+        let id = hex::encode(broadcast.id());
+        t_warn!("🚨 Broadcasting emergency notice {}", id);
+        Ok(id)
+    }
+
+    /// Handle an emergency frame from a peer: verify it, show it once, and
+    /// pass it on while its TTL lasts. Returns the notice if it is new.
+    pub fn ingest_emergency(
+        &self,
+        frame: &[u8],
+    ) -> Result<Option<crate::ble::EmergencyNotice>, String> {
+        use crate::ble::emergency::{EMERGENCY_MAX_AGE_SECS, MAX_EMERGENCY_INBOX};
+
+        let mut broadcast = crate::ble::EmergencyBroadcast::from_frame_bytes(frame)?;
+        if !broadcast.verify() {
+            return Err("Emergency notice has an invalid signature".to_string());
+        }
+        if crate::util::clock::secs_since(broadcast.timestamp) > EMERGENCY_MAX_AGE_SECS {
+            t_debug!("🚨 Dropping stale emergency notice");
+            return Ok(None);
+        }
+        if !self.mark_emergency_seen(broadcast.id()) {
+            return Ok(None);
+        }
+
+        let notice = crate::ble::EmergencyNotice::from(&broadcast);
+        t_warn!(
+            "🚨 Emergency notice {} from {} ({} hop(s)): {}",
+            notice.id,
+            notice.origin,
+            notice.hops,
+            notice.message
+        );
+        {
+            let mut inbox = self.emergency_inbox.lock();
+            if inbox.len() >= MAX_EMERGENCY_INBOX {
+                inbox.pop_front();
+            }
+            inbox.push_back(notice.clone());
+        }
+        self.sdk.notify(crate::SdkEvent::EmergencyReceived {
+            id: notice.id.clone(),
+            origin: notice.origin.clone(),
+            message: notice.message.clone(),
+            hops: notice.hops,
+        });
+        self.emit_event(ProtocolEvent::emergency(&notice));
+
+        if broadcast.decrement_ttl() {
+            self.outbound_queue
+                .lock()
+                .push_front(broadcast.to_frame_bytes());
+        }
+        Ok(Some(notice))
+    }
+
+    /// Emergency notices received, oldest first
+    pub fn emergency_notices(&self) -> Vec<crate::ble::EmergencyNotice> {
+        self.emergency_inbox.lock().iter().cloned().collect()
+    }
+
+    /// Remember a notice id; false if it was already seen
+    fn mark_emergency_seen(&self, id: [u8; 16]) -> bool {
+        let mut seen = self.emergency_seen.lock();
+        if seen.contains(&id) {
+            return false;
+        }
+        if seen.len() >= crate::ble::emergency::MAX_SEEN_EMERGENCIES {
+            seen.pop_front();
+        }
+        seen.push_back(id);
+        true
+    }
+
+    /// Sign a payment request with this device's key (merchant side) and
+    /// queue it ahead of other frames for the payer in range
+    pub fn create_payment_request(
+        &self,
+        req: &super::types::CreatePaymentRequestRequest,
+    ) -> Result<crate::ble::PaymentRequestInfo, String> {
+        let decode = |value: &str, field: &str| -> Result<[u8; 32], String> {
+            bs58::decode(value)
+                .into_vec()
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| format!("Invalid {} '{}'", field, value))
+        };
+        let recipient = decode(&req.recipient, "recipient")?;
+        let mint = match &req.mint {
+            Some(mint) => Some((decode(mint, "mint")?, req.decimals)),
+            None => None,
+        };
+        let request = crate::ble::PaymentRequest::sign(
+            &self.device_key,
+            recipient,
+            req.amount,
+            mint,
+            req.memo.clone(),
+            crate::util::clock::now_secs() + req.expires_in_secs,
+        )?;
+        self.outbound_queue
+            .lock()
+            .push_front(request.to_frame_bytes());
+        let info = crate::ble::PaymentRequestInfo::from(&request);
+        {
+            let mut issued = self.issued_payment_requests.lock();
+            if issued.len() >= crate::ble::payment_request::MAX_PENDING_PAYMENT_REQUESTS {
+                issued.pop_front();
+            }
+            issued.push_back(request);
+        }
+        t_info!(
+            "🧾 Issued payment request {} for {} to {}",
+            info.request_id,
+            info.amount,
+            info.recipient
+        );
+        Ok(info)
+    }
+
+    /// Handle a payment request frame from a merchant (payer side): check
+    /// its signature and expiry and keep it until it is paid
+    pub fn ingest_payment_request(
+        &self,
+        frame: &[u8],
+    ) -> Result<crate::ble::PaymentRequestInfo, String> {
+        use crate::ble::payment_request::MAX_PENDING_PAYMENT_REQUESTS;
+
+        let request = crate::ble::PaymentRequest::from_frame_bytes(frame)?;
+        if !request.verify() {
+            return Err("Payment request has an invalid signature".to_string());
+        }
+        if request.is_expired(crate::util::clock::now_secs()) {
+            return Err("Payment request has expired".to_string());
+        }
+        let info = crate::ble::PaymentRequestInfo::from(&request);
+        {
+            let mut requests = self.payment_requests.lock();
+            requests.retain(|r| r.id != request.id);
+            if requests.len() >= MAX_PENDING_PAYMENT_REQUESTS {
+                requests.pop_front();
+            }
+            requests.push_back(request);
+        }
+        t_info!(
+            "🧾 Payment request {} from merchant {}: {} to {}",
+            info.request_id,
+            info.merchant,
+            info.amount,
+            info.recipient
+        );
+        self.sdk.notify(crate::SdkEvent::PaymentRequested {
+            request_id: info.request_id.clone(),
+            recipient: info.recipient.clone(),
+            amount: info.amount,
+            mint: info.mint.clone(),
+        });
+        Ok(info)
+    }
+
+    /// Payment requests received and still payable
+    pub fn payment_requests(&self) -> Vec<crate::ble::PaymentRequestInfo> {
+        let now = crate::util::clock::now_secs();
+        let mut requests = self.payment_requests.lock();
+        requests.retain(|r| !r.is_expired(now));
+        requests
+            .iter()
+            .map(crate::ble::PaymentRequestInfo::from)
+            .collect()
+    }
+
+    /// Build the durable-nonce transaction paying a received request. The
+    /// payer signs it and sends it back with `queue_transaction`.
+    pub fn build_payment_transaction(
+        &self,
+        request_id: &str,
+        payer: &str,
+        nonce: &super::nonce_accounts::NonceAccountInfo,
+    ) -> Result<super::types::PaymentTransactionResponse, FfiError> {
+        let request = self
+            .payment_requests
+            .lock()
+            .iter()
+            .find(|r| hex::encode(r.id) == request_id)
+            .cloned()
+            .ok_or_else(|| {
+                FfiError::not_found(format!("Unknown payment request {}", request_id))
+            })?;
+        if request.is_expired(crate::util::clock::now_secs()) {
+            return Err(FfiError::invalid_input(format!(
+                "Payment request {} has expired",
+                request_id
+            )));
+        }
+        let staleness = *self.nonce_staleness.lock();
+        super::builders::payment_transaction(&request, payer, nonce, &staleness)
+    }
+
+    /// Sign a receipt for a payment this device's wallet just signed (payer
+    /// side), store it and queue it ahead of other frames for the merchant.
+    /// `request_id` ties it to a received payment request, whose recipient
+    /// and amount the transaction must match.
+    pub fn create_payment_receipt(
+        &self,
+        signed_tx: &[u8],
+        request_id: Option<&str>,
+    ) -> Result<crate::storage::PaymentReceiptRecord, FfiError> {
+        let tx: solana_sdk::transaction::Transaction = bincode1::deserialize(signed_tx)
+            .map_err(|e| FfiError::invalid_input(format!("Invalid transaction: {}", e)))?;
+        let tx_signature = tx
+            .signatures
+            .first()
+            .filter(|s| **s != solana_sdk::signature::Signature::default())
+            .ok_or_else(|| {
+                FfiError::new(FfiErrorCode::InvalidSignature, "Transaction is not signed")
+            })?;
+        let (recipient, amount) = crate::intent::transfer_summary(&tx).ok_or_else(|| {
+            FfiError::invalid_input("Transaction has no SOL or SPL Token transfer")
+        })?;
+
+        let request_id = match request_id {
+            Some(request_id) => {
+                let request = self
+                    .payment_requests
+                    .lock()
+                    .iter()
+                    .find(|r| hex::encode(r.id) == request_id)
+                    .cloned()
+                    .ok_or_else(|| {
+                        FfiError::not_found(format!("Unknown payment request {}", request_id))
+                    })?;
+                if !request.is_paid_by(recipient.to_bytes(), amount) {
+                    return Err(FfiError::invalid_input(format!(
+                        "Transaction does not pay payment request {}",
+                        request_id
+                    )));
+                }
+                request.id
+            }
+            None => [0; 16],
+        };
+
+        let receipt = crate::ble::PaymentReceipt::sign(
+            &self.device_key,
+            request_id,
+            tx_signature.as_ref().try_into().unwrap(),
+            recipient.to_bytes(),
+            amount,
+            crate::util::clock::now_secs(),
+        );
+        let record = crate::storage::PaymentReceiptRecord::from_receipt(&receipt, true);
+        self.save_payment_receipt(record.clone())?;
+        if receipt.has_request() {
+            self.payment_requests.lock().retain(|r| r.id != request_id);
+        }
+        self.outbound_queue
+            .lock()
+            .push_front(receipt.to_frame_bytes());
+        t_info!(
+            "🧾 Signed payment receipt for {} ({} to {})",
+            record.tx_signature,
+            record.amount,
+            record.recipient
+        );
+        Ok(record)
+    }
+
+    /// Handle a payment receipt frame from a peer. A payer's receipt is
+    /// verified and stored, and countersigned right away if it pays a
+    /// request this device issued; a merchant's countersignature is added
+    /// to the stored receipt it belongs to.
+    pub fn ingest_payment_receipt(
+        &self,
+        frame: &[u8],
+    ) -> Result<crate::storage::PaymentReceiptRecord, FfiError> {
+        match crate::ble::ReceiptFrame::from_frame_bytes(frame).map_err(FfiError::invalid_input)? {
+            crate::ble::ReceiptFrame::Receipt(receipt) => {
+                if !receipt.verify() {
+                    return Err(FfiError::new(
+                        FfiErrorCode::InvalidSignature,
+                        "Payment receipt has an invalid signature",
+                    ));
+                }
+                let tx_signature = bs58::encode(receipt.tx_signature).into_string();
+                if let Ok(stored) = self.stored_payment_receipt(&tx_signature) {
+                    if stored.is_countersigned() {
+                        // Resent receipt; keep the countersigned copy
+                        return Ok(stored);
+                    }
+                }
+                let issued = receipt.has_request()
+                    && self.issued_payment_requests.lock().iter().any(|r| {
+                        r.id == receipt.request_id
+                            && r.is_paid_by(receipt.recipient, receipt.amount)
+                    });
+                if issued {
+                    self.save_payment_receipt(crate::storage::PaymentReceiptRecord::from_receipt(
+                        &receipt, false,
+                    ))?;
+                    return self.countersign_payment_receipt(&tx_signature);
+                }
+                let record = crate::storage::PaymentReceiptRecord::from_receipt(&receipt, false);
+                self.save_payment_receipt(record.clone())?;
+                t_info!(
+                    "🧾 Payment receipt for {} from {} (not countersigned)",
+                    tx_signature,
+                    record.payer
+                );
+                self.sdk.notify(crate::SdkEvent::PaymentReceipt {
+                    tx_signature,
+                    countersigned: false,
+                });
+                Ok(record)
+            }
+            crate::ble::ReceiptFrame::Countersign {
+                tx_signature,
+                countersignature,
+            } => {
+                let tx_signature = bs58::encode(tx_signature).into_string();
+                let mut receipt = self
+                    .stored_payment_receipt(&tx_signature)?
+                    .to_receipt()
+                    .ok_or_else(|| {
+                        FfiError::storage(format!("Stored receipt {} is corrupt", tx_signature))
+                    })?;
+                if !receipt.apply_countersignature(countersignature) {
+                    return Err(FfiError::new(
+                        FfiErrorCode::InvalidSignature,
+                        "Payment receipt countersignature is invalid",
+                    ));
+                }
+                let record = crate::storage::PaymentReceiptRecord::from_receipt(&receipt, true);
+                self.save_payment_receipt(record.clone())?;
+                t_info!(
+                    "🧾 Payment receipt for {} countersigned by {}",
+                    tx_signature,
+                    record.merchant.as_deref().unwrap_or_default()
+                );
+                self.sdk.notify(crate::SdkEvent::PaymentReceipt {
+                    tx_signature,
+                    countersigned: true,
+                });
+                Ok(record)
+            }
+        }
+    }
+
+    /// Countersign a stored receipt as the merchant (e.g. after checking a
+    /// payment made without a request) and queue the countersignature for
+    /// the payer
+    pub fn countersign_payment_receipt(
+        &self,
+        tx_signature: &str,
+    ) -> Result<crate::storage::PaymentReceiptRecord, FfiError> {
+        let stored = self.stored_payment_receipt(tx_signature)?;
+        if stored.paid {
+            return Err(FfiError::invalid_input(
+                "Only the payee countersigns a payment receipt",
+            ));
+        }
+        let mut receipt = stored.to_receipt().ok_or_else(|| {
+            FfiError::storage(format!("Stored receipt {} is corrupt", tx_signature))
+        })?;
+        receipt.countersign(&self.device_key);
+        let record = crate::storage::PaymentReceiptRecord::from_receipt(&receipt, false);
+        self.save_payment_receipt(record.clone())?;
+        if let Some(frame) = receipt.countersign_frame() {
+            self.outbound_queue.lock().push_front(frame);
+        }
+        t_info!("🧾 Countersigned payment receipt for {}", tx_signature);
+        self.sdk.notify(crate::SdkEvent::PaymentReceipt {
+            tx_signature: tx_signature.to_string(),
+            countersigned: true,
+        });
+        Ok(record)
+    }
+
+    /// Stored payment receipts, newest first
+    pub fn payment_receipts(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<crate::storage::PaymentReceiptRecord>, FfiError> {
+        Ok(self.require_payment_receipts()?.list(offset, limit))
+    }
+
+    fn stored_payment_receipt(
+        &self,
+        tx_signature: &str,
+    ) -> Result<crate::storage::PaymentReceiptRecord, FfiError> {
+        self.require_payment_receipts()?
+            .get(tx_signature)
+            .ok_or_else(|| FfiError::not_found(format!("No payment receipt for {}", tx_signature)))
+    }
+
+    fn save_payment_receipt(
+        &self,
+        record: crate::storage::PaymentReceiptRecord,
+    ) -> Result<(), FfiError> {
+        self.require_payment_receipts()?
+            .save(record)
+            .map_err(|e| FfiError::storage(e.to_string()))
+    }
+
+    fn require_payment_receipts(
+        &self,
+    ) -> Result<Arc<crate::storage::PaymentReceiptStore>, FfiError> {
+        self.payment_receipt_store().ok_or_else(|| {
+            FfiError::not_configured(
+                "Payment receipts need a storage directory (set_secure_storage)",
+            )
+        })
+    }
+
+    /// Sign an envelope as its origin, with the next sequence number, and
+    /// encode it for sending
+    pub fn seal_message(&self, mut message: crate::ble::PolliNetMessage) -> Vec<u8> {
+        message.sequence = self
+            .message_sequence
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+            + 1;
+        message.sign(&self.device_key);
+        message.encode()
+    }
+
+    /// Decode an envelope from a peer, dropping tampered, unsigned and
+    /// replayed messages, and messages whose origin key isn't the one pinned
+    /// for its short id, before anything acts on or relays them
+    pub fn open_message(&self, frame: &[u8]) -> Result<crate::ble::PolliNetMessage, String> {
+        let message = crate::ble::PolliNetMessage::decode(frame).map_err(|e| e.to_string())?;
+        if let Err(e) = message.check_relay() {
+            t_warn!(
+                "⚠️ Dropped envelope after {} hops: {}",
+                message.hop_count(),
+                e
+            );
+            return Err(e.to_string());
+        }
+        let origin = message
+            .origin
+            .filter(|_| message.signature.is_some())
+            .ok_or_else(|| "Unsigned envelope".to_string())?;
+        if let Err(e) = self.check_pinned_origin(&origin, message.is_control()) {
+            t_warn!("⚠️ Dropped envelope: {}", e);
+            return Err(e);
+        }
+        // Only origins that verified and match their pinned key reach the
+        // window, so made-up keys can't crowd real ones out of it
+        if !self.replay_window.lock().accept(&origin, message.sequence) {
+            let error_msg = format!(
+                "Replayed message (kind 0x{:02x}, sequence {})",
+                message.kind, message.sequence
+            );
+            t_warn!("⚠️ Dropped envelope: {}", error_msg);
+            return Err(error_msg);
+        }
+        Ok(message)
+    }
+
+    /// Sign a receipt for `tx_id`, store it and queue it for `peer_id` (the
+    /// device the transaction came from), if known
+    fn issue_relay_receipt(
+        &self,
+        tx_id: &str,
+        action: crate::ble::ReceiptAction,
+        peer_id: Option<String>,
+    ) {
+        let receipt = crate::ble::RelayReceipt::sign(
+            &self.device_key,
+            crate::ble::tx_id_hash(tx_id),
+            action,
+            Self::current_timestamp(),
+        );
+        if let Some(store) = self.relay_receipts() {
+            let record = crate::storage::ReceiptRecord::from_receipt(&receipt, true);
+            if let Err(e) = store.record(record) {
+                t_warn!("⚠️ Failed to store relay receipt: {}", e);
+            }
+        }
+        if let Some(peer_id) = peer_id {
+            t_debug!("🧾 Issued {} receipt for {}", action.as_str(), peer_id);
+            self.queue_relay_receipt(peer_id, receipt);
+        }
+    }
+
+    fn queue_relay_receipt(&self, peer_id: String, receipt: crate::ble::RelayReceipt) {
+        let mut queue = self.outbound_receipts.lock();
+        if queue.len() >= MAX_OUTBOUND_RECEIPTS {
+            queue.pop_front();
+        }
+        queue.push_back((peer_id, receipt));
+    }
+
+    /// Next relay receipt frame to send: (peer_id, frame bytes)
+    pub fn next_relay_receipt(&self) -> Option<(String, Vec<u8>)> {
+        loop {
+            let (peer_id, receipt) = self.outbound_receipts.lock().pop_front()?;
+            match receipt.to_frame_bytes() {
+                Ok(frame) => return Some((peer_id, frame)),
+                Err(e) => {
+                    t_warn!("⚠️ Dropping relay receipt: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Ingest a relay receipt frame from a peer. Verified receipts are stored
+    /// and passed on toward whoever sent us the transaction, so they reach the
+    /// origin hop by hop. Returns false for duplicates and invalid receipts.
+    pub fn ingest_relay_receipt(&self, frame: &[u8]) -> Result<bool, String> {
+        let mut receipt = crate::ble::RelayReceipt::from_frame_bytes(frame)?;
+        if !receipt.verify() {
+            t_warn!(
+                "⚠️ Dropped relay receipt with a bad signature for tx_id_hash={}",
+                hex::encode(receipt.tx_id_hash)
+            );
+            return Ok(false);
+        }
+        if receipt.relayer == self.device_key.verifying_key().to_bytes() {
+            return Ok(false);
+        }
+
+        let record = crate::storage::ReceiptRecord::from_receipt(&receipt, false);
+        if let Some(store) = self.relay_receipts() {
+            if !store.record(record.clone()).map_err(|e| e.to_string())? {
+                return Ok(false);
+            }
+        }
+        t_info!(
+            "🧾 Relay receipt: tx {} {} by {}",
+            record.tx_id_hash,
+            record.action.as_str(),
+            record.relayer
+        );
+        self.emit_event(ProtocolEvent::relay_receipt(
+            record.tx_id_hash.clone(),
+            record.action.as_str(),
+            record.relayer.clone(),
+        ));
+
+        // Pass it on if we carried the transaction ourselves
+        let from_peer = self
+            .tx_history()
+            .and_then(|h| h.find_where(|tx_id| crate::ble::tx_id_hash(tx_id) == receipt.tx_id_hash))
+            .and_then(|r| r.peer_id);
+        match from_peer {
+            Some(peer_id) if receipt.increment_hop() => {
+                self.queue_relay_receipt(peer_id, receipt);
+            }
+            // Our own transaction reached a gateway
+            None if receipt.action == crate::ble::ReceiptAction::Submitted => {
+                self.record_mesh_stat(crate::storage::MeshStatsEvent::Delivered {
+                    hops: receipt.hop_count.saturating_add(1),
+                });
+            }
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    /// Start auto-submitting received transactions (replacing a running
+    /// gateway). Must be called within a tokio runtime.
+    pub fn start_gateway(
+        self: &Arc<Self>,
+        submitter: Arc<dyn super::gateway::TransactionSubmitter>,
+    ) {
+        let handle = super::gateway::GatewayService::new(self.clone(), submitter).spawn();
+        if let Some(previous) = self.gateway.lock().replace(handle) {
+            previous.stop();
+        }
+    }
+
+    /// Stop the gateway; returns false if none was running
+    pub fn stop_gateway(&self) -> bool {
+        match self.gateway.lock().take() {
+            Some(gateway) => {
+                gateway.stop();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Enable, replace or (with `None`) disable per-origin relay limits;
+    /// replacing the policy resets its counters
+    pub fn set_relay_policy(&self, config: Option<super::relay_policy::RelayPolicyConfig>) {
+        *self.relay_policy.lock() =
+            config.map(|config| Arc::new(super::relay_policy::RelayPolicy::new(config)));
+    }
+
+    /// Relay policy counters, if a policy is set
+    pub fn relay_policy_stats(&self) -> Option<super::relay_policy::RelayPolicyStats> {
+        self.relay_policy
+            .lock()
+            .as_ref()
+            .map(|policy| policy.stats())
+    }
+
+    /// Set the device role (outbound priorities and advertised capabilities)
+    pub fn set_device_role(&self, role: Option<crate::DeviceRole>) {
+        self.sdk.queue_manager().set_role(role);
+    }
+
+    pub fn device_role(&self) -> Option<crate::DeviceRole> {
+        self.sdk.queue_manager().role()
+    }
+
+    /// Capability byte for the host's BLE advertisement (see
+    /// [`crate::role::capability`]); devices without a role advertise as originators
+    pub fn capability_flags(&self) -> u8 {
+        let role = self.device_role().unwrap_or(crate::DeviceRole::Originator);
+        let mut flags = role.capability() | crate::role::capability::COMPACT_FRAMES;
+        if self.connectivity.is_online() {
+            flags |= crate::role::capability::ONLINE;
+        }
+        flags
+    }
+
+    /// Pin a peer's base58 device key (e.g. exchanged in person) under its
+    /// short id, replacing whatever key was pinned there on first use.
+    /// Envelopes from that short id must then be signed by this key, and
+    /// control messages are only accepted from pinned keys.
+    pub fn pin_peer_key(&self, device_key: &str) -> Result<(), String> {
+        let key: [u8; 32] = bs58::decode(device_key)
+            .into_vec()
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .filter(|key| ed25519_dalek::VerifyingKey::from_bytes(key).is_ok())
+            .ok_or_else(|| format!("Invalid device key '{}'", device_key))?;
+        let short_id = [key[0], key[1], key[2], key[3]];
+        let mut pinned = self.pinned_keys.lock();
+        if pinned.len() >= MAX_PINNED_KEYS && !pinned.contains_key(&short_id) {
+            let evicted = pinned
+                .iter()
+                .find(|(_, (_, by_host))| !by_host)
+                .map(|(id, _)| *id)
+                .ok_or_else(|| format!("At most {} device keys can be pinned", MAX_PINNED_KEYS))?;
+            pinned.remove(&evicted);
+        }
+        pinned.insert(short_id, (key, true));
+        Ok(())
+    }
+
+    /// Check an envelope origin against the key pinned for its short id.
+    /// Unknown origins of data messages are pinned on first use while
+    /// there is room; control messages need a key pinned by the host.
+    fn check_pinned_origin(&self, origin: &[u8; 32], control: bool) -> Result<(), String> {
+        let short_id = [origin[0], origin[1], origin[2], origin[3]];
+        let mut pinned = self.pinned_keys.lock();
+        match pinned.get(&short_id) {
+            Some((key, by_host)) if key == origin => {
+                if control && !by_host {
+                    return Err(format!(
+                        "Control message from {} whose key was not pinned by the host",
+                        hex::encode(short_id)
+                    ));
+                }
+                Ok(())
+            }
+            Some(_) => Err(format!(
+                "Origin key does not match the key pinned for {}",
+                hex::encode(short_id)
+            )),
+            None if control => Err(format!(
+                "Control message from unknown origin {}",
+                hex::encode(short_id)
+            )),
+            None if pinned.len() >= MAX_PINNED_KEYS => Err(format!(
+                "Too many pinned origins to accept {}",
+                hex::encode(short_id)
+            )),
+            None => {
+                pinned.insert(short_id, (*origin, false));
+                Ok(())
+            }
+        }
+    }
+
+    /// Remember the origin key of a transaction; the first one seen stays
+    fn record_tx_origin(&self, tx_id: &str, origin: [u8; 32]) {
+        let mut origins = self.tx_origins.lock();
+        if origins.contains_key(tx_id) {
+            return;
+        }
+        if origins.len() >= MAX_TX_ORIGINS {
+            if let Some(evicted) = origins.keys().next().cloned() {
+                origins.remove(&evicted);
+            }
+        }
+        origins.insert(tx_id.to_string(), origin);
+    }
+
+    /// Record the capability byte a peer advertises (see
+    /// [`crate::role::capability`])
+    pub fn set_peer_capabilities(&self, peer_id: &str, capabilities: u8) {
+        let mut peers = self.peer_capabilities.lock();
+        if peers.len() >= MAX_PEER_DIGESTS && !peers.contains_key(peer_id) {
+            if let Some(evicted) = peers.keys().next().cloned() {
+                peers.remove(&evicted);
+            }
+        }
+        peers.insert(peer_id.to_string(), capabilities);
+    }
+
+    /// Whether `peer_id` reads compact fragment frames
+    pub fn peer_supports_compact_frames(&self, peer_id: &str) -> bool {
+        self.peer_capabilities
+            .lock()
+            .get(peer_id)
+            .is_some_and(|caps| caps & crate::role::capability::COMPACT_FRAMES != 0)
+    }
+
+    fn add_peer_capabilities(&self, peer_id: &str, capabilities: u8) {
+        let current = self
+            .peer_capabilities
+            .lock()
+            .get(peer_id)
+            .copied()
+            .unwrap_or(0);
+        if current & capabilities != capabilities {
+            self.set_peer_capabilities(peer_id, current | capabilities);
+        }
+    }
+
+    /// Turn advertisement privacy mode on or off
+    pub fn set_privacy(&self, privacy: crate::ble::PrivacySettings) {
+        *self.privacy.lock() = privacy;
+    }
+
+    pub fn privacy(&self) -> crate::ble::PrivacySettings {
+        *self.privacy.lock()
+    }
+
+    /// Manufacturer data to advertise now; in privacy mode, advertise again
+    /// at `rotates_at` to pick up the next pseudonym
+    pub fn advertisement(&self) -> crate::ble::Advertisement {
+        crate::ble::Advertisement::build(
+            &self.device_key,
+            self.capability_flags(),
+            &self.privacy(),
+            crate::util::clock::now_secs(),
+        )
+    }
+
+    /// Internet connectivity state, followed by the gateway
+    pub fn connectivity(&self) -> &Arc<super::connectivity::ConnectivityMonitor> {
+        &self.connectivity
+    }
+
+    /// Record a verified mesh confirmation: publishes `ConfirmationReceived` and
+    /// updates the transaction history. Confirmations carry `tx_id_hash(tx_id)`
+    /// rather than the tx_id itself.
+    pub fn record_confirmation(&self, confirmation: &crate::ble::MeshConfirmation) {
+        let success = confirmation.status == crate::ble::ConfirmationStatus::Success;
+        self.sdk.notify(crate::SdkEvent::ConfirmationReceived {
+            tx_id_hash: hex::encode(confirmation.tx_id_hash),
+            success,
+        });
+
+        // Success confirmations may carry the signature instead of a slot
+        let signature = (success && confirmation.slot_or_error.len() == 64)
+            .then(|| bs58::encode(&confirmation.slot_or_error).into_string());
+        let updates =
+            self.tx_watch
+                .update_mesh(&confirmation.tx_id_hash, signature.as_deref(), success);
+        self.publish_watch_updates(updates);
+
+        let Some(history) = self.tx_history() else {
+            return;
+        };
+        let status = if success {
+            crate::storage::TxHistoryStatus::Confirmed
+        } else {
+            crate::storage::TxHistoryStatus::Failed
+        };
+        let matches = |tx_id: &str| crate::ble::tx_id_hash(tx_id) == confirmation.tx_id_hash;
+        if let Err(e) = history.update_status_where(matches, status) {
+            t_warn!("⚠️ Failed to update transaction history: {}", e);
+        }
+    }
+
+    /// Watch a transaction by mesh id or signature; `callback` (and a `TxStatus`
+    /// event) fires on every status change, whether it comes from this device's
+    /// gateway submission or from a mesh confirmation
+    pub fn watch_transaction(
+        &self,
+        id: &str,
+        callback: Option<super::tx_watch::WatchCallback>,
+    ) -> Result<super::tx_watch::WatchedTransaction, FfiError> {
+        self.tx_watch
+            .watch(id, callback)
+            .map_err(FfiError::invalid_input)
+    }
+
+    /// Stop watching; false if the watch already ended
+    pub fn unwatch_transaction(&self, watch_id: u64) -> bool {
+        self.tx_watch.unwatch(watch_id)
+    }
+
+    pub fn watched_transactions(&self) -> Vec<super::tx_watch::WatchedTransaction> {
+        self.tx_watch.list()
+    }
+
+    /// Report an RPC outcome for a submission of `tx_id` to its watchers
+    pub(crate) fn update_tx_watch(
+        &self,
+        tx_id: &str,
+        signature: &str,
+        status: super::tx_watch::WatchedStatus,
+        error: Option<String>,
+    ) {
+        let updates = self.tx_watch.update_rpc(tx_id, signature, status, error);
+        self.publish_watch_updates(updates);
+    }
+
+    fn publish_watch_updates(&self, updates: Vec<super::tx_watch::WatchedTransaction>) {
+        for watched in &updates {
+            self.emit_event(ProtocolEvent::tx_status(watched));
+        }
+    }
+
+    pub(crate) fn update_tx_history(&self, tx_id: &str, status: crate::storage::TxHistoryStatus) {
+        if let Some(history) = self.tx_history() {
+            if let Err(e) = history.update_status(tx_id, status) {
+                t_warn!("⚠️ Failed to update transaction history: {}", e);
+            }
+        }
+    }
+
+    /// Queue a text message for `peer_id`; returns the message id
+    ///
+    /// The frame is sent by the host via `next_text_frame`; delivery status
+    /// becomes `DELIVERED` once the recipient's ack is ingested.
+    pub fn send_text_message(
+        &self,
+        peer_id: &str,
+        body: &str,
+    ) -> Result<super::messaging::OutgoingTextMessage, String> {
+        let message = self.text_messaging.send(peer_id, body)?;
+        self.log_text(
+            peer_id,
+            crate::storage::MessageDirection::Outbound,
+            &message.message_id,
+            body,
+        );
+        t_info!(
+            "💬 Queued text message {} for {}",
+            message.message_id,
+            peer_id
+        );
+        Ok(message)
+    }
+
+    /// Handle a text frame (a signed envelope) received from `peer_id`
+    ///
+    /// The envelope is opened first, so tampered and replayed frames are
+    /// dropped before anything acts on them. New messages are logged, raise a `TextMessage` event and queue an ack;
+    /// acks and read receipts update the status of our own messages (logged
+    /// and raised as `MessageStatus` events).
+    pub fn ingest_text_frame(
+        &self,
+        peer_id: &str,
+        data: &[u8],
+    ) -> Result<super::messaging::IngestedText, String> {
+        let message = self.open_message(data)?;
+        if message.kind != crate::ble::PacketType::TextMessage as u8 {
+            return Err(format!(
+                "Envelope of kind 0x{:02x} is not a text frame",
+                message.kind
+            ));
+        }
+        let ingested = self
+            .text_messaging
+            .ingest_frame(peer_id, &message.payload)?;
+        match &ingested {
+            super::messaging::IngestedText::Message(message) => {
+                t_info!("💬 Text message {} from {}", message.message_id, peer_id);
+                self.log_text(
+                    peer_id,
+                    crate::storage::MessageDirection::Inbound,
+                    &message.message_id,
+                    &message.body,
+                );
+                self.emit_event(ProtocolEvent::text_message(peer_id, &message.body));
+            }
+            super::messaging::IngestedText::Delivered(Some(message)) => {
+                t_info!("✅ Text message {} delivered", message.message_id);
+                self.record_text_status(message);
+            }
+            super::messaging::IngestedText::Read(messages) => {
+                for message in messages {
+                    self.record_text_status(message);
+                }
+            }
+            super::messaging::IngestedText::Delivered(None)
+            | super::messaging::IngestedText::Duplicate => {}
+        }
+        Ok(ingested)
+    }
+
+    /// Next text or ack frame to send, sealed in a signed envelope, if one
+    /// fits in `max_len`
+    pub fn next_text_frame(&self, max_len: usize) -> Option<Vec<u8>> {
+        let frame = self
+            .text_messaging
+            .next_frame(max_len.checked_sub(crate::ble::envelope::SIGNED_ENVELOPE_OVERHEAD)?)?;
+        Some(self.seal_message(crate::ble::PolliNetMessage::new(
+            crate::ble::PacketType::TextMessage as u8,
+            frame,
+        )))
+    }
+
+    /// Pop the oldest received text message not yet retrieved by the host
+    pub fn next_text_message(&self) -> Option<super::messaging::IncomingTextMessage> {
+        self.text_messaging.next_received()
+    }
+
+    /// Number of received text messages waiting for retrieval
+    pub fn pending_text_message_count(&self) -> usize {
+        self.text_messaging.received_count()
+    }
+
+    /// Delivery status of a sent text message
+    pub fn text_message_status(
+        &self,
+        message_id: &str,
+    ) -> Option<super::messaging::OutgoingTextMessage> {
+        self.text_messaging.status(message_id)
+    }
+
+    /// Send read receipts to `peer_id` for messages received from it
+    pub fn mark_text_messages_read(
+        &self,
+        peer_id: &str,
+        message_ids: &[String],
+    ) -> Result<(), String> {
+        self.text_messaging.mark_read(message_ids)?;
+        for message_id in message_ids {
+            self.log_receipt(
+                peer_id,
+                crate::storage::MessageDirection::Outbound,
+                message_id,
+                super::messaging::TextMessageStatus::Read,
+            );
+        }
+        Ok(())
+    }
+
+    /// Last `limit` messages exchanged with `peer_id` (oldest first) with their
+    /// statuses; `None` without a message log
+    pub fn conversation(
+        &self,
+        peer_id: &str,
+        limit: usize,
+    ) -> Option<Vec<super::messaging::ConversationMessage>> {
+        let log = self.message_log()?;
+        let mut messages =
+            super::messaging::fold_conversation(log.conversation(peer_id, usize::MAX));
+        let skip = messages.len().saturating_sub(limit);
+        messages.drain(..skip);
+        // Statuses not logged yet (QUEUED/SENT) come from the live outbox
+        for message in &mut messages {
+            let live = message
+                .message_id
+                .as_deref()
+                .and_then(|id| self.text_messaging.status(id));
+            if let (Some(live), Some(status)) = (live, message.status.as_mut()) {
+                if live.status.is_pending() && status.is_pending() {
+                    *status = live.status;
+                }
+            }
+        }
+        Some(messages)
+    }
+
+    /// Requeue unacknowledged outgoing messages found in the message log
+    /// (e.g. after a restart); returns how many were requeued
+    pub fn resume_text_messages(&self) -> usize {
+        let Some(log) = self.message_log() else {
+            return 0;
+        };
+        let mut resumed = 0;
+        for peer_id in log.peers() {
+            for message in
+                super::messaging::fold_conversation(log.conversation(&peer_id, usize::MAX))
+            {
+                let (Some(message_id), Some(status)) = (message.message_id, message.status) else {
+                    continue;
+                };
+                if message.direction != crate::storage::MessageDirection::Outbound {
+                    continue;
+                }
+                let outgoing = super::messaging::OutgoingTextMessage {
+                    message_id,
+                    peer_id: message.peer_id,
+                    body: message.body,
+                    created_at: message.timestamp,
+                    status,
+                    attempts: 0,
+                };
+                match self.text_messaging.restore(outgoing) {
+                    Ok(true) => resumed += 1,
+                    Ok(false) => {}
+                    Err(e) => {
+                        t_warn!("⚠️ Failed to resume text message: {}", e);
+                    }
+                }
+            }
+        }
+        if resumed > 0 {
+            t_info!("💬 Resumed {} unacknowledged text messages", resumed);
+        }
+        resumed
+    }
+
+    /// Log and announce a status change of one of our messages
+    fn record_text_status(&self, message: &super::messaging::OutgoingTextMessage) {
+        // Acks and read receipts come from the peer; failures are local
+        let direction = if message.status == super::messaging::TextMessageStatus::Failed {
+            crate::storage::MessageDirection::Outbound
+        } else {
+            crate::storage::MessageDirection::Inbound
+        };
+        self.log_receipt(
+            &message.peer_id,
+            direction,
+            &message.message_id,
+            message.status,
+        );
+        self.emit_event(ProtocolEvent::message_status(
+            &message.message_id,
+            message.status.as_str(),
+        ));
+    }
+
+    fn log_text(
+        &self,
+        peer_id: &str,
+        direction: crate::storage::MessageDirection,
+        message_id: &str,
+        body: &str,
+    ) {
+        self.append_to_log(
+            crate::storage::LoggedMessage::new(
+                peer_id,
+                direction,
+                crate::storage::MessageKind::Text,
+                body,
+            )
+            .with_message_id(message_id),
+        );
+    }
+
+    fn log_receipt(
+        &self,
+        peer_id: &str,
+        direction: crate::storage::MessageDirection,
+        message_id: &str,
+        status: super::messaging::TextMessageStatus,
+    ) {
+        self.append_to_log(
+            crate::storage::LoggedMessage::new(
+                peer_id,
+                direction,
+                crate::storage::MessageKind::Receipt,
+                status.as_str(),
+            )
+            .with_message_id(message_id),
+        );
+    }
+
+    fn append_to_log(&self, entry: crate::storage::LoggedMessage) {
+        if let Some(log) = self.message_log() {
+            if let Err(e) = log.append(&entry) {
+                t_warn!("⚠️ Failed to log text message: {}", e);
+            }
+        }
+    }
+
+    /// Record a scan observation for density estimation, publishing
+    /// `PeerDiscovered` for peers not seen within the density window
+    pub fn record_scan_result(&self, peer_id: &str) {
+        let is_new = self.density_estimator.lock().record(peer_id);
+        if is_new {
+            self.sdk.notify(crate::SdkEvent::PeerDiscovered {
+                peer_id: peer_id.to_string(),
+            });
+        }
+    }
+
+    /// Adapter state, role and connectivity
+    pub fn adapter_status(&self) -> super::types::AdapterStatus {
+        super::types::AdapterStatus {
+            state: self.sdk.adapter_state(),
+            role: self.device_role(),
+            capability_flags: self.capability_flags(),
+            online: self.connectivity.is_online(),
+        }
+    }
+
+    /// Adapter, peer and queue status (its `Display` form is the human-readable summary)
+    pub fn ble_status(&self) -> super::types::BleStatus {
+        let connected_peers = self
+            .health_monitor
+            .get_snapshot()
+            .peers
+            .iter()
+            .filter(|p| p.state == crate::ble::HealthPeerState::Connected)
+            .count();
+        super::types::BleStatus {
+            adapter: self.adapter_status(),
+            nearby_peers: self.density_estimator.lock().evict_and_count(),
+            connected_peers,
+            outbound_frames: self.outbound_queue_size(),
+            pending_reassemblies: self.inbound_buffers.lock().len(),
+            received_transactions: self.received_queue_size(),
+        }
+    }
+
+    /// Discovered/connected peers, per-peer stats and topology for mesh views
+    pub fn mesh_state(&self) -> super::types::MeshStateFFI {
+        let snapshot = self.health_monitor.get_snapshot();
+        let recent = self.density_estimator.lock().recent_peers();
+        let cooldown = self.cooldown_list.lock();
+        let discovered_peers = recent
+            .into_iter()
+            .map(|(peer_id, last_seen)| super::types::DiscoveredPeerFFI {
+                cooling_down: cooldown.is_cooling(&peer_id),
+                peer_id,
+                last_seen,
+            })
+            .collect();
+        drop(cooldown);
+
+        let mut connected_peers: Vec<String> = snapshot
+            .peers
+            .iter()
+            .filter(|p| p.state == crate::ble::HealthPeerState::Connected)
+            .map(|p| p.peer_id.clone())
+            .chain(snapshot.topology.direct_connections.iter().cloned())
+            .collect();
+        connected_peers.sort();
+        connected_peers.dedup();
+
+        super::types::MeshStateFFI {
+            discovered_peers,
+            connected_peers,
+            peers: snapshot.peers,
+            topology: snapshot.topology,
+            metrics: snapshot.metrics,
+        }
+    }
+
+    /// Register (or clear) the host event callback
+    ///
+    /// The callback runs synchronously on the thread that produced the event
+    /// and must not block; it may call back into the transport.
+    pub fn set_event_callback(&self, callback: Option<EventCallback>) {
+        *self.event_callback.lock() = callback;
+    }
+
+    /// Flush persisted state and detach the host callback before the engine is dropped
+    pub async fn shutdown(&self) {
+        self.set_event_callback(None);
+        self.stop_gateway();
+        self.connectivity.stop();
+        if let Err(e) = self.sdk.shutdown().await {
+            t_warn!("⚠️ {}", e);
+        }
+        if let Some(dir) = self.get_queue_storage_dir() {
+            if let Err(e) = self.save_received_queue(&dir) {
+                t_warn!("⚠️ Failed to save received queue on shutdown: {}", e);
+            }
+        }
+        if let Some(stats) = self.mesh_stats() {
+            if let Err(e) = stats.flush() {
+                t_warn!("⚠️ Failed to save mesh statistics on shutdown: {}", e);
+            }
         }
+        t_info!("🛑 Transport engine shut down");
+    }
 
-        Ok(ffi_fragments)
+    /// Deliver an event to the registered callback, if any
+    pub fn emit_event(&self, event: ProtocolEvent) {
+        // Clone out of the lock so the callback can re-enter the transport
+        let callback = self.event_callback.lock().clone();
+        if let Some(callback) = callback {
+            callback(&event);
+        }
     }
 
-    /// Queue pre-fragmented transaction fragments directly to outbound queue
-    /// This is used when fragments are already created (e.g., from accept_and_queue_external_transaction)
-    pub fn queue_fragments(
+    /// Write an encrypted archive of all persisted state to `path`
+    ///
+    /// The received queue is flushed first so the archive matches memory.
+    pub fn backup_state(
         &self,
-        fragments: &[crate::ble::mesh::TransactionFragment],
-    ) -> Result<(), String> {
-        let mut queue = self.outbound_queue.lock();
+        path: &str,
+        passphrase: &str,
+    ) -> Result<crate::storage::BackupSummary, String> {
+        let storage = self
+            .secure_storage
+            .as_ref()
+            .ok_or("Backup requires a storage directory")?;
+
+        if let Some(queue_storage_dir) = self.get_queue_storage_dir() {
+            self.save_received_queue(&queue_storage_dir)?;
+        }
 
-        for fragment in fragments {
-            let binary_bytes = bincode1::serialize(fragment)
-                .map_err(|e| format!("Failed to serialize fragment: {}", e))?;
-            if queue.len() >= MAX_OUTBOUND_FRAMES {
-                queue.pop_front();
-                t_warn!(
-                    "⚠️ Outbound queue overflow: dropped oldest frame to make room (max {})",
-                    MAX_OUTBOUND_FRAMES
-                );
+        let (archive, summary) = crate::storage::create_backup(storage, passphrase)
+            .map_err(|e| format!("Failed to create backup: {}", e))?;
+        crate::storage::write_atomic(std::path::Path::new(path), &archive)
+            .map_err(|e| format!("Failed to write backup: {}", e))?;
+
+        t_info!("🗄️ Backup written to {}", path);
+        Ok(summary)
+    }
+
+    /// Replace all persisted state with the archive at `path` and reload it
+    pub fn restore_state(
+        &self,
+        path: &str,
+        passphrase: &str,
+    ) -> Result<crate::storage::BackupSummary, String> {
+        let storage = self
+            .secure_storage
+            .as_ref()
+            .ok_or("Restore requires a storage directory")?;
+
+        let archive =
+            std::fs::read(path).map_err(|e| format!("Failed to read backup {}: {}", path, e))?;
+        let summary = crate::storage::restore_backup(storage, &archive, passphrase)
+            .map_err(|e| format!("Failed to restore backup: {}", e))?;
+
+        if let Some(queue_storage_dir) = self.get_queue_storage_dir() {
+            self.load_received_queue(&queue_storage_dir)?;
+        }
+        // Reopen so the log picks up the restored file sizes
+        if let Some(log) = self.message_log() {
+            match crate::storage::MessageLog::open(log.dir()) {
+                Ok(reopened) => self.set_message_log(Some(Arc::new(reopened))),
+                Err(e) => {
+                    t_warn!("⚠️ Failed to reopen message log after restore: {}", e);
+                }
+            }
+        }
+        if let Some(history) = self.tx_history() {
+            match crate::storage::TxHistory::open(history.dir()) {
+                Ok(reopened) => self.set_tx_history(Some(Arc::new(reopened))),
+                Err(e) => {
+                    t_warn!(
+                        "⚠️ Failed to reopen transaction history after restore: {}",
+                        e
+                    );
+                }
             }
-            queue.push_back(binary_bytes);
         }
 
-        t_info!(
-            "✅ Queued {} fragments directly to outbound queue",
-            fragments.len()
-        );
-        Ok(())
+        t_info!("🗄️ Restored state from {}", path);
+        Ok(summary)
     }
 
-    /// Periodic tick for retries and timeouts
-    pub fn tick(&self, _now_ms: u64) -> Vec<Vec<u8>> {
-        t_debug!("⏱️ HostBleTransport::tick() called (retry/timeout logic not yet implemented)");
-        Vec::new()
+    /// Run a cleanup pass now (`None` when no budget is configured)
+    pub fn enforce_storage_quota(&self) -> Option<crate::storage::CleanupReport> {
+        self.storage_quota
+            .lock()
+            .as_mut()
+            .map(|quota| quota.enforce())
     }
 
     /// Get current metrics snapshot
     pub fn metrics(&self) -> MetricsSnapshot {
+        let compression = self.sdk.get_compression_stats();
         let metrics = self.metrics.lock();
         let snapshot = MetricsSnapshot {
             fragments_buffered: metrics.fragments_buffered,
             transactions_complete: metrics.transactions_complete,
             reassembly_failures: metrics.reassembly_failures,
+            corrupt_fragments: metrics.corrupt_fragments,
+            reassembly_evictions: metrics.reassembly_evictions,
+            reassembly_timeouts: metrics.reassembly_timeouts,
+            inbound_dropped: self.inbound_queue.dropped() as u32,
             last_error: metrics.last_error.clone(),
             updated_at: metrics.updated_at,
+            compression_bytes_in: compression.bytes_in,
+            compression_bytes_out: compression.bytes_out,
+            compression_ratio: compression.ratio,
         };
 
         t_debug!(
@@ -879,11 +3286,10 @@ impl HostBleTransport {
     /// Called when a BLE confirmation (success OR failure) is received so the
     /// originating device stops re-broadcasting a transaction that has already
     /// been handled by a relay peer.  The `tx_id` is the hex-encoded SHA-256
-    /// hash that is encoded in the first 32 bytes of every bincode-serialized
-    /// `TransactionFragment`.
+    /// hash carried in the header of every fragment frame.
     pub fn clear_outbound_for_tx(&self, tx_id: &str) -> usize {
-        let id_bytes = match hex::decode(tx_id) {
-            Ok(b) if b.len() == 32 => b,
+        let id_bytes: [u8; 32] = match hex::decode(tx_id).map(<[u8; 32]>::try_from) {
+            Ok(Ok(b)) => b,
             _ => {
                 t_warn!("⚠️  clear_outbound_for_tx: invalid tx_id '{}'", tx_id);
                 return 0;
@@ -891,7 +3297,7 @@ impl HostBleTransport {
         };
         let mut queue = self.outbound_queue.lock();
         let before = queue.len();
-        queue.retain(|entry| entry.len() < 32 || &entry[..32] != id_bytes.as_slice());
+        queue.retain(|entry| frame_transaction_id(entry) != Some(id_bytes));
         let removed = before - queue.len();
         if removed > 0 {
             t_info!(
@@ -903,6 +3309,88 @@ impl HostBleTransport {
         removed
     }
 
+    /// Cancel a transaction (superseded, confirmed or expired): drop its
+    /// fragments here and queue a TX_CANCEL frame, signed with the device
+    /// key, so peers drop theirs. Peers only honor it for transactions this
+    /// device originated. Returns the number of fragments and frames
+    /// dropped locally.
+    pub fn cancel_transaction(
+        &self,
+        tx_id: &str,
+        reason: crate::ble::CancelReason,
+    ) -> Result<usize, String> {
+        let id: [u8; 32] = hex::decode(tx_id)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| format!("Invalid tx_id '{}'", tx_id))?;
+        let cancel = crate::ble::TxCancelFrame::sign(&self.device_key, id, reason);
+        let dropped = self.apply_cancel(&cancel);
+        self.outbound_queue
+            .lock()
+            .push_front(cancel.to_frame_bytes());
+        Ok(dropped)
+    }
+
+    /// Honor a TX_CANCEL frame from a peer. It must be signed by the origin
+    /// key the transaction's first fragment carried; cancels for
+    /// transactions whose origin is unknown here are refused. The frame is
+    /// passed on only if it dropped something here, so cancels for already
+    /// cancelled transactions stop spreading. Returns whether it did.
+    pub fn ingest_tx_cancel(&self, frame: &[u8]) -> Result<bool, String> {
+        let mut cancel = crate::ble::TxCancelFrame::from_frame_bytes(frame)?;
+        let tx_id = hex::encode(cancel.tx_id);
+        let origin = self
+            .tx_origins
+            .lock()
+            .get(&tx_id)
+            .copied()
+            .ok_or_else(|| format!("Unknown origin for cancelled tx {}", tx_id))?;
+        if !cancel.verify(&origin) {
+            t_warn!(
+                "⚠️ Ignoring TX_CANCEL for tx {} not signed by its origin",
+                tx_id
+            );
+            return Err(format!(
+                "TX_CANCEL for tx {} is not signed by its origin",
+                tx_id
+            ));
+        }
+        let dropped = self.apply_cancel(&cancel);
+        if dropped > 0 && cancel.increment_hop() {
+            self.outbound_queue
+                .lock()
+                .push_front(cancel.to_frame_bytes());
+        }
+        Ok(dropped > 0)
+    }
+
+    /// Drop a cancelled transaction's reassembly buffer and queued frames,
+    /// and tombstone it so late fragments are ignored
+    fn apply_cancel(&self, cancel: &crate::ble::TxCancelFrame) -> usize {
+        let tx_id = hex::encode(cancel.tx_id);
+        let buffered = self
+            .inbound_buffers
+            .lock()
+            .remove(&tx_id)
+            .map_or(0, |fragments| fragments.len());
+        let queued = self.clear_outbound_for_tx(&tx_id);
+
+        let hash = crate::ble::tx_id_hash(&tx_id);
+        self.tombstones.lock().insert(
+            hex::encode(hash),
+            crate::ble::Tombstone::new(hash, crate::ble::CONFIRMATION_TTL_SECS / 2),
+        );
+
+        t_info!(
+            "🚫 Cancelled tx {} ({:?}): dropped {} buffered fragments, {} queued frames",
+            &tx_id[..16],
+            cancel.reason,
+            buffered,
+            queued
+        );
+        buffered + queued
+    }
+
     /// Clear all reassembly buffers and completed transactions
     /// Note: This does NOT clear nonce data
     pub fn clear_all_reassembly_buffers(&self) {
@@ -971,6 +3459,19 @@ impl HostBleTransport {
             tx_hash.len()
         );
 
+        let peer_id = self.inbound_peers.lock().remove(&tx_hash_hex);
+
+        // Open transactions sealed for this device; sealed ones for another
+        // gateway stay opaque and are only carried on
+        let (tx_bytes, tx_hash, tx_hash_hex) = match self.open_sealed(&tx_bytes) {
+            Some(opened) => {
+                let tx_hash = Sha256::digest(&opened).to_vec();
+                let tx_hash_hex = hex::encode(&tx_hash);
+                (opened, tx_hash, tx_hash_hex)
+            }
+            None => (tx_bytes, tx_hash, tx_hash_hex),
+        };
+
         // Check if transaction was already submitted (O(1) HashMap lookup)
         let submitted = self.submitted_tx_hashes.lock();
         if submitted.contains_key(&tx_hash) {
@@ -995,6 +3496,27 @@ impl HostBleTransport {
         }
         drop(hash_set);
 
+        // Per-origin limits, checked after dedup so repeats of one tx don't count
+        let relay_policy = self.relay_policy.lock().clone();
+        if let Some(policy) = relay_policy {
+            let fee_payer = super::gateway::decode_transaction(&tx_bytes)
+                .ok()
+                .and_then(|tx| {
+                    tx.message
+                        .static_account_keys()
+                        .first()
+                        .map(|k| k.to_string())
+                });
+            if let Err(origin) = policy.admit(fee_payer.as_deref(), peer_id.as_deref()) {
+                t_warn!(
+                    "🚫 Dropping transaction {}: {} over relay limit",
+                    tx_hash_hex.chars().take(16).collect::<String>(),
+                    origin
+                );
+                return false;
+            }
+        }
+
         // Proceed with adding to queue
         let now = Self::current_timestamp();
         t_debug!(
@@ -1033,6 +3555,16 @@ impl HostBleTransport {
         drop(hash_set);
         drop(queue);
 
+        if let Some(history) = self.tx_history() {
+            if let Err(e) = history.record_received(&tx_hash_hex, tx_bytes.len(), peer_id.clone()) {
+                t_warn!("⚠️ Failed to record transaction history: {}", e);
+            }
+        }
+        // Gateways acknowledge with a `Submitted` receipt once they submit
+        if self.device_role() != Some(crate::DeviceRole::Gateway) {
+            self.issue_relay_receipt(&tx_hash_hex, crate::ble::ReceiptAction::Relayed, peer_id);
+        }
+
         t_info!(
             "📥 Queued received transaction {} for auto-submission (queue size: {} -> {})",
             tx_id,
@@ -1088,6 +3620,29 @@ impl HostBleTransport {
         result
     }
 
+    /// Sender, recipient, amount and expiry of the transactions waiting for
+    /// submission, without popping them; undecodable ones are left out
+    pub fn received_transaction_metadata(&self) -> Vec<super::tx_metadata::ReceivedTxMetadata> {
+        use super::tx_metadata::{ReceivedTxMetadata, TransactionMetadata};
+
+        let queue = self.received_tx_queue.lock().clone();
+        queue
+            .into_iter()
+            .filter_map(|(tx_id, tx_bytes, received_at)| {
+                let metadata = TransactionMetadata::from_bytes(&tx_bytes)
+                    .map_err(|e| {
+                        t_debug!("⚠️ No metadata for received tx {}: {}", tx_id, e);
+                    })
+                    .ok()?;
+                Some(ReceivedTxMetadata {
+                    tx_id,
+                    received_at,
+                    metadata,
+                })
+            })
+            .collect()
+    }
+
     /// Get count of transactions waiting for auto-submission
     pub fn received_queue_size(&self) -> usize {
         let size = self.received_tx_queue.lock().len();
@@ -1172,15 +3727,28 @@ impl HostBleTransport {
         hasher.update(tx_bytes);
         let tx_hash = hasher.finalize().to_vec();
 
+        let tx_id = hex::encode(&tx_hash);
+        self.record_mesh_stat(crate::storage::MeshStatsEvent::Submitted);
         let mut submitted = self.submitted_tx_hashes.lock();
         submitted.insert(tx_hash, Self::current_timestamp());
+        drop(submitted);
+        self.update_tx_history(&tx_id, crate::storage::TxHistoryStatus::Submitted);
+
+        // Only relayed transactions have someone to send a receipt back to
+        let from_peer = self
+            .tx_history()
+            .and_then(|h| h.get(&tx_id))
+            .and_then(|r| r.peer_id);
+        if from_peer.is_some() {
+            self.issue_relay_receipt(&tx_id, crate::ble::ReceiptAction::Submitted, from_peer);
+        }
 
         t_debug!("✅ Marked transaction as submitted");
     }
 
     /// Clean up old submitted transaction hashes (older than 24 hours)
     pub fn cleanup_old_submissions(&self) {
-        let cutoff = Self::current_timestamp() - (24 * 60 * 60); // 24 hours ago
+        let cutoff = Self::current_timestamp().saturating_sub(24 * 60 * 60); // 24 hours ago
 
         let mut submitted = self.submitted_tx_hashes.lock();
         submitted.retain(|_, timestamp| *timestamp > cutoff);
@@ -1218,11 +3786,32 @@ impl HostBleTransport {
     }
 
     fn current_timestamp() -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
+        crate::util::clock::now_secs()
+    }
+
+    fn current_timestamp_ms() -> u64 {
+        crate::util::clock::now_ms()
+    }
+}
+
+/// Load the persisted device signing key, creating one on first use
+fn load_or_create_device_key(storage: &SecureStorage) -> Result<ed25519_dalek::SigningKey, String> {
+    if let Some(secret) = storage
+        .get_decrypted(DEVICE_KEY_STORAGE_KEY)
+        .map_err(|e| e.to_string())?
+    {
+        let secret: [u8; 32] = secret
+            .as_slice()
+            .try_into()
+            .map_err(|_| "Stored device key is corrupt".to_string())?;
+        return Ok(ed25519_dalek::SigningKey::from_bytes(&secret));
     }
+    let key = crate::ble::generate_device_key();
+    storage
+        .put_encrypted(DEVICE_KEY_STORAGE_KEY, &key.to_bytes())
+        .map_err(|e| e.to_string())?;
+    t_info!("🔑 Created device signing key");
+    Ok(key)
 }
 
 /// `HostBleTransport` is the canonical implementation of the radio-agnostic
@@ -1295,4 +3884,1044 @@ mod tests {
         assert_eq!(metrics.transactions_complete, 0);
         assert_eq!(metrics.fragments_buffered, 0);
     }
+
+    #[tokio::test]
+    async fn test_relay_records_provenance_and_status() {
+        use crate::storage::{TxHistory, TxHistoryQuery, TxHistoryStatus};
+
+        let dir = tempfile::tempdir().unwrap();
+        let sender = HostBleTransport::new().await.unwrap();
+        let receiver = HostBleTransport::new().await.unwrap();
+        receiver.set_tx_history(Some(Arc::new(TxHistory::open(dir.path()).unwrap())));
+
+        let tx_bytes: Vec<u8> = (0..700u32).map(|i| (i % 251) as u8).collect();
+        let fragments = sender.queue_transaction(tx_bytes.clone(), None).unwrap();
+        assert!(fragments.len() > 1);
+        while let Some(frame) = sender.next_outbound(512) {
+            receiver.push_inbound_from("peer-a", frame).unwrap();
+        }
+
+        let (_, received, _) = receiver.next_received_transaction().unwrap();
+        assert_eq!(received, tx_bytes);
+
+        let history = receiver.tx_history().unwrap();
+        let from_peer = history.query(&TxHistoryQuery {
+            peer_id: Some("peer-a".into()),
+            ..Default::default()
+        });
+        assert_eq!(from_peer.total, 1);
+        assert_eq!(from_peer.records[0].size, tx_bytes.len());
+
+        receiver.mark_transaction_submitted(&tx_bytes);
+        let record = history.get(&from_peer.records[0].tx_id).unwrap();
+        assert_eq!(record.status, TxHistoryStatus::Submitted);
+    }
+
+    #[tokio::test]
+    async fn test_unacknowledged_messages_resume_from_log() {
+        use super::super::messaging::TextMessageStatus;
+        use crate::storage::MessageLog;
+
+        let dir = tempfile::tempdir().unwrap();
+        let sender = HostBleTransport::new().await.unwrap();
+        sender.set_message_log(Some(Arc::new(MessageLog::open(dir.path()).unwrap())));
+        let delivered = sender.send_text_message("bob", "first").unwrap();
+        let pending = sender.send_text_message("bob", "second").unwrap();
+
+        let bob = HostBleTransport::new().await.unwrap();
+        let frame = sender.next_text_frame(512).unwrap();
+        assert_eq!(frame[0], crate::ble::envelope::ENVELOPE_FRAME_TYPE);
+        assert!(frame.len() <= 512);
+        bob.ingest_text_frame("alice", &frame).unwrap();
+        // The same envelope played back is dropped
+        assert!(bob.ingest_text_frame("alice", &frame).is_err());
+        let ack = bob.next_text_frame(512).unwrap();
+        sender.ingest_text_frame("bob", &ack).unwrap();
+
+        let conversation = sender.conversation("bob", 10).unwrap();
+        assert_eq!(conversation.len(), 2);
+        assert_eq!(conversation[0].status, Some(TextMessageStatus::Delivered));
+        assert_eq!(conversation[1].status, Some(TextMessageStatus::Queued));
+
+        // After a restart only the unacknowledged message is queued again
+        let restarted = HostBleTransport::new().await.unwrap();
+        restarted.set_message_log(Some(Arc::new(MessageLog::open(dir.path()).unwrap())));
+        assert_eq!(restarted.resume_text_messages(), 1);
+        assert!(restarted
+            .text_message_status(&delivered.message_id)
+            .is_none());
+        assert!(restarted.text_message_status(&pending.message_id).is_some());
+
+        let resent = restarted.next_text_frame(512).unwrap();
+        match bob.ingest_text_frame("alice", &resent).unwrap() {
+            super::super::messaging::IngestedText::Message(message) => {
+                assert_eq!(message.message_id, pending.message_id)
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_relay_policy_drops_over_limit_device() {
+        use super::super::relay_policy::RelayPolicyConfig;
+
+        let sender = HostBleTransport::new().await.unwrap();
+        let relay = HostBleTransport::new().await.unwrap();
+        relay.set_relay_policy(Some(RelayPolicyConfig {
+            per_fee_payer_per_minute: None,
+            per_device_per_minute: Some(1),
+        }));
+
+        for seed in 0..2u8 {
+            sender.queue_transaction(vec![seed; 300], None).unwrap();
+            while let Some(frame) = sender.next_outbound(512) {
+                relay.push_inbound_from("spammer", frame).unwrap();
+            }
+        }
+
+        assert_eq!(relay.received_queue_size(), 1);
+        let stats = relay.relay_policy_stats().unwrap();
+        assert_eq!(stats.accepted, 1);
+        assert_eq!(stats.dropped_device, 1);
+    }
+
+    #[tokio::test]
+    async fn test_relay_receipts_travel_back_to_origin() {
+        use sha2::Digest;
+
+        let dir = tempfile::tempdir().unwrap();
+        let open_history = |name: &str| {
+            Some(Arc::new(
+                crate::storage::TxHistory::open(dir.path().join(name)).unwrap(),
+            ))
+        };
+
+        let origin = HostBleTransport::new().await.unwrap();
+        let relay = HostBleTransport::new().await.unwrap();
+        let gateway = HostBleTransport::new().await.unwrap();
+        relay.set_tx_history(open_history("relay"));
+        gateway.set_tx_history(open_history("gateway"));
+        gateway.set_device_role(Some(crate::DeviceRole::Gateway));
+        origin.set_relay_receipts(Some(Arc::new(
+            crate::storage::ReceiptStore::open(dir.path().join("receipts")).unwrap(),
+        )));
+
+        let tx = vec![9u8; 300];
+        origin.queue_transaction(tx.clone(), None).unwrap();
+        while let Some(frame) = origin.next_outbound(512) {
+            relay.push_inbound_from("origin", frame).unwrap();
+        }
+        let (_, relayed, _) = relay.next_received_transaction().unwrap();
+        relay.queue_transaction(relayed, None).unwrap();
+        while let Some(frame) = relay.next_outbound(512) {
+            gateway.push_inbound_from("relay", frame).unwrap();
+        }
+        // Gateways only sign once they submit
+        assert!(gateway.next_relay_receipt().is_none());
+        let (_, received, _) = gateway.next_received_transaction().unwrap();
+        gateway.mark_transaction_submitted(&received);
+
+        let (peer, frame) = gateway.next_relay_receipt().unwrap();
+        assert_eq!(peer, "relay");
+        assert!(relay.ingest_relay_receipt(&frame).unwrap());
+
+        // The relay sends its own receipt, then passes on the gateway's
+        let mut delivered = 0;
+        while let Some((peer, frame)) = relay.next_relay_receipt() {
+            assert_eq!(peer, "origin");
+            assert!(origin.ingest_relay_receipt(&frame).unwrap());
+            // Receipts are not stored twice
+            assert!(!origin.ingest_relay_receipt(&frame).unwrap());
+            delivered += 1;
+        }
+        assert_eq!(delivered, 2);
+
+        let tx_id = hex::encode(sha2::Sha256::digest(&tx));
+        let receipts = origin
+            .relay_receipts()
+            .unwrap()
+            .for_transaction(&hex::encode(crate::ble::tx_id_hash(&tx_id)));
+        assert_eq!(receipts.len(), 2);
+        assert!(receipts
+            .iter()
+            .any(|r| r.relayer == relay.device_public_key()
+                && r.action == crate::ble::ReceiptAction::Relayed));
+        assert!(receipts
+            .iter()
+            .any(|r| r.relayer == gateway.device_public_key()
+                && r.action == crate::ble::ReceiptAction::Submitted));
+    }
+
+    #[tokio::test]
+    async fn test_device_identity_survives_restart() {
+        use crate::storage::PassphraseKeyProvider;
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage_dir = dir.path().to_str().unwrap();
+        let provider = || Arc::new(PassphraseKeyProvider::new("test".into()));
+
+        let mut first = HostBleTransport::new().await.unwrap();
+        assert!(!first.device_identity().persisted);
+        first.set_secure_storage(storage_dir, provider()).unwrap();
+        let identity = first.device_identity();
+        assert!(identity.persisted);
+        assert_eq!(identity.short_id, hex::encode(first.node_id()));
+
+        let mut restarted = HostBleTransport::new().await.unwrap();
+        restarted
+            .set_secure_storage(storage_dir, provider())
+            .unwrap();
+        assert_eq!(restarted.device_identity(), identity);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_aborts_background_tasks() {
+        let transport = HostBleTransport::new().await.unwrap();
+        let task = transport
+            .sdk
+            .start_queue_maintenance(std::time::Duration::from_secs(3600));
+
+        transport.shutdown().await;
+        assert!(transport.sdk.is_shut_down());
+        assert!(task.await.unwrap_err().is_cancelled());
+        // Second shutdown is a no-op
+        transport.sdk.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_partial_reassembly_survives_sdk_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let config: SdkConfig = serde_json::from_value(serde_json::json!({
+            "storageDirectory": dir.path().to_str().unwrap(),
+        }))
+        .unwrap();
+        let fragment = crate::ble::fragmenter::fragment_transaction(&[7u8; 1200]).remove(0);
+        let tx_id_hex = hex::encode(fragment.transaction_id);
+
+        let sdk = crate::PolliNetSDK::from_config(&config).await.unwrap();
+        sdk.local_cache
+            .write()
+            .await
+            .add_ble_fragment(fragment)
+            .unwrap();
+        sdk.shutdown().await.unwrap();
+
+        let restarted = crate::PolliNetSDK::from_config(&config).await.unwrap();
+        assert!(restarted.local_cache.write().await.cancel(&tx_id_hex));
+    }
+
+    #[tokio::test]
+    async fn test_sdk_events_follow_mesh_activity() {
+        use crate::{AdapterState, SdkEvent};
+
+        let sender = HostBleTransport::new().await.unwrap();
+        let receiver = HostBleTransport::new().await.unwrap();
+        let mut events = receiver.sdk.subscribe();
+
+        receiver.record_scan_result("peer-a");
+        receiver.record_scan_result("peer-a");
+        receiver.sdk.report_adapter_state(AdapterState::On);
+        receiver.sdk.report_adapter_state(AdapterState::On);
+
+        let tx_bytes = vec![7u8; 900];
+        let fragments = sender.queue_transaction(tx_bytes.clone(), None).unwrap();
+        while let Some(frame) = sender.next_outbound(512) {
+            receiver.push_inbound(frame).unwrap();
+        }
+
+        let mut received = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            received.push(event);
+        }
+        assert_eq!(
+            received[0],
+            SdkEvent::PeerDiscovered {
+                peer_id: "peer-a".into()
+            }
+        );
+        assert_eq!(
+            received[1],
+            SdkEvent::AdapterStateChanged {
+                state: AdapterState::On
+            }
+        );
+        let fragment_events = received
+            .iter()
+            .filter(|e| matches!(e, SdkEvent::FragmentReceived { .. }))
+            .count();
+        assert_eq!(fragment_events, fragments.len());
+        assert!(matches!(
+            received.last(),
+            Some(SdkEvent::TransactionComplete { size: 900, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_partial_transactions_bounded_and_oldest_evicted() {
+        use crate::ble::fragmenter::fragment_transaction_with_max_payload;
+
+        let receiver = HostBleTransport::new().await.unwrap();
+        let first_fragment = |n: u32| {
+            let tx: Vec<u8> = (0..600u32).map(|i| (i * n % 251) as u8).collect();
+            fragment_transaction_with_max_payload(&tx, 512).remove(0)
+        };
+
+        let mut bogus = first_fragment(1);
+        bogus.total_fragments = u16::MAX;
+        assert!(receiver.push_inbound(bogus.serialize()).is_err());
+        assert!(receiver.inbound_buffers.lock().is_empty());
+
+        let oldest = hex::encode(first_fragment(1).transaction_id);
+        for n in 1..=MAX_PENDING_TRANSACTIONS as u32 + 1 {
+            receiver
+                .push_inbound(first_fragment(n).serialize())
+                .unwrap();
+        }
+        let buffers = receiver.inbound_buffers.lock();
+        assert_eq!(buffers.len(), MAX_PENDING_TRANSACTIONS);
+        assert!(!buffers.contains_key(&oldest));
+        drop(buffers);
+        assert_eq!(receiver.metrics().reassembly_evictions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_full_inbound_queue_drops_and_nacks_sender() {
+        use crate::ble::fragmenter::fragment_transaction_with_max_payload;
+        use crate::ble::{ControlFrameType, FragmentNackFrame, NackReason};
+        use crate::queue::{OutboundTransaction, Priority};
+
+        let sender = HostBleTransport::new().await.unwrap();
+        let receiver = HostBleTransport::new().await.unwrap();
+
+        let tx_bytes: Vec<u8> = (0..700u32).map(|i| (i % 251) as u8).collect();
+        let fragments = fragment_transaction_with_max_payload(&tx_bytes, 512);
+        let tx_id = hex::encode(fragments[0].transaction_id);
+        sender
+            .sdk
+            .queue_manager()
+            .outbound
+            .write()
+            .await
+            .push(OutboundTransaction::new(
+                tx_id,
+                tx_bytes,
+                fragments.clone(),
+                Priority::Normal,
+            ))
+            .unwrap();
+
+        for _ in 0..crate::ffi::inbound_queue::INBOUND_QUEUE_CAPACITY {
+            assert!(receiver.enqueue_inbound(Some("peer"), vec![0xFF]));
+        }
+        assert!(!receiver.enqueue_inbound(Some("peer"), fragments[1].serialize()));
+        assert_eq!(receiver.metrics().inbound_dropped, 1);
+
+        let nack = receiver.next_outbound(512).unwrap();
+        assert_eq!(nack[0], ControlFrameType::FragmentNack as u8);
+        let decoded = FragmentNackFrame::from_frame_bytes(&nack).unwrap();
+        assert_eq!(decoded.fragment_index, Some(1));
+        assert_eq!(decoded.reason, NackReason::QueueFull);
+
+        // Draining frees the queue; the sender resends just the dropped fragment
+        receiver.tick(0);
+        assert_eq!(receiver.inbound_queue_len(), 0);
+        assert_eq!(sender.ingest_fragment_nack(&nack).unwrap(), 1);
+        let resent = sender.next_outbound(512).unwrap();
+        assert!(receiver.enqueue_inbound(Some("peer"), resent));
+        assert_eq!(receiver.process_inbound(usize::MAX), 1);
+        assert_eq!(receiver.inbound_buffers.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_peer_session_resumes_interrupted_transfer() {
+        use crate::ble::fragmenter::fragment_transaction_with_max_payload;
+        use crate::ble::{FragmentNackFrame, NackReason};
+        use crate::queue::{OutboundTransaction, Priority};
+
+        let sender = HostBleTransport::new().await.unwrap();
+        let receiver = HostBleTransport::new().await.unwrap();
+        let sender_key = sender.device_public_key();
+
+        let tx_bytes: Vec<u8> = (0..2000u32).map(|i| (i % 251) as u8).collect();
+        let fragments = fragment_transaction_with_max_payload(&tx_bytes, 512);
+        assert!(fragments.len() > 2);
+        let tx_id = hex::encode(fragments[0].transaction_id);
+        sender
+            .sdk
+            .queue_manager()
+            .outbound
+            .write()
+            .await
+            .push(OutboundTransaction::new(
+                tx_id,
+                tx_bytes.clone(),
+                fragments.clone(),
+                Priority::Normal,
+            ))
+            .unwrap();
+
+        // The link drops after the first fragment
+        receiver
+            .push_inbound_from("addr-1", fragments[0].serialize())
+            .unwrap();
+        receiver
+            .ingest_tx_digest("addr-1", &sender.tx_digest())
+            .unwrap();
+        receiver.save_peer_session("addr-1", &sender_key, Some(247), Some(1));
+        assert!(receiver
+            .resume_peer_session("addr-2", "someone-else")
+            .is_none());
+
+        // The sender comes back under a rotated address
+        let resumed = receiver.resume_peer_session("addr-2", &sender_key).unwrap();
+        assert_eq!(resumed.mtu, Some(247));
+        assert!(resumed.digest_restored);
+        assert_eq!(resumed.resumed_transfers, 1);
+        assert_eq!(resumed.fragments_requested, fragments.len() - 1);
+
+        while let Some(nack) = receiver.next_outbound(512) {
+            let decoded = FragmentNackFrame::from_frame_bytes(&nack).unwrap();
+            assert_eq!(decoded.reason, NackReason::Resume);
+            assert_ne!(decoded.fragment_index, Some(0));
+            sender.ingest_fragment_nack(&nack).unwrap();
+        }
+        while let Some(frame) = sender.next_outbound(512) {
+            receiver.push_inbound_from("addr-2", frame).unwrap();
+        }
+        let (_, received, _) = receiver.next_received_transaction().unwrap();
+        assert_eq!(received, tx_bytes);
+        assert!(receiver
+            .resume_peer_session("addr-2", &sender_key)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sealed_transaction_opened_only_by_its_gateway() {
+        let origin = HostBleTransport::new().await.unwrap();
+        let relay = HostBleTransport::new().await.unwrap();
+        let gateway = HostBleTransport::new().await.unwrap();
+
+        let tx_bytes: Vec<u8> = (0..400u32).map(|i| (i % 251) as u8).collect();
+        assert!(origin
+            .queue_sealed_transaction(tx_bytes.clone(), "not-a-key", None)
+            .is_err());
+        origin
+            .queue_sealed_transaction(tx_bytes.clone(), &gateway.device_public_key(), None)
+            .unwrap();
+        let frames: Vec<Vec<u8>> = std::iter::from_fn(|| origin.next_outbound(512)).collect();
+
+        for frame in &frames {
+            relay.push_inbound_from("origin", frame.clone()).unwrap();
+        }
+        let (_, carried, _) = relay.next_received_transaction().unwrap();
+        assert!(crate::ble::sealed::is_sealed(&carried));
+
+        for frame in frames {
+            gateway.push_inbound_from("relay", frame).unwrap();
+        }
+        let (_, opened, _) = gateway.next_received_transaction().unwrap();
+        assert_eq!(opened, tx_bytes);
+    }
+
+    #[tokio::test]
+    async fn test_emergency_notice_jumps_queue_and_floods_once() {
+        use crate::ble::ControlFrameType;
+
+        let origin = HostBleTransport::new().await.unwrap();
+        let relay = HostBleTransport::new().await.unwrap();
+        assert!(origin.broadcast_emergency(&"x".repeat(101)).is_err());
+
+        let tx_bytes: Vec<u8> = (0..700u32).map(|i| (i % 251) as u8).collect();
+        origin.queue_transaction(tx_bytes, None).unwrap();
+        let id = origin
+            .broadcast_emergency("Bridge is out on route 9")
+            .unwrap();
+        let frame = origin.next_outbound(512).unwrap();
+        assert_eq!(frame[0], ControlFrameType::EmergencyBroadcast as u8);
+
+        let notice = relay.ingest_emergency(&frame).unwrap().unwrap();
+        assert_eq!(notice.id, id);
+        assert_eq!(notice.origin, origin.device_public_key());
+        assert_eq!(notice.hops, 0);
+        assert_eq!(relay.emergency_notices(), vec![notice]);
+
+        // Relayed once with one hop spent; duplicates are dropped
+        let relayed = relay.next_outbound(512).unwrap();
+        assert_eq!(relayed[0], ControlFrameType::EmergencyBroadcast as u8);
+        assert!(relay.ingest_emergency(&frame).unwrap().is_none());
+        assert!(relay.next_outbound(512).is_none());
+        assert!(origin.ingest_emergency(&relayed).unwrap().is_none());
+
+        let mut forged = relayed;
+        forged[50] ^= 1;
+        assert!(HostBleTransport::new()
+            .await
+            .unwrap()
+            .ingest_emergency(&forged)
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_payment_request_builds_nonce_transaction() {
+        use crate::ble::ControlFrameType;
+        use crate::ffi::types::CreatePaymentRequestRequest;
+        use solana_sdk::{pubkey::Pubkey, transaction::Transaction};
+
+        let merchant = HostBleTransport::new().await.unwrap();
+        let payer = HostBleTransport::new().await.unwrap();
+        let recipient = Pubkey::new_unique();
+        let info = merchant
+            .create_payment_request(&CreatePaymentRequestRequest {
+                recipient: recipient.to_string(),
+                amount: 1_500_000,
+                mint: None,
+                decimals: 0,
+                memo: Some("Table 4".to_string()),
+                expires_in_secs: 300,
+            })
+            .unwrap();
+        let frame = merchant.next_outbound(512).unwrap();
+        assert_eq!(frame[0], ControlFrameType::PaymentRequest as u8);
+
+        let received = payer.ingest_payment_request(&frame).unwrap();
+        assert_eq!(received, info);
+        assert_eq!(received.merchant, merchant.device_public_key());
+        assert_eq!(payer.payment_requests(), vec![info.clone()]);
+
+        let wallet = Pubkey::new_unique();
+        let nonce = crate::ffi::nonce_accounts::NonceAccountInfo {
+            address: Pubkey::new_unique().to_string(),
+            authority: wallet.to_string(),
+            nonce: solana_sdk::hash::Hash::new_unique().to_string(),
+            lamports_per_signature: 5_000,
+            fetched_at: Some(crate::util::clock::now_secs()),
+        };
+        assert!(payer
+            .build_payment_transaction("00", &wallet.to_string(), &nonce)
+            .is_err());
+        let built = payer
+            .build_payment_transaction(&info.request_id, &wallet.to_string(), &nonce)
+            .unwrap();
+        let raw = base64::Engine::decode(
+            &base64::engine::general_purpose::STANDARD,
+            &built.transaction,
+        )
+        .unwrap();
+        let tx: Transaction = bincode1::deserialize(&raw).unwrap();
+        // Advance nonce, transfer, memo; the nonce is the blockhash
+        assert_eq!(tx.message.instructions.len(), 3);
+        assert_eq!(tx.message.recent_blockhash.to_string(), nonce.nonce);
+        assert_eq!(tx.message.account_keys[0], wallet);
+        assert!(tx.message.account_keys.contains(&recipient));
+
+        // The configured policy refuses a nonce of unknown age
+        payer.set_nonce_staleness(crate::ffi::nonce_accounts::NonceStalenessPolicy {
+            max_age_secs: 60,
+            action: crate::ffi::nonce_accounts::StaleNonceAction::Refuse,
+        });
+        let unknown_age = crate::ffi::nonce_accounts::NonceAccountInfo {
+            fetched_at: None,
+            ..nonce.clone()
+        };
+        let err = payer
+            .build_payment_transaction(&info.request_id, &wallet.to_string(), &unknown_age)
+            .unwrap_err();
+        assert_eq!(err.code, crate::ffi::types::FfiErrorCode::StaleNonce);
+
+        let mut forged = frame;
+        let last = forged.len() - 1;
+        forged[last] ^= 1;
+        assert!(payer.ingest_payment_request(&forged).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_payment_receipt_countersigned_by_merchant() {
+        use crate::ble::ControlFrameType;
+        use crate::ffi::types::CreatePaymentRequestRequest;
+        use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+
+        let dir = tempfile::tempdir().unwrap();
+        let merchant = HostBleTransport::new().await.unwrap();
+        let payer = HostBleTransport::new().await.unwrap();
+        for (transport, name) in [(&merchant, "merchant"), (&payer, "payer")] {
+            transport.set_payment_receipts(Some(Arc::new(
+                crate::storage::PaymentReceiptStore::open(dir.path().join(name)).unwrap(),
+            )));
+        }
+        let info = merchant
+            .create_payment_request(&CreatePaymentRequestRequest {
+                recipient: Pubkey::new_unique().to_string(),
+                amount: 42_000,
+                mint: None,
+                decimals: 0,
+                memo: None,
+                expires_in_secs: 300,
+            })
+            .unwrap();
+        payer
+            .ingest_payment_request(&merchant.next_outbound(512).unwrap())
+            .unwrap();
+
+        let wallet = Keypair::new();
+        let nonce = crate::ffi::nonce_accounts::NonceAccountInfo {
+            address: Pubkey::new_unique().to_string(),
+            authority: wallet.pubkey().to_string(),
+            nonce: solana_sdk::hash::Hash::new_unique().to_string(),
+            lamports_per_signature: 5_000,
+            fetched_at: Some(crate::util::clock::now_secs()),
+        };
+        let built = payer
+            .build_payment_transaction(&info.request_id, &wallet.pubkey().to_string(), &nonce)
+            .unwrap();
+        let raw = base64::Engine::decode(
+            &base64::engine::general_purpose::STANDARD,
+            &built.transaction,
+        )
+        .unwrap();
+        assert_eq!(
+            payer
+                .create_payment_receipt(&raw, Some(&info.request_id))
+                .unwrap_err()
+                .code,
+            FfiErrorCode::InvalidSignature
+        );
+        let mut tx: solana_sdk::transaction::Transaction = bincode1::deserialize(&raw).unwrap();
+        let blockhash = tx.message.recent_blockhash;
+        tx.sign(&[&wallet], blockhash);
+        let signed = bincode1::serialize(&tx).unwrap();
+        assert_eq!(
+            payer
+                .create_payment_receipt(&signed, Some(&hex::encode([9u8; 16])))
+                .unwrap_err()
+                .code,
+            FfiErrorCode::NotFound
+        );
+
+        let issued = payer
+            .create_payment_receipt(&signed, Some(&info.request_id))
+            .unwrap();
+        assert_eq!(issued.tx_signature, tx.signatures[0].to_string());
+        assert_eq!(issued.amount, 42_000);
+        assert!(issued.paid && !issued.is_countersigned());
+        // The paid request is settled on the payer's side
+        assert!(payer.payment_requests().is_empty());
+
+        let frame = payer.next_outbound(512).unwrap();
+        assert_eq!(frame[0], ControlFrameType::PaymentReceipt as u8);
+        let received = merchant.ingest_payment_receipt(&frame).unwrap();
+        assert!(!received.paid);
+        assert_eq!(received.merchant, Some(merchant.device_public_key()));
+
+        let countersigned = payer
+            .ingest_payment_receipt(&merchant.next_outbound(512).unwrap())
+            .unwrap();
+        assert!(countersigned.is_countersigned());
+        assert_eq!(
+            payer.payment_receipts(0, 10).unwrap(),
+            vec![countersigned.clone()]
+        );
+        assert!(countersigned.to_receipt().unwrap().verify());
+        // Only the payee countersigns
+        assert_eq!(
+            payer
+                .countersign_payment_receipt(&issued.tx_signature)
+                .unwrap_err()
+                .code,
+            FfiErrorCode::InvalidInput
+        );
+    }
+
+    #[tokio::test]
+    async fn test_idle_partial_transaction_collected_and_restarted() {
+        use crate::ble::{FragmentNackFrame, NackReason};
+
+        let receiver = HostBleTransport::new().await.unwrap();
+        let tx_bytes: Vec<u8> = (0..700u32).map(|i| (i % 251) as u8).collect();
+        let fragments =
+            crate::ble::fragmenter::fragment_transaction_with_max_payload(&tx_bytes, 512);
+        receiver
+            .push_inbound_from("peer", fragments[0].serialize())
+            .unwrap();
+
+        // Within the default timeout nothing is collected
+        assert_eq!(receiver.collect_idle_reassemblies(), 0);
+        assert!(receiver.next_outbound(512).is_none());
+
+        receiver.set_reassembly_timeout(Duration::ZERO, true);
+        receiver.tick(0);
+        assert!(receiver.inbound_buffers.lock().is_empty());
+        assert!(receiver.inbound_peers.lock().is_empty());
+        assert_eq!(receiver.metrics().reassembly_timeouts, 1);
+
+        let nack =
+            FragmentNackFrame::from_frame_bytes(&receiver.next_outbound(512).unwrap()).unwrap();
+        assert_eq!(nack.tx_id, fragments[0].transaction_id);
+        assert_eq!(nack.fragment_index, None);
+        assert_eq!(nack.reason, NackReason::Timeout);
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_fragment_rejected_until_resent() {
+        use crate::SdkEvent;
+
+        let sender = HostBleTransport::new().await.unwrap();
+        let receiver = HostBleTransport::new().await.unwrap();
+        let mut events = receiver.sdk.subscribe();
+
+        let tx_bytes: Vec<u8> = (0..600u32).map(|i| (i % 251) as u8).collect();
+        sender.queue_transaction(tx_bytes, None).unwrap();
+        let mut frames = Vec::new();
+        while let Some(frame) = sender.next_outbound(512) {
+            frames.push(frame);
+        }
+        assert!(frames.len() > 1);
+
+        let mut corrupt = frames[0].clone();
+        *corrupt.last_mut().unwrap() ^= 0xFF;
+        assert!(receiver.push_inbound(corrupt).is_err());
+        assert_eq!(receiver.metrics().corrupt_fragments, 1);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(SdkEvent::FragmentCorrupt { index: 0, .. })
+        ));
+
+        // The intact resend fills the slot and the transaction completes
+        let _nack = receiver.next_outbound(512).unwrap();
+        for frame in frames {
+            receiver.push_inbound(frame).unwrap();
+        }
+        assert!(receiver.pop_completed().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_fragment_nacked_and_resent() {
+        use crate::ble::fragmenter::fragment_transaction_with_first_room;
+        use crate::ble::{ControlFrameType, FragmentNackFrame, NackReason};
+        use crate::queue::{OutboundTransaction, Priority};
+
+        let sender = HostBleTransport::new().await.unwrap();
+        let receiver = HostBleTransport::new().await.unwrap();
+        let tx_bytes: Vec<u8> = (0..600u32).map(|i| (i % 251) as u8).collect();
+        sender.queue_transaction(tx_bytes.clone(), None).unwrap();
+        // NACKs are answered from the outbound queue, which holds the same fragments
+        let fragments =
+            fragment_transaction_with_first_room(&tx_bytes, None, crate::ble::wire::ORIGIN_KEY_LEN);
+        sender
+            .sdk
+            .queue_manager()
+            .outbound
+            .write()
+            .await
+            .push(OutboundTransaction::new(
+                hex::encode(fragments[0].transaction_id),
+                tx_bytes,
+                fragments,
+                Priority::Normal,
+            ))
+            .unwrap();
+        let mut frames = Vec::new();
+        while let Some(frame) = sender.next_outbound(512) {
+            frames.push(frame);
+        }
+
+        // One flipped payload byte in the second fragment
+        let mut corrupt = frames[1].clone();
+        let middle = corrupt.len() - 10;
+        corrupt[middle] ^= 0x01;
+        assert!(receiver.push_inbound(corrupt).is_err());
+
+        let nack = receiver.next_outbound(512).unwrap();
+        assert_eq!(nack[0], ControlFrameType::FragmentNack as u8);
+        let decoded = FragmentNackFrame::from_frame_bytes(&nack).unwrap();
+        assert_eq!(decoded.fragment_index, Some(1));
+        assert_eq!(decoded.reason, NackReason::Corrupt);
+
+        assert_eq!(sender.ingest_fragment_nack(&nack).unwrap(), 1);
+        receiver
+            .push_inbound(sender.next_outbound(512).unwrap())
+            .unwrap();
+        assert_eq!(receiver.metrics().corrupt_fragments, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sealed_control_messages_open_once() {
+        use crate::ble::{ControlFrameType, PolliNetMessage};
+
+        let origin = HostBleTransport::new().await.unwrap();
+        let relay = HostBleTransport::new().await.unwrap();
+
+        let confirmation = PolliNetMessage::new(ControlFrameType::Confirmation as u8, vec![1; 32]);
+        let first = origin.seal_message(confirmation.clone());
+        let second = origin.seal_message(confirmation);
+
+        // A valid signature alone doesn't make a control message trusted
+        assert!(relay.open_message(&first).is_err());
+        assert!(relay.replay_window.lock().is_empty());
+        relay.pin_peer_key(&origin.device_public_key()).unwrap();
+
+        assert!(relay.open_message(&first).is_ok());
+        assert!(relay.open_message(&second).is_ok());
+        // A captured frame played back later is dropped
+        assert!(relay.open_message(&first).is_err());
+
+        let mut tampered = first.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 0x01;
+        assert!(relay.open_message(&tampered).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_envelope_origin_must_match_pinned_key() {
+        use crate::ble::{PacketType, PolliNetMessage};
+
+        let alice = HostBleTransport::new().await.unwrap();
+        let bob = HostBleTransport::new().await.unwrap();
+        let text = || {
+            alice.seal_message(PolliNetMessage::new(
+                PacketType::TextMessage as u8,
+                vec![7; 16],
+            ))
+        };
+
+        // Data messages pin an unknown origin on first use
+        assert!(bob.open_message(&text()).is_ok());
+        assert_eq!(bob.pinned_keys.lock().len(), 1);
+
+        // Another key claiming alice's short id is refused
+        let short_id = alice.node_id();
+        let impostor = crate::ble::generate_device_key().verifying_key().to_bytes();
+        bob.pinned_keys.lock().insert(short_id, (impostor, true));
+        assert!(bob.open_message(&text()).is_err());
+
+        assert!(bob.pin_peer_key("not-a-key").is_err());
+        bob.pin_peer_key(&alice.device_public_key()).unwrap();
+        assert!(bob.open_message(&text()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_digest_skips_transactions_peer_holds() {
+        let sender = HostBleTransport::new().await.unwrap();
+        let receiver = HostBleTransport::new().await.unwrap();
+
+        let shared: Vec<u8> = (0..300u32).map(|i| (i % 251) as u8).collect();
+        let fresh: Vec<u8> = (0..300u32).map(|i| (i % 241) as u8).collect();
+        sender.queue_transaction(shared.clone(), None).unwrap();
+        sender.queue_transaction(fresh.clone(), None).unwrap();
+        assert!(receiver.push_received_transaction(shared));
+
+        let summary = sender
+            .ingest_tx_digest("peer-b", &receiver.tx_digest())
+            .unwrap();
+        assert_eq!(summary.peer_count, 1);
+        assert_eq!(summary.already_held, 1);
+        assert_eq!(summary.to_send, 1);
+
+        // Only the fresh transaction goes to peer-b; the other stays queued
+        while let Some(frame) = sender.next_outbound_for_peer("peer-b", 512) {
+            receiver.push_inbound(frame).unwrap();
+        }
+        assert_eq!(receiver.pop_completed().unwrap().1, fresh);
+        assert!(sender.outbound_queue_size() > 0);
+
+        sender.clear_tx_digest("peer-b");
+        assert!(sender.next_outbound_for_peer("peer-b", 512).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_legacy_peer_gets_legacy_fragments() {
+        use crate::role::capability::COMPACT_FRAMES;
+
+        let sender = HostBleTransport::new().await.unwrap();
+        // Several fragments, so both peers below get one
+        let tx_bytes: Vec<u8> = (0..1500u32).map(|i| (i % 251) as u8).collect();
+        sender.queue_transaction(tx_bytes.clone(), None).unwrap();
+
+        // An unknown peer may run the baseline and only reads bincode fragments
+        let frame = sender.next_outbound_for_peer("old-peer", 512).unwrap();
+        let fragment = TransactionFragment::deserialize(&frame).unwrap();
+        assert_eq!(frame, bincode1::serialize(&fragment).unwrap());
+
+        sender.set_peer_capabilities("new-peer", COMPACT_FRAMES);
+        let frame = sender.next_outbound_for_peer("new-peer", 512).unwrap();
+        assert_eq!(frame[0], crate::ble::wire::FRAGMENT_FRAME_TYPE);
+
+        // A compact frame from a peer shows it reads them too
+        let receiver = HostBleTransport::new().await.unwrap();
+        receiver.push_inbound_from("old-peer", frame).unwrap();
+        assert!(receiver.peer_supports_compact_frames("old-peer"));
+        assert!(sender.peer_supports_compact_frames("new-peer"));
+        assert!(!sender.peer_supports_compact_frames("old-peer"));
+    }
+
+    #[tokio::test]
+    async fn test_tx_cancel_drops_fragments_and_propagates_once() {
+        use crate::ble::{CancelReason, ControlFrameType};
+
+        let origin = HostBleTransport::new().await.unwrap();
+        let relay = HostBleTransport::new().await.unwrap();
+
+        let tx_bytes: Vec<u8> = (0..700u32).map(|i| (i % 251) as u8).collect();
+        origin.queue_transaction(tx_bytes, None).unwrap();
+        let first = origin.next_outbound(512).unwrap();
+        let tx_id = hex::encode(crate::ble::wire::frame_transaction_id(&first).unwrap());
+        relay.push_inbound(first.clone()).unwrap();
+        assert_eq!(relay.inbound_buffers.lock().len(), 1);
+
+        // The origin drops its queued frames and sends TX_CANCEL first
+        assert!(
+            origin
+                .cancel_transaction(&tx_id, CancelReason::Superseded)
+                .unwrap()
+                > 0
+        );
+        let cancel = origin.next_outbound(512).unwrap();
+        assert_eq!(cancel[0], ControlFrameType::TxCancel as u8);
+        assert!(origin.next_outbound(512).is_none());
+
+        // The relay drops its partial buffer, ignores late fragments and
+        // passes the cancel on exactly once
+        assert!(relay.ingest_tx_cancel(&cancel).unwrap());
+        assert!(relay.inbound_buffers.lock().is_empty());
+        relay.push_inbound(first).unwrap();
+        assert!(relay.inbound_buffers.lock().is_empty());
+        let relayed = relay.next_outbound(512).unwrap();
+        assert_eq!(relayed[34], 1);
+        assert!(!relay.ingest_tx_cancel(&relayed).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_tx_cancel_must_be_signed_by_origin() {
+        use crate::ble::{CancelReason, TxCancelFrame};
+
+        let origin = HostBleTransport::new().await.unwrap();
+        let relay = HostBleTransport::new().await.unwrap();
+        let tx_bytes: Vec<u8> = (0..700u32).map(|i| (i % 251) as u8).collect();
+        origin.queue_transaction(tx_bytes.clone(), None).unwrap();
+        let first = origin.next_outbound(512).unwrap();
+        let id = crate::ble::wire::frame_transaction_id(&first).unwrap();
+        let origin_key = origin.device_key.verifying_key().to_bytes();
+        assert_eq!(crate::ble::wire::fragment_origin(&first), Some(origin_key));
+
+        // Before the first fragment the relay can't tell who may cancel
+        let forged = TxCancelFrame::sign(
+            &crate::ble::generate_device_key(),
+            id,
+            CancelReason::Superseded,
+        )
+        .to_frame_bytes();
+        assert!(relay.ingest_tx_cancel(&forged).is_err());
+
+        relay.push_inbound(first).unwrap();
+        assert!(relay.ingest_tx_cancel(&forged).is_err());
+        assert_eq!(relay.inbound_buffers.lock().len(), 1);
+        assert!(relay.next_outbound(512).is_none());
+
+        // Forwarded fragments keep the origin's key, not the relay's
+        relay.queue_transaction(tx_bytes, None).unwrap();
+        let forwarded = relay.outbound_queue.lock().front().cloned().unwrap();
+        assert_eq!(
+            crate::ble::wire::fragment_origin(&forwarded),
+            Some(origin_key)
+        );
+
+        // The origin's cancel still verifies after a hop
+        let mut relayed = TxCancelFrame::sign(&origin.device_key, id, CancelReason::Superseded);
+        assert!(relayed.increment_hop());
+        assert!(relay.ingest_tx_cancel(&relayed.to_frame_bytes()).unwrap());
+        assert!(relay.inbound_buffers.lock().is_empty());
+        assert_eq!(relay.outbound_queue_size(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_traceroute_records_relay_and_answers_origin() {
+        let origin = HostBleTransport::new().await.unwrap();
+        let relay = HostBleTransport::new().await.unwrap();
+        let target = HostBleTransport::new().await.unwrap();
+
+        let probe_id = origin.traceroute(&target.device_public_key()).unwrap();
+        let ping = origin.next_outbound(512).unwrap();
+        assert_eq!(ping[0], crate::ble::PacketType::Ping as u8);
+
+        // Not for the relay: forwarded once, with its hop added
+        assert!(relay.ingest_probe_frame("origin", &ping).unwrap().is_none());
+        let relayed = relay.next_outbound(512).unwrap();
+        assert!(relay.ingest_probe_frame("origin", &ping).unwrap().is_none());
+        assert!(relay.next_outbound(512).is_none());
+
+        target.ingest_probe_frame("relay", &relayed).unwrap();
+        let pong = target.next_outbound(512).unwrap();
+        assert_eq!(pong[0], crate::ble::PacketType::Pong as u8);
+
+        relay.ingest_probe_frame("target", &pong).unwrap();
+        let pong = relay.next_outbound(512).unwrap();
+        let result = origin.ingest_probe_frame("relay", &pong).unwrap().unwrap();
+        assert_eq!(result.probe_id, probe_id);
+        let nodes: Vec<String> = result.hops.iter().map(|h| h.node.clone()).collect();
+        assert_eq!(
+            nodes,
+            vec![hex::encode(relay.node_id()), hex::encode(target.node_id())]
+        );
+        assert_eq!(origin.probe_result(&probe_id), Some(result));
+
+        // A plain ping is answered by the neighbour itself
+        let ping_id = origin.ping_peer("AA:BB").unwrap();
+        relay
+            .ingest_probe_frame("origin", &origin.next_outbound(512).unwrap())
+            .unwrap();
+        let pong = relay.next_outbound(512).unwrap();
+        let result = origin.ingest_probe_frame("relay", &pong).unwrap().unwrap();
+        assert_eq!((result.probe_id, result.hops.len()), (ping_id, 1));
+    }
+
+    #[tokio::test]
+    async fn test_batched_transactions_complete_separately() {
+        let sender = HostBleTransport::new().await.unwrap();
+        let receiver = HostBleTransport::new().await.unwrap();
+
+        let transactions: Vec<Vec<u8>> = (1..=3u32)
+            .map(|n| (0..250u32).map(|i| ((i * n) % 251) as u8).collect())
+            .collect();
+        sender
+            .queue_transaction_batch(transactions.clone(), None)
+            .unwrap();
+        while let Some(frame) = sender.next_outbound(512) {
+            receiver.push_inbound(frame).unwrap();
+        }
+
+        let completed: Vec<Vec<u8>> = std::iter::from_fn(|| receiver.pop_completed())
+            .map(|(_, bytes)| bytes)
+            .collect();
+        assert_eq!(completed, transactions);
+        assert_eq!(receiver.metrics().transactions_complete, 3);
+        assert_eq!(receiver.received_queue_size(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_ble_status_reports_role_and_adapter() {
+        use crate::role::capability;
+        use crate::{AdapterState, DeviceRole};
+
+        let transport = HostBleTransport::new().await.unwrap();
+        assert_eq!(transport.ble_status().adapter.state, None);
+
+        transport.sdk.report_adapter_state(AdapterState::On);
+        transport.set_device_role(Some(DeviceRole::Gateway));
+        transport.connectivity().report(true);
+        transport.record_scan_result("peer-a");
+        transport.push_received_transaction(vec![3u8; 32]);
+
+        let status = transport.ble_status();
+        assert_eq!(status.adapter.state, Some(AdapterState::On));
+        assert_eq!(
+            status.adapter.capability_flags,
+            capability::GATEWAY | capability::ONLINE | capability::COMPACT_FRAMES
+        );
+        assert_eq!(status.nearby_peers, 1);
+        assert_eq!(status.received_transactions, 1);
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["adapter"]["role"], "gateway");
+        assert_eq!(json["receivedTransactions"], 1);
+        assert!(status.to_string().starts_with("Adapter: On, role Gateway"));
+    }
 }
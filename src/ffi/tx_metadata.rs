@@ -0,0 +1,186 @@
+//! Display and policy metadata for relayed transactions
+//!
+//! Receivers see only opaque transaction bytes until they submit them. The
+//! sender, recipient, amount and expiry an app wants to show (or a relay
+//! wants to filter on) are all in the signed message, so they are read from
+//! it rather than trusted from a separate header a relay could rewrite.
+//!
+//! Only System Program transfers carry a recipient and amount here; other
+//! transactions report their fee payer and expiry alone.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+
+use super::gateway::decode_transaction;
+
+/// System Program id (`11111111111111111111111111111111`)
+const SYSTEM_PROGRAM: Pubkey = Pubkey::new_from_array([0; 32]);
+/// `SystemInstruction::Transfer` discriminant
+const SYSTEM_TRANSFER: u32 = 2;
+/// `SystemInstruction::AdvanceNonceAccount` discriminant
+const SYSTEM_ADVANCE_NONCE: u32 = 4;
+
+/// What a relayed transaction does, read from its message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionMetadata {
+    /// Base58 fee payer, the account that signed for the fees
+    pub sender: String,
+    /// Base58 destination of the first System transfer, if any
+    pub recipient: Option<String>,
+    /// Lamports moved by System transfers from the sender
+    pub amount_lamports: Option<u64>,
+    /// Base58 recent blockhash, or the nonce value for nonce transactions
+    pub recent_blockhash: String,
+    /// Durable nonce transactions don't expire with their blockhash
+    pub uses_durable_nonce: bool,
+    pub instruction_count: usize,
+}
+
+impl TransactionMetadata {
+    /// Read metadata from mesh transaction bytes (compressed or not)
+    pub fn from_bytes(tx_bytes: &[u8]) -> Result<Self, String> {
+        let tx = decode_transaction(tx_bytes)?;
+        let message = &tx.message;
+        let keys = message.static_account_keys();
+        let sender = *keys
+            .first()
+            .ok_or_else(|| "Transaction has no fee payer".to_string())?;
+
+        let system_calls: Vec<(usize, u32, &[u8], &[u8])> = message
+            .instructions()
+            .iter()
+            .enumerate()
+            .filter(|(_, ix)| keys.get(ix.program_id_index as usize) == Some(&SYSTEM_PROGRAM))
+            .filter_map(|(i, ix)| {
+                let tag = u32::from_le_bytes(ix.data.get(..4)?.try_into().ok()?);
+                Some((i, tag, &ix.data[4..], &ix.accounts[..]))
+            })
+            .collect();
+
+        let mut recipient: Option<Pubkey> = None;
+        let mut amount_lamports: Option<u64> = None;
+        for (_, _, data, accounts) in system_calls
+            .iter()
+            .filter(|(_, tag, _, _)| *tag == SYSTEM_TRANSFER)
+        {
+            let (Some(from), Some(to), Some(lamports)) = (
+                accounts.first().and_then(|&a| keys.get(a as usize)),
+                accounts.get(1).and_then(|&a| keys.get(a as usize)),
+                data.get(..8)
+                    .and_then(|b| b.try_into().ok())
+                    .map(u64::from_le_bytes),
+            ) else {
+                continue;
+            };
+            if *from != sender {
+                continue;
+            }
+            recipient.get_or_insert(*to);
+            amount_lamports = Some(amount_lamports.unwrap_or(0).saturating_add(lamports));
+        }
+
+        let uses_durable_nonce = system_calls
+            .first()
+            .is_some_and(|(i, tag, _, _)| *i == 0 && *tag == SYSTEM_ADVANCE_NONCE);
+        let recent_blockhash = match message {
+            VersionedMessage::Legacy(m) => m.recent_blockhash,
+            VersionedMessage::V0(m) => m.recent_blockhash,
+        };
+
+        Ok(Self {
+            sender: sender.to_string(),
+            recipient: recipient.map(|k| k.to_string()),
+            amount_lamports,
+            recent_blockhash: recent_blockhash.to_string(),
+            uses_durable_nonce,
+            instruction_count: message.instructions().len(),
+        })
+    }
+}
+
+/// Metadata of a transaction waiting in the received queue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceivedTxMetadata {
+    pub tx_id: String,
+    /// Unix timestamp (seconds) it was received
+    pub received_at: u64,
+    #[serde(flatten)]
+    pub metadata: TransactionMetadata,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::message::Message;
+    use solana_sdk::signature::Signature;
+    use solana_sdk::transaction::VersionedTransaction;
+
+    fn system_call(tag: u32, args: &[u8], accounts: Vec<AccountMeta>) -> Instruction {
+        let mut data = tag.to_le_bytes().to_vec();
+        data.extend_from_slice(args);
+        Instruction::new_with_bytes(SYSTEM_PROGRAM, &data, accounts)
+    }
+
+    fn transfer(from: &Pubkey, to: &Pubkey, lamports: u64) -> Instruction {
+        system_call(
+            SYSTEM_TRANSFER,
+            &lamports.to_le_bytes(),
+            vec![AccountMeta::new(*from, true), AccountMeta::new(*to, false)],
+        )
+    }
+
+    fn serialize(message: Message) -> Vec<u8> {
+        let tx = VersionedTransaction {
+            signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+            message: VersionedMessage::Legacy(message),
+        };
+        bincode1::serialize(&tx).unwrap()
+    }
+
+    #[test]
+    fn test_metadata_reads_transfer_and_nonce() {
+        let sender = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+
+        let message = Message::new_with_blockhash(
+            &[transfer(&sender, &recipient, 1_500_000)],
+            Some(&sender),
+            &blockhash,
+        );
+        let metadata = TransactionMetadata::from_bytes(&serialize(message)).unwrap();
+        assert_eq!(metadata.sender, sender.to_string());
+        assert_eq!(metadata.recipient, Some(recipient.to_string()));
+        assert_eq!(metadata.amount_lamports, Some(1_500_000));
+        assert_eq!(metadata.recent_blockhash, blockhash.to_string());
+        assert!(!metadata.uses_durable_nonce);
+
+        let nonce_account = Pubkey::new_unique();
+        let nonce = Message::new_with_blockhash(
+            &[
+                system_call(
+                    SYSTEM_ADVANCE_NONCE,
+                    &[],
+                    vec![
+                        AccountMeta::new(nonce_account, false),
+                        AccountMeta::new(sender, true),
+                    ],
+                ),
+                transfer(&sender, &recipient, 7),
+            ],
+            Some(&sender),
+            &blockhash,
+        );
+        let metadata = TransactionMetadata::from_bytes(&serialize(nonce)).unwrap();
+        assert!(metadata.uses_durable_nonce);
+        assert_eq!(metadata.amount_lamports, Some(7));
+        assert_eq!(metadata.instruction_count, 2);
+
+        assert!(TransactionMetadata::from_bytes(&[1, 2, 3]).is_err());
+    }
+}
@@ -0,0 +1,315 @@
+//! Transaction watch-list
+//!
+//! Hosts watch a transaction by its mesh id (SHA-256 hex of the bytes) or its
+//! base58 signature and hear about every status change, whichever way the news
+//! arrives: from RPC when this device submitted it as a gateway, or as a mesh
+//! `SuccessConfirmation` when an offline originator hears back from one. A
+//! watch ends once the transaction is confirmed or failed.
+//!
+//! Native embedders pass a [`WatchCallback`]; binding layers rely on the
+//! `TxStatus` [`ProtocolEvent`](super::types::ProtocolEvent) the transport
+//! emits for every update instead.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Transactions watched at once
+pub const MAX_WATCHES: usize = 1000;
+
+/// Status reported for a watched transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatchedStatus {
+    /// Waiting for news
+    Pending,
+    /// Submitted to the network by this device
+    Submitted,
+    Confirmed,
+    /// Failed on chain or terminally rejected
+    Failed,
+}
+
+impl WatchedStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WatchedStatus::Pending => "pending",
+            WatchedStatus::Submitted => "submitted",
+            WatchedStatus::Confirmed => "confirmed",
+            WatchedStatus::Failed => "failed",
+        }
+    }
+
+    fn is_final(self) -> bool {
+        matches!(self, WatchedStatus::Confirmed | WatchedStatus::Failed)
+    }
+}
+
+/// Where a status change came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfirmationSource {
+    /// This device's own RPC submission
+    Rpc,
+    /// A confirmation relayed through the mesh
+    Mesh,
+}
+
+/// A watched transaction and its last known status
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedTransaction {
+    pub watch_id: u64,
+    /// Mesh id, once known
+    pub tx_id: Option<String>,
+    /// Base58 signature, once known
+    pub signature: Option<String>,
+    pub status: WatchedStatus,
+    pub source: Option<ConfirmationSource>,
+    /// Failure reason, when the source gave one
+    pub error: Option<String>,
+}
+
+/// Native callback for status changes of one watch
+pub type WatchCallback = Arc<dyn Fn(&WatchedTransaction) + Send + Sync>;
+
+struct Watch {
+    state: WatchedTransaction,
+    callback: Option<WatchCallback>,
+}
+
+impl Watch {
+    fn matches(&self, tx_id: Option<&str>, signature: Option<&str>) -> bool {
+        let same = |ours: &Option<String>, theirs: Option<&str>| {
+            theirs.is_some() && ours.as_deref() == theirs
+        };
+        same(&self.state.tx_id, tx_id) || same(&self.state.signature, signature)
+    }
+}
+
+#[derive(Default)]
+struct WatchState {
+    watches: Vec<Watch>,
+    next_id: u64,
+}
+
+/// Watched transactions of one transport
+#[derive(Default)]
+pub struct TxWatchList {
+    state: Mutex<WatchState>,
+}
+
+impl TxWatchList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Watch a transaction by mesh id (64 hex chars) or base58 signature
+    pub fn watch(
+        &self,
+        id: &str,
+        callback: Option<WatchCallback>,
+    ) -> Result<WatchedTransaction, String> {
+        let id = id.trim();
+        let (tx_id, signature) = if is_tx_id(id) {
+            (Some(id.to_ascii_lowercase()), None)
+        } else if is_signature(id) {
+            (None, Some(id.to_string()))
+        } else {
+            return Err(format!(
+                "Not a transaction id or signature: {}",
+                id.chars().take(16).collect::<String>()
+            ));
+        };
+
+        let mut state = self.state.lock();
+        if state.watches.len() >= MAX_WATCHES {
+            return Err(format!(
+                "Too many watched transactions (max {})",
+                MAX_WATCHES
+            ));
+        }
+        state.next_id += 1;
+        let watched = WatchedTransaction {
+            watch_id: state.next_id,
+            tx_id,
+            signature,
+            status: WatchedStatus::Pending,
+            source: None,
+            error: None,
+        };
+        state.watches.push(Watch {
+            state: watched.clone(),
+            callback,
+        });
+        Ok(watched)
+    }
+
+    /// Stop watching; false if the watch already ended
+    pub fn unwatch(&self, watch_id: u64) -> bool {
+        let mut state = self.state.lock();
+        let before = state.watches.len();
+        state.watches.retain(|w| w.state.watch_id != watch_id);
+        state.watches.len() != before
+    }
+
+    /// Active watches, oldest first
+    pub fn list(&self) -> Vec<WatchedTransaction> {
+        let state = self.state.lock();
+        state.watches.iter().map(|w| w.state.clone()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().watches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Apply a status change reported by RPC, where both ids are known.
+    /// Returns the updates (callbacks have already run).
+    pub fn update_rpc(
+        &self,
+        tx_id: &str,
+        signature: &str,
+        status: WatchedStatus,
+        error: Option<String>,
+    ) -> Vec<WatchedTransaction> {
+        self.update(
+            |w| w.matches(Some(tx_id), Some(signature)),
+            Some(tx_id),
+            Some(signature),
+            status,
+            ConfirmationSource::Rpc,
+            error,
+        )
+    }
+
+    /// Apply a mesh confirmation, which only carries `tx_id_hash(tx_id)`
+    /// (and, on success, possibly the signature)
+    pub fn update_mesh(
+        &self,
+        tx_id_hash: &[u8; 16],
+        signature: Option<&str>,
+        success: bool,
+    ) -> Vec<WatchedTransaction> {
+        let status = if success {
+            WatchedStatus::Confirmed
+        } else {
+            WatchedStatus::Failed
+        };
+        self.update(
+            |w| {
+                w.matches(None, signature)
+                    || w.state
+                        .tx_id
+                        .as_deref()
+                        .is_some_and(|tx_id| crate::ble::tx_id_hash(tx_id) == *tx_id_hash)
+            },
+            None,
+            signature,
+            status,
+            ConfirmationSource::Mesh,
+            None,
+        )
+    }
+
+    fn update(
+        &self,
+        matches: impl Fn(&Watch) -> bool,
+        tx_id: Option<&str>,
+        signature: Option<&str>,
+        status: WatchedStatus,
+        source: ConfirmationSource,
+        error: Option<String>,
+    ) -> Vec<WatchedTransaction> {
+        let mut fired = Vec::new();
+        {
+            let mut state = self.state.lock();
+            for watch in state.watches.iter_mut().filter(|w| matches(w)) {
+                if watch.state.status == status {
+                    continue;
+                }
+                let watched = &mut watch.state;
+                watched.status = status;
+                watched.source = Some(source);
+                watched.error = error.clone();
+                if watched.tx_id.is_none() {
+                    watched.tx_id = tx_id.map(str::to_string);
+                }
+                if watched.signature.is_none() {
+                    watched.signature = signature.map(str::to_string);
+                }
+                fired.push((watched.clone(), watch.callback.clone()));
+            }
+            state.watches.retain(|w| !w.state.status.is_final());
+        }
+
+        // Callbacks run outside the lock so they can watch or unwatch
+        fired
+            .into_iter()
+            .map(|(watched, callback)| {
+                if let Some(callback) = callback {
+                    callback(&watched);
+                }
+                watched
+            })
+            .collect()
+    }
+}
+
+fn is_tx_id(id: &str) -> bool {
+    id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn is_signature(id: &str) -> bool {
+    bs58::decode(id)
+        .into_vec()
+        .is_ok_and(|bytes| bytes.len() == 64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_watch_fires_for_rpc_and_mesh_confirmations() {
+        let watches = TxWatchList::new();
+        let tx_id = "ab".repeat(32);
+        let signature = bs58::encode([7u8; 64]).into_string();
+        assert!(watches.watch("not-an-id", None).is_err());
+
+        // Gateway path: submitted, then confirmed over RPC
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let by_signature = watches
+            .watch(
+                &signature,
+                Some(Arc::new(move |_: &WatchedTransaction| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                })),
+            )
+            .unwrap();
+        let updates = watches.update_rpc(&tx_id, &signature, WatchedStatus::Submitted, None);
+        assert_eq!(updates[0].watch_id, by_signature.watch_id);
+        assert_eq!(updates[0].tx_id.as_deref(), Some(tx_id.as_str()));
+        // Repeats of the same status are not reported again
+        assert!(watches
+            .update_rpc(&tx_id, &signature, WatchedStatus::Submitted, None)
+            .is_empty());
+        watches.update_rpc(&tx_id, &signature, WatchedStatus::Confirmed, None);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(watches.is_empty());
+
+        // Originator path: a mesh confirmation only carries the id hash
+        let by_id = watches.watch(&tx_id.to_uppercase(), None).unwrap();
+        let updates = watches.update_mesh(&crate::ble::tx_id_hash(&tx_id), None, false);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].watch_id, by_id.watch_id);
+        assert_eq!(updates[0].status, WatchedStatus::Failed);
+        assert_eq!(updates[0].source, Some(ConfirmationSource::Mesh));
+        assert!(!watches.unwatch(by_id.watch_id));
+    }
+}
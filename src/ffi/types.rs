@@ -67,6 +67,208 @@ impl<T> FfiResult<T> {
     }
 }
 
+// ============================================================================
+// Error codes
+// ============================================================================
+
+/// Machine-readable `code` of an `FfiResult::Err`, stable across releases
+///
+/// Hosts should branch on the code and treat `message` as diagnostic text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FfiErrorCode {
+    /// Handle was never issued, or is bound to a transport lacking the call
+    #[serde(rename = "ERR_INVALID_HANDLE")]
+    InvalidHandle,
+    /// Handle was valid but has been shut down
+    #[serde(rename = "ERR_SHUT_DOWN")]
+    ShutDown,
+    /// Malformed argument: unreadable string/bytes, bad JSON, bad key or hex
+    #[serde(rename = "ERR_INVALID_INPUT")]
+    InvalidInput,
+    /// Transaction is unsigned or its signatures do not verify
+    #[serde(rename = "ERR_INVALID_SIGNATURE")]
+    InvalidSignature,
+    /// Requested item (message, transaction, backup) does not exist
+    #[serde(rename = "ERR_NOT_FOUND")]
+    NotFound,
+    /// Feature needs configuration the node was not initialized with
+    /// (storage directory, storage budget, Pollicore URL, ...)
+    #[serde(rename = "ERR_NOT_CONFIGURED")]
+    NotConfigured,
+    /// Network request (Solana RPC, Pollicore) failed
+    #[serde(rename = "ERR_RPC")]
+    Rpc,
+    /// Queue rejected the operation (full, persistence failure)
+    #[serde(rename = "ERR_QUEUE")]
+    Queue,
+    /// Reading or writing persisted state failed
+    #[serde(rename = "ERR_STORAGE")]
+    Storage,
+    /// Host requires a newer SDK or wire protocol than this build provides
+    #[serde(rename = "ERR_VERSION_MISMATCH")]
+    VersionMismatch,
+    /// Cached nonce is older than the staleness policy allows; refresh it
+    #[serde(rename = "ERR_STALE_NONCE")]
+    StaleNonce,
+    /// Unexpected failure; see `message`
+    #[serde(rename = "ERR_INTERNAL")]
+    Internal,
+}
+
+impl FfiErrorCode {
+    pub const ALL: [FfiErrorCode; 12] = [
+        FfiErrorCode::InvalidHandle,
+        FfiErrorCode::ShutDown,
+        FfiErrorCode::InvalidInput,
+        FfiErrorCode::InvalidSignature,
+        FfiErrorCode::NotFound,
+        FfiErrorCode::NotConfigured,
+        FfiErrorCode::Rpc,
+        FfiErrorCode::Queue,
+        FfiErrorCode::Storage,
+        FfiErrorCode::VersionMismatch,
+        FfiErrorCode::StaleNonce,
+        FfiErrorCode::Internal,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FfiErrorCode::InvalidHandle => "ERR_INVALID_HANDLE",
+            FfiErrorCode::ShutDown => "ERR_SHUT_DOWN",
+            FfiErrorCode::InvalidInput => "ERR_INVALID_INPUT",
+            FfiErrorCode::InvalidSignature => "ERR_INVALID_SIGNATURE",
+            FfiErrorCode::NotFound => "ERR_NOT_FOUND",
+            FfiErrorCode::NotConfigured => "ERR_NOT_CONFIGURED",
+            FfiErrorCode::Rpc => "ERR_RPC",
+            FfiErrorCode::Queue => "ERR_QUEUE",
+            FfiErrorCode::Storage => "ERR_STORAGE",
+            FfiErrorCode::VersionMismatch => "ERR_VERSION_MISMATCH",
+            FfiErrorCode::StaleNonce => "ERR_STALE_NONCE",
+            FfiErrorCode::Internal => "ERR_INTERNAL",
+        }
+    }
+}
+
+/// Coded FFI error
+///
+/// FFI entry points run closures returning `Result<_, FfiError>` and only turn
+/// the error into an `FfiResult` envelope at the JNI/C boundary (see
+/// [`result_envelope`]). Plain `String` errors from lower layers carry no code
+/// and convert to `ERR_INTERNAL`; map them explicitly where the code is known.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("[{}] {message}", .code.as_str())]
+pub struct FfiError {
+    pub code: FfiErrorCode,
+    pub message: String,
+}
+
+impl FfiError {
+    pub fn new(code: FfiErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(FfiErrorCode::InvalidInput, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(FfiErrorCode::NotFound, message)
+    }
+
+    pub fn not_configured(message: impl Into<String>) -> Self {
+        Self::new(FfiErrorCode::NotConfigured, message)
+    }
+
+    pub fn storage(message: impl Into<String>) -> Self {
+        Self::new(FfiErrorCode::Storage, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(FfiErrorCode::Internal, message)
+    }
+
+    /// Failure to serialize a response
+    pub fn serialization(error: serde_json::Error) -> Self {
+        Self::internal(format!("Serialization error: {}", error))
+    }
+
+    /// `FfiResult::Err` envelope for this error
+    pub fn to_result<T>(&self) -> FfiResult<T> {
+        FfiResult::error(self.code.as_str(), self.message.clone())
+    }
+}
+
+/// `FfiResult` JSON for a closure result: success JSON passes through, errors
+/// become a coded error envelope
+pub fn result_envelope(result: Result<String, FfiError>) -> String {
+    result.unwrap_or_else(|e| {
+        let error_response: FfiResult<()> = e.to_result();
+        serde_json::to_string(&error_response)
+            .unwrap_or_else(|_| fallback_envelope("Serialization failed"))
+    })
+}
+
+/// Hand-built `ERR_INTERNAL` envelope for when even the error can't be
+/// encoded; `message` must not need JSON escaping
+pub(crate) fn fallback_envelope(message: &str) -> String {
+    format!(
+        r#"{{"ok":false,"code":"{}","message":"{}"}}"#,
+        FfiErrorCode::Internal.as_str(),
+        message
+    )
+}
+
+impl From<String> for FfiError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<&str> for FfiError {
+    fn from(message: &str) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<super::handles::HandleError> for FfiError {
+    fn from(error: super::handles::HandleError) -> Self {
+        let code = match error {
+            super::handles::HandleError::Invalid(_) => FfiErrorCode::InvalidHandle,
+            super::handles::HandleError::ShutDown(_) => FfiErrorCode::ShutDown,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<super::nonce_accounts::StaleNonce> for FfiError {
+    fn from(error: super::nonce_accounts::StaleNonce) -> Self {
+        Self::new(FfiErrorCode::StaleNonce, error.to_string())
+    }
+}
+
+impl From<crate::PolliNetError> for FfiError {
+    fn from(error: crate::PolliNetError) -> Self {
+        let code = match &error {
+            crate::PolliNetError::SolanaRpc(_) => FfiErrorCode::Rpc,
+            crate::PolliNetError::Serialization(_) => FfiErrorCode::InvalidInput,
+            crate::PolliNetError::Configuration(_) => FfiErrorCode::NotConfigured,
+            crate::PolliNetError::Queue(_) => FfiErrorCode::Queue,
+            crate::PolliNetError::InvalidSignature(_) => FfiErrorCode::InvalidSignature,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+/// Id of a background request started by an `*Async` call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestIdResponse {
+    #[serde(rename = "requestId")]
+    pub request_id: u64,
+}
+
 // ============================================================================
 // Fragmentation types
 // ============================================================================
@@ -93,10 +295,121 @@ pub struct FragmentList {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProtocolEvent {
     #[serde(rename = "type")]
-    pub event_type: String, // "TransactionComplete" | "TextMessage" | "Error" | "Ack"
+    pub event_type: String, // "TransactionComplete" | "TextMessage" | "MessageStatus" | "RelayReceipt" | "TxStatus" | "Error" | "Ack" | "RequestComplete"
     pub tx_id: Option<String>,
     pub size: Option<u64>,
     pub message: Option<String>,
+    /// Background request id (`RequestComplete` only)
+    #[serde(rename = "requestId", default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<u64>,
+}
+
+impl ProtocolEvent {
+    /// All fragments of a transaction arrived and it was reassembled
+    pub fn transaction_complete(tx_id: impl Into<String>, size: usize) -> Self {
+        Self {
+            event_type: "TransactionComplete".to_string(),
+            tx_id: Some(tx_id.into()),
+            size: Some(size as u64),
+            message: None,
+            request_id: None,
+        }
+    }
+
+    /// A submission confirmation for `tx_id` arrived from the mesh
+    pub fn ack(tx_id: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            event_type: "Ack".to_string(),
+            tx_id: Some(tx_id.into()),
+            size: None,
+            message: Some(message.into()),
+            request_id: None,
+        }
+    }
+
+    /// A text message was exchanged with a peer
+    pub fn text_message(peer_id: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            event_type: "TextMessage".to_string(),
+            tx_id: None,
+            size: None,
+            message: Some(format!("{}: {}", peer_id.into(), body.into())),
+            request_id: None,
+        }
+    }
+
+    /// A sent text message changed status (`DELIVERED`, `READ` or `FAILED`)
+    pub fn message_status(message_id: impl Into<String>, status: impl Into<String>) -> Self {
+        Self {
+            event_type: "MessageStatus".to_string(),
+            tx_id: None,
+            size: None,
+            message: Some(format!("{}: {}", message_id.into(), status.into())),
+            request_id: None,
+        }
+    }
+
+    /// A verified relay receipt arrived for a transaction (`tx_id` holds its
+    /// `tx_id_hash`, `message` the action and relayer key)
+    pub fn relay_receipt(
+        tx_id_hash: impl Into<String>,
+        action: impl Into<String>,
+        relayer: impl Into<String>,
+    ) -> Self {
+        Self {
+            event_type: "RelayReceipt".to_string(),
+            tx_id: Some(tx_id_hash.into()),
+            size: None,
+            message: Some(format!("{} by {}", action.into(), relayer.into())),
+            request_id: None,
+        }
+    }
+
+    /// A watched transaction changed status; `message` holds the
+    /// `WatchedTransaction` JSON
+    pub fn tx_status(watched: &super::tx_watch::WatchedTransaction) -> Self {
+        Self {
+            event_type: "TxStatus".to_string(),
+            tx_id: watched.tx_id.clone().or_else(|| watched.signature.clone()),
+            size: None,
+            message: serde_json::to_string(watched).ok(),
+            request_id: None,
+        }
+    }
+
+    /// A new emergency notice arrived; `message` holds the
+    /// `EmergencyNotice` JSON
+    pub fn emergency(notice: &crate::ble::EmergencyNotice) -> Self {
+        Self {
+            event_type: "Emergency".to_string(),
+            tx_id: None,
+            size: None,
+            message: serde_json::to_string(notice).ok(),
+            request_id: None,
+        }
+    }
+
+    /// Processing failed (e.g. reassembly)
+    pub fn error(tx_id: Option<String>, message: impl Into<String>) -> Self {
+        Self {
+            event_type: "Error".to_string(),
+            tx_id,
+            size: None,
+            message: Some(message.into()),
+            request_id: None,
+        }
+    }
+
+    /// A background request finished; `message` holds its `FfiResult` JSON
+    pub fn request_complete(request_id: u64, result_json: impl Into<String>) -> Self {
+        Self {
+            event_type: "RequestComplete".to_string(),
+            tx_id: None,
+            size: None,
+            message: Some(result_json.into()),
+            request_id: Some(request_id),
+        }
+    }
 }
 
 // ============================================================================
@@ -104,6 +417,7 @@ pub struct ProtocolEvent {
 // ============================================================================
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
 pub struct MetricsSnapshot {
     #[serde(rename = "fragmentsBuffered")]
     pub fragments_buffered: u32,
@@ -111,10 +425,105 @@ pub struct MetricsSnapshot {
     pub transactions_complete: u32,
     #[serde(rename = "reassemblyFailures")]
     pub reassembly_failures: u32,
+    /// Fragments rejected at receive time for a bad CRC
+    #[serde(rename = "corruptFragments", default)]
+    pub corrupt_fragments: u32,
+    /// Partial transactions evicted to stay within the reassembly budget
+    #[serde(rename = "reassemblyEvictions", default)]
+    pub reassembly_evictions: u32,
+    /// Partial transactions discarded after the reassembly timeout
+    #[serde(rename = "reassemblyTimeouts", default)]
+    pub reassembly_timeouts: u32,
+    /// Inbound frames dropped because the inbound queue was full
+    #[serde(rename = "inboundDropped", default)]
+    pub inbound_dropped: u32,
     #[serde(rename = "lastError")]
     pub last_error: String,
     #[serde(rename = "updatedAt")]
     pub updated_at: u64,
+    /// Bytes handed to the SDK compressor
+    #[serde(rename = "compressionBytesIn", default)]
+    pub compression_bytes_in: u64,
+    /// Bytes it produced (headers included)
+    #[serde(rename = "compressionBytesOut", default)]
+    pub compression_bytes_out: u64,
+    /// `compressionBytesOut / compressionBytesIn` (0 before any payload)
+    #[serde(rename = "compressionRatio", default)]
+    pub compression_ratio: f64,
+}
+
+/// Radio adapter status as last reported by the host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdapterStatus {
+    /// `None` until the host reports a state
+    pub state: Option<crate::AdapterState>,
+    pub role: Option<crate::DeviceRole>,
+    /// Advertised capability byte (see [`crate::role::capability`])
+    pub capability_flags: u8,
+    pub online: bool,
+}
+
+impl std::fmt::Display for AdapterStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.state {
+            Some(state) => write!(f, "Adapter: {:?}", state)?,
+            None => write!(f, "Adapter: unknown")?,
+        }
+        if let Some(role) = self.role {
+            write!(f, ", role {:?}", role)?;
+        }
+        write!(
+            f,
+            ", capabilities 0x{:02x}, {}",
+            self.capability_flags,
+            if self.online { "online" } else { "offline" }
+        )
+    }
+}
+
+/// What a peer's transaction digest says about our outbound queue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestSummary {
+    /// Transactions the peer reported holding
+    pub peer_count: usize,
+    /// Queued transactions the peer (probably) already has
+    pub already_held: usize,
+    /// Queued transactions still to send to it
+    pub to_send: usize,
+}
+
+/// BLE engine status
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BleStatus {
+    pub adapter: AdapterStatus,
+    /// Peers seen in scans within the density window
+    pub nearby_peers: u32,
+    pub connected_peers: usize,
+    /// Frames waiting for the host to send
+    pub outbound_frames: usize,
+    /// Transactions with fragments still being reassembled
+    pub pending_reassemblies: usize,
+    /// Reassembled transactions waiting for submission
+    pub received_transactions: usize,
+}
+
+impl std::fmt::Display for BleStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.adapter)?;
+        writeln!(
+            f,
+            "Peers: {} nearby, {} connected",
+            self.nearby_peers, self.connected_peers
+        )?;
+        write!(
+            f,
+            "Queues: {} outbound frames, {} reassembling, {} received",
+            self.outbound_frames, self.pending_reassemblies, self.received_transactions
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -148,6 +557,9 @@ pub struct SdkConfig {
     pub version: u32,
     #[serde(rename = "rpcUrl", default)]
     pub rpc_url: Option<String>,
+    /// Fallback RPC endpoints, tried after `rpcUrl` in order
+    #[serde(rename = "rpcEndpoints", default)]
+    pub rpc_endpoints: Vec<String>,
     #[serde(rename = "enableLogging", default = "default_enable_logging")]
     pub enable_logging: bool,
     #[serde(rename = "logLevel", default)]
@@ -159,6 +571,15 @@ pub struct SdkConfig {
     /// environment variable when absent (useful for CLI/server usage).
     #[serde(rename = "encryptionKey", default)]
     pub encryption_key: Option<String>,
+    /// Base64-encoded 32-byte storage key held by the platform keystore
+    /// (Android Keystore / iOS Keychain). Used as-is, with no derivation in Rust;
+    /// takes precedence over `encryptionKey` when both are set.
+    #[serde(rename = "platformKey", default)]
+    pub platform_key: Option<String>,
+    /// Disk budget in bytes for `storageDirectory`. When exceeded, the oldest
+    /// fragment caches, logs and archives are deleted (queues and keys are kept).
+    #[serde(rename = "storageBudgetBytes", default)]
+    pub storage_budget_bytes: Option<u64>,
     /// Base58-encoded Solana wallet address that owns this node session.
     /// When provided it is stored on the transport and will be used to attribute
     /// uptime, relay and submission rewards to the correct wallet.
@@ -166,6 +587,197 @@ pub struct SdkConfig {
     /// in the mesh but rewards cannot be allocated until a wallet is associated.
     #[serde(rename = "walletAddress", default)]
     pub wallet_address: Option<String>,
+    /// Oldest SDK version (`major.minor.patch`) the host app works with;
+    /// init fails with `ERR_VERSION_MISMATCH` when this build is older.
+    #[serde(rename = "minSdkVersion", default)]
+    pub min_sdk_version: Option<String>,
+    /// Oldest mesh wire protocol version the host app works with
+    #[serde(rename = "minProtocolVersion", default)]
+    pub min_protocol_version: Option<u8>,
+    /// Part this device plays in the mesh ("originator", "relay", "gateway");
+    /// unset keeps outbound priorities as requested. Relays never use `rpcUrl`.
+    #[serde(default)]
+    pub role: Option<crate::DeviceRole>,
+    /// Radio settings shared by every transport handle
+    #[serde(default)]
+    pub ble: BleSettings,
+    /// Outbound/confirmation queue limits
+    #[serde(default)]
+    pub queue: crate::queue::QueueLimits,
+    /// Compression used for transactions this device originates
+    #[serde(default)]
+    pub compression: crate::util::compression::CompressionSettings,
+    /// Maximum age of cached nonces used by `buildPaymentTransaction`
+    #[serde(rename = "nonceStaleness", default)]
+    pub nonce_staleness: super::nonce_accounts::NonceStalenessPolicy,
+}
+
+/// Radio settings from an `SdkConfig`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BleSettings {
+    /// Fragment payload used when `queueTransaction` is called without one
+    /// (defaults to `MAX_FRAGMENT_DATA`)
+    #[serde(default)]
+    pub fragment_payload: Option<usize>,
+    /// Seconds without a new fragment before a partial transaction is
+    /// discarded (defaults to `DEFAULT_REASSEMBLY_TIMEOUT_SECS`)
+    #[serde(default)]
+    pub reassembly_timeout_secs: Option<u64>,
+    /// Ask the sender to restart a transaction discarded for inactivity
+    #[serde(default)]
+    pub nack_on_reassembly_timeout: bool,
+    /// Per-origin relay limits (unset leaves relaying unlimited)
+    #[serde(default)]
+    pub relay_policy: Option<super::relay_policy::RelayPolicyConfig>,
+    /// Rotate the advertised id so passive scanners can't track the device
+    #[serde(default)]
+    pub privacy: crate::ble::PrivacySettings,
+}
+
+impl SdkConfig {
+    /// Load a config file, TOML or JSON by extension, with the same keys as
+    /// the JSON passed to `init`
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, FfiError> {
+        use config::{Config, File, FileFormat};
+
+        let path = path.as_ref();
+        let format = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => FileFormat::Toml,
+            Some("json") => FileFormat::Json,
+            _ => {
+                return Err(FfiError::invalid_input(format!(
+                    "Unsupported config file {} (expected .toml or .json)",
+                    path.display()
+                )))
+            }
+        };
+        Config::builder()
+            .add_source(File::from(path).format(format))
+            .build()
+            .and_then(|c| c.try_deserialize())
+            .map_err(|e| {
+                FfiError::invalid_input(format!("Failed to load config {}: {}", path.display(), e))
+            })
+    }
+
+    /// `rpcUrl` followed by `rpcEndpoints`, comma-separated as `RpcPool::from_list` expects
+    pub fn rpc_url_list(&self) -> Option<String> {
+        let urls: Vec<&str> = self
+            .rpc_url
+            .iter()
+            .chain(&self.rpc_endpoints)
+            .map(|url| url.trim())
+            .filter(|url| !url.is_empty())
+            .collect();
+        (!urls.is_empty()).then(|| urls.join(","))
+    }
+
+    /// Resolve the secure storage key source (platform key first, then passphrase/env)
+    pub fn storage_key_provider(
+        &self,
+    ) -> Result<std::sync::Arc<dyn crate::storage::KeyProvider>, String> {
+        use crate::storage::{PassphraseKeyProvider, PlatformKeyProvider};
+
+        match &self.platform_key {
+            Some(encoded) => PlatformKeyProvider::from_base64(encoded)
+                .map(|p| std::sync::Arc::new(p) as _)
+                .map_err(|e| e.to_string()),
+            None => PassphraseKeyProvider::from_option_or_env(self.encryption_key.clone())
+                .map(|p| std::sync::Arc::new(p) as _)
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Fail fast when the host requires a newer SDK or protocol than this build
+    pub fn check_compatibility(&self) -> Result<(), FfiError> {
+        let info = ProtocolInfo::current();
+        if let Some(required) = &self.min_sdk_version {
+            let wanted = parse_version(required).ok_or_else(|| {
+                FfiError::invalid_input(format!("Invalid minSdkVersion: {}", required))
+            })?;
+            if parse_version(&info.sdk_version) < Some(wanted) {
+                return Err(FfiError::new(
+                    FfiErrorCode::VersionMismatch,
+                    format!(
+                        "App requires PolliNet SDK >= {}, but this build is {}",
+                        required, info.sdk_version
+                    ),
+                ));
+            }
+        }
+        if let Some(required) = self.min_protocol_version {
+            if info.protocol_version < required {
+                return Err(FfiError::new(
+                    FfiErrorCode::VersionMismatch,
+                    format!(
+                        "App requires wire protocol >= {}, but this build speaks {}",
+                        required, info.protocol_version
+                    ),
+                ));
+            }
+        }
+        if self.queue.store == crate::queue::QueueStoreKind::Sqlite && !cfg!(feature = "sqlite") {
+            return Err(FfiError::invalid_input(
+                "queue.store \"sqlite\" needs a build with the sqlite feature",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// `major.minor.patch` (missing parts are 0, pre-release/build suffixes ignored)
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.trim().split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    parts.next().is_none().then_some((major, minor, patch))
+}
+
+/// SDK and wire protocol versions plus the features compiled into this build,
+/// so hosts can check compatibility before creating a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolInfo {
+    pub sdk_version: String,
+    /// Mesh packet header version spoken on the wire
+    pub protocol_version: u8,
+    /// Highest `SdkConfig.version` understood
+    pub config_version: u32,
+    /// Cargo features and API capabilities available in this build
+    pub features: Vec<String>,
+}
+
+impl ProtocolInfo {
+    pub fn current() -> Self {
+        let compiled = [
+            ("android", cfg!(feature = "android")),
+            ("uniffi", cfg!(feature = "uniffi")),
+            ("c-api", cfg!(feature = "c-api")),
+            ("sqlite", cfg!(feature = "sqlite")),
+            ("vault", cfg!(feature = "vault")),
+        ];
+        let capabilities = [
+            "errorCodes",
+            "textMessaging",
+            "transactionHistory",
+            "asyncRequests",
+        ];
+        Self {
+            sdk_version: env!("CARGO_PKG_VERSION").to_string(),
+            protocol_version: crate::ble::PROTOCOL_VERSION,
+            config_version: default_version(),
+            features: compiled
+                .into_iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(name, _)| name)
+                .chain(capabilities)
+                .map(String::from)
+                .collect(),
+        }
+    }
 }
 
 // SubmitIntentRequest / SubmitIntentResponse live in crate::submission — see src/submission/mod.rs
@@ -178,6 +790,49 @@ fn default_enable_logging() -> bool {
     true
 }
 
+// ============================================================================
+// Mesh state types
+// ============================================================================
+
+/// Peer observed in BLE scans within the density window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveredPeerFFI {
+    #[serde(rename = "peerId")]
+    pub peer_id: String,
+    /// Unix timestamp (seconds) of the latest scan result
+    #[serde(rename = "lastSeen")]
+    pub last_seen: u64,
+    /// Peer is in the rotation cooldown list
+    #[serde(rename = "coolingDown")]
+    pub cooling_down: bool,
+}
+
+/// Live view of the local mesh for host UIs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeshStateFFI {
+    #[serde(rename = "discoveredPeers")]
+    pub discovered_peers: Vec<DiscoveredPeerFFI>,
+    /// Peers with a live connection (heartbeat within the stale threshold)
+    #[serde(rename = "connectedPeers")]
+    pub connected_peers: Vec<String>,
+    /// Per-peer link statistics
+    pub peers: Vec<crate::ble::PeerHealth>,
+    pub topology: crate::ble::NetworkTopology,
+    pub metrics: crate::ble::HealthMetrics,
+}
+
+/// Connection graph reported by the host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateTopologyRequest {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    #[serde(rename = "directConnections")]
+    pub direct_connections: Vec<String>,
+    /// peer_id -> peers it reports being connected to
+    #[serde(default)]
+    pub connections: std::collections::HashMap<String, Vec<String>>,
+}
+
 // ============================================================================
 // Queue Management Types (Phase 2)
 // ============================================================================
@@ -226,6 +881,24 @@ pub struct RetryItemFFI {
     pub age_seconds: u64,
 }
 
+/// Dead-lettered transaction for FFI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetterFFI {
+    #[serde(rename = "txBytes")]
+    pub tx_bytes: String, // base64
+    #[serde(rename = "txId")]
+    pub tx_id: String,
+    pub attempts: usize,
+    #[serde(rename = "lastError")]
+    pub last_error: String,
+    /// "MaxRetriesExceeded" or "Expired"
+    pub reason: String,
+    #[serde(rename = "firstFailedAt")]
+    pub first_failed_at: u64,
+    #[serde(rename = "deadLetteredAt")]
+    pub dead_lettered_at: u64,
+}
+
 /// Confirmation status for FFI
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -264,6 +937,55 @@ pub struct QueueMetricsFFI {
     pub retry_size: usize,
     #[serde(rename = "retryAvgAttempts")]
     pub retry_avg_attempts: f32,
+    #[serde(rename = "deadLetterSize")]
+    pub dead_letter_size: usize,
+    #[serde(rename = "outboundExpiredTotal")]
+    pub outbound_expired_total: u64,
+    #[serde(rename = "outboundEvictedTotal")]
+    pub outbound_evicted_total: u64,
+    #[serde(rename = "outboundThrottledTotal")]
+    pub outbound_throttled_total: u64,
+}
+
+impl From<crate::queue::QueueMetrics> for QueueMetricsFFI {
+    fn from(m: crate::queue::QueueMetrics) -> Self {
+        Self {
+            outbound_size: m.outbound_size,
+            outbound_high_priority: m.outbound_high_priority,
+            outbound_normal_priority: m.outbound_normal_priority,
+            outbound_low_priority: m.outbound_low_priority,
+            confirmation_size: m.confirmation_size,
+            retry_size: m.retry_size,
+            retry_avg_attempts: m.retry_avg_attempts,
+            dead_letter_size: m.dead_letter_size,
+            outbound_expired_total: m.outbound_expired_total,
+            outbound_evicted_total: m.outbound_evicted_total,
+            outbound_throttled_total: m.outbound_throttled_total,
+        }
+    }
+}
+
+/// Queue health for FFI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueHealthFFI {
+    /// "HEALTHY" | "WARNING" | "CRITICAL"
+    pub status: String,
+    pub issues: Vec<String>,
+}
+
+impl From<crate::queue::HealthStatus> for QueueHealthFFI {
+    fn from(health: crate::queue::HealthStatus) -> Self {
+        use crate::queue::HealthStatus;
+        let (status, issues) = match health {
+            HealthStatus::Healthy => ("HEALTHY", Vec::new()),
+            HealthStatus::Warning(issues) => ("WARNING", issues),
+            HealthStatus::Critical(issues) => ("CRITICAL", issues),
+        };
+        Self {
+            status: status.to_string(),
+            issues,
+        }
+    }
 }
 
 /// Request to push outbound transaction
@@ -332,6 +1054,20 @@ pub struct SuccessResponse {
     pub success: bool,
 }
 
+/// Stable identity of this device: its signing key and the short id derived
+/// from it. Survives restarts once secure storage is configured.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceIdentity {
+    /// Base58 Ed25519 public key
+    pub public_key: String,
+    /// Hex of the first 4 key bytes; fits in a BLE advertisement and matches
+    /// the node id in traceroute hops
+    pub short_id: String,
+    /// False while the key is ephemeral (no secure storage yet)
+    pub persisted: bool,
+}
+
 /// Queue size response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueueSizeResponse {
@@ -430,3 +1166,193 @@ pub struct RevokeTransactionResponse {
     /// Base64-encoded unsigned transaction; sign with owner_wallet before submitting.
     pub transaction: String,
 }
+
+/// Builds an unsigned transaction from arbitrary program instructions
+/// (governance votes, program-specific apps) for the same offline signing path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCustomTransactionRequest {
+    pub fee_payer: String,
+    /// Recent blockhash (base58).
+    pub recent_blockhash: String,
+    pub instructions: Vec<crate::intent::CustomInstructionInput>,
+}
+
+/// Response for [CreateCustomTransactionRequest].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomTransactionResponse {
+    /// Base64-encoded unsigned transaction.
+    pub transaction: String,
+    /// Accounts that must sign (base58), fee payer first.
+    pub required_signers: Vec<String>,
+}
+
+/// Issues a signed offline payment request (merchant side).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePaymentRequestRequest {
+    /// Wallet to pay (base58).
+    pub recipient: String,
+    /// Lamports, or the token's smallest unit when `mint` is set.
+    pub amount: u64,
+    /// SPL Token mint (base58); omit for SOL.
+    #[serde(default)]
+    pub mint: Option<String>,
+    /// Token decimal places (required with `mint`).
+    #[serde(default)]
+    pub decimals: u8,
+    #[serde(default)]
+    pub memo: Option<String>,
+    /// Seconds the request stays payable.
+    #[serde(default = "default_payment_request_ttl")]
+    pub expires_in_secs: u64,
+}
+
+fn default_payment_request_ttl() -> u64 {
+    5 * 60
+}
+
+/// Pays a Solana Pay transfer request URL with a durable-nonce transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSolanaPayTransactionRequest {
+    /// `solana:<recipient>?amount=...` URL (e.g. from a scanned QR code).
+    pub url: String,
+    /// Wallet that pays and signs (base58).
+    pub payer: String,
+    /// Payer's durable nonce account.
+    pub nonce_account: super::nonce_accounts::NonceAccountInfo,
+    /// Decimal places of the `spl-token` mint (required for token payments).
+    #[serde(default)]
+    pub decimals: Option<u8>,
+    /// Amount in base units, used only when the URL has none.
+    #[serde(default)]
+    pub amount: Option<u64>,
+    /// Maximum age of `nonce_account` (defaults to a day, warning only).
+    #[serde(default)]
+    pub staleness: super::nonce_accounts::NonceStalenessPolicy,
+}
+
+/// Response for [CreateSolanaPayTransactionRequest].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolanaPayTransactionResponse {
+    /// Base64-encoded unsigned transaction; sign with the payer (and the
+    /// nonce authority) before relaying it.
+    pub transaction: String,
+    /// Amount paid in base units (lamports or token units).
+    pub amount: u64,
+    /// The parsed URL, to show the label and message before signing.
+    pub request: crate::intent::solana_pay::TransferRequest,
+}
+
+/// Unsigned durable-nonce transaction paying a received payment request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentTransactionResponse {
+    pub request_id: String,
+    /// Base64-encoded unsigned transaction; sign with the payer (and the
+    /// nonce authority) before sending it back.
+    pub transaction: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ffi_error_envelope() {
+        let error = FfiError::not_found("Unknown text message: abc");
+        assert_eq!(
+            error.to_string(),
+            "[ERR_NOT_FOUND] Unknown text message: abc"
+        );
+
+        let plain = FfiError::from("something broke".to_string());
+        assert_eq!(plain.code, FfiErrorCode::Internal);
+        assert_eq!(plain.message, "something broke");
+
+        let json: serde_json::Value = serde_json::from_str(&result_envelope(Err(error))).unwrap();
+        assert_eq!(json["ok"], false);
+        assert_eq!(json["code"], "ERR_NOT_FOUND");
+        // The code is not repeated in the message
+        assert_eq!(json["message"], "Unknown text message: abc");
+
+        // The hand-built fallback stays within the documented codes
+        let fallback: serde_json::Value =
+            serde_json::from_str(&fallback_envelope("Serialization failed")).unwrap();
+        assert_eq!(fallback["code"], FfiErrorCode::Internal.as_str());
+    }
+
+    #[test]
+    fn test_min_version_check() {
+        let config = |json: &str| serde_json::from_str::<SdkConfig>(json).unwrap();
+
+        assert!(config("{}").check_compatibility().is_ok());
+        assert!(config(r#"{"minSdkVersion":"0.1"}"#)
+            .check_compatibility()
+            .is_ok());
+        assert!(config(r#"{"minProtocolVersion":1}"#)
+            .check_compatibility()
+            .is_ok());
+
+        let newer = config(r#"{"minSdkVersion":"99.0.0-beta"}"#).check_compatibility();
+        assert_eq!(newer.unwrap_err().code, FfiErrorCode::VersionMismatch);
+        let newer = config(r#"{"minProtocolVersion":200}"#).check_compatibility();
+        assert_eq!(newer.unwrap_err().code, FfiErrorCode::VersionMismatch);
+        let bad = config(r#"{"minSdkVersion":"one"}"#).check_compatibility();
+        assert_eq!(bad.unwrap_err().code, FfiErrorCode::InvalidInput);
+        let sqlite = config(r#"{"queue":{"store":"sqlite"}}"#).check_compatibility();
+        assert_eq!(sqlite.is_ok(), cfg!(feature = "sqlite"));
+    }
+
+    #[test]
+    fn test_config_from_toml_and_json_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml_path = dir.path().join("node.toml");
+        std::fs::write(
+            &toml_path,
+            r#"
+rpcUrl = "https://a.example"
+rpcEndpoints = ["https://b.example"]
+storageDirectory = "/var/lib/pollinet"
+role = "gateway"
+
+[ble]
+fragmentPayload = 200
+relayPolicy = { perDevicePerMinute = 5 }
+
+[queue]
+maxOutbound = 50
+outboundEvictionPolicy = "drop_oldest"
+"#,
+        )
+        .unwrap();
+        let config = SdkConfig::from_file(&toml_path).unwrap();
+        assert_eq!(
+            config.rpc_url_list().as_deref(),
+            Some("https://a.example,https://b.example")
+        );
+        assert_eq!(config.role, Some(crate::DeviceRole::Gateway));
+        assert_eq!(config.ble.fragment_payload, Some(200));
+        let policy = config.ble.relay_policy.unwrap();
+        assert_eq!(policy.per_device_per_minute, Some(5));
+        assert!(policy.per_fee_payer_per_minute.is_some());
+        assert_eq!(config.queue.max_outbound, Some(50));
+        assert!(config.enable_logging);
+
+        let json_path = dir.path().join("node.json");
+        std::fs::write(&json_path, r#"{"queue":{"maxConfirmations":10}}"#).unwrap();
+        let config = SdkConfig::from_file(&json_path).unwrap();
+        assert_eq!(config.queue.max_confirmations, Some(10));
+        assert_eq!(config.rpc_url_list(), None);
+
+        let err = SdkConfig::from_file(dir.path().join("node.yaml")).unwrap_err();
+        assert_eq!(err.code, FfiErrorCode::InvalidInput);
+    }
+
+    #[test]
+    fn test_error_code_names_match_serde() {
+        for code in FfiErrorCode::ALL {
+            assert_eq!(
+                serde_json::to_value(code).unwrap(),
+                serde_json::Value::from(code.as_str())
+            );
+        }
+    }
+}
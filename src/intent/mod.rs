@@ -4,17 +4,25 @@
 //!  - SPL Token `approve_checked` instruction building (delegates to executor PDA)
 //!  - Borsh-compatible 169-byte Intent struct serialization
 //!  - Executor PDA derivation
+//!  - Generic unsigned transactions from caller-supplied instructions
+//!  - Durable-nonce SOL/SPL transfers for offline payments (and Solana Pay
+//!    transfer request parsing, see [`solana_pay`])
 
 use base64::{engine::general_purpose::STANDARD, Engine};
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use solana_sdk::{
-    hash::Hash, instruction::Instruction, message::Message, pubkey::Pubkey,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey::Pubkey,
     transaction::Transaction,
 };
 use spl_token::instruction::approve_checked;
 use std::str::FromStr;
 
+pub mod solana_pay;
+
 pub const POLLINET_PROGRAM_ID: &str = "EJ28rMA3AgRVdNqdCnq4DrpRUfYA12aPdJy1bbFNsQ1A";
 
 /// Token-2022 program ID (hardcoded to avoid adding the crate).
@@ -180,3 +188,232 @@ pub fn build_approve_transaction(
 
     Ok(STANDARD.encode(raw))
 }
+
+// ─── Custom instruction building ─────────────────────────────────────────────
+
+/// One account referenced by a [`CustomInstructionInput`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountMetaInput {
+    /// Account address (base58).
+    pub pubkey: String,
+    #[serde(default)]
+    pub is_signer: bool,
+    #[serde(default)]
+    pub is_writable: bool,
+}
+
+/// A program-specific instruction (governance votes, custom programs, ...).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomInstructionInput {
+    /// Program to invoke (base58).
+    pub program_id: String,
+    pub accounts: Vec<AccountMetaInput>,
+    /// Instruction data, base64-encoded.
+    #[serde(default)]
+    pub data: String,
+}
+
+/// Builds a single unsigned `Transaction` from caller-supplied instructions, in order.
+/// Returns the transaction (bincode, base64) and the signers it requires (fee payer first).
+pub fn build_custom_transaction(
+    fee_payer: &Pubkey,
+    recent_blockhash: Hash,
+    instructions: &[CustomInstructionInput],
+) -> Result<(String, Vec<Pubkey>), String> {
+    if instructions.is_empty() {
+        return Err("At least one instruction is required".to_string());
+    }
+
+    let mut ixs: Vec<Instruction> = Vec::with_capacity(instructions.len());
+    for (i, input) in instructions.iter().enumerate() {
+        let program_id = Pubkey::from_str(&input.program_id).map_err(|e| {
+            format!(
+                "Invalid program_id '{}' (instruction {}): {}",
+                input.program_id, i, e
+            )
+        })?;
+        let accounts = input
+            .accounts
+            .iter()
+            .map(|meta| {
+                let pubkey = Pubkey::from_str(&meta.pubkey).map_err(|e| {
+                    format!(
+                        "Invalid account '{}' (instruction {}): {}",
+                        meta.pubkey, i, e
+                    )
+                })?;
+                Ok(if meta.is_writable {
+                    AccountMeta::new(pubkey, meta.is_signer)
+                } else {
+                    AccountMeta::new_readonly(pubkey, meta.is_signer)
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let data = STANDARD
+            .decode(input.data.trim())
+            .map_err(|e| format!("Invalid data (instruction {}): {}", i, e))?;
+        ixs.push(Instruction {
+            program_id,
+            accounts,
+            data,
+        });
+    }
+
+    let message = Message::new_with_blockhash(&ixs, Some(fee_payer), &recent_blockhash);
+    let signers = message
+        .account_keys
+        .iter()
+        .take(message.header.num_required_signatures as usize)
+        .copied()
+        .collect();
+    let tx = Transaction::new_unsigned(message);
+
+    let raw =
+        bincode1::serialize(&tx).map_err(|e| format!("Transaction serialization failed: {}", e))?;
+
+    Ok((STANDARD.encode(raw), signers))
+}
+
+// ─── Durable-nonce transfers ─────────────────────────────────────────────────
+
+/// SPL Memo program v2.
+const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TczNwbNDS6U2Qt2RAuuPqC";
+
+/// RecentBlockhashes sysvar, read by `AdvanceNonceAccount`.
+const RECENT_BLOCKHASHES_SYSVAR: &str = "SysvarRecentB1ockHashes11111111111111111111";
+
+/// `SystemInstruction` discriminants (encoded by hand; the SDK's system
+/// instruction module is deprecated).
+const SYSTEM_TRANSFER: u32 = 2;
+const SYSTEM_ADVANCE_NONCE: u32 = 4;
+
+/// A SOL or SPL Token payment that stays valid offline: its recent blockhash
+/// is the value of a durable nonce account, advanced by the first instruction.
+#[derive(Debug, Clone)]
+pub struct NonceTransferInput {
+    /// Pays the amount and the fee.
+    pub payer: Pubkey,
+    pub recipient: Pubkey,
+    /// Lamports, or the token's smallest unit when `mint` is set.
+    pub amount: u64,
+    /// SPL Token mint; `None` for SOL.
+    pub mint: Option<Pubkey>,
+    /// Token decimal places (required by `transfer_checked`, ignored for SOL).
+    pub decimals: u8,
+    pub memo: Option<String>,
+    /// Read-only keys added to the transfer so the recipient can find it
+    /// (Solana Pay `reference`).
+    pub references: Vec<Pubkey>,
+    pub nonce_account: Pubkey,
+    pub nonce_authority: Pubkey,
+    /// Current nonce value.
+    pub nonce: Hash,
+}
+
+/// Builds an unsigned durable-nonce transfer: advance nonce, (create the
+/// recipient's token account if needed,) transfer, memo.
+/// Returns the transaction serialized with bincode and base64-encoded.
+pub fn build_nonce_transfer_transaction(input: &NonceTransferInput) -> Result<String, String> {
+    let system_program = Pubkey::default();
+    let mut ixs: Vec<Instruction> = Vec::with_capacity(4);
+
+    ixs.push(Instruction {
+        program_id: system_program,
+        accounts: vec![
+            AccountMeta::new(input.nonce_account, false),
+            AccountMeta::new_readonly(Pubkey::from_str(RECENT_BLOCKHASHES_SYSVAR).unwrap(), false),
+            AccountMeta::new_readonly(input.nonce_authority, true),
+        ],
+        data: SYSTEM_ADVANCE_NONCE.to_le_bytes().to_vec(),
+    });
+
+    match &input.mint {
+        None => {
+            let mut data = SYSTEM_TRANSFER.to_le_bytes().to_vec();
+            data.extend_from_slice(&input.amount.to_le_bytes());
+            ixs.push(Instruction {
+                program_id: system_program,
+                accounts: vec![
+                    AccountMeta::new(input.payer, true),
+                    AccountMeta::new(input.recipient, false),
+                ],
+                data,
+            });
+        }
+        Some(mint) => {
+            use spl_associated_token_account::{
+                get_associated_token_address,
+                instruction::create_associated_token_account_idempotent,
+            };
+
+            let source = get_associated_token_address(&input.payer, mint);
+            let destination = get_associated_token_address(&input.recipient, mint);
+            ixs.push(create_associated_token_account_idempotent(
+                &input.payer,
+                &input.recipient,
+                mint,
+                &spl_token::id(),
+            ));
+            ixs.push(
+                spl_token::instruction::transfer_checked(
+                    &spl_token::id(),
+                    &source,
+                    mint,
+                    &destination,
+                    &input.payer,
+                    &[],
+                    input.amount,
+                    input.decimals,
+                )
+                .map_err(|e| format!("transfer_checked for mint {}: {}", mint, e))?,
+            );
+        }
+    }
+    // The transfer is the last instruction so far
+    let transfer = ixs.last_mut().unwrap();
+    transfer.accounts.extend(
+        input
+            .references
+            .iter()
+            .map(|reference| AccountMeta::new_readonly(*reference, false)),
+    );
+
+    if let Some(memo) = input.memo.as_deref().filter(|m| !m.is_empty()) {
+        ixs.push(Instruction {
+            program_id: Pubkey::from_str(MEMO_PROGRAM_ID).unwrap(),
+            accounts: vec![AccountMeta::new_readonly(input.payer, true)],
+            data: memo.as_bytes().to_vec(),
+        });
+    }
+
+    let message = Message::new_with_blockhash(&ixs, Some(&input.payer), &input.nonce);
+    let tx = Transaction::new_unsigned(message);
+    let raw =
+        bincode1::serialize(&tx).map_err(|e| format!("Transaction serialization failed: {}", e))?;
+    Ok(STANDARD.encode(raw))
+}
+
+/// Recipient and amount of the SOL transfer or SPL `transfer_checked` in
+/// `tx` (the first one found). For SPL the recipient is the destination
+/// token account.
+pub fn transfer_summary(tx: &Transaction) -> Option<(Pubkey, u64)> {
+    let keys = &tx.message.account_keys;
+    tx.message.instructions.iter().find_map(|ix| {
+        let program_id = keys.get(ix.program_id_index as usize)?;
+        let account = |i: usize| keys.get(*ix.accounts.get(i)? as usize).copied();
+        let amount = |range: std::ops::Range<usize>| {
+            Some(u64::from_le_bytes(ix.data.get(range)?.try_into().ok()?))
+        };
+        if *program_id == Pubkey::default()
+            && ix.data.len() == 12
+            && ix.data[..4] == SYSTEM_TRANSFER.to_le_bytes()
+        {
+            Some((account(1)?, amount(4..12)?))
+        } else if *program_id == spl_token::id() && ix.data.len() == 10 && ix.data[0] == 12 {
+            // TokenInstruction::TransferChecked: source, mint, destination, owner
+            Some((account(2)?, amount(1..9)?))
+        } else {
+            None
+        }
+    })
+}
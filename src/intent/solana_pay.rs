@@ -0,0 +1,220 @@
+//! Solana Pay transfer request URLs
+//!
+//! Parses `solana:<recipient>?amount=..&spl-token=..&reference=..&label=..&message=..&memo=..`
+//! (the non-interactive "transfer request" form of the Solana Pay spec) so a
+//! standard Solana Pay QR code scanned offline can be paid with a
+//! durable-nonce transaction relayed over the mesh. Transaction request URLs
+//! (`solana:https://...`) need the merchant's server and are rejected.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// URL scheme of Solana Pay requests
+pub const SOLANA_PAY_SCHEME: &str = "solana:";
+/// Decimal places of SOL amounts
+pub const SOL_DECIMALS: u8 = 9;
+
+/// A parsed Solana Pay transfer request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferRequest {
+    /// Base58 wallet to pay
+    pub recipient: String,
+    /// Amount in user units (e.g. "1.5" SOL), as written in the URL; `None`
+    /// lets the payer choose
+    pub amount: Option<String>,
+    /// Base58 SPL Token mint; `None` for SOL
+    pub spl_token: Option<String>,
+    /// Base58 keys to add to the transfer so the merchant can find it
+    pub references: Vec<String>,
+    pub label: Option<String>,
+    pub message: Option<String>,
+    pub memo: Option<String>,
+}
+
+impl TransferRequest {
+    /// Parse a `solana:` transfer request URL
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let rest = url
+            .strip_prefix(SOLANA_PAY_SCHEME)
+            .ok_or_else(|| "Not a Solana Pay URL (expected 'solana:')".to_string())?;
+        let (recipient, query) = rest.split_once('?').unwrap_or((rest, ""));
+        if recipient.starts_with("https") {
+            return Err(
+                "Solana Pay transaction requests need the merchant's server; only transfer \
+                 requests can be paid offline"
+                    .to_string(),
+            );
+        }
+        let recipient = parse_key(&percent_decode(recipient)?, "recipient")?;
+
+        let mut request = Self {
+            recipient,
+            amount: None,
+            spl_token: None,
+            references: Vec::new(),
+            label: None,
+            message: None,
+            memo: None,
+        };
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value)?;
+            let single = |slot: &mut Option<String>, value: String| {
+                if slot.replace(value).is_some() {
+                    return Err(format!("Duplicate '{}' parameter", key));
+                }
+                Ok(())
+            };
+            match key {
+                "amount" => {
+                    // Check the format now; the mint's decimals come later
+                    split_amount(&value)?;
+                    single(&mut request.amount, value)?
+                }
+                "spl-token" => single(&mut request.spl_token, parse_key(&value, "spl-token")?)?,
+                "reference" => request.references.push(parse_key(&value, "reference")?),
+                "label" => single(&mut request.label, value)?,
+                "message" => single(&mut request.message, value)?,
+                "memo" => single(&mut request.memo, value)?,
+                // Unknown parameters are ignored, as the spec allows
+                _ => {}
+            }
+        }
+        Ok(request)
+    }
+
+    /// Amount in the smallest unit (lamports, or token base units with the
+    /// mint's `decimals`); `None` if the URL leaves it to the payer
+    pub fn amount_in_base_units(&self, decimals: u8) -> Result<Option<u64>, String> {
+        self.amount
+            .as_deref()
+            .map(|amount| parse_amount(amount, decimals))
+            .transpose()
+    }
+}
+
+fn parse_key(value: &str, field: &str) -> Result<String, String> {
+    Pubkey::from_str(value)
+        .map(|key| key.to_string())
+        .map_err(|e| format!("Invalid {} '{}': {}", field, value, e))
+}
+
+/// Whole and fractional digits of a non-negative decimal ("1", "0.25")
+fn split_amount(value: &str) -> Result<(&str, &str), String> {
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if whole.is_empty()
+        || !whole.bytes().all(|b| b.is_ascii_digit())
+        || !fraction.bytes().all(|b| b.is_ascii_digit())
+        || (value.contains('.') && fraction.is_empty())
+    {
+        return Err(format!("Invalid amount '{}'", value));
+    }
+    Ok((whole, fraction))
+}
+
+/// Parse a non-negative decimal into base units with `decimals` places
+pub fn parse_amount(value: &str, decimals: u8) -> Result<u64, String> {
+    let invalid = || format!("Invalid amount '{}'", value);
+    let (whole, fraction) = split_amount(value)?;
+    if fraction.len() > decimals as usize {
+        return Err(format!(
+            "Amount '{}' has more than {} decimal places",
+            value, decimals
+        ));
+    }
+    let scale = 10u64.checked_pow(decimals as u32).ok_or_else(invalid)?;
+    let whole: u64 = whole.parse().map_err(|_| invalid())?;
+    let fraction: u64 = if fraction.is_empty() {
+        0
+    } else {
+        let padded = format!("{:0<width$}", fraction, width = decimals as usize);
+        padded.parse().map_err(|_| invalid())?
+    };
+    whole
+        .checked_mul(scale)
+        .and_then(|units| units.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
+/// Decode `%XX` escapes (and `+` as a space) in a URL component
+fn percent_decode(value: &str) -> Result<String, String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = value
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| format!("Invalid escape in '{}'", value))?;
+                decoded.push(hex);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| format!("'{}' is not UTF-8", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIPIENT: &str = "mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    #[test]
+    fn test_parse_transfer_requests() {
+        let sol = TransferRequest::parse(&format!(
+            "solana:{}?amount=1.5&label=Michael&message=Thanks%20for%20all%20the%20fish&memo=OrderId12345",
+            RECIPIENT
+        ))
+        .unwrap();
+        assert_eq!(sol.recipient, RECIPIENT);
+        assert_eq!(
+            sol.amount_in_base_units(SOL_DECIMALS).unwrap(),
+            Some(1_500_000_000)
+        );
+        assert_eq!(sol.message.as_deref(), Some("Thanks for all the fish"));
+        assert_eq!(sol.memo.as_deref(), Some("OrderId12345"));
+        assert_eq!(sol.spl_token, None);
+
+        let reference = Pubkey::new_unique().to_string();
+        let usdc = TransferRequest::parse(&format!(
+            "solana:{}?amount=0.01&spl-token={}&reference={}",
+            RECIPIENT, USDC, reference
+        ))
+        .unwrap();
+        assert_eq!(usdc.spl_token.as_deref(), Some(USDC));
+        assert_eq!(usdc.references, vec![reference]);
+        assert_eq!(usdc.amount_in_base_units(6).unwrap(), Some(10_000));
+        assert!(usdc.amount_in_base_units(1).is_err());
+
+        let open = TransferRequest::parse(&format!("solana:{}", RECIPIENT)).unwrap();
+        assert_eq!(open.amount_in_base_units(9).unwrap(), None);
+
+        assert!(TransferRequest::parse("solana:https%3A%2F%2Fexample.com").is_err());
+        assert!(TransferRequest::parse("solana:https://example.com/pay").is_err());
+        assert!(TransferRequest::parse(&format!("bitcoin:{}", RECIPIENT)).is_err());
+        for amount in ["-1", ".5", "1.", "1e3", "1,5"] {
+            assert!(
+                TransferRequest::parse(&format!("solana:{}?amount={}", RECIPIENT, amount)).is_err(),
+                "{}",
+                amount
+            );
+        }
+        assert!(
+            TransferRequest::parse(&format!("solana:{}?amount=1&amount=2", RECIPIENT)).is_err()
+        );
+    }
+}
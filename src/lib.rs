@@ -4,18 +4,34 @@
 //! over Bluetooth Low Energy (BLE) mesh networks, inspired by biological pollination.
 
 pub mod ble;
+pub mod events;
 pub mod intent;
 pub mod queue;
+pub mod role;
 pub mod storage;
 pub mod submission;
 pub mod util;
 
-#[cfg(feature = "android")]
+// Host-driven engine shared by every embedder; only the binding layers
+// (JNI, UniFFI, C API) inside it are feature-gated
+#[cfg(not(target_arch = "wasm32"))]
 pub mod ffi;
 
+#[cfg(feature = "python")]
+mod python;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
+
+pub use events::{AdapterState, SdkEvent};
+pub use role::DeviceRole;
+
 use std::sync::Arc;
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 
 /// Core PolliNet SDK instance
 pub struct PolliNetSDK {
@@ -23,13 +39,40 @@ pub struct PolliNetSDK {
     pub local_cache: Arc<RwLock<ble::fragmenter::TransactionCache>>,
     /// Queue manager for all queue operations
     queue_manager: Arc<queue::QueueManager>,
+    /// Mesh activity events (see [`PolliNetSDK::subscribe`])
+    events: broadcast::Sender<SdkEvent>,
+    /// Last adapter state reported by the host
+    adapter_state: parking_lot::Mutex<Option<AdapterState>>,
+    /// Background tasks aborted by [`PolliNetSDK::shutdown`]
+    background_tasks: parking_lot::Mutex<Vec<tokio::task::AbortHandle>>,
+    shut_down: std::sync::atomic::AtomicBool,
+    /// Compression for transactions accepted for relay
+    compressor: util::compression::Compressor,
+    /// Where `local_cache` is saved on shutdown (set by `from_config`)
+    cache_backend: Option<Arc<dyn storage::StorageBackend>>,
 }
 
 impl PolliNetSDK {
-    fn make_queue_manager(storage_dir: Option<&str>) -> Arc<queue::QueueManager> {
+    fn with_queue_manager(queue_manager: Arc<queue::QueueManager>) -> Self {
+        Self {
+            local_cache: Arc::new(RwLock::new(ble::fragmenter::TransactionCache::new())),
+            queue_manager,
+            events: broadcast::channel(events::SDK_EVENT_CHANNEL_CAPACITY).0,
+            adapter_state: parking_lot::Mutex::new(None),
+            background_tasks: parking_lot::Mutex::new(Vec::new()),
+            shut_down: std::sync::atomic::AtomicBool::new(false),
+            compressor: Default::default(),
+            cache_backend: None,
+        }
+    }
+
+    fn make_queue_manager(
+        storage_dir: Option<&str>,
+        store: queue::QueueStoreKind,
+    ) -> Arc<queue::QueueManager> {
         if let Some(dir) = storage_dir {
-            tracing::info!("Using persistent queue storage: {}", dir);
-            Arc::new(queue::QueueManager::with_storage(dir).unwrap_or_else(|e| {
+            tracing::info!("Using persistent queue storage: {} ({:?})", dir, store);
+            Arc::new(queue::QueueManager::open(dir, store).unwrap_or_else(|e| {
                 tracing::warn!("Failed to load queues from storage: {}, starting fresh", e);
                 queue::QueueManager::new()
             }))
@@ -41,18 +84,131 @@ impl PolliNetSDK {
 
     /// Initialize a new PolliNet SDK instance without RPC client
     pub async fn new() -> Result<Self, PolliNetError> {
-        Ok(Self {
-            local_cache: Arc::new(RwLock::new(ble::fragmenter::TransactionCache::new())),
-            queue_manager: Self::make_queue_manager(None),
-        })
+        Ok(Self::with_queue_manager(Self::make_queue_manager(
+            None,
+            Default::default(),
+        )))
+    }
+
+    /// Initialize a new PolliNet SDK instance whose queues persist under `storage_dir`
+    pub async fn new_with_storage(storage_dir: &str) -> Result<Self, PolliNetError> {
+        Ok(Self::with_queue_manager(Self::make_queue_manager(
+            Some(storage_dir),
+            Default::default(),
+        )))
+    }
+
+    /// Queue manager and reassembly-cache backend for `storage_dir`: with
+    /// `sqlite` both share `{storage_dir}/queues/queues.db`, otherwise queues
+    /// are JSON files and the cache is stored under `{storage_dir}/cache`
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_storage(
+        storage_dir: &str,
+        store: queue::QueueStoreKind,
+    ) -> (
+        Arc<queue::QueueManager>,
+        Option<Arc<dyn storage::StorageBackend>>,
+    ) {
+        let queue_dir = format!("{}/queues", storage_dir);
+
+        #[cfg(feature = "sqlite")]
+        if store == queue::QueueStoreKind::Sqlite {
+            let path = std::path::Path::new(&queue_dir).join(queue::QUEUE_DATABASE);
+            let backend: Arc<dyn storage::StorageBackend> =
+                match storage::SqliteBackend::open(&path) {
+                    Ok(backend) => Arc::new(backend),
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to open {}: {}, queues will not persist",
+                            path.display(),
+                            e
+                        );
+                        return (Arc::new(queue::QueueManager::new()), None);
+                    }
+                };
+            tracing::info!("Using persistent queue storage: {}", path.display());
+            let queue_manager =
+                queue::QueueManager::with_backend(backend.clone()).unwrap_or_else(|e| {
+                    tracing::warn!("Failed to load queues from storage: {}, starting fresh", e);
+                    queue::QueueManager::new()
+                });
+            return (Arc::new(queue_manager), Some(backend));
+        }
+
+        let cache_backend = match storage::FileBackend::open(format!("{}/cache", storage_dir)) {
+            Ok(backend) => Some(Arc::new(backend) as Arc<dyn storage::StorageBackend>),
+            Err(e) => {
+                tracing::warn!("Reassembly cache will not persist: {}", e);
+                None
+            }
+        };
+        (
+            Self::make_queue_manager(Some(&queue_dir), store),
+            cache_backend,
+        )
+    }
+
+    /// Initialize from an `SdkConfig` (e.g. loaded with `SdkConfig::from_file`):
+    /// queues persist under `{storageDirectory}/queues` (as JSON files or, with
+    /// `queue.store = "sqlite"`, one SQLite database) with the configured
+    /// limits and role, and partial reassemblies survive restarts. Host
+    /// transports built with `HostBleTransport::from_config` use this too.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn from_config(config: &ffi::types::SdkConfig) -> Result<Self, PolliNetError> {
+        let (queue_manager, cache_backend) = match &config.storage_directory {
+            Some(dir) => Self::open_storage(dir, config.queue.store),
+            None => (Self::make_queue_manager(None, Default::default()), None),
+        };
+        queue_manager.apply_limits(&config.queue).await;
+        queue_manager.set_role(config.role);
+        let mut sdk = Self::with_queue_manager(queue_manager);
+        sdk.compressor = util::compression::Compressor::new(config.compression.clone());
+        if let Some(backend) = cache_backend {
+            match ble::fragmenter::TransactionCache::load_from(backend.as_ref()) {
+                Ok(cache) => sdk.local_cache = Arc::new(RwLock::new(cache)),
+                Err(e) => tracing::warn!("Failed to load reassembly cache: {}, starting fresh", e),
+            }
+            sdk.cache_backend = Some(backend);
+        }
+        Ok(sdk)
     }
 
     /// Initialize a new PolliNet SDK instance (RPC URL param reserved for future use)
     pub async fn new_with_rpc(_rpc_url: &str) -> Result<Self, PolliNetError> {
-        Ok(Self {
-            local_cache: Arc::new(RwLock::new(ble::fragmenter::TransactionCache::new())),
-            queue_manager: Self::make_queue_manager(None),
-        })
+        Ok(Self::with_queue_manager(Self::make_queue_manager(
+            None,
+            Default::default(),
+        )))
+    }
+
+    // =========================================================================
+    // Events
+    // =========================================================================
+
+    /// Subscribe to mesh activity events
+    pub fn subscribe(&self) -> broadcast::Receiver<SdkEvent> {
+        self.events.subscribe()
+    }
+
+    /// Publish an event to subscribers. The host-driven transport calls this;
+    /// embedders driving the mesh themselves can too.
+    pub fn notify(&self, event: SdkEvent) {
+        // No subscribers is not an error
+        let _ = self.events.send(event);
+    }
+
+    /// Last adapter state reported by the host
+    pub fn adapter_state(&self) -> Option<AdapterState> {
+        *self.adapter_state.lock()
+    }
+
+    /// Record the radio adapter state reported by the host; publishes
+    /// `AdapterStateChanged` only when it differs from the last report
+    pub fn report_adapter_state(&self, state: AdapterState) {
+        let previous = self.adapter_state.lock().replace(state);
+        if previous != Some(state) {
+            self.notify(SdkEvent::AdapterStateChanged { state });
+        }
     }
 
     // =========================================================================
@@ -78,11 +234,96 @@ impl PolliNetSDK {
         self.queue_manager.get_metrics().await
     }
 
+    /// Compression totals for transactions accepted for relay
+    pub fn get_compression_stats(&self) -> util::compression::CompressionStats {
+        self.compressor.stats()
+    }
+
     /// Get queue health status
     pub async fn get_queue_health(&self) -> queue::HealthStatus {
         self.queue_manager.get_health().await
     }
 
+    /// Record a failed RPC submission in the retry queue
+    pub async fn record_submission_failure(
+        &self,
+        tx_bytes: Vec<u8>,
+        tx_id: String,
+        error: String,
+    ) -> Result<(), PolliNetError> {
+        self.queue_manager
+            .record_submission_failure(tx_bytes, tx_id, error)
+            .await
+            .map_err(|e| PolliNetError::Queue(e.to_string()))
+    }
+
+    /// Record a successful RPC submission: stops relaying the transaction and
+    /// queues a confirmation for the mesh
+    pub async fn record_submission_success(
+        &self,
+        tx_id: &str,
+        signature: String,
+    ) -> Result<(), PolliNetError> {
+        self.queue_manager
+            .record_submission_success(tx_id, signature)
+            .await
+            .map_err(|e| PolliNetError::Queue(e.to_string()))
+    }
+
+    /// Start the background task that expires stale queue entries and auto-saves.
+    /// Must be called from within a tokio runtime. The task is aborted by
+    /// [`PolliNetSDK::shutdown`].
+    pub fn start_queue_maintenance(
+        &self,
+        interval: std::time::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let task = self.queue_manager.spawn_maintenance(interval);
+        self.track_task(&task);
+        task
+    }
+
+    /// Have [`PolliNetSDK::shutdown`] abort `task`
+    pub(crate) fn track_task<T>(&self, task: &tokio::task::JoinHandle<T>) {
+        self.background_tasks.lock().push(task.abort_handle());
+    }
+
+    /// Stop background tasks and flush queues to storage.
+    ///
+    /// Radio advertising/scanning and peer connections are owned by the host;
+    /// hosts stop them before calling this so no new frames arrive mid-flush.
+    /// Idempotent: later calls return `Ok(())` without doing anything.
+    pub async fn shutdown(&self) -> Result<(), PolliNetError> {
+        use std::sync::atomic::Ordering;
+
+        if self.shut_down.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let tasks = std::mem::take(&mut *self.background_tasks.lock());
+        for task in &tasks {
+            task.abort();
+        }
+        tracing::info!("🛑 Aborted {} background task(s)", tasks.len());
+
+        self.queue_manager
+            .force_save()
+            .await
+            .map_err(|e| PolliNetError::Queue(format!("Failed to flush queues: {}", e)))?;
+        if let Some(backend) = &self.cache_backend {
+            // Partial reassemblies are disposable; losing them only costs a resend
+            if let Err(e) = self.local_cache.read().await.save_to(backend.as_ref()) {
+                tracing::warn!("Failed to save reassembly cache: {}", e);
+            }
+        }
+        tracing::info!("🛑 PolliNet SDK shut down");
+        Ok(())
+    }
+
+    /// Whether [`PolliNetSDK::shutdown`] has been called
+    pub fn is_shut_down(&self) -> bool {
+        self.shut_down.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
     /// Accept and queue a pre-signed transaction from external partners
     ///
     /// This method is designed for accepting transactions from external partners.
@@ -136,7 +377,7 @@ impl PolliNetSDK {
             .count();
 
         if valid_sigs == 0 {
-            return Err(PolliNetError::Serialization(
+            return Err(PolliNetError::InvalidSignature(
                 "Transaction must be signed before queuing for relay".to_string(),
             ));
         }
@@ -144,7 +385,7 @@ impl PolliNetSDK {
         // Verify transaction signatures
         if let Err(err) = tx.verify() {
             tracing::error!("❌ Transaction signature verification failed: {}", err);
-            return Err(PolliNetError::Serialization(format!(
+            return Err(PolliNetError::InvalidSignature(format!(
                 "Transaction signature verification failed: {}",
                 err
             )));
@@ -168,18 +409,17 @@ impl PolliNetSDK {
                 "Compressing transaction (threshold: {} bytes)",
                 crate::COMPRESSION_THRESHOLD
             );
-            // Create a temporary compressor to compress
-            let compressor = crate::util::lz::Lz4Compressor::new()
-                .map_err(|e| PolliNetError::Serialization(e.to_string()))?;
-            let compressed = compressor
-                .compress_with_size(&tx_bytes)
+            let compressed = self
+                .compressor
+                .compress(&tx_bytes)
                 .map_err(|e| PolliNetError::Serialization(e.to_string()))?;
             tracing::info!(
-                "Compressed: {} bytes -> {} bytes",
+                "Compressed with {:?}: {} bytes -> {} bytes",
+                compressed.algorithm,
                 tx_bytes.len(),
-                compressed.len()
+                compressed.bytes.len()
             );
-            compressed
+            compressed.bytes
         } else {
             tracing::info!("Transaction below compression threshold, keeping uncompressed");
             tx_bytes
@@ -213,11 +453,12 @@ impl PolliNetSDK {
         );
 
         // Add to outbound queue
-        let mut queue = self.queue_manager.outbound.write().await;
-        queue.push(outbound_tx).map_err(|e| {
-            PolliNetError::Serialization(format!("Failed to add transaction to queue: {}", e))
-        })?;
-        drop(queue);
+        self.queue_manager
+            .push_outbound(outbound_tx)
+            .await
+            .map_err(|e| {
+                PolliNetError::Serialization(format!("Failed to add transaction to queue: {}", e))
+            })?;
 
         tracing::info!("✅ External transaction queued for relay: {}", tx_id);
 
@@ -236,6 +477,12 @@ pub enum PolliNetError {
 
     #[error("Configuration error: {0}")]
     Configuration(String),
+
+    #[error("Queue error: {0}")]
+    Queue(String),
+
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
 }
 
 /// BLE MTU size for packet fragmentation
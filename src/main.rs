@@ -1,6 +1,29 @@
-//! PolliNet SDK - run `cargo run --example <name>` to try the examples
+//! `pollinet` — command-line node for desktop relays and gateways
+//!
+//! Runs the same engine as the mobile SDKs, configured from an `SdkConfig`
+//! file, so a laptop or kiosk can queue payments, inspect its queues and act as
+//! a gateway without writing Rust. See `pollinet help` for the commands.
+
+mod cli;
+
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    Ok(())
+async fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let invocation = match cli::Invocation::parse(&args) {
+        Ok(invocation) => invocation,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match cli::run(invocation).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,205 @@
+//! Python bindings for gateway scripting
+//!
+//! Built with the `python` feature (`maturin build --features python`). Wraps
+//! the SDK's transaction intake and queue APIs so operators can script
+//! gateways — watch a directory of collected transactions, submit them, export
+//! reports — without writing Rust. Structured results are JSON strings, as in
+//! the mobile FFI.
+
+// `#[pymethods]` expands `PyResult` returns into an `Into<PyErr>` call that
+// clippy flags on every method
+#![allow(clippy::useless_conversion)]
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::ble::fragmenter;
+use crate::ble::mesh::TransactionFragment;
+use crate::queue::{retry::RetryError, HealthStatus};
+use crate::PolliNetSDK;
+
+fn runtime_err(e: impl std::fmt::Display) -> PyErr {
+    PyRuntimeError::new_err(e.to_string())
+}
+
+/// Queue-backed gateway: accepts signed transactions and tracks their submission
+#[pyclass(module = "pollinet")]
+struct Gateway {
+    runtime: tokio::runtime::Runtime,
+    sdk: PolliNetSDK,
+}
+
+#[pymethods]
+impl Gateway {
+    /// Create a gateway; queues persist under `storage_dir` when given
+    #[new]
+    #[pyo3(signature = (storage_dir=None))]
+    fn new(storage_dir: Option<String>) -> PyResult<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(runtime_err)?;
+        let sdk = runtime
+            .block_on(async {
+                match &storage_dir {
+                    Some(dir) => PolliNetSDK::new_with_storage(dir).await,
+                    None => PolliNetSDK::new().await,
+                }
+            })
+            .map_err(runtime_err)?;
+        Ok(Self { runtime, sdk })
+    }
+
+    /// Verify, fragment and queue a base64 signed transaction; returns its tx id
+    #[pyo3(signature = (tx_base64, max_payload=None))]
+    fn accept_transaction(
+        &self,
+        py: Python<'_>,
+        tx_base64: &str,
+        max_payload: Option<usize>,
+    ) -> PyResult<String> {
+        py.allow_threads(|| {
+            self.runtime.block_on(
+                self.sdk
+                    .accept_and_queue_external_transaction(tx_base64, max_payload),
+            )
+        })
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Pop the next outbound transaction as `(tx_id, tx_bytes)`, or `None`
+    fn pop_outbound(&self, py: Python<'_>) -> Option<(String, Py<PyBytes>)> {
+        let tx = self
+            .runtime
+            .block_on(self.sdk.queue_manager().pop_outbound())?;
+        Some((
+            tx.tx_id,
+            PyBytes::new_bound(py, &tx.original_bytes).unbind(),
+        ))
+    }
+
+    /// Record a failed submission; returns `False` once retries are exhausted
+    /// (the transaction is then dead-lettered)
+    fn record_failure(&self, tx_bytes: Vec<u8>, tx_id: String, error: String) -> PyResult<bool> {
+        let result = self.runtime.block_on(
+            self.sdk
+                .queue_manager()
+                .record_submission_failure(tx_bytes, tx_id, error),
+        );
+        match result {
+            Ok(()) => Ok(true),
+            Err(RetryError::MaxRetriesExceeded { .. }) => Ok(false),
+            Err(e) => Err(runtime_err(e)),
+        }
+    }
+
+    /// Record a successful submission (stops relaying, queues a mesh confirmation)
+    fn record_success(&self, tx_id: &str, signature: String) -> PyResult<()> {
+        self.runtime
+            .block_on(self.sdk.record_submission_success(tx_id, signature))
+            .map_err(runtime_err)
+    }
+
+    /// Pop a retry whose backoff elapsed as `(tx_id, tx_bytes, attempts)`, or `None`
+    fn pop_ready_retry(&self, py: Python<'_>) -> Option<(String, Py<PyBytes>, usize)> {
+        let item = self
+            .runtime
+            .block_on(self.sdk.queue_manager().pop_ready_retry())?;
+        Some((
+            item.tx_id,
+            PyBytes::new_bound(py, &item.tx_bytes).unbind(),
+            item.attempt_count,
+        ))
+    }
+
+    /// Queue metrics as JSON
+    fn metrics(&self) -> PyResult<String> {
+        serde_json::to_string(&self.runtime.block_on(self.sdk.get_queue_metrics()))
+            .map_err(runtime_err)
+    }
+
+    /// Queue health as JSON `{"status": ..., "issues": [...]}`
+    fn health(&self) -> String {
+        let (status, issues) = match self.runtime.block_on(self.sdk.get_queue_health()) {
+            HealthStatus::Healthy => ("healthy", Vec::new()),
+            HealthStatus::Warning(issues) => ("warning", issues),
+            HealthStatus::Critical(issues) => ("critical", issues),
+        };
+        serde_json::json!({ "status": status, "issues": issues }).to_string()
+    }
+
+    /// Full queue snapshot as JSON, for reports
+    #[pyo3(signature = (include_payloads=false))]
+    fn snapshot(&self, include_payloads: bool) -> PyResult<String> {
+        let snapshot = self
+            .runtime
+            .block_on(self.sdk.queue_manager().snapshot(include_payloads));
+        serde_json::to_string(&snapshot).map_err(runtime_err)
+    }
+
+    /// Expire stale entries; returns the maintenance report as JSON
+    fn run_maintenance(&self) -> PyResult<String> {
+        let report = self
+            .runtime
+            .block_on(self.sdk.queue_manager().run_maintenance());
+        serde_json::to_string(&report).map_err(runtime_err)
+    }
+
+    /// Persist queues now (no-op without a storage directory)
+    fn save(&self) -> PyResult<()> {
+        self.runtime
+            .block_on(self.sdk.queue_manager().force_save())
+            .map_err(runtime_err)
+    }
+}
+
+/// Split a transaction into mesh wire frames
+#[pyfunction]
+#[pyo3(signature = (tx_bytes, max_payload=None))]
+fn fragment_transaction(
+    py: Python<'_>,
+    tx_bytes: &[u8],
+    max_payload: Option<usize>,
+) -> PyResult<Vec<Py<PyBytes>>> {
+    let fragments = match max_payload {
+        Some(max_payload) => {
+            fragmenter::fragment_transaction_with_max_payload(tx_bytes, max_payload)
+        }
+        None => fragmenter::fragment_transaction(tx_bytes),
+    };
+    Ok(fragments
+        .iter()
+        .map(|fragment| PyBytes::new_bound(py, &fragment.serialize()).unbind())
+        .collect())
+}
+
+/// Reassemble a transaction from mesh wire frames (any order)
+#[pyfunction]
+fn reassemble_transaction(py: Python<'_>, frames: Vec<Vec<u8>>) -> PyResult<Py<PyBytes>> {
+    let fragments = frames
+        .iter()
+        .map(|frame| TransactionFragment::deserialize(frame))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| PyValueError::new_err(format!("Invalid fragment: {}", e)))?;
+    let tx_bytes =
+        fragmenter::reconstruct_transaction(&fragments).map_err(PyValueError::new_err)?;
+    Ok(PyBytes::new_bound(py, &tx_bytes).unbind())
+}
+
+/// Transaction id (SHA-256 hex) as used by the queues and the mesh
+#[pyfunction]
+fn transaction_id(tx_bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(tx_bytes))
+}
+
+#[pymodule]
+fn pollinet(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add_class::<Gateway>()?;
+    m.add_function(wrap_pyfunction!(fragment_transaction, m)?)?;
+    m.add_function(wrap_pyfunction!(reassemble_transaction, m)?)?;
+    m.add_function(wrap_pyfunction!(transaction_id, m)?)?;
+    Ok(())
+}
@@ -5,7 +5,8 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
-use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::eviction::EvictionPolicy;
 
 /// Confirmation status
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,10 +45,7 @@ impl Confirmation {
 
     /// Create new confirmation with status
     pub fn new(original_tx_id: [u8; 32], status: ConfirmationStatus) -> Self {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let now = crate::util::clock::now_secs();
 
         Self {
             original_tx_id,
@@ -74,11 +72,7 @@ impl Confirmation {
 
     /// Get age in seconds
     pub fn age_seconds(&self) -> u64 {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        now.saturating_sub(self.timestamp)
+        crate::util::clock::secs_since(self.timestamp)
     }
 
     /// Check if confirmation is expired (older than TTL)
@@ -100,6 +94,10 @@ pub struct ConfirmationQueue {
     max_size: usize,
     /// Default TTL in seconds (1 hour)
     default_ttl: u64,
+    /// What to do when a push arrives while the queue is full
+    eviction_policy: EvictionPolicy,
+    /// Total number of confirmations evicted to make room
+    evicted_total: u64,
 }
 
 impl ConfirmationQueue {
@@ -110,11 +108,7 @@ impl ConfirmationQueue {
 
     /// Create new confirmation queue with specified capacity
     pub fn with_capacity(max_size: usize) -> Self {
-        Self {
-            pending: VecDeque::new(),
-            max_size,
-            default_ttl: 3600, // 1 hour
-        }
+        Self::with_ttl(max_size, 3600) // 1 hour
     }
 
     /// Create queue with custom TTL
@@ -123,15 +117,36 @@ impl ConfirmationQueue {
             pending: VecDeque::new(),
             max_size,
             default_ttl: ttl_seconds,
+            eviction_policy: EvictionPolicy::default(),
+            evicted_total: 0,
         }
     }
 
+    /// Change the capacity; a smaller one only affects later pushes
+    pub fn set_capacity(&mut self, max_size: usize) {
+        self.max_size = max_size;
+    }
+
+    /// Set the capacity eviction policy (`DropLowestPriority` behaves as `DropOldest`)
+    pub fn set_eviction_policy(&mut self, policy: EvictionPolicy) {
+        self.eviction_policy = policy;
+    }
+
+    /// Total number of confirmations evicted to make room since creation
+    pub fn evicted_total(&self) -> u64 {
+        self.evicted_total
+    }
+
     /// Push confirmation to queue
     pub fn push(&mut self, confirmation: Confirmation) -> Result<(), ConfirmationError> {
         // Check queue size
         if self.pending.len() >= self.max_size {
+            if self.eviction_policy == EvictionPolicy::Reject {
+                return Err(ConfirmationError::QueueFull(self.max_size));
+            }
             // Try to make room by removing oldest confirmation
             if let Some(dropped) = self.pending.pop_front() {
+                self.evicted_total += 1;
                 tracing::warn!(
                     "Confirmation queue full ({}), dropped oldest confirmation for tx {}",
                     self.max_size,
@@ -183,6 +198,11 @@ impl ConfirmationQueue {
         self.pending.front()
     }
 
+    /// Iterate pending confirmations in FIFO order
+    pub(crate) fn confirmations(&self) -> impl Iterator<Item = &Confirmation> {
+        self.pending.iter()
+    }
+
     /// Get queue length
     pub fn len(&self) -> usize {
         self.pending.len()
@@ -279,6 +299,9 @@ pub enum ConfirmationError {
 
     #[error("Confirmation expired (age: {age}s, TTL: {ttl}s)")]
     Expired { age: u64, ttl: u64 },
+
+    #[error("Invalid transaction ID (expected 32-byte hex): {0}")]
+    InvalidTxId(String),
 }
 
 #[cfg(test)]
@@ -360,6 +383,21 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_queue_full_reject_policy() {
+        let mut queue = ConfirmationQueue::with_capacity(1);
+        queue.set_eviction_policy(EvictionPolicy::Reject);
+
+        queue
+            .push(Confirmation::success([1u8; 32], "sig1".to_string()))
+            .unwrap();
+        assert!(matches!(
+            queue.push(Confirmation::success([2u8; 32], "sig2".to_string())),
+            Err(ConfirmationError::QueueFull(1))
+        ));
+        assert_eq!(queue.evicted_total(), 0);
+    }
+
     #[test]
     fn test_queue_full_drops_oldest() {
         let mut queue = ConfirmationQueue::with_capacity(2);
@@ -436,11 +474,7 @@ mod tests {
         assert_eq!(conf.age_seconds(), 0);
 
         // Manually set old timestamp
-        conf.timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
-            - 100;
+        conf.timestamp = crate::util::clock::now_secs() - 100;
 
         assert!(conf.age_seconds() >= 100);
         assert!(conf.is_expired(50));
@@ -0,0 +1,201 @@
+//! Dead-Letter Queue
+//!
+//! Holds submissions that exhausted their retry budget (or aged out of the retry
+//! queue) so they are not silently lost. Operators can inspect the entries and
+//! requeue them for another round of retries.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use super::retry::RetryItem;
+
+/// Default dead-letter capacity
+pub const DEFAULT_DEAD_LETTER_CAPACITY: usize = 200;
+
+/// Why an item was dead-lettered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeadLetterReason {
+    /// Retry attempts exhausted
+    MaxRetriesExceeded,
+    /// Item exceeded the retry queue's max age
+    Expired,
+}
+
+/// A failed submission parked for operator inspection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    /// Transaction ID (SHA-256 hash as hex string)
+    pub tx_id: String,
+    /// Signed transaction bytes
+    pub tx_bytes: Vec<u8>,
+    /// Attempts made before giving up
+    pub attempts: usize,
+    /// Error message from the last failure
+    pub last_error: String,
+    /// Why it was dead-lettered
+    pub reason: DeadLetterReason,
+    /// Unix timestamp when the transaction first failed
+    pub first_failed_at: u64,
+    /// Unix timestamp when it was dead-lettered
+    pub dead_lettered_at: u64,
+}
+
+impl DeadLetter {
+    /// Build a dead letter from a retry item
+    pub fn from_retry_item(item: RetryItem, reason: DeadLetterReason) -> Self {
+        let now = crate::util::clock::now_secs();
+
+        Self {
+            tx_id: item.tx_id,
+            tx_bytes: item.tx_bytes,
+            attempts: item.attempt_count,
+            last_error: item.last_error,
+            reason,
+            first_failed_at: item.created_at_unix,
+            dead_lettered_at: now,
+        }
+    }
+
+    /// Turn back into a fresh retry item (attempt count reset)
+    pub fn into_retry_item(self) -> RetryItem {
+        RetryItem::new(self.tx_bytes, self.tx_id, self.last_error)
+    }
+}
+
+/// Bounded FIFO of dead letters (oldest dropped when full)
+#[derive(Debug, Clone)]
+pub struct DeadLetterQueue {
+    items: VecDeque<DeadLetter>,
+    max_size: usize,
+}
+
+impl DeadLetterQueue {
+    /// Create with default capacity
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_DEAD_LETTER_CAPACITY)
+    }
+
+    /// Create with a specific capacity
+    pub fn with_capacity(max_size: usize) -> Self {
+        Self {
+            items: VecDeque::new(),
+            max_size,
+        }
+    }
+
+    /// Add a dead letter, replacing any existing entry for the same tx
+    pub fn push(&mut self, letter: DeadLetter) {
+        self.items.retain(|l| l.tx_id != letter.tx_id);
+        if self.items.len() >= self.max_size {
+            if let Some(dropped) = self.items.pop_front() {
+                tracing::warn!(
+                    "Dead-letter queue full ({}), dropped oldest tx {}",
+                    self.max_size,
+                    dropped.tx_id.chars().take(8).collect::<String>()
+                );
+            }
+        }
+        tracing::warn!(
+            "☠️ Dead-lettered tx {} after {} attempts ({:?}): {}",
+            letter.tx_id.chars().take(8).collect::<String>(),
+            letter.attempts,
+            letter.reason,
+            letter.last_error
+        );
+        self.items.push_back(letter);
+    }
+
+    /// Re-add a persisted entry on load (no logging, capacity still enforced)
+    pub(crate) fn restore(&mut self, letter: DeadLetter) {
+        if self.items.len() >= self.max_size {
+            self.items.pop_front();
+        }
+        self.items.push_back(letter);
+    }
+
+    /// Remove and return the entry for `tx_id`
+    pub fn remove(&mut self, tx_id: &str) -> Option<DeadLetter> {
+        let pos = self.items.iter().position(|l| l.tx_id == tx_id)?;
+        self.items.remove(pos)
+    }
+
+    /// Remove and return every entry matching `pred`
+    pub fn remove_where(&mut self, mut pred: impl FnMut(&DeadLetter) -> bool) -> Vec<DeadLetter> {
+        let mut removed = Vec::new();
+        self.items.retain(|letter| {
+            if pred(letter) {
+                removed.push(letter.clone());
+                false
+            } else {
+                true
+            }
+        });
+        removed
+    }
+
+    /// Look up an entry without removing it
+    pub fn get(&self, tx_id: &str) -> Option<&DeadLetter> {
+        self.items.iter().find(|l| l.tx_id == tx_id)
+    }
+
+    /// Iterate entries (oldest first)
+    pub fn iter(&self) -> impl Iterator<Item = &DeadLetter> {
+        self.items.iter()
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check if empty
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Remove all entries
+    pub fn clear(&mut self) {
+        self.items.clear();
+        tracing::info!("Cleared dead-letter queue");
+    }
+}
+
+impl Default for DeadLetterQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letter(id: &str) -> DeadLetter {
+        let item = RetryItem::new(vec![1, 2], id.to_string(), "rpc down".to_string());
+        DeadLetter::from_retry_item(item, DeadLetterReason::MaxRetriesExceeded)
+    }
+
+    #[test]
+    fn test_push_dedup_and_capacity() {
+        let mut dlq = DeadLetterQueue::with_capacity(2);
+        dlq.push(letter("a"));
+        dlq.push(letter("a"));
+        assert_eq!(dlq.len(), 1);
+
+        dlq.push(letter("b"));
+        dlq.push(letter("c"));
+        assert_eq!(dlq.len(), 2);
+        assert!(dlq.get("a").is_none());
+    }
+
+    #[test]
+    fn test_remove_into_retry_item() {
+        let mut dlq = DeadLetterQueue::new();
+        dlq.push(letter("a"));
+
+        let item = dlq.remove("a").unwrap().into_retry_item();
+        assert_eq!(item.tx_id, "a");
+        assert_eq!(item.attempt_count, 0);
+        assert!(dlq.is_empty());
+    }
+}
@@ -0,0 +1,34 @@
+//! Queue Change Notifications
+//!
+//! `QueueManager` publishes a [`QueueEvent`] on a broadcast channel whenever an
+//! item enters or leaves a queue through its API, so hosts can refresh UI state
+//! without polling `get_metrics`.
+
+use serde::{Deserialize, Serialize};
+
+use super::outbound::Priority;
+
+/// Capacity of the event channel. Slow subscribers lag (and skip events)
+/// instead of blocking queue operations.
+pub const QUEUE_EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Queue change event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum QueueEvent {
+    /// Transaction added to the outbound queue
+    #[serde(rename_all = "camelCase")]
+    Enqueued { tx_id: String, priority: Priority },
+    /// Transaction removed from the outbound queue for transmission
+    #[serde(rename_all = "camelCase")]
+    Dequeued { tx_id: String },
+    /// Failed submission scheduled for another attempt
+    #[serde(rename_all = "camelCase")]
+    RetryScheduled { tx_id: String, attempt: usize },
+    /// Transaction gave up after exhausting its retry budget
+    #[serde(rename_all = "camelCase")]
+    RetryExhausted { tx_id: String, attempts: usize },
+    /// Submission confirmed; transaction removed from the outbound queue
+    #[serde(rename_all = "camelCase")]
+    ConfirmationMatched { tx_id: String },
+}
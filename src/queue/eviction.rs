@@ -0,0 +1,20 @@
+//! Capacity Eviction Policies
+//!
+//! What a bounded queue does when a push arrives while it is full.
+
+use serde::{Deserialize, Serialize};
+
+/// Behaviour of a full queue on push
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionPolicy {
+    /// Refuse the new item (`QueueFull`)
+    Reject,
+    /// Evict the oldest item regardless of priority
+    DropOldest,
+    /// Evict the oldest item of the lowest priority below the incoming one (LOW
+    /// items are always evictable); reject if there is none. Queues without
+    /// priorities treat this as `DropOldest`.
+    #[default]
+    DropLowestPriority,
+}
@@ -3,10 +3,10 @@
 //! Priority-based queue for transactions awaiting BLE transmission.
 //! Supports HIGH, NORMAL, and LOW priority with deduplication.
 
+use super::eviction::EvictionPolicy;
 use crate::ble::mesh::TransactionFragment;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashSet, VecDeque};
-use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Transaction priority levels
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -73,10 +73,7 @@ impl OutboundTransaction {
         fragments: Vec<TransactionFragment>,
         priority: Priority,
     ) -> Self {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let now = crate::util::clock::now_secs();
 
         Self {
             tx_id,
@@ -106,14 +103,39 @@ impl OutboundTransaction {
 
     /// Get age in seconds
     pub fn age_seconds(&self) -> u64 {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        now.saturating_sub(self.created_at)
+        crate::util::clock::secs_since(self.created_at)
+    }
+
+    /// Unix timestamp after which this transaction is no longer relayed
+    pub fn expires_at(&self) -> u64 {
+        self.created_at.saturating_add(self.ttl_secs)
+    }
+
+    /// Check if the TTL has elapsed at unix time `now`
+    pub fn is_expired_at(&self, now: u64) -> bool {
+        now >= self.expires_at()
+    }
+
+    /// Bytes this transaction occupies on the wire: serialized fragments
+    /// (38-byte header each) or the raw bytes if not fragmented yet
+    pub fn wire_size(&self) -> usize {
+        if self.fragments.is_empty() {
+            self.original_bytes.len()
+        } else {
+            self.fragments
+                .iter()
+                .map(|f| FRAGMENT_HEADER_BYTES + f.data.len())
+                .sum()
+        }
     }
 }
 
+/// Serialized TransactionFragment header: tx id (32) + index (2) + total (2) + len (2)
+const FRAGMENT_HEADER_BYTES: usize = 38;
+
+/// Maximum number of expired transactions kept for inspection
+const MAX_EXPIRED_RETAINED: usize = 100;
+
 /// Priority-based outbound queue with deduplication
 pub struct OutboundQueue {
     /// High priority queue (user-initiated)
@@ -126,6 +148,14 @@ pub struct OutboundQueue {
     deduplication_set: HashSet<String>,
     /// Maximum queue size (across all priorities)
     max_size: usize,
+    /// Dead-letter list of transactions swept after their TTL elapsed (bounded)
+    expired: VecDeque<OutboundTransaction>,
+    /// Total number of transactions expired since creation
+    expired_total: u64,
+    /// What to do when a push arrives while the queue is full
+    eviction_policy: EvictionPolicy,
+    /// Total number of transactions evicted to make room
+    evicted_total: u64,
 }
 
 impl OutboundQueue {
@@ -142,30 +172,82 @@ impl OutboundQueue {
             low_priority: VecDeque::new(),
             deduplication_set: HashSet::new(),
             max_size,
+            expired: VecDeque::new(),
+            expired_total: 0,
+            eviction_policy: EvictionPolicy::default(),
+            evicted_total: 0,
+        }
+    }
+
+    /// Change the capacity; a smaller one only affects later pushes
+    pub fn set_capacity(&mut self, max_size: usize) {
+        self.max_size = max_size;
+    }
+
+    /// Set the capacity eviction policy
+    pub fn set_eviction_policy(&mut self, policy: EvictionPolicy) {
+        self.eviction_policy = policy;
+    }
+
+    /// Current capacity eviction policy
+    pub fn eviction_policy(&self) -> EvictionPolicy {
+        self.eviction_policy
+    }
+
+    /// Total number of transactions evicted to make room since creation
+    pub fn evicted_total(&self) -> u64 {
+        self.evicted_total
+    }
+
+    /// Pick the lane to evict from under the current policy (None = reject)
+    fn eviction_lane(&self, incoming: Priority) -> Option<Priority> {
+        match self.eviction_policy {
+            EvictionPolicy::Reject => None,
+            EvictionPolicy::DropOldest => [
+                (Priority::Low, &self.low_priority),
+                (Priority::Normal, &self.normal_priority),
+                (Priority::High, &self.high_priority),
+            ]
+            .into_iter()
+            .filter_map(|(p, lane)| lane.front().map(|tx| (p, tx.created_at)))
+            .min_by_key(|(_, created_at)| *created_at)
+            .map(|(p, _)| p),
+            EvictionPolicy::DropLowestPriority => [Priority::Low, Priority::Normal]
+                .into_iter()
+                .filter(|p| *p == Priority::Low || (*p as u8) < (incoming as u8))
+                .find(|p| self.len_priority(*p) > 0),
         }
     }
 
-    /// Push transaction to queue (returns error if duplicate or queue full)
-    pub fn push(&mut self, tx: OutboundTransaction) -> Result<(), QueueError> {
+    /// Push transaction to queue; returns the ids evicted to make room
+    /// (error if duplicate or queue full)
+    pub fn push(&mut self, tx: OutboundTransaction) -> Result<Vec<String>, QueueError> {
         // Check for duplicates
         if self.deduplication_set.contains(&tx.tx_id) {
             return Err(QueueError::Duplicate(tx.tx_id));
         }
 
         // Check queue size
+        let mut evicted = Vec::new();
         if self.len() >= self.max_size {
-            // Try to make room by dropping oldest low priority transaction
-            if !self.low_priority.is_empty() {
-                if let Some(dropped) = self.low_priority.pop_front() {
-                    self.deduplication_set.remove(&dropped.tx_id);
-                    tracing::warn!(
-                        "Queue full ({}), dropped low priority tx: {}",
-                        self.max_size,
-                        dropped.tx_id
-                    );
-                }
-            } else {
-                return Err(QueueError::QueueFull(self.max_size));
+            // Try to make room according to the eviction policy
+            let lane = match self.eviction_lane(tx.priority) {
+                Some(Priority::High) => &mut self.high_priority,
+                Some(Priority::Normal) => &mut self.normal_priority,
+                Some(Priority::Low) => &mut self.low_priority,
+                None => return Err(QueueError::QueueFull(self.max_size)),
+            };
+            if let Some(dropped) = lane.pop_front() {
+                self.deduplication_set.remove(&dropped.tx_id);
+                self.evicted_total += 1;
+                tracing::warn!(
+                    "Queue full ({}), evicted {:?} priority tx: {} ({:?})",
+                    self.max_size,
+                    dropped.priority,
+                    dropped.tx_id,
+                    self.eviction_policy
+                );
+                evicted.push(dropped.tx_id);
             }
         }
 
@@ -190,7 +272,7 @@ impl OutboundQueue {
             self.len()
         );
 
-        Ok(())
+        Ok(evicted)
     }
 
     /// Pop next transaction (priority-based: HIGH → NORMAL → LOW)
@@ -216,6 +298,95 @@ impl OutboundQueue {
         tx
     }
 
+    /// Pop the next transaction from the highest priority lane that `allow` accepts
+    ///
+    /// `allow` is only consulted for non-empty lanes, so rate limiters can count a
+    /// rejection as a throttled send.
+    pub fn pop_where(
+        &mut self,
+        mut allow: impl FnMut(Priority) -> bool,
+    ) -> Option<OutboundTransaction> {
+        let lanes = [
+            (Priority::High, &mut self.high_priority),
+            (Priority::Normal, &mut self.normal_priority),
+            (Priority::Low, &mut self.low_priority),
+        ];
+
+        for (priority, lane) in lanes {
+            if lane.is_empty() || !allow(priority) {
+                continue;
+            }
+            let tx = lane.pop_front()?;
+            self.deduplication_set.remove(&tx.tx_id);
+            return Some(tx);
+        }
+
+        None
+    }
+
+    /// Dequeue as many transactions as fit in `max_bytes` of `wire_size`, in
+    /// priority order (HIGH → NORMAL → LOW, FIFO within a priority)
+    ///
+    /// Stops at the first transaction that doesn't fit, so a smaller low-priority
+    /// item never jumps ahead of a larger high-priority one. A single transaction
+    /// larger than `max_bytes` is still returned on its own when the batch is empty.
+    pub fn dequeue_batch(&mut self, max_bytes: usize) -> Vec<OutboundTransaction> {
+        self.dequeue_batch_where(max_bytes, |_| true)
+    }
+
+    /// Like [`dequeue_batch`](Self::dequeue_batch), but a priority is skipped for the
+    /// rest of the batch once `allow` rejects it (e.g. rate limiting)
+    pub fn dequeue_batch_where(
+        &mut self,
+        max_bytes: usize,
+        mut allow: impl FnMut(Priority) -> bool,
+    ) -> Vec<OutboundTransaction> {
+        let mut batch = Vec::new();
+        let mut used = 0usize;
+        let mut blocked = [false; 3];
+
+        loop {
+            let next = [Priority::High, Priority::Normal, Priority::Low]
+                .into_iter()
+                .enumerate()
+                .find(|(i, p)| !blocked[*i] && self.len_priority(*p) > 0);
+            let Some((slot, priority)) = next else {
+                break;
+            };
+
+            let lane = match priority {
+                Priority::High => &mut self.high_priority,
+                Priority::Normal => &mut self.normal_priority,
+                Priority::Low => &mut self.low_priority,
+            };
+            let size = lane.front().map_or(0, |tx| tx.wire_size());
+            if !batch.is_empty() && used + size > max_bytes {
+                break;
+            }
+            if !allow(priority) {
+                blocked[slot] = true;
+                continue;
+            }
+
+            if let Some(tx) = lane.pop_front() {
+                self.deduplication_set.remove(&tx.tx_id);
+                used += size;
+                batch.push(tx);
+            }
+        }
+
+        if !batch.is_empty() {
+            tracing::debug!(
+                "Dequeued batch of {} transactions ({} bytes, remaining: {})",
+                batch.len(),
+                used,
+                self.len()
+            );
+        }
+
+        batch
+    }
+
     /// Check if transaction exists in queue
     pub fn contains(&self, tx_id: &str) -> bool {
         self.deduplication_set.contains(tx_id)
@@ -302,6 +473,55 @@ impl OutboundQueue {
         removed_count
     }
 
+    /// Move transactions whose TTL has elapsed to the expired list.
+    /// Returns the number of transactions swept.
+    pub fn sweep_expired(&mut self) -> usize {
+        let now = crate::util::clock::now_secs();
+
+        let mut swept = Vec::new();
+        for lane in [
+            &mut self.high_priority,
+            &mut self.normal_priority,
+            &mut self.low_priority,
+        ] {
+            let (expired, live): (VecDeque<_>, VecDeque<_>) =
+                lane.drain(..).partition(|tx| tx.is_expired_at(now));
+            *lane = live;
+            swept.extend(expired);
+        }
+
+        let count = swept.len();
+        for tx in swept {
+            self.deduplication_set.remove(&tx.tx_id);
+            tracing::debug!("TTL elapsed — moved tx {} to expired list", tx.tx_id);
+            if self.expired.len() >= MAX_EXPIRED_RETAINED {
+                self.expired.pop_front();
+            }
+            self.expired.push_back(tx);
+        }
+        self.expired_total += count as u64;
+
+        if count > 0 {
+            tracing::info!("Swept {} expired outbound transactions", count);
+        }
+        count
+    }
+
+    /// Transactions swept by `sweep_expired` (oldest first, bounded)
+    pub fn expired(&self) -> impl Iterator<Item = &OutboundTransaction> {
+        self.expired.iter()
+    }
+
+    /// Drain the expired list
+    pub fn take_expired(&mut self) -> Vec<OutboundTransaction> {
+        self.expired.drain(..).collect()
+    }
+
+    /// Total number of transactions expired since creation
+    pub fn expired_total(&self) -> u64 {
+        self.expired_total
+    }
+
     /// Get statistics about queue contents
     pub fn stats(&self) -> QueueStats {
         QueueStats {
@@ -310,6 +530,8 @@ impl OutboundQueue {
             normal_priority: self.normal_priority.len(),
             low_priority: self.low_priority.len(),
             oldest_age_seconds: self.get_oldest_age_seconds(),
+            expired_total: self.expired_total,
+            evicted_total: self.evicted_total,
         }
     }
 
@@ -376,10 +598,7 @@ impl OutboundQueue {
     /// Filters out: already delivered to this peer, zero relevance, TTL expired.
     /// Sorted by: priority (high→low), then relevance (high→low), then age (oldest first).
     pub fn outbound_for_peer(&self, peer_id: &[u8; 4]) -> Vec<&OutboundTransaction> {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let now = crate::util::clock::now_secs();
         let mut result: Vec<&OutboundTransaction> = self
             .high_priority
             .iter()
@@ -446,6 +665,32 @@ impl OutboundQueue {
         false
     }
 
+    /// Remove every entry matching `pred` (all priorities). Returns the removed entries.
+    pub fn purge_where(
+        &mut self,
+        mut pred: impl FnMut(&OutboundTransaction) -> bool,
+    ) -> Vec<OutboundTransaction> {
+        let mut removed = Vec::new();
+        for lane in [
+            &mut self.high_priority,
+            &mut self.normal_priority,
+            &mut self.low_priority,
+        ] {
+            let mut i = 0;
+            while i < lane.len() {
+                if pred(&lane[i]) {
+                    if let Some(tx) = lane.remove(i) {
+                        self.deduplication_set.remove(&tx.tx_id);
+                        removed.push(tx);
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+        }
+        removed
+    }
+
     /// Expire cooldown for all peers not in the given delivered_to set.
     /// Called when a new high-priority entry arrives (Subsystem 1/2 integration).
     /// Returns the list of peer IDs whose cooldowns were cleared.
@@ -479,6 +724,8 @@ pub struct QueueStats {
     pub normal_priority: usize,
     pub low_priority: usize,
     pub oldest_age_seconds: Option<u64>,
+    pub expired_total: u64,
+    pub evicted_total: u64,
 }
 
 /// Queue operation errors
@@ -522,6 +769,42 @@ mod tests {
         assert!(queue.is_empty());
     }
 
+    #[test]
+    fn test_pop_where_skips_rejected_lanes() {
+        let mut queue = OutboundQueue::new();
+        queue.push(create_test_tx("high", Priority::High)).unwrap();
+        queue.push(create_test_tx("low", Priority::Low)).unwrap();
+
+        let popped = queue.pop_where(|p| p != Priority::High).unwrap();
+        assert_eq!(popped.tx_id, "low");
+        assert!(queue.pop_where(|p| p != Priority::High).is_none());
+        assert!(queue.contains("high"));
+    }
+
+    #[test]
+    fn test_dequeue_batch_respects_size_and_priority() {
+        let mut queue = OutboundQueue::new();
+        let sized = |id: &str, len: usize, priority| {
+            OutboundTransaction::new(id.to_string(), vec![0; len], vec![], priority)
+        };
+        queue.push(sized("low", 10, Priority::Low)).unwrap();
+        queue.push(sized("high", 60, Priority::High)).unwrap();
+        queue.push(sized("normal", 50, Priority::Normal)).unwrap();
+
+        let batch = queue.dequeue_batch(100);
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].tx_id, "high");
+
+        let batch = queue.dequeue_batch(100);
+        let ids: Vec<_> = batch.iter().map(|tx| tx.tx_id.as_str()).collect();
+        assert_eq!(ids, ["normal", "low"]);
+        assert!(queue.is_empty());
+
+        // Oversized item is still returned alone
+        queue.push(sized("big", 500, Priority::Normal)).unwrap();
+        assert_eq!(queue.dequeue_batch(100).len(), 1);
+    }
+
     #[test]
     fn test_priority_ordering() {
         let mut queue = OutboundQueue::new();
@@ -588,6 +871,38 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_eviction_policies() {
+        let full_queue = |policy| {
+            let mut queue = OutboundQueue::with_capacity(2);
+            queue.set_eviction_policy(policy);
+            queue
+                .push(create_test_tx("normal1", Priority::Normal))
+                .unwrap();
+            queue.push(create_test_tx("high1", Priority::High)).unwrap();
+            queue
+        };
+
+        let mut queue = full_queue(EvictionPolicy::Reject);
+        assert!(matches!(
+            queue.push(create_test_tx("high2", Priority::High)),
+            Err(QueueError::QueueFull(2))
+        ));
+        assert_eq!(queue.evicted_total(), 0);
+
+        let mut queue = full_queue(EvictionPolicy::DropLowestPriority);
+        assert!(queue.push(create_test_tx("low1", Priority::Low)).is_err());
+        queue.push(create_test_tx("high2", Priority::High)).unwrap();
+        assert!(!queue.contains("normal1"));
+        assert_eq!(queue.evicted_total(), 1);
+
+        let mut queue = full_queue(EvictionPolicy::DropOldest);
+        queue.push(create_test_tx("low1", Priority::Low)).unwrap();
+        assert_eq!(queue.len(), 2);
+        assert!(queue.contains("low1"));
+        assert_eq!(queue.stats().evicted_total, 1);
+    }
+
     #[test]
     fn test_peek() {
         let mut queue = OutboundQueue::new();
@@ -670,11 +985,7 @@ mod tests {
         assert_eq!(tx.age_seconds(), 0); // Just created
 
         let mut old_tx = create_test_tx("tx2", Priority::Normal);
-        old_tx.created_at = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
-            - 100;
+        old_tx.created_at = crate::util::clock::now_secs() - 100;
         assert!(old_tx.age_seconds() >= 100);
     }
 
@@ -694,4 +1005,25 @@ mod tests {
         assert_eq!(tx.retry_count, 3);
         assert!(tx.has_exceeded_retries());
     }
+
+    #[test]
+    fn test_sweep_expired() {
+        let mut queue = OutboundQueue::new();
+
+        let mut stale = create_test_tx("stale", Priority::High);
+        stale.created_at -= stale.ttl_secs + 1;
+        queue.push(stale).unwrap();
+        queue.push(create_test_tx("fresh", Priority::Low)).unwrap();
+
+        assert_eq!(queue.sweep_expired(), 1);
+        assert_eq!(queue.len(), 1);
+        assert!(!queue.contains("stale"));
+        assert_eq!(queue.expired().next().unwrap().tx_id, "stale");
+        assert_eq!(queue.stats().expired_total, 1);
+
+        // Expired id can be queued again
+        queue.push(create_test_tx("stale", Priority::High)).unwrap();
+        assert_eq!(queue.take_expired().len(), 1);
+        assert_eq!(queue.expired().count(), 0);
+    }
 }
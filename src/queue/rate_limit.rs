@@ -0,0 +1,194 @@
+//! Outbound Rate Limiting
+//!
+//! Token buckets (one per priority class) that cap how fast a device drains its
+//! outbound queue onto the mesh. A throttled priority is skipped, so lower
+//! priorities can still use their own budget.
+
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use super::outbound::Priority;
+
+/// Token bucket parameters for one priority class
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BucketConfig {
+    /// Maximum burst size (tokens)
+    pub capacity: u32,
+    /// Tokens added per second
+    pub refill_per_sec: f64,
+}
+
+/// Per-priority rate limit configuration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    pub high: BucketConfig,
+    pub normal: BucketConfig,
+    pub low: BucketConfig,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            high: BucketConfig {
+                capacity: 20,
+                refill_per_sec: 10.0,
+            },
+            normal: BucketConfig {
+                capacity: 10,
+                refill_per_sec: 5.0,
+            },
+            low: BucketConfig {
+                capacity: 5,
+                refill_per_sec: 1.0,
+            },
+        }
+    }
+}
+
+/// Classic token bucket
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    config: BucketConfig,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Create a full bucket
+    pub fn new(config: BucketConfig) -> Self {
+        Self {
+            config,
+            tokens: config.capacity as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * self.config.refill_per_sec).min(self.config.capacity as f64);
+        self.last_refill = now;
+    }
+
+    /// Take one token if available
+    pub fn try_acquire(&mut self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Tokens currently available (after refill)
+    pub fn available(&mut self) -> u32 {
+        self.refill(Instant::now());
+        self.tokens as u32
+    }
+}
+
+/// Rate limiter for the outbound queue
+#[derive(Debug, Clone)]
+pub struct OutboundRateLimiter {
+    high: TokenBucket,
+    normal: TokenBucket,
+    low: TokenBucket,
+    /// Throttled sends per priority (high, normal, low)
+    throttled: [u64; 3],
+}
+
+impl OutboundRateLimiter {
+    /// Create a limiter with full buckets
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            high: TokenBucket::new(config.high),
+            normal: TokenBucket::new(config.normal),
+            low: TokenBucket::new(config.low),
+            throttled: [0; 3],
+        }
+    }
+
+    fn slot(priority: Priority) -> usize {
+        match priority {
+            Priority::High => 0,
+            Priority::Normal => 1,
+            Priority::Low => 2,
+        }
+    }
+
+    /// Take a send token for `priority`; counts a throttled send if none is left
+    pub fn try_acquire(&mut self, priority: Priority) -> bool {
+        let bucket = match priority {
+            Priority::High => &mut self.high,
+            Priority::Normal => &mut self.normal,
+            Priority::Low => &mut self.low,
+        };
+
+        if bucket.try_acquire() {
+            true
+        } else {
+            self.throttled[Self::slot(priority)] += 1;
+            tracing::debug!("🚦 Outbound {:?} send throttled", priority);
+            false
+        }
+    }
+
+    /// Throttled sends for one priority
+    pub fn throttled(&self, priority: Priority) -> u64 {
+        self.throttled[Self::slot(priority)]
+    }
+
+    /// Throttled sends across all priorities
+    pub fn throttled_total(&self) -> u64 {
+        self.throttled.iter().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_token_bucket_refill() {
+        let mut bucket = TokenBucket::new(BucketConfig {
+            capacity: 2,
+            refill_per_sec: 1.0,
+        });
+        let start = bucket.last_refill;
+
+        assert!(bucket.try_acquire_at(start));
+        assert!(bucket.try_acquire_at(start));
+        assert!(!bucket.try_acquire_at(start));
+        assert!(bucket.try_acquire_at(start + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_limiter_counts_throttled() {
+        let empty = BucketConfig {
+            capacity: 0,
+            refill_per_sec: 0.0,
+        };
+        let mut limiter = OutboundRateLimiter::new(RateLimitConfig {
+            high: BucketConfig {
+                capacity: 1,
+                refill_per_sec: 0.0,
+            },
+            normal: empty,
+            low: empty,
+        });
+
+        assert!(limiter.try_acquire(Priority::High));
+        assert!(!limiter.try_acquire(Priority::High));
+        assert!(!limiter.try_acquire(Priority::Low));
+        assert_eq!(limiter.throttled(Priority::High), 1);
+        assert_eq!(limiter.throttled_total(), 2);
+    }
+}
@@ -5,13 +5,31 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant};
+
+/// Default cap on exponential backoff delay (5 minutes)
+fn default_max_delay_seconds() -> u64 {
+    300
+}
+
+/// Default jitter: ±20% of the computed delay
+fn default_jitter_ratio() -> f64 {
+    0.2
+}
 
 /// Backoff strategy for retries
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BackoffStrategy {
-    /// Exponential backoff: delay = base_seconds * 2^attempt
-    Exponential { base_seconds: u64 },
+    /// Exponential backoff: delay = base_seconds * 2^attempt, capped at
+    /// `max_delay_seconds`, then randomized by ±`jitter_ratio` so devices that
+    /// failed together don't retry in lockstep
+    Exponential {
+        base_seconds: u64,
+        #[serde(default = "default_max_delay_seconds")]
+        max_delay_seconds: u64,
+        #[serde(default = "default_jitter_ratio")]
+        jitter_ratio: f64,
+    },
     /// Linear backoff: delay = increment_seconds * attempt
     Linear { increment_seconds: u64 },
     /// Fixed interval between retries
@@ -20,18 +38,34 @@ pub enum BackoffStrategy {
 
 impl Default for BackoffStrategy {
     fn default() -> Self {
-        BackoffStrategy::Exponential { base_seconds: 2 }
+        BackoffStrategy::exponential(2)
     }
 }
 
 impl BackoffStrategy {
+    /// Exponential backoff with the default cap and jitter
+    pub fn exponential(base_seconds: u64) -> Self {
+        BackoffStrategy::Exponential {
+            base_seconds,
+            max_delay_seconds: default_max_delay_seconds(),
+            jitter_ratio: default_jitter_ratio(),
+        }
+    }
+
     /// Calculate next retry delay based on attempt count
     pub fn calculate_delay(&self, attempt_count: usize) -> Duration {
         let seconds = match self {
-            BackoffStrategy::Exponential { base_seconds } => {
-                // Exponential: 2s, 4s, 8s, 16s, 32s, 64s (caps at 64s)
+            BackoffStrategy::Exponential {
+                base_seconds,
+                max_delay_seconds,
+                jitter_ratio,
+            } => {
+                // Exponential: 2s, 4s, 8s, 16s, 32s, 64s, 128s (exponent caps at 6)
                 let exp = (attempt_count as u32).min(6);
-                base_seconds * 2u64.pow(exp)
+                let delay = base_seconds
+                    .saturating_mul(2u64.pow(exp))
+                    .min(*max_delay_seconds);
+                return Self::apply_jitter(delay, *jitter_ratio, *max_delay_seconds);
             }
             BackoffStrategy::Linear { increment_seconds } => {
                 // Linear: increment * (attempt + 1)
@@ -45,6 +79,19 @@ impl BackoffStrategy {
 
         Duration::from_secs(seconds)
     }
+
+    /// Randomize `delay_seconds` by up to ±`jitter_ratio`, never exceeding `max_seconds`
+    fn apply_jitter(delay_seconds: u64, jitter_ratio: f64, max_seconds: u64) -> Duration {
+        let delay = Duration::from_secs(delay_seconds);
+        let ratio = jitter_ratio.clamp(0.0, 1.0);
+        if ratio == 0.0 || delay_seconds == 0 {
+            return delay;
+        }
+
+        use rand::Rng;
+        let factor = rand::thread_rng().gen_range(1.0 - ratio..=1.0 + ratio);
+        delay.mul_f64(factor).min(Duration::from_secs(max_seconds))
+    }
 }
 
 /// Retry item for failed transactions
@@ -70,10 +117,7 @@ impl RetryItem {
     /// Create new retry item
     pub fn new(tx_bytes: Vec<u8>, tx_id: String, error: String) -> Self {
         let now = Instant::now();
-        let now_unix = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let now_unix = crate::util::clock::now_secs();
 
         Self {
             tx_bytes,
@@ -216,6 +260,16 @@ impl RetryQueue {
         self.items.keys().next().copied()
     }
 
+    /// Iterate all items in retry-time order
+    pub(crate) fn items(&self) -> impl Iterator<Item = &RetryItem> {
+        self.items.values()
+    }
+
+    /// Maximum retry attempts per transaction
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
     /// Get number of items in retry queue
     pub fn len(&self) -> usize {
         self.items.len()
@@ -238,8 +292,27 @@ impl RetryQueue {
         tracing::info!("Cleared retry queue");
     }
 
+    /// Remove and return every item matching `pred`
+    pub fn remove_where(&mut self, mut pred: impl FnMut(&RetryItem) -> bool) -> Vec<RetryItem> {
+        let keys: Vec<Instant> = self
+            .items
+            .iter()
+            .filter(|(_, item)| pred(item))
+            .map(|(k, _)| *k)
+            .collect();
+
+        keys.into_iter()
+            .filter_map(|key| self.items.remove(&key))
+            .collect()
+    }
+
     /// Cleanup expired items (older than max_age)
     pub fn cleanup_expired(&mut self) -> usize {
+        self.drain_expired().len()
+    }
+
+    /// Remove and return expired items (older than max_age)
+    pub fn drain_expired(&mut self) -> Vec<RetryItem> {
         let expired_keys: Vec<Instant> = self
             .items
             .iter()
@@ -247,8 +320,7 @@ impl RetryQueue {
             .map(|(k, _)| *k)
             .collect();
 
-        let count = expired_keys.len();
-
+        let mut expired = Vec::with_capacity(expired_keys.len());
         for key in expired_keys {
             if let Some(item) = self.items.remove(&key) {
                 tracing::info!(
@@ -256,10 +328,11 @@ impl RetryQueue {
                     item.tx_id.chars().take(8).collect::<String>(),
                     item.age().as_secs() / 3600
                 );
+                expired.push(item);
             }
         }
 
-        count
+        expired
     }
 
     /// Get average number of attempts across all items
@@ -354,7 +427,11 @@ mod tests {
 
     #[test]
     fn test_backoff_exponential() {
-        let strategy = BackoffStrategy::Exponential { base_seconds: 2 };
+        let strategy = BackoffStrategy::Exponential {
+            base_seconds: 2,
+            max_delay_seconds: 600,
+            jitter_ratio: 0.0,
+        };
 
         assert_eq!(strategy.calculate_delay(0).as_secs(), 2); // 2^0 * 2 = 2
         assert_eq!(strategy.calculate_delay(1).as_secs(), 4); // 2^1 * 2 = 4
@@ -363,6 +440,21 @@ mod tests {
         assert_eq!(strategy.calculate_delay(6).as_secs(), 128); // 2^6 * 2 = 128 (capped)
     }
 
+    #[test]
+    fn test_backoff_exponential_jitter_and_cap() {
+        let strategy = BackoffStrategy::Exponential {
+            base_seconds: 2,
+            max_delay_seconds: 30,
+            jitter_ratio: 0.5,
+        };
+
+        for _ in 0..50 {
+            let delay = strategy.calculate_delay(2); // 8s ± 50%
+            assert!(delay >= Duration::from_secs(4) && delay <= Duration::from_secs(12));
+            assert!(strategy.calculate_delay(6) <= Duration::from_secs(30));
+        }
+    }
+
     #[test]
     fn test_backoff_linear() {
         let strategy = BackoffStrategy::Linear {
@@ -552,7 +644,7 @@ mod tests {
 
     #[test]
     fn test_prepare_next_retry() {
-        let strategy = BackoffStrategy::Exponential { base_seconds: 2 };
+        let strategy = BackoffStrategy::exponential(2);
         let mut item = create_test_item("tx1");
 
         assert_eq!(item.attempt_count, 0);
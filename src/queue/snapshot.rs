@@ -0,0 +1,157 @@
+//! Queue Snapshot Export
+//!
+//! Point-in-time dump of every queue for diagnostics. Payload bytes are
+//! redacted unless explicitly requested, so snapshots from field devices can be
+//! shared with support without leaking signed transactions.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Serialize;
+
+use super::confirmation::{Confirmation, ConfirmationStatus};
+use super::dead_letter::{DeadLetter, DeadLetterReason};
+use super::outbound::{OutboundTransaction, Priority};
+use super::retry::RetryItem;
+use super::QueueMetrics;
+
+/// Full queue snapshot
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueSnapshot {
+    /// Unix timestamp when the snapshot was taken
+    pub taken_at: u64,
+    /// Whether payload bytes are included (base64) or redacted
+    pub payloads_included: bool,
+    pub metrics: QueueMetrics,
+    pub outbound: Vec<OutboundSnapshot>,
+    pub expired: Vec<OutboundSnapshot>,
+    pub retries: Vec<RetrySnapshot>,
+    pub confirmations: Vec<ConfirmationSnapshot>,
+    pub dead_letters: Vec<DeadLetterSnapshot>,
+}
+
+/// Outbound (or expired) transaction entry
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboundSnapshot {
+    pub tx_id: String,
+    pub priority: Priority,
+    pub created_at: u64,
+    pub age_seconds: u64,
+    pub ttl_secs: u64,
+    pub retry_count: u8,
+    pub hop_count: u8,
+    pub relevance: u8,
+    pub is_confirmation: bool,
+    pub fragment_count: usize,
+    pub size_bytes: usize,
+    /// Only present when payloads are included
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+}
+
+impl OutboundSnapshot {
+    pub(super) fn new(tx: &OutboundTransaction, include_payloads: bool) -> Self {
+        Self {
+            tx_id: tx.tx_id.clone(),
+            priority: tx.priority,
+            created_at: tx.created_at,
+            age_seconds: tx.age_seconds(),
+            ttl_secs: tx.ttl_secs,
+            retry_count: tx.retry_count,
+            hop_count: tx.hop_count,
+            relevance: tx.relevance,
+            is_confirmation: tx.is_confirmation,
+            fragment_count: tx.fragments.len(),
+            size_bytes: tx.original_bytes.len(),
+            payload: include_payloads.then(|| BASE64.encode(&tx.original_bytes)),
+        }
+    }
+}
+
+/// Retry queue entry
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetrySnapshot {
+    pub tx_id: String,
+    pub attempt_count: usize,
+    pub last_error: String,
+    pub next_retry_in_secs: u64,
+    pub age_seconds: u64,
+    pub size_bytes: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+}
+
+impl RetrySnapshot {
+    pub(super) fn new(item: &RetryItem, include_payloads: bool) -> Self {
+        Self {
+            tx_id: item.tx_id.clone(),
+            attempt_count: item.attempt_count,
+            last_error: item.last_error.clone(),
+            next_retry_in_secs: item.time_until_retry().as_secs(),
+            age_seconds: item.age().as_secs(),
+            size_bytes: item.tx_bytes.len(),
+            payload: include_payloads.then(|| BASE64.encode(&item.tx_bytes)),
+        }
+    }
+}
+
+/// Confirmation queue entry (no payload to redact)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmationSnapshot {
+    pub tx_id: String,
+    pub success: bool,
+    /// Signature on success, error message on failure
+    pub detail: String,
+    pub timestamp: u64,
+    pub relay_count: u8,
+    pub max_hops: u8,
+}
+
+impl From<&Confirmation> for ConfirmationSnapshot {
+    fn from(conf: &Confirmation) -> Self {
+        let (success, detail) = match &conf.status {
+            ConfirmationStatus::Success { signature } => (true, signature.clone()),
+            ConfirmationStatus::Failed { error } => (false, error.clone()),
+        };
+        Self {
+            tx_id: conf.tx_id_hex(),
+            success,
+            detail,
+            timestamp: conf.timestamp,
+            relay_count: conf.relay_count,
+            max_hops: conf.max_hops,
+        }
+    }
+}
+
+/// Dead-letter entry
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetterSnapshot {
+    pub tx_id: String,
+    pub attempts: usize,
+    pub last_error: String,
+    pub reason: DeadLetterReason,
+    pub first_failed_at: u64,
+    pub dead_lettered_at: u64,
+    pub size_bytes: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+}
+
+impl DeadLetterSnapshot {
+    pub(super) fn new(letter: &DeadLetter, include_payloads: bool) -> Self {
+        Self {
+            tx_id: letter.tx_id.clone(),
+            attempts: letter.attempts,
+            last_error: letter.last_error.clone(),
+            reason: letter.reason,
+            first_failed_at: letter.first_failed_at,
+            dead_lettered_at: letter.dead_lettered_at,
+            size_bytes: letter.tx_bytes.len(),
+            payload: include_payloads.then(|| BASE64.encode(&letter.tx_bytes)),
+        }
+    }
+}
@@ -5,28 +5,141 @@
 
 #![allow(deprecated)]
 
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::io::Write;
+use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use thiserror::Error;
 
+use crate::storage::migration::{add_version_field, Migration, Migrator};
+use crate::storage::{remove_stale_temp_files, write_atomic, BatchOp, StorageBackend};
+
 use super::confirmation::{Confirmation, ConfirmationQueue};
+use super::dead_letter::{DeadLetter, DeadLetterQueue, DeadLetterReason};
 use super::outbound::{OutboundQueue, OutboundTransaction, Priority};
 use super::retry::{RetryItem, RetryQueue};
 
 /// Type alias for the tuple returned by `load_all`
-type AllQueues = (
+pub type AllQueues = (
     OutboundQueue,
     RetryQueue,
     ConfirmationQueue,
     Vec<(String, Vec<u8>, u64)>,
 );
 
+/// On-disk format of each queue file (bump `current` and add a migration on change)
+static OUTBOUND_FORMAT: Migrator = Migrator::new("outbound queue", 1, UNVERSIONED_TO_V1);
+static RETRY_FORMAT: Migrator = Migrator::new("retry queue", 1, UNVERSIONED_TO_V1);
+static CONFIRMATION_FORMAT: Migrator = Migrator::new("confirmation queue", 1, UNVERSIONED_TO_V1);
+static RECEIVED_FORMAT: Migrator = Migrator::new("received queue", 1, UNVERSIONED_TO_V1);
+static DEAD_LETTER_FORMAT: Migrator = Migrator::new("dead-letter queue", 1, UNVERSIONED_TO_V1);
+
+const UNVERSIONED_TO_V1: &[Migration] = &[Migration {
+    from: 0,
+    migrate: add_version_field,
+}];
+
+/// Persistence backend used by `QueueManager`
+///
+/// Implemented by the JSON file store ([`QueueStorage`]) and by
+/// [`BackendQueueStore`] over any [`StorageBackend`] (e.g. SQLite).
+pub trait QueueStore: Send + Sync {
+    /// Persist all queues
+    fn save_all(
+        &self,
+        outbound: &OutboundQueue,
+        retry: &RetryQueue,
+        confirmation: &ConfirmationQueue,
+        received: &[(String, Vec<u8>, u64)],
+    ) -> Result<(), StorageError>;
+
+    /// Load all queues (empty queues if nothing was saved yet)
+    fn load_all(&self) -> Result<AllQueues, StorageError>;
+
+    /// Persist the dead-letter queue
+    fn save_dead_letters(&self, queue: &DeadLetterQueue) -> Result<(), StorageError>;
+
+    /// Load the dead-letter queue (empty if nothing was saved yet)
+    fn load_dead_letters(&self) -> Result<DeadLetterQueue, StorageError>;
+
+    /// Durably record a single queue mutation between full saves
+    ///
+    /// Backends that commit every save transactionally may ignore this.
+    fn append_wal(&self, _record: &WalRecord) -> Result<(), StorageError> {
+        Ok(())
+    }
+}
+
+/// One queue mutation in the write-ahead log (stored as a JSON line)
+///
+/// `QueueManager` appends a record for each mutation while holding the
+/// affected queue's write lock; `save_all` writes a full snapshot and then
+/// truncates the log (compaction). On load the log is replayed on top of the
+/// last snapshot, so nothing accepted since that snapshot is lost on power failure.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum WalRecord {
+    OutboundPush { tx: OutboundTransactionPersist },
+    OutboundRemove { tx_id: String },
+    RetryPush { item: RetryItemPersist },
+    RetryRemove { tx_id: String },
+    ConfirmationPush { confirmation: Confirmation },
+}
+
+impl WalRecord {
+    pub fn outbound_push(tx: &OutboundTransaction) -> Self {
+        WalRecord::OutboundPush {
+            tx: OutboundTransactionPersist::from_transaction(tx),
+        }
+    }
+
+    pub fn retry_push(item: &RetryItem) -> Self {
+        WalRecord::RetryPush {
+            item: RetryItemPersist::from_retry_item(item),
+        }
+    }
+
+    /// Re-apply this mutation to freshly loaded queues (idempotent)
+    fn apply(
+        self,
+        outbound: &mut OutboundQueue,
+        retry: &mut RetryQueue,
+        confirmation: &mut ConfirmationQueue,
+    ) -> Result<(), String> {
+        match self {
+            WalRecord::OutboundPush { tx } => {
+                let tx = tx.to_transaction()?;
+                if !outbound.contains(&tx.tx_id) {
+                    outbound.push(tx).map_err(|e| e.to_string())?;
+                }
+            }
+            WalRecord::OutboundRemove { tx_id } => {
+                outbound.purge_by_tx_id(&tx_id);
+            }
+            WalRecord::RetryPush { item } => {
+                let item = item.to_retry_item()?;
+                retry.remove_where(|existing| existing.tx_id == item.tx_id);
+                retry.push(item).map_err(|e| e.to_string())?;
+            }
+            WalRecord::RetryRemove { tx_id } => {
+                retry.remove_where(|existing| existing.tx_id == tx_id);
+            }
+            WalRecord::ConfirmationPush { confirmation: conf } => {
+                confirmation.push(conf).map_err(|e| e.to_string())?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Queue storage manager
 pub struct QueueStorage {
     /// Base directory for queue storage
     storage_dir: PathBuf,
+    /// Open write-ahead log (lazily opened, closed on compaction)
+    wal: Mutex<Option<fs::File>>,
 }
 
 impl QueueStorage {
@@ -41,7 +154,83 @@ impl QueueStorage {
             })?;
         }
 
-        Ok(Self { storage_dir })
+        let stale = remove_stale_temp_files(&storage_dir);
+        if stale > 0 {
+            tracing::warn!("Removed {} interrupted queue temp file(s)", stale);
+        }
+
+        Ok(Self {
+            storage_dir,
+            wal: Mutex::new(None),
+        })
+    }
+
+    /// Get write-ahead log path
+    fn wal_path(&self) -> PathBuf {
+        self.storage_dir.join("queue.wal")
+    }
+
+    /// Append a record to the write-ahead log (synced before returning)
+    pub fn append_wal(&self, record: &WalRecord) -> Result<(), StorageError> {
+        let mut line = serde_json::to_vec(record).map_err(|e| {
+            StorageError::SerializationError(format!("Failed to serialize WAL record: {}", e))
+        })?;
+        line.push(b'\n');
+
+        let mut wal = self.wal.lock();
+        if wal.is_none() {
+            let file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.wal_path())
+                .map_err(|e| StorageError::IoError(format!("Failed to open WAL: {}", e)))?;
+            *wal = Some(file);
+        }
+
+        if let Some(file) = wal.as_mut() {
+            file.write_all(&line)
+                .map_err(|e| StorageError::IoError(format!("Failed to append to WAL: {}", e)))?;
+            file.sync_data()
+                .map_err(|e| StorageError::IoError(format!("Failed to sync WAL: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Read all records from the write-ahead log
+    ///
+    /// A torn record (power lost mid-append) can only be the last line; it is skipped.
+    pub fn read_wal(&self) -> Result<Vec<WalRecord>, StorageError> {
+        let path = self.wal_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let file = fs::File::open(&path)
+            .map_err(|e| StorageError::IoError(format!("Failed to open WAL: {}", e)))?;
+
+        let mut records = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line =
+                line.map_err(|e| StorageError::IoError(format!("Failed to read WAL: {}", e)))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(record) => records.push(record),
+                Err(e) => tracing::warn!("Skipping unreadable WAL record: {}", e),
+            }
+        }
+        Ok(records)
+    }
+
+    /// Truncate the write-ahead log (caller holds the WAL lock)
+    fn truncate_wal(&self, wal: &mut Option<fs::File>) -> Result<(), StorageError> {
+        *wal = None;
+        let file = fs::File::create(self.wal_path())
+            .map_err(|e| StorageError::IoError(format!("Failed to truncate WAL: {}", e)))?;
+        file.sync_all()
+            .map_err(|e| StorageError::IoError(format!("Failed to sync WAL: {}", e)))?;
+        Ok(())
     }
 
     /// Get file path for a queue
@@ -49,15 +238,9 @@ impl QueueStorage {
         self.storage_dir.join(format!("{}.json", queue_name))
     }
 
-    /// Get temporary file path for atomic writes
-    fn temp_path(&self, queue_name: &str) -> PathBuf {
-        self.storage_dir.join(format!("{}.tmp", queue_name))
-    }
-
     /// Save outbound queue to disk (atomic write)
     pub fn save_outbound_queue(&self, queue: &OutboundQueue) -> Result<(), StorageError> {
         let path = self.queue_path("outbound_queue");
-        let temp_path = self.temp_path("outbound_queue");
 
         // Serialize to persistable format
         let persistable = OutboundQueuePersist::from_queue(queue);
@@ -65,19 +248,8 @@ impl QueueStorage {
             StorageError::SerializationError(format!("Failed to serialize outbound queue: {}", e))
         })?;
 
-        // Atomic write: write to temp file first
-        {
-            let mut file = fs::File::create(&temp_path)
-                .map_err(|e| StorageError::IoError(format!("Failed to create temp file: {}", e)))?;
-            file.write_all(json.as_bytes())
-                .map_err(|e| StorageError::IoError(format!("Failed to write temp file: {}", e)))?;
-            file.sync_all()
-                .map_err(|e| StorageError::IoError(format!("Failed to sync temp file: {}", e)))?;
-        }
-
-        // Rename temp to final (atomic on most filesystems)
-        fs::rename(&temp_path, &path)
-            .map_err(|e| StorageError::IoError(format!("Failed to rename temp file: {}", e)))?;
+        // Atomic write: temp file + fsync + rename
+        write_atomic(&path, json.as_bytes())?;
 
         tracing::debug!("Saved outbound queue to {}", path.display());
         Ok(())
@@ -95,12 +267,7 @@ impl QueueStorage {
         let json = fs::read_to_string(&path)
             .map_err(|e| StorageError::IoError(format!("Failed to read outbound queue: {}", e)))?;
 
-        let persistable: OutboundQueuePersist = serde_json::from_str(&json).map_err(|e| {
-            StorageError::DeserializationError(format!(
-                "Failed to deserialize outbound queue: {}",
-                e
-            ))
-        })?;
+        let persistable: OutboundQueuePersist = OUTBOUND_FORMAT.decode(json.as_bytes())?;
 
         let queue = persistable.to_queue();
         tracing::info!("Loaded outbound queue: {} transactions", queue.len());
@@ -111,24 +278,14 @@ impl QueueStorage {
     /// Save retry queue to disk (atomic write)
     pub fn save_retry_queue(&self, queue: &RetryQueue) -> Result<(), StorageError> {
         let path = self.queue_path("retry_queue");
-        let temp_path = self.temp_path("retry_queue");
 
         let persistable = RetryQueuePersist::from_queue(queue);
         let json = serde_json::to_string_pretty(&persistable).map_err(|e| {
             StorageError::SerializationError(format!("Failed to serialize retry queue: {}", e))
         })?;
 
-        {
-            let mut file = fs::File::create(&temp_path)
-                .map_err(|e| StorageError::IoError(format!("Failed to create temp file: {}", e)))?;
-            file.write_all(json.as_bytes())
-                .map_err(|e| StorageError::IoError(format!("Failed to write temp file: {}", e)))?;
-            file.sync_all()
-                .map_err(|e| StorageError::IoError(format!("Failed to sync temp file: {}", e)))?;
-        }
-
-        fs::rename(&temp_path, &path)
-            .map_err(|e| StorageError::IoError(format!("Failed to rename temp file: {}", e)))?;
+        // Atomic write: temp file + fsync + rename
+        write_atomic(&path, json.as_bytes())?;
 
         tracing::debug!("Saved retry queue to {}", path.display());
         Ok(())
@@ -146,9 +303,7 @@ impl QueueStorage {
         let json = fs::read_to_string(&path)
             .map_err(|e| StorageError::IoError(format!("Failed to read retry queue: {}", e)))?;
 
-        let persistable: RetryQueuePersist = serde_json::from_str(&json).map_err(|e| {
-            StorageError::DeserializationError(format!("Failed to deserialize retry queue: {}", e))
-        })?;
+        let persistable: RetryQueuePersist = RETRY_FORMAT.decode(json.as_bytes())?;
 
         let queue = persistable.to_queue();
         tracing::info!("Loaded retry queue: {} items", queue.len());
@@ -159,7 +314,6 @@ impl QueueStorage {
     /// Save confirmation queue to disk (atomic write)
     pub fn save_confirmation_queue(&self, queue: &ConfirmationQueue) -> Result<(), StorageError> {
         let path = self.queue_path("confirmation_queue");
-        let temp_path = self.temp_path("confirmation_queue");
 
         let persistable = ConfirmationQueuePersist::from_queue(queue);
         let json = serde_json::to_string_pretty(&persistable).map_err(|e| {
@@ -169,17 +323,8 @@ impl QueueStorage {
             ))
         })?;
 
-        {
-            let mut file = fs::File::create(&temp_path)
-                .map_err(|e| StorageError::IoError(format!("Failed to create temp file: {}", e)))?;
-            file.write_all(json.as_bytes())
-                .map_err(|e| StorageError::IoError(format!("Failed to write temp file: {}", e)))?;
-            file.sync_all()
-                .map_err(|e| StorageError::IoError(format!("Failed to sync temp file: {}", e)))?;
-        }
-
-        fs::rename(&temp_path, &path)
-            .map_err(|e| StorageError::IoError(format!("Failed to rename temp file: {}", e)))?;
+        // Atomic write: temp file + fsync + rename
+        write_atomic(&path, json.as_bytes())?;
 
         tracing::debug!("Saved confirmation queue to {}", path.display());
         Ok(())
@@ -198,12 +343,7 @@ impl QueueStorage {
             StorageError::IoError(format!("Failed to read confirmation queue: {}", e))
         })?;
 
-        let persistable: ConfirmationQueuePersist = serde_json::from_str(&json).map_err(|e| {
-            StorageError::DeserializationError(format!(
-                "Failed to deserialize confirmation queue: {}",
-                e
-            ))
-        })?;
+        let persistable: ConfirmationQueuePersist = CONFIRMATION_FORMAT.decode(json.as_bytes())?;
 
         let queue = persistable.to_queue();
         tracing::info!("Loaded confirmation queue: {} confirmations", queue.len());
@@ -218,24 +358,14 @@ impl QueueStorage {
         queue: &[(String, Vec<u8>, u64)],
     ) -> Result<(), StorageError> {
         let path = self.queue_path("received_queue");
-        let temp_path = self.temp_path("received_queue");
 
         let persistable = ReceivedQueuePersist::from_queue(queue);
         let json = serde_json::to_string_pretty(&persistable).map_err(|e| {
             StorageError::SerializationError(format!("Failed to serialize received queue: {}", e))
         })?;
 
-        {
-            let mut file = fs::File::create(&temp_path)
-                .map_err(|e| StorageError::IoError(format!("Failed to create temp file: {}", e)))?;
-            file.write_all(json.as_bytes())
-                .map_err(|e| StorageError::IoError(format!("Failed to write temp file: {}", e)))?;
-            file.sync_all()
-                .map_err(|e| StorageError::IoError(format!("Failed to sync temp file: {}", e)))?;
-        }
-
-        fs::rename(&temp_path, &path)
-            .map_err(|e| StorageError::IoError(format!("Failed to rename temp file: {}", e)))?;
+        // Atomic write: temp file + fsync + rename
+        write_atomic(&path, json.as_bytes())?;
 
         tracing::debug!("Saved received queue to {}", path.display());
         Ok(())
@@ -253,15 +383,50 @@ impl QueueStorage {
         let json = fs::read_to_string(&path)
             .map_err(|e| StorageError::IoError(format!("Failed to read received queue: {}", e)))?;
 
-        let persistable: ReceivedQueuePersist = serde_json::from_str(&json).map_err(|e| {
-            StorageError::DeserializationError(format!(
-                "Failed to deserialize received queue: {}",
+        let persistable: ReceivedQueuePersist = RECEIVED_FORMAT.decode(json.as_bytes())?;
+
+        let queue = persistable.to_queue();
+        tracing::info!("Loaded received queue: {} transactions", queue.len());
+
+        Ok(queue)
+    }
+
+    /// Save dead-letter queue to disk (atomic write)
+    pub fn save_dead_letter_queue(&self, queue: &DeadLetterQueue) -> Result<(), StorageError> {
+        let path = self.queue_path("dead_letter_queue");
+
+        let persistable = DeadLetterQueuePersist::from_queue(queue);
+        let json = serde_json::to_string_pretty(&persistable).map_err(|e| {
+            StorageError::SerializationError(format!(
+                "Failed to serialize dead-letter queue: {}",
                 e
             ))
         })?;
 
+        // Atomic write: temp file + fsync + rename
+        write_atomic(&path, json.as_bytes())?;
+
+        tracing::debug!("Saved dead-letter queue to {}", path.display());
+        Ok(())
+    }
+
+    /// Load dead-letter queue from disk
+    pub fn load_dead_letter_queue(&self) -> Result<DeadLetterQueue, StorageError> {
+        let path = self.queue_path("dead_letter_queue");
+
+        if !path.exists() {
+            tracing::debug!("No saved dead-letter queue found, starting fresh");
+            return Ok(DeadLetterQueue::new());
+        }
+
+        let json = fs::read_to_string(&path).map_err(|e| {
+            StorageError::IoError(format!("Failed to read dead-letter queue: {}", e))
+        })?;
+
+        let persistable: DeadLetterQueuePersist = DEAD_LETTER_FORMAT.decode(json.as_bytes())?;
+
         let queue = persistable.to_queue();
-        tracing::info!("Loaded received queue: {} transactions", queue.len());
+        tracing::info!("Loaded dead-letter queue: {} items", queue.len());
 
         Ok(queue)
     }
@@ -274,34 +439,245 @@ impl QueueStorage {
         confirmation: &ConfirmationQueue,
         received: &[(String, Vec<u8>, u64)],
     ) -> Result<(), StorageError> {
+        // Hold the WAL lock so no record lands between the snapshot and the truncate
+        let mut wal = self.wal.lock();
+
         self.save_outbound_queue(outbound)?;
         self.save_retry_queue(retry)?;
         self.save_confirmation_queue(confirmation)?;
         self.save_received_queue(received)?;
 
+        // Snapshot is durable; the log is now redundant (compaction)
+        self.truncate_wal(&mut wal)?;
+
         tracing::info!("Saved all queues to disk");
         Ok(())
     }
 
-    /// Load all queues
+    /// Load all queues (last snapshot plus write-ahead log replay)
     pub fn load_all(&self) -> Result<AllQueues, StorageError> {
-        let outbound = self.load_outbound_queue()?;
-        let retry = self.load_retry_queue()?;
-        let confirmation = self.load_confirmation_queue()?;
+        let mut outbound = self.load_outbound_queue()?;
+        let mut retry = self.load_retry_queue()?;
+        let mut confirmation = self.load_confirmation_queue()?;
         let received = self.load_received_queue()?;
 
+        let records = self.read_wal()?;
+        let replayed = records.len();
+        for record in records {
+            if let Err(e) = record.apply(&mut outbound, &mut retry, &mut confirmation) {
+                tracing::warn!("Failed to replay WAL record: {}", e);
+            }
+        }
+
         tracing::info!(
-            "Loaded all queues: {} outbound, {} retry, {} confirmation, {} received",
+            "Loaded all queues: {} outbound, {} retry, {} confirmation, {} received ({} WAL records replayed)",
             outbound.len(),
             retry.len(),
             confirmation.len(),
-            received.len()
+            received.len(),
+            replayed
         );
 
         Ok((outbound, retry, confirmation, received))
     }
 }
 
+impl QueueStore for QueueStorage {
+    fn save_all(
+        &self,
+        outbound: &OutboundQueue,
+        retry: &RetryQueue,
+        confirmation: &ConfirmationQueue,
+        received: &[(String, Vec<u8>, u64)],
+    ) -> Result<(), StorageError> {
+        QueueStorage::save_all(self, outbound, retry, confirmation, received)
+    }
+
+    fn load_all(&self) -> Result<AllQueues, StorageError> {
+        QueueStorage::load_all(self)
+    }
+
+    fn save_dead_letters(&self, queue: &DeadLetterQueue) -> Result<(), StorageError> {
+        self.save_dead_letter_queue(queue)
+    }
+
+    fn load_dead_letters(&self) -> Result<DeadLetterQueue, StorageError> {
+        self.load_dead_letter_queue()
+    }
+
+    fn append_wal(&self, record: &WalRecord) -> Result<(), StorageError> {
+        QueueStorage::append_wal(self, record)
+    }
+}
+
+/// Queue persistence on top of a generic [`StorageBackend`]
+///
+/// Every queue is one key under `queues/`; WAL records are individual keys
+/// under `queues/wal/` so appends never rewrite existing data. A save writes
+/// the snapshot and deletes the replayed WAL keys in one atomic batch.
+pub struct BackendQueueStore {
+    backend: Arc<dyn StorageBackend>,
+    /// Next WAL sequence number (guarded so appends and compaction don't interleave)
+    wal_seq: Mutex<u64>,
+}
+
+impl BackendQueueStore {
+    const OUTBOUND_KEY: &'static str = "queues/outbound.json";
+    const RETRY_KEY: &'static str = "queues/retry.json";
+    const CONFIRMATION_KEY: &'static str = "queues/confirmation.json";
+    const RECEIVED_KEY: &'static str = "queues/received.json";
+    const DEAD_LETTER_KEY: &'static str = "queues/dead_letter.json";
+    const WAL_PREFIX: &'static str = "queues/wal/";
+
+    /// Create a queue store over `backend`
+    pub fn new(backend: Arc<dyn StorageBackend>) -> Result<Self, StorageError> {
+        // Continue numbering after any WAL records left from a previous run
+        let next_seq = backend
+            .list(Self::WAL_PREFIX)?
+            .iter()
+            .filter_map(|key| key[Self::WAL_PREFIX.len()..].parse::<u64>().ok())
+            .max()
+            .map_or(0, |seq| seq + 1);
+
+        Ok(Self {
+            backend,
+            wal_seq: Mutex::new(next_seq),
+        })
+    }
+
+    fn load<T: for<'de> Deserialize<'de>>(
+        &self,
+        key: &str,
+        format: &Migrator,
+    ) -> Result<Option<T>, StorageError> {
+        match self.backend.get(key)? {
+            Some(bytes) => Ok(Some(format.decode(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+fn to_json<T: Serialize>(key: &str, value: &T) -> Result<BatchOp, StorageError> {
+    let value = serde_json::to_vec(value).map_err(|e| {
+        StorageError::SerializationError(format!("Failed to serialize {}: {}", key, e))
+    })?;
+    Ok(BatchOp::Put {
+        key: key.to_string(),
+        value,
+    })
+}
+
+impl QueueStore for BackendQueueStore {
+    fn save_all(
+        &self,
+        outbound: &OutboundQueue,
+        retry: &RetryQueue,
+        confirmation: &ConfirmationQueue,
+        received: &[(String, Vec<u8>, u64)],
+    ) -> Result<(), StorageError> {
+        // Hold the sequence lock so no record lands between the snapshot and the delete
+        let _seq = self.wal_seq.lock();
+
+        let mut ops = vec![
+            to_json(
+                Self::OUTBOUND_KEY,
+                &OutboundQueuePersist::from_queue(outbound),
+            )?,
+            to_json(Self::RETRY_KEY, &RetryQueuePersist::from_queue(retry))?,
+            to_json(
+                Self::CONFIRMATION_KEY,
+                &ConfirmationQueuePersist::from_queue(confirmation),
+            )?,
+            to_json(
+                Self::RECEIVED_KEY,
+                &ReceivedQueuePersist::from_queue(received),
+            )?,
+        ];
+        ops.extend(
+            self.backend
+                .list(Self::WAL_PREFIX)?
+                .into_iter()
+                .map(|key| BatchOp::Delete { key }),
+        );
+
+        self.backend.batch(&ops)?;
+        tracing::debug!("Saved all queues to storage backend");
+        Ok(())
+    }
+
+    fn load_all(&self) -> Result<AllQueues, StorageError> {
+        let mut outbound = self
+            .load::<OutboundQueuePersist>(Self::OUTBOUND_KEY, &OUTBOUND_FORMAT)?
+            .map(OutboundQueuePersist::to_queue)
+            .unwrap_or_default();
+        let mut retry = self
+            .load::<RetryQueuePersist>(Self::RETRY_KEY, &RETRY_FORMAT)?
+            .map(RetryQueuePersist::to_queue)
+            .unwrap_or_default();
+        let mut confirmation = self
+            .load::<ConfirmationQueuePersist>(Self::CONFIRMATION_KEY, &CONFIRMATION_FORMAT)?
+            .map(ConfirmationQueuePersist::to_queue)
+            .unwrap_or_default();
+        let received = self
+            .load::<ReceivedQueuePersist>(Self::RECEIVED_KEY, &RECEIVED_FORMAT)?
+            .map(ReceivedQueuePersist::to_queue)
+            .unwrap_or_default();
+
+        // Keys are zero-padded, so lexical order is append order
+        let wal_keys = self.backend.list(Self::WAL_PREFIX)?;
+        for key in &wal_keys {
+            let Some(bytes) = self.backend.get(key)? else {
+                continue;
+            };
+            let record: WalRecord = match serde_json::from_slice(&bytes) {
+                Ok(record) => record,
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable WAL record: {}", e);
+                    continue;
+                }
+            };
+            if let Err(e) = record.apply(&mut outbound, &mut retry, &mut confirmation) {
+                tracing::warn!("Failed to replay WAL record: {}", e);
+            }
+        }
+
+        tracing::info!(
+            "Loaded all queues: {} outbound, {} retry, {} confirmation, {} received ({} WAL records replayed)",
+            outbound.len(),
+            retry.len(),
+            confirmation.len(),
+            received.len(),
+            wal_keys.len()
+        );
+
+        Ok((outbound, retry, confirmation, received))
+    }
+
+    fn save_dead_letters(&self, queue: &DeadLetterQueue) -> Result<(), StorageError> {
+        let op = to_json(
+            Self::DEAD_LETTER_KEY,
+            &DeadLetterQueuePersist::from_queue(queue),
+        )?;
+        self.backend.batch(&[op])?;
+        Ok(())
+    }
+
+    fn load_dead_letters(&self) -> Result<DeadLetterQueue, StorageError> {
+        Ok(self
+            .load::<DeadLetterQueuePersist>(Self::DEAD_LETTER_KEY, &DEAD_LETTER_FORMAT)?
+            .map(DeadLetterQueuePersist::to_queue)
+            .unwrap_or_default())
+    }
+
+    fn append_wal(&self, record: &WalRecord) -> Result<(), StorageError> {
+        let mut seq = self.wal_seq.lock();
+        let key = format!("{}{:020}", Self::WAL_PREFIX, *seq);
+        let op = to_json(&key, record)?;
+        self.backend.batch(&[op])?;
+        *seq += 1;
+        Ok(())
+    }
+}
 // =============================================================================
 // Persistable Queue Formats
 // =============================================================================
@@ -318,12 +694,7 @@ struct OutboundQueuePersist {
 
 impl OutboundQueuePersist {
     fn from_queue(queue: &OutboundQueue) -> Self {
-        use std::time::{SystemTime, UNIX_EPOCH};
-
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let now = crate::util::clock::now_secs();
 
         let mut high_priority = Vec::new();
         let mut normal_priority = Vec::new();
@@ -339,7 +710,7 @@ impl OutboundQueuePersist {
         }
 
         Self {
-            version: 1,
+            version: OUTBOUND_FORMAT.current(),
             high_priority,
             normal_priority,
             low_priority,
@@ -378,7 +749,7 @@ impl OutboundQueuePersist {
 
 /// Persistable outbound transaction
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct OutboundTransactionPersist {
+pub struct OutboundTransactionPersist {
     tx_id: String,
     original_bytes: String, // base64
     fragment_count: usize,
@@ -398,7 +769,7 @@ struct OutboundTransactionPersist {
 }
 
 impl OutboundTransactionPersist {
-    fn from_transaction(tx: &OutboundTransaction) -> Self {
+    pub(super) fn from_transaction(tx: &OutboundTransaction) -> Self {
         Self {
             tx_id: tx.tx_id.clone(),
             original_bytes: base64::encode(&tx.original_bytes),
@@ -415,7 +786,7 @@ impl OutboundTransactionPersist {
     }
 
     #[allow(clippy::wrong_self_convention)]
-    fn to_transaction(self) -> Result<OutboundTransaction, String> {
+    pub(super) fn to_transaction(self) -> Result<OutboundTransaction, String> {
         let original_bytes = base64::decode(&self.original_bytes)
             .map_err(|e| format!("Failed to decode transaction bytes: {}", e))?;
 
@@ -448,18 +819,16 @@ struct RetryQueuePersist {
 }
 
 impl RetryQueuePersist {
-    fn from_queue(_queue: &RetryQueue) -> Self {
-        use std::time::{SystemTime, UNIX_EPOCH};
-
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+    fn from_queue(queue: &RetryQueue) -> Self {
+        let now = crate::util::clock::now_secs();
 
         Self {
-            version: 1,
-            items: Vec::new(), // Will be populated
-            max_retries: 5,    // queue.max_retries (private field)
+            version: RETRY_FORMAT.current(),
+            items: queue
+                .items()
+                .map(RetryItemPersist::from_retry_item)
+                .collect(),
+            max_retries: queue.max_retries(),
             saved_at: now,
         }
     }
@@ -481,7 +850,7 @@ impl RetryQueuePersist {
 
 /// Persistable retry item
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct RetryItemPersist {
+pub struct RetryItemPersist {
     tx_bytes: String, // base64
     tx_id: String,
     attempt_count: usize,
@@ -490,8 +859,7 @@ struct RetryItemPersist {
 }
 
 impl RetryItemPersist {
-    #[allow(dead_code)]
-    fn from_retry_item(item: &RetryItem) -> Self {
+    pub(super) fn from_retry_item(item: &RetryItem) -> Self {
         Self {
             tx_bytes: base64::encode(&item.tx_bytes),
             tx_id: item.tx_id.clone(),
@@ -502,7 +870,7 @@ impl RetryItemPersist {
     }
 
     #[allow(clippy::wrong_self_convention)]
-    fn to_retry_item(self) -> Result<RetryItem, String> {
+    pub(super) fn to_retry_item(self) -> Result<RetryItem, String> {
         use std::time::Instant;
 
         let tx_bytes = base64::decode(&self.tx_bytes)
@@ -531,17 +899,12 @@ struct ConfirmationQueuePersist {
 }
 
 impl ConfirmationQueuePersist {
-    fn from_queue(_queue: &ConfirmationQueue) -> Self {
-        use std::time::{SystemTime, UNIX_EPOCH};
-
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+    fn from_queue(queue: &ConfirmationQueue) -> Self {
+        let now = crate::util::clock::now_secs();
 
         Self {
-            version: 1,
-            confirmations: Vec::new(), // Will be populated
+            version: CONFIRMATION_FORMAT.current(),
+            confirmations: queue.confirmations().cloned().collect(),
             saved_at: now,
         }
     }
@@ -568,12 +931,7 @@ struct ReceivedQueuePersist {
 
 impl ReceivedQueuePersist {
     fn from_queue(queue: &[(String, Vec<u8>, u64)]) -> Self {
-        use std::time::{SystemTime, UNIX_EPOCH};
-
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let now = crate::util::clock::now_secs();
 
         let transactions: Vec<ReceivedTransactionPersist> = queue
             .iter()
@@ -585,7 +943,7 @@ impl ReceivedQueuePersist {
             .collect();
 
         Self {
-            version: 1,
+            version: RECEIVED_FORMAT.current(),
             transactions,
             saved_at: now,
         }
@@ -614,6 +972,83 @@ struct ReceivedTransactionPersist {
     received_at: u64,
 }
 
+/// Persistable dead-letter queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DeadLetterQueuePersist {
+    version: u32,
+    items: Vec<DeadLetterPersist>,
+    saved_at: u64,
+}
+
+impl DeadLetterQueuePersist {
+    fn from_queue(queue: &DeadLetterQueue) -> Self {
+        let now = crate::util::clock::now_secs();
+
+        Self {
+            version: DEAD_LETTER_FORMAT.current(),
+            items: queue
+                .iter()
+                .map(DeadLetterPersist::from_dead_letter)
+                .collect(),
+            saved_at: now,
+        }
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn to_queue(self) -> DeadLetterQueue {
+        let mut queue = DeadLetterQueue::new();
+        for item in self.items {
+            match item.to_dead_letter() {
+                Ok(letter) => queue.restore(letter),
+                Err(e) => tracing::warn!("Failed to decode dead letter: {}", e),
+            }
+        }
+        queue
+    }
+}
+
+/// Persistable dead letter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct DeadLetterPersist {
+    tx_id: String,
+    tx_bytes: String, // base64
+    attempts: usize,
+    last_error: String,
+    reason: DeadLetterReason,
+    first_failed_at: u64,
+    dead_lettered_at: u64,
+}
+
+impl DeadLetterPersist {
+    pub(super) fn from_dead_letter(letter: &DeadLetter) -> Self {
+        Self {
+            tx_id: letter.tx_id.clone(),
+            tx_bytes: base64::encode(&letter.tx_bytes),
+            attempts: letter.attempts,
+            last_error: letter.last_error.clone(),
+            reason: letter.reason,
+            first_failed_at: letter.first_failed_at,
+            dead_lettered_at: letter.dead_lettered_at,
+        }
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    pub(super) fn to_dead_letter(self) -> Result<DeadLetter, String> {
+        let tx_bytes = base64::decode(&self.tx_bytes)
+            .map_err(|e| format!("Failed to decode transaction bytes: {}", e))?;
+
+        Ok(DeadLetter {
+            tx_id: self.tx_id,
+            tx_bytes,
+            attempts: self.attempts,
+            last_error: self.last_error,
+            reason: self.reason,
+            first_failed_at: self.first_failed_at,
+            dead_lettered_at: self.dead_lettered_at,
+        })
+    }
+}
+
 /// Storage errors
 #[derive(Debug, Error)]
 pub enum StorageError {
@@ -630,6 +1065,18 @@ pub enum StorageError {
     CorruptedFile(String),
 }
 
+impl From<crate::storage::StorageError> for StorageError {
+    fn from(e: crate::storage::StorageError) -> Self {
+        use crate::storage::StorageError as Backend;
+        match e {
+            Backend::Serialization(_) | Backend::UnsupportedVersion(_) => {
+                StorageError::DeserializationError(e.to_string())
+            }
+            _ => StorageError::IoError(e.to_string()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -676,6 +1123,124 @@ mod tests {
         assert_eq!(loaded.len(), 0);
     }
 
+    #[test]
+    fn test_save_load_retry_and_confirmation_queues() {
+        let dir = tempdir().unwrap();
+        let storage = QueueStorage::new(dir.path()).unwrap();
+
+        let mut retry = RetryQueue::new();
+        retry
+            .push(RetryItem::new(
+                vec![4, 5, 6],
+                "tx1".to_string(),
+                "timeout".to_string(),
+            ))
+            .unwrap();
+        let mut confirmations = ConfirmationQueue::new();
+        confirmations
+            .push(Confirmation::success([2u8; 32], "sig".to_string()))
+            .unwrap();
+
+        storage.save_retry_queue(&retry).unwrap();
+        storage.save_confirmation_queue(&confirmations).unwrap();
+
+        let loaded_retry = storage.load_retry_queue().unwrap();
+        assert_eq!(loaded_retry.len(), 1);
+        assert_eq!(loaded_retry.peek_next().unwrap().tx_bytes, vec![4, 5, 6]);
+        assert_eq!(storage.load_confirmation_queue().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_wal_replay_and_compaction() {
+        let dir = tempdir().unwrap();
+        let storage = QueueStorage::new(dir.path()).unwrap();
+
+        let tx1 = OutboundTransaction::new("tx1".to_string(), vec![1], vec![], Priority::High);
+        let tx2 = OutboundTransaction::new("tx2".to_string(), vec![2], vec![], Priority::Low);
+        storage.append_wal(&WalRecord::outbound_push(&tx1)).unwrap();
+        storage.append_wal(&WalRecord::outbound_push(&tx2)).unwrap();
+        storage
+            .append_wal(&WalRecord::OutboundRemove {
+                tx_id: "tx1".to_string(),
+            })
+            .unwrap();
+        storage
+            .append_wal(&WalRecord::retry_push(&RetryItem::new(
+                vec![3],
+                "tx3".to_string(),
+                "rpc".to_string(),
+            )))
+            .unwrap();
+
+        // Simulate a torn final record
+        {
+            let mut file = fs::OpenOptions::new()
+                .append(true)
+                .open(dir.path().join("queue.wal"))
+                .unwrap();
+            file.write_all(b"{\"op\":\"outbound_pu").unwrap();
+        }
+
+        // Nothing was snapshotted, so everything comes from the WAL
+        let (outbound, retry, _, _) = storage.load_all().unwrap();
+        assert_eq!(outbound.len(), 1);
+        assert!(outbound.contains("tx2"));
+        assert_eq!(retry.len(), 1);
+
+        // Compaction: snapshot then empty log, same state on reload
+        storage
+            .save_all(&outbound, &retry, &ConfirmationQueue::new(), &[])
+            .unwrap();
+        assert!(storage.read_wal().unwrap().is_empty());
+        let (outbound, retry, _, _) = storage.load_all().unwrap();
+        assert_eq!(outbound.len(), 1);
+        assert_eq!(retry.len(), 1);
+    }
+
+    #[test]
+    fn test_backend_queue_store_wal_and_compaction() {
+        let backend: Arc<dyn StorageBackend> = Arc::new(crate::storage::MemoryBackend::new());
+        let store = BackendQueueStore::new(backend.clone()).unwrap();
+
+        let tx = OutboundTransaction::new("tx1".to_string(), vec![1], vec![], Priority::High);
+        store.append_wal(&WalRecord::outbound_push(&tx)).unwrap();
+        assert_eq!(backend.list("queues/wal/").unwrap().len(), 1);
+
+        // A fresh store over the same backend continues the sequence and replays
+        let store = BackendQueueStore::new(backend.clone()).unwrap();
+        let (outbound, retry, confirmation, received) = store.load_all().unwrap();
+        assert!(outbound.contains("tx1"));
+
+        store
+            .save_all(&outbound, &retry, &confirmation, &received)
+            .unwrap();
+        assert!(backend.list("queues/wal/").unwrap().is_empty());
+        assert_eq!(store.load_all().unwrap().0.len(), 1);
+    }
+    #[test]
+    fn test_loads_unversioned_and_rejects_newer_files() {
+        let dir = tempdir().unwrap();
+        let storage = QueueStorage::new(dir.path()).unwrap();
+
+        // Written by a release that predates the version field
+        fs::write(
+            dir.path().join("confirmation_queue.json"),
+            r#"{"confirmations": [], "saved_at": 0}"#,
+        )
+        .unwrap();
+        assert_eq!(storage.load_confirmation_queue().unwrap().len(), 0);
+
+        fs::write(
+            dir.path().join("confirmation_queue.json"),
+            r#"{"version": 99, "confirmations": [], "saved_at": 0}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            storage.load_confirmation_queue(),
+            Err(StorageError::DeserializationError(_))
+        ));
+    }
+
     #[test]
     fn test_missing_file_returns_empty() {
         let dir = tempdir().unwrap();
@@ -0,0 +1,112 @@
+//! Device role: what part a device plays in the mesh
+//!
+//! - **Originator** creates transactions; its own go out first and relayed
+//!   ones wait.
+//! - **Relay** never submits (so never needs RPC) and carries other devices'
+//!   transactions, keeping them longer.
+//! - **Gateway** submits what it receives (see `ffi::gateway`), so rebroadcasting
+//!   relayed transactions is secondary for it.
+//!
+//! Without a role, outbound priorities stay as requested. The role is also
+//! advertised to nearby devices through [`capability`] flags.
+
+use serde::{Deserialize, Serialize};
+
+use crate::queue::{OutboundTransaction, Priority};
+
+/// Minimum TTL a relay keeps relayed transactions for: 30 minutes
+pub const RELAY_CACHE_TTL_SECS: u64 = 1800;
+
+/// Capability bits hosts put in their BLE advertisement (one byte)
+pub mod capability {
+    /// Creates transactions
+    pub const ORIGINATOR: u8 = 1 << 0;
+    /// Dedicated relay
+    pub const RELAY: u8 = 1 << 1;
+    /// Submits relayed transactions to the network
+    pub const GATEWAY: u8 = 1 << 2;
+    /// Currently has internet connectivity
+    pub const ONLINE: u8 = 1 << 3;
+    /// Reads and sends compact `ble::wire` fragment frames
+    pub const COMPACT_FRAMES: u8 = 1 << 4;
+}
+
+/// What part this device plays in the mesh
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DeviceRole {
+    Originator,
+    Relay,
+    Gateway,
+}
+
+impl DeviceRole {
+    /// Whether this role submits transactions (and so needs an RPC endpoint)
+    pub fn submits(self) -> bool {
+        self != DeviceRole::Relay
+    }
+
+    /// Capability flag for this role
+    pub fn capability(self) -> u8 {
+        match self {
+            DeviceRole::Originator => capability::ORIGINATOR,
+            DeviceRole::Relay => capability::RELAY,
+            DeviceRole::Gateway => capability::GATEWAY,
+        }
+    }
+
+    /// Priority for an outbound transaction; `relayed` is true for transactions
+    /// that came from another device
+    pub fn outbound_priority(self, requested: Priority, relayed: bool) -> Priority {
+        match (self, relayed) {
+            (DeviceRole::Originator, false) => Priority::High,
+            (DeviceRole::Originator, true) | (DeviceRole::Gateway, true) => Priority::Low,
+            (DeviceRole::Relay, true) if requested == Priority::Low => Priority::Normal,
+            _ => requested,
+        }
+    }
+
+    /// Adjust a transaction entering the outbound queue (confirmations untouched)
+    pub fn apply(self, tx: &mut OutboundTransaction) {
+        if tx.is_confirmation {
+            return;
+        }
+        let relayed = tx.hop_count > 0;
+        tx.priority = self.outbound_priority(tx.priority, relayed);
+        if self == DeviceRole::Relay && relayed {
+            tx.ttl_secs = tx.ttl_secs.max(RELAY_CACHE_TTL_SECS);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_priorities_and_relay_cache() {
+        let mut relayed = OutboundTransaction::new("aa".into(), vec![1], vec![], Priority::Low);
+        relayed.hop_count = 2;
+
+        let mut tx = relayed.clone();
+        DeviceRole::Relay.apply(&mut tx);
+        assert_eq!(tx.priority, Priority::Normal);
+        assert_eq!(tx.ttl_secs, RELAY_CACHE_TTL_SECS);
+
+        let mut tx = relayed;
+        DeviceRole::Gateway.apply(&mut tx);
+        assert_eq!(tx.priority, Priority::Low);
+
+        let mut own = OutboundTransaction::new("bb".into(), vec![1], vec![], Priority::Normal);
+        DeviceRole::Originator.apply(&mut own);
+        assert_eq!(own.priority, Priority::High);
+
+        let mut confirmation =
+            OutboundTransaction::new("cc".into(), vec![1], vec![], Priority::Low);
+        confirmation.is_confirmation = true;
+        DeviceRole::Originator.apply(&mut confirmation);
+        assert_eq!(confirmation.priority, Priority::Low);
+
+        assert!(!DeviceRole::Relay.submits());
+    }
+}
@@ -0,0 +1,216 @@
+//! Offline address book
+//!
+//! A small label ↔ pubkey directory (with optional preferred token mints per
+//! contact) so kiosk and wallet UIs can offer a recipient picker without each
+//! platform persisting its own. Labels are unique ignoring case; the whole
+//! book is rewritten atomically to `contacts.json` on every change.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{write_atomic, StorageError};
+
+/// Contacts kept at most
+pub const MAX_CONTACTS: usize = 500;
+/// Longest label, in characters
+pub const MAX_LABEL_CHARS: usize = 64;
+
+const CONTACTS_FILE: &str = "contacts.json";
+
+/// One address book entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Contact {
+    pub label: String,
+    /// Base58 Solana address
+    pub pubkey: String,
+    /// Preferred SPL token mints (base58), most preferred first
+    #[serde(default)]
+    pub mints: Vec<String>,
+    /// Unix timestamp (seconds) of the last change
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+impl Contact {
+    pub fn new(label: impl Into<String>, pubkey: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            pubkey: pubkey.into(),
+            mints: Vec::new(),
+            updated_at: 0,
+        }
+    }
+
+    fn validate(&mut self) -> Result<(), StorageError> {
+        self.label = self.label.trim().to_string();
+        if self.label.is_empty() || self.label.chars().count() > MAX_LABEL_CHARS {
+            return Err(StorageError::InvalidData(format!(
+                "Contact label must be 1-{} characters",
+                MAX_LABEL_CHARS
+            )));
+        }
+        for address in std::iter::once(&self.pubkey).chain(&self.mints) {
+            let valid = bs58::decode(address)
+                .into_vec()
+                .is_ok_and(|bytes| bytes.len() == 32);
+            if !valid {
+                return Err(StorageError::InvalidData(format!(
+                    "Invalid address: {}",
+                    address
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Contacts in their own directory
+pub struct AddressBook {
+    dir: PathBuf,
+    /// Sorted by label (case-insensitive)
+    contacts: Mutex<Vec<Contact>>,
+}
+
+impl AddressBook {
+    /// Open (or create) the address book in `dir`
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .map_err(|e| StorageError::Io(format!("Failed to create address book: {}", e)))?;
+
+        let contacts = match fs::read(dir.join(CONTACTS_FILE)) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| {
+                StorageError::Serialization(format!("Failed to read address book: {}", e))
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                return Err(StorageError::Io(format!(
+                    "Failed to read address book: {}",
+                    e
+                )))
+            }
+        };
+
+        Ok(Self {
+            dir,
+            contacts: Mutex::new(contacts),
+        })
+    }
+
+    /// Add a contact, or replace the one with the same label
+    pub fn save(&self, mut contact: Contact) -> Result<Contact, StorageError> {
+        contact.validate()?;
+        contact.updated_at = crate::util::clock::now_secs();
+
+        let mut contacts = self.contacts.lock();
+        let mut updated = contacts.clone();
+        match position(&updated, &contact.label) {
+            Ok(i) => updated[i] = contact.clone(),
+            Err(i) => {
+                if updated.len() >= MAX_CONTACTS {
+                    return Err(StorageError::InvalidData(format!(
+                        "Address book is full ({} contacts)",
+                        MAX_CONTACTS
+                    )));
+                }
+                updated.insert(i, contact.clone());
+            }
+        }
+        self.persist(&updated)?;
+        *contacts = updated;
+        Ok(contact)
+    }
+
+    /// Remove a contact by label; returns false if there was none
+    pub fn remove(&self, label: &str) -> Result<bool, StorageError> {
+        let mut contacts = self.contacts.lock();
+        let Ok(i) = position(&contacts, label.trim()) else {
+            return Ok(false);
+        };
+        let mut updated = contacts.clone();
+        updated.remove(i);
+        self.persist(&updated)?;
+        *contacts = updated;
+        Ok(true)
+    }
+
+    /// Contact by label (ignoring case)
+    pub fn get(&self, label: &str) -> Option<Contact> {
+        let contacts = self.contacts.lock();
+        position(&contacts, label.trim())
+            .ok()
+            .map(|i| contacts[i].clone())
+    }
+
+    /// Contacts saved under `pubkey`
+    pub fn find_by_pubkey(&self, pubkey: &str) -> Vec<Contact> {
+        let contacts = self.contacts.lock();
+        contacts
+            .iter()
+            .filter(|c| c.pubkey == pubkey)
+            .cloned()
+            .collect()
+    }
+
+    /// All contacts sorted by label
+    pub fn list(&self) -> Vec<Contact> {
+        self.contacts.lock().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.contacts.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn persist(&self, contacts: &[Contact]) -> Result<(), StorageError> {
+        let bytes = serde_json::to_vec(contacts).map_err(|e| {
+            StorageError::Serialization(format!("Failed to serialize address book: {}", e))
+        })?;
+        write_atomic(&self.dir.join(CONTACTS_FILE), &bytes)
+    }
+}
+
+/// Index of `label` in the sorted list, or where it would go
+fn position(contacts: &[Contact], label: &str) -> Result<usize, usize> {
+    let key = label.to_lowercase();
+    contacts.binary_search_by(|c| c.label.to_lowercase().cmp(&key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_contacts_persist_sorted_and_unique_by_label() {
+        let dir = tempdir().unwrap();
+        let alice = bs58::encode([1u8; 32]).into_string();
+        let bob = bs58::encode([2u8; 32]).into_string();
+        {
+            let book = AddressBook::open(dir.path()).unwrap();
+            book.save(Contact::new("bob", &bob)).unwrap();
+            let mut contact = Contact::new(" Alice ", &alice);
+            contact.mints.push(bs58::encode([3u8; 32]).into_string());
+            book.save(contact).unwrap();
+            // Same label in another case replaces the entry
+            book.save(Contact::new("BOB", &alice)).unwrap();
+            assert!(book.save(Contact::new("carol", "not-a-key")).is_err());
+            assert!(book.save(Contact::new("  ", &alice)).is_err());
+        }
+
+        let book = AddressBook::open(dir.path()).unwrap();
+        let labels: Vec<String> = book.list().into_iter().map(|c| c.label).collect();
+        assert_eq!(labels, vec!["Alice", "BOB"]);
+        assert_eq!(book.get("alice").unwrap().mints.len(), 1);
+        assert_eq!(book.find_by_pubkey(&alice).len(), 2);
+        assert!(book.remove("bob").unwrap());
+        assert!(!book.remove("bob").unwrap());
+        assert_eq!(book.len(), 1);
+    }
+}
@@ -0,0 +1,430 @@
+//! Pluggable key-value storage backends
+//!
+//! Secure storage, SQLite-backed queues and the reassembly cache go through
+//! [`StorageBackend`], so hosts can pick plain files, SQLite, or memory without
+//! each subsystem inventing its own on-disk layout.
+//!
+//! Keys are `/`-separated paths (e.g. `queues/outbound.json`). Segments must be
+//! non-empty, must not start with `.`, and must not be `..`.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::StorageError;
+
+/// Suffix of in-flight atomic writes (ignored by `list`)
+const TEMP_SUFFIX: &str = ".tmp";
+/// Redo journal for an in-flight batch (in the backend root)
+const BATCH_JOURNAL: &str = ".batch.journal";
+
+/// A single operation in an atomic batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BatchOp {
+    Put { key: String, value: Vec<u8> },
+    Delete { key: String },
+}
+
+/// Key-value storage used by persistence code
+pub trait StorageBackend: Send + Sync {
+    /// Read a value (`None` if the key does not exist)
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// Durably write a value, replacing any previous one
+    fn put(&self, key: &str, value: &[u8]) -> Result<(), StorageError>;
+
+    /// Remove a key; returns whether it existed
+    fn delete(&self, key: &str) -> Result<bool, StorageError>;
+
+    /// All keys starting with `prefix`, sorted
+    fn list(&self, prefix: &str) -> Result<Vec<String>, StorageError>;
+
+    /// Apply all operations or none of them (also across a crash)
+    fn batch(&self, ops: &[BatchOp]) -> Result<(), StorageError>;
+}
+
+/// Check that `key` is a valid backend key
+pub fn validate_key(key: &str) -> Result<(), StorageError> {
+    let valid = !key.is_empty()
+        && !key.contains('\\')
+        && !key.ends_with(TEMP_SUFFIX)
+        && key
+            .split('/')
+            .all(|segment| !segment.is_empty() && !segment.starts_with('.'));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidKey(key.to_string()))
+    }
+}
+
+/// Files under a root directory, one file per key
+///
+/// Writes go to a temp file that is fsynced and renamed over the target.
+/// Batches are first written as a redo journal, so a crash mid-batch is
+/// completed on the next `open`.
+pub struct FileBackend {
+    root: PathBuf,
+    /// Serializes batches against single writes
+    write_lock: Mutex<()>,
+}
+
+impl FileBackend {
+    /// Open (or create) a file backend rooted at `root`
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)
+            .map_err(|e| StorageError::Io(format!("Failed to create storage directory: {}", e)))?;
+
+        let stale = remove_stale_temp_files(&root);
+        if stale > 0 {
+            tracing::warn!("Removed {} interrupted temp file(s) from storage", stale);
+        }
+
+        let backend = Self {
+            root,
+            write_lock: Mutex::new(()),
+        };
+        backend.recover_batch()?;
+        Ok(backend)
+    }
+
+    /// Root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path_for(&self, key: &str) -> Result<PathBuf, StorageError> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+
+    fn journal_path(&self) -> PathBuf {
+        self.root.join(BATCH_JOURNAL)
+    }
+
+    fn apply(&self, op: &BatchOp) -> Result<(), StorageError> {
+        match op {
+            BatchOp::Put { key, value } => write_atomic(&self.path_for(key)?, value),
+            BatchOp::Delete { key } => remove_file(&self.path_for(key)?).map(|_| ()),
+        }
+    }
+
+    /// Finish a batch interrupted by a crash (journal present = batch committed)
+    fn recover_batch(&self) -> Result<(), StorageError> {
+        let journal = self.journal_path();
+        if !journal.exists() {
+            return Ok(());
+        }
+
+        let bytes = fs::read(&journal)
+            .map_err(|e| StorageError::Io(format!("Failed to read batch journal: {}", e)))?;
+        match bincode1::deserialize::<Vec<BatchOp>>(&bytes) {
+            Ok(ops) => {
+                tracing::warn!("Replaying interrupted storage batch ({} ops)", ops.len());
+                for op in &ops {
+                    self.apply(op)?;
+                }
+            }
+            Err(e) => tracing::warn!("Discarding unreadable batch journal: {}", e),
+        }
+
+        remove_file(&journal)?;
+        Ok(())
+    }
+
+    fn collect_keys(dir: &Path, prefix: &str, keys: &mut Vec<String>) -> Result<(), StorageError> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(StorageError::Io(format!("Failed to list storage: {}", e))),
+        };
+
+        for entry in entries {
+            let entry =
+                entry.map_err(|e| StorageError::Io(format!("Failed to list storage: {}", e)))?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') || name.ends_with(TEMP_SUFFIX) {
+                continue;
+            }
+
+            let key = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            if entry.path().is_dir() {
+                Self::collect_keys(&entry.path(), &key, keys)?;
+            } else {
+                keys.push(key);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl StorageBackend for FileBackend {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        match fs::read(self.path_for(key)?) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(StorageError::Io(format!("Failed to read {}: {}", key, e))),
+        }
+    }
+
+    fn put(&self, key: &str, value: &[u8]) -> Result<(), StorageError> {
+        let path = self.path_for(key)?;
+        let _guard = self.write_lock.lock();
+        write_atomic(&path, value)
+    }
+
+    fn delete(&self, key: &str) -> Result<bool, StorageError> {
+        let path = self.path_for(key)?;
+        let _guard = self.write_lock.lock();
+        remove_file(&path)
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        let mut keys = Vec::new();
+        Self::collect_keys(&self.root, "", &mut keys)?;
+        keys.retain(|key| key.starts_with(prefix));
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn batch(&self, ops: &[BatchOp]) -> Result<(), StorageError> {
+        for op in ops {
+            match op {
+                BatchOp::Put { key, .. } | BatchOp::Delete { key } => validate_key(key)?,
+            }
+        }
+
+        let _guard = self.write_lock.lock();
+        let journal = bincode1::serialize(ops)
+            .map_err(|e| StorageError::Serialization(format!("Failed to encode batch: {}", e)))?;
+
+        // Commit point: once the journal is on disk the batch will be applied
+        write_atomic(&self.journal_path(), &journal)?;
+        for op in ops {
+            self.apply(op)?;
+        }
+        remove_file(&self.journal_path())?;
+        Ok(())
+    }
+}
+
+/// Write `bytes` to `path` via temp file + fsync + rename
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), StorageError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| StorageError::Io(format!("Failed to create directory: {}", e)))?;
+    }
+
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(TEMP_SUFFIX);
+    let temp_path = path.with_file_name(temp_name);
+
+    {
+        let mut file = fs::File::create(&temp_path)
+            .map_err(|e| StorageError::Io(format!("Failed to create temp file: {}", e)))?;
+        file.write_all(bytes)
+            .map_err(|e| StorageError::Io(format!("Failed to write temp file: {}", e)))?;
+        file.sync_all()
+            .map_err(|e| StorageError::Io(format!("Failed to sync temp file: {}", e)))?;
+    }
+
+    fs::rename(&temp_path, path)
+        .map_err(|e| StorageError::Io(format!("Failed to rename temp file: {}", e)))?;
+
+    // Make the rename itself durable (directory entry)
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        if let Ok(dir) = fs::File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+
+    Ok(())
+}
+
+/// Delete temp files left behind by writes interrupted mid-way (recursive)
+///
+/// A leftover temp file never replaced its target, so the target still holds
+/// the previous complete version and the temp file can simply be dropped.
+pub fn remove_stale_temp_files(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            removed += remove_stale_temp_files(&path);
+        } else if entry.file_name().to_string_lossy().ends_with(TEMP_SUFFIX)
+            && fs::remove_file(&path).is_ok()
+        {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+fn remove_file(path: &Path) -> Result<bool, StorageError> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(StorageError::Io(format!(
+            "Failed to delete {}: {}",
+            path.display(),
+            e
+        ))),
+    }
+}
+
+/// In-memory backend (tests and ephemeral nodes)
+#[derive(Default)]
+pub struct MemoryBackend {
+    entries: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StorageBackend for MemoryBackend {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        validate_key(key)?;
+        Ok(self.entries.lock().get(key).cloned())
+    }
+
+    fn put(&self, key: &str, value: &[u8]) -> Result<(), StorageError> {
+        validate_key(key)?;
+        self.entries.lock().insert(key.to_string(), value.to_vec());
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<bool, StorageError> {
+        validate_key(key)?;
+        Ok(self.entries.lock().remove(key).is_some())
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        Ok(self
+            .entries
+            .lock()
+            .keys()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+
+    fn batch(&self, ops: &[BatchOp]) -> Result<(), StorageError> {
+        for op in ops {
+            match op {
+                BatchOp::Put { key, .. } | BatchOp::Delete { key } => validate_key(key)?,
+            }
+        }
+
+        let mut entries = self.entries.lock();
+        for op in ops {
+            match op {
+                BatchOp::Put { key, value } => {
+                    entries.insert(key.clone(), value.clone());
+                }
+                BatchOp::Delete { key } => {
+                    entries.remove(key);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn exercise(backend: &dyn StorageBackend) {
+        backend.put("queues/outbound.json", b"a").unwrap();
+        backend.put("queues/wal/1", b"b").unwrap();
+        backend.put("other", b"c").unwrap();
+
+        assert_eq!(backend.get("queues/outbound.json").unwrap().unwrap(), b"a");
+        assert!(backend.get("missing").unwrap().is_none());
+        assert_eq!(
+            backend.list("queues/").unwrap(),
+            vec!["queues/outbound.json", "queues/wal/1"]
+        );
+
+        backend
+            .batch(&[
+                BatchOp::Put {
+                    key: "queues/outbound.json".to_string(),
+                    value: b"z".to_vec(),
+                },
+                BatchOp::Delete {
+                    key: "queues/wal/1".to_string(),
+                },
+            ])
+            .unwrap();
+        assert_eq!(backend.get("queues/outbound.json").unwrap().unwrap(), b"z");
+        assert!(!backend.delete("queues/wal/1").unwrap());
+        assert!(backend.delete("other").unwrap());
+
+        assert!(backend.put("../escape", b"x").is_err());
+        assert!(backend.put(".hidden", b"x").is_err());
+    }
+
+    #[test]
+    fn test_memory_backend() {
+        exercise(&MemoryBackend::new());
+    }
+
+    #[test]
+    fn test_file_backend() {
+        let dir = tempdir().unwrap();
+        exercise(&FileBackend::open(dir.path()).unwrap());
+    }
+
+    #[test]
+    fn test_open_removes_interrupted_temp_files() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("queues")).unwrap();
+        fs::write(dir.path().join("queues/outbound.json"), b"old").unwrap();
+        fs::write(dir.path().join("queues/outbound.json.tmp"), b"ne").unwrap();
+
+        let backend = FileBackend::open(dir.path()).unwrap();
+        assert!(!dir.path().join("queues/outbound.json.tmp").exists());
+        assert_eq!(
+            backend.get("queues/outbound.json").unwrap().unwrap(),
+            b"old"
+        );
+    }
+
+    #[test]
+    fn test_file_backend_replays_committed_batch() {
+        let dir = tempdir().unwrap();
+        let ops = vec![BatchOp::Put {
+            key: "k".to_string(),
+            value: b"v".to_vec(),
+        }];
+        // Crash after the journal was committed but before it was applied
+        fs::write(
+            dir.path().join(BATCH_JOURNAL),
+            bincode1::serialize(&ops).unwrap(),
+        )
+        .unwrap();
+
+        let backend = FileBackend::open(dir.path()).unwrap();
+        assert_eq!(backend.get("k").unwrap().unwrap(), b"v");
+        assert!(!dir.path().join(BATCH_JOURNAL).exists());
+    }
+}
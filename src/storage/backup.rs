@@ -0,0 +1,300 @@
+//! Backup and restore of all SDK state
+//!
+//! For device replacement: every key in the storage backend (queues, fragment
+//! cache, message history, secure storage) is packed into one archive that is
+//! encrypted under a backup passphrase. Secure-storage values are decrypted
+//! with the old device key while packing and re-encrypted with the new device
+//! key on restore, since platform keys never leave the device.
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+use super::backend::validate_key;
+use super::keystore::wipe;
+use super::{BatchOp, SecureStorage, StorageError, MAGIC_HEADER};
+
+/// Archive format version
+const BACKUP_FORMAT_VERSION: u32 = 1;
+/// Extension of backup archives (never packed into another backup)
+pub const BACKUP_EXTENSION: &str = "pnbak";
+/// Default SHA-256 stretching rounds for the backup passphrase
+const DEFAULT_KDF_ROUNDS: u32 = 200_000;
+const SALT_LEN: usize = 16;
+
+/// On-disk archive envelope
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupFile {
+    version: u32,
+    kdf_rounds: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// One backed-up key
+#[derive(Serialize, Deserialize)]
+struct BackupEntry {
+    key: String,
+    /// Value was secure-storage ciphertext; `data` holds the plaintext
+    secure: bool,
+    data: Vec<u8>,
+}
+
+/// Encrypted archive contents
+#[derive(Serialize, Deserialize)]
+struct BackupPayload {
+    created_at: u64,
+    entries: Vec<BackupEntry>,
+}
+
+/// Summary of a backup or restore
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSummary {
+    /// Unix timestamp (seconds) the archive was created
+    pub created_at: u64,
+    pub entries: usize,
+    pub secure_entries: usize,
+    pub bytes: u64,
+}
+
+/// Pack every key in `storage` into an encrypted archive
+pub fn create_backup(
+    storage: &SecureStorage,
+    passphrase: &str,
+) -> Result<(Vec<u8>, BackupSummary), StorageError> {
+    create_backup_with_rounds(storage, passphrase, DEFAULT_KDF_ROUNDS)
+}
+
+fn create_backup_with_rounds(
+    storage: &SecureStorage,
+    passphrase: &str,
+    kdf_rounds: u32,
+) -> Result<(Vec<u8>, BackupSummary), StorageError> {
+    if passphrase.is_empty() {
+        return Err(StorageError::Encryption(
+            "Backup passphrase must not be empty".to_string(),
+        ));
+    }
+
+    let backend = storage.backend();
+    let mut entries = Vec::new();
+    for key in backend.list("")? {
+        if key.ends_with(&format!(".{}", BACKUP_EXTENSION)) {
+            continue;
+        }
+        let Some(value) = backend.get(&key)? else {
+            continue;
+        };
+        if value.starts_with(MAGIC_HEADER) {
+            if let Ok(plaintext) = storage.decrypt_data(&value) {
+                entries.push(BackupEntry {
+                    key,
+                    secure: true,
+                    data: plaintext,
+                });
+                continue;
+            }
+        }
+        entries.push(BackupEntry {
+            key,
+            secure: false,
+            data: value,
+        });
+    }
+
+    let payload = BackupPayload {
+        created_at: crate::util::clock::now_secs(),
+        entries,
+    };
+    let mut plaintext = bincode1::serialize(&payload)
+        .map_err(|e| StorageError::Serialization(format!("Failed to encode backup: {}", e)))?;
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt, kdf_rounds));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, plaintext.as_ref());
+    wipe(&mut plaintext);
+    let ciphertext =
+        ciphertext.map_err(|e| StorageError::Encryption(format!("Encryption failed: {}", e)))?;
+
+    let file = BackupFile {
+        version: BACKUP_FORMAT_VERSION,
+        kdf_rounds,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+    let archive = serde_json::to_vec(&file)
+        .map_err(|e| StorageError::Serialization(format!("Failed to encode backup: {}", e)))?;
+
+    let summary = summarize(&payload, archive.len());
+    tracing::info!(
+        "🗄️ Created backup: {} entries ({} secure), {} bytes",
+        summary.entries,
+        summary.secure_entries,
+        summary.bytes
+    );
+    Ok((archive, summary))
+}
+
+/// Replace the contents of `storage` with an archive from `create_backup`
+///
+/// Keys not present in the archive are deleted, and the whole restore is one
+/// atomic batch. Nothing is written if the passphrase is wrong.
+pub fn restore_backup(
+    storage: &SecureStorage,
+    archive: &[u8],
+    passphrase: &str,
+) -> Result<BackupSummary, StorageError> {
+    let file: BackupFile = serde_json::from_slice(archive)
+        .map_err(|e| StorageError::Serialization(format!("Not a PolliNet backup: {}", e)))?;
+    if file.version > BACKUP_FORMAT_VERSION {
+        return Err(StorageError::UnsupportedVersion(format!(
+            "backup version {} is newer than supported version {}",
+            file.version, BACKUP_FORMAT_VERSION
+        )));
+    }
+
+    let decode = |field: &str| {
+        BASE64
+            .decode(field)
+            .map_err(|e| StorageError::Serialization(format!("Corrupt backup: {}", e)))
+    };
+    let salt = decode(&file.salt)?;
+    let nonce = decode(&file.nonce)?;
+    let ciphertext = decode(&file.ciphertext)?;
+    if nonce.len() != 12 {
+        return Err(StorageError::Serialization(
+            "Corrupt backup: bad nonce length".to_string(),
+        ));
+    }
+
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt, file.kdf_rounds));
+    let mut plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| {
+            StorageError::Decryption("Wrong passphrase or corrupted backup".to_string())
+        })?;
+    let payload = bincode1::deserialize::<BackupPayload>(&plaintext);
+    wipe(&mut plaintext);
+    let mut payload =
+        payload.map_err(|e| StorageError::Serialization(format!("Corrupt backup: {}", e)))?;
+
+    let backend = storage.backend();
+    let restored: HashSet<&str> = payload.entries.iter().map(|e| e.key.as_str()).collect();
+    let mut ops = Vec::with_capacity(payload.entries.len());
+    for key in backend.list("")? {
+        if !restored.contains(key.as_str()) && !key.ends_with(&format!(".{}", BACKUP_EXTENSION)) {
+            ops.push(BatchOp::Delete { key });
+        }
+    }
+    for entry in &payload.entries {
+        validate_key(&entry.key)?;
+        let value = if entry.secure {
+            storage.encrypt_data(&entry.data)?
+        } else {
+            entry.data.clone()
+        };
+        ops.push(BatchOp::Put {
+            key: entry.key.clone(),
+            value,
+        });
+    }
+    let result = backend.batch(&ops);
+
+    for entry in payload.entries.iter_mut().filter(|e| e.secure) {
+        wipe(&mut entry.data);
+    }
+    result?;
+
+    let summary = summarize(&payload, archive.len());
+    tracing::info!(
+        "🗄️ Restored backup from {}: {} entries ({} secure)",
+        summary.created_at,
+        summary.entries,
+        summary.secure_entries
+    );
+    Ok(summary)
+}
+
+fn summarize(payload: &BackupPayload, bytes: usize) -> BackupSummary {
+    BackupSummary {
+        created_at: payload.created_at,
+        entries: payload.entries.len(),
+        secure_entries: payload.entries.iter().filter(|e| e.secure).count(),
+        bytes: bytes as u64,
+    }
+}
+
+/// Stretch the passphrase with salted, iterated SHA-256
+fn derive_key(passphrase: &str, salt: &[u8], rounds: u32) -> Key<Aes256Gcm> {
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(passphrase.as_bytes());
+    let mut digest: [u8; 32] = hasher.finalize().into();
+    for _ in 1..rounds.max(1) {
+        let mut hasher = Sha256::new();
+        hasher.update(digest);
+        hasher.update(salt);
+        digest = hasher.finalize().into();
+    }
+    let key = *Key::<Aes256Gcm>::from_slice(&digest);
+    wipe(&mut digest);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{MemoryBackend, PlatformKeyProvider, StorageBackend};
+    use std::sync::Arc;
+
+    fn storage(key: u8) -> (Arc<MemoryBackend>, SecureStorage) {
+        let backend = Arc::new(MemoryBackend::new());
+        let provider = PlatformKeyProvider::from_bytes(&[key; 32]).unwrap();
+        let storage = SecureStorage::with_backend(backend.clone(), Arc::new(provider));
+        (backend, storage)
+    }
+
+    #[test]
+    fn test_restore_onto_new_device_key() {
+        let (_, old) = storage(1);
+        old.backend()
+            .put("queues/outbound_queue.json", b"{}")
+            .unwrap();
+        old.put_encrypted("secrets/identity", b"device secret")
+            .unwrap();
+        let (archive, summary) = create_backup_with_rounds(&old, "pass", 10).unwrap();
+        assert_eq!(summary.entries, 2);
+        assert_eq!(summary.secure_entries, 1);
+
+        let (backend, new) = storage(2);
+        backend.put("stale/key", b"x").unwrap();
+        assert!(matches!(
+            restore_backup(&new, &archive, "wrong"),
+            Err(StorageError::Decryption(_))
+        ));
+        assert!(backend.get("stale/key").unwrap().is_some());
+
+        restore_backup(&new, &archive, "pass").unwrap();
+        assert!(backend.get("stale/key").unwrap().is_none());
+        assert_eq!(
+            backend.get("queues/outbound_queue.json").unwrap().unwrap(),
+            b"{}"
+        );
+        assert_eq!(
+            new.get_decrypted("secrets/identity").unwrap().unwrap(),
+            b"device secret"
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Storage key providers
+//!
+//! The AES-256-GCM key used by [`SecureStorage`](super::SecureStorage) comes from
+//! a [`KeyProvider`]. On mobile the key is generated and protected by the
+//! platform (Android Keystore / iOS Keychain) and handed to Rust as raw bytes
+//! through FFI, so nothing is derived in Rust and no key material touches disk.
+//! The passphrase provider keeps the SHA-256 derivation for CLI/server use.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use sha2::{Digest, Sha256};
+
+use super::StorageError;
+
+/// Length of an AES-256 key
+pub const STORAGE_KEY_LEN: usize = 32;
+
+/// Source of the secure storage encryption key
+pub trait KeyProvider: Send + Sync {
+    /// Return the 256-bit storage key
+    fn storage_key(&self) -> Result<[u8; STORAGE_KEY_LEN], StorageError>;
+
+    /// Short label for logs (never the key itself)
+    fn describe(&self) -> &'static str;
+}
+
+/// Raw key bytes supplied by the platform keystore
+pub struct PlatformKeyProvider {
+    key: [u8; STORAGE_KEY_LEN],
+}
+
+impl PlatformKeyProvider {
+    /// Wrap raw key bytes (must be exactly 32 bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StorageError> {
+        let key: [u8; STORAGE_KEY_LEN] = bytes.try_into().map_err(|_| {
+            StorageError::Encryption(format!(
+                "Platform key must be {} bytes, got {}",
+                STORAGE_KEY_LEN,
+                bytes.len()
+            ))
+        })?;
+        Ok(Self { key })
+    }
+
+    /// Decode a base64 key as passed in `SdkConfig.platformKey`
+    pub fn from_base64(encoded: &str) -> Result<Self, StorageError> {
+        let mut bytes = BASE64
+            .decode(encoded.trim())
+            .map_err(|e| StorageError::Encryption(format!("Invalid platform key: {}", e)))?;
+        let provider = Self::from_bytes(&bytes);
+        wipe(&mut bytes);
+        provider
+    }
+}
+
+impl KeyProvider for PlatformKeyProvider {
+    fn storage_key(&self) -> Result<[u8; STORAGE_KEY_LEN], StorageError> {
+        Ok(self.key)
+    }
+
+    fn describe(&self) -> &'static str {
+        "platform keystore"
+    }
+}
+
+impl Drop for PlatformKeyProvider {
+    fn drop(&mut self) {
+        wipe(&mut self.key);
+    }
+}
+
+/// Key derived from a passphrase with SHA-256 (CLI/server fallback)
+pub struct PassphraseKeyProvider {
+    passphrase: String,
+}
+
+impl PassphraseKeyProvider {
+    pub fn new(passphrase: String) -> Self {
+        Self { passphrase }
+    }
+
+    /// Use `passphrase`, falling back to the `POLLINET_ENCRYPTION_KEY` env var
+    pub fn from_option_or_env(passphrase: Option<String>) -> Result<Self, StorageError> {
+        passphrase
+            .or_else(|| std::env::var("POLLINET_ENCRYPTION_KEY").ok())
+            .map(Self::new)
+            .ok_or_else(|| {
+                StorageError::Encryption(
+                    "POLLINET_ENCRYPTION_KEY must be set — no insecure fallback allowed"
+                        .to_string(),
+                )
+            })
+    }
+}
+
+impl KeyProvider for PassphraseKeyProvider {
+    fn storage_key(&self) -> Result<[u8; STORAGE_KEY_LEN], StorageError> {
+        let mut hasher = Sha256::new();
+        hasher.update(self.passphrase.as_bytes());
+        Ok(hasher.finalize().into())
+    }
+
+    fn describe(&self) -> &'static str {
+        "passphrase"
+    }
+}
+
+impl Drop for PassphraseKeyProvider {
+    fn drop(&mut self) {
+        // SAFETY: zero bytes are valid UTF-8
+        wipe(unsafe { self.passphrase.as_bytes_mut() });
+    }
+}
+
+/// Overwrite key material so it doesn't linger in freed memory
+pub(super) fn wipe(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        // SAFETY: `byte` is a valid, aligned &mut u8
+        unsafe { std::ptr::write_volatile(byte, 0) };
+    }
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_platform_key_length_checked() {
+        assert!(PlatformKeyProvider::from_bytes(&[1u8; 16]).is_err());
+        let provider = PlatformKeyProvider::from_base64(&BASE64.encode([7u8; 32])).unwrap();
+        assert_eq!(provider.storage_key().unwrap(), [7u8; 32]);
+    }
+
+    #[test]
+    fn test_passphrase_matches_sha256() {
+        let provider = PassphraseKeyProvider::new("secret".to_string());
+        let expected: [u8; 32] = Sha256::digest(b"secret").into();
+        assert_eq!(provider.storage_key().unwrap(), expected);
+    }
+}
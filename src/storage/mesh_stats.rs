@@ -0,0 +1,296 @@
+//! Persistent mesh activity statistics
+//!
+//! Counts what this device did for the mesh (transactions originated,
+//! relayed and submitted, peers met, bytes over the radio, how far its
+//! transactions travelled to a gateway) in hourly buckets, so apps can
+//! report activity over the last hour, day or month. Buckets are kept for
+//! [`STATS_RETENTION_SECS`] in `mesh_stats.json`, written at most every
+//! [`STATS_FLUSH_INTERVAL_SECS`] (and on [`MeshStats::flush`]), so a crash
+//! loses a minute of counts at most.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{write_atomic, StorageError};
+use crate::util::clock;
+
+/// Width of one bucket
+pub const STATS_BUCKET_SECS: u64 = 60 * 60;
+/// Buckets older than this are dropped (30 days)
+pub const STATS_RETENTION_SECS: u64 = 30 * 24 * 60 * 60;
+/// Minimum time between writes of the stats file
+pub const STATS_FLUSH_INTERVAL_SECS: u64 = 60;
+
+const STATS_FILE: &str = "mesh_stats.json";
+/// Distinct peers remembered per bucket
+const MAX_PEERS_PER_BUCKET: usize = 256;
+/// Transaction ids remembered so a re-queued transaction counts once
+const MAX_COUNTED_TX_IDS: usize = 1024;
+
+/// Something worth counting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeshStatsEvent<'a> {
+    /// A transaction created on this device was queued (hex tx id)
+    Originated(&'a str),
+    /// A transaction received from a peer was queued to carry on
+    Relayed(&'a str),
+    /// This device submitted a transaction to the network
+    Submitted,
+    /// A submission attempt failed
+    SubmitFailed,
+    /// A gateway confirmed submitting a transaction this device originated,
+    /// `hops` devices away
+    Delivered {
+        hops: u8,
+    },
+    PeerSeen(&'a str),
+    BytesSent(usize),
+    BytesReceived(usize),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct StatsBucket {
+    /// Unix seconds the bucket starts at
+    start: u64,
+    originated: u64,
+    relayed: u64,
+    submitted: u64,
+    submit_failures: u64,
+    delivered: u64,
+    gateway_hops: u64,
+    bytes_sent: u64,
+    bytes_received: u64,
+    peers: BTreeSet<String>,
+}
+
+/// Activity over a time window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeshStatsSummary {
+    pub window_secs: u64,
+    /// Unix seconds of the oldest bucket included
+    pub since: u64,
+    pub transactions_originated: u64,
+    pub transactions_relayed: u64,
+    pub transactions_submitted: u64,
+    pub submit_failures: u64,
+    /// Originated transactions a gateway reported submitting
+    pub transactions_delivered: u64,
+    pub unique_peers: usize,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// Mean hops from this device to the gateway that submitted its
+    /// transactions (`None` before the first delivery)
+    pub avg_hops_to_gateway: Option<f64>,
+    /// `submitted / (submitted + failures)`
+    pub submit_success_rate: Option<f64>,
+    /// `delivered / originated`
+    pub delivery_rate: Option<f64>,
+}
+
+struct StatsState {
+    /// Buckets oldest first
+    buckets: VecDeque<StatsBucket>,
+    counted: VecDeque<String>,
+    dirty: bool,
+    flushed_at: u64,
+}
+
+/// Mesh statistics in their own directory
+pub struct MeshStats {
+    dir: PathBuf,
+    state: Mutex<StatsState>,
+}
+
+impl MeshStats {
+    /// Open (or create) the statistics; an unreadable file starts fresh
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .map_err(|e| StorageError::Io(format!("Failed to create mesh stats: {}", e)))?;
+
+        let buckets = match fs::read(dir.join(STATS_FILE)) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                tracing::warn!("⚠️ Discarding unreadable mesh stats: {}", e);
+                VecDeque::new()
+            }),
+            Err(_) => VecDeque::new(),
+        };
+
+        Ok(Self {
+            dir,
+            state: Mutex::new(StatsState {
+                buckets,
+                counted: VecDeque::new(),
+                dirty: false,
+                flushed_at: clock::now_secs(),
+            }),
+        })
+    }
+
+    /// Directory holding the stats file
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Count an event in the current bucket
+    pub fn record(&self, event: MeshStatsEvent<'_>) -> Result<(), StorageError> {
+        let mut state = self.state.lock();
+        if let MeshStatsEvent::Originated(tx_id) | MeshStatsEvent::Relayed(tx_id) = event {
+            if state.counted.iter().any(|id| id == tx_id) {
+                return Ok(());
+            }
+            if state.counted.len() >= MAX_COUNTED_TX_IDS {
+                state.counted.pop_front();
+            }
+            state.counted.push_back(tx_id.to_string());
+        }
+
+        let now = clock::now_secs();
+        let bucket = current_bucket(&mut state.buckets, now);
+        match event {
+            MeshStatsEvent::Originated(_) => bucket.originated += 1,
+            MeshStatsEvent::Relayed(_) => bucket.relayed += 1,
+            MeshStatsEvent::Submitted => bucket.submitted += 1,
+            MeshStatsEvent::SubmitFailed => bucket.submit_failures += 1,
+            MeshStatsEvent::Delivered { hops } => {
+                bucket.delivered += 1;
+                bucket.gateway_hops += hops as u64;
+            }
+            MeshStatsEvent::PeerSeen(peer_id) => {
+                if bucket.peers.len() < MAX_PEERS_PER_BUCKET {
+                    bucket.peers.insert(peer_id.to_string());
+                }
+            }
+            MeshStatsEvent::BytesSent(n) => bucket.bytes_sent += n as u64,
+            MeshStatsEvent::BytesReceived(n) => bucket.bytes_received += n as u64,
+        }
+        state.dirty = true;
+
+        if now.saturating_sub(state.flushed_at) >= STATS_FLUSH_INTERVAL_SECS {
+            self.write(&mut state, now)?;
+        }
+        Ok(())
+    }
+
+    /// Totals over the last `window_secs` (whole buckets)
+    pub fn summary(&self, window_secs: u64) -> MeshStatsSummary {
+        let state = self.state.lock();
+        let now = clock::now_secs();
+        let since = bucket_start(now.saturating_sub(window_secs));
+        let buckets: Vec<&StatsBucket> =
+            state.buckets.iter().filter(|b| b.start >= since).collect();
+        let sum = |field: fn(&StatsBucket) -> u64| buckets.iter().map(|b| field(b)).sum::<u64>();
+
+        let originated = sum(|b| b.originated);
+        let submitted = sum(|b| b.submitted);
+        let submit_failures = sum(|b| b.submit_failures);
+        let delivered = sum(|b| b.delivered);
+        let peers: HashSet<&String> = buckets.iter().flat_map(|b| &b.peers).collect();
+        let ratio = |part: u64, whole: u64| (whole > 0).then(|| part as f64 / whole as f64);
+
+        MeshStatsSummary {
+            window_secs,
+            since,
+            transactions_originated: originated,
+            transactions_relayed: sum(|b| b.relayed),
+            transactions_submitted: submitted,
+            submit_failures,
+            transactions_delivered: delivered,
+            unique_peers: peers.len(),
+            bytes_sent: sum(|b| b.bytes_sent),
+            bytes_received: sum(|b| b.bytes_received),
+            avg_hops_to_gateway: ratio(sum(|b| b.gateway_hops), delivered),
+            submit_success_rate: ratio(submitted, submitted + submit_failures),
+            delivery_rate: ratio(delivered.min(originated), originated),
+        }
+    }
+
+    /// Write pending counts to disk
+    pub fn flush(&self) -> Result<(), StorageError> {
+        let mut state = self.state.lock();
+        if !state.dirty {
+            return Ok(());
+        }
+        self.write(&mut state, clock::now_secs())
+    }
+
+    fn write(&self, state: &mut StatsState, now: u64) -> Result<(), StorageError> {
+        let oldest = bucket_start(now.saturating_sub(STATS_RETENTION_SECS));
+        while state.buckets.front().is_some_and(|b| b.start < oldest) {
+            state.buckets.pop_front();
+        }
+        let bytes = serde_json::to_vec(&state.buckets).map_err(|e| {
+            StorageError::Serialization(format!("Failed to serialize mesh stats: {}", e))
+        })?;
+        write_atomic(&self.dir.join(STATS_FILE), &bytes)?;
+        state.dirty = false;
+        state.flushed_at = now;
+        Ok(())
+    }
+}
+
+fn bucket_start(secs: u64) -> u64 {
+    secs - secs % STATS_BUCKET_SECS
+}
+
+/// Bucket for `now`, appended if it does not exist yet
+fn current_bucket(buckets: &mut VecDeque<StatsBucket>, now: u64) -> &mut StatsBucket {
+    let start = bucket_start(now);
+    // A clock that moved backwards keeps counting into the newest bucket
+    if buckets.back().is_none_or(|b| b.start < start) {
+        buckets.push_back(StatsBucket {
+            start,
+            ..Default::default()
+        });
+    }
+    buckets.back_mut().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::clock::{with_clock, ManualClock};
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_stats_windowed_and_survive_reopen() {
+        let dir = tempdir().unwrap();
+        let clock = Arc::new(ManualClock::new(1_700_000_000_000));
+        with_clock(clock.clone(), || {
+            let stats = MeshStats::open(dir.path()).unwrap();
+            stats.record(MeshStatsEvent::Originated("aa")).unwrap();
+            stats.record(MeshStatsEvent::Originated("aa")).unwrap();
+            stats.record(MeshStatsEvent::PeerSeen("peer-a")).unwrap();
+            stats.record(MeshStatsEvent::Delivered { hops: 3 }).unwrap();
+
+            clock.advance_secs(2 * STATS_BUCKET_SECS as i64);
+            stats.record(MeshStatsEvent::Relayed("bb")).unwrap();
+            stats.record(MeshStatsEvent::PeerSeen("peer-a")).unwrap();
+            stats.record(MeshStatsEvent::PeerSeen("peer-b")).unwrap();
+            stats.record(MeshStatsEvent::Submitted).unwrap();
+            stats.record(MeshStatsEvent::SubmitFailed).unwrap();
+            stats.record(MeshStatsEvent::BytesSent(512)).unwrap();
+            stats.flush().unwrap();
+
+            let recent = stats.summary(STATS_BUCKET_SECS);
+            assert_eq!(recent.transactions_originated, 0);
+            assert_eq!(recent.transactions_relayed, 1);
+            assert_eq!(recent.unique_peers, 2);
+            assert_eq!(recent.submit_success_rate, Some(0.5));
+
+            let reopened = MeshStats::open(dir.path()).unwrap();
+            let day = reopened.summary(24 * 60 * 60);
+            assert_eq!(day.transactions_originated, 1);
+            assert_eq!(day.unique_peers, 2);
+            assert_eq!(day.bytes_sent, 512);
+            assert_eq!(day.avg_hops_to_gateway, Some(3.0));
+            assert_eq!(day.delivery_rate, Some(1.0));
+        });
+    }
+}
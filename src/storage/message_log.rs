@@ -0,0 +1,290 @@
+//! Persistent mesh message log
+//!
+//! Text and control messages exchanged over the mesh are appended as JSON
+//! lines to `current.log`. When it reaches the size limit it is rotated to a
+//! numbered archive, and only the newest archives are kept, so history survives
+//! restarts without unbounded growth.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use super::StorageError;
+
+/// Default size at which `current.log` is rotated
+pub const DEFAULT_MAX_LOG_FILE_BYTES: u64 = 256 * 1024;
+/// Default number of log files kept (current + archives)
+pub const DEFAULT_MAX_LOG_FILES: usize = 8;
+
+const CURRENT_LOG: &str = "current.log";
+const ARCHIVE_PREFIX: &str = "archive-";
+const LOG_EXTENSION: &str = ".log";
+
+/// Message direction relative to this device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageDirection {
+    Inbound,
+    Outbound,
+}
+
+/// Kind of logged message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageKind {
+    Text,
+    Control,
+    /// Status change (`body`) of the message `message_id`, e.g. `DELIVERED`
+    Receipt,
+}
+
+/// One logged mesh message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoggedMessage {
+    /// Unix timestamp (seconds)
+    pub timestamp: u64,
+    /// Remote peer the message was exchanged with
+    pub peer_id: String,
+    pub direction: MessageDirection,
+    pub kind: MessageKind,
+    /// Message text (control messages: a short description or JSON)
+    pub body: String,
+    /// Mesh message id, when the message has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+}
+
+impl LoggedMessage {
+    pub fn new(
+        peer_id: impl Into<String>,
+        direction: MessageDirection,
+        kind: MessageKind,
+        body: impl Into<String>,
+    ) -> Self {
+        Self {
+            timestamp: crate::util::clock::now_secs(),
+            peer_id: peer_id.into(),
+            direction,
+            kind,
+            body: body.into(),
+            message_id: None,
+        }
+    }
+
+    pub fn with_message_id(mut self, message_id: impl Into<String>) -> Self {
+        self.message_id = Some(message_id.into());
+        self
+    }
+}
+
+struct LogState {
+    file: Option<fs::File>,
+    size: u64,
+}
+
+/// Size-rotated message log in its own directory
+pub struct MessageLog {
+    dir: PathBuf,
+    max_file_bytes: u64,
+    max_files: usize,
+    state: Mutex<LogState>,
+}
+
+impl MessageLog {
+    /// Open (or create) a message log with default rotation limits
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, StorageError> {
+        Self::with_limits(dir, DEFAULT_MAX_LOG_FILE_BYTES, DEFAULT_MAX_LOG_FILES)
+    }
+
+    /// Open with explicit rotation limits (`max_files` includes the current file)
+    pub fn with_limits(
+        dir: impl AsRef<Path>,
+        max_file_bytes: u64,
+        max_files: usize,
+    ) -> Result<Self, StorageError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .map_err(|e| StorageError::Io(format!("Failed to create message log: {}", e)))?;
+
+        let size = fs::metadata(dir.join(CURRENT_LOG))
+            .map(|m| m.len())
+            .unwrap_or(0);
+
+        Ok(Self {
+            dir,
+            max_file_bytes,
+            max_files: max_files.max(1),
+            state: Mutex::new(LogState { file: None, size }),
+        })
+    }
+
+    /// Directory holding the log files
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Append a message, rotating first if the current file is full
+    pub fn append(&self, message: &LoggedMessage) -> Result<(), StorageError> {
+        let mut line = serde_json::to_vec(message).map_err(|e| {
+            StorageError::Serialization(format!("Failed to serialize message: {}", e))
+        })?;
+        line.push(b'\n');
+
+        let mut state = self.state.lock();
+        if state.size > 0 && state.size + line.len() as u64 > self.max_file_bytes {
+            self.rotate(&mut state)?;
+        }
+
+        if state.file.is_none() {
+            let file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.dir.join(CURRENT_LOG))
+                .map_err(|e| StorageError::Io(format!("Failed to open message log: {}", e)))?;
+            state.file = Some(file);
+        }
+        if let Some(file) = state.file.as_mut() {
+            file.write_all(&line)
+                .map_err(|e| StorageError::Io(format!("Failed to append message: {}", e)))?;
+        }
+        state.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Archived log files, oldest first
+    fn archives(&self) -> Vec<(u64, PathBuf)> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut archives: Vec<(u64, PathBuf)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let seq = name
+                    .strip_prefix(ARCHIVE_PREFIX)?
+                    .strip_suffix(LOG_EXTENSION)?
+                    .parse()
+                    .ok()?;
+                Some((seq, entry.path()))
+            })
+            .collect();
+        archives.sort_by_key(|(seq, _)| *seq);
+        archives
+    }
+
+    fn rotate(&self, state: &mut LogState) -> Result<(), StorageError> {
+        state.file = None;
+
+        let mut archives = self.archives();
+        let next_seq = archives.last().map_or(0, |(seq, _)| seq + 1);
+        let archive = self.dir.join(format!(
+            "{}{:010}{}",
+            ARCHIVE_PREFIX, next_seq, LOG_EXTENSION
+        ));
+        fs::rename(self.dir.join(CURRENT_LOG), &archive)
+            .map_err(|e| StorageError::Io(format!("Failed to rotate message log: {}", e)))?;
+        archives.push((next_seq, archive));
+        state.size = 0;
+
+        // Keep max_files - 1 archives alongside the new current file
+        let excess = archives.len().saturating_sub(self.max_files - 1);
+        for (_, path) in archives.into_iter().take(excess) {
+            if let Err(e) = fs::remove_file(&path) {
+                tracing::warn!("Failed to prune {}: {}", path.display(), e);
+            }
+        }
+
+        tracing::debug!("Rotated message log (archive {})", next_seq);
+        Ok(())
+    }
+
+    /// Read every message accepted by `filter`, oldest first
+    fn read_matching(&self, filter: impl Fn(&LoggedMessage) -> bool) -> Vec<LoggedMessage> {
+        let mut paths: Vec<PathBuf> = self.archives().into_iter().map(|(_, p)| p).collect();
+        paths.push(self.dir.join(CURRENT_LOG));
+
+        let mut messages = Vec::new();
+        for path in paths {
+            let Ok(file) = fs::File::open(&path) else {
+                continue;
+            };
+            for line in BufReader::new(file).lines().map_while(Result::ok) {
+                // A torn final line (crash mid-append) is skipped
+                if let Ok(message) = serde_json::from_str::<LoggedMessage>(&line) {
+                    if filter(&message) {
+                        messages.push(message);
+                    }
+                }
+            }
+        }
+        messages
+    }
+
+    /// Last `limit` messages exchanged with `peer_id`, oldest first
+    pub fn conversation(&self, peer_id: &str, limit: usize) -> Vec<LoggedMessage> {
+        let _state = self.state.lock();
+        let mut messages = self.read_matching(|m| m.peer_id == peer_id);
+        let skip = messages.len().saturating_sub(limit);
+        messages.drain(..skip);
+        messages
+    }
+
+    /// Peers with at least one logged message
+    pub fn peers(&self) -> Vec<String> {
+        let _state = self.state.lock();
+        let mut peers: Vec<String> = self
+            .read_matching(|_| true)
+            .into_iter()
+            .map(|m| m.peer_id)
+            .collect();
+        peers.sort();
+        peers.dedup();
+        peers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn text(peer: &str, body: &str) -> LoggedMessage {
+        LoggedMessage::new(peer, MessageDirection::Inbound, MessageKind::Text, body)
+    }
+
+    #[test]
+    fn test_conversation_survives_reopen() {
+        let dir = tempdir().unwrap();
+        {
+            let log = MessageLog::open(dir.path()).unwrap();
+            log.append(&text("alice", "hi")).unwrap();
+            log.append(&text("bob", "yo")).unwrap();
+            log.append(&text("alice", "there")).unwrap();
+        }
+
+        let log = MessageLog::open(dir.path()).unwrap();
+        let history = log.conversation("alice", 10);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].body, "there");
+        assert_eq!(log.conversation("alice", 1)[0].body, "there");
+        assert_eq!(log.peers(), vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn test_rotation_keeps_newest_files() {
+        let dir = tempdir().unwrap();
+        let log = MessageLog::with_limits(dir.path(), 200, 3).unwrap();
+        for i in 0..40 {
+            log.append(&text("alice", &format!("message {}", i)))
+                .unwrap();
+        }
+
+        assert_eq!(log.archives().len(), 2);
+        let history = log.conversation("alice", 1000);
+        assert!(history.len() < 40);
+        assert_eq!(history.last().unwrap().body, "message 39");
+    }
+}
@@ -0,0 +1,143 @@
+//! On-disk format versioning
+//!
+//! Persisted JSON documents carry a top-level `version` field. A [`Migrator`]
+//! upgrades documents written by older releases one step at a time before they
+//! are deserialized, so a format change never strands data already on a device.
+//! Documents from a *newer* release are rejected instead of misread.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::StorageError;
+
+/// Upgrade a document from `from` to `from + 1` (the version field is set by the framework)
+pub type MigrationFn = fn(Value) -> Result<Value, String>;
+
+/// One upgrade step
+pub struct Migration {
+    /// Version this step upgrades from
+    pub from: u32,
+    pub migrate: MigrationFn,
+}
+
+/// Versioned format of one kind of persisted document
+pub struct Migrator {
+    name: &'static str,
+    current: u32,
+    migrations: &'static [Migration],
+}
+
+impl Migrator {
+    pub const fn new(name: &'static str, current: u32, migrations: &'static [Migration]) -> Self {
+        Self {
+            name,
+            current,
+            migrations,
+        }
+    }
+
+    /// Version written by this release
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// Upgrade `value` to the current version (a missing `version` counts as 0)
+    pub fn migrate(&self, mut value: Value) -> Result<Value, StorageError> {
+        let mut version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .map(|v| v as u32)
+            .unwrap_or(0);
+
+        if version > self.current {
+            return Err(StorageError::UnsupportedVersion(format!(
+                "{} version {} is newer than supported version {}",
+                self.name, version, self.current
+            )));
+        }
+
+        while version < self.current {
+            let step = self
+                .migrations
+                .iter()
+                .find(|m| m.from == version)
+                .ok_or_else(|| {
+                    StorageError::UnsupportedVersion(format!(
+                        "no migration for {} from version {}",
+                        self.name, version
+                    ))
+                })?;
+
+            value = (step.migrate)(value).map_err(|e| {
+                StorageError::Serialization(format!(
+                    "Failed to migrate {} from version {}: {}",
+                    self.name, version, e
+                ))
+            })?;
+            version += 1;
+            if let Some(obj) = value.as_object_mut() {
+                obj.insert("version".to_string(), Value::from(version));
+            }
+            tracing::info!("⬆️ Migrated {} to version {}", self.name, version);
+        }
+
+        Ok(value)
+    }
+
+    /// Parse JSON bytes, migrate, then deserialize into `T`
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, StorageError> {
+        let value: Value = serde_json::from_slice(bytes).map_err(|e| {
+            StorageError::Serialization(format!("Failed to parse {}: {}", self.name, e))
+        })?;
+        serde_json::from_value(self.migrate(value)?).map_err(|e| {
+            StorageError::Serialization(format!("Failed to deserialize {}: {}", self.name, e))
+        })
+    }
+}
+
+/// Step for formats whose first release only lacked the `version` field
+pub fn add_version_field(value: Value) -> Result<Value, String> {
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rename_items(mut value: Value) -> Result<Value, String> {
+        let obj = value.as_object_mut().ok_or("not an object")?;
+        let items = obj.remove("entries").ok_or("missing entries")?;
+        obj.insert("items".to_string(), items);
+        Ok(value)
+    }
+
+    static FORMAT: Migrator = Migrator::new(
+        "test document",
+        2,
+        &[
+            Migration {
+                from: 0,
+                migrate: add_version_field,
+            },
+            Migration {
+                from: 1,
+                migrate: rename_items,
+            },
+        ],
+    );
+
+    #[test]
+    fn test_migrates_unversioned_document() {
+        let migrated = FORMAT.migrate(json!({ "entries": [1, 2] })).unwrap();
+        assert_eq!(migrated, json!({ "version": 2, "items": [1, 2] }));
+    }
+
+    #[test]
+    fn test_rejects_newer_version() {
+        assert!(matches!(
+            FORMAT.migrate(json!({ "version": 3 })),
+            Err(StorageError::UnsupportedVersion(_))
+        ));
+    }
+}
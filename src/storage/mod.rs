@@ -0,0 +1,248 @@
+//! Secure storage for encrypted offline data
+//!
+//! Provides encrypted persistence for sensitive data using AES-256-GCM
+
+pub mod address_book;
+pub mod backend;
+pub mod backup;
+pub mod keystore;
+pub mod mesh_stats;
+pub mod message_log;
+pub mod migration;
+pub mod payment_receipts;
+pub mod quota;
+pub mod relay_receipts;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod tx_history;
+#[cfg(feature = "vault")]
+pub mod vault;
+
+pub use address_book::{AddressBook, Contact};
+pub use backend::{
+    remove_stale_temp_files, write_atomic, BatchOp, FileBackend, MemoryBackend, StorageBackend,
+};
+pub use backup::{create_backup, restore_backup, BackupSummary, BACKUP_EXTENSION};
+pub use keystore::{KeyProvider, PassphraseKeyProvider, PlatformKeyProvider};
+pub use mesh_stats::{MeshStats, MeshStatsEvent, MeshStatsSummary};
+pub use message_log::{LoggedMessage, MessageDirection, MessageKind, MessageLog};
+pub use payment_receipts::{PaymentReceiptRecord, PaymentReceiptStore};
+pub use quota::{CleanupReport, StorageCategory, StorageQuota, StorageUsage};
+pub use relay_receipts::{ReceiptRecord, ReceiptStore};
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteBackend;
+pub use tx_history::{TxHistory, TxHistoryPage, TxHistoryQuery, TxHistoryRecord, TxHistoryStatus};
+#[cfg(feature = "vault")]
+pub use vault::{KdfParams, KeypairVault, VaultEntry};
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use thiserror::Error;
+
+const NONCE_SIZE: usize = 12; // AES-GCM nonce size
+const MAGIC_HEADER: &[u8] = b"PNET"; // Magic header to identify encrypted files
+const MAGIC_HEADER_SIZE: usize = 4;
+
+/// Secure storage manager
+pub struct SecureStorage {
+    storage_dir: PathBuf,
+    key_provider: Arc<dyn KeyProvider>,
+    backend: Arc<dyn StorageBackend>,
+}
+
+impl SecureStorage {
+    /// Create a new secure storage instance.
+    /// `encryption_key` is the raw key string (hashed with SHA-256 internally to produce 32 bytes).
+    /// Falls back to the `POLLINET_ENCRYPTION_KEY` environment variable when `encryption_key` is `None`.
+    pub fn new(
+        storage_dir: impl AsRef<Path>,
+        encryption_key: Option<String>,
+    ) -> Result<Self, StorageError> {
+        let provider = PassphraseKeyProvider::from_option_or_env(encryption_key)?;
+        Self::with_key_provider(storage_dir, Arc::new(provider))
+    }
+
+    /// Create a secure storage instance whose key comes from `key_provider`
+    /// (e.g. a key held by Android Keystore / iOS Keychain and passed through FFI).
+    pub fn with_key_provider(
+        storage_dir: impl AsRef<Path>,
+        key_provider: Arc<dyn KeyProvider>,
+    ) -> Result<Self, StorageError> {
+        let storage_dir = storage_dir.as_ref().to_path_buf();
+        let backend = Arc::new(FileBackend::open(&storage_dir)?);
+
+        tracing::info!(
+            "📁 Initialized secure storage at: {} (key: {})",
+            storage_dir.display(),
+            key_provider.describe()
+        );
+
+        Ok(Self {
+            storage_dir,
+            ..Self::with_backend(backend, key_provider)
+        })
+    }
+
+    /// Create secure storage on top of an arbitrary backend
+    pub fn with_backend(
+        backend: Arc<dyn StorageBackend>,
+        key_provider: Arc<dyn KeyProvider>,
+    ) -> Self {
+        Self {
+            storage_dir: PathBuf::new(),
+            key_provider,
+            backend,
+        }
+    }
+
+    /// Directory passed at construction (empty for `with_backend`)
+    pub fn storage_dir(&self) -> &Path {
+        &self.storage_dir
+    }
+
+    /// Underlying key-value backend
+    pub fn backend(&self) -> &Arc<dyn StorageBackend> {
+        &self.backend
+    }
+
+    /// Encrypt `plaintext` and store it under `key`
+    pub fn put_encrypted(&self, key: &str, plaintext: &[u8]) -> Result<(), StorageError> {
+        let encrypted = self.encrypt_data(plaintext)?;
+        self.backend.put(key, &encrypted)
+    }
+
+    /// Load and decrypt the value under `key`
+    pub fn get_decrypted(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        match self.backend.get(key)? {
+            Some(encrypted) => self.decrypt_data(&encrypted).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Delete the value under `key`
+    pub fn delete(&self, key: &str) -> Result<bool, StorageError> {
+        self.backend.delete(key)
+    }
+
+    /// Fetch the AES-256-GCM key from the key provider
+    fn get_encryption_key(&self) -> Result<Key<Aes256Gcm>, StorageError> {
+        let key_bytes = self.key_provider.storage_key()?;
+        Ok(*Key::<Aes256Gcm>::from_slice(&key_bytes))
+    }
+
+    /// Encrypt data using AES-256-GCM
+    fn encrypt_data(&self, plaintext: &[u8]) -> Result<Vec<u8>, StorageError> {
+        let key = self.get_encryption_key()?;
+        let cipher = Aes256Gcm::new(&key);
+
+        // Generate random nonce
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+        // Encrypt the data
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_ref())
+            .map_err(|e| StorageError::Encryption(format!("Encryption failed: {}", e)))?;
+
+        // Format: [MAGIC_HEADER][NONCE][CIPHERTEXT]
+        let mut encrypted = Vec::with_capacity(MAGIC_HEADER_SIZE + NONCE_SIZE + ciphertext.len());
+        encrypted.extend_from_slice(MAGIC_HEADER);
+        encrypted.extend_from_slice(&nonce);
+        encrypted.extend_from_slice(&ciphertext);
+
+        Ok(encrypted)
+    }
+
+    /// Decrypt data using AES-256-GCM
+    fn decrypt_data(&self, encrypted: &[u8]) -> Result<Vec<u8>, StorageError> {
+        // Check minimum size
+        if encrypted.len() < MAGIC_HEADER_SIZE + NONCE_SIZE {
+            return Err(StorageError::Decryption(
+                "Encrypted data too short".to_string(),
+            ));
+        }
+
+        // Check magic header
+        if &encrypted[..MAGIC_HEADER_SIZE] != MAGIC_HEADER {
+            return Err(StorageError::Decryption(
+                "Invalid magic header - file may not be encrypted".to_string(),
+            ));
+        }
+
+        let key = self.get_encryption_key()?;
+        let cipher = Aes256Gcm::new(&key);
+
+        // Extract nonce and ciphertext
+        let nonce_start = MAGIC_HEADER_SIZE;
+        let nonce_end = nonce_start + NONCE_SIZE;
+        let nonce = Nonce::from_slice(&encrypted[nonce_start..nonce_end]);
+        let ciphertext = &encrypted[nonce_end..];
+
+        // Decrypt the data
+        let plaintext = cipher
+            .decrypt(nonce, ciphertext.as_ref())
+            .map_err(|e| StorageError::Decryption(format!("Decryption failed: {}", e)))?;
+
+        Ok(plaintext)
+    }
+}
+
+/// Storage errors
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("IO error: {0}")]
+    Io(String),
+
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+
+    #[error("Decryption error: {0}")]
+    Decryption(String),
+
+    #[error("Invalid storage key: {0}")]
+    InvalidKey(String),
+
+    #[error("Unsupported format version: {0}")]
+    UnsupportedVersion(String),
+
+    #[error("Invalid data: {0}")]
+    InvalidData(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const TEST_KEY: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+
+    #[test]
+    fn test_storage_creation() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = SecureStorage::new(temp_dir.path(), Some(TEST_KEY.to_string())).unwrap();
+        assert!(storage.storage_dir.exists());
+    }
+
+    #[test]
+    fn test_encrypted_roundtrip_through_backend() {
+        let backend = Arc::new(MemoryBackend::new());
+        let provider = PlatformKeyProvider::from_bytes(&[9u8; 32]).unwrap();
+        let storage = SecureStorage::with_backend(backend.clone(), Arc::new(provider));
+
+        storage.put_encrypted("secrets/a", b"hello").unwrap();
+        let raw = backend.get("secrets/a").unwrap().unwrap();
+        assert!(raw.starts_with(MAGIC_HEADER));
+        assert_eq!(
+            storage.get_decrypted("secrets/a").unwrap().unwrap(),
+            b"hello"
+        );
+        assert!(storage.delete("secrets/a").unwrap());
+        assert!(storage.get_decrypted("secrets/a").unwrap().is_none());
+    }
+}
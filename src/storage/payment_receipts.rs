@@ -0,0 +1,230 @@
+//! Persistent offline payment receipts
+//!
+//! Payer and merchant both keep the [`PaymentReceipt`]s of their offline
+//! payments as proof until (and after) the transaction confirms. A receipt
+//! is stored when it is signed or received and updated in place once the
+//! merchant's countersignature arrives. The whole set is rewritten
+//! atomically to `payment_receipts.json` on every change; the oldest are
+//! dropped past [`MAX_PAYMENT_RECEIPTS`].
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{write_atomic, StorageError};
+use crate::ble::{Countersignature, PaymentReceipt};
+
+/// Receipts kept at most (oldest are dropped first)
+pub const MAX_PAYMENT_RECEIPTS: usize = 1000;
+
+const RECEIPTS_FILE: &str = "payment_receipts.json";
+
+/// One stored receipt, in a host-friendly encoding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentReceiptRecord {
+    /// Base58 transaction signature (the transaction's on-chain id)
+    pub tx_signature: String,
+    /// Hex payment request id, if the payment answered one
+    pub request_id: Option<String>,
+    /// Base58 wallet (SOL) or token account (SPL) paid
+    pub recipient: String,
+    pub amount: u64,
+    /// Unix seconds the payer signed the receipt
+    pub issued_at: u64,
+    /// Base58 device key of the payer
+    pub payer: String,
+    /// Hex Ed25519 signature of the payer's device
+    pub payer_signature: String,
+    /// Base58 device key of the merchant, once countersigned
+    pub merchant: Option<String>,
+    /// Hex Ed25519 countersignature
+    pub merchant_signature: Option<String>,
+    /// This device paid (rather than received) the payment
+    pub paid: bool,
+}
+
+impl PaymentReceiptRecord {
+    pub fn from_receipt(receipt: &PaymentReceipt, paid: bool) -> Self {
+        let countersignature = receipt.countersignature.as_ref();
+        Self {
+            tx_signature: bs58::encode(receipt.tx_signature).into_string(),
+            request_id: receipt
+                .has_request()
+                .then(|| hex::encode(receipt.request_id)),
+            recipient: bs58::encode(receipt.recipient).into_string(),
+            amount: receipt.amount,
+            issued_at: receipt.issued_at,
+            payer: bs58::encode(receipt.payer).into_string(),
+            payer_signature: hex::encode(receipt.payer_signature),
+            merchant: countersignature.map(|c| bs58::encode(c.merchant).into_string()),
+            merchant_signature: countersignature.map(|c| hex::encode(c.signature)),
+            paid,
+        }
+    }
+
+    /// Back to a verifiable receipt (e.g. to show it to a third party)
+    pub fn to_receipt(&self) -> Option<PaymentReceipt> {
+        fn b58<const N: usize>(value: &str) -> Option<[u8; N]> {
+            bs58::decode(value).into_vec().ok()?.try_into().ok()
+        }
+        fn hex_array<const N: usize>(value: &str) -> Option<[u8; N]> {
+            hex::decode(value).ok()?.try_into().ok()
+        }
+        let countersignature = match (&self.merchant, &self.merchant_signature) {
+            (Some(merchant), Some(signature)) => Some(Countersignature {
+                merchant: b58(merchant)?,
+                signature: hex_array(signature)?,
+            }),
+            _ => None,
+        };
+        Some(PaymentReceipt {
+            request_id: match &self.request_id {
+                Some(id) => hex_array(id)?,
+                None => [0; 16],
+            },
+            tx_signature: b58(&self.tx_signature)?,
+            recipient: b58(&self.recipient)?,
+            amount: self.amount,
+            issued_at: self.issued_at,
+            payer: b58(&self.payer)?,
+            payer_signature: hex_array(&self.payer_signature)?,
+            countersignature,
+        })
+    }
+
+    pub fn is_countersigned(&self) -> bool {
+        self.merchant_signature.is_some()
+    }
+}
+
+/// Payment receipts in their own directory
+pub struct PaymentReceiptStore {
+    dir: PathBuf,
+    /// Oldest first
+    records: Mutex<VecDeque<PaymentReceiptRecord>>,
+}
+
+impl PaymentReceiptStore {
+    /// Open (or create) the store in `dir`
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(|e| {
+            StorageError::Io(format!("Failed to create payment receipt store: {}", e))
+        })?;
+
+        let records = match fs::read(dir.join(RECEIPTS_FILE)) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| {
+                StorageError::Serialization(format!("Failed to read payment receipts: {}", e))
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => VecDeque::new(),
+            Err(e) => {
+                return Err(StorageError::Io(format!(
+                    "Failed to read payment receipts: {}",
+                    e
+                )))
+            }
+        };
+
+        Ok(Self {
+            dir,
+            records: Mutex::new(records),
+        })
+    }
+
+    /// Directory holding the receipt file
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Store a receipt, replacing the one for the same transaction
+    pub fn save(&self, record: PaymentReceiptRecord) -> Result<(), StorageError> {
+        let mut records = self.records.lock();
+        let mut updated = records.clone();
+        match updated
+            .iter_mut()
+            .find(|r| r.tx_signature == record.tx_signature)
+        {
+            Some(existing) => *existing = record,
+            None => {
+                if updated.len() >= MAX_PAYMENT_RECEIPTS {
+                    updated.pop_front();
+                }
+                updated.push_back(record);
+            }
+        }
+        let bytes = serde_json::to_vec(&updated).map_err(|e| {
+            StorageError::Serialization(format!("Failed to serialize payment receipts: {}", e))
+        })?;
+        write_atomic(&self.dir.join(RECEIPTS_FILE), &bytes)?;
+        *records = updated;
+        Ok(())
+    }
+
+    /// Receipt for one transaction (base58 signature)
+    pub fn get(&self, tx_signature: &str) -> Option<PaymentReceiptRecord> {
+        self.records
+            .lock()
+            .iter()
+            .find(|r| r.tx_signature == tx_signature)
+            .cloned()
+    }
+
+    /// Receipts newest first
+    pub fn list(&self, offset: usize, limit: usize) -> Vec<PaymentReceiptRecord> {
+        self.records
+            .lock()
+            .iter()
+            .rev()
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ble::generate_device_key;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_receipt_updated_with_countersignature_and_reopened() {
+        let dir = tempdir().unwrap();
+        let payer = generate_device_key();
+        let merchant = generate_device_key();
+        let mut receipt = PaymentReceipt::sign(&payer, [4; 16], [5; 64], [6; 32], 700, 10);
+        {
+            let store = PaymentReceiptStore::open(dir.path()).unwrap();
+            store
+                .save(PaymentReceiptRecord::from_receipt(&receipt, true))
+                .unwrap();
+            receipt.countersign(&merchant);
+            store
+                .save(PaymentReceiptRecord::from_receipt(&receipt, true))
+                .unwrap();
+            assert_eq!(store.len(), 1);
+        }
+
+        let store = PaymentReceiptStore::open(dir.path()).unwrap();
+        let record = store.list(0, 10).remove(0);
+        assert!(record.is_countersigned());
+        assert_eq!(record.request_id, Some(hex::encode([4u8; 16])));
+        assert_eq!(store.get(&record.tx_signature), Some(record.clone()));
+        // Stored receipts still verify
+        let restored = record.to_receipt().unwrap();
+        assert_eq!(restored, receipt);
+        assert!(restored.verify());
+    }
+}
@@ -0,0 +1,316 @@
+//! Storage quota and cleanup
+//!
+//! Long-running relays accumulate fragment caches, logs and archives. A
+//! [`StorageQuota`] reports how much of the storage directory each category
+//! uses and, when the configured budget is exceeded, deletes the oldest
+//! disposable files first. Queue state and secure (key) material are never
+//! deleted to satisfy the budget.
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often `enforce_if_due` actually scans the disk
+pub const DEFAULT_QUOTA_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// What a file under the storage directory holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageCategory {
+    /// Queue snapshots and write-ahead logs
+    Queues,
+    /// Partial reassembly buffers
+    Fragments,
+    /// Log files
+    Logs,
+    /// Archived (rotated) data
+    Archives,
+    /// Everything else, including encrypted secure storage
+    Other,
+}
+
+impl StorageCategory {
+    const ALL: [StorageCategory; 5] = [
+        StorageCategory::Queues,
+        StorageCategory::Fragments,
+        StorageCategory::Logs,
+        StorageCategory::Archives,
+        StorageCategory::Other,
+    ];
+
+    /// Classify a path relative to the storage root
+    pub fn classify(relative: &Path) -> Self {
+        let first = relative
+            .components()
+            .next()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let is_log = relative.extension().is_some_and(|ext| ext == "log");
+
+        match first.as_str() {
+            "queues" => StorageCategory::Queues,
+            "txcache" => StorageCategory::Fragments,
+            "logs" => StorageCategory::Logs,
+            "archive" | "archives" => StorageCategory::Archives,
+            _ if is_log => StorageCategory::Logs,
+            _ => StorageCategory::Other,
+        }
+    }
+
+    /// Whether files in this category may be deleted to meet the budget
+    pub fn is_evictable(self) -> bool {
+        matches!(
+            self,
+            StorageCategory::Fragments | StorageCategory::Logs | StorageCategory::Archives
+        )
+    }
+}
+
+/// Usage of one category
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryUsage {
+    pub category: StorageCategory,
+    pub bytes: u64,
+    pub files: usize,
+}
+
+/// Usage report for the storage directory
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsage {
+    pub total_bytes: u64,
+    pub budget_bytes: u64,
+    pub over_budget: bool,
+    pub categories: Vec<CategoryUsage>,
+}
+
+/// Result of a cleanup pass
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupReport {
+    pub files_removed: usize,
+    pub bytes_freed: u64,
+    pub usage: StorageUsage,
+}
+
+struct FileEntry {
+    path: PathBuf,
+    category: StorageCategory,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+/// Storage budget for one storage directory
+#[derive(Debug, Clone)]
+pub struct StorageQuota {
+    root: PathBuf,
+    budget_bytes: u64,
+    check_interval: Duration,
+    last_check: Option<Instant>,
+}
+
+impl StorageQuota {
+    /// Budget `budget_bytes` for everything under `root`
+    pub fn new(root: impl AsRef<Path>, budget_bytes: u64) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            budget_bytes,
+            check_interval: DEFAULT_QUOTA_CHECK_INTERVAL,
+            last_check: None,
+        }
+    }
+
+    /// Set the minimum interval between automatic checks
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    pub fn budget_bytes(&self) -> u64 {
+        self.budget_bytes
+    }
+
+    fn scan(&self) -> Vec<FileEntry> {
+        let mut files = Vec::new();
+        scan_dir(&self.root, &self.root, &mut files);
+        files
+    }
+
+    fn summarize(&self, files: &[FileEntry]) -> StorageUsage {
+        let categories: Vec<CategoryUsage> = StorageCategory::ALL
+            .iter()
+            .map(|&category| {
+                let (bytes, count) = files
+                    .iter()
+                    .filter(|f| f.category == category)
+                    .fold((0u64, 0usize), |(b, n), f| (b + f.bytes, n + 1));
+                CategoryUsage {
+                    category,
+                    bytes,
+                    files: count,
+                }
+            })
+            .collect();
+        let total_bytes = categories.iter().map(|c| c.bytes).sum();
+
+        StorageUsage {
+            total_bytes,
+            budget_bytes: self.budget_bytes,
+            over_budget: total_bytes > self.budget_bytes,
+            categories,
+        }
+    }
+
+    /// Current usage
+    pub fn usage(&self) -> StorageUsage {
+        self.summarize(&self.scan())
+    }
+
+    /// Delete the oldest disposable files until usage fits the budget
+    pub fn enforce(&mut self) -> CleanupReport {
+        self.last_check = Some(Instant::now());
+
+        let mut files = self.scan();
+        let mut total: u64 = files.iter().map(|f| f.bytes).sum();
+        let mut files_removed = 0;
+        let mut bytes_freed = 0;
+
+        if total > self.budget_bytes {
+            let mut candidates: Vec<usize> = (0..files.len())
+                .filter(|&i| files[i].category.is_evictable())
+                .collect();
+            candidates.sort_by_key(|&i| files[i].modified);
+
+            let mut removed = Vec::new();
+            for i in candidates {
+                if total <= self.budget_bytes {
+                    break;
+                }
+                match fs::remove_file(&files[i].path) {
+                    Ok(()) => {
+                        total -= files[i].bytes;
+                        bytes_freed += files[i].bytes;
+                        files_removed += 1;
+                        removed.push(i);
+                    }
+                    Err(e) => tracing::warn!(
+                        "Failed to remove {} during storage cleanup: {}",
+                        files[i].path.display(),
+                        e
+                    ),
+                }
+            }
+
+            removed.sort_unstable();
+            for i in removed.into_iter().rev() {
+                files.swap_remove(i);
+            }
+
+            if total > self.budget_bytes {
+                tracing::warn!(
+                    "💾 Storage still over budget after cleanup: {} / {} bytes (only queue/secure data left)",
+                    total,
+                    self.budget_bytes
+                );
+            } else {
+                tracing::info!(
+                    "💾 Storage cleanup freed {} bytes ({} files)",
+                    bytes_freed,
+                    files_removed
+                );
+            }
+        }
+
+        CleanupReport {
+            files_removed,
+            bytes_freed,
+            usage: self.summarize(&files),
+        }
+    }
+
+    /// Run `enforce` if the check interval has elapsed since the last run
+    pub fn enforce_if_due(&mut self) -> Option<CleanupReport> {
+        let due = match self.last_check {
+            Some(last) => last.elapsed() >= self.check_interval,
+            None => true,
+        };
+        due.then(|| self.enforce())
+    }
+}
+
+fn scan_dir(root: &Path, dir: &Path, files: &mut Vec<FileEntry>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if meta.is_dir() {
+            scan_dir(root, &path, files);
+        } else if meta.is_file() {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            files.push(FileEntry {
+                category: StorageCategory::classify(relative),
+                bytes: meta.len(),
+                modified: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                path,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            StorageCategory::classify(Path::new("queues/outbound_queue.json")),
+            StorageCategory::Queues
+        );
+        assert_eq!(
+            StorageCategory::classify(Path::new("txcache/abcd")),
+            StorageCategory::Fragments
+        );
+        assert_eq!(
+            StorageCategory::classify(Path::new("relay.log")),
+            StorageCategory::Logs
+        );
+        assert!(!StorageCategory::classify(Path::new("keys.bin")).is_evictable());
+    }
+
+    #[test]
+    fn test_enforce_removes_oldest_disposable_files() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("queues")).unwrap();
+        fs::create_dir_all(root.join("logs")).unwrap();
+        fs::write(root.join("queues/outbound_queue.json"), vec![0u8; 100]).unwrap();
+        fs::write(root.join("logs/old.log"), vec![0u8; 100]).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        fs::write(root.join("logs/new.log"), vec![0u8; 100]).unwrap();
+
+        let mut quota = StorageQuota::new(root, 250);
+        assert!(quota.usage().over_budget);
+
+        let report = quota.enforce();
+        assert_eq!(report.files_removed, 1);
+        assert_eq!(report.bytes_freed, 100);
+        assert!(!report.usage.over_budget);
+        assert!(!root.join("logs/old.log").exists());
+        assert!(root.join("logs/new.log").exists());
+        assert!(root.join("queues/outbound_queue.json").exists());
+
+        // Queue data is never evicted, even when still over budget
+        let mut tight = StorageQuota::new(root, 10);
+        tight.enforce();
+        assert!(root.join("queues/outbound_queue.json").exists());
+    }
+}
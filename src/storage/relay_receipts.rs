@@ -0,0 +1,251 @@
+//! Persistent relay receipts
+//!
+//! Keeps the signed [`RelayReceipt`]s this device issued (as proof of its own
+//! relaying) and the ones it received back for transactions it originated or
+//! carried. Receipts are appended as JSON lines to `receipts.log`; the oldest
+//! are dropped past the limit and the file is rewritten once dropped lines
+//! dominate.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use super::{write_atomic, StorageError};
+use crate::ble::{ReceiptAction, RelayReceipt};
+
+/// Default number of receipts kept (oldest are dropped first)
+pub const DEFAULT_MAX_RECEIPTS: usize = 2000;
+
+const RECEIPTS_LOG: &str = "receipts.log";
+
+/// One stored receipt, in a host-friendly encoding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptRecord {
+    /// Hex of the 16-byte confirmation key, `tx_id_hash(tx_id)`
+    pub tx_id_hash: String,
+    pub action: ReceiptAction,
+    /// Unix timestamp (seconds) signed by the relayer
+    pub timestamp: u64,
+    /// Base58 Ed25519 key of the signing device
+    pub relayer: String,
+    /// Hex Ed25519 signature
+    pub signature: String,
+    /// Signed by this device rather than received from another
+    pub issued: bool,
+}
+
+impl ReceiptRecord {
+    pub fn from_receipt(receipt: &RelayReceipt, issued: bool) -> Self {
+        Self {
+            tx_id_hash: hex::encode(receipt.tx_id_hash),
+            action: receipt.action,
+            timestamp: receipt.timestamp,
+            relayer: bs58::encode(receipt.relayer).into_string(),
+            signature: hex::encode(&receipt.signature),
+            issued,
+        }
+    }
+
+    /// Back to a verifiable receipt (e.g. to present it for a reward claim)
+    pub fn to_receipt(&self) -> Option<RelayReceipt> {
+        let tx_id_hash = hex::decode(&self.tx_id_hash).ok()?.try_into().ok()?;
+        let relayer = bs58::decode(&self.relayer)
+            .into_vec()
+            .ok()?
+            .try_into()
+            .ok()?;
+        Some(RelayReceipt {
+            tx_id_hash,
+            action: self.action,
+            timestamp: self.timestamp,
+            relayer,
+            signature: hex::decode(&self.signature).ok()?,
+            hop_count: 0,
+        })
+    }
+
+    fn same_receipt(&self, other: &ReceiptRecord) -> bool {
+        self.tx_id_hash == other.tx_id_hash
+            && self.relayer == other.relayer
+            && self.action == other.action
+    }
+}
+
+struct ReceiptState {
+    /// Receipts in storage order
+    records: VecDeque<ReceiptRecord>,
+    /// Lines in `receipts.log`, including dropped ones
+    lines: usize,
+}
+
+/// Relay receipts in their own directory
+pub struct ReceiptStore {
+    dir: PathBuf,
+    max_receipts: usize,
+    state: Mutex<ReceiptState>,
+}
+
+impl ReceiptStore {
+    /// Open (or create) a store with the default limit
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, StorageError> {
+        Self::with_limit(dir, DEFAULT_MAX_RECEIPTS)
+    }
+
+    /// Open keeping at most `max_receipts` receipts
+    pub fn with_limit(dir: impl AsRef<Path>, max_receipts: usize) -> Result<Self, StorageError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .map_err(|e| StorageError::Io(format!("Failed to create receipt store: {}", e)))?;
+
+        let mut records = VecDeque::new();
+        let mut lines = 0;
+        if let Ok(file) = fs::File::open(dir.join(RECEIPTS_LOG)) {
+            for line in BufReader::new(file).lines().map_while(Result::ok) {
+                lines += 1;
+                // A torn final line (crash mid-append) is skipped
+                if let Ok(record) = serde_json::from_str::<ReceiptRecord>(&line) {
+                    records.push_back(record);
+                }
+            }
+        }
+
+        let max_receipts = max_receipts.max(1);
+        let excess = records.len().saturating_sub(max_receipts);
+        records.drain(..excess);
+
+        Ok(Self {
+            dir,
+            max_receipts,
+            state: Mutex::new(ReceiptState { records, lines }),
+        })
+    }
+
+    /// Directory holding the receipt file
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Store a receipt; returns false if the same relayer already has a
+    /// receipt for this transaction and action
+    pub fn record(&self, record: ReceiptRecord) -> Result<bool, StorageError> {
+        let mut state = self.state.lock();
+        if state.records.iter().any(|r| r.same_receipt(&record)) {
+            return Ok(false);
+        }
+        if state.records.len() >= self.max_receipts {
+            state.records.pop_front();
+        }
+        state.records.push_back(record.clone());
+        self.persist(&mut state, &record)?;
+        Ok(true)
+    }
+
+    /// Receipts newest first
+    pub fn list(&self, offset: usize, limit: usize) -> Vec<ReceiptRecord> {
+        let state = self.state.lock();
+        state
+            .records
+            .iter()
+            .rev()
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Receipts for one transaction (by `tx_id_hash` hex), oldest first
+    pub fn for_transaction(&self, tx_id_hash: &str) -> Vec<ReceiptRecord> {
+        let state = self.state.lock();
+        state
+            .records
+            .iter()
+            .filter(|r| r.tx_id_hash == tx_id_hash)
+            .cloned()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append `record`, rewriting the file once dropped lines dominate
+    fn persist(
+        &self,
+        state: &mut ReceiptState,
+        record: &ReceiptRecord,
+    ) -> Result<(), StorageError> {
+        if state.lines >= 2 * self.max_receipts {
+            return self.compact(state);
+        }
+
+        let mut line = serde_json::to_vec(record).map_err(|e| {
+            StorageError::Serialization(format!("Failed to serialize receipt: {}", e))
+        })?;
+        line.push(b'\n');
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(RECEIPTS_LOG))
+            .map_err(|e| StorageError::Io(format!("Failed to open receipt store: {}", e)))?;
+        file.write_all(&line)
+            .map_err(|e| StorageError::Io(format!("Failed to append receipt: {}", e)))?;
+        state.lines += 1;
+        Ok(())
+    }
+
+    fn compact(&self, state: &mut ReceiptState) -> Result<(), StorageError> {
+        let mut bytes = Vec::new();
+        for record in &state.records {
+            serde_json::to_writer(&mut bytes, record).map_err(|e| {
+                StorageError::Serialization(format!("Failed to serialize receipt: {}", e))
+            })?;
+            bytes.push(b'\n');
+        }
+        write_atomic(&self.dir.join(RECEIPTS_LOG), &bytes)?;
+        state.lines = state.records.len();
+        tracing::debug!("Compacted relay receipts ({} receipts)", state.lines);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ble::generate_device_key;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_receipts_dedup_and_survive_reopen() {
+        let dir = tempdir().unwrap();
+        let key = generate_device_key();
+        let relayed = RelayReceipt::sign(&key, [1u8; 16], ReceiptAction::Relayed, 10);
+        let submitted = RelayReceipt::sign(&key, [1u8; 16], ReceiptAction::Submitted, 20);
+        {
+            let store = ReceiptStore::with_limit(dir.path(), 2).unwrap();
+            assert!(store
+                .record(ReceiptRecord::from_receipt(&relayed, true))
+                .unwrap());
+            assert!(!store
+                .record(ReceiptRecord::from_receipt(&relayed, false))
+                .unwrap());
+            assert!(store
+                .record(ReceiptRecord::from_receipt(&submitted, true))
+                .unwrap());
+        }
+
+        let store = ReceiptStore::with_limit(dir.path(), 2).unwrap();
+        let records = store.for_transaction(&hex::encode([1u8; 16]));
+        assert_eq!(records.len(), 2);
+        assert_eq!(store.list(0, 1)[0].action, ReceiptAction::Submitted);
+        // Stored receipts still verify
+        assert!(records.iter().all(|r| r.to_receipt().unwrap().verify()));
+    }
+}
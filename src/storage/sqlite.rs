@@ -0,0 +1,163 @@
+//! SQLite Storage Backend
+//!
+//! Single `kv` table behind the [`StorageBackend`] trait. Batches run in one
+//! SQLite transaction, so they are atomic across crashes via SQLite's journal.
+
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+
+use super::backend::{validate_key, BatchOp, StorageBackend};
+use super::StorageError;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS kv (
+        key   TEXT PRIMARY KEY,
+        value BLOB NOT NULL
+    );
+";
+
+/// SQLite-backed key-value storage
+pub struct SqliteBackend {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteBackend {
+    /// Open (or create) the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    StorageError::Io(format!("Failed to create storage directory: {}", e))
+                })?;
+            }
+        }
+
+        let conn = Connection::open(path)
+            .map_err(|e| StorageError::Io(format!("Failed to open database: {}", e)))?;
+        Self::init(conn)
+    }
+
+    /// Open an in-memory database
+    pub fn open_in_memory() -> Result<Self, StorageError> {
+        let conn = Connection::open_in_memory()
+            .map_err(|e| StorageError::Io(format!("Failed to open database: {}", e)))?;
+        Self::init(conn)
+    }
+
+    fn init(conn: Connection) -> Result<Self, StorageError> {
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))
+            .map_err(|e| StorageError::Io(format!("Failed to enable WAL: {}", e)))?;
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| StorageError::Io(format!("Failed to create schema: {}", e)))?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+}
+
+impl StorageBackend for SqliteBackend {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        validate_key(key)?;
+        self.conn
+            .lock()
+            .query_row("SELECT value FROM kv WHERE key = ?1", params![key], |row| {
+                row.get::<_, Vec<u8>>(0)
+            })
+            .optional()
+            .map_err(sql_err)
+    }
+
+    fn put(&self, key: &str, value: &[u8]) -> Result<(), StorageError> {
+        validate_key(key)?;
+        self.conn
+            .lock()
+            .execute(
+                "INSERT OR REPLACE INTO kv (key, value) VALUES (?1, ?2)",
+                params![key, value],
+            )
+            .map_err(sql_err)?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<bool, StorageError> {
+        validate_key(key)?;
+        let removed = self
+            .conn
+            .lock()
+            .execute("DELETE FROM kv WHERE key = ?1", params![key])
+            .map_err(sql_err)?;
+        Ok(removed > 0)
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        let conn = self.conn.lock();
+        let mut stmt = conn
+            .prepare("SELECT key FROM kv WHERE substr(key, 1, length(?1)) = ?1 ORDER BY key")
+            .map_err(sql_err)?;
+        let rows = stmt
+            .query_map(params![prefix], |row| row.get::<_, String>(0))
+            .map_err(sql_err)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(sql_err)
+    }
+
+    fn batch(&self, ops: &[BatchOp]) -> Result<(), StorageError> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction().map_err(sql_err)?;
+        for op in ops {
+            match op {
+                BatchOp::Put { key, value } => {
+                    validate_key(key)?;
+                    tx.execute(
+                        "INSERT OR REPLACE INTO kv (key, value) VALUES (?1, ?2)",
+                        params![key, value],
+                    )
+                    .map_err(sql_err)?;
+                }
+                BatchOp::Delete { key } => {
+                    validate_key(key)?;
+                    tx.execute("DELETE FROM kv WHERE key = ?1", params![key])
+                        .map_err(sql_err)?;
+                }
+            }
+        }
+        tx.commit().map_err(sql_err)
+    }
+}
+
+fn sql_err(e: rusqlite::Error) -> StorageError {
+    StorageError::Io(format!("SQLite error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqlite_backend_roundtrip() {
+        let backend = SqliteBackend::open_in_memory().unwrap();
+        backend.put("queues/a", b"1").unwrap();
+        backend.put("queues/b", b"2").unwrap();
+        backend.put("other", b"3").unwrap();
+
+        assert_eq!(
+            backend.list("queues/").unwrap(),
+            vec!["queues/a", "queues/b"]
+        );
+        backend
+            .batch(&[
+                BatchOp::Delete {
+                    key: "queues/a".to_string(),
+                },
+                BatchOp::Put {
+                    key: "queues/b".to_string(),
+                    value: b"9".to_vec(),
+                },
+            ])
+            .unwrap();
+        assert!(backend.get("queues/a").unwrap().is_none());
+        assert_eq!(backend.get("queues/b").unwrap().unwrap(), b"9");
+    }
+}
@@ -0,0 +1,380 @@
+//! Persistent received-transaction history
+//!
+//! Every transaction received over the mesh gets a record keyed by its
+//! SHA-256 hex id, tracking where it came from and how far it got
+//! (received → submitted → confirmed or failed). Records are appended as JSON lines to
+//! `history.log` (the last line for an id wins) and the file is compacted once
+//! superseded lines dominate, so apps can render a transaction log that
+//! survives restarts.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use super::{write_atomic, StorageError};
+
+/// Default number of transactions kept (oldest are dropped first)
+pub const DEFAULT_MAX_TX_RECORDS: usize = 1000;
+/// Default page size for history queries
+pub const DEFAULT_TX_HISTORY_PAGE: usize = 50;
+
+const HISTORY_LOG: &str = "history.log";
+
+/// How far a received transaction has progressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TxHistoryStatus {
+    /// Reassembled from the mesh and queued for submission
+    Received,
+    /// Submitted to the network by this device
+    Submitted,
+    /// Success confirmation seen on the mesh
+    Confirmed,
+    /// Terminal failure confirmation seen on the mesh
+    Failed,
+}
+
+/// One received transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxHistoryRecord {
+    /// SHA-256 of the transaction bytes (hex), same id the mesh uses
+    pub tx_id: String,
+    /// Transaction size in bytes
+    pub size: usize,
+    /// Peer the fragments arrived from, when the host reported it
+    pub peer_id: Option<String>,
+    pub status: TxHistoryStatus,
+    /// Unix timestamp (seconds) of reception
+    pub received_at: u64,
+    /// Unix timestamp (seconds) of the last status change
+    pub updated_at: u64,
+}
+
+/// Filters and pagination for [`TxHistory::query`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TxHistoryQuery {
+    pub status: Option<TxHistoryStatus>,
+    pub peer_id: Option<String>,
+    /// Matching records to skip (newest first)
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl Default for TxHistoryQuery {
+    fn default() -> Self {
+        Self {
+            status: None,
+            peer_id: None,
+            offset: 0,
+            limit: DEFAULT_TX_HISTORY_PAGE,
+        }
+    }
+}
+
+/// One page of history, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxHistoryPage {
+    pub records: Vec<TxHistoryRecord>,
+    /// Number of records matching the filters (across all pages)
+    pub total: usize,
+}
+
+struct HistoryState {
+    /// Records in reception order
+    records: VecDeque<TxHistoryRecord>,
+    /// Lines in `history.log`, including superseded ones
+    lines: usize,
+}
+
+/// Received-transaction history in its own directory
+pub struct TxHistory {
+    dir: PathBuf,
+    max_records: usize,
+    state: Mutex<HistoryState>,
+}
+
+impl TxHistory {
+    /// Open (or create) a history with the default record limit
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, StorageError> {
+        Self::with_limit(dir, DEFAULT_MAX_TX_RECORDS)
+    }
+
+    /// Open keeping at most `max_records` transactions
+    pub fn with_limit(dir: impl AsRef<Path>, max_records: usize) -> Result<Self, StorageError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .map_err(|e| StorageError::Io(format!("Failed to create tx history: {}", e)))?;
+
+        let mut records: VecDeque<TxHistoryRecord> = VecDeque::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut lines = 0;
+        if let Ok(file) = fs::File::open(dir.join(HISTORY_LOG)) {
+            for line in BufReader::new(file).lines().map_while(Result::ok) {
+                lines += 1;
+                // A torn final line (crash mid-append) is skipped
+                let Ok(record) = serde_json::from_str::<TxHistoryRecord>(&line) else {
+                    continue;
+                };
+                match index.get(&record.tx_id) {
+                    Some(&i) => records[i] = record,
+                    None => {
+                        index.insert(record.tx_id.clone(), records.len());
+                        records.push_back(record);
+                    }
+                }
+            }
+        }
+
+        let max_records = max_records.max(1);
+        let excess = records.len().saturating_sub(max_records);
+        records.drain(..excess);
+
+        Ok(Self {
+            dir,
+            max_records,
+            state: Mutex::new(HistoryState { records, lines }),
+        })
+    }
+
+    /// Directory holding the history file
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Record a newly received transaction (no-op if `tx_id` is already known)
+    pub fn record_received(
+        &self,
+        tx_id: &str,
+        size: usize,
+        peer_id: Option<String>,
+    ) -> Result<(), StorageError> {
+        let mut state = self.state.lock();
+        if state.records.iter().any(|r| r.tx_id == tx_id) {
+            return Ok(());
+        }
+
+        let now = now_secs();
+        let record = TxHistoryRecord {
+            tx_id: tx_id.to_string(),
+            size,
+            peer_id,
+            status: TxHistoryStatus::Received,
+            received_at: now,
+            updated_at: now,
+        };
+        if state.records.len() >= self.max_records {
+            state.records.pop_front();
+        }
+        state.records.push_back(record.clone());
+        self.persist(&mut state, &record)
+    }
+
+    /// Advance a transaction's status; returns false if `tx_id` is unknown.
+    /// Status never moves backwards (a confirmed tx stays confirmed).
+    pub fn update_status(
+        &self,
+        tx_id: &str,
+        status: TxHistoryStatus,
+    ) -> Result<bool, StorageError> {
+        self.update_status_where(|id| id == tx_id, status)
+    }
+
+    /// Like [`Self::update_status`], for the newest record whose id matches
+    /// `matches` (used when only a derived hash of the id is known)
+    pub fn update_status_where(
+        &self,
+        matches: impl Fn(&str) -> bool,
+        status: TxHistoryStatus,
+    ) -> Result<bool, StorageError> {
+        let mut state = self.state.lock();
+        let Some(record) = state.records.iter_mut().rev().find(|r| matches(&r.tx_id)) else {
+            return Ok(false);
+        };
+        if rank(record.status) >= rank(status) {
+            return Ok(true);
+        }
+        record.status = status;
+        record.updated_at = now_secs();
+        let record = record.clone();
+        self.persist(&mut state, &record)?;
+        Ok(true)
+    }
+
+    /// Record for one transaction
+    pub fn get(&self, tx_id: &str) -> Option<TxHistoryRecord> {
+        let state = self.state.lock();
+        state.records.iter().find(|r| r.tx_id == tx_id).cloned()
+    }
+
+    /// Newest record whose id matches `matches`
+    pub fn find_where(&self, matches: impl Fn(&str) -> bool) -> Option<TxHistoryRecord> {
+        let state = self.state.lock();
+        state
+            .records
+            .iter()
+            .rev()
+            .find(|r| matches(&r.tx_id))
+            .cloned()
+    }
+
+    /// Records matching `query`, newest first
+    pub fn query(&self, query: &TxHistoryQuery) -> TxHistoryPage {
+        let state = self.state.lock();
+        let matching: Vec<&TxHistoryRecord> = state
+            .records
+            .iter()
+            .rev()
+            .filter(|r| query.status.is_none_or(|s| r.status == s))
+            .filter(|r| {
+                query
+                    .peer_id
+                    .as_deref()
+                    .is_none_or(|p| r.peer_id.as_deref() == Some(p))
+            })
+            .collect();
+
+        TxHistoryPage {
+            total: matching.len(),
+            records: matching
+                .into_iter()
+                .skip(query.offset)
+                .take(query.limit)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Append `record`, compacting once superseded lines outnumber live ones
+    fn persist(
+        &self,
+        state: &mut HistoryState,
+        record: &TxHistoryRecord,
+    ) -> Result<(), StorageError> {
+        if state.lines >= 2 * self.max_records.max(state.records.len()) {
+            return self.compact(state);
+        }
+
+        let mut line = serde_json::to_vec(record).map_err(|e| {
+            StorageError::Serialization(format!("Failed to serialize tx record: {}", e))
+        })?;
+        line.push(b'\n');
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(HISTORY_LOG))
+            .map_err(|e| StorageError::Io(format!("Failed to open tx history: {}", e)))?;
+        file.write_all(&line)
+            .map_err(|e| StorageError::Io(format!("Failed to append tx record: {}", e)))?;
+        state.lines += 1;
+        Ok(())
+    }
+
+    fn compact(&self, state: &mut HistoryState) -> Result<(), StorageError> {
+        let mut bytes = Vec::new();
+        for record in &state.records {
+            serde_json::to_writer(&mut bytes, record).map_err(|e| {
+                StorageError::Serialization(format!("Failed to serialize tx record: {}", e))
+            })?;
+            bytes.push(b'\n');
+        }
+        write_atomic(&self.dir.join(HISTORY_LOG), &bytes)?;
+        state.lines = state.records.len();
+        tracing::debug!("Compacted tx history ({} records)", state.lines);
+        Ok(())
+    }
+}
+
+fn rank(status: TxHistoryStatus) -> u8 {
+    match status {
+        TxHistoryStatus::Received => 0,
+        TxHistoryStatus::Submitted => 1,
+        TxHistoryStatus::Confirmed | TxHistoryStatus::Failed => 2,
+    }
+}
+
+fn now_secs() -> u64 {
+    crate::util::clock::now_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_history_survives_reopen_with_latest_status() {
+        let dir = tempdir().unwrap();
+        {
+            let history = TxHistory::open(dir.path()).unwrap();
+            history
+                .record_received("aa", 100, Some("alice".into()))
+                .unwrap();
+            history.record_received("bb", 200, None).unwrap();
+            history
+                .update_status("aa", TxHistoryStatus::Confirmed)
+                .unwrap();
+            // Never downgraded, and terminal states are final
+            history
+                .update_status("aa", TxHistoryStatus::Submitted)
+                .unwrap();
+            history
+                .update_status_where(|id| id.starts_with('a'), TxHistoryStatus::Failed)
+                .unwrap();
+        }
+
+        let history = TxHistory::open(dir.path()).unwrap();
+        assert_eq!(
+            history.get("aa").unwrap().status,
+            TxHistoryStatus::Confirmed
+        );
+        assert!(!history
+            .update_status("cc", TxHistoryStatus::Submitted)
+            .unwrap());
+
+        let page = history.query(&TxHistoryQuery::default());
+        assert_eq!(page.total, 2);
+        assert_eq!(page.records[0].tx_id, "bb");
+    }
+
+    #[test]
+    fn test_query_filters_paginate_and_compaction_keeps_newest() {
+        let dir = tempdir().unwrap();
+        let history = TxHistory::with_limit(dir.path(), 10).unwrap();
+        for i in 0..30 {
+            let peer = if i % 2 == 0 { "alice" } else { "bob" };
+            history
+                .record_received(&format!("tx{}", i), i, Some(peer.into()))
+                .unwrap();
+        }
+        history
+            .update_status("tx29", TxHistoryStatus::Submitted)
+            .unwrap();
+
+        let by_peer = history.query(&TxHistoryQuery {
+            peer_id: Some("alice".into()),
+            offset: 1,
+            limit: 2,
+            ..Default::default()
+        });
+        assert_eq!(by_peer.total, 5);
+        let ids: Vec<&str> = by_peer.records.iter().map(|r| r.tx_id.as_str()).collect();
+        assert_eq!(ids, vec!["tx26", "tx24"]);
+
+        let submitted = history.query(&TxHistoryQuery {
+            status: Some(TxHistoryStatus::Submitted),
+            ..Default::default()
+        });
+        assert_eq!(submitted.total, 1);
+
+        let reopened = TxHistory::with_limit(dir.path(), 10).unwrap();
+        assert_eq!(reopened.query(&TxHistoryQuery::default()).total, 10);
+        assert!(reopened.get("tx19").is_none());
+        assert_eq!(reopened.get("tx20").unwrap().size, 20);
+    }
+}
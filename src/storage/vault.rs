@@ -0,0 +1,313 @@
+//! Encrypted keypair vault
+//!
+//! For desktop relays and gateways that have no Mobile Wallet Adapter: Solana
+//! keypairs are stored one file per name, encrypted with AES-256-GCM under a
+//! key derived from an operator passphrase with Argon2id. Operators create,
+//! load and sign by name instead of keeping raw keypair bytes in scripts.
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use super::keystore::wipe;
+use super::{write_atomic, StorageError};
+
+/// Vault file format version
+const VAULT_FORMAT_VERSION: u32 = 1;
+const VAULT_EXTENSION: &str = "vault";
+const SALT_LEN: usize = 16;
+
+/// Argon2id cost parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Memory cost in KiB
+    pub memory_kib: u32,
+    /// Number of passes
+    pub iterations: u32,
+    /// Degree of parallelism
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    /// OWASP-recommended Argon2id minimum (19 MiB, 2 passes)
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+/// On-disk vault entry
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    pubkey: String,
+    kdf: KdfParams,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Public info about a stored keypair
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultEntry {
+    pub name: String,
+    pub pubkey: String,
+}
+
+/// Directory of passphrase-encrypted keypairs
+pub struct KeypairVault {
+    dir: PathBuf,
+    kdf: KdfParams,
+}
+
+impl KeypairVault {
+    /// Open (or create) a vault directory
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .map_err(|e| StorageError::Io(format!("Failed to create vault directory: {}", e)))?;
+        Ok(Self {
+            dir,
+            kdf: KdfParams::default(),
+        })
+    }
+
+    /// Use different Argon2id costs for newly stored keypairs
+    pub fn with_kdf_params(mut self, kdf: KdfParams) -> Self {
+        self.kdf = kdf;
+        self
+    }
+
+    fn path_for(&self, name: &str) -> Result<PathBuf, StorageError> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(StorageError::InvalidKey(format!(
+                "Invalid keypair name '{}' (use letters, digits, '-' and '_')",
+                name
+            )));
+        }
+        Ok(self.dir.join(format!("{}.{}", name, VAULT_EXTENSION)))
+    }
+
+    /// Generate a new keypair and store it under `name`
+    pub fn create(&self, name: &str, passphrase: &str) -> Result<Pubkey, StorageError> {
+        let keypair = Keypair::new();
+        self.import(name, &keypair, passphrase)?;
+        Ok(keypair.pubkey())
+    }
+
+    /// Store an existing keypair under `name` (fails if the name is taken)
+    pub fn import(
+        &self,
+        name: &str,
+        keypair: &Keypair,
+        passphrase: &str,
+    ) -> Result<(), StorageError> {
+        let path = self.path_for(name)?;
+        if path.exists() {
+            return Err(StorageError::InvalidKey(format!(
+                "Keypair '{}' already exists",
+                name
+            )));
+        }
+
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt, &self.kdf)?);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+        let mut secret = keypair.to_bytes();
+        let ciphertext = cipher.encrypt(&nonce, secret.as_ref());
+        wipe(&mut secret);
+        let ciphertext = ciphertext
+            .map_err(|e| StorageError::Encryption(format!("Encryption failed: {}", e)))?;
+
+        let file = VaultFile {
+            version: VAULT_FORMAT_VERSION,
+            pubkey: keypair.pubkey().to_string(),
+            kdf: self.kdf,
+            salt: BASE64.encode(salt),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        let json = serde_json::to_vec_pretty(&file)
+            .map_err(|e| StorageError::Serialization(format!("Failed to encode vault: {}", e)))?;
+        write_atomic(&path, &json)?;
+
+        tracing::info!("🔑 Stored keypair '{}' ({}) in vault", name, file.pubkey);
+        Ok(())
+    }
+
+    fn read(&self, name: &str) -> Result<VaultFile, StorageError> {
+        let path = self.path_for(name)?;
+        let bytes = fs::read(&path)
+            .map_err(|e| StorageError::Io(format!("Failed to read keypair '{}': {}", name, e)))?;
+        let file: VaultFile = serde_json::from_slice(&bytes)
+            .map_err(|e| StorageError::Serialization(format!("Corrupt vault file: {}", e)))?;
+        if file.version > VAULT_FORMAT_VERSION {
+            return Err(StorageError::UnsupportedVersion(format!(
+                "vault file version {} is newer than supported version {}",
+                file.version, VAULT_FORMAT_VERSION
+            )));
+        }
+        Ok(file)
+    }
+
+    /// Decrypt the keypair stored under `name`
+    pub fn load(&self, name: &str, passphrase: &str) -> Result<Keypair, StorageError> {
+        let file = self.read(name)?;
+        let decode = |field: &str| {
+            BASE64
+                .decode(field)
+                .map_err(|e| StorageError::Serialization(format!("Corrupt vault file: {}", e)))
+        };
+        let salt = decode(&file.salt)?;
+        let nonce = decode(&file.nonce)?;
+        let ciphertext = decode(&file.ciphertext)?;
+        if nonce.len() != 12 {
+            return Err(StorageError::Serialization(
+                "Corrupt vault file: bad nonce length".to_string(),
+            ));
+        }
+
+        let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt, &file.kdf)?);
+        let mut secret = cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| {
+                StorageError::Decryption("Wrong passphrase or corrupted keypair".to_string())
+            })?;
+
+        let keypair = Keypair::try_from(secret.as_slice());
+        wipe(&mut secret);
+        let keypair =
+            keypair.map_err(|e| StorageError::Decryption(format!("Invalid keypair: {}", e)))?;
+
+        if keypair.pubkey().to_string() != file.pubkey {
+            return Err(StorageError::Decryption(
+                "Decrypted keypair does not match stored public key".to_string(),
+            ));
+        }
+        Ok(keypair)
+    }
+
+    /// Sign `message` with the keypair stored under `name`
+    pub fn sign(
+        &self,
+        name: &str,
+        passphrase: &str,
+        message: &[u8],
+    ) -> Result<Signature, StorageError> {
+        Ok(self.load(name, passphrase)?.sign_message(message))
+    }
+
+    /// Public key of a stored keypair (no passphrase needed)
+    pub fn pubkey(&self, name: &str) -> Result<Pubkey, StorageError> {
+        let file = self.read(name)?;
+        Pubkey::from_str(&file.pubkey)
+            .map_err(|e| StorageError::Serialization(format!("Corrupt vault file: {}", e)))
+    }
+
+    /// All stored keypairs, sorted by name
+    pub fn list(&self) -> Result<Vec<VaultEntry>, StorageError> {
+        let entries = fs::read_dir(&self.dir)
+            .map_err(|e| StorageError::Io(format!("Failed to list vault: {}", e)))?;
+
+        let mut list = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == VAULT_EXTENSION) {
+                if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                    match self.read(name) {
+                        Ok(file) => list.push(VaultEntry {
+                            name: name.to_string(),
+                            pubkey: file.pubkey,
+                        }),
+                        Err(e) => tracing::warn!("Skipping unreadable vault entry {}: {}", name, e),
+                    }
+                }
+            }
+        }
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(list)
+    }
+
+    /// Remove a stored keypair; returns whether it existed
+    pub fn delete(&self, name: &str) -> Result<bool, StorageError> {
+        match fs::remove_file(self.path_for(name)?) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(StorageError::Io(format!(
+                "Failed to delete keypair '{}': {}",
+                name, e
+            ))),
+        }
+    }
+}
+
+/// Derive the AES-256 key from a passphrase with Argon2id
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    kdf: &KdfParams,
+) -> Result<Key<Aes256Gcm>, StorageError> {
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32))
+        .map_err(|e| StorageError::Encryption(format!("Invalid KDF parameters: {}", e)))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| StorageError::Encryption(format!("Key derivation failed: {}", e)))?;
+    let derived = *Key::<Aes256Gcm>::from_slice(&key);
+    wipe(&mut key);
+    Ok(derived)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn fast_vault(dir: &Path) -> KeypairVault {
+        KeypairVault::open(dir).unwrap().with_kdf_params(KdfParams {
+            memory_kib: 64,
+            iterations: 1,
+            parallelism: 1,
+        })
+    }
+
+    #[test]
+    fn test_create_load_sign() {
+        let dir = tempdir().unwrap();
+        let vault = fast_vault(dir.path());
+
+        let pubkey = vault.create("relay", "correct horse").unwrap();
+        assert_eq!(vault.pubkey("relay").unwrap(), pubkey);
+        assert_eq!(vault.list().unwrap()[0].pubkey, pubkey.to_string());
+
+        let signature = vault.sign("relay", "correct horse", b"hello").unwrap();
+        assert!(signature.verify(pubkey.as_ref(), b"hello"));
+
+        assert!(matches!(
+            vault.load("relay", "wrong"),
+            Err(StorageError::Decryption(_))
+        ));
+        assert!(vault.create("relay", "again").is_err());
+        assert!(vault.create("../escape", "x").is_err());
+    }
+}
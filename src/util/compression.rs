@@ -0,0 +1,388 @@
+//! Payload compression selected by config
+//!
+//! Originators try every configured algorithm and keep the smallest result,
+//! or the original bytes when nothing saves space. Compressed payloads start
+//! with a 3-byte header: a tag byte (`0xC0 | algorithm id`) and the original
+//! size (u16 LE). A raw Solana transaction never starts with a tag byte (that
+//! would be a signature count of 64 or more), so receivers can tell them apart.
+//! Payloads with the older `LZ4`/`ZST` + u32 size headers are still decoded.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::lz::{Lz4Compressor, Lz4Error};
+#[cfg(not(target_arch = "wasm32"))]
+use super::zstd::{ZstdCompressor, ZstdError, ZSTD_MAGIC};
+
+/// High nibble of the tag byte
+const HEADER_TAG: u8 = 0xC0;
+const HEADER_TAG_MASK: u8 = 0xF0;
+/// Tag byte + u16 LE original size
+pub const HEADER_LEN: usize = 3;
+
+/// Compression algorithm for mesh payloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    /// Sent as-is (no header)
+    None,
+    Lz4,
+    /// Zstd with the built-in Solana dictionary (native builds only)
+    Zstd,
+}
+
+impl CompressionAlgorithm {
+    /// Id carried in the low nibble of the tag byte
+    pub fn id(self) -> u8 {
+        match self {
+            CompressionAlgorithm::None => 0,
+            CompressionAlgorithm::Lz4 => 1,
+            CompressionAlgorithm::Zstd => 2,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(CompressionAlgorithm::None),
+            1 => Some(CompressionAlgorithm::Lz4),
+            2 => Some(CompressionAlgorithm::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Whether this build can compress and decompress with it
+    pub fn is_available(self) -> bool {
+        !(cfg!(target_arch = "wasm32") && self == CompressionAlgorithm::Zstd)
+    }
+}
+
+/// Algorithms tried by default: everything this build supports
+pub fn available_algorithms() -> Vec<CompressionAlgorithm> {
+    [CompressionAlgorithm::Lz4, CompressionAlgorithm::Zstd]
+        .into_iter()
+        .filter(|a| a.is_available())
+        .collect()
+}
+
+/// `compression` section of an `SdkConfig`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompressionSettings {
+    /// Algorithms to try; the smallest output wins. Empty disables compression.
+    #[serde(default = "available_algorithms")]
+    pub algorithms: Vec<CompressionAlgorithm>,
+    /// Zstd level (1-22); unset uses the zstd module's default
+    #[serde(default)]
+    pub level: Option<i32>,
+}
+
+impl Default for CompressionSettings {
+    fn default() -> Self {
+        Self {
+            algorithms: available_algorithms(),
+            level: None,
+        }
+    }
+}
+
+/// Output of [`CompressionSettings::compress`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compressed {
+    /// `None` when the input was kept as-is
+    pub algorithm: CompressionAlgorithm,
+    pub bytes: Vec<u8>,
+}
+
+impl CompressionSettings {
+    /// Compress `data` with whichever configured algorithm produces the
+    /// smallest payload (header included), or keep it as-is if none is
+    /// smaller than the input
+    pub fn compress(&self, data: &[u8]) -> Result<Compressed, CompressionError> {
+        let mut best = Compressed {
+            algorithm: CompressionAlgorithm::None,
+            bytes: data.to_vec(),
+        };
+        if data.is_empty() || data.len() > u16::MAX as usize {
+            return Ok(best);
+        }
+
+        for &algorithm in &self.algorithms {
+            if algorithm == CompressionAlgorithm::None || !algorithm.is_available() {
+                continue;
+            }
+            let body = self.compress_body(algorithm, data)?;
+            if HEADER_LEN + body.len() < best.bytes.len() {
+                let mut bytes = Vec::with_capacity(HEADER_LEN + body.len());
+                bytes.push(HEADER_TAG | algorithm.id());
+                bytes.extend_from_slice(&(data.len() as u16).to_le_bytes());
+                bytes.extend_from_slice(&body);
+                best = Compressed { algorithm, bytes };
+            }
+        }
+
+        tracing::debug!(
+            "Compression picked {:?}: {} -> {} bytes",
+            best.algorithm,
+            data.len(),
+            best.bytes.len()
+        );
+        Ok(best)
+    }
+
+    fn compress_body(
+        &self,
+        algorithm: CompressionAlgorithm,
+        data: &[u8],
+    ) -> Result<Vec<u8>, CompressionError> {
+        match algorithm {
+            CompressionAlgorithm::Lz4 => Ok(Lz4Compressor::new()?.compress(data)?),
+            #[cfg(not(target_arch = "wasm32"))]
+            CompressionAlgorithm::Zstd => {
+                let mut compressor = ZstdCompressor::new();
+                if let Some(level) = self.level {
+                    compressor = compressor.with_level(level);
+                }
+                Ok(compressor.compress_frame(data)?)
+            }
+            _ => Err(CompressionError::Unsupported(format!("{:?}", algorithm))),
+        }
+    }
+}
+
+/// Totals for one algorithm
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgorithmUsage {
+    /// `none` counts payloads kept as-is
+    pub algorithm: CompressionAlgorithm,
+    pub payloads: u64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+}
+
+/// Running compression totals of a [`Compressor`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompressionStats {
+    pub payloads: u64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    /// `bytes_out / bytes_in` (lower is better; 0 before any payload)
+    pub ratio: f64,
+    /// Ordered by algorithm id
+    pub by_algorithm: Vec<AlgorithmUsage>,
+}
+
+impl CompressionStats {
+    fn record(&mut self, algorithm: CompressionAlgorithm, bytes_in: usize, bytes_out: usize) {
+        self.payloads += 1;
+        self.bytes_in += bytes_in as u64;
+        self.bytes_out += bytes_out as u64;
+        self.ratio = self.bytes_out as f64 / self.bytes_in as f64;
+
+        let index = match self
+            .by_algorithm
+            .binary_search_by_key(&algorithm.id(), |u| u.algorithm.id())
+        {
+            Ok(i) => i,
+            Err(i) => {
+                self.by_algorithm.insert(
+                    i,
+                    AlgorithmUsage {
+                        algorithm,
+                        payloads: 0,
+                        bytes_in: 0,
+                        bytes_out: 0,
+                    },
+                );
+                i
+            }
+        };
+        let usage = &mut self.by_algorithm[index];
+        usage.payloads += 1;
+        usage.bytes_in += bytes_in as u64;
+        usage.bytes_out += bytes_out as u64;
+    }
+
+    /// Bytes saved, as a percentage of bytes in
+    pub fn savings_percent(&self) -> f64 {
+        if self.bytes_in == 0 {
+            return 0.0;
+        }
+        (1.0 - self.ratio) * 100.0
+    }
+}
+
+/// [`CompressionSettings`] plus the totals of everything compressed with them
+#[derive(Debug, Default)]
+pub struct Compressor {
+    settings: CompressionSettings,
+    stats: Mutex<CompressionStats>,
+}
+
+impl Compressor {
+    pub fn new(settings: CompressionSettings) -> Self {
+        Self {
+            settings,
+            stats: Mutex::new(CompressionStats::default()),
+        }
+    }
+
+    pub fn settings(&self) -> &CompressionSettings {
+        &self.settings
+    }
+
+    /// [`CompressionSettings::compress`], counted in the stats
+    pub fn compress(&self, data: &[u8]) -> Result<Compressed, CompressionError> {
+        let compressed = self.settings.compress(data)?;
+        self.stats
+            .lock()
+            .record(compressed.algorithm, data.len(), compressed.bytes.len());
+        Ok(compressed)
+    }
+
+    pub fn stats(&self) -> CompressionStats {
+        self.stats.lock().clone()
+    }
+
+    pub fn reset_stats(&self) {
+        *self.stats.lock() = CompressionStats::default();
+    }
+}
+
+/// Algorithm of a payload produced by [`CompressionSettings::compress`]
+/// (or an older `LZ4`/`ZST` header); `None` for anything else
+pub fn detect(data: &[u8]) -> Option<CompressionAlgorithm> {
+    match data.first() {
+        Some(&tag) if tag & HEADER_TAG_MASK == HEADER_TAG && data.len() >= HEADER_LEN => {
+            CompressionAlgorithm::from_id(tag & !HEADER_TAG_MASK)
+        }
+        _ if data.starts_with(b"LZ4") => Some(CompressionAlgorithm::Lz4),
+        #[cfg(not(target_arch = "wasm32"))]
+        _ if data.starts_with(ZSTD_MAGIC) => Some(CompressionAlgorithm::Zstd),
+        _ => None,
+    }
+}
+
+/// Decompress a payload with a compression header
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    let Some(&tag) = data.first() else {
+        return Err(CompressionError::UnknownFormat);
+    };
+
+    if tag & HEADER_TAG_MASK != HEADER_TAG {
+        // Older magic-prefixed payloads
+        #[cfg(not(target_arch = "wasm32"))]
+        if data.starts_with(ZSTD_MAGIC) {
+            return Ok(ZstdCompressor::new().decompress_with_size(data)?);
+        }
+        if data.starts_with(b"LZ4") {
+            return Ok(Lz4Compressor::new()?.decompress_with_size(data)?);
+        }
+        return Err(CompressionError::UnknownFormat);
+    }
+
+    if data.len() < HEADER_LEN {
+        return Err(CompressionError::UnknownFormat);
+    }
+    let original_size = u16::from_le_bytes([data[1], data[2]]) as usize;
+    let body = &data[HEADER_LEN..];
+    match CompressionAlgorithm::from_id(tag & !HEADER_TAG_MASK) {
+        Some(CompressionAlgorithm::None) => Ok(body.to_vec()),
+        Some(CompressionAlgorithm::Lz4) => {
+            Ok(Lz4Compressor::new()?.decompress_exact(body, original_size)?)
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        Some(CompressionAlgorithm::Zstd) => {
+            Ok(ZstdCompressor::new().decompress_frame(body, original_size)?)
+        }
+        #[cfg(target_arch = "wasm32")]
+        Some(algorithm) => Err(CompressionError::Unsupported(format!("{:?}", algorithm))),
+        None => Err(CompressionError::UnknownFormat),
+    }
+}
+
+/// Errors from the configured compressor
+#[derive(Error, Debug)]
+pub enum CompressionError {
+    #[error(transparent)]
+    Lz4(#[from] Lz4Error),
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[error(transparent)]
+    Zstd(#[from] ZstdError),
+
+    #[error("Compression algorithm not available in this build: {0}")]
+    Unsupported(String),
+
+    #[error("Unknown compression header")]
+    UnknownFormat,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_compression_picks_smallest_and_round_trips() {
+        let settings = CompressionSettings::default();
+        let text = b"pollinet pollinet pollinet pollinet pollinet pollinet".repeat(4);
+
+        let compressed = settings.compress(&text).unwrap();
+        assert_ne!(compressed.algorithm, CompressionAlgorithm::None);
+        assert_eq!(detect(&compressed.bytes), Some(compressed.algorithm));
+        assert_eq!(decompress(&compressed.bytes).unwrap(), text);
+        for algorithm in available_algorithms() {
+            let single = CompressionSettings {
+                algorithms: vec![algorithm],
+                level: None,
+            };
+            assert!(single.compress(&text).unwrap().bytes.len() >= compressed.bytes.len());
+        }
+
+        // Incompressible input is kept as-is
+        let noise: Vec<u8> = (0..8u8)
+            .flat_map(|i| {
+                use sha2::{Digest, Sha256};
+                Sha256::digest([i])
+            })
+            .collect();
+        let kept = settings.compress(&noise).unwrap();
+        assert_eq!(kept.algorithm, CompressionAlgorithm::None);
+        assert_eq!(kept.bytes, noise);
+
+        // Older LZ4 headers still decode; unknown tags don't
+        let legacy = Lz4Compressor::new()
+            .unwrap()
+            .compress_with_size(&text)
+            .unwrap();
+        assert_eq!(decompress(&legacy).unwrap(), text);
+        assert!(decompress(&[0xCF, 0x01, 0x00, 0x00]).is_err());
+    }
+
+    #[test]
+    fn test_compressor_tracks_totals_per_algorithm() {
+        let compressor = Compressor::new(CompressionSettings {
+            algorithms: vec![CompressionAlgorithm::Lz4],
+            level: None,
+        });
+        let text = b"relay relay relay relay relay relay relay relay".repeat(4);
+        let out = compressor.compress(&text).unwrap().bytes.len();
+        compressor.compress(&[0x42]).unwrap();
+
+        let stats = compressor.stats();
+        assert_eq!(stats.payloads, 2);
+        assert_eq!(stats.bytes_in, text.len() as u64 + 1);
+        assert_eq!(stats.bytes_out, out as u64 + 1);
+        assert!(stats.ratio < 1.0 && stats.savings_percent() > 0.0);
+        let algorithms: Vec<_> = stats.by_algorithm.iter().map(|u| u.algorithm).collect();
+        assert_eq!(
+            algorithms,
+            vec![CompressionAlgorithm::None, CompressionAlgorithm::Lz4]
+        );
+
+        compressor.reset_stats();
+        assert_eq!(compressor.stats(), CompressionStats::default());
+    }
+}
@@ -0,0 +1,137 @@
+//! Mnemonic and BIP44 keypair derivation
+//!
+//! Desktop gateways and test tooling derive Solana keypairs from a mnemonic
+//! the same way wallets do — PBKDF2 seed, then SLIP-0010 ed25519 derivation
+//! along `m/44'/501'/<account>'/<change>'` — instead of passing raw 64-byte
+//! keypairs around in base64.
+//!
+//! Mnemonics are checked for shape (12–24 lowercase ASCII words) but not
+//! against the BIP39 wordlist or checksum; a typo yields a different, still
+//! valid, keypair.
+
+use solana_derivation_path::DerivationPath;
+use solana_keypair::seed_derivable::keypair_from_seed_and_derivation_path;
+use solana_keypair::Keypair;
+use solana_seed_phrase::generate_seed_from_seed_phrase_and_passphrase;
+use thiserror::Error;
+
+/// Default Solana wallet path (Phantom, Solflare, `solana-keygen --derivation-path`)
+pub const SOLANA_DERIVATION_PATH: &str = "m/44'/501'/0'/0'";
+
+/// Word counts BIP39 allows
+const MNEMONIC_WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// Errors from key derivation
+#[derive(Error, Debug)]
+pub enum KeyError {
+    #[error("Invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
+
+    #[error("Invalid derivation path: {0}")]
+    InvalidDerivationPath(String),
+
+    #[error("Key derivation failed: {0}")]
+    Derivation(String),
+}
+
+/// Normalize a mnemonic to single-space separated lowercase words
+pub fn normalize_mnemonic(phrase: &str) -> Result<String, KeyError> {
+    let words: Vec<String> = phrase
+        .split_whitespace()
+        .map(|w| w.to_ascii_lowercase())
+        .collect();
+    if !MNEMONIC_WORD_COUNTS.contains(&words.len()) {
+        return Err(KeyError::InvalidMnemonic(format!(
+            "expected 12, 15, 18, 21 or 24 words, got {}",
+            words.len()
+        )));
+    }
+    if let Some(word) = words
+        .iter()
+        .find(|w| !w.chars().all(|c| c.is_ascii_lowercase()))
+    {
+        return Err(KeyError::InvalidMnemonic(format!(
+            "unexpected word '{}'",
+            word
+        )));
+    }
+    Ok(words.join(" "))
+}
+
+/// 64-byte BIP39 seed for a mnemonic and optional passphrase
+pub fn seed_from_mnemonic(phrase: &str, passphrase: &str) -> Result<[u8; 64], KeyError> {
+    let phrase = normalize_mnemonic(phrase)?;
+    let seed = generate_seed_from_seed_phrase_and_passphrase(&phrase, passphrase);
+    seed.try_into()
+        .map_err(|_| KeyError::Derivation("Unexpected seed length".to_string()))
+}
+
+/// Parse an absolute path such as `m/44'/501'/0'/0'`. Every component is
+/// hardened, as ed25519 derivation requires.
+pub fn parse_derivation_path(path: &str) -> Result<DerivationPath, KeyError> {
+    DerivationPath::from_absolute_path_str(path.trim())
+        .map_err(|e| KeyError::InvalidDerivationPath(e.to_string()))
+}
+
+/// `m/44'/501'/<account>'/0'`, the path wallets use for their nth account
+pub fn solana_path(account: u32) -> DerivationPath {
+    DerivationPath::new_bip44(Some(account), Some(0))
+}
+
+/// Keypair at `path` below a BIP39 seed
+pub fn keypair_from_seed(seed: &[u8], path: &DerivationPath) -> Result<Keypair, KeyError> {
+    keypair_from_seed_and_derivation_path(seed, Some(path.clone()))
+        .map_err(|e| KeyError::Derivation(e.to_string()))
+}
+
+/// Keypair for a mnemonic at `path`, or [`SOLANA_DERIVATION_PATH`] if `None`
+pub fn keypair_from_mnemonic(
+    phrase: &str,
+    passphrase: &str,
+    path: Option<&str>,
+) -> Result<Keypair, KeyError> {
+    let path = parse_derivation_path(path.unwrap_or(SOLANA_DERIVATION_PATH))?;
+    let seed = seed_from_mnemonic(phrase, passphrase)?;
+    keypair_from_seed(&seed, &path)
+}
+
+/// The first `count` wallet accounts of a seed, for test fixtures
+pub fn derive_accounts(seed: &[u8], count: u32) -> Result<Vec<Keypair>, KeyError> {
+    (0..count)
+        .map(|account| keypair_from_seed(seed, &solana_path(account)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Signer;
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon \
+                          abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_mnemonic_derivation_is_deterministic() {
+        // Spacing and case don't change the seed
+        let seed = seed_from_mnemonic(PHRASE, "").unwrap();
+        let messy = format!("  {}  ", PHRASE.to_uppercase().replace(' ', "\n"));
+        assert_eq!(seed_from_mnemonic(&messy, "").unwrap(), seed);
+        assert_ne!(seed_from_mnemonic(PHRASE, "pass").unwrap(), seed);
+        assert!(seed_from_mnemonic("abandon about", "").is_err());
+        assert!(seed_from_mnemonic(&PHRASE.replace("about", "ab0ut"), "").is_err());
+
+        // The default path is account 0
+        let default = keypair_from_mnemonic(PHRASE, "", None).unwrap();
+        let accounts = derive_accounts(&seed, 2).unwrap();
+        assert_eq!(default.pubkey(), accounts[0].pubkey());
+        assert_ne!(accounts[0].pubkey(), accounts[1].pubkey());
+
+        let path = parse_derivation_path("m/44'/501'/1'/0'").unwrap();
+        assert_eq!(path, solana_path(1));
+        assert_eq!(
+            keypair_from_seed(&seed, &path).unwrap().pubkey(),
+            accounts[1].pubkey()
+        );
+        assert!(parse_derivation_path("44/501").is_err());
+    }
+}
@@ -2,9 +2,61 @@
 //!
 //! Provides fast lossless compression for transaction payloads
 
+#[cfg(not(target_arch = "wasm32"))]
 use std::time::Instant;
 use thiserror::Error;
 
+/// `std::time::Instant::now` panics on wasm32-unknown-unknown; timings read 0 there
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Copy)]
+struct Instant;
+
+#[cfg(target_arch = "wasm32")]
+impl Instant {
+    fn now() -> Self {
+        Instant
+    }
+
+    fn elapsed(&self) -> std::time::Duration {
+        std::time::Duration::ZERO
+    }
+}
+
+/// Compress one raw LZ4 block (no size prefix)
+#[cfg(not(target_arch = "wasm32"))]
+fn compress_block(data: &[u8], high_compression: bool) -> Result<Vec<u8>, Lz4Error> {
+    let mode = if high_compression {
+        lz4::block::CompressionMode::HIGHCOMPRESSION(i32::MAX)
+    } else {
+        lz4::block::CompressionMode::DEFAULT
+    };
+    lz4::block::compress(data, Some(mode), false)
+        .map_err(|e| Lz4Error::CompressionFailed(e.to_string()))
+}
+
+/// Compress one raw LZ4 block (no size prefix); the pure-Rust encoder has no HC mode
+#[cfg(target_arch = "wasm32")]
+fn compress_block(data: &[u8], _high_compression: bool) -> Result<Vec<u8>, Lz4Error> {
+    Ok(lz4_flex::block::compress(data))
+}
+
+/// Decompress one LZ4 block; without `original_size` a 4-byte LE size prefix is expected
+#[cfg(not(target_arch = "wasm32"))]
+fn decompress_block(data: &[u8], original_size: Option<usize>) -> Result<Vec<u8>, Lz4Error> {
+    lz4::block::decompress(data, original_size.map(|size| size as i32))
+        .map_err(|e| Lz4Error::DecompressionFailed(e.to_string()))
+}
+
+/// Decompress one LZ4 block; without `original_size` a 4-byte LE size prefix is expected
+#[cfg(target_arch = "wasm32")]
+fn decompress_block(data: &[u8], original_size: Option<usize>) -> Result<Vec<u8>, Lz4Error> {
+    match original_size {
+        Some(size) => lz4_flex::block::decompress(data, size),
+        None => lz4_flex::block::decompress_size_prepended(data),
+    }
+    .map_err(|e| Lz4Error::DecompressionFailed(e.to_string()))
+}
+
 /// LZ4 compressor for transaction payloads
 pub struct Lz4Compressor;
 
@@ -23,12 +75,7 @@ impl Lz4Compressor {
         }
 
         // Use real LZ4 compression
-        let compressed = lz4::block::compress(
-            data,
-            Some(lz4::block::CompressionMode::HIGHCOMPRESSION(i32::MAX)),
-            false,
-        )
-        .map_err(|e| Lz4Error::CompressionFailed(e.to_string()))?;
+        let compressed = compress_block(data, true)?;
 
         let compression_time = start_time.elapsed().as_micros();
 
@@ -51,9 +98,26 @@ impl Lz4Compressor {
             return Ok(Vec::new());
         }
 
-        let decompressed = lz4::block::decompress(compressed_data, None)
-            .map_err(|e| Lz4Error::DecompressionFailed(e.to_string()))?;
+        let decompressed = decompress_block(compressed_data, None)?;
+
+        Ok(decompressed)
+    }
 
+    /// Decompress a raw block from [`Lz4Compressor::compress`] whose original
+    /// size is known
+    pub fn decompress_exact(
+        &self,
+        block: &[u8],
+        original_size: usize,
+    ) -> Result<Vec<u8>, Lz4Error> {
+        let decompressed = decompress_block(block, Some(original_size))?;
+        if decompressed.len() != original_size {
+            return Err(Lz4Error::InvalidData(format!(
+                "Decompressed size mismatch: expected {}, got {}",
+                original_size,
+                decompressed.len()
+            )));
+        }
         Ok(decompressed)
     }
 
@@ -86,9 +150,7 @@ impl Lz4Compressor {
         compressed.extend_from_slice(&(data.len() as u32).to_le_bytes());
 
         // Compress the actual data
-        let compressed_data =
-            lz4::block::compress(data, Some(lz4::block::CompressionMode::DEFAULT), false)
-                .map_err(|e| Lz4Error::CompressionFailed(e.to_string()))?;
+        let compressed_data = compress_block(data, false)?;
 
         compressed.extend_from_slice(&compressed_data);
 
@@ -144,8 +206,7 @@ impl Lz4Compressor {
         );
 
         // Decompress
-        let decompressed = lz4::block::decompress(data, Some(original_size as i32))
-            .map_err(|e| Lz4Error::DecompressionFailed(e.to_string()))?;
+        let decompressed = decompress_block(data, Some(original_size))?;
 
         if decompressed.len() != original_size {
             return Err(Lz4Error::InvalidData(format!(
@@ -2,19 +2,36 @@
 //!
 //! Includes compression, serialization, and other helper functions
 
+pub mod clock;
+pub mod compression;
+pub mod keys;
 pub mod lz;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod zstd;
 
 /// Common utility functions
 pub mod common {
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use once_cell::sync::Lazy;
+    use rand::Rng;
+    use std::sync::atomic::{AtomicU32, Ordering};
 
-    /// Generate a unique identifier based on current timestamp
+    /// Random per-device (per-process) prefix of generated ids
+    static DEVICE_ENTROPY: Lazy<[u8; 4]> = Lazy::new(|| rand::thread_rng().gen());
+    /// Starts at a random value so restarts don't replay a sequence
+    static ID_COUNTER: Lazy<AtomicU32> = Lazy::new(|| AtomicU32::new(rand::thread_rng().gen()));
+
+    /// Generate a compact 12-byte id, as 24 hex characters: device entropy,
+    /// unix seconds and a counter. Two devices can't collide unless their
+    /// random prefixes do, however close together the ids are made.
     pub fn generate_id() -> String {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        format!("id_{:x}", timestamp)
+        let seconds = crate::util::clock::now_secs() as u32;
+        let counter = ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+        let mut id = [0u8; 12];
+        id[..4].copy_from_slice(&*DEVICE_ENTROPY);
+        id[4..8].copy_from_slice(&seconds.to_be_bytes());
+        id[8..].copy_from_slice(&counter.to_be_bytes());
+        hex::encode(id)
     }
 
     /// Check if data should be compressed based on size threshold
@@ -0,0 +1,276 @@
+//! Zstd compression with a Solana transaction dictionary
+//!
+//! Most of a ~300-byte transaction is incompressible (signatures, user keys,
+//! blockhash), so LZ4 rarely saves much. What repeats across transactions —
+//! program ids, sysvars, popular mints and instruction layouts — repeats
+//! *between* payloads rather than within one, which a shared dictionary
+//! captures. Both ends of the mesh must use the same dictionary, so the
+//! built-in one is versioned and never changes once shipped; a dictionary
+//! trained on captured traffic with [`train_dictionary`] becomes the next
+//! version.
+//!
+//! Payloads carry the same 7-byte header as `Lz4Compressor::compress_with_size`:
+//! a `ZST` magic followed by the original size (u32 LE).
+
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
+
+/// Magic prefix of zstd payloads
+pub const ZSTD_MAGIC: &[u8; 3] = b"ZST";
+/// Version of the built-in dictionary
+pub const DICTIONARY_VERSION: u8 = 1;
+/// Level used when the config doesn't set one; payloads are tiny, so the
+/// strongest levels cost next to nothing
+pub const DEFAULT_LEVEL: i32 = 19;
+
+const HEADER_LEN: usize = 7;
+/// Largest payload accepted for decompression (well above any transaction)
+const MAX_DECOMPRESSED_SIZE: usize = 64 * 1024;
+
+/// Accounts found in most mesh transactions, least common first: zstd
+/// reaches the end of a raw dictionary most cheaply
+const DICTIONARY_ACCOUNTS: [Pubkey; 10] = [
+    Pubkey::from_str_const("SysvarRent111111111111111111111111111111111"),
+    Pubkey::from_str_const("SysvarRecentB1ockHashes11111111111111111111"),
+    // USDT, USDC
+    Pubkey::from_str_const("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB"),
+    Pubkey::from_str_const("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+    Pubkey::from_str_const("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"),
+    Pubkey::from_str_const("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr"),
+    Pubkey::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+    Pubkey::from_str_const("ComputeBudget111111111111111111111111111111"),
+    Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+    Pubkey::from_str_const("11111111111111111111111111111111"),
+];
+
+/// Common byte sequences of the legacy wire format
+const DICTIONARY_TEMPLATES: [&[u8]; 9] = [
+    // Message headers: 1 signer / 1-2 read-only accounts, 2 signers (fee payer + nonce authority)
+    &[0x01, 0x00, 0x01],
+    &[0x01, 0x00, 0x02],
+    &[0x02, 0x00, 0x01],
+    // Compute budget: SetComputeUnitLimit(200_000), SetComputeUnitPrice
+    &[0x02, 0x40, 0x0d, 0x03, 0x00],
+    &[0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    // System: AdvanceNonceAccount, Transfer
+    &[0x04, 0x00, 0x00, 0x00],
+    &[0x02, 0x00, 0x00, 0x00],
+    // SPL token: TransferChecked with 6 decimals (amount omitted), Transfer
+    &[0x0c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06],
+    &[0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+];
+
+static BUILTIN_DICTIONARY: Lazy<Vec<u8>> = Lazy::new(|| {
+    let mut dictionary = Vec::new();
+    for template in DICTIONARY_TEMPLATES {
+        dictionary.extend_from_slice(template);
+    }
+    for account in DICTIONARY_ACCOUNTS {
+        dictionary.extend_from_slice(account.as_ref());
+    }
+    dictionary
+});
+
+/// The built-in raw-content dictionary (version [`DICTIONARY_VERSION`])
+pub fn builtin_dictionary() -> &'static [u8] {
+    &BUILTIN_DICTIONARY
+}
+
+/// Train a dictionary from sample payloads (e.g. serialized transactions
+/// captured by a gateway) for use with [`ZstdCompressor::with_dictionary`]
+pub fn train_dictionary<S: AsRef<[u8]>>(
+    samples: &[S],
+    max_size: usize,
+) -> Result<Vec<u8>, ZstdError> {
+    ::zstd::dict::from_samples(samples, max_size)
+        .map_err(|e| ZstdError::DictionaryFailed(e.to_string()))
+}
+
+/// Zstd compressor for transaction payloads
+pub struct ZstdCompressor {
+    dictionary: Vec<u8>,
+    level: i32,
+}
+
+impl ZstdCompressor {
+    /// Compressor using the built-in dictionary at [`DEFAULT_LEVEL`]
+    pub fn new() -> Self {
+        Self::with_dictionary(builtin_dictionary().to_vec())
+    }
+
+    /// Compressor using a custom dictionary; only peers with the same
+    /// dictionary can decompress its output
+    pub fn with_dictionary(dictionary: Vec<u8>) -> Self {
+        Self {
+            dictionary,
+            level: DEFAULT_LEVEL,
+        }
+    }
+
+    /// Set the compression level (1-22)
+    pub fn with_level(mut self, level: i32) -> Self {
+        self.level = level.clamp(1, 22);
+        self
+    }
+
+    /// Compress with a `ZST` + original size header
+    pub fn compress_with_size(&self, data: &[u8]) -> Result<Vec<u8>, ZstdError> {
+        if data.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let compressed = self.compress_frame(data)?;
+
+        let mut payload = Vec::with_capacity(HEADER_LEN + compressed.len());
+        payload.extend_from_slice(ZSTD_MAGIC);
+        payload.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        payload.extend_from_slice(&compressed);
+
+        tracing::debug!(
+            "Zstd compression with header: {} -> {} bytes (level {})",
+            data.len(),
+            payload.len(),
+            self.level
+        );
+
+        Ok(payload)
+    }
+
+    /// Decompress data with a `ZST` header
+    pub fn decompress_with_size(&self, compressed_data: &[u8]) -> Result<Vec<u8>, ZstdError> {
+        if compressed_data.len() < HEADER_LEN {
+            return Err(ZstdError::InvalidData(
+                "Data too short for zstd header".to_string(),
+            ));
+        }
+        if &compressed_data[..3] != ZSTD_MAGIC {
+            return Err(ZstdError::InvalidData("Invalid zstd header".to_string()));
+        }
+
+        let original_size = u32::from_le_bytes([
+            compressed_data[3],
+            compressed_data[4],
+            compressed_data[5],
+            compressed_data[6],
+        ]) as usize;
+        self.decompress_frame(&compressed_data[HEADER_LEN..], original_size)
+    }
+
+    /// Decompress a bare frame from [`ZstdCompressor::compress_frame`]
+    pub fn decompress_frame(
+        &self,
+        frame: &[u8],
+        original_size: usize,
+    ) -> Result<Vec<u8>, ZstdError> {
+        if original_size > MAX_DECOMPRESSED_SIZE {
+            return Err(ZstdError::InvalidData(format!(
+                "Declared size {} exceeds {} bytes",
+                original_size, MAX_DECOMPRESSED_SIZE
+            )));
+        }
+
+        let decompressed = ::zstd::bulk::Decompressor::with_dictionary(&self.dictionary)
+            .and_then(|mut d| d.decompress(frame, original_size))
+            .map_err(|e| ZstdError::DecompressionFailed(e.to_string()))?;
+
+        if decompressed.len() != original_size {
+            return Err(ZstdError::InvalidData(format!(
+                "Decompressed size mismatch: expected {}, got {}",
+                original_size,
+                decompressed.len()
+            )));
+        }
+
+        Ok(decompressed)
+    }
+
+    /// One zstd frame without the checksum, content size and dictionary id
+    /// fields; the caller records the original size
+    pub fn compress_frame(&self, data: &[u8]) -> Result<Vec<u8>, ZstdError> {
+        let compress = || -> std::io::Result<Vec<u8>> {
+            let mut compressor =
+                ::zstd::bulk::Compressor::with_dictionary(self.level, &self.dictionary)?;
+            compressor.include_checksum(false)?;
+            compressor.include_contentsize(false)?;
+            compressor.include_dictid(false)?;
+            compressor.compress(data)
+        };
+        compress().map_err(|e| ZstdError::CompressionFailed(e.to_string()))
+    }
+}
+
+impl Default for ZstdCompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Zstd-specific error types
+#[derive(Error, Debug)]
+pub enum ZstdError {
+    #[error("Compression failed: {0}")]
+    CompressionFailed(String),
+
+    #[error("Decompression failed: {0}")]
+    DecompressionFailed(String),
+
+    #[error("Invalid data: {0}")]
+    InvalidData(String),
+
+    #[error("Dictionary training failed: {0}")]
+    DictionaryFailed(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::lz::Lz4Compressor;
+
+    /// Bytes that don't compress, standing in for keys and signatures
+    fn noise(label: &str, len: usize) -> Vec<u8> {
+        use sha2::{Digest, Sha256};
+        (0..len.div_ceil(32))
+            .flat_map(|i| Sha256::digest(format!("{}{}", label, i)))
+            .take(len)
+            .collect()
+    }
+
+    /// Serialized legacy transfer with a priority fee
+    fn sample_transfer() -> Vec<u8> {
+        let mut tx = vec![0x01];
+        tx.extend(noise("signature", 64));
+        tx.extend_from_slice(&[0x01, 0x00, 0x02, 0x04]);
+        tx.extend(noise("payer", 32));
+        tx.extend(noise("recipient", 32));
+        tx.extend_from_slice(DICTIONARY_ACCOUNTS[7].as_ref());
+        tx.extend_from_slice(DICTIONARY_ACCOUNTS[9].as_ref());
+        tx.extend(noise("blockhash", 32));
+        tx.extend_from_slice(&[0x02, 0x02, 0x00, 0x09, 0x03]);
+        tx.extend_from_slice(&5_000u64.to_le_bytes());
+        tx.extend_from_slice(&[0x03, 0x02, 0x00, 0x01, 0x0c, 0x02, 0x00, 0x00, 0x00]);
+        tx.extend_from_slice(&1_000_000u64.to_le_bytes());
+        tx
+    }
+
+    #[test]
+    fn test_zstd_round_trip_beats_lz4() {
+        let compressor = ZstdCompressor::new();
+        let tx = sample_transfer();
+
+        let compressed = compressor.compress_with_size(&tx).unwrap();
+        assert_eq!(&compressed[..3], ZSTD_MAGIC);
+        assert_eq!(compressor.decompress_with_size(&compressed).unwrap(), tx);
+
+        let lz4 = Lz4Compressor::new()
+            .unwrap()
+            .compress_with_size(&tx)
+            .unwrap();
+        assert!(compressed.len() < lz4.len());
+
+        // A different dictionary can't read the payload
+        let other = ZstdCompressor::with_dictionary(vec![0xAA; 64]);
+        assert_ne!(other.decompress_with_size(&compressed).ok(), Some(tx));
+        assert!(compressor.decompress_with_size(b"ZST").is_err());
+    }
+}
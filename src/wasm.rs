@@ -0,0 +1,236 @@
+//! Browser bindings for gateway tools
+//!
+//! Built with the `wasm` feature for `wasm32-unknown-unknown`. Exposes the
+//! radio-independent core — transaction building, signature application,
+//! fragmentation/reassembly, compression and submission prep — so web
+//! dashboards can decode and submit transactions collected from the mesh.
+//! Structured results are JSON strings, as in the mobile FFI.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Serialize;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::Transaction;
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+use crate::ble::fragmenter;
+use crate::ble::mesh::TransactionFragment;
+use crate::intent::TokenApprovalInput;
+use crate::util::lz::Lz4Compressor;
+
+/// Fragments of one transaction, ready to hand to a radio
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FragmentedTransaction {
+    tx_id: String,
+    /// Base64 wire frames (see `ble::wire`), as sent by `nextOutbound`
+    fragments: Vec<String>,
+}
+
+/// Everything needed for an RPC `sendTransaction` call
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SubmissionPrep {
+    tx_id: String,
+    /// First signature (base58), the transaction's on-chain id
+    signature: String,
+    /// Base64 wire transaction for `sendTransaction` with `encoding: "base64"`
+    transaction_base64: String,
+    recent_blockhash: String,
+    fee_payer: String,
+    signatures_verified: bool,
+}
+
+fn js_err(message: impl std::fmt::Display) -> JsError {
+    JsError::new(&message.to_string())
+}
+
+fn parse_pubkey(value: &str, field: &str) -> Result<Pubkey, JsError> {
+    Pubkey::from_str(value).map_err(|e| js_err(format!("Invalid {}: {}", field, e)))
+}
+
+fn parse_blockhash(value: &str) -> Result<Hash, JsError> {
+    let bytes = bs58::decode(value)
+        .into_vec()
+        .map_err(|e| js_err(format!("Invalid recent_blockhash: {}", e)))?;
+    let array: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| js_err("recent_blockhash must decode to 32 bytes"))?;
+    Ok(Hash::new_from_array(array))
+}
+
+fn decode_transaction(tx_bytes: &[u8]) -> Result<Transaction, JsError> {
+    bincode1::deserialize(tx_bytes).map_err(|e| js_err(format!("Invalid transaction: {}", e)))
+}
+
+/// SDK version
+#[wasm_bindgen]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+/// Build an unsigned approve transaction; `approvals_json` is a `TokenApprovalInput` array.
+/// Returns the base64 bincode transaction.
+#[wasm_bindgen(js_name = createApproveTransaction)]
+pub fn create_approve_transaction(
+    owner: &str,
+    fee_payer: &str,
+    recent_blockhash: &str,
+    approvals_json: &str,
+) -> Result<String, JsError> {
+    let approvals: Vec<TokenApprovalInput> = serde_json::from_str(approvals_json)
+        .map_err(|e| js_err(format!("Invalid approvals: {}", e)))?;
+    crate::intent::build_approve_transaction(
+        &parse_pubkey(owner, "owner")?,
+        &parse_pubkey(fee_payer, "fee_payer")?,
+        parse_blockhash(recent_blockhash)?,
+        &approvals,
+    )
+    .map_err(js_err)
+}
+
+/// Build an unsigned revoke transaction; `token_accounts_json` is an array of base58 accounts.
+/// Returns the base64 bincode transaction.
+#[wasm_bindgen(js_name = createRevokeTransaction)]
+pub fn create_revoke_transaction(
+    owner: &str,
+    fee_payer: &str,
+    recent_blockhash: &str,
+    token_accounts_json: &str,
+    token_program: &str,
+) -> Result<String, JsError> {
+    let token_accounts: Vec<String> = serde_json::from_str(token_accounts_json)
+        .map_err(|e| js_err(format!("Invalid token accounts: {}", e)))?;
+    crate::intent::build_revoke_transaction(
+        &parse_pubkey(owner, "owner")?,
+        &parse_pubkey(fee_payer, "fee_payer")?,
+        parse_blockhash(recent_blockhash)?,
+        &token_accounts,
+        token_program,
+    )
+    .map_err(js_err)
+}
+
+/// Insert a wallet signature (base58) for `signer` into a base64 transaction.
+/// The signature is verified against the message first. Returns the updated base64 transaction.
+#[wasm_bindgen(js_name = applySignature)]
+pub fn apply_signature(tx_base64: &str, signer: &str, signature: &str) -> Result<String, JsError> {
+    let tx_bytes = BASE64
+        .decode(tx_base64.trim())
+        .map_err(|e| js_err(format!("Invalid base64 transaction: {}", e)))?;
+    let mut tx = decode_transaction(&tx_bytes)?;
+    let signer = parse_pubkey(signer, "signer")?;
+    let signature =
+        Signature::from_str(signature).map_err(|e| js_err(format!("Invalid signature: {}", e)))?;
+
+    let required = tx.message.header.num_required_signatures as usize;
+    let index = tx
+        .message
+        .account_keys
+        .iter()
+        .take(required)
+        .position(|key| *key == signer)
+        .ok_or_else(|| js_err(format!("{} is not a required signer", signer)))?;
+    if !signature.verify(signer.as_ref(), &tx.message_data()) {
+        return Err(js_err("Signature does not match the transaction message"));
+    }
+
+    if tx.signatures.len() < required {
+        tx.signatures.resize(required, Signature::default());
+    }
+    tx.signatures[index] = signature;
+
+    let raw =
+        bincode1::serialize(&tx).map_err(|e| js_err(format!("Serialization failed: {}", e)))?;
+    Ok(BASE64.encode(raw))
+}
+
+/// Split a transaction into mesh fragments (`max_payload` 0 = default size).
+/// Returns JSON `{ txId, fragments: [base64 frame] }`.
+#[wasm_bindgen(js_name = fragmentTransaction)]
+pub fn fragment_transaction(tx_bytes: &[u8], max_payload: usize) -> Result<String, JsError> {
+    let fragments = if max_payload > 0 {
+        fragmenter::fragment_transaction_with_max_payload(tx_bytes, max_payload)
+    } else {
+        fragmenter::fragment_transaction(tx_bytes)
+    };
+    let tx_id = fragments
+        .first()
+        .map(|f| hex::encode(f.transaction_id))
+        .unwrap_or_default();
+
+    let frames: Vec<String> = fragments
+        .iter()
+        .map(|fragment| BASE64.encode(fragment.serialize()))
+        .collect();
+
+    serde_json::to_string(&FragmentedTransaction {
+        tx_id,
+        fragments: frames,
+    })
+    .map_err(js_err)
+}
+
+/// Reassemble a transaction from a JSON array of base64 wire frames (any order)
+#[wasm_bindgen(js_name = reassembleTransaction)]
+pub fn reassemble_transaction(frames_json: &str) -> Result<Vec<u8>, JsError> {
+    let frames: Vec<String> = serde_json::from_str(frames_json)
+        .map_err(|e| js_err(format!("Invalid frame list: {}", e)))?;
+
+    let fragments = frames
+        .iter()
+        .map(|frame| {
+            let bytes = BASE64
+                .decode(frame.trim())
+                .map_err(|e| js_err(format!("Invalid base64 frame: {}", e)))?;
+            TransactionFragment::deserialize(&bytes)
+                .map_err(|e| js_err(format!("Invalid fragment: {}", e)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    fragmenter::reconstruct_transaction(&fragments).map_err(js_err)
+}
+
+/// LZ4-compress a payload (with the `LZ4` + size header used on the mesh)
+#[wasm_bindgen(js_name = compressPayload)]
+pub fn compress_payload(data: &[u8]) -> Result<Vec<u8>, JsError> {
+    Lz4Compressor::new()
+        .and_then(|c| c.compress_with_size(data))
+        .map_err(js_err)
+}
+
+/// Reverse of `compressPayload`; also reads payloads compressed by native
+/// nodes (LZ4 with the compact header)
+#[wasm_bindgen(js_name = decompressPayload)]
+pub fn decompress_payload(data: &[u8]) -> Result<Vec<u8>, JsError> {
+    crate::util::compression::decompress(data).map_err(js_err)
+}
+
+/// Decode a reassembled transaction and return what an RPC submit needs as JSON
+#[wasm_bindgen(js_name = prepareSubmission)]
+pub fn prepare_submission(tx_bytes: &[u8]) -> Result<String, JsError> {
+    use sha2::{Digest, Sha256};
+
+    let tx = decode_transaction(tx_bytes)?;
+    let signature = tx
+        .signatures
+        .first()
+        .ok_or_else(|| js_err("Transaction has no signatures"))?;
+    let fee_payer = tx
+        .message
+        .account_keys
+        .first()
+        .ok_or_else(|| js_err("Transaction has no accounts"))?;
+
+    serde_json::to_string(&SubmissionPrep {
+        tx_id: hex::encode(Sha256::digest(tx_bytes)),
+        signature: signature.to_string(),
+        transaction_base64: BASE64.encode(tx_bytes),
+        recent_blockhash: tx.message.recent_blockhash.to_string(),
+        fee_payer: fee_payer.to_string(),
+        signatures_verified: tx.verify().is_ok(),
+    })
+    .map_err(js_err)
+}
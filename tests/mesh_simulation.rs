@@ -6,10 +6,6 @@
 //! shared mesh/queue stack under hostile conditions at 10 / 50 / 100 nodes: duplicate
 //! suppression, hop-limit (TTL) bounding, packet loss, network partition + heal, and
 //! node churn.
-//!
-//! `ffi` is android-gated, so this whole file is too; it runs under
-//! `cargo test --features android` and is an empty (passing) crate otherwise.
-#![cfg(feature = "android")]
 
 use pollinet::ble::MAX_HOPS;
 use pollinet::ffi::host_transport::HostTransport;
@@ -0,0 +1,6 @@
+//! Binding generator for the `uniffi` feature, e.g.
+//! `cargo run --features uniffi --bin uniffi-bindgen generate --library target/release/libpollinet.so --language kotlin --out-dir out`
+
+fn main() {
+    uniffi::uniffi_bindgen_main()
+}